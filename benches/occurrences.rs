@@ -0,0 +1,66 @@
+use chrono::{DateTime, Utc};
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use rrules::{Frequency, Recurrence};
+use std::str::FromStr;
+
+fn recurrence(value: &str) -> Recurrence {
+    Recurrence::from_str(value).unwrap()
+}
+
+fn nth_occurrence(c: &mut Criterion) {
+    let every_second = recurrence("FREQ=SECONDLY;INTERVAL=1;DTSTART=2023-01-01T00:00:00Z");
+    c.bench_function("secondly nth 10000", |b| {
+        b.iter(|| every_second.nth_occurrence(black_box(10_000)))
+    });
+    c.bench_function("secondly iterate 10000", |b| {
+        b.iter(|| every_second.iter().take(black_box(10_000)).last())
+    });
+    let weekly = recurrence("FREQ=WEEKLY;INTERVAL=2;BYDAY=MO,WE,FR;DTSTART=2023-01-01T09:00:00Z");
+    c.bench_function("weekly by day nth 1000", |b| {
+        b.iter(|| weekly.nth_occurrence(black_box(1_000)))
+    });
+}
+
+fn between(c: &mut Criterion) {
+    let from = DateTime::<Utc>::from_str("2030-01-01T00:00:00Z").unwrap();
+    let to = DateTime::<Utc>::from_str("2030-01-02T00:00:00Z").unwrap();
+    let every_minute = recurrence("FREQ=MINUTELY;INTERVAL=1;DTSTART=2000-01-01T00:00:00Z");
+    c.bench_function("minutely between a day", |b| {
+        b.iter(|| {
+            every_minute
+                .between(black_box(&from), black_box(&to))
+                .count()
+        })
+    });
+    let daily = recurrence("FREQ=DAILY;INTERVAL=1;BYTIME=09:00,18:00;DTSTART=2000-01-01T00:00:00Z");
+    c.bench_function("daily by time between a day", |b| {
+        b.iter(|| daily.between(black_box(&from), black_box(&to)).count())
+    });
+}
+
+fn monthly(c: &mut Criterion) {
+    let nth_weekdays =
+        recurrence("FREQ=MONTHLY;INTERVAL=1;BYDAY=1MO,3FR,4SU;DTSTART=2023-01-01T00:00:00Z");
+    c.bench_function("monthly nth weekdays iterate 1000", |b| {
+        b.iter(|| nth_weekdays.iter().take(black_box(1_000)).last())
+    });
+    let month_days =
+        recurrence("FREQ=MONTHLY;INTERVAL=1;BYMONTHDAY=1,15,31;DTSTART=2023-01-01T00:00:00Z");
+    c.bench_function("monthly by month day iterate 1000", |b| {
+        b.iter(|| month_days.iter().take(black_box(1_000)).last())
+    });
+}
+
+fn parse(c: &mut Criterion) {
+    let rule = "FREQ=WEEKLY;INTERVAL=2;BYDAY=MO,WE,FR;BYTIME=09:00,18:00;DTSTART=2023-01-01T09:00:00Z;DTEND=2024-01-01T09:00:00Z;DURATION=PT1H";
+    c.bench_function("recurrence from str", |b| {
+        b.iter(|| Recurrence::from_str(black_box(rule)).unwrap())
+    });
+    let frequency = "FREQ=MONTHLY;INTERVAL=1;BYDAY=1MO,3FR,4SU;BYTIME=09:00";
+    c.bench_function("frequency from str", |b| {
+        b.iter(|| Frequency::from_str(black_box(frequency)).unwrap())
+    });
+}
+
+criterion_group!(benches, nth_occurrence, between, monthly, parse);
+criterion_main!(benches);
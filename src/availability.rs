@@ -0,0 +1,196 @@
+//! Availability utilities, to find the free time left between the events of recurrences.
+//!
+//! Every event of a recurrence is a busy block that starts at the event date and lasts
+//! the `duration` of the recurrence.
+//!
+//! Examples:
+//! ```
+//! use std::str::FromStr;
+//! use chrono::{DateTime, Duration, Utc};
+//! use rrules::availability::free_slots;
+//! use rrules::Recurrence;
+//!
+//! let meetings = Recurrence::from_str("FREQ=HOURLY;INTERVAL=2;DTSTART=2023-01-02T09:00:00Z;DURATION=PT1H").unwrap();
+//! let from = DateTime::<Utc>::from_str("2023-01-02T09:00:00Z").unwrap();
+//! let to = DateTime::<Utc>::from_str("2023-01-02T13:00:00Z").unwrap();
+//! let slots = free_slots(&[meetings], from..to, Duration::minutes(30));
+//! assert_eq!(slots.len(), 2);
+//! assert_eq!(slots[0].start, DateTime::<Utc>::from_str("2023-01-02T10:00:00Z").unwrap());
+//! assert_eq!(slots[0].end, DateTime::<Utc>::from_str("2023-01-02T11:00:00Z").unwrap());
+//! ```
+
+use crate::Recurrence;
+use alloc::vec::Vec;
+use chrono::{DateTime, Duration, Utc};
+use core::ops::Range;
+
+/// Returns the free time slots of the window, where none of the events of the recurrences
+/// take place, sorted and lasting at least `min_length`.
+///
+/// Events that started before the window but are still going on at its start are taken
+/// into account. Events without a duration don't take any time, so they don't split the
+/// free slots.
+pub fn free_slots(
+    recurrences: &[Recurrence],
+    window: Range<DateTime<Utc>>,
+    min_length: Duration,
+) -> Vec<Range<DateTime<Utc>>> {
+    let mut busy: Vec<Range<DateTime<Utc>>> = recurrences
+        .iter()
+        .flat_map(|recurrence| busy_blocks(recurrence, &window))
+        .collect();
+    busy.sort_by_key(|block| block.start);
+
+    let mut slots = Vec::new();
+    let mut free_from = window.start;
+    for block in busy {
+        if block.start > free_from {
+            slots.push(free_from..block.start);
+        }
+        free_from = free_from.max(block.end);
+    }
+    if window.end > free_from {
+        slots.push(free_from..window.end);
+    }
+    slots.retain(|slot| slot.end - slot.start >= min_length);
+    slots
+}
+
+/// Returns the busy blocks of the events of the recurrence that overlap the window,
+/// clamped to the window.
+fn busy_blocks<'a>(
+    recurrence: &'a Recurrence,
+    window: &Range<DateTime<Utc>>,
+) -> impl Iterator<Item = Range<DateTime<Utc>>> + 'a {
+    let duration = recurrence.duration;
+    let (start, end) = (window.start, window.end);
+    let from = start
+        .checked_sub_signed(duration)
+        .unwrap_or(DateTime::<Utc>::MIN_UTC);
+    recurrence
+        .between(&from, &end)
+        .take_while(move |event| duration > Duration::zero() && *event < end)
+        .filter_map(move |event| {
+            let event_end = event
+                .checked_add_signed(duration)
+                .unwrap_or(DateTime::<Utc>::MAX_UTC);
+            (event_end > start).then(|| event.max(start)..event_end.min(end))
+        })
+}
+
+#[cfg(test)]
+mod test_availability {
+    use crate::availability::free_slots;
+    use crate::test_utils::date;
+    use crate::{Frequency, Recurrence};
+    use chrono::Duration;
+    use std::num::NonZeroU32;
+    use std::str::FromStr;
+
+    #[test]
+    fn returns_the_gaps_between_events() {
+        let meetings = Recurrence::from_str(
+            "FREQ=DAILY;INTERVAL=1;BYTIME=09:00,14:00;DTSTART=2023-01-01T00:00:00Z;DURATION=PT1H",
+        )
+        .unwrap();
+        let slots = free_slots(
+            &[meetings],
+            date("2023-01-02T08:00:00Z")..date("2023-01-02T18:00:00Z"),
+            Duration::zero(),
+        );
+        assert_eq!(
+            slots,
+            vec![
+                date("2023-01-02T08:00:00Z")..date("2023-01-02T09:00:00Z"),
+                date("2023-01-02T10:00:00Z")..date("2023-01-02T14:00:00Z"),
+                date("2023-01-02T15:00:00Z")..date("2023-01-02T18:00:00Z"),
+            ]
+        );
+    }
+
+    #[test]
+    fn merges_overlapping_events_of_many_recurrences() {
+        let long = Recurrence::from_str(
+            "FREQ=DAILY;INTERVAL=1;BYTIME=09:00;DTSTART=2023-01-01T00:00:00Z;DURATION=PT3H",
+        )
+        .unwrap();
+        let short = Recurrence::from_str(
+            "FREQ=DAILY;INTERVAL=1;BYTIME=10:00,13:00;DTSTART=2023-01-01T00:00:00Z;DURATION=PT30M",
+        )
+        .unwrap();
+        let slots = free_slots(
+            &[long, short],
+            date("2023-01-02T09:00:00Z")..date("2023-01-02T14:00:00Z"),
+            Duration::zero(),
+        );
+        assert_eq!(
+            slots,
+            vec![
+                date("2023-01-02T12:00:00Z")..date("2023-01-02T13:00:00Z"),
+                date("2023-01-02T13:30:00Z")..date("2023-01-02T14:00:00Z"),
+            ]
+        );
+    }
+
+    #[test]
+    fn includes_events_that_started_before_the_window() {
+        let nights = Recurrence::from_str(
+            "FREQ=DAILY;INTERVAL=1;BYTIME=22:00;DTSTART=2023-01-01T00:00:00Z;DURATION=PT10H",
+        )
+        .unwrap();
+        let slots = free_slots(
+            &[nights],
+            date("2023-01-02T00:00:00Z")..date("2023-01-03T00:00:00Z"),
+            Duration::zero(),
+        );
+        assert_eq!(
+            slots,
+            vec![date("2023-01-02T08:00:00Z")..date("2023-01-02T22:00:00Z")]
+        );
+    }
+
+    #[test]
+    fn skips_short_slots() {
+        let meetings = [Recurrence::from_str(
+            "FREQ=MINUTELY;INTERVAL=50;DTSTART=2023-01-02T09:00:00Z;DURATION=PT45M",
+        )
+        .unwrap()];
+        let window = date("2023-01-02T09:00:00Z")..date("2023-01-02T12:00:00Z");
+        let slots = free_slots(&meetings, window.clone(), Duration::minutes(5));
+        assert_eq!(
+            slots,
+            vec![
+                date("2023-01-02T09:45:00Z")..date("2023-01-02T09:50:00Z"),
+                date("2023-01-02T10:35:00Z")..date("2023-01-02T10:40:00Z"),
+                date("2023-01-02T11:25:00Z")..date("2023-01-02T11:30:00Z"),
+            ]
+        );
+        assert!(free_slots(&meetings, window, Duration::minutes(10)).is_empty());
+    }
+
+    #[test]
+    fn ignores_events_without_duration() {
+        let reminders = Recurrence::new(
+            Frequency::Daily {
+                interval: NonZeroU32::new(1).unwrap(),
+                by_day: vec![],
+                by_time: vec![],
+            },
+            date("2023-01-01T12:00:00Z"),
+            None,
+            None,
+        )
+        .unwrap();
+        let window = date("2023-01-02T00:00:00Z")..date("2023-01-03T00:00:00Z");
+        assert_eq!(
+            free_slots(&[reminders], window.clone(), Duration::hours(1)),
+            vec![window.clone()]
+        );
+        assert_eq!(
+            free_slots(&[], window.clone(), Duration::days(1)),
+            vec![window]
+        );
+        let empty = date("2023-01-02T00:00:00Z")..date("2023-01-02T00:00:00Z");
+        assert!(free_slots(&[], empty, Duration::zero()).is_empty());
+    }
+}
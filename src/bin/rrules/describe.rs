@@ -0,0 +1,368 @@
+//! Plain English descriptions of the recurrences, in the format read back by `text`.
+
+use chrono::{DateTime, Duration, Utc, Weekday};
+use rrules::{Frequency, MonthlyDate, NthWeekday, OverflowPolicy, Recurrence, Time};
+
+/// Returns the description of the recurrence, E.g. `every 2 weeks on Monday and Friday
+/// from 2023-01-02T09:00:00Z`.
+pub fn describe(recurrence: &Recurrence) -> String {
+    let mut clauses = describe_frequency(recurrence.frequency());
+    if recurrence.all_day {
+        clauses.push("all day".to_string());
+    }
+    clauses.push(format!(
+        "from {}",
+        format_date(&recurrence.start, recurrence.all_day)
+    ));
+    if let Some(end) = recurrence.end() {
+        clauses.push(format!("until {}", format_date(&end, recurrence.all_day)));
+    }
+    let default_duration = if recurrence.all_day {
+        Duration::days(1)
+    } else {
+        Duration::zero()
+    };
+    if recurrence.duration != default_duration && recurrence.duration > Duration::zero() {
+        clauses.push(format!("lasting {}", format_duration(&recurrence.duration)));
+    }
+    if !recurrence.exdates.is_empty() {
+        let exdates: Vec<String> = recurrence
+            .exdates
+            .iter()
+            .map(|date| format_date(date, recurrence.all_day))
+            .collect();
+        clauses.push(format!("except {}", join(&exdates)));
+    }
+    for (key, value) in &recurrence.unknown_params {
+        clauses.push(format!("with {key}={value}"));
+    }
+    clauses.join(" ")
+}
+
+/// Returns the clauses of the frequency, starting with its interval.
+fn describe_frequency(frequency: &Frequency) -> Vec<String> {
+    let mut clauses = vec![];
+    match frequency {
+        Frequency::Secondly {
+            interval,
+            by_day,
+            by_time,
+        }
+        | Frequency::Minutely {
+            interval,
+            by_day,
+            by_time,
+            ..
+        }
+        | Frequency::Hourly {
+            interval,
+            by_day,
+            by_time,
+            ..
+        } => {
+            let unit = match frequency {
+                Frequency::Secondly { .. } => "second",
+                Frequency::Minutely { .. } => "minute",
+                _ => "hour",
+            };
+            clauses.push(every(interval.get(), unit));
+            clauses.extend(on_weekdays(by_day));
+            clauses.extend(at_times(by_time));
+            if let Some((start, end)) = frequency.by_hour_range() {
+                clauses.push(format!("between hours {start} and {end}"));
+            }
+        }
+        Frequency::Daily {
+            interval,
+            by_day,
+            by_time,
+        } => {
+            clauses.push(every(interval.get(), "day"));
+            clauses.extend(on_weekdays(by_day));
+            clauses.extend(at_times(by_time));
+        }
+        Frequency::BusinessDaily { interval } => {
+            clauses.push(every(interval.get(), "business day"));
+        }
+        Frequency::Weekly {
+            interval,
+            by_day,
+            by_time,
+            by_day_time,
+        } => {
+            clauses.push(every(interval.get(), "week"));
+            clauses.extend(on_weekdays(by_day));
+            clauses.extend(at_times(by_time));
+            if !by_day_time.is_empty() {
+                let day_times: Vec<String> = by_day_time
+                    .iter()
+                    .map(|(weekday, time)| format!("{} at {time}", weekday_name(*weekday)))
+                    .collect();
+                clauses.push(format!("on {}", join(&day_times)));
+            }
+        }
+        Frequency::Monthly {
+            interval,
+            by_month_day,
+            by_day,
+            nth_weekdays,
+            by_time,
+            overflow,
+        } => {
+            clauses.push(every_months(interval.get()));
+            if !by_month_day.is_empty() {
+                let days: Vec<String> = by_month_day.iter().map(|day| ordinal(*day)).collect();
+                clauses.push(format!("on the {}", join(&days)));
+            }
+            clauses.extend(on_weekdays(by_day));
+            clauses.extend(on_nth_weekdays(nth_weekdays));
+            clauses.extend(at_times(by_time));
+            clauses.extend(with_overflow(overflow));
+        }
+        Frequency::Yearly {
+            interval,
+            by_month,
+            by_monthly_date,
+            nth_weekdays,
+            by_week_number,
+            by_time,
+            overflow,
+        } => {
+            clauses.push(every(interval.get(), "year"));
+            if !by_month.is_empty() {
+                let months: Vec<String> = by_month
+                    .iter()
+                    .map(|month| month.name().to_string())
+                    .collect();
+                clauses.push(format!("in {}", join(&months)));
+            }
+            if !by_monthly_date.is_empty() {
+                let dates: Vec<String> = by_monthly_date.iter().map(monthly_date).collect();
+                clauses.push(format!("on {}", join(&dates)));
+            }
+            if by_week_number.is_empty() {
+                clauses.extend(on_nth_weekdays(nth_weekdays));
+            } else {
+                // Weekdays inside ISO weeks happen once, so they have no ordinal
+                let weekdays: Vec<Weekday> = nth_weekdays
+                    .iter()
+                    .map(|nth_weekday| nth_weekday.weekday)
+                    .collect();
+                clauses.extend(on_weekdays(&weekdays));
+                let weeks: Vec<String> =
+                    by_week_number.iter().map(|week| week.to_string()).collect();
+                clauses.push(format!("in weeks {}", join(&weeks)));
+            }
+            clauses.extend(at_times(by_time));
+            clauses.extend(with_overflow(overflow));
+        }
+    }
+    clauses
+}
+
+/// Returns the interval clause, E.g. `every day` or `every 3 weeks`.
+fn every(interval: u32, unit: &str) -> String {
+    if interval == 1 {
+        format!("every {unit}")
+    } else {
+        format!("every {interval} {unit}s")
+    }
+}
+
+/// Describes the monthly intervals of the quarterly and semi-annual rules by their periods.
+fn every_months(interval: u32) -> String {
+    match interval {
+        3 => "every quarter".to_string(),
+        6 => "every half year".to_string(),
+        _ => every(interval, "month"),
+    }
+}
+
+fn on_weekdays(weekdays: &[Weekday]) -> Option<String> {
+    if weekdays.is_empty() {
+        return None;
+    }
+    let names: Vec<String> = weekdays
+        .iter()
+        .map(|weekday| weekday_name(*weekday).to_string())
+        .collect();
+    Some(format!("on {}", join(&names)))
+}
+
+fn on_nth_weekdays(nth_weekdays: &[NthWeekday]) -> Option<String> {
+    if nth_weekdays.is_empty() {
+        return None;
+    }
+    let names: Vec<String> = nth_weekdays
+        .iter()
+        .map(|nth_weekday| {
+            format!(
+                "{} {}",
+                ordinal(nth_weekday.week_number),
+                weekday_name(nth_weekday.weekday)
+            )
+        })
+        .collect();
+    Some(format!("on the {}", join(&names)))
+}
+
+fn at_times(times: &[Time]) -> Option<String> {
+    if times.is_empty() {
+        return None;
+    }
+    let times: Vec<String> = times.iter().map(|time| time.to_string()).collect();
+    Some(format!("at {}", join(&times)))
+}
+
+fn with_overflow(overflow: &OverflowPolicy) -> Option<String> {
+    match overflow {
+        OverflowPolicy::Skip => None,
+        OverflowPolicy::ClampToLastDay => {
+            Some("with missing days clamped to the last day".to_string())
+        }
+        OverflowPolicy::NextValid => Some("with missing days moved to the next month".to_string()),
+    }
+}
+
+/// Returns the English name of the weekday, E.g. `Monday`.
+fn weekday_name(weekday: Weekday) -> &'static str {
+    match weekday {
+        Weekday::Mon => "Monday",
+        Weekday::Tue => "Tuesday",
+        Weekday::Wed => "Wednesday",
+        Weekday::Thu => "Thursday",
+        Weekday::Fri => "Friday",
+        Weekday::Sat => "Saturday",
+        Weekday::Sun => "Sunday",
+    }
+}
+
+fn monthly_date(date: &MonthlyDate) -> String {
+    format!("{} {}", date.month.name(), date.day)
+}
+
+/// Returns the ordinal of the position, E.g. `2nd`.
+fn ordinal(position: i32) -> String {
+    let suffix = match (position % 10, position % 100) {
+        (_, 11..=13) => "th",
+        (1, _) => "st",
+        (2, _) => "nd",
+        (3, _) => "rd",
+        _ => "th",
+    };
+    format!("{position}{suffix}")
+}
+
+/// Formats the date as RFC3339, or as the day alone for all-day recurrences.
+pub fn format_date(date: &DateTime<Utc>, all_day: bool) -> String {
+    if all_day {
+        date.format("%Y-%m-%d").to_string()
+    } else {
+        date.format("%Y-%m-%dT%H:%M:%SZ").to_string()
+    }
+}
+
+/// Returns the duration in days, hours, minutes and seconds, E.g. `1 hour 30 minutes`.
+fn format_duration(duration: &Duration) -> String {
+    let seconds = duration.num_seconds();
+    let parts = [
+        (seconds / 86_400, "day"),
+        (seconds % 86_400 / 3_600, "hour"),
+        (seconds % 3_600 / 60, "minute"),
+        (seconds % 60, "second"),
+    ];
+    let parts: Vec<String> = parts
+        .iter()
+        .filter(|(value, _)| *value > 0)
+        .map(|(value, unit)| match value {
+            1 => format!("1 {unit}"),
+            value => format!("{value} {unit}s"),
+        })
+        .collect();
+    parts.join(" ")
+}
+
+/// Joins the values of a list, E.g. `Monday, Wednesday and Friday`.
+fn join(values: &[String]) -> String {
+    match values {
+        [] => String::new(),
+        [value] => value.clone(),
+        [first @ .., last] => format!("{} and {last}", first.join(", ")),
+    }
+}
+
+#[cfg(test)]
+mod test_describe {
+    use super::{describe, ordinal};
+    use rrules::Recurrence;
+    use std::str::FromStr;
+
+    fn describe_rule(rule: &str) -> String {
+        describe(&Recurrence::from_str(rule).unwrap())
+    }
+
+    #[test]
+    fn describes_the_frequencies() {
+        assert_eq!(
+            describe_rule("FREQ=DAILY;INTERVAL=1;DTSTART=2023-01-01T09:00:00Z"),
+            "every day from 2023-01-01T09:00:00Z"
+        );
+        assert_eq!(
+            describe_rule(
+                "FREQ=HOURLY;INTERVAL=2;BYDAY=MO,TU,FR;BYTIME=09:30,17:30;DTSTART=2023-01-01T01:30:00Z"
+            ),
+            "every 2 hours on Monday, Tuesday and Friday at 09:30 and 17:30 from 2023-01-01T01:30:00Z"
+        );
+        assert_eq!(
+            describe_rule(
+                "FREQ=WEEKLY;INTERVAL=1;X-BYDAYTIME=MO09:00,TH14:00;DTSTART=2023-01-02T00:00:00Z"
+            ),
+            "every week on Monday at 09:00 and Thursday at 14:00 from 2023-01-02T00:00:00Z"
+        );
+        assert_eq!(
+            describe_rule(
+                "FREQ=WEEKLY;INTERVAL=1;BYDAY=MO,WE;BYTIME=09:00,17:00;DTSTART=2023-01-02T00:00:00Z"
+            ),
+            "every week on Monday and Wednesday at 09:00 and 17:00 from 2023-01-02T00:00:00Z"
+        );
+        assert_eq!(
+            describe_rule(
+                "FREQ=MONTHLY;INTERVAL=3;BYMONTHDAY=1,31;X-OVERFLOW=CLAMP;DTSTART=2023-01-01T00:00:00Z;DTEND=2024-01-01T00:00:00Z"
+            ),
+            "every quarter on the 1st and 31st with missing days clamped to the last day \
+             from 2023-01-01T00:00:00Z until 2024-01-01T00:00:00Z"
+        );
+        assert_eq!(
+            describe_rule("FREQ=MONTHLY;INTERVAL=6;BYDAY=1MO;DTSTART=2023-01-01T00:00:00Z"),
+            "every half year on the 1st Monday from 2023-01-01T00:00:00Z"
+        );
+        assert_eq!(
+            describe_rule("FREQ=MONTHLY;INTERVAL=4;DTSTART=2023-01-01T00:00:00Z"),
+            "every 4 months from 2023-01-01T00:00:00Z"
+        );
+        assert_eq!(
+            describe_rule(
+                "FREQ=YEARLY;INTERVAL=1;BYMONTH=11;BYDAY=4TH;DTSTART=2023-01-01T12:00:00Z;DURATION=PT1H30M"
+            ),
+            "every year in November on the 4th Thursday from 2023-01-01T12:00:00Z lasting 1 hour 30 minutes"
+        );
+    }
+
+    #[test]
+    fn describes_all_day_recurrences_and_unknown_params() {
+        assert_eq!(
+            describe_rule(
+                "FREQ=YEARLY;INTERVAL=1;BYMONTH=6;BYMONTHDAY=12;DTSTART=VALUE=DATE:20230612;X-NAME=birthday"
+            ),
+            "every year on June 12 all day from 2023-06-12 with X-NAME=birthday"
+        );
+    }
+
+    #[test]
+    fn ordinals() {
+        assert_eq!(ordinal(1), "1st");
+        assert_eq!(ordinal(12), "12th");
+        assert_eq!(ordinal(22), "22nd");
+        assert_eq!(ordinal(53), "53rd");
+    }
+}
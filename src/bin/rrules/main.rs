@@ -0,0 +1,252 @@
+//! Command line tool to expand, validate and describe recurrence rules, available with
+//! the `cli` feature.
+
+mod describe;
+mod text;
+
+use chrono::{DateTime, Utc};
+use describe::{describe, format_date};
+use rrules::{ParseOptions, Recurrence};
+use std::io::{self, BufRead, Write};
+use std::process::ExitCode;
+use std::str::FromStr;
+use text::parse_text;
+
+const USAGE: &str = "\
+Usage: rrules <COMMAND>
+
+Commands:
+  next <RULE> [--count <N>] [--after <DATE>]  Prints the next event dates of the rule, 10 by default
+  validate [--strict] [RULE]...              Validates the rules, or every line of stdin when none is given
+  describe <RULE>                            Prints the rule in plain English
+  parse <TEXT>...                            Prints the rule of a plain English description
+
+The rules without DTSTART and the descriptions without a `from` date start now.";
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let stdin = io::stdin().lock();
+    let mut stdout = io::stdout().lock();
+    match run(&args, Utc::now(), stdin, &mut stdout) {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::FAILURE,
+        Err(error) => {
+            eprintln!("error: {error}");
+            ExitCode::from(2)
+        }
+    }
+}
+
+/// Runs the command of the arguments, returning whether the rules were valid.
+fn run(
+    args: &[String],
+    now: DateTime<Utc>,
+    input: impl BufRead,
+    output: &mut impl Write,
+) -> Result<bool, String> {
+    let Some((command, args)) = args.split_first() else {
+        return Err(format!("Missing command\n\n{USAGE}"));
+    };
+    match command.as_str() {
+        "next" => next(args, now, output),
+        "validate" => validate(args, now, input, output),
+        "describe" => {
+            let [rule] = args else {
+                return Err(format!("Expected one rule\n\n{USAGE}"));
+            };
+            let recurrence = parse_rule(rule, now)?;
+            print(output, &describe(&recurrence))?;
+            Ok(true)
+        }
+        "parse" => {
+            let recurrence = parse_text(&args.join(" "), now)?;
+            print(output, &recurrence.to_string())?;
+            Ok(true)
+        }
+        "help" | "--help" | "-h" => {
+            print(output, USAGE)?;
+            Ok(true)
+        }
+        _ => Err(format!("Unknown command `{command}`\n\n{USAGE}")),
+    }
+}
+
+/// Prints the event dates after the given date, or after the current date.
+fn next(args: &[String], now: DateTime<Utc>, output: &mut impl Write) -> Result<bool, String> {
+    let mut count = 10;
+    let mut after = now;
+    let mut rule = None;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-n" | "--count" => {
+                let value = option_value(arg, args.next())?;
+                count = value
+                    .parse()
+                    .map_err(|_| format!("Invalid count: {value}"))?;
+            }
+            "--after" => {
+                let value = option_value(arg, args.next())?;
+                after = DateTime::<Utc>::from_str(value)
+                    .map_err(|_| format!("Invalid date: {value}"))?;
+            }
+            _ if rule.is_none() => rule = Some(arg),
+            _ => return Err(format!("Unexpected argument `{arg}`")),
+        }
+    }
+    let rule = rule.ok_or_else(|| format!("Missing rule\n\n{USAGE}"))?;
+    let recurrence = parse_rule(rule, now)?;
+    let mut date = after;
+    for _ in 0..count {
+        let Some(event) = recurrence.next_from(&date) else {
+            break;
+        };
+        print(output, &format_date(&event, recurrence.all_day))?;
+        date = event;
+    }
+    Ok(true)
+}
+
+/// Prints whether every rule is valid, reading them from the input when there are none.
+fn validate(
+    args: &[String],
+    now: DateTime<Utc>,
+    input: impl BufRead,
+    output: &mut impl Write,
+) -> Result<bool, String> {
+    let strict = args.iter().any(|arg| arg == "--strict");
+    let options = if strict {
+        ParseOptions::strict()
+    } else {
+        ParseOptions::lenient()
+    }
+    .with_default_start(now);
+    let rules: Vec<(String, String)> = if args.iter().all(|arg| arg == "--strict") {
+        let lines: Vec<String> = input
+            .lines()
+            .collect::<Result<_, _>>()
+            .map_err(|e| format!("Cannot read the rules: {e}"))?;
+        lines
+            .into_iter()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(index, line)| (format!("line {}", index + 1), line.trim().to_string()))
+            .collect()
+    } else {
+        args.iter()
+            .filter(|arg| *arg != "--strict")
+            .map(|rule| (rule.clone(), rule.clone()))
+            .collect()
+    };
+    let mut valid = true;
+    for (name, rule) in rules {
+        match Recurrence::from_str_with(&rule, options) {
+            Ok(_) => print(output, &format!("{name}: ok"))?,
+            Err(error) => {
+                valid = false;
+                print(output, &format!("{name}: {error}"))?;
+            }
+        }
+    }
+    Ok(valid)
+}
+
+fn parse_rule(rule: &str, now: DateTime<Utc>) -> Result<Recurrence, String> {
+    Recurrence::from_str_with_default_start(rule, now).map_err(|e| e.to_string())
+}
+
+fn option_value<'a>(option: &str, value: Option<&'a String>) -> Result<&'a str, String> {
+    value
+        .map(String::as_str)
+        .ok_or_else(|| format!("Missing value of `{option}`"))
+}
+
+fn print(output: &mut impl Write, line: &str) -> Result<(), String> {
+    writeln!(output, "{line}").map_err(|e| format!("Cannot write the output: {e}"))
+}
+
+#[cfg(test)]
+mod test_cli {
+    use super::run;
+    use chrono::{DateTime, Utc};
+    use std::str::FromStr;
+
+    fn run_command(args: &[&str], input: &str) -> (Result<bool, String>, String) {
+        let args: Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
+        let now = DateTime::<Utc>::from_str("2023-01-01T12:00:00Z").unwrap();
+        let mut output = vec![];
+        let result = run(&args, now, input.as_bytes(), &mut output);
+        (result, String::from_utf8(output).unwrap())
+    }
+
+    #[test]
+    fn prints_the_next_events() {
+        let (result, output) = run_command(
+            &[
+                "next",
+                "FREQ=WEEKLY;INTERVAL=1;BYDAY=MO,TH;DTSTART=2022-12-01T09:00:00Z",
+                "-n",
+                "3",
+            ],
+            "",
+        );
+        assert_eq!(result, Ok(true));
+        assert_eq!(
+            output,
+            "2023-01-02T09:00:00Z\n2023-01-05T09:00:00Z\n2023-01-09T09:00:00Z\n"
+        );
+
+        let (_, output) = run_command(
+            &[
+                "next",
+                "FREQ=DAILY;INTERVAL=1",
+                "--after",
+                "2023-02-01T00:00:00Z",
+                "--count",
+                "2",
+            ],
+            "",
+        );
+        assert_eq!(output, "2023-02-01T12:00:00Z\n2023-02-02T12:00:00Z\n");
+    }
+
+    #[test]
+    fn validates_the_rules() {
+        let input = "FREQ=DAILY;INTERVAL=1;DTSTART=2023-01-01T12:00:00Z\n\nFREQ=DAILY;INTERVAL=0\n";
+        let (result, output) = run_command(&["validate"], input);
+        assert_eq!(result, Ok(false));
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines[0], "line 1: ok");
+        assert!(lines[1].starts_with("line 3: "));
+
+        let rule = "FREQ=DAILY;INTERVAL=1;X-NAME=standup";
+        assert_eq!(run_command(&["validate", rule], "").0, Ok(true));
+        assert_eq!(
+            run_command(&["validate", "--strict", rule], "").0,
+            Ok(false)
+        );
+    }
+
+    #[test]
+    fn converts_between_rules_and_descriptions() {
+        let rule = "FREQ=MONTHLY;INTERVAL=1;BYDAY=2TU;DTSTART=2023-01-10T18:00:00Z";
+        let (_, description) = run_command(&["describe", rule], "");
+        assert_eq!(
+            description,
+            "every month on the 2nd Tuesday from 2023-01-10T18:00:00Z\n"
+        );
+        let words: Vec<&str> = description.split_whitespace().collect();
+        let (_, parsed) = run_command(&[&["parse"], &words[..]].concat(), "");
+        assert_eq!(parsed.trim_end(), rule);
+    }
+
+    #[test]
+    fn usage_errors() {
+        assert!(run_command(&[], "").0.unwrap_err().contains("Usage"));
+        assert!(run_command(&["expand"], "").0.is_err());
+        assert!(run_command(&["next"], "").0.is_err());
+        assert!(run_command(&["describe", "FREQ=DAILY;INTERVAL=0"], "")
+            .0
+            .is_err());
+    }
+}
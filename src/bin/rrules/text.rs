@@ -0,0 +1,690 @@
+//! Parsing of the plain English descriptions written by `describe`.
+//!
+//! A description starts with its interval, E.g. `every 2 weeks`, followed by clauses in
+//! any order: `on Monday and Friday`, `on the 1st and 15th`, `on the 2nd Tuesday`,
+//! `on June 12`, `in March`, `in weeks 1 and 20`, `at 09:00`, `between hours 9 and 18`,
+//! `all day`, `from <date>`, `until <date>`, `lasting 1 hour 30 minutes`, `except <dates>`
+//! and `with <policy>`.
+
+use chrono::{DateTime, Duration, Month, NaiveDate, NaiveTime, TimeZone, Utc, Weekday};
+use rrules::recurrences::validations::validate_duration_with;
+use rrules::{Frequency, MonthlyDate, NthWeekday, OverflowPolicy, Recurrence, Time};
+use std::num::NonZeroU32;
+use std::str::FromStr;
+
+/// Parses a description into a recurrence, starting at the given date when the
+/// description has no `from` date.
+pub fn parse_text(text: &str, now: DateTime<Utc>) -> Result<Recurrence, String> {
+    let mut parser = Parser::new(text);
+    if !parser.eat("every") {
+        return Err("The description must start with `every`".to_string());
+    }
+    let interval = match parser.attempt(Parser::number) {
+        Some(interval) => u32::try_from(interval)
+            .ok()
+            .and_then(NonZeroU32::new)
+            .ok_or_else(|| format!("Invalid interval: {interval}"))?,
+        None => NonZeroU32::MIN,
+    };
+    let (unit, months) = parser.unit()?;
+    let interval = interval
+        .checked_mul(months)
+        .ok_or_else(|| format!("Invalid interval: {interval}"))?;
+    let mut rule = Rule::new(interval, unit);
+    while let Some(token) = parser.next() {
+        match token.to_lowercase().as_str() {
+            "on" => parser.on(&mut rule)?,
+            "in" if parser.eat("weeks") => {
+                rule.week_numbers.extend(parser.list(Parser::number)?);
+            }
+            "in" => rule.months.extend(parser.list(Parser::month)?),
+            "at" => rule.times.extend(parser.list(Parser::time)?),
+            "between" if parser.eat("hours") => rule.hour_range = Some(parser.hour_range()?),
+            "all" if parser.eat("day") => rule.all_day = true,
+            "from" => rule.start = Some(parser.date()?),
+            "until" => rule.end = Some(parser.date()?),
+            "lasting" => rule.duration = Some(parser.duration()?),
+            "except" => rule.exdates.extend(parser.list(Parser::date)?),
+            "with" => parser.with(&mut rule)?,
+            _ => return Err(format!("Unexpected `{token}`")),
+        }
+    }
+    rule.into_recurrence(now)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Unit {
+    Second,
+    Minute,
+    Hour,
+    Day,
+    BusinessDay,
+    Week,
+    Month,
+    Year,
+}
+
+impl Unit {
+    fn name(&self) -> &'static str {
+        match self {
+            Unit::Second => "secondly",
+            Unit::Minute => "minutely",
+            Unit::Hour => "hourly",
+            Unit::Day => "daily",
+            Unit::BusinessDay => "business daily",
+            Unit::Week => "weekly",
+            Unit::Month => "monthly",
+            Unit::Year => "yearly",
+        }
+    }
+}
+
+/// The values read from a description.
+struct Rule {
+    interval: NonZeroU32,
+    unit: Unit,
+    weekdays: Vec<Weekday>,
+    day_times: Vec<(Weekday, Time)>,
+    month_days: Vec<i32>,
+    nth_weekdays: Vec<NthWeekday>,
+    months: Vec<Month>,
+    monthly_dates: Vec<MonthlyDate>,
+    week_numbers: Vec<i32>,
+    times: Vec<Time>,
+    hour_range: Option<(u8, u8)>,
+    overflow: OverflowPolicy,
+    all_day: bool,
+    start: Option<DateTime<Utc>>,
+    end: Option<DateTime<Utc>>,
+    duration: Option<Duration>,
+    exdates: Vec<DateTime<Utc>>,
+    unknown_params: Vec<(String, String)>,
+}
+
+impl Rule {
+    fn new(interval: NonZeroU32, unit: Unit) -> Self {
+        Rule {
+            interval,
+            unit,
+            weekdays: vec![],
+            day_times: vec![],
+            month_days: vec![],
+            nth_weekdays: vec![],
+            months: vec![],
+            monthly_dates: vec![],
+            week_numbers: vec![],
+            times: vec![],
+            hour_range: None,
+            overflow: OverflowPolicy::Skip,
+            all_day: false,
+            start: None,
+            end: None,
+            duration: None,
+            exdates: vec![],
+            unknown_params: vec![],
+        }
+    }
+
+    fn into_recurrence(self, now: DateTime<Utc>) -> Result<Recurrence, String> {
+        let frequency = self.frequency()?;
+        let start = self.start.unwrap_or(now);
+        let recurrence = if self.all_day {
+            let mut recurrence = Recurrence::all_day(
+                frequency,
+                start.date_naive(),
+                self.end.map(|end| end.date_naive()),
+            )
+            .map_err(|e| e.to_string())?;
+            if let Some(duration) = self.duration {
+                validate_duration_with(
+                    &recurrence.frequency,
+                    &recurrence.start,
+                    &duration,
+                    &recurrence.validation,
+                )
+                .map_err(|e| e.to_string())?;
+                recurrence.duration = duration;
+            }
+            recurrence
+        } else {
+            Recurrence::new(frequency, start, self.end, self.duration).map_err(|e| e.to_string())?
+        };
+        Ok(recurrence
+            .with_exdates(self.exdates)
+            .with_unknown_params(self.unknown_params))
+    }
+
+    fn frequency(&self) -> Result<Frequency, String> {
+        let interval = self.interval;
+        let frequency = match self.unit {
+            Unit::Second => {
+                self.only(&["weekdays", "times"])?;
+                Frequency::Secondly {
+                    interval,
+                    by_day: self.weekdays.clone(),
+                    by_time: self.times.clone(),
+                }
+            }
+            Unit::Minute | Unit::Hour => {
+                self.only(&["weekdays", "times", "hour ranges"])?;
+                let (by_day, by_time) = (self.weekdays.clone(), self.times.clone());
+                let by_hour_range = self.hour_range;
+                match self.unit {
+                    Unit::Minute => Frequency::Minutely {
+                        interval,
+                        by_day,
+                        by_time,
+                        by_hour_range,
+                    },
+                    _ => Frequency::Hourly {
+                        interval,
+                        by_day,
+                        by_time,
+                        by_hour_range,
+                    },
+                }
+            }
+            Unit::Day => {
+                self.only(&["weekdays", "times"])?;
+                Frequency::Daily {
+                    interval,
+                    by_day: self.weekdays.clone(),
+                    by_time: self.times.clone(),
+                }
+            }
+            Unit::BusinessDay => {
+                self.only(&[])?;
+                Frequency::BusinessDaily { interval }
+            }
+            Unit::Week => {
+                self.only(&["weekdays", "times", "day times"])?;
+                Frequency::Weekly {
+                    interval,
+                    by_day: self.weekdays.clone(),
+                    by_time: self.times.clone(),
+                    by_day_time: self.day_times.clone(),
+                }
+            }
+            Unit::Month => {
+                self.only(&[
+                    "weekdays",
+                    "month days",
+                    "nth weekdays",
+                    "times",
+                    "overflow policies",
+                ])?;
+                Frequency::Monthly {
+                    interval,
+                    by_month_day: self.month_days.clone(),
+                    by_day: self.weekdays.clone(),
+                    nth_weekdays: self.nth_weekdays.clone(),
+                    by_time: self.times.clone(),
+                    overflow: self.overflow,
+                }
+            }
+            Unit::Year => {
+                self.only(&[
+                    "weekdays",
+                    "nth weekdays",
+                    "months",
+                    "dates",
+                    "week numbers",
+                    "times",
+                    "overflow policies",
+                ])?;
+                // Weekdays inside ISO weeks happen once, so they have no ordinal
+                let nth_weekdays = match (self.weekdays.is_empty(), self.week_numbers.is_empty()) {
+                    (true, _) => self.nth_weekdays.clone(),
+                    (false, false) => self
+                        .weekdays
+                        .iter()
+                        .map(|weekday| NthWeekday::new(*weekday, 1))
+                        .collect(),
+                    (false, true) => {
+                        return Err(
+                            "Weekdays of yearly rules need an ordinal, E.g. the 20th Monday, \
+                             or week numbers"
+                                .to_string(),
+                        )
+                    }
+                };
+                Frequency::Yearly {
+                    interval,
+                    by_month: self.months.clone(),
+                    by_monthly_date: self.monthly_dates.clone(),
+                    nth_weekdays,
+                    by_week_number: self.week_numbers.clone(),
+                    by_time: self.times.clone(),
+                    overflow: self.overflow,
+                }
+            }
+        };
+        Ok(frequency)
+    }
+
+    /// Returns an error if the rule has filters other than the given ones.
+    fn only(&self, allowed: &[&str]) -> Result<(), String> {
+        let filters = [
+            ("weekdays", !self.weekdays.is_empty()),
+            ("day times", !self.day_times.is_empty()),
+            ("month days", !self.month_days.is_empty()),
+            ("nth weekdays", !self.nth_weekdays.is_empty()),
+            ("months", !self.months.is_empty()),
+            ("dates", !self.monthly_dates.is_empty()),
+            ("week numbers", !self.week_numbers.is_empty()),
+            ("times", !self.times.is_empty()),
+            ("hour ranges", self.hour_range.is_some()),
+            ("overflow policies", self.overflow != OverflowPolicy::Skip),
+        ];
+        match filters
+            .iter()
+            .find(|(name, used)| *used && !allowed.contains(name))
+        {
+            Some((name, _)) => Err(format!(
+                "{} cannot be used with {} rules",
+                capitalize(name),
+                self.unit.name()
+            )),
+            None => Ok(()),
+        }
+    }
+}
+
+/// A value of the `on` clauses without `the`.
+enum OnValue {
+    Weekday(Weekday),
+    WeekdayAt(Weekday, Time),
+    Date(MonthlyDate),
+}
+
+struct Parser {
+    tokens: Vec<String>,
+    position: usize,
+}
+
+impl Parser {
+    fn new(text: &str) -> Self {
+        let mut tokens = vec![];
+        for word in text.split_whitespace() {
+            match word.strip_suffix(',') {
+                Some(word) => {
+                    if !word.is_empty() {
+                        tokens.push(word.to_string());
+                    }
+                    tokens.push(",".to_string());
+                }
+                None => tokens.push(word.to_string()),
+            }
+        }
+        Parser {
+            tokens,
+            position: 0,
+        }
+    }
+
+    fn next(&mut self) -> Option<String> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    fn expect_next(&mut self, what: &str) -> Result<String, String> {
+        self.next()
+            .ok_or_else(|| format!("Missing {what} at the end of the description"))
+    }
+
+    /// Moves past the next token when it is the given word.
+    fn eat(&mut self, word: &str) -> bool {
+        self.eat_words(&[word])
+    }
+
+    /// Moves past the next tokens when they are the given words.
+    fn eat_words(&mut self, words: &[&str]) -> bool {
+        let matches = words.iter().enumerate().all(|(index, word)| {
+            self.tokens
+                .get(self.position + index)
+                .is_some_and(|token| token.eq_ignore_ascii_case(word))
+        });
+        if matches {
+            self.position += words.len();
+        }
+        matches
+    }
+
+    /// Reads a value, moving back to the current token when it cannot be read.
+    fn attempt<T>(&mut self, read: impl FnOnce(&mut Self) -> Result<T, String>) -> Option<T> {
+        let position = self.position;
+        let value = read(self).ok();
+        if value.is_none() {
+            self.position = position;
+        }
+        value
+    }
+
+    /// Reads the values separated by commas or `and`.
+    fn list<T>(&mut self, read: impl Fn(&mut Self) -> Result<T, String>) -> Result<Vec<T>, String> {
+        let mut values = vec![read(self)?];
+        loop {
+            let position = self.position;
+            if !self.eat(",") && !self.eat("and") {
+                break;
+            }
+            match self.attempt(&read) {
+                Some(value) => values.push(value),
+                None => {
+                    self.position = position;
+                    break;
+                }
+            }
+        }
+        Ok(values)
+    }
+
+    /// Reads the unit of the frequency, with the number of units in each of its periods, like
+    /// the 3 months of a quarter.
+    fn unit(&mut self) -> Result<(Unit, NonZeroU32), String> {
+        let token = self.expect_next("the frequency")?.to_lowercase();
+        if token == "business" && (self.eat("day") || self.eat("days")) {
+            return Ok((Unit::BusinessDay, NonZeroU32::MIN));
+        }
+        let months = match token.as_str() {
+            "quarter" | "quarters" => NonZeroU32::new(3),
+            "half" if self.eat("year") => NonZeroU32::new(6),
+            _ => None,
+        };
+        if let Some(months) = months {
+            return Ok((Unit::Month, months));
+        }
+        let unit = match token.strip_suffix('s').unwrap_or(&token) {
+            "second" => Unit::Second,
+            "minute" => Unit::Minute,
+            "hour" => Unit::Hour,
+            "day" => Unit::Day,
+            "week" => Unit::Week,
+            "month" => Unit::Month,
+            "year" => Unit::Year,
+            _ => return Err(format!("Unknown frequency `{token}`")),
+        };
+        Ok((unit, NonZeroU32::MIN))
+    }
+
+    fn on(&mut self, rule: &mut Rule) -> Result<(), String> {
+        if self.eat("the") {
+            let values = self.list(|parser| {
+                let position = parser.ordinal()?;
+                Ok((position, parser.attempt(Parser::weekday)))
+            })?;
+            for (position, weekday) in values {
+                match weekday {
+                    Some(weekday) => rule.nth_weekdays.push(NthWeekday::new(weekday, position)),
+                    None => rule.month_days.push(position),
+                }
+            }
+            return Ok(());
+        }
+        let unit = rule.unit;
+        let position = self.position;
+        let values = self.list(|parser| parser.on_value(unit))?;
+        // The times after the last of the weekdays are the times of all of them, E.g.
+        // `on Monday and Friday at 09:00`, so they are read again as an `at` clause
+        if let [weekdays @ .., OnValue::WeekdayAt(..)] = values.as_slice() {
+            if !weekdays.is_empty()
+                && weekdays
+                    .iter()
+                    .all(|value| matches!(value, OnValue::Weekday(_)))
+            {
+                self.position = position;
+                rule.weekdays.extend(self.list(Parser::weekday)?);
+                return Ok(());
+            }
+        }
+        for day in values {
+            match day {
+                OnValue::Weekday(weekday) => rule.weekdays.push(weekday),
+                OnValue::WeekdayAt(weekday, time) => rule.day_times.push((weekday, time)),
+                OnValue::Date(date) => rule.monthly_dates.push(date),
+            }
+        }
+        Ok(())
+    }
+
+    fn on_value(&mut self, unit: Unit) -> Result<OnValue, String> {
+        if let Some(month) = self.attempt(Parser::month) {
+            let day = self.number()?;
+            return Ok(OnValue::Date(MonthlyDate { month, day }));
+        }
+        let weekday = self.weekday()?;
+        // Only weekly rules have a time for each day, the others are followed by their times
+        if unit == Unit::Week {
+            let time = self.attempt(|parser| {
+                if !parser.eat("at") {
+                    return Err(String::new());
+                }
+                parser.time()
+            });
+            if let Some(time) = time {
+                return Ok(OnValue::WeekdayAt(weekday, time));
+            }
+        }
+        Ok(OnValue::Weekday(weekday))
+    }
+
+    fn with(&mut self, rule: &mut Rule) -> Result<(), String> {
+        if self.eat_words(&["missing", "days", "clamped", "to", "the", "last", "day"]) {
+            rule.overflow = OverflowPolicy::ClampToLastDay;
+        } else if self.eat_words(&["missing", "days", "moved", "to", "the", "next", "month"]) {
+            rule.overflow = OverflowPolicy::NextValid;
+        } else {
+            let token = self.expect_next("the parameter")?;
+            let (key, value) = token
+                .split_once('=')
+                .ok_or_else(|| format!("Unexpected `with {token}`"))?;
+            rule.unknown_params
+                .push((key.to_string(), value.to_string()));
+        }
+        Ok(())
+    }
+
+    fn number(&mut self) -> Result<i32, String> {
+        let token = self.expect_next("a number")?;
+        token
+            .parse()
+            .map_err(|_| format!("Invalid number: {token}"))
+    }
+
+    /// Reads the hours of a range after `between hours`, E.g. `9 and 18`.
+    fn hour_range(&mut self) -> Result<(u8, u8), String> {
+        let start = self.number()?;
+        if !self.eat("and") {
+            return Err("Missing `and` between the hours".to_string());
+        }
+        let end = self.number()?;
+        match (u8::try_from(start), u8::try_from(end)) {
+            (Ok(start), Ok(end)) => Ok((start, end)),
+            _ => Err(format!("Invalid hours: {start} and {end}")),
+        }
+    }
+
+    /// Reads an ordinal, E.g. `2nd`.
+    fn ordinal(&mut self) -> Result<i32, String> {
+        let token = self.expect_next("an ordinal")?.to_lowercase();
+        ["st", "nd", "rd", "th"]
+            .iter()
+            .find_map(|suffix| token.strip_suffix(suffix))
+            .and_then(|number| number.parse().ok())
+            .ok_or_else(|| format!("Invalid ordinal: {token}"))
+    }
+
+    /// Reads a weekday, E.g. `Monday`, `mon` or `Mondays`.
+    fn weekday(&mut self) -> Result<Weekday, String> {
+        let token = self.expect_next("a weekday")?;
+        Weekday::from_str(&token)
+            .or_else(|_| Weekday::from_str(token.strip_suffix('s').unwrap_or(&token)))
+            .map_err(|_| format!("Invalid weekday: {token}"))
+    }
+
+    fn month(&mut self) -> Result<Month, String> {
+        let token = self.expect_next("a month")?;
+        Month::from_str(&token).map_err(|_| format!("Invalid month: {token}"))
+    }
+
+    fn time(&mut self) -> Result<Time, String> {
+        let token = self.expect_next("a time")?;
+        Time::from_str(&token).map_err(|e| e.to_string())
+    }
+
+    /// Reads an RFC3339 date, or a day that starts at midnight UTC.
+    fn date(&mut self) -> Result<DateTime<Utc>, String> {
+        let token = self.expect_next("a date")?;
+        if let Ok(date) = DateTime::<Utc>::from_str(&token) {
+            return Ok(date);
+        }
+        NaiveDate::parse_from_str(&token, "%Y-%m-%d")
+            .map(|date| Utc.from_utc_datetime(&date.and_time(NaiveTime::MIN)))
+            .map_err(|_| format!("Invalid date: {token}"))
+    }
+
+    /// Reads a duration, E.g. `1 hour 30 minutes`.
+    fn duration(&mut self) -> Result<Duration, String> {
+        let mut duration = Duration::zero();
+        loop {
+            let value = self.number()?;
+            let token = self.expect_next("the duration unit")?.to_lowercase();
+            let unit = match token.strip_suffix('s').unwrap_or(&token) {
+                "day" => Duration::days(1),
+                "hour" => Duration::hours(1),
+                "minute" => Duration::minutes(1),
+                "second" => Duration::seconds(1),
+                _ => return Err(format!("Invalid duration unit: {token}")),
+            };
+            duration += unit * value;
+            let has_more = self
+                .tokens
+                .get(self.position)
+                .is_some_and(|token| token.parse::<i32>().is_ok());
+            if !has_more {
+                return Ok(duration);
+            }
+        }
+    }
+}
+
+fn capitalize(value: &str) -> String {
+    let mut chars = value.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod test_text {
+    use super::parse_text;
+    use crate::describe::describe;
+    use chrono::{DateTime, Utc};
+    use rrules::Recurrence;
+    use std::str::FromStr;
+
+    fn now() -> DateTime<Utc> {
+        DateTime::from_str("2023-01-01T09:00:00Z").unwrap()
+    }
+
+    fn parse(text: &str) -> String {
+        parse_text(text, now()).unwrap().to_string()
+    }
+
+    #[test]
+    fn parses_descriptions() {
+        assert_eq!(
+            parse("every day"),
+            "FREQ=DAILY;INTERVAL=1;DTSTART=2023-01-01T09:00:00Z"
+        );
+        assert_eq!(
+            parse("every 2 weeks on Mondays and fri from 2023-01-02T10:00:00Z until 2023-03-01"),
+            "FREQ=WEEKLY;INTERVAL=2;BYDAY=MO,FR;DTSTART=2023-01-02T10:00:00Z;DTEND=2023-03-01T00:00:00Z"
+        );
+        assert_eq!(
+            parse("Every month on the 2nd Tuesday, 4th Friday with missing days moved to the next month"),
+            "FREQ=MONTHLY;INTERVAL=1;BYDAY=2TU,4FR;X-OVERFLOW=NEXT;DTSTART=2023-01-01T09:00:00Z"
+        );
+        assert_eq!(
+            parse("every hour on Monday at 09:00 and 17:30 lasting 15 minutes"),
+            "FREQ=HOURLY;INTERVAL=1;BYDAY=MO;BYTIME=09:00,17:30;DTSTART=2023-01-01T09:00:00Z;DURATION=PT15M"
+        );
+        assert_eq!(
+            parse("every week on Monday and Friday at 09:00"),
+            "FREQ=WEEKLY;INTERVAL=1;BYDAY=MO,FR;BYHOUR=9;BYMINUTE=0;DTSTART=2023-01-01T09:00:00Z"
+        );
+        assert_eq!(
+            parse("every week on Monday at 09:00 and Friday at 10:00"),
+            "FREQ=WEEKLY;INTERVAL=1;X-BYDAYTIME=MO09:00,FR10:00;DTSTART=2023-01-01T09:00:00Z"
+        );
+        assert_eq!(
+            parse("every 30 minutes on Monday and Friday between hours 9 and 18"),
+            "FREQ=MINUTELY;INTERVAL=30;BYDAY=MO,FR;X-BYHOURRANGE=9-18;DTSTART=2023-01-01T09:00:00Z"
+        );
+        assert_eq!(
+            parse("every 2 quarters on the 15th"),
+            "FREQ=MONTHLY;INTERVAL=6;BYMONTHDAY=15;DTSTART=2023-01-01T09:00:00Z"
+        );
+        assert_eq!(
+            parse("every half year on the 2nd Friday"),
+            "FREQ=MONTHLY;INTERVAL=6;BYDAY=2FR;DTSTART=2023-01-01T09:00:00Z"
+        );
+    }
+
+    #[test]
+    fn descriptions_round_trip() {
+        let rules = [
+            "FREQ=SECONDLY;INTERVAL=30;DTSTART=2023-01-01T00:00:00Z;DTEND=2023-01-01T01:00:00Z",
+            "FREQ=HOURLY;INTERVAL=2;X-BYHOURRANGE=22-24;DTSTART=2023-01-01T00:00:00Z",
+            "FREQ=DAILY;INTERVAL=1;BYHOUR=2;BYMINUTE=30;DTSTART=2023-01-01T00:00:00Z",
+            "FREQ=DAILY;INTERVAL=2;X-BUSINESS=TRUE;DTSTART=2023-01-02T09:00:00Z",
+            "FREQ=DAILY;INTERVAL=2;BYDAY=MO,WE;BYHOUR=9;BYMINUTE=0;DTSTART=2023-01-02T00:00:00Z",
+            "FREQ=WEEKLY;INTERVAL=1;X-BYDAYTIME=MO09:00,TH14:00;DTSTART=2023-01-02T00:00:00Z",
+            "FREQ=WEEKLY;INTERVAL=1;BYDAY=MO,FR;BYTIME=09:00;DTSTART=2023-01-02T00:00:00Z",
+            "FREQ=WEEKLY;INTERVAL=2;BYDAY=MO,WE,FR;BYTIME=09:00,17:30;DTSTART=2023-01-02T00:00:00Z",
+            "FREQ=MONTHLY;INTERVAL=1;BYMONTHDAY=13;BYDAY=FR;DTSTART=2023-01-01T00:00:00Z",
+            "FREQ=MONTHLY;INTERVAL=1;BYMONTHDAY=1,31;X-OVERFLOW=CLAMP;DTSTART=2023-01-01T00:00:00Z",
+            "FREQ=MONTHLY;INTERVAL=3;BYMONTHDAY=28;DTSTART=2023-01-01T00:00:00Z",
+            "FREQ=MONTHLY;INTERVAL=6;BYDAY=1MO;DTSTART=2023-01-01T00:00:00Z",
+            "FREQ=YEARLY;INTERVAL=1;BYMONTH=11;BYDAY=4TH;DTSTART=2023-01-01T12:00:00Z",
+            "FREQ=YEARLY;INTERVAL=1;BYDAY=MO;BYWEEKNO=1,20;DTSTART=2023-01-01T00:00:00Z",
+            "FREQ=YEARLY;INTERVAL=1;X-BYMONTHDATE=JAN1,DEC25;DTSTART=VALUE=DATE:20230101;X-NAME=holidays",
+        ];
+        for rule in rules {
+            let recurrence = Recurrence::from_str(rule).unwrap();
+            let parsed = parse_text(&describe(&recurrence), now()).unwrap();
+            assert_eq!(parsed, recurrence, "{rule}");
+        }
+        let recurrence = Recurrence::from_str("FREQ=DAILY;INTERVAL=1;DTSTART=2023-01-01T09:00:00Z")
+            .unwrap()
+            .with_exdates(vec![DateTime::from_str("2023-01-05T09:00:00Z").unwrap()]);
+        let parsed = parse_text(&describe(&recurrence), now()).unwrap();
+        assert_eq!(parsed, recurrence);
+    }
+
+    #[test]
+    fn invalid_descriptions() {
+        let error = |text: &str| parse_text(text, now()).unwrap_err();
+        assert_eq!(error("daily"), "The description must start with `every`");
+        assert_eq!(error("every fortnight"), "Unknown frequency `fortnight`");
+        assert_eq!(
+            error("every day in March"),
+            "Months cannot be used with daily rules"
+        );
+        assert_eq!(error("every week on the moon"), "Invalid ordinal: moon");
+        assert_eq!(error("every 0 days"), "Invalid interval: 0");
+        assert_eq!(error("every half day"), "Unknown frequency `half`");
+        assert_eq!(
+            error("every day between hours 9 and 18"),
+            "Hour ranges cannot be used with daily rules"
+        );
+        assert_eq!(
+            error("every hour between hours 9 to 18"),
+            "Missing `and` between the hours"
+        );
+        assert!(error("every hour between hours 18 and 9").contains("Invalid range of hours"));
+        assert!(error("every day until 2022-01-01").contains("before end date"));
+    }
+}
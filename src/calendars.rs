@@ -0,0 +1,498 @@
+//! Calendar scales, the calendars the months and years of the dates are counted in.
+//!
+//! RFC 7529 lets the rules use other calendars than the Gregorian one with the `RSCALE` key.
+//! A `CalendarScale` defines the months of the years and the days of the months of a calendar,
+//! and converts its dates from and to the Gregorian dates of the events. The rules use the
+//! `Gregorian` scale, and the `calendars` feature adds the `Hebrew` and `IslamicCivil` scales,
+//! E.g. to find the dates of religious holidays.
+//!
+//! Examples:
+//! ```
+//! use chrono::NaiveDate;
+//! use rrules::calendars::{CalendarDate, CalendarScale, Gregorian};
+//! use rrules::OverflowPolicy;
+//!
+//! assert_eq!(Gregorian.days_in_month(2024, 2), Some(29));
+//! assert_eq!(Gregorian.add_months(2023, 11, 3), Some((2024, 2)));
+//! assert_eq!(
+//!     Gregorian.resolve(2023, 2, 31, OverflowPolicy::ClampToLastDay),
+//!     NaiveDate::from_ymd_opt(2023, 2, 28)
+//! );
+//! let date = NaiveDate::from_ymd_opt(2023, 3, 1).unwrap();
+//! assert_eq!(Gregorian.calendar_date(&date), Some(CalendarDate::new(2023, 3, 1)));
+//! ```
+
+use crate::OverflowPolicy;
+use chrono::{Datelike, NaiveDate};
+
+/// A date of a calendar scale, with months and days counted from 1.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CalendarDate {
+    pub year: i32,
+    pub month: u32,
+    pub day: u32,
+}
+
+impl CalendarDate {
+    pub fn new(year: i32, month: u32, day: u32) -> Self {
+        CalendarDate { year, month, day }
+    }
+}
+
+/// The months and days of a calendar, and the conversions of its dates from and to
+/// Gregorian dates.
+///
+/// Only `add_months` and `resolve` are provided, built on the other methods, so the
+/// monthly and yearly date math doesn't depend on the lengths of the Gregorian months.
+pub trait CalendarScale {
+    /// Returns the name of the calendar scale, as written in the `RSCALE` key.
+    fn name(&self) -> &'static str;
+
+    /// Returns the number of months of the year.
+    fn months_in_year(&self, year: i32) -> u32;
+
+    /// Returns the number of days of the month, or None when the year doesn't have the month.
+    fn days_in_month(&self, year: i32, month: u32) -> Option<u32>;
+
+    /// Returns the Gregorian date of the date, or None when it doesn't exist in the calendar.
+    fn gregorian_date(&self, date: &CalendarDate) -> Option<NaiveDate>;
+
+    /// Returns the date in the calendar of the Gregorian date.
+    fn calendar_date(&self, date: &NaiveDate) -> Option<CalendarDate>;
+
+    /// Returns the year and the month a number of months away from the month,
+    /// counting the months of every year in between.
+    fn add_months(&self, year: i32, month: u32, months: i64) -> Option<(i32, u32)> {
+        if month < 1 || month > self.months_in_year(year) {
+            return None;
+        }
+        let (mut year, mut month) = (year, i64::from(month) + months);
+        while month > i64::from(self.months_in_year(year)) {
+            month -= i64::from(self.months_in_year(year));
+            year = year.checked_add(1)?;
+        }
+        while month < 1 {
+            year = year.checked_sub(1)?;
+            month += i64::from(self.months_in_year(year));
+        }
+        Some((year, month as u32))
+    }
+
+    /// Returns the Gregorian date of the day of the month, applying the overflow policy
+    /// when the month doesn't have it, like the 30th of a month of 29 days.
+    fn resolve(
+        &self,
+        year: i32,
+        month: u32,
+        day: i32,
+        overflow: OverflowPolicy,
+    ) -> Option<NaiveDate> {
+        let last_day = self.days_in_month(year, month)?;
+        let date = |day: u32| self.gregorian_date(&CalendarDate::new(year, month, day));
+        match u32::try_from(day) {
+            Ok(0) | Err(_) => None,
+            Ok(day) if day <= last_day => date(day),
+            Ok(_) => match overflow {
+                OverflowPolicy::Skip => None,
+                OverflowPolicy::ClampToLastDay => date(last_day),
+                OverflowPolicy::NextValid => date(last_day)?.succ_opt(),
+            },
+        }
+    }
+}
+
+/// The Gregorian calendar, used by the rules.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Gregorian;
+
+impl CalendarScale for Gregorian {
+    fn name(&self) -> &'static str {
+        "GREGORIAN"
+    }
+
+    fn months_in_year(&self, _year: i32) -> u32 {
+        12
+    }
+
+    fn days_in_month(&self, year: i32, month: u32) -> Option<u32> {
+        (28..=31)
+            .rev()
+            .find(|day| NaiveDate::from_ymd_opt(year, month, *day).is_some())
+    }
+
+    fn gregorian_date(&self, date: &CalendarDate) -> Option<NaiveDate> {
+        NaiveDate::from_ymd_opt(date.year, date.month, date.day)
+    }
+
+    fn calendar_date(&self, date: &NaiveDate) -> Option<CalendarDate> {
+        Some(CalendarDate::new(date.year(), date.month(), date.day()))
+    }
+
+    fn add_months(&self, year: i32, month: u32, months: i64) -> Option<(i32, u32)> {
+        if !(1..=12).contains(&month) {
+            return None;
+        }
+        let total_months = i64::from(year) * 12 + i64::from(month - 1) + months;
+        let year = i32::try_from(total_months.div_euclid(12)).ok()?;
+        Some((year, total_months.rem_euclid(12) as u32 + 1))
+    }
+}
+
+/// Returns the Gregorian date of a day number, counted from 1 on the 1st of January of year 1.
+#[cfg(feature = "calendars")]
+fn from_day_number(day: i64) -> Option<NaiveDate> {
+    NaiveDate::from_num_days_from_ce_opt(i32::try_from(day).ok()?)
+}
+
+/// The arithmetic Hebrew calendar.
+///
+/// The months are counted from Tishri, the first month of the civil year, so leap years
+/// have Adar I as their 6th month and Adar II as their 7th, and end with Elul as their
+/// 13th month.
+/// ```
+/// use chrono::NaiveDate;
+/// use rrules::calendars::{CalendarDate, CalendarScale, Hebrew};
+///
+/// // Yom Kippur, the 10th of Tishri
+/// let yom_kippur = Hebrew.gregorian_date(&CalendarDate::new(5784, 1, 10));
+/// assert_eq!(yom_kippur, NaiveDate::from_ymd_opt(2023, 9, 25));
+/// assert_eq!(Hebrew.months_in_year(5784), 13);
+/// ```
+#[cfg(feature = "calendars")]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Hebrew;
+
+/// Day number of the 1st of Tishri of the year 1.
+#[cfg(feature = "calendars")]
+const HEBREW_EPOCH: i64 = -1373427;
+
+#[cfg(feature = "calendars")]
+impl Hebrew {
+    fn is_leap_year(year: i64) -> bool {
+        (7 * year + 1).rem_euclid(19) < 7
+    }
+
+    /// Returns the days from the epoch to the molad of Tishri of the year,
+    /// postponed when it falls on a Sunday, a Wednesday or a Friday.
+    fn elapsed_days(year: i64) -> i64 {
+        let months_elapsed = (235 * year - 234).div_euclid(19);
+        let parts_elapsed = 12084 + 13753 * months_elapsed;
+        let days = 29 * months_elapsed + parts_elapsed.div_euclid(25920);
+        if (3 * (days + 1)).rem_euclid(7) < 3 {
+            days + 1
+        } else {
+            days
+        }
+    }
+
+    /// Returns the day number of the 1st of Tishri of the year, postponed to keep the
+    /// lengths of the years within the allowed ones.
+    fn new_year(year: i64) -> i64 {
+        let (previous, current, next) = (
+            Hebrew::elapsed_days(year - 1),
+            Hebrew::elapsed_days(year),
+            Hebrew::elapsed_days(year + 1),
+        );
+        let delay = if next - current == 356 {
+            2
+        } else if current - previous == 382 {
+            1
+        } else {
+            0
+        };
+        HEBREW_EPOCH + current + delay
+    }
+
+    fn month_length(year: i64, month: u32) -> Option<u32> {
+        let leap = Hebrew::is_leap_year(year);
+        if month < 1 || month > if leap { 13 } else { 12 } {
+            return None;
+        }
+        // Heshvan and Kislev have 29 or 30 days, depending on the length of the year
+        let year_length = Hebrew::new_year(year + 1) - Hebrew::new_year(year);
+        let length = match (month, leap) {
+            (6, true) => 30,
+            (month, true) if month > 6 => 29 + (month - 1) % 2,
+            (2, _) => 29 + u32::from(year_length % 10 == 5),
+            (3, _) => 30 - u32::from(year_length % 10 == 3),
+            (month, _) => 29 + month % 2,
+        };
+        Some(length)
+    }
+}
+
+#[cfg(feature = "calendars")]
+impl CalendarScale for Hebrew {
+    fn name(&self) -> &'static str {
+        "HEBREW"
+    }
+
+    fn months_in_year(&self, year: i32) -> u32 {
+        if Hebrew::is_leap_year(i64::from(year)) {
+            13
+        } else {
+            12
+        }
+    }
+
+    fn days_in_month(&self, year: i32, month: u32) -> Option<u32> {
+        Hebrew::month_length(i64::from(year), month)
+    }
+
+    fn gregorian_date(&self, date: &CalendarDate) -> Option<NaiveDate> {
+        let year = i64::from(date.year);
+        if date.day < 1 || date.day > Hebrew::month_length(year, date.month)? {
+            return None;
+        }
+        let month_days: u32 = (1..date.month)
+            .filter_map(|month| Hebrew::month_length(year, month))
+            .sum();
+        from_day_number(Hebrew::new_year(year) + i64::from(month_days + date.day) - 1)
+    }
+
+    fn calendar_date(&self, date: &NaiveDate) -> Option<CalendarDate> {
+        let day_number = i64::from(date.num_days_from_ce());
+        // Start from an approximation of the year, based on the mean length of the years
+        let mut year = (day_number - HEBREW_EPOCH) * 98496 / 35975351 + 1;
+        while Hebrew::new_year(year) > day_number {
+            year -= 1;
+        }
+        while Hebrew::new_year(year + 1) <= day_number {
+            year += 1;
+        }
+        let mut day = u32::try_from(day_number - Hebrew::new_year(year)).ok()? + 1;
+        let mut month = 1;
+        while day > Hebrew::month_length(year, month)? {
+            day -= Hebrew::month_length(year, month)?;
+            month += 1;
+        }
+        Some(CalendarDate::new(i32::try_from(year).ok()?, month, day))
+    }
+}
+
+/// The arithmetic Islamic calendar, with the civil epoch of the 16th of July of 622.
+///
+/// The months alternate between 30 and 29 days, and the last month has 30 days in the
+/// 11 leap years of every 30 years. The dates can be a day or two away from the ones
+/// based on the observation of the moon.
+/// ```
+/// use chrono::NaiveDate;
+/// use rrules::calendars::{CalendarDate, CalendarScale, IslamicCivil};
+///
+/// // The 1st of Ramadan
+/// let ramadan = IslamicCivil.gregorian_date(&CalendarDate::new(1444, 9, 1));
+/// assert_eq!(ramadan, NaiveDate::from_ymd_opt(2023, 3, 23));
+/// ```
+#[cfg(feature = "calendars")]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct IslamicCivil;
+
+/// Day number of the 1st of Muharram of the year 1, the 16th of July of 622 in the Julian calendar.
+#[cfg(feature = "calendars")]
+const ISLAMIC_EPOCH: i64 = 227015;
+
+#[cfg(feature = "calendars")]
+impl IslamicCivil {
+    fn is_leap_year(year: i64) -> bool {
+        (14 + 11 * year).rem_euclid(30) < 11
+    }
+
+    /// Returns the day number of the date, which must exist.
+    fn day_number(year: i64, month: u32, day: u32) -> i64 {
+        let month = i64::from(month);
+        ISLAMIC_EPOCH - 1
+            + (year - 1) * 354
+            + (3 + 11 * year).div_euclid(30)
+            + 29 * (month - 1)
+            + month / 2
+            + i64::from(day)
+    }
+}
+
+#[cfg(feature = "calendars")]
+impl CalendarScale for IslamicCivil {
+    fn name(&self) -> &'static str {
+        "ISLAMIC-CIVIL"
+    }
+
+    fn months_in_year(&self, _year: i32) -> u32 {
+        12
+    }
+
+    fn days_in_month(&self, year: i32, month: u32) -> Option<u32> {
+        match month {
+            12 if IslamicCivil::is_leap_year(i64::from(year)) => Some(30),
+            1..=12 => Some(29 + month % 2),
+            _ => None,
+        }
+    }
+
+    fn gregorian_date(&self, date: &CalendarDate) -> Option<NaiveDate> {
+        if date.day < 1 || date.day > self.days_in_month(date.year, date.month)? {
+            return None;
+        }
+        from_day_number(IslamicCivil::day_number(
+            i64::from(date.year),
+            date.month,
+            date.day,
+        ))
+    }
+
+    fn calendar_date(&self, date: &NaiveDate) -> Option<CalendarDate> {
+        let day_number = i64::from(date.num_days_from_ce());
+        let year = (30 * (day_number - ISLAMIC_EPOCH) + 10646).div_euclid(10631);
+        let month = (1..=12)
+            .rev()
+            .find(|month| IslamicCivil::day_number(year, *month, 1) <= day_number)?;
+        let day = day_number - IslamicCivil::day_number(year, month, 1) + 1;
+        Some(CalendarDate::new(
+            i32::try_from(year).ok()?,
+            month,
+            u32::try_from(day).ok()?,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod test_calendars {
+    use crate::calendars::{CalendarDate, CalendarScale, Gregorian};
+    use crate::OverflowPolicy;
+    use chrono::NaiveDate;
+
+    fn date(year: i32, month: u32, day: u32) -> Option<NaiveDate> {
+        NaiveDate::from_ymd_opt(year, month, day)
+    }
+
+    #[test]
+    fn gregorian_months() {
+        assert_eq!(Gregorian.days_in_month(2023, 2), Some(28));
+        assert_eq!(Gregorian.days_in_month(2024, 2), Some(29));
+        assert_eq!(Gregorian.days_in_month(2023, 4), Some(30));
+        assert_eq!(Gregorian.days_in_month(2023, 13), None);
+        assert_eq!(Gregorian.add_months(2023, 1, -1), Some((2022, 12)));
+        assert_eq!(Gregorian.add_months(2023, 1, 25), Some((2025, 2)));
+        assert_eq!(Gregorian.add_months(2023, 0, 1), None);
+        assert_eq!(Gregorian.add_months(i32::MAX, 12, 1), None);
+    }
+
+    #[test]
+    fn resolves_the_missing_days_with_the_overflow_policy() {
+        assert_eq!(Gregorian.resolve(2023, 4, 31, OverflowPolicy::Skip), None);
+        assert_eq!(
+            Gregorian.resolve(2023, 4, 31, OverflowPolicy::ClampToLastDay),
+            date(2023, 4, 30)
+        );
+        assert_eq!(
+            Gregorian.resolve(2023, 12, 32, OverflowPolicy::NextValid),
+            date(2024, 1, 1)
+        );
+        assert_eq!(
+            Gregorian.resolve(2023, 4, 0, OverflowPolicy::NextValid),
+            None
+        );
+        assert_eq!(
+            Gregorian.resolve(2023, 4, 30, OverflowPolicy::Skip),
+            date(2023, 4, 30)
+        );
+    }
+
+    #[test]
+    fn the_default_month_arithmetic_counts_the_months_of_every_year() {
+        /// A calendar like the Gregorian one, relying on the provided methods
+        struct Plain;
+
+        impl CalendarScale for Plain {
+            fn name(&self) -> &'static str {
+                "PLAIN"
+            }
+            fn months_in_year(&self, year: i32) -> u32 {
+                Gregorian.months_in_year(year)
+            }
+            fn days_in_month(&self, year: i32, month: u32) -> Option<u32> {
+                Gregorian.days_in_month(year, month)
+            }
+            fn gregorian_date(&self, date: &CalendarDate) -> Option<NaiveDate> {
+                Gregorian.gregorian_date(date)
+            }
+            fn calendar_date(&self, date: &NaiveDate) -> Option<CalendarDate> {
+                Gregorian.calendar_date(date)
+            }
+        }
+
+        for months in -30..=30 {
+            assert_eq!(
+                Plain.add_months(2023, 5, months),
+                Gregorian.add_months(2023, 5, months)
+            );
+        }
+        assert_eq!(Plain.add_months(2023, 13, 1), None);
+    }
+
+    #[cfg(feature = "calendars")]
+    #[test]
+    fn hebrew_dates() {
+        use crate::calendars::Hebrew;
+
+        let hebrew = |year, month, day| Hebrew.gregorian_date(&CalendarDate::new(year, month, day));
+        // Rosh Hashanah, Hanukkah, Purim in Adar II, and Passover of a leap year
+        assert_eq!(hebrew(5784, 1, 1), date(2023, 9, 16));
+        assert_eq!(hebrew(5784, 3, 25), date(2023, 12, 8));
+        assert_eq!(hebrew(5784, 7, 14), date(2024, 3, 24));
+        assert_eq!(hebrew(5784, 8, 15), date(2024, 4, 23));
+        assert_eq!(hebrew(5785, 1, 1), date(2024, 10, 3));
+        // Purim in Adar and Passover of a common year
+        assert_eq!(hebrew(5783, 6, 14), date(2023, 3, 7));
+        assert_eq!(hebrew(5783, 7, 15), date(2023, 4, 6));
+        assert_eq!(hebrew(5783, 13, 1), None);
+        assert_eq!(hebrew(5783, 6, 30), None);
+
+        assert_eq!(Hebrew.months_in_year(5783), 12);
+        assert_eq!(Hebrew.months_in_year(5784), 13);
+        let days_in_year: u32 = (1..=13)
+            .filter_map(|month| Hebrew.days_in_month(5784, month))
+            .sum();
+        assert_eq!(days_in_year, 383);
+        assert_eq!(Hebrew.add_months(5783, 12, 1), Some((5784, 1)));
+        assert_eq!(Hebrew.add_months(5784, 1, 13), Some((5785, 1)));
+        assert_eq!(
+            Hebrew.resolve(5784, 2, 30, OverflowPolicy::NextValid),
+            hebrew(5784, 3, 1)
+        );
+    }
+
+    #[cfg(feature = "calendars")]
+    #[test]
+    fn islamic_civil_dates() {
+        use crate::calendars::IslamicCivil;
+
+        let islamic =
+            |year, month, day| IslamicCivil.gregorian_date(&CalendarDate::new(year, month, day));
+        assert_eq!(islamic(1, 1, 1), date(622, 7, 19));
+        assert_eq!(islamic(1445, 1, 1), date(2023, 7, 19));
+        assert_eq!(islamic(1445, 10, 1), date(2024, 4, 10));
+        assert_eq!(islamic(1445, 2, 30), None);
+        assert_eq!(IslamicCivil.days_in_month(1445, 12), Some(30));
+        assert_eq!(IslamicCivil.days_in_month(1444, 12), Some(29));
+    }
+
+    #[cfg(feature = "calendars")]
+    #[test]
+    fn calendar_dates_round_trip() {
+        use crate::calendars::{Hebrew, IslamicCivil};
+
+        let scales: [&dyn CalendarScale; 3] = [&Gregorian, &Hebrew, &IslamicCivil];
+        let mut day = date(1990, 1, 1).unwrap();
+        while day < date(2050, 1, 1).unwrap() {
+            for scale in scales {
+                let calendar_date = scale.calendar_date(&day).unwrap();
+                assert_eq!(
+                    scale.gregorian_date(&calendar_date),
+                    Some(day),
+                    "{} {calendar_date:?}",
+                    scale.name()
+                );
+            }
+            day = day.succ_opt().unwrap();
+        }
+    }
+}
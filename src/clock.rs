@@ -0,0 +1,128 @@
+//! Sources of the current time, for the methods that depend on it.
+//!
+//! `SystemClock` reads the time of the system, and is used by methods like
+//! `Recurrence::next_from_now`. `MockClock` returns a time that only changes when it is set
+//! or advanced, so the code computing the next events from the current time, like the
+//! scheduling logic of an application, can be tested deterministically. Clones of a
+//! `MockClock` share the same time.
+//!
+//! Examples:
+//! ```
+//! use std::str::FromStr;
+//! use chrono::{DateTime, Duration, Utc};
+//! use rrules::clock::{Clock, MockClock};
+//! use rrules::Recurrence;
+//!
+//! let clock = MockClock::new(DateTime::<Utc>::from_str("2023-01-01T10:30:00Z").unwrap());
+//! let hourly = Recurrence::from_str("FREQ=HOURLY;INTERVAL=1;DTSTART=2023-01-01T00:00:00Z").unwrap();
+//! assert_eq!(
+//!     hourly.next_from_now_with(&clock),
+//!     Some(DateTime::<Utc>::from_str("2023-01-01T11:00:00Z").unwrap())
+//! );
+//!
+//! clock.advance(Duration::hours(2));
+//! assert_eq!(clock.now(), DateTime::<Utc>::from_str("2023-01-01T12:30:00Z").unwrap());
+//! ```
+
+#[cfg(feature = "std")]
+use chrono::Duration;
+use chrono::{DateTime, Utc};
+#[cfg(feature = "std")]
+use std::sync::{Arc, Mutex, MutexGuard};
+
+/// A source of the current time.
+pub trait Clock {
+    /// Returns the current time.
+    fn now(&self) -> DateTime<Utc>;
+}
+
+impl<C: Clock + ?Sized> Clock for &C {
+    fn now(&self) -> DateTime<Utc> {
+        (**self).now()
+    }
+}
+
+/// The clock of the system, the default of the methods that depend on the current time.
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SystemClock;
+
+#[cfg(feature = "std")]
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+/// A clock whose time is set by hand, for tests.
+///
+/// Clones share the same time, so a clone can be given to the tested code and the time
+/// changed from the test.
+#[cfg(feature = "std")]
+#[derive(Debug, Clone)]
+pub struct MockClock {
+    now: Arc<Mutex<DateTime<Utc>>>,
+}
+
+#[cfg(feature = "std")]
+impl MockClock {
+    /// Creates a clock stopped at the given time.
+    pub fn new(now: DateTime<Utc>) -> Self {
+        MockClock {
+            now: Arc::new(Mutex::new(now)),
+        }
+    }
+
+    /// Sets the time of the clock.
+    pub fn set(&self, now: DateTime<Utc>) {
+        *self.lock() = now;
+    }
+
+    /// Moves the time of the clock by the given duration, or leaves it unchanged when the
+    /// result is out of range.
+    pub fn advance(&self, duration: Duration) {
+        let mut now = self.lock();
+        *now = now.checked_add_signed(duration).unwrap_or(*now);
+    }
+
+    fn lock(&self) -> MutexGuard<'_, DateTime<Utc>> {
+        // The time is always valid, even if a thread panicked while holding the lock
+        self.now.lock().unwrap_or_else(|error| error.into_inner())
+    }
+}
+
+#[cfg(feature = "std")]
+impl Clock for MockClock {
+    fn now(&self) -> DateTime<Utc> {
+        *self.lock()
+    }
+}
+
+#[cfg(test)]
+mod test_clock {
+    use crate::clock::{Clock, MockClock, SystemClock};
+    use crate::test_utils::date;
+    use chrono::{DateTime, Duration, Utc};
+
+    #[test]
+    fn mock_clocks_share_their_time() {
+        let clock = MockClock::new(date("2023-01-01T00:00:00Z"));
+        let shared = clock.clone();
+        clock.advance(Duration::minutes(90));
+        assert_eq!(shared.now(), date("2023-01-01T01:30:00Z"));
+        shared.set(date("2024-02-29T12:00:00Z"));
+        assert_eq!(clock.now(), date("2024-02-29T12:00:00Z"));
+
+        clock.set(DateTime::<Utc>::MAX_UTC);
+        clock.advance(Duration::seconds(1));
+        assert_eq!(clock.now(), DateTime::<Utc>::MAX_UTC);
+    }
+
+    #[test]
+    fn system_clock_reads_the_current_time() {
+        let before = Utc::now();
+        let clock: &dyn Clock = &SystemClock;
+        let now = clock.now();
+        assert!(before <= now && now <= Utc::now());
+    }
+}
@@ -0,0 +1,120 @@
+//! Conversions between the chrono weekdays and months and the codes used by the rules.
+//!
+//! Weekdays use the two-letter codes of RFC 5545 (E.g. `MO`), and months use their number
+//! or the three-letter codes of the `X-BYMONTHDATE` extension (E.g. `JAN`). Codes are
+//! upper case.
+//!
+//! Examples:
+//! ```
+//! use chrono::{Month, Weekday};
+//! use rrules::codes::{month_code, month_from_code, weekday_code, weekday_from_code};
+//!
+//! assert_eq!(weekday_code(Weekday::Mon), "MO");
+//! assert_eq!(weekday_from_code("FR"), Some(Weekday::Fri));
+//! assert_eq!(month_code(Month::January), "JAN");
+//! assert_eq!(month_from_code("DEC"), Some(Month::December));
+//! ```
+
+use chrono::{Month, Weekday};
+
+/// Returns the two-letter code of the weekday, E.g. `MO` for Monday.
+pub fn weekday_code(weekday: Weekday) -> &'static str {
+    match weekday {
+        Weekday::Mon => "MO",
+        Weekday::Tue => "TU",
+        Weekday::Wed => "WE",
+        Weekday::Thu => "TH",
+        Weekday::Fri => "FR",
+        Weekday::Sat => "SA",
+        Weekday::Sun => "SU",
+    }
+}
+
+/// Returns the weekday of a two-letter code, or None when the code is unknown.
+pub fn weekday_from_code(code: &str) -> Option<Weekday> {
+    match code {
+        "MO" => Some(Weekday::Mon),
+        "TU" => Some(Weekday::Tue),
+        "WE" => Some(Weekday::Wed),
+        "TH" => Some(Weekday::Thu),
+        "FR" => Some(Weekday::Fri),
+        "SA" => Some(Weekday::Sat),
+        "SU" => Some(Weekday::Sun),
+        _ => None,
+    }
+}
+
+/// Returns the three-letter code of the month, E.g. `JAN` for January.
+pub fn month_code(month: Month) -> &'static str {
+    match month {
+        Month::January => "JAN",
+        Month::February => "FEB",
+        Month::March => "MAR",
+        Month::April => "APR",
+        Month::May => "MAY",
+        Month::June => "JUN",
+        Month::July => "JUL",
+        Month::August => "AUG",
+        Month::September => "SEP",
+        Month::October => "OCT",
+        Month::November => "NOV",
+        Month::December => "DEC",
+    }
+}
+
+/// Returns the month of a three-letter code, or None when the code is unknown.
+pub fn month_from_code(code: &str) -> Option<Month> {
+    match code {
+        "JAN" => Some(Month::January),
+        "FEB" => Some(Month::February),
+        "MAR" => Some(Month::March),
+        "APR" => Some(Month::April),
+        "MAY" => Some(Month::May),
+        "JUN" => Some(Month::June),
+        "JUL" => Some(Month::July),
+        "AUG" => Some(Month::August),
+        "SEP" => Some(Month::September),
+        "OCT" => Some(Month::October),
+        "NOV" => Some(Month::November),
+        "DEC" => Some(Month::December),
+        _ => None,
+    }
+}
+
+/// Returns the month of its number, from 1 for January to 12 for December.
+pub fn month_from_number(number: i32) -> Option<Month> {
+    u8::try_from(number)
+        .ok()
+        .and_then(|number| Month::try_from(number).ok())
+}
+
+#[cfg(test)]
+mod test_codes {
+    use crate::codes::{
+        month_code, month_from_code, month_from_number, weekday_code, weekday_from_code,
+    };
+    use chrono::Weekday;
+
+    #[test]
+    fn weekday_codes_round_trip() {
+        for number in 0..7 {
+            let weekday = Weekday::try_from(number).unwrap();
+            assert_eq!(weekday_from_code(weekday_code(weekday)), Some(weekday));
+        }
+        assert_eq!(weekday_from_code("mo"), None);
+        assert_eq!(weekday_from_code("MON"), None);
+    }
+
+    #[test]
+    fn month_codes_round_trip() {
+        for number in 1..=12 {
+            let month = month_from_number(number).unwrap();
+            assert_eq!(month.number_from_month(), number as u32);
+            assert_eq!(month_from_code(month_code(month)), Some(month));
+        }
+        assert_eq!(month_from_code("January"), None);
+        assert_eq!(month_from_number(0), None);
+        assert_eq!(month_from_number(13), None);
+        assert_eq!(month_from_number(-1), None);
+    }
+}
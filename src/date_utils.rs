@@ -0,0 +1,339 @@
+//! Date arithmetic used by the frequencies, for UTC dates and calendar dates.
+//!
+//! `DateUtils` moves dates by days, weeks, months and years, and to the weekdays around
+//! them. `weekday_ordinal` and `weekday_ordinal_from_end` tell which occurrence of its
+//! weekday in the month a date is, like the 2nd or the last Monday. Instead of panicking,
+//! the methods return None when the result is out of the range of chrono, or when the day
+//! doesn't exist in the resulting month, like February 29th of a common year, unless an
+//! `OverflowPolicy` clamps it to the last day of the month or moves it to the next day.
+//!
+//! Examples:
+//! ```
+//! use chrono::{NaiveDate, Weekday};
+//! use rrules::date_utils::{weekday_ordinal, weekday_ordinal_from_end, DateUtils};
+//! use rrules::OverflowPolicy;
+//!
+//! let date = NaiveDate::from_ymd_opt(2024, 1, 31).unwrap();
+//! assert_eq!(date.shift_months(2), NaiveDate::from_ymd_opt(2024, 3, 31));
+//! assert_eq!(date.shift_months(1), None);
+//! assert_eq!(
+//!     date.shift_months_with(1, OverflowPolicy::ClampToLastDay),
+//!     NaiveDate::from_ymd_opt(2024, 2, 29)
+//! );
+//! assert_eq!(date.next_weekday(Weekday::Mon), NaiveDate::from_ymd_opt(2024, 2, 5));
+//! assert_eq!(weekday_ordinal(&date), 5);
+//! assert_eq!(weekday_ordinal_from_end(&date), -1);
+//! ```
+
+use crate::frequencies::OverflowPolicy;
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveDateTime, TimeZone, Utc, Weekday};
+
+/// Calendar arithmetic on dates, keeping the time of the dates that have one.
+///
+/// Only `shift_days` and `shift_months_with` are implemented by every type, the other
+/// methods are built on them.
+pub trait DateUtils: Datelike + Sized {
+    /// Returns the date the given number of days later, or earlier when negative.
+    fn shift_days(self, days: i64) -> Option<Self>;
+
+    /// Returns the date the given number of months later, or earlier when negative, on the
+    /// same day of the month, applying the overflow policy when the resulting month doesn't
+    /// have the day, E.g. one month after January 31st.
+    ///
+    /// Returns None when the policy skips the month, or when its year is out of range.
+    fn shift_months_with(self, months: i64, overflow: OverflowPolicy) -> Option<Self>;
+
+    /// Returns the date the given number of months later, or earlier when negative, on the
+    /// same day of the month.
+    ///
+    /// Returns None when the resulting month doesn't have the day, the same as
+    /// `shift_months_with` and the `Skip` policy.
+    fn shift_months(self, months: i64) -> Option<Self> {
+        self.shift_months_with(months, OverflowPolicy::Skip)
+    }
+
+    /// Returns the date the given number of weeks later, or earlier when negative.
+    fn shift_weeks(self, weeks: i64) -> Option<Self> {
+        self.shift_days(weeks.checked_mul(7)?)
+    }
+
+    /// Returns the date the given number of years later, or earlier when negative, on the
+    /// same month and day.
+    ///
+    /// Returns None from February 29th to a common year, or when the year is out of range.
+    fn shift_years(self, years: i64) -> Option<Self> {
+        self.shift_months(years.checked_mul(12)?)
+    }
+
+    /// Returns the date on the weekday of the same week, with weeks starting on Monday.
+    ///
+    /// The date moves backwards to the earlier days of the week and forwards to the later
+    /// ones, E.g. from a Wednesday, the Monday is 2 days earlier and the Sunday 4 days later.
+    fn with_weekday(self, weekday: Weekday) -> Option<Self> {
+        let days = i64::from(weekday.num_days_from_monday())
+            - i64::from(self.weekday().num_days_from_monday());
+        self.shift_days(days)
+    }
+
+    /// Returns the first date on the weekday after the date, from 1 to 7 days later.
+    fn next_weekday(self, weekday: Weekday) -> Option<Self> {
+        let days = weekday.days_since(self.weekday());
+        self.shift_days(if days == 0 { 7 } else { i64::from(days) })
+    }
+
+    /// Returns the last date on the weekday before the date, from 1 to 7 days earlier.
+    fn previous_weekday(self, weekday: Weekday) -> Option<Self> {
+        let days = self.weekday().days_since(weekday);
+        self.shift_days(if days == 0 { -7 } else { -i64::from(days) })
+    }
+}
+
+impl DateUtils for NaiveDate {
+    fn shift_days(self, days: i64) -> Option<Self> {
+        self.checked_add_signed(Duration::try_days(days)?)
+    }
+
+    fn shift_months_with(self, months: i64, overflow: OverflowPolicy) -> Option<Self> {
+        let total_months = i64::from(self.year()) * 12 + i64::from(self.month0());
+        let total_months = total_months.checked_add(months)?;
+        let year = i32::try_from(total_months.div_euclid(12)).ok()?;
+        let month = total_months.rem_euclid(12) as u32 + 1;
+        overflow.resolve(year, month, self.day() as i32)
+    }
+}
+
+impl DateUtils for NaiveDateTime {
+    fn shift_days(self, days: i64) -> Option<Self> {
+        self.checked_add_signed(Duration::try_days(days)?)
+    }
+
+    fn shift_months_with(self, months: i64, overflow: OverflowPolicy) -> Option<Self> {
+        let date = self.date().shift_months_with(months, overflow)?;
+        Some(date.and_time(self.time()))
+    }
+}
+
+impl DateUtils for DateTime<Utc> {
+    fn shift_days(self, days: i64) -> Option<Self> {
+        self.checked_add_signed(Duration::try_days(days)?)
+    }
+
+    fn shift_months_with(self, months: i64, overflow: OverflowPolicy) -> Option<Self> {
+        let date = self.naive_utc().shift_months_with(months, overflow)?;
+        Some(Utc.from_utc_datetime(&date))
+    }
+}
+
+/// Returns the occurrence of the weekday of the date in its month, from 1 to 5.
+///
+/// E.g. 2023-01-09 is the 2nd Monday of January 2023.
+pub fn weekday_ordinal(date: &impl Datelike) -> i32 {
+    ((date.day() - 1) / 7 + 1) as i32
+}
+
+/// Returns the occurrence of the weekday of the date in its month counted from the end of
+/// the month, from -1 for the last one to -5.
+///
+/// E.g. 2024-02-26 is the last Monday of February 2024, and 2024-02-01 the 5th Thursday
+/// from the end, as the leap year has 29 days in February.
+pub fn weekday_ordinal_from_end(date: &impl Datelike) -> i32 {
+    -(((u32::from(date.num_days_in_month()) - date.day()) / 7 + 1) as i32)
+}
+
+#[cfg(test)]
+mod test_date_utils {
+    use super::*;
+    use crate::test_utils::date;
+
+    fn day(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    #[test]
+    fn test_shift_month() {
+        let result = date("2019-01-01T00:00:00Z").shift_months(1).unwrap();
+        assert_eq!(result.month(), 2);
+        let result = date("2019-01-01T00:00:00Z").shift_months(2).unwrap();
+        assert_eq!(result.month(), 3);
+    }
+
+    #[test]
+    fn test_shift_month_to_next_year() {
+        let result = date("2019-01-01T00:00:00Z").shift_months(14).unwrap();
+        assert_eq!(result, date("2020-03-01T00:00:00Z"));
+    }
+
+    #[test]
+    fn test_shift_backwards() {
+        let result = date("2019-05-01T00:00:00Z").shift_months(-1).unwrap();
+        assert_eq!(result.month(), 4);
+        let result = date("2019-01-01T00:00:00Z").shift_months(-1).unwrap();
+        assert_eq!(result, date("2018-12-01T00:00:00Z"));
+        let result = date("2019-01-01T00:00:00Z").shift_months(-14).unwrap();
+        assert_eq!(result, date("2017-11-01T00:00:00Z"));
+    }
+
+    #[test]
+    fn test_shift_month_to_december() {
+        let result = date("2019-11-15T10:00:00Z").shift_months(1).unwrap();
+        assert_eq!(result, date("2019-12-15T10:00:00Z"));
+        let result = result.shift_months(12).unwrap();
+        assert_eq!(result, date("2020-12-15T10:00:00Z"));
+    }
+
+    #[test]
+    fn test_shift_month_to_missing_day() {
+        let date = date("2019-01-31T00:00:00Z");
+        assert!(date.shift_months(1).is_none());
+        assert_eq!(date.shift_months(2).unwrap().day(), 31);
+    }
+
+    #[test]
+    fn test_shift_month_with_an_overflow_policy() {
+        let date = date("2023-01-31T10:00:00Z");
+        assert_eq!(date.shift_months_with(1, OverflowPolicy::Skip), None);
+        assert_eq!(
+            date.shift_months_with(1, OverflowPolicy::ClampToLastDay),
+            Some(self::date("2023-02-28T10:00:00Z"))
+        );
+        assert_eq!(
+            date.shift_months_with(1, OverflowPolicy::NextValid),
+            Some(self::date("2023-03-01T10:00:00Z"))
+        );
+        assert_eq!(
+            date.shift_months_with(13, OverflowPolicy::ClampToLastDay),
+            Some(self::date("2024-02-29T10:00:00Z"))
+        );
+        assert_eq!(
+            date.shift_months_with(-2, OverflowPolicy::NextValid),
+            Some(self::date("2022-12-01T10:00:00Z"))
+        );
+        // Months with the day are the same for every policy
+        for overflow in [
+            OverflowPolicy::Skip,
+            OverflowPolicy::ClampToLastDay,
+            OverflowPolicy::NextValid,
+        ] {
+            assert_eq!(
+                date.shift_months_with(2, overflow),
+                Some(self::date("2023-03-31T10:00:00Z"))
+            );
+        }
+        let leap_day = day(2024, 2, 29);
+        assert_eq!(
+            leap_day.shift_years(1),
+            leap_day.shift_months_with(12, OverflowPolicy::Skip)
+        );
+        assert_eq!(
+            leap_day.shift_months_with(12, OverflowPolicy::ClampToLastDay),
+            Some(day(2025, 2, 28))
+        );
+        assert_eq!(
+            day(2023, 1, 1).shift_months_with(i64::MAX, OverflowPolicy::ClampToLastDay),
+            None
+        );
+    }
+
+    #[test]
+    fn test_shift_out_of_range() {
+        assert_eq!(day(2023, 1, 1).shift_months(i64::MAX), None);
+        assert_eq!(day(2023, 1, 1).shift_months(i64::MIN), None);
+        assert_eq!(day(2023, 1, 1).shift_years(i64::MAX / 2), None);
+        assert_eq!(day(2023, 1, 1).shift_weeks(i64::MAX), None);
+        assert_eq!(NaiveDate::MAX.shift_days(1), None);
+        assert_eq!(DateTime::<Utc>::MIN_UTC.shift_months(-1), None);
+    }
+
+    #[test]
+    fn test_shift_years_from_a_leap_day() {
+        let leap_day = day(2024, 2, 29);
+        assert_eq!(leap_day.shift_years(1), None);
+        assert_eq!(leap_day.shift_years(4), Some(day(2028, 2, 29)));
+        assert_eq!(leap_day.shift_years(-4), Some(day(2020, 2, 29)));
+        // 2100 is not a leap year
+        assert_eq!(day(2096, 2, 29).shift_years(4), None);
+        assert_eq!(day(1996, 2, 29).shift_years(4), Some(day(2000, 2, 29)));
+    }
+
+    #[test]
+    fn test_shift_weeks() {
+        let date = date("2019-01-01T10:30:00Z");
+        assert_eq!(
+            date.shift_weeks(1).unwrap(),
+            self::date("2019-01-08T10:30:00Z")
+        );
+        assert_eq!(date.shift_weeks(2).unwrap().day(), 15);
+        assert_eq!(
+            date.shift_weeks(-1).unwrap(),
+            self::date("2018-12-25T10:30:00Z")
+        );
+        // Across the leap day
+        assert_eq!(day(2024, 2, 26).shift_weeks(1), Some(day(2024, 3, 4)));
+    }
+
+    #[test]
+    fn test_naive_date_times_keep_their_time() {
+        let date = day(2023, 3, 31).and_hms_opt(9, 15, 0).unwrap();
+        assert_eq!(date.shift_months(-1), None);
+        assert_eq!(date.shift_months(1), None);
+        assert_eq!(date.shift_months(2), day(2023, 5, 31).and_hms_opt(9, 15, 0));
+        assert_eq!(date.shift_weeks(-1), day(2023, 3, 24).and_hms_opt(9, 15, 0));
+    }
+
+    #[test]
+    fn test_with_weekday() {
+        let date = date("2019-01-01T00:00:00Z");
+        assert_eq!(date.weekday(), Weekday::Tue);
+        assert_eq!(
+            date.with_weekday(Weekday::Mon).unwrap(),
+            self::date("2018-12-31T00:00:00Z")
+        );
+        assert_eq!(
+            date.with_weekday(Weekday::Sun).unwrap(),
+            self::date("2019-01-06T00:00:00Z")
+        );
+        assert_eq!(date.with_weekday(Weekday::Tue).unwrap(), date);
+    }
+
+    #[test]
+    fn test_next_and_previous_weekdays() {
+        let wednesday = day(2023, 3, 1);
+        assert_eq!(wednesday.next_weekday(Weekday::Thu), Some(day(2023, 3, 2)));
+        assert_eq!(wednesday.next_weekday(Weekday::Wed), Some(day(2023, 3, 8)));
+        assert_eq!(wednesday.next_weekday(Weekday::Tue), Some(day(2023, 3, 7)));
+        assert_eq!(
+            wednesday.previous_weekday(Weekday::Tue),
+            Some(day(2023, 2, 28))
+        );
+        assert_eq!(
+            wednesday.previous_weekday(Weekday::Wed),
+            Some(day(2023, 2, 22))
+        );
+        assert_eq!(
+            wednesday.previous_weekday(Weekday::Thu),
+            Some(day(2023, 2, 23))
+        );
+        assert_eq!(NaiveDate::MAX.next_weekday(Weekday::Mon), None);
+    }
+
+    #[test]
+    fn test_get_weekday_ordinal() {
+        assert_eq!(weekday_ordinal(&date("2023-02-06T00:00:00Z")), 1);
+        assert_eq!(weekday_ordinal(&date("2023-02-28T00:00:00Z")), 4);
+        assert_eq!(weekday_ordinal(&date("2023-01-29T00:00:00Z")), 5);
+        assert_eq!(weekday_ordinal(&date("2023-01-07T00:00:00Z")), 1);
+        assert_eq!(weekday_ordinal(&day(2023, 1, 9)), 2);
+    }
+
+    #[test]
+    fn test_get_weekday_ordinal_from_end() {
+        assert_eq!(weekday_ordinal_from_end(&day(2023, 2, 28)), -1);
+        assert_eq!(weekday_ordinal_from_end(&day(2023, 2, 22)), -1);
+        assert_eq!(weekday_ordinal_from_end(&day(2023, 2, 21)), -2);
+        assert_eq!(weekday_ordinal_from_end(&day(2023, 2, 1)), -4);
+        // February of a leap year has a 5th Thursday
+        assert_eq!(weekday_ordinal_from_end(&day(2024, 2, 1)), -5);
+        assert_eq!(weekday_ordinal_from_end(&day(2024, 2, 26)), -1);
+        assert_eq!(weekday_ordinal_from_end(&date("2023-12-31T10:00:00Z")), -1);
+    }
+}
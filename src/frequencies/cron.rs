@@ -0,0 +1,881 @@
+//! Conversions from and to cron expressions, like `0 9 * * MON-FRI` for 9:00 on weekdays.
+//!
+//! The expressions have five fields: the minutes, the hours, the days of the month, the months
+//! and the weekdays, made of lists, ranges and steps like `1,15`, `MON-FRI` or `*/15`. The
+//! `@yearly`, `@annually`, `@monthly`, `@weekly`, `@daily`, `@midnight` and `@hourly` shortcuts
+//! are also read. Cron has no start date, so only the frequencies with explicit times and days
+//! can be written as an expression. The steps of the minutes and the hours that divide the hour
+//! and the day, like `*/15`, are the intervals of minutely and hourly frequencies, which match
+//! cron when the recurrence starts at midnight.
+
+use crate::frequencies::models::MONTH_LENGTHS;
+use crate::frequencies::{InvalidFrequency, ParseError, ParseErrorKind};
+use crate::{Frequency, MonthlyDate, OverflowPolicy, Time};
+use alloc::collections::BTreeSet;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+use chrono::{Month, Weekday};
+use core::fmt::{Display, Formatter};
+use core::num::NonZeroU32;
+
+/// The error of a frequency that cannot be written as a cron expression, with the parts of the
+/// rule that cron cannot represent.
+///
+/// Examples:
+/// ```
+/// use std::str::FromStr;
+/// use rrules::Frequency;
+///
+/// let frequency = Frequency::from_str("FREQ=MONTHLY;INTERVAL=2;BYDAY=4FR;BYHOUR=9").unwrap();
+/// let error = frequency.to_cron().unwrap_err();
+/// assert_eq!(error.features, vec!["BYDAY", "INTERVAL"]);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CronUnsupported {
+    /// The keys of the parts of the rule, sorted, like `INTERVAL` or `BYWEEKNO`. `DTSTART` means
+    /// that the times or the days of the events are taken from the start of the recurrence.
+    pub features: Vec<&'static str>,
+}
+
+impl Display for CronUnsupported {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "Cannot be written as a cron expression: {}",
+            self.features.join(", ")
+        )
+    }
+}
+
+impl core::error::Error for CronUnsupported {}
+
+/// A field of the cron expressions, with the range of its values and their names, if any.
+struct Field {
+    min: u32,
+    max: u32,
+    names: &'static [&'static str],
+    expected: &'static str,
+}
+
+const MINUTES: Field = Field {
+    min: 0,
+    max: 59,
+    names: &[],
+    expected: "minutes between 0 and 59",
+};
+const HOURS: Field = Field {
+    min: 0,
+    max: 23,
+    names: &[],
+    expected: "hours between 0 and 23",
+};
+const MONTH_DAYS: Field = Field {
+    min: 1,
+    max: 31,
+    names: &[],
+    expected: "days of the month between 1 and 31",
+};
+const MONTHS: Field = Field {
+    min: 1,
+    max: 12,
+    names: &[
+        "JAN", "FEB", "MAR", "APR", "MAY", "JUN", "JUL", "AUG", "SEP", "OCT", "NOV", "DEC",
+    ],
+    expected: "months between 1 and 12 or JAN and DEC",
+};
+// Sunday is both 0 and 7
+const WEEKDAYS: Field = Field {
+    min: 0,
+    max: 7,
+    names: &["SUN", "MON", "TUE", "WED", "THU", "FRI", "SAT"],
+    expected: "weekdays between 0 and 7 or SUN and SAT",
+};
+
+/// The weekdays by their number in cron, from Sunday.
+const CRON_WEEKDAYS: [Weekday; 7] = [
+    Weekday::Sun,
+    Weekday::Mon,
+    Weekday::Tue,
+    Weekday::Wed,
+    Weekday::Thu,
+    Weekday::Fri,
+    Weekday::Sat,
+];
+
+impl Field {
+    /// Parses the comma separated values, ranges and steps of the field at the offset of the
+    /// expression, returning an error pointing at the field when it is not valid.
+    fn parse(&self, offset: usize, value: &str) -> Result<BTreeSet<u32>, ParseError> {
+        self.parse_values(value).ok_or_else(|| ParseError {
+            kind: ParseErrorKind::InvalidValue,
+            span: offset..offset + value.len(),
+            found: value.to_string(),
+            expected: self.expected.to_string(),
+        })
+    }
+
+    fn parse_values(&self, value: &str) -> Option<BTreeSet<u32>> {
+        let mut values = BTreeSet::new();
+        for item in value.split(',') {
+            let (range, step) = match item.split_once('/') {
+                Some((range, step)) => (range, step.parse::<usize>().ok().filter(|s| *s > 0)?),
+                None => (item, 1),
+            };
+            let (first, last) = match range.split_once('-') {
+                _ if range == "*" || range == "?" => (self.min, self.max),
+                Some((first, last)) => (self.value(first)?, self.value(last)?),
+                // A single value with a step repeats until the end of the range
+                None if item.contains('/') => (self.value(range)?, self.max),
+                None => (self.value(range)?, self.value(range)?),
+            };
+            if first > last {
+                return None;
+            }
+            values.extend((first..=last).step_by(step));
+        }
+        Some(values)
+    }
+
+    fn value(&self, value: &str) -> Option<u32> {
+        let number = match self
+            .names
+            .iter()
+            .position(|name| name.eq_ignore_ascii_case(value))
+        {
+            Some(index) => index as u32 + self.min,
+            None if value.bytes().all(|c| c.is_ascii_digit()) => value.parse().ok()?,
+            None => return None,
+        };
+        (self.min..=self.max).contains(&number).then_some(number)
+    }
+
+    /// Returns the step of the values when they are every step of the field from its first value,
+    /// like `*/15`, with a step that divides the range of the field so it repeats evenly.
+    fn step(&self, values: &BTreeSet<u32>) -> Option<NonZeroU32> {
+        let range = self.max - self.min + 1;
+        (1..range)
+            .filter(|step| range.is_multiple_of(*step))
+            .find(|step| {
+                values
+                    .iter()
+                    .copied()
+                    .eq((self.min..=self.max).step_by(*step as usize))
+            })
+            .and_then(NonZeroU32::new)
+    }
+
+    /// Returns every step of the field from its first value, when the step divides the range of
+    /// the field. Returns None otherwise.
+    fn steps(&self, step: NonZeroU32) -> Option<BTreeSet<u32>> {
+        let range = self.max - self.min + 1;
+        range
+            .is_multiple_of(step.get())
+            .then(|| (self.min..=self.max).step_by(step.get() as usize).collect())
+    }
+
+    /// Formats the values as a step like `*/15` when they are every step of the field.
+    fn format_step(&self, values: &BTreeSet<u32>) -> String {
+        match self.step(values) {
+            Some(step) if step.get() > 1 => format!("*/{step}"),
+            _ => self.format(values),
+        }
+    }
+
+    /// Formats the values as a list of numbers and ranges, or `*` when it has all of them.
+    fn format(&self, values: &BTreeSet<u32>) -> String {
+        if values.is_empty() || values.len() as u32 > self.max - self.min {
+            return "*".to_string();
+        }
+        let mut runs: Vec<(u32, u32)> = vec![];
+        for value in values {
+            match runs.last_mut() {
+                Some((_, last)) if *last + 1 == *value => *last = *value,
+                _ => runs.push((*value, *value)),
+            }
+        }
+        let parts: Vec<String> = runs
+            .iter()
+            .flat_map(|(first, last)| match last - first {
+                0 => vec![first.to_string()],
+                1 => vec![first.to_string(), last.to_string()],
+                _ => vec![format!("{first}-{last}")],
+            })
+            .collect();
+        parts.join(",")
+    }
+}
+
+impl Frequency {
+    /// Parses the frequency of a cron expression, like `0 9 * * MON-FRI` for 9:00 on weekdays.
+    ///
+    /// The times of the expression are the `by_time` of a daily, weekly, monthly or yearly
+    /// frequency, depending on which of the days of the month, the months and the weekdays are
+    /// restricted. The steps of the minutes in a range of hours, like `*/15 9-17`, are minutely
+    /// frequencies, and the steps of the hours at minute 0, like `0 */2`, hourly frequencies,
+    /// with the step as interval, so the recurrence must start at midnight. The days of the month
+    /// and the weekdays cannot be both restricted, as cron then matches the days of either of them.
+    /// Examples:
+    /// ```
+    /// use chrono::Weekday;
+    /// use rrules::{Frequency, Time};
+    ///
+    /// let frequency = Frequency::from_cron("0 9 * * MON-FRI").unwrap();
+    /// assert_eq!(frequency.by_day(), [Weekday::Mon, Weekday::Tue, Weekday::Wed, Weekday::Thu, Weekday::Fri]);
+    /// assert_eq!(frequency.by_time(), [Time { hour: 9, minute: 0, second: 0 }]);
+    /// assert_eq!(frequency.to_cron().unwrap(), "0 9 * * 1-5");
+    ///
+    /// let frequency = Frequency::from_cron("*/15 * * * *").unwrap();
+    /// assert_eq!(frequency.to_string(), "FREQ=MINUTELY;INTERVAL=15");
+    /// assert_eq!(frequency.to_cron().unwrap(), "*/15 * * * *");
+    /// ```
+    pub fn from_cron(expression: &str) -> Result<Frequency, InvalidFrequency> {
+        let expression = match expression.trim() {
+            "@yearly" | "@annually" => "0 0 1 1 *",
+            "@monthly" => "0 0 1 * *",
+            "@weekly" => "0 0 * * 0",
+            "@daily" | "@midnight" => "0 0 * * *",
+            "@hourly" => "0 * * * *",
+            _ => expression,
+        };
+        let fields = split_fields(expression);
+        let [minutes, hours, month_days, months, weekdays] = fields[..] else {
+            return Err(InvalidFrequency::Format {
+                message: format!(
+                    "A cron expression has 5 fields, got {}: {expression}",
+                    fields.len()
+                ),
+            });
+        };
+        let minutes = MINUTES.parse(minutes.0, minutes.1)?;
+        let hours = HOURS.parse(hours.0, hours.1)?;
+        let month_days = MONTH_DAYS.parse(month_days.0, month_days.1)?;
+        let months = MONTHS.parse(months.0, months.1)?;
+        let weekdays: BTreeSet<u32> = WEEKDAYS
+            .parse(weekdays.0, weekdays.1)?
+            .into_iter()
+            .map(|weekday| weekday % 7)
+            .collect();
+
+        let mut by_day: Vec<Weekday> = if weekdays.len() < 7 {
+            weekdays
+                .iter()
+                .map(|weekday| CRON_WEEKDAYS[*weekday as usize])
+                .collect()
+        } else {
+            vec![]
+        };
+        by_day.sort_by_key(|weekday| weekday.num_days_from_monday());
+        let by_time: Vec<Time> = hours
+            .iter()
+            .flat_map(|hour| {
+                minutes.iter().map(|minute| Time {
+                    hour: *hour as i32,
+                    minute: *minute as i32,
+                    second: 0,
+                })
+            })
+            .collect();
+        let interval = NonZeroU32::MIN;
+        let by_month_day = month_days.len() < 31;
+        let by_month = months.len() < 12;
+        let minute_step = MINUTES.step(&minutes);
+        let hour_step = HOURS.step(&hours).filter(|_| minutes.iter().eq([0].iter()));
+        // The minutely events are left in a range of hours, from the first to the last excluded
+        let hour_range = match (hours.first(), hours.last()) {
+            (Some(first), Some(last)) if (last - first + 1) as usize == hours.len() => {
+                Some((*first as u8, *last as u8 + 1))
+            }
+            _ => None,
+        };
+
+        // The steps of the minutes in a range of hours, or of the hours at minute 0, are intervals
+        if !by_month_day && !by_month {
+            match (minute_step, hour_range, hour_step) {
+                (Some(step), Some(range), _) => {
+                    return Ok(Frequency::Minutely {
+                        interval: step,
+                        by_day,
+                        by_time: vec![],
+                        by_hour_range: Some(range).filter(|range| *range != (0, 24)),
+                    })
+                }
+                (_, _, Some(step)) => {
+                    return Ok(Frequency::Hourly {
+                        interval: step,
+                        by_day,
+                        by_time: vec![],
+                        by_hour_range: None,
+                    })
+                }
+                _ => {}
+            }
+        }
+
+        let frequency = match (by_month_day, by_month, by_day.is_empty()) {
+            (false, false, true) => Frequency::Daily {
+                interval,
+                by_day: vec![],
+                by_time,
+            },
+            (false, false, false) => Frequency::Weekly {
+                interval,
+                by_day,
+                by_time,
+                by_day_time: vec![],
+            },
+            (true, false, true) => Frequency::Monthly {
+                interval,
+                by_month_day: month_days.iter().map(|day| *day as i32).collect(),
+                by_day: vec![],
+                nth_weekdays: vec![],
+                by_time,
+                overflow: OverflowPolicy::Skip,
+            },
+            (_, true, true) => {
+                let by_monthly_date: Vec<MonthlyDate> = months
+                    .iter()
+                    .flat_map(|month| {
+                        month_days
+                            .iter()
+                            .filter(|day| **day as i32 <= MONTH_LENGTHS[*month as usize - 1])
+                            .map(|day| MonthlyDate {
+                                month: Month::try_from(*month as u8).unwrap_or(Month::January),
+                                day: *day as i32,
+                            })
+                    })
+                    .collect();
+                if by_monthly_date.is_empty() {
+                    return Err(InvalidFrequency::Day {
+                        message: format!("The cron expression never matches a date: {expression}"),
+                    });
+                }
+                Frequency::Yearly {
+                    interval,
+                    by_month: vec![],
+                    by_monthly_date,
+                    nth_weekdays: vec![],
+                    by_week_number: vec![],
+                    by_time,
+                    overflow: OverflowPolicy::Skip,
+                }
+            }
+            (_, _, false) => {
+                return Err(InvalidFrequency::Format {
+                    message: format!(
+                        "Cron expressions restricting the weekdays and the days of the month or the months cannot be represented: {expression}"
+                    ),
+                })
+            }
+        };
+        Ok(frequency)
+    }
+
+    /// Writes the frequency as a cron expression, when cron can represent it.
+    ///
+    /// Cron has no interval nor start date, so the frequency must happen every time its days and
+    /// times match, and define them with `by_time`, `by_day`, `by_month_day` or `by_monthly_date`.
+    /// Only minutely and hourly frequencies without times are written with the minutes and hours
+    /// of their interval from midnight, like `*/15 * * * *`, when the interval divides the hour or
+    /// the day. Otherwise the error lists every part of the rule that cannot be represented.
+    /// Examples:
+    /// ```
+    /// use std::str::FromStr;
+    /// use rrules::Frequency;
+    ///
+    /// let frequency = Frequency::from_str("FREQ=MONTHLY;INTERVAL=1;BYMONTHDAY=1,15;BYHOUR=8,20;BYMINUTE=30").unwrap();
+    /// assert_eq!(frequency.to_cron().unwrap(), "30 8,20 1,15 * *");
+    ///
+    /// let frequency = Frequency::from_str("FREQ=DAILY;INTERVAL=1").unwrap();
+    /// assert_eq!(frequency.to_cron().unwrap_err().features, vec!["DTSTART"]);
+    /// ```
+    pub fn to_cron(&self) -> Result<String, CronUnsupported> {
+        let mut unsupported: BTreeSet<&'static str> = BTreeSet::new();
+        // The minutes or the hours of the sub-daily intervals from midnight, like the steps of cron
+        let steps = match self {
+            Frequency::Minutely {
+                interval, by_time, ..
+            } if by_time.is_empty() => MINUTES.steps(*interval),
+            Frequency::Hourly {
+                interval, by_time, ..
+            } if by_time.is_empty() => HOURS.steps(*interval),
+            _ => None,
+        };
+        if self.interval().get() > 1 && steps.is_none() {
+            unsupported.insert("INTERVAL");
+        }
+        let mut weekdays: BTreeSet<u32> = BTreeSet::new();
+        let mut month_days: BTreeSet<u32> = BTreeSet::new();
+        let mut months: BTreeSet<u32> = BTreeSet::new();
+        let mut times: Vec<Time> = vec![];
+        // The times are taken from the start, unless they are every minute or already unsupported
+        let mut times_from_start = true;
+
+        match self {
+            Frequency::Secondly { .. } => {
+                unsupported.insert("FREQ=SECONDLY");
+                times_from_start = false;
+            }
+            Frequency::Minutely {
+                by_day, by_time, ..
+            }
+            | Frequency::Hourly {
+                by_day, by_time, ..
+            } => {
+                weekdays = weekday_numbers(by_day);
+                times = by_time.clone();
+                times_from_start = steps.is_none();
+            }
+            Frequency::Daily {
+                by_day, by_time, ..
+            } => {
+                weekdays = weekday_numbers(by_day);
+                times = by_time.clone();
+            }
+            Frequency::BusinessDaily { .. } => {
+                weekdays = weekday_numbers(&CRON_WEEKDAYS[1..6]);
+            }
+            Frequency::Weekly {
+                by_day,
+                by_time,
+                by_day_time,
+                ..
+            } => {
+                if !by_day_time.is_empty() {
+                    let day_times = by_day_time
+                        .iter()
+                        .map(|(weekday, time)| (weekday.num_days_from_sunday(), time.clone()));
+                    match cross_product(day_times, |_, _| true) {
+                        Some((days, day_times)) => {
+                            weekdays = days;
+                            times = day_times.into_iter().collect();
+                        }
+                        None => {
+                            unsupported.insert("X-BYDAYTIME");
+                            times_from_start = false;
+                        }
+                    }
+                } else if by_day.is_empty() {
+                    unsupported.insert("DTSTART");
+                } else {
+                    weekdays = weekday_numbers(by_day);
+                    times = by_time.clone();
+                }
+            }
+            Frequency::Monthly {
+                by_month_day,
+                by_day,
+                nth_weekdays,
+                by_time,
+                overflow,
+                ..
+            } => {
+                // Cron matches either the days of the month or the weekdays, not both
+                if !nth_weekdays.is_empty() || !by_month_day.is_empty() && !by_day.is_empty() {
+                    unsupported.insert("BYDAY");
+                }
+                if by_month_day.is_empty() && by_day.is_empty() && nth_weekdays.is_empty() {
+                    unsupported.insert("DTSTART");
+                }
+                if *overflow != OverflowPolicy::Skip {
+                    unsupported.insert("X-OVERFLOW");
+                }
+                month_days.extend(by_month_day.iter().map(|day| *day as u32));
+                weekdays = weekday_numbers(by_day);
+                times = by_time.clone();
+            }
+            Frequency::Yearly {
+                by_month,
+                by_monthly_date,
+                nth_weekdays,
+                by_week_number,
+                by_time,
+                overflow,
+                ..
+            } => {
+                if !nth_weekdays.is_empty() {
+                    unsupported.insert("BYDAY");
+                }
+                if !by_week_number.is_empty() {
+                    unsupported.insert("BYWEEKNO");
+                }
+                if *overflow != OverflowPolicy::Skip {
+                    unsupported.insert("X-OVERFLOW");
+                }
+                if by_monthly_date.is_empty() {
+                    if nth_weekdays.is_empty() && by_week_number.is_empty() {
+                        unsupported.insert("DTSTART");
+                    }
+                } else if !by_month.is_empty() {
+                    unsupported.insert("BYMONTH");
+                } else {
+                    let dates = by_monthly_date
+                        .iter()
+                        .map(|date| (date.month.number_from_month(), date.day as u32));
+                    match cross_product(dates, |month, day| {
+                        day as i32 <= MONTH_LENGTHS[month as usize - 1]
+                    }) {
+                        Some((date_months, days)) => {
+                            months = date_months;
+                            month_days = days;
+                        }
+                        None => {
+                            unsupported.insert("X-BYMONTHDATE");
+                        }
+                    }
+                }
+                times = by_time.clone();
+            }
+        }
+
+        let mut hours: BTreeSet<u32> = BTreeSet::new();
+        let mut minutes: BTreeSet<u32> = BTreeSet::new();
+        if times.iter().any(|time| time.second != 0) {
+            unsupported.insert("BYSECOND");
+        }
+        if times.is_empty() && times_from_start {
+            unsupported.insert("DTSTART");
+        }
+        let hour_minutes = times
+            .iter()
+            .map(|time| (time.hour as u32, time.minute as u32));
+        match cross_product(hour_minutes, |_, _| true) {
+            Some((time_hours, time_minutes)) => {
+                hours = time_hours;
+                minutes = time_minutes;
+            }
+            None => {
+                unsupported.insert("BYTIME");
+            }
+        }
+        match (self, &steps) {
+            (Frequency::Minutely { .. }, Some(steps)) => minutes = steps.clone(),
+            (Frequency::Hourly { .. }, Some(steps)) => {
+                minutes = BTreeSet::from([0]);
+                hours = steps.clone();
+            }
+            _ => {}
+        }
+        // Sub-daily events outside the range of hours are left out of its hours
+        if let Some((start, end)) = self.by_hour_range() {
+            let range = u32::from(start)..u32::from(end);
+            if hours.is_empty() {
+                hours = range.collect();
+            } else {
+                hours.retain(|hour| range.contains(hour));
+                if hours.is_empty() {
+                    unsupported.insert("X-BYHOURRANGE");
+                }
+            }
+        }
+
+        // The intervals are written as steps, like `*/15`
+        let format_times = |field: &Field, values: &BTreeSet<u32>| match &steps {
+            Some(_) => field.format_step(values),
+            None => field.format(values),
+        };
+        if !unsupported.is_empty() {
+            return Err(CronUnsupported {
+                features: unsupported.into_iter().collect(),
+            });
+        }
+        Ok(format!(
+            "{} {} {} {} {}",
+            format_times(&MINUTES, &minutes),
+            format_times(&HOURS, &hours),
+            MONTH_DAYS.format(&month_days),
+            MONTHS.format(&months),
+            // Sunday is written once, as 0
+            Field { max: 6, ..WEEKDAYS }.format(&weekdays)
+        ))
+    }
+}
+
+/// Returns the numbers of the weekdays in cron, from 0 for Sunday.
+fn weekday_numbers(by_day: &[Weekday]) -> BTreeSet<u32> {
+    by_day
+        .iter()
+        .map(|weekday| weekday.num_days_from_sunday())
+        .collect()
+}
+
+/// Returns the fields of the expression separated by whitespace, with their offsets.
+fn split_fields(expression: &str) -> Vec<(usize, &str)> {
+    let mut fields = vec![];
+    let mut start = None;
+    for (index, c) in expression.char_indices().chain([(expression.len(), ' ')]) {
+        match (c.is_whitespace(), start) {
+            (false, None) => start = Some(index),
+            (true, Some(first)) => {
+                fields.push((first, &expression[first..index]));
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    fields
+}
+
+/// Splits the pairs into their first and second values, when the pairs are every combination of
+/// them that exists. Returns None otherwise.
+fn cross_product<A: Ord + Clone, B: Ord + Clone>(
+    pairs: impl Iterator<Item = (A, B)>,
+    exists: impl Fn(A, B) -> bool,
+) -> Option<(BTreeSet<A>, BTreeSet<B>)> {
+    let pairs: BTreeSet<(A, B)> = pairs.collect();
+    let firsts: BTreeSet<A> = pairs.iter().map(|(first, _)| first.clone()).collect();
+    let seconds: BTreeSet<B> = pairs.iter().map(|(_, second)| second.clone()).collect();
+    let combinations = firsts
+        .iter()
+        .flat_map(|first| seconds.iter().map(move |second| (first, second)))
+        .filter(|(first, second)| exists((*first).clone(), (*second).clone()))
+        .count();
+    (combinations == pairs.len()).then_some((firsts, seconds))
+}
+
+#[cfg(test)]
+mod test_cron {
+    use crate::{Frequency, InvalidFrequency, MonthlyDate, ParseErrorKind, Time};
+    use chrono::{Month, Weekday};
+    use std::str::FromStr;
+
+    fn time(hour: i32, minute: i32) -> Time {
+        Time {
+            hour,
+            minute,
+            second: 0,
+        }
+    }
+
+    #[test]
+    fn parses_the_fields() {
+        let frequency = Frequency::from_cron("*/20 9-11,17 * * *").unwrap();
+        assert_eq!(
+            frequency,
+            Frequency::from_str(
+                "FREQ=DAILY;INTERVAL=1;BYTIME=09:00,09:20,09:40,10:00,10:20,10:40,11:00,11:20,11:40,17:00,17:20,17:40"
+            )
+            .unwrap()
+        );
+
+        let frequency = Frequency::from_cron("30 8 1,15 * *").unwrap();
+        assert_eq!(
+            frequency,
+            Frequency::from_str("FREQ=MONTHLY;INTERVAL=1;BYMONTHDAY=1,15;BYTIME=08:30").unwrap()
+        );
+
+        let frequency = Frequency::from_cron("0 12 * * sat,0").unwrap();
+        assert_eq!(frequency.by_day(), [Weekday::Sat, Weekday::Sun]);
+        assert_eq!(frequency.by_time(), [time(12, 0)]);
+
+        let frequency = Frequency::from_cron("* * * * 7").unwrap();
+        assert_eq!(
+            frequency,
+            Frequency::from_str("FREQ=MINUTELY;INTERVAL=1;BYDAY=SU").unwrap()
+        );
+    }
+
+    #[test]
+    fn parses_the_steps_as_intervals() {
+        for (expression, rule) in [
+            ("*/15 * * * *", "FREQ=MINUTELY;INTERVAL=15"),
+            (
+                "*/5 9-17 * * MON-FRI",
+                "FREQ=MINUTELY;INTERVAL=5;BYDAY=MO,TU,WE,TH,FR;X-BYHOURRANGE=9-18",
+            ),
+            ("0 */6 * * *", "FREQ=HOURLY;INTERVAL=6"),
+            ("0 * * * SUN", "FREQ=HOURLY;INTERVAL=1;BYDAY=SU"),
+        ] {
+            assert_eq!(
+                Frequency::from_cron(expression).unwrap(),
+                Frequency::from_str(rule).unwrap(),
+                "{expression}"
+            );
+        }
+        // Steps that don't divide the hour restart every hour, so they are times
+        let frequency = Frequency::from_cron("*/25 * * * *").unwrap();
+        assert_eq!(frequency.by_time().len(), 24 * 3);
+        assert_eq!(frequency.to_cron().unwrap(), "0,25,50 * * * *");
+    }
+
+    #[test]
+    fn parses_the_yearly_dates() {
+        let Frequency::Yearly {
+            by_monthly_date, ..
+        } = Frequency::from_cron("0 0 30,31 JAN-APR *").unwrap()
+        else {
+            panic!("Expected a yearly frequency");
+        };
+        let date = |month, day| MonthlyDate { month, day };
+        assert_eq!(
+            by_monthly_date,
+            vec![
+                date(Month::January, 30),
+                date(Month::January, 31),
+                date(Month::March, 30),
+                date(Month::March, 31),
+                date(Month::April, 30),
+            ]
+        );
+        assert!(Frequency::from_cron("0 0 30 2 *").is_err());
+    }
+
+    #[test]
+    fn parses_the_shortcuts() {
+        for (shortcut, expression) in [
+            ("@yearly", "0 0 1 1 *"),
+            ("@annually", "0 0 1 1 *"),
+            ("@monthly", "0 0 1 * *"),
+            ("@weekly", "0 0 * * 0"),
+            ("@daily", "0 0 * * *"),
+            ("@midnight", "0 0 * * *"),
+            ("@hourly", "0 * * * *"),
+        ] {
+            assert_eq!(
+                Frequency::from_cron(shortcut).unwrap(),
+                Frequency::from_cron(expression).unwrap()
+            );
+            assert_eq!(
+                Frequency::from_cron(shortcut).unwrap().to_cron().unwrap(),
+                expression
+            );
+        }
+    }
+
+    #[test]
+    fn invalid_expressions() {
+        for expression in [
+            "",
+            "@reboot",
+            "0 9 * *",
+            "0 9 * * * 2023",
+            "0 9 1 * MON",
+            "0 9 * 6 MON",
+        ] {
+            assert!(
+                matches!(
+                    Frequency::from_cron(expression),
+                    Err(InvalidFrequency::Format { .. })
+                ),
+                "{expression}"
+            );
+        }
+        for (expression, found) in [
+            ("60 9 * * *", "60"),
+            ("0 9-5 * * *", "9-5"),
+            ("0 */0 * * *", "*/0"),
+            ("0 9 0 * *", "0"),
+            ("0 9 * 13 *", "13"),
+            ("0 9 * * MON-", "MON-"),
+            ("0 9 * *  FRIDAY", "FRIDAY"),
+            ("0 9 * * 1,,2", "1,,2"),
+            ("0 +9 * * *", "+9"),
+        ] {
+            let Err(InvalidFrequency::Parse { error }) = Frequency::from_cron(expression) else {
+                panic!("Expected a parse error for {expression}");
+            };
+            assert_eq!(error.kind, ParseErrorKind::InvalidValue);
+            assert_eq!(error.found, found);
+            assert_eq!(&expression[error.span], found);
+        }
+    }
+
+    #[test]
+    fn writes_the_frequencies() {
+        for (rule, expression) in [
+            ("FREQ=MINUTELY;INTERVAL=1", "* * * * *"),
+            (
+                "FREQ=HOURLY;INTERVAL=1;BYDAY=MO;BYTIME=09:00,09:30,10:00,10:30",
+                "0,30 9,10 * * 1",
+            ),
+            (
+                "FREQ=MINUTELY;INTERVAL=1;BYDAY=MO,TU,WE,TH,FR;X-BYHOURRANGE=9-18",
+                "* 9-17 * * 1-5",
+            ),
+            (
+                "FREQ=HOURLY;INTERVAL=1;BYTIME=08:30,09:30,10:30;X-BYHOURRANGE=9-18",
+                "30 9,10 * * *",
+            ),
+            ("FREQ=DAILY;INTERVAL=1;BYHOUR=0,1,2,3,12", "0 0-3,12 * * *"),
+            (
+                "FREQ=WEEKLY;INTERVAL=1;BYDAY=MO,TU,WE,TH,FR,SA,SU;BYHOUR=9",
+                "0 9 * * *",
+            ),
+            (
+                "FREQ=WEEKLY;INTERVAL=1;X-BYDAYTIME=MO09:00,WE09:00",
+                "0 9 * * 1,3",
+            ),
+            ("FREQ=MONTHLY;INTERVAL=1;BYDAY=SU;BYHOUR=6", "0 6 * * 0"),
+            (
+                "FREQ=YEARLY;INTERVAL=1;BYMONTH=6,12;BYMONTHDAY=1,30;BYHOUR=0",
+                "0 0 1,30 6,12 *",
+            ),
+            (
+                "FREQ=YEARLY;INTERVAL=1;X-BYMONTHDATE=JAN31,MAR31,JAN1,FEB1,MAR1;BYHOUR=0",
+                "0 0 1,31 1-3 *",
+            ),
+        ] {
+            let frequency = Frequency::from_str(rule).unwrap();
+            assert_eq!(frequency.to_cron().unwrap(), expression, "{rule}");
+        }
+    }
+
+    #[test]
+    fn round_trips() {
+        for expression in [
+            "0 9 * * 1-5",
+            "*/15 * * * *",
+            "*/10 9-17 * * 1-5",
+            "0 */2 * * *",
+            "0 * * * 0,6",
+            "0 0 29 2 *",
+            "5 4 * * 0",
+            "0 22 1,15 * *",
+            "*/30 0-6 * * 6",
+        ] {
+            let frequency = Frequency::from_cron(expression).unwrap();
+            assert_eq!(frequency.to_cron().unwrap(), expression);
+        }
+    }
+
+    #[test]
+    fn lists_the_unsupported_features() {
+        for (rule, features) in [
+            ("FREQ=SECONDLY;INTERVAL=1", vec!["FREQ=SECONDLY"]),
+            ("FREQ=HOURLY;INTERVAL=5", vec!["DTSTART", "INTERVAL"]),
+            ("FREQ=MINUTELY;INTERVAL=7", vec!["DTSTART", "INTERVAL"]),
+            ("FREQ=DAILY;INTERVAL=1;BYTIME=09:00,17:30", vec!["BYTIME"]),
+            (
+                "FREQ=HOURLY;INTERVAL=1;BYTIME=20:00;X-BYHOURRANGE=9-18",
+                vec!["X-BYHOURRANGE"],
+            ),
+            ("FREQ=DAILY;INTERVAL=1;BYTIME=09:00:30", vec!["BYSECOND"]),
+            ("FREQ=DAILY;INTERVAL=1;X-BUSINESS=TRUE", vec!["DTSTART"]),
+            (
+                "FREQ=WEEKLY;INTERVAL=1;X-BYDAYTIME=MO09:00,TU10:00",
+                vec!["X-BYDAYTIME"],
+            ),
+            (
+                "FREQ=MONTHLY;INTERVAL=1;BYMONTHDAY=13;BYDAY=FR;BYHOUR=9;X-OVERFLOW=CLAMP",
+                vec!["BYDAY", "X-OVERFLOW"],
+            ),
+            (
+                "FREQ=YEARLY;INTERVAL=1;BYWEEKNO=1;BYDAY=MO;BYHOUR=9",
+                vec!["BYDAY", "BYWEEKNO"],
+            ),
+            (
+                "FREQ=YEARLY;INTERVAL=1;X-BYMONTHDATE=JAN1,FEB2;BYHOUR=9",
+                vec!["X-BYMONTHDATE"],
+            ),
+            ("FREQ=YEARLY;INTERVAL=1;BYMONTH=3", vec!["DTSTART"]),
+        ] {
+            let frequency = Frequency::from_str(rule).unwrap();
+            assert_eq!(
+                frequency.to_cron().unwrap_err().features,
+                features,
+                "{rule}"
+            );
+        }
+    }
+}
@@ -0,0 +1,359 @@
+use crate::frequencies::{Frequency, InvalidFrequency};
+use crate::utils::aligned_month_start;
+use chrono::{DateTime, Duration, Utc};
+
+/// The rules that generate the events of a `Recurrence`.
+///
+/// `Frequency` implements the rules of the RFC 5545 frequencies, other implementations give
+/// a `Recurrence` the events of custom business rules, E.g. a rotation of shifts. The intervals
+/// of the rules are counted from an anchor, the start date of the recurrence, while the end
+/// date, the excluded dates and the iteration limits are applied by the recurrence.
+///
+/// Examples:
+/// ```
+/// use chrono::{DateTime, Duration, Utc};
+/// use rrules::{InvalidFrequency, OccurrenceEngine, Recurrence};
+/// use std::str::FromStr;
+///
+/// /// Four days in a row at the time of the anchor, then four days off.
+/// #[derive(Debug, Clone)]
+/// struct FourOnFourOff;
+///
+/// impl OccurrenceEngine for FourOnFourOff {
+///     fn next_event(&self, anchor: &DateTime<Utc>, date: &DateTime<Utc>) -> Option<DateTime<Utc>> {
+///         let mut days = if date < anchor { 0 } else { (*date - *anchor).num_days() + 1 };
+///         if days % 8 >= 4 {
+///             days += 8 - days % 8;
+///         }
+///         anchor.checked_add_signed(Duration::days(days))
+///     }
+///
+///     fn previous_event(&self, anchor: &DateTime<Utc>, date: &DateTime<Utc>) -> Option<DateTime<Utc>> {
+///         if date <= anchor {
+///             return None;
+///         }
+///         let mut days = (*date - *anchor - Duration::nanoseconds(1)).num_days();
+///         if days % 8 >= 4 {
+///             days -= days % 8 - 3;
+///         }
+///         anchor.checked_add_signed(Duration::days(days))
+///     }
+///
+///     fn contains(&self, anchor: &DateTime<Utc>, date: &DateTime<Utc>) -> bool {
+///         let days = (*date - *anchor).num_days();
+///         *date - *anchor == Duration::days(days) && days >= 0 && days % 8 < 4
+///     }
+///
+///     fn validate(&self) -> Result<(), InvalidFrequency> {
+///         Ok(())
+///     }
+/// }
+///
+/// let start = DateTime::<Utc>::from_str("2023-01-01T06:00:00Z").unwrap();
+/// let shifts = Recurrence::from_engine(FourOnFourOff, start, None, Some(Duration::hours(12))).unwrap();
+/// let events: Vec<DateTime<Utc>> = shifts.iter().take(5).collect();
+/// assert_eq!(events[3], DateTime::<Utc>::from_str("2023-01-04T06:00:00Z").unwrap());
+/// assert_eq!(events[4], DateTime::<Utc>::from_str("2023-01-09T06:00:00Z").unwrap());
+/// assert!(!shifts.contains(&DateTime::<Utc>::from_str("2023-01-06T06:00:00Z").unwrap()));
+/// ```
+pub trait OccurrenceEngine: Clone {
+    /// Returns the first event after the date, or None when there are no more events.
+    ///
+    /// The date must be the anchor or an event of the rules, as the events are generated one
+    /// after the other from the anchor. The events from any other date follow the
+    /// `previous_event` of the date, or the anchor when there is none.
+    fn next_event(&self, anchor: &DateTime<Utc>, date: &DateTime<Utc>) -> Option<DateTime<Utc>>;
+
+    /// Returns the last event before the date and not before the anchor, or None when there
+    /// is no such event. It is used to skip the events before a date without computing them.
+    fn previous_event(&self, anchor: &DateTime<Utc>, date: &DateTime<Utc>)
+        -> Option<DateTime<Utc>>;
+
+    /// Verifies if the date is an event of the rules with the intervals counted from the anchor.
+    fn contains(&self, anchor: &DateTime<Utc>, date: &DateTime<Utc>) -> bool;
+
+    /// Verifies that the rules are valid, when a recurrence is created from them.
+    fn validate(&self) -> Result<(), InvalidFrequency>;
+
+    /// Returns the rules in their canonical form, E.g. with their lists sorted and without
+    /// duplicates, applied before validating them when a recurrence is created from them.
+    fn normalized(self) -> Self {
+        self
+    }
+
+    /// Returns the constant time between the events, when every event is the anchor moved by a
+    /// whole number of steps, so the events can be counted and skipped arithmetically.
+    fn fixed_step(&self) -> Option<Duration> {
+        None
+    }
+}
+
+impl OccurrenceEngine for Frequency {
+    fn next_event(&self, anchor: &DateTime<Utc>, date: &DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let mut current_date = *date;
+        loop {
+            // A next date that doesn't move forward ends the events rather than looping
+            let next_date = self
+                .next_aligned_event(anchor, &current_date)
+                .filter(|next_date| *next_date > current_date)?;
+            if Frequency::contains(self, &next_date) {
+                return Some(next_date);
+            }
+            current_date = next_date;
+        }
+    }
+
+    fn previous_event(
+        &self,
+        anchor: &DateTime<Utc>,
+        date: &DateTime<Utc>,
+    ) -> Option<DateTime<Utc>> {
+        if date <= anchor {
+            return None;
+        }
+        if let Some(step) = Frequency::fixed_step(self) {
+            let step = step.num_milliseconds();
+            let steps = date.signed_duration_since(*anchor).num_milliseconds() / step;
+            let event = |steps: i64| {
+                let span = steps.checked_mul(step)?;
+                anchor.checked_add_signed(Duration::milliseconds(span))
+            };
+            return event(steps)
+                .filter(|event| event < date)
+                .or_else(|| event(steps - 1));
+        }
+        self.nth_weekdays_window(anchor, date)
+            .and_then(|first_date| self.previous_event_from(anchor, first_date, date))
+            .or_else(|| self.previous_event_from(anchor, *anchor, date))
+    }
+
+    fn contains(&self, anchor: &DateTime<Utc>, date: &DateTime<Utc>) -> bool {
+        Frequency::contains(self, date) && self.is_aligned(anchor, date)
+    }
+
+    fn validate(&self) -> Result<(), InvalidFrequency> {
+        self.is_valid()
+    }
+
+    fn normalized(self) -> Self {
+        Frequency::normalized(self)
+    }
+
+    fn fixed_step(&self) -> Option<Duration> {
+        Frequency::fixed_step(self)
+    }
+}
+
+impl Frequency {
+    /// Returns the last event before the date, going through the dates the events are looked
+    /// for in from the first date, which is the anchor or one of those dates.
+    fn previous_event_from(
+        &self,
+        anchor: &DateTime<Utc>,
+        first_date: DateTime<Utc>,
+        date: &DateTime<Utc>,
+    ) -> Option<DateTime<Utc>> {
+        let mut previous_event = None;
+        let mut current_date = Some(first_date);
+        while let Some(candidate) = current_date.filter(|candidate| candidate < date) {
+            // The anchor is only an event when it matches the rules, the following dates
+            // are already a number of intervals away from it
+            let is_event = if candidate == *anchor {
+                OccurrenceEngine::contains(self, anchor, &candidate)
+            } else {
+                Frequency::contains(self, &candidate)
+            };
+            if is_event {
+                previous_event = Some(candidate);
+            }
+            current_date = self
+                .next_aligned_event(anchor, &candidate)
+                .filter(|next_date| *next_date > candidate);
+        }
+        previous_event
+    }
+
+    /// Returns the first date the monthly nth weekdays are looked for in the month window
+    /// before the window of the date, as the nth weekdays are looked for in the month windows
+    /// of the anchor. None for the other frequencies, or when the window is not after the anchor.
+    fn nth_weekdays_window(
+        &self,
+        anchor: &DateTime<Utc>,
+        date: &DateTime<Utc>,
+    ) -> Option<DateTime<Utc>> {
+        let interval = match self {
+            Frequency::Monthly {
+                interval,
+                by_month_day,
+                by_day,
+                nth_weekdays,
+                ..
+            } if by_month_day.is_empty() && by_day.is_empty() && !nth_weekdays.is_empty() => {
+                i64::from(interval.get())
+            }
+            _ => return None,
+        };
+        let window = aligned_month_start(anchor, date, interval)?;
+        let previous_window = window
+            .checked_sub_signed(Duration::nanoseconds(1))
+            .and_then(|before| aligned_month_start(anchor, &before, interval))
+            .filter(|previous_window| previous_window > anchor)?;
+        previous_window
+            .checked_sub_signed(Duration::nanoseconds(1))
+            .and_then(|before| self.next_aligned_event(anchor, &before))
+    }
+}
+
+#[cfg(test)]
+mod test_engine {
+    use crate::frequencies::{Frequency, InvalidFrequency, OccurrenceEngine};
+    use crate::recurrences::Recurrence;
+    use crate::test_utils::date;
+    use chrono::{DateTime, Duration, Utc};
+    use std::str::FromStr;
+
+    /// Every given number of minutes, without a fixed step so the events are computed.
+    #[derive(Debug, Clone)]
+    struct EveryMinutes(i64);
+
+    impl OccurrenceEngine for EveryMinutes {
+        fn next_event(
+            &self,
+            anchor: &DateTime<Utc>,
+            date: &DateTime<Utc>,
+        ) -> Option<DateTime<Utc>> {
+            let steps = (*date - *anchor).num_minutes().div_euclid(self.0) + 1;
+            Some(*anchor + Duration::minutes(steps.max(0) * self.0))
+        }
+
+        fn previous_event(
+            &self,
+            anchor: &DateTime<Utc>,
+            date: &DateTime<Utc>,
+        ) -> Option<DateTime<Utc>> {
+            let minutes = (*date - *anchor - Duration::seconds(1)).num_minutes();
+            (*date > *anchor).then(|| *anchor + Duration::minutes(minutes - minutes % self.0))
+        }
+
+        fn contains(&self, anchor: &DateTime<Utc>, date: &DateTime<Utc>) -> bool {
+            let span = *date - *anchor;
+            span.num_seconds() % 60 == 0 && span.num_minutes() % self.0 == 0
+        }
+
+        fn validate(&self) -> Result<(), InvalidFrequency> {
+            match self.0 {
+                1.. => Ok(()),
+                _ => Err(InvalidFrequency::Interval {
+                    message: "The minutes must be positive".into(),
+                }),
+            }
+        }
+    }
+
+    #[test]
+    fn recurrences_of_a_custom_engine() {
+        let start = date("2023-01-01T00:00:00Z");
+        let end = Some(date("2023-01-02T00:00:00Z"));
+        assert!(Recurrence::from_engine(EveryMinutes(0), start, end, None).is_err());
+
+        let recurrence = Recurrence::from_engine(EveryMinutes(90), start, end, None)
+            .unwrap()
+            .with_exdates(vec![date("2023-01-01T03:00:00Z")]);
+        assert_eq!(recurrence.count_occurrences(), Some(16));
+        assert_eq!(
+            recurrence.next_from(&date("2023-01-01T02:00:00Z")),
+            Some(date("2023-01-01T04:30:00Z"))
+        );
+        let events: Vec<DateTime<Utc>> = recurrence
+            .between(&date("2023-01-01T12:00:00Z"), &date("2023-01-01T16:00:00Z"))
+            .collect();
+        assert_eq!(
+            events,
+            vec![
+                date("2023-01-01T12:00:00Z"),
+                date("2023-01-01T13:30:00Z"),
+                date("2023-01-01T15:00:00Z")
+            ]
+        );
+        assert_eq!(
+            recurrence.position_of(&date("2023-01-01T06:00:00Z")),
+            Some(3)
+        );
+        assert!(!recurrence.contains(&date("2023-01-01T03:00:00Z")));
+        assert!(!recurrence.is_expired(&date("2023-01-01T22:30:00Z")));
+        assert!(recurrence.is_expired(&date("2023-01-02T00:00:00Z")));
+    }
+
+    #[test]
+    fn previous_events_are_the_events_before_the_date() {
+        let rules = [
+            "FREQ=HOURLY;INTERVAL=5;DTSTART=2023-01-01T10:30:00Z",
+            "FREQ=DAILY;INTERVAL=2;BYTIME=09:00,17:00;DTSTART=2023-01-01T09:00:00Z",
+            "FREQ=WEEKLY;INTERVAL=2;BYDAY=MO,FR;DTSTART=2023-01-04T08:00:00Z",
+            "FREQ=MONTHLY;INTERVAL=2;BYDAY=2TU,5FR;DTSTART=2023-01-01T18:00:00Z",
+            "FREQ=MONTHLY;INTERVAL=1;BYMONTHDAY=31;DTSTART=2023-01-31T00:00:00Z",
+            "FREQ=YEARLY;INTERVAL=1;BYMONTH=2;BYMONTHDAY=29;DTSTART=2020-02-29T12:00:00Z",
+        ];
+        for rule in rules {
+            let recurrence = Recurrence::from_str(rule).unwrap();
+            let (anchor, frequency) = (recurrence.start, &recurrence.frequency);
+            let events: Vec<DateTime<Utc>> = recurrence.iter().take(40).collect();
+            for (index, event) in events.iter().enumerate() {
+                let previous = index.checked_sub(1).map(|index| events[index]);
+                assert_eq!(frequency.previous_event(&anchor, event), previous, "{rule}");
+                let after = *event + Duration::seconds(1);
+                assert_eq!(
+                    frequency.previous_event(&anchor, &after),
+                    Some(*event),
+                    "{rule}"
+                );
+                if let Some(next) = events.get(index + 1) {
+                    let next_event = OccurrenceEngine::next_event(frequency, &anchor, event);
+                    assert_eq!(next_event, Some(*next), "{rule}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn next_events_follow_the_anchor_and_the_events() {
+        let rules = [
+            "FREQ=HOURLY;INTERVAL=5;DTSTART=2023-01-01T10:30:00Z",
+            "FREQ=WEEKLY;INTERVAL=2;BYDAY=MO,FR;DTSTART=2023-01-04T08:00:00Z",
+            "FREQ=MONTHLY;INTERVAL=2;BYDAY=2TU,5FR;DTSTART=2023-01-01T18:00:00Z",
+            "FREQ=YEARLY;INTERVAL=1;BYMONTH=2;BYMONTHDAY=29;DTSTART=2020-02-29T12:00:00Z",
+        ];
+        for rule in rules {
+            let recurrence = Recurrence::from_str(rule).unwrap();
+            let (anchor, frequency) = (recurrence.start, &recurrence.frequency);
+            let events: Vec<DateTime<Utc>> = recurrence.iter().take(20).collect();
+            let mut current_date = anchor;
+            for event in events.iter().filter(|event| **event > anchor) {
+                let next_event = OccurrenceEngine::next_event(frequency, &anchor, &current_date);
+                assert_eq!(next_event, Some(*event), "{rule}");
+                current_date = *event;
+            }
+            for (previous, event) in events.iter().zip(events.iter().skip(1)) {
+                let date = *previous + (*event - *previous) / 2;
+                let from = frequency.previous_event(&anchor, &date).unwrap_or(anchor);
+                let next_event = OccurrenceEngine::next_event(frequency, &anchor, &from);
+                assert_eq!(next_event, Some(*event), "{rule}");
+            }
+        }
+    }
+
+    #[test]
+    fn engine_contains_the_aligned_events() {
+        let every_2_weeks = Frequency::from_str("FREQ=WEEKLY;INTERVAL=2;BYDAY=MO").unwrap();
+        let anchor = date("2023-01-02T10:00:00Z");
+        let contains =
+            |event: &str| OccurrenceEngine::contains(&every_2_weeks, &anchor, &date(event));
+        assert!(contains("2023-01-16T10:00:00Z"));
+        assert!(!contains("2023-01-09T10:00:00Z"));
+        assert!(Frequency::contains(
+            &every_2_weeks,
+            &date("2023-01-09T10:00:00Z")
+        ));
+        assert_eq!(every_2_weeks.previous_event(&anchor, &anchor), None);
+    }
+}
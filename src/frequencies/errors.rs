@@ -1,15 +1,33 @@
-use std::fmt::{Display, Formatter};
+use alloc::string::String;
+use core::fmt::{Display, Formatter};
+use core::ops::Range;
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
 pub enum InvalidFrequency {
-    Interval { message: String },
-    Time { message: String },
-    Day { message: String },
-    Format { message: String },
+    Interval {
+        message: String,
+    },
+    Time {
+        message: String,
+    },
+    Day {
+        message: String,
+    },
+    Format {
+        message: String,
+    },
+    Parse {
+        error: ParseError,
+    },
+    /// The values are valid on their own, but their combination is not supported
+    Unsupported {
+        message: String,
+    },
 }
 
 impl Display for InvalidFrequency {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         match self {
             InvalidFrequency::Interval { message } => {
                 write!(f, "Invalid interval: {message}")
@@ -17,6 +35,87 @@ impl Display for InvalidFrequency {
             InvalidFrequency::Time { message } => write!(f, "Invalid time: {message}"),
             InvalidFrequency::Day { message } => write!(f, "Invalid day: {message}"),
             InvalidFrequency::Format { message } => write!(f, "Invalid format: {message}"),
+            InvalidFrequency::Parse { error } => write!(f, "Invalid format: {error}"),
+            InvalidFrequency::Unsupported { message } => {
+                write!(f, "Unsupported combination: {message}")
+            }
+        }
+    }
+}
+
+impl core::error::Error for InvalidFrequency {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            InvalidFrequency::Parse { error } => Some(error),
+            _ => None,
         }
     }
 }
+
+impl From<ParseError> for InvalidFrequency {
+    fn from(error: ParseError) -> Self {
+        InvalidFrequency::Parse { error }
+    }
+}
+
+/// The kind of problem found while parsing a rule string.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ParseErrorKind {
+    /// A part of the rule is not a `KEY=VALUE` pair
+    Malformed,
+    /// The key is not supported (only reported in strict mode)
+    UnknownKey,
+    /// The key was already defined (only reported in strict mode)
+    DuplicatedKey,
+    /// A value is repeated in the list of values of a key (only reported in strict mode)
+    DuplicatedValue,
+    /// The value, or one of the values of a list, cannot be parsed
+    InvalidValue,
+}
+
+/// A parse error pointing at the part of the rule string that is wrong.
+///
+/// Examples:
+/// ```
+/// use std::str::FromStr;
+/// use rrules::{Frequency, InvalidFrequency, ParseErrorKind};
+///
+/// let value = "FREQ=DAILY;INTERVAL=1;BYHOUR=9,25";
+/// match Frequency::from_str(value) {
+///     Err(InvalidFrequency::Parse { error }) => {
+///         assert_eq!(error.kind, ParseErrorKind::InvalidValue);
+///         assert_eq!(&value[error.span.clone()], "25");
+///         assert_eq!(error.found, "25");
+///     }
+///     _ => panic!("Expected a parse error"),
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub kind: ParseErrorKind,
+    /// Byte range of the rule string where the error was found
+    pub span: Range<usize>,
+    /// The text found at the span
+    pub found: String,
+    /// Description of what was expected at the span
+    pub expected: String,
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        let found = &self.found;
+        match self.kind {
+            ParseErrorKind::Malformed => write!(f, "Expected KEY=VALUE, got {found:?}")?,
+            ParseErrorKind::UnknownKey => write!(f, "Unknown key {found}")?,
+            ParseErrorKind::DuplicatedKey => write!(f, "Duplicated key {found}")?,
+            ParseErrorKind::DuplicatedValue => write!(f, "Duplicated value {found}")?,
+            ParseErrorKind::InvalidValue => {
+                write!(f, "Invalid value {found:?}, expected {}", self.expected)?
+            }
+        }
+        write!(f, " at {}..{}", self.span.start, self.span.end)
+    }
+}
+
+impl core::error::Error for ParseError {}
@@ -2,29 +2,36 @@
 mod secondly_frequency {
     use crate::frequencies::Frequency;
     use chrono::{DateTime, Timelike, Utc};
+    use std::num::NonZeroU32;
     use std::str::FromStr;
 
     #[test]
     fn every_second_frequency() {
-        let f = Frequency::Secondly { interval: 1 };
+        let f = Frequency::Secondly {
+            interval: NonZeroU32::new(1).unwrap(),
+            by_day: vec![],
+            by_time: vec![],
+        };
         let result = f.is_valid();
         assert!(result.is_ok());
     }
 
     #[test]
     fn invalid_interval() {
-        let f = Frequency::Secondly { interval: 0 };
-        let result = f.is_valid();
+        let result = Frequency::from_str("FREQ=SECONDLY;INTERVAL=0");
         assert!(result.is_err());
 
-        let f = Frequency::Secondly { interval: -1 };
-        let result = f.is_valid();
+        let result = Frequency::from_str("FREQ=SECONDLY;INTERVAL=-1");
         assert!(result.is_err());
     }
 
     #[test]
     fn every_second_collect_events() {
-        let f = Frequency::Secondly { interval: 1 };
+        let f = Frequency::Secondly {
+            interval: NonZeroU32::new(1).unwrap(),
+            by_day: vec![],
+            by_time: vec![],
+        };
         let now = Utc::now();
         let next_event = f.next_event(&now);
         assert_eq!(next_event.unwrap().second(), now.second() + 1);
@@ -32,7 +39,11 @@ mod secondly_frequency {
 
     #[test]
     fn collect_events_that_span_to_another_minute() {
-        let f = Frequency::Secondly { interval: 30 };
+        let f = Frequency::Secondly {
+            interval: NonZeroU32::new(30).unwrap(),
+            by_day: vec![],
+            by_time: vec![],
+        };
         let date = DateTime::<Utc>::from_str("2020-01-01T00:00:59Z").unwrap();
         let next_event = f.next_event(&date);
         assert_eq!(next_event.unwrap().second(), 29);
@@ -46,29 +57,38 @@ mod secondly_frequency {
 mod minutely_frequency {
     use crate::frequencies::Frequency;
     use chrono::{DateTime, Timelike, Utc};
+    use std::num::NonZeroU32;
     use std::str::FromStr;
 
     #[test]
     fn every_minute_frequency() {
-        let f = Frequency::Minutely { interval: 1 };
+        let f = Frequency::Minutely {
+            interval: NonZeroU32::new(1).unwrap(),
+            by_day: vec![],
+            by_time: vec![],
+            by_hour_range: None,
+        };
         let result = f.is_valid();
         assert!(result.is_ok());
     }
 
     #[test]
     fn invalid_interval() {
-        let f = Frequency::Minutely { interval: 0 };
-        let result = f.is_valid();
+        let result = Frequency::from_str("FREQ=MINUTELY;INTERVAL=0");
         assert!(result.is_err());
 
-        let f = Frequency::Minutely { interval: -1 };
-        let result = f.is_valid();
+        let result = Frequency::from_str("FREQ=MINUTELY;INTERVAL=-1");
         assert!(result.is_err());
     }
 
     #[test]
     fn every_minute_collect_events() {
-        let f = Frequency::Minutely { interval: 1 };
+        let f = Frequency::Minutely {
+            interval: NonZeroU32::new(1).unwrap(),
+            by_day: vec![],
+            by_time: vec![],
+            by_hour_range: None,
+        };
         let date = DateTime::<Utc>::from_str("2023-01-01T00:00:00Z").unwrap();
         let next_event = f.next_event(&date);
         assert_eq!(next_event.unwrap().minute(), date.minute() + 1);
@@ -76,7 +96,12 @@ mod minutely_frequency {
 
     #[test]
     fn collect_events_that_span_to_another_hour() {
-        let f = Frequency::Minutely { interval: 30 };
+        let f = Frequency::Minutely {
+            interval: NonZeroU32::new(30).unwrap(),
+            by_day: vec![],
+            by_time: vec![],
+            by_hour_range: None,
+        };
         let date = DateTime::<Utc>::from_str("2020-01-01T00:00:59Z").unwrap();
         let next_event = f.next_event(&date);
         assert_eq!(next_event.unwrap().minute(), 30);
@@ -90,29 +115,38 @@ mod minutely_frequency {
 mod hourly_frequency {
     use crate::frequencies::Frequency;
     use chrono::{DateTime, Datelike, Timelike, Utc};
+    use std::num::NonZeroU32;
     use std::str::FromStr;
 
     #[test]
     fn every_hour_frequency() {
-        let f = Frequency::Hourly { interval: 1 };
+        let f = Frequency::Hourly {
+            interval: NonZeroU32::new(1).unwrap(),
+            by_day: vec![],
+            by_time: vec![],
+            by_hour_range: None,
+        };
         let result = f.is_valid();
         assert!(result.is_ok());
     }
 
     #[test]
     fn invalid_interval() {
-        let f = Frequency::Hourly { interval: 0 };
-        let result = f.is_valid();
+        let result = Frequency::from_str("FREQ=HOURLY;INTERVAL=0");
         assert!(result.is_err());
 
-        let f = Frequency::Hourly { interval: -1 };
-        let result = f.is_valid();
+        let result = Frequency::from_str("FREQ=HOURLY;INTERVAL=-1");
         assert!(result.is_err());
     }
 
     #[test]
     fn every_hour_collect_events() {
-        let f = Frequency::Hourly { interval: 1 };
+        let f = Frequency::Hourly {
+            interval: NonZeroU32::new(1).unwrap(),
+            by_day: vec![],
+            by_time: vec![],
+            by_hour_range: None,
+        };
         let now = DateTime::<Utc>::from_str("2020-01-01T00:00:00Z").unwrap();
         let next_event = f.next_event(&now);
         assert_eq!(next_event.unwrap().hour(), 1);
@@ -120,7 +154,12 @@ mod hourly_frequency {
 
     #[test]
     fn collect_events_that_span_to_another_day() {
-        let f = Frequency::Hourly { interval: 12 };
+        let f = Frequency::Hourly {
+            interval: NonZeroU32::new(12).unwrap(),
+            by_day: vec![],
+            by_time: vec![],
+            by_hour_range: None,
+        };
         let date = DateTime::<Utc>::from_str("2020-01-01T00:00:59Z").unwrap();
         let next_event = f.next_event(&date);
         assert_eq!(next_event.unwrap().hour(), 12);
@@ -131,16 +170,142 @@ mod hourly_frequency {
     }
 }
 
+#[cfg(test)]
+mod sub_daily_filters {
+    use crate::frequencies::{Frequency, Time};
+    use chrono::{DateTime, Utc, Weekday};
+    use std::num::NonZeroU32;
+    use std::str::FromStr;
+
+    #[test]
+    fn hourly_on_weekdays_only() {
+        let f = Frequency::Hourly {
+            interval: NonZeroU32::new(1).unwrap(),
+            by_day: vec![Weekday::Mon],
+            by_time: vec![],
+            by_hour_range: None,
+        };
+        assert!(f.is_valid().is_ok());
+
+        // 2023-01-01 is a Sunday
+        let date = DateTime::<Utc>::from_str("2023-01-01T22:00:00Z").unwrap();
+        assert!(!f.contains(&date));
+        let next_event = f.next_event(&date).unwrap();
+        assert_eq!(
+            next_event,
+            DateTime::<Utc>::from_str("2023-01-02T00:00:00Z").unwrap()
+        );
+    }
+
+    #[test]
+    fn hourly_at_specific_times() {
+        let f = Frequency::Hourly {
+            interval: NonZeroU32::new(1).unwrap(),
+            by_day: vec![],
+            by_time: vec![
+                Time::from_str("09:00").unwrap(),
+                Time::from_str("17:00").unwrap(),
+            ],
+            by_hour_range: None,
+        };
+        let date = DateTime::<Utc>::from_str("2023-01-01T09:00:00Z").unwrap();
+        assert!(f.contains(&date));
+
+        let next_event = f.next_event(&date).unwrap();
+        assert_eq!(
+            next_event,
+            DateTime::<Utc>::from_str("2023-01-01T17:00:00Z").unwrap()
+        );
+        let next_event = f.next_event(&next_event).unwrap();
+        assert_eq!(
+            next_event,
+            DateTime::<Utc>::from_str("2023-01-02T09:00:00Z").unwrap()
+        );
+    }
+
+    #[test]
+    fn every_15_minutes_within_a_time_on_mondays() {
+        let f = Frequency::Minutely {
+            interval: NonZeroU32::new(15).unwrap(),
+            by_day: vec![Weekday::Mon],
+            by_time: vec![Time::from_str("10:30").unwrap()],
+            by_hour_range: None,
+        };
+        let date = DateTime::<Utc>::from_str("2023-01-01T10:00:00Z").unwrap();
+        let next_event = f.next_event(&date).unwrap();
+        assert_eq!(
+            next_event,
+            DateTime::<Utc>::from_str("2023-01-02T10:30:00Z").unwrap()
+        );
+        let next_event = f.next_event(&next_event).unwrap();
+        assert_eq!(
+            next_event,
+            DateTime::<Utc>::from_str("2023-01-09T10:30:00Z").unwrap()
+        );
+    }
+
+    #[test]
+    fn secondly_stays_within_the_minute() {
+        let f = Frequency::Secondly {
+            interval: NonZeroU32::new(30).unwrap(),
+            by_day: vec![],
+            by_time: vec![Time::from_str("08:00").unwrap()],
+        };
+        let date = DateTime::<Utc>::from_str("2023-01-01T08:00:30Z").unwrap();
+        let next_event = f.next_event(&date).unwrap();
+        assert_eq!(
+            next_event,
+            DateTime::<Utc>::from_str("2023-01-02T08:00:00Z").unwrap()
+        );
+    }
+
+    #[test]
+    fn unreachable_time_returns_none() {
+        let f = Frequency::Hourly {
+            interval: NonZeroU32::new(2).unwrap(),
+            by_day: vec![],
+            by_time: vec![Time::from_str("01:00").unwrap()],
+            by_hour_range: None,
+        };
+        let date = DateTime::<Utc>::from_str("2023-01-01T00:00:00Z").unwrap();
+        assert!(f.next_event(&date).is_none());
+    }
+
+    #[test]
+    fn invalid_repeated_filters() {
+        let f = Frequency::Minutely {
+            interval: NonZeroU32::new(1).unwrap(),
+            by_day: vec![Weekday::Mon, Weekday::Mon],
+            by_time: vec![],
+            by_hour_range: None,
+        };
+        assert!(f.is_valid().is_err());
+
+        let f = Frequency::Minutely {
+            interval: NonZeroU32::new(1).unwrap(),
+            by_day: vec![],
+            by_time: vec![
+                Time::from_str("08:00").unwrap(),
+                Time::from_str("08:00").unwrap(),
+            ],
+            by_hour_range: None,
+        };
+        assert!(f.is_valid().is_err());
+    }
+}
+
 #[cfg(test)]
 mod daily_frequency {
     use crate::frequencies::Frequency;
     use chrono::{DateTime, Datelike, Utc};
+    use std::num::NonZeroU32;
     use std::str::FromStr;
 
     #[test]
     fn every_day_frequency() {
         let f = Frequency::Daily {
-            interval: 1,
+            interval: NonZeroU32::new(1).unwrap(),
+            by_day: vec![],
             by_time: vec![],
         };
         let result = f.is_valid();
@@ -149,25 +314,18 @@ mod daily_frequency {
 
     #[test]
     fn invalid_interval() {
-        let f = Frequency::Daily {
-            interval: 0,
-            by_time: vec![],
-        };
-        let result = f.is_valid();
+        let result = Frequency::from_str("FREQ=DAILY;INTERVAL=0");
         assert!(result.is_err());
 
-        let f = Frequency::Daily {
-            interval: -1,
-            by_time: vec![],
-        };
-        let result = f.is_valid();
+        let result = Frequency::from_str("FREQ=DAILY;INTERVAL=-1");
         assert!(result.is_err());
     }
 
     #[test]
     fn every_day_collect_events() {
         let f = Frequency::Daily {
-            interval: 1,
+            interval: NonZeroU32::new(1).unwrap(),
+            by_day: vec![],
             by_time: vec![],
         };
         let now = Utc::now();
@@ -178,7 +336,8 @@ mod daily_frequency {
     #[test]
     fn collect_events_that_span_to_another_month() {
         let f = Frequency::Daily {
-            interval: 15,
+            interval: NonZeroU32::new(15).unwrap(),
+            by_day: vec![],
             by_time: vec![],
         };
         let date = DateTime::<Utc>::from_str("2020-01-02T00:00:59Z").unwrap();
@@ -195,12 +354,14 @@ mod daily_frequency {
 mod daily_frequencies_by_hour {
     use crate::frequencies::{Frequency, Time};
     use chrono::{DateTime, Datelike, Timelike, Utc};
+    use std::num::NonZeroU32;
     use std::str::FromStr;
 
     #[test]
     fn twice_a_day() {
         let f = Frequency::Daily {
-            interval: 1,
+            interval: NonZeroU32::new(1).unwrap(),
+            by_day: vec![],
             by_time: vec![
                 Time::from_str("00:00").unwrap(),
                 Time::from_str("12:00").unwrap(),
@@ -218,7 +379,8 @@ mod daily_frequencies_by_hour {
     #[test]
     fn twice_a_day_with_interval() {
         let f = Frequency::Daily {
-            interval: 2,
+            interval: NonZeroU32::new(2).unwrap(),
+            by_day: vec![],
             by_time: vec![
                 Time::from_str("00:00").unwrap(),
                 Time::from_str("12:00").unwrap(),
@@ -232,20 +394,134 @@ mod daily_frequencies_by_hour {
         assert_eq!(next_event.day(), now.day() + 2);
         assert_eq!(next_event.hour(), 0);
     }
+
+    #[test]
+    fn times_with_seconds() {
+        let f = Frequency::Daily {
+            interval: NonZeroU32::new(1).unwrap(),
+            by_day: vec![],
+            by_time: vec![
+                Time::from_str("09:30:15").unwrap(),
+                Time::from_str("09:30").unwrap(),
+            ],
+        };
+        let now = DateTime::<Utc>::from_str("2020-01-01T09:30:00Z").unwrap();
+        let next_event = f.next_event(&now).unwrap();
+        assert_eq!(
+            next_event,
+            DateTime::<Utc>::from_str("2020-01-01T09:30:15Z").unwrap()
+        );
+        assert!(f.contains(&next_event));
+        assert!(!f.contains(&DateTime::<Utc>::from_str("2020-01-01T09:30:16Z").unwrap()));
+        assert_eq!(
+            f.next_event(&next_event).unwrap(),
+            DateTime::<Utc>::from_str("2020-01-02T09:30:00Z").unwrap()
+        );
+    }
+}
+
+#[cfg(test)]
+mod daily_by_day {
+    use crate::frequencies::{Frequency, InvalidFrequency};
+    use crate::test_utils::date;
+    use crate::Recurrence;
+    use chrono::{DateTime, Utc, Weekday};
+    use std::str::FromStr;
+
+    #[test]
+    fn weekdays_restrict_the_days_of_the_interval() {
+        let rule = "FREQ=DAILY;INTERVAL=2;BYDAY=MO,WE;DTSTART=2023-01-02T09:00:00Z";
+        let recurrence = Recurrence::from_str(rule).unwrap();
+        assert_eq!(recurrence.to_string(), rule);
+        assert_eq!(recurrence.frequency.by_day(), [Weekday::Mon, Weekday::Wed]);
+        let events: Vec<DateTime<Utc>> = recurrence.iter().take(4).collect();
+        assert_eq!(
+            events,
+            [
+                date("2023-01-02T09:00:00Z"),
+                date("2023-01-04T09:00:00Z"),
+                date("2023-01-16T09:00:00Z"),
+                date("2023-01-18T09:00:00Z"),
+            ]
+        );
+        // A Monday an odd number of days away from the start is not an event
+        assert!(!recurrence.contains(&date("2023-01-09T09:00:00Z")));
+        assert_eq!(
+            recurrence.next_from(&date("2023-01-05T00:00:00Z")),
+            Some(date("2023-01-16T09:00:00Z"))
+        );
+        assert_eq!(
+            recurrence.next_from(&date("2024-01-01T00:00:00Z")),
+            Some(date("2024-01-01T09:00:00Z"))
+        );
+    }
+
+    #[test]
+    fn weekdays_with_times() {
+        let recurrence = Recurrence::from_str(
+            "FREQ=DAILY;INTERVAL=1;BYDAY=SA,SU;BYHOUR=10,16;DTSTART=2023-01-02T00:00:00Z",
+        )
+        .unwrap();
+        let events: Vec<DateTime<Utc>> = recurrence.iter().take(5).collect();
+        assert_eq!(
+            events,
+            [
+                date("2023-01-07T10:00:00Z"),
+                date("2023-01-07T16:00:00Z"),
+                date("2023-01-08T10:00:00Z"),
+                date("2023-01-08T16:00:00Z"),
+                date("2023-01-14T10:00:00Z"),
+            ]
+        );
+    }
+
+    #[test]
+    fn weekdays_never_reached_by_the_interval() {
+        // Every 7 days from a Monday only goes through Mondays
+        let recurrence = Recurrence::from_str(
+            "FREQ=DAILY;INTERVAL=7;BYDAY=TU;BYHOUR=9,18;DTSTART=2023-01-02T00:00:00Z",
+        )
+        .unwrap();
+        assert_eq!(recurrence.iter().next(), None);
+    }
+
+    #[test]
+    fn invalid_weekdays() {
+        assert!(Frequency::from_str("FREQ=DAILY;BYDAY=1MO").is_err());
+        let frequency = Frequency::from_str("FREQ=DAILY;BYDAY=MO,TU").unwrap();
+        let Frequency::Daily {
+            interval, by_time, ..
+        } = frequency
+        else {
+            panic!("Expected a daily frequency");
+        };
+        let repeated = Frequency::Daily {
+            interval,
+            by_day: vec![Weekday::Mon, Weekday::Mon],
+            by_time,
+        };
+        assert!(matches!(
+            repeated.is_valid(),
+            Err(InvalidFrequency::Day { .. })
+        ));
+    }
 }
 
 #[cfg(test)]
 mod weekly_frequency {
     use crate::frequencies::Frequency;
     use chrono::{DateTime, Datelike, Duration, Utc};
+    use std::num::NonZeroU32;
     use std::ops::Add;
     use std::str::FromStr;
 
     #[test]
     fn every_week_frequency() {
         let f = Frequency::Weekly {
-            interval: 1,
+            interval: NonZeroU32::new(1).unwrap(),
             by_day: vec![],
+            by_time: vec![],
+            by_day_time: vec![],
         };
         let result = f.is_valid();
         assert!(result.is_ok());
@@ -253,26 +529,20 @@ mod weekly_frequency {
 
     #[test]
     fn invalid_interval() {
-        let f = Frequency::Weekly {
-            interval: 0,
-            by_day: vec![],
-        };
-        let result = f.is_valid();
+        let result = Frequency::from_str("FREQ=WEEKLY;INTERVAL=0");
         assert!(result.is_err());
 
-        let f = Frequency::Weekly {
-            interval: -1,
-            by_day: vec![],
-        };
-        let result = f.is_valid();
+        let result = Frequency::from_str("FREQ=WEEKLY;INTERVAL=-1");
         assert!(result.is_err());
     }
 
     #[test]
     fn every_week_collect_events() {
         let f = Frequency::Weekly {
-            interval: 1,
+            interval: NonZeroU32::new(1).unwrap(),
             by_day: vec![],
+            by_time: vec![],
+            by_day_time: vec![],
         };
         let now = Utc::now();
         let next_event = f.next_event(&now);
@@ -282,8 +552,10 @@ mod weekly_frequency {
     #[test]
     fn collect_events_that_span_to_another_month() {
         let f = Frequency::Weekly {
-            interval: 1,
+            interval: NonZeroU32::new(1).unwrap(),
             by_day: vec![],
+            by_time: vec![],
+            by_day_time: vec![],
         };
         let date = DateTime::<Utc>::from_str("2020-01-28T00:00:59Z").unwrap();
         let next_event = f.next_event(&date);
@@ -296,13 +568,16 @@ mod weekly_frequency {
 mod weekly_by_day {
     use crate::frequencies::Frequency;
     use chrono::{DateTime, Datelike, Utc, Weekday};
+    use std::num::NonZeroU32;
     use std::str::FromStr;
 
     #[test]
     fn every_monday() {
         let f = Frequency::Weekly {
-            interval: 1,
+            interval: NonZeroU32::new(1).unwrap(),
             by_day: vec![Weekday::Mon],
+            by_time: vec![],
+            by_day_time: vec![],
         };
         let date = DateTime::<Utc>::from_str("2020-01-01T00:00:00Z").unwrap();
         let next_event = f.next_event(&date).unwrap();
@@ -317,28 +592,129 @@ mod weekly_by_day {
     #[test]
     fn twice_a_week() {
         let f = Frequency::Weekly {
-            interval: 1,
+            interval: NonZeroU32::new(1).unwrap(),
             by_day: vec![Weekday::Mon, Weekday::Wed],
+            by_time: vec![],
+            by_day_time: vec![],
         };
         let date = DateTime::<Utc>::from_str("2023-01-01T00:00:00Z").unwrap();
         let next_event = f.next_event(&date).unwrap();
         assert_eq!(next_event.weekday(), Weekday::Mon);
         assert_eq!(next_event.day(), 2);
     }
+
+    fn collect_events(f: &Frequency, start: &str, count: usize) -> Vec<String> {
+        let mut date = DateTime::<Utc>::from_str(start).unwrap();
+        let mut events = vec![];
+        for _ in 0..count {
+            date = f.next_event(&date).unwrap();
+            events.push(date.format("%Y-%m-%d").to_string());
+        }
+        events
+    }
+
+    #[test]
+    fn every_2_weeks_on_many_days() {
+        let f = Frequency::Weekly {
+            interval: NonZeroU32::new(2).unwrap(),
+            by_day: vec![Weekday::Fri, Weekday::Mon],
+            by_time: vec![],
+            by_day_time: vec![],
+        };
+        // Wednesday, the Friday of the same week is the first event
+        let events = collect_events(&f, "2020-01-01T00:00:00Z", 5);
+        assert_eq!(
+            events,
+            vec![
+                "2020-01-03",
+                "2020-01-13",
+                "2020-01-17",
+                "2020-01-27",
+                "2020-01-31"
+            ]
+        );
+    }
+
+    #[test]
+    fn every_3_weeks_on_many_days() {
+        let f = Frequency::Weekly {
+            interval: NonZeroU32::new(3).unwrap(),
+            by_day: vec![Weekday::Tue, Weekday::Thu, Weekday::Sat],
+            by_time: vec![],
+            by_day_time: vec![],
+        };
+        let events = collect_events(&f, "2023-01-03T09:00:00Z", 6);
+        assert_eq!(
+            events,
+            vec![
+                "2023-01-05",
+                "2023-01-07",
+                "2023-01-24",
+                "2023-01-26",
+                "2023-01-28",
+                "2023-02-14"
+            ]
+        );
+    }
+
+    #[test]
+    fn sunday_is_the_last_day_of_the_week() {
+        let f = Frequency::Weekly {
+            interval: NonZeroU32::new(2).unwrap(),
+            by_day: vec![Weekday::Sun, Weekday::Mon],
+            by_time: vec![],
+            by_day_time: vec![],
+        };
+        // Sunday, the following Monday starts a week that is skipped
+        let events = collect_events(&f, "2023-01-01T00:00:00Z", 4);
+        assert_eq!(
+            events,
+            vec!["2023-01-09", "2023-01-15", "2023-01-23", "2023-01-29"]
+        );
+    }
+
+    #[test]
+    fn weeks_stay_aligned_from_dates_in_other_weeks() {
+        let f = Frequency::Weekly {
+            interval: NonZeroU32::new(2).unwrap(),
+            by_day: vec![Weekday::Mon],
+            by_time: vec![],
+            by_day_time: vec![],
+        };
+        let anchor = DateTime::<Utc>::from_str("2023-01-30T09:00:00Z").unwrap();
+        // The week of 2023-02-06 is not a whole number of intervals away from the anchor
+        let date = DateTime::<Utc>::from_str("2023-02-07T09:00:00Z").unwrap();
+        let next_event = f.next_aligned_event(&anchor, &date).unwrap();
+        assert_eq!(
+            next_event,
+            DateTime::<Utc>::from_str("2023-02-13T09:00:00Z").unwrap()
+        );
+        assert!(f.is_aligned(&anchor, &next_event));
+        let date = DateTime::<Utc>::from_str("2023-02-27T09:00:00Z").unwrap();
+        assert_eq!(
+            f.next_aligned_event(&anchor, &date).unwrap(),
+            DateTime::<Utc>::from_str("2023-03-13T09:00:00Z").unwrap()
+        );
+        assert_eq!(next_event.weekday(), Weekday::Mon);
+    }
 }
 
 #[cfg(test)]
 mod monthly_frequency {
-    use crate::frequencies::Frequency;
+    use crate::frequencies::{Frequency, OverflowPolicy};
     use chrono::{DateTime, Datelike, Utc};
+    use std::num::NonZeroU32;
     use std::str::FromStr;
 
     #[test]
     fn every_month_frequency() {
         let f = Frequency::Monthly {
-            interval: 1,
+            interval: NonZeroU32::new(1).unwrap(),
             by_month_day: vec![],
+            by_day: vec![],
             nth_weekdays: vec![],
+            by_time: vec![],
+            overflow: OverflowPolicy::Skip,
         };
         let result = f.is_valid();
         assert!(result.is_ok());
@@ -346,29 +722,22 @@ mod monthly_frequency {
 
     #[test]
     fn invalid_interval() {
-        let f = Frequency::Monthly {
-            interval: 0,
-            by_month_day: vec![],
-            nth_weekdays: vec![],
-        };
-        let result = f.is_valid();
+        let result = Frequency::from_str("FREQ=MONTHLY;INTERVAL=0");
         assert!(result.is_err());
 
-        let f = Frequency::Monthly {
-            interval: -1,
-            by_month_day: vec![],
-            nth_weekdays: vec![],
-        };
-        let result = f.is_valid();
+        let result = Frequency::from_str("FREQ=MONTHLY;INTERVAL=-1");
         assert!(result.is_err());
     }
 
     #[test]
     fn every_month_collect_events() {
         let f = Frequency::Monthly {
-            interval: 1,
+            interval: NonZeroU32::new(1).unwrap(),
             by_month_day: vec![],
+            by_day: vec![],
             nth_weekdays: vec![],
+            by_time: vec![],
+            overflow: OverflowPolicy::Skip,
         };
         let date = DateTime::<Utc>::from_str("2020-01-01T00:00:00Z").unwrap();
         let next_event = f.next_event(&date);
@@ -378,9 +747,12 @@ mod monthly_frequency {
     #[test]
     fn collect_events_that_span_to_another_year() {
         let f = Frequency::Monthly {
-            interval: 1,
+            interval: NonZeroU32::new(1).unwrap(),
             by_month_day: vec![],
+            by_day: vec![],
             nth_weekdays: vec![],
+            by_time: vec![],
+            overflow: OverflowPolicy::Skip,
         };
         let date = DateTime::<Utc>::from_str("2020-12-02T00:00:59Z").unwrap();
         let next_event = f.next_event(&date);
@@ -391,16 +763,21 @@ mod monthly_frequency {
 
 #[cfg(test)]
 mod monthly_by_month_day {
-    use crate::frequencies::Frequency;
+    use crate::frequencies::{Frequency, OverflowPolicy};
+    use crate::ParseOptions;
     use chrono::{DateTime, Datelike, Utc};
+    use std::num::NonZeroU32;
     use std::str::FromStr;
 
     #[test]
     fn every_1st_of_month() {
         let f = Frequency::Monthly {
-            interval: 1,
+            interval: NonZeroU32::new(1).unwrap(),
             by_month_day: vec![1],
+            by_day: vec![],
             nth_weekdays: vec![],
+            by_time: vec![],
+            overflow: OverflowPolicy::Skip,
         };
         let date = DateTime::<Utc>::from_str("2020-01-01T00:00:00Z").unwrap();
         let next_event = f.next_event(&date).unwrap();
@@ -423,9 +800,12 @@ mod monthly_by_month_day {
     #[test]
     fn every_1st_and_15th_of_the_month() {
         let f = Frequency::Monthly {
-            interval: 1,
+            interval: NonZeroU32::new(1).unwrap(),
             by_month_day: vec![1, 15],
+            by_day: vec![],
             nth_weekdays: vec![],
+            by_time: vec![],
+            overflow: OverflowPolicy::Skip,
         };
         let date = DateTime::<Utc>::from_str("2020-01-01T00:00:00Z").unwrap();
         let next_event = f.next_event(&date).unwrap();
@@ -454,53 +834,159 @@ mod monthly_by_month_day {
     }
 
     #[test]
-    fn every_31th() {
+    fn every_31th_clamped_to_last_day() {
         let f = Frequency::Monthly {
-            interval: 1,
+            interval: NonZeroU32::new(1).unwrap(),
             by_month_day: vec![31],
+            by_day: vec![],
             nth_weekdays: vec![],
+            by_time: vec![],
+            overflow: OverflowPolicy::ClampToLastDay,
         };
-        let date = DateTime::<Utc>::from_str("2020-01-01T00:00:00Z").unwrap();
+        let date = DateTime::<Utc>::from_str("2020-01-31T10:00:00Z").unwrap();
         let next_event = f.next_event(&date).unwrap();
         assert_eq!(
-            next_event.day(),
-            31,
-            "next event should be the 31th of the month"
+            next_event,
+            DateTime::<Utc>::from_str("2020-02-29T10:00:00Z").unwrap()
         );
+        assert!(f.contains(&next_event));
+        let next_event = f.next_event(&next_event).unwrap();
         assert_eq!(
-            next_event.month(),
-            1,
-            "next event should be in the same month"
-        );
-
-        let next_event = f.next_event(&next_event);
-        assert!(
-            next_event.is_none(),
-            "next event should be none because february does not have a 31th day"
+            next_event,
+            DateTime::<Utc>::from_str("2020-03-31T10:00:00Z").unwrap()
         );
     }
-}
-
-#[cfg(test)]
-mod monthly_by_weekday {
-    use crate::frequencies::{Frequency, NthWeekday};
-    use chrono::{DateTime, Datelike, Utc, Weekday};
-    use std::str::FromStr;
 
     #[test]
-    fn every_1st_monday_of_the_month() {
+    fn every_30th_and_31th_clamped_once_in_short_months() {
         let f = Frequency::Monthly {
-            interval: 1,
-            by_month_day: vec![],
-            nth_weekdays: vec![NthWeekday {
-                week_number: 1,
-                weekday: Weekday::Mon,
-            }],
+            interval: NonZeroU32::new(1).unwrap(),
+            by_month_day: vec![30, 31],
+            by_day: vec![],
+            nth_weekdays: vec![],
+            by_time: vec![],
+            overflow: OverflowPolicy::ClampToLastDay,
         };
-        let date = DateTime::<Utc>::from_str("2023-01-01T00:00:00Z").unwrap();
+        let date = DateTime::<Utc>::from_str("2021-01-31T00:00:00Z").unwrap();
         let next_event = f.next_event(&date).unwrap();
         assert_eq!(
-            next_event.day(),
+            next_event,
+            DateTime::<Utc>::from_str("2021-02-28T00:00:00Z").unwrap()
+        );
+        let next_event = f.next_event(&next_event).unwrap();
+        assert_eq!(
+            next_event,
+            DateTime::<Utc>::from_str("2021-03-30T00:00:00Z").unwrap()
+        );
+    }
+
+    #[test]
+    fn every_31th_moved_to_next_valid_day() {
+        let f = Frequency::Monthly {
+            interval: NonZeroU32::new(1).unwrap(),
+            by_month_day: vec![31],
+            by_day: vec![],
+            nth_weekdays: vec![],
+            by_time: vec![],
+            overflow: OverflowPolicy::NextValid,
+        };
+        let date = DateTime::<Utc>::from_str("2023-03-31T00:00:00Z").unwrap();
+        let next_event = f.next_event(&date).unwrap();
+        assert_eq!(
+            next_event,
+            DateTime::<Utc>::from_str("2023-05-01T00:00:00Z").unwrap()
+        );
+        assert!(f.contains(&next_event));
+        assert!(!f.contains(&DateTime::<Utc>::from_str("2023-04-01T00:00:00Z").unwrap()));
+        let next_event = f.next_event(&next_event).unwrap();
+        assert_eq!(
+            next_event,
+            DateTime::<Utc>::from_str("2023-05-31T00:00:00Z").unwrap()
+        );
+    }
+
+    #[test]
+    fn every_31th() {
+        let f = Frequency::Monthly {
+            interval: NonZeroU32::new(1).unwrap(),
+            by_month_day: vec![31],
+            by_day: vec![],
+            nth_weekdays: vec![],
+            by_time: vec![],
+            overflow: OverflowPolicy::Skip,
+        };
+        let date = DateTime::<Utc>::from_str("2020-01-01T00:00:00Z").unwrap();
+        let next_event = f.next_event(&date).unwrap();
+        assert_eq!(
+            next_event.day(),
+            31,
+            "next event should be the 31th of the month"
+        );
+        assert_eq!(
+            next_event.month(),
+            1,
+            "next event should be in the same month"
+        );
+
+        let next_event = f.next_event(&next_event).unwrap();
+        assert_eq!(
+            next_event.month(),
+            3,
+            "next event should skip february because it does not have a 31th day"
+        );
+        assert_eq!(next_event.day(), 31);
+
+        let next_event = f.next_event(&next_event).unwrap();
+        assert_eq!(next_event.month(), 5, "april should be skipped too");
+        assert_eq!(next_event.day(), 31);
+    }
+
+    #[test]
+    fn same_day_as_start() {
+        let start = DateTime::<Utc>::from_str("2023-01-31T09:00:00Z").unwrap();
+        let f = Frequency::monthly_same_day_as_start(NonZeroU32::new(1).unwrap(), &start);
+        assert_eq!(f.by_month_day(), [31]);
+        assert!(f.is_valid().is_ok());
+        // February doesn't have the day
+        assert_eq!(
+            f.next_event(&start).unwrap(),
+            DateTime::<Utc>::from_str("2023-03-31T09:00:00Z").unwrap()
+        );
+    }
+
+    #[test]
+    fn rejects_the_months() {
+        let value = "FREQ=MONTHLY;INTERVAL=1;BYMONTH=1,6;BYMONTHDAY=15";
+        assert!(Frequency::from_str(value).is_err());
+        assert!(Frequency::from_str_with(value, ParseOptions::strict()).is_err());
+        assert!(Frequency::from_str("FREQ=YEARLY;INTERVAL=1;BYMONTH=1,6;BYMONTHDAY=15").is_ok());
+    }
+}
+
+#[cfg(test)]
+mod monthly_by_weekday {
+    use crate::frequencies::{Frequency, NthWeekday, OverflowPolicy};
+    use chrono::{DateTime, Datelike, Utc, Weekday};
+    use std::num::NonZeroU32;
+    use std::str::FromStr;
+
+    #[test]
+    fn every_1st_monday_of_the_month() {
+        let f = Frequency::Monthly {
+            interval: NonZeroU32::new(1).unwrap(),
+            by_month_day: vec![],
+            by_day: vec![],
+            nth_weekdays: vec![NthWeekday {
+                week_number: 1,
+                weekday: Weekday::Mon,
+            }],
+            by_time: vec![],
+            overflow: OverflowPolicy::Skip,
+        };
+        let date = DateTime::<Utc>::from_str("2023-01-01T00:00:00Z").unwrap();
+        let next_event = f.next_event(&date).unwrap();
+        assert_eq!(
+            next_event.day(),
             2,
             "next event should be the 2th of the month"
         );
@@ -514,12 +1000,15 @@ mod monthly_by_weekday {
     #[test]
     fn every_2nd_tuesday() {
         let f = Frequency::Monthly {
-            interval: 1,
+            interval: NonZeroU32::new(1).unwrap(),
             by_month_day: vec![],
+            by_day: vec![],
             nth_weekdays: vec![NthWeekday {
                 week_number: 2,
                 weekday: Weekday::Tue,
             }],
+            by_time: vec![],
+            overflow: OverflowPolicy::Skip,
         };
         let date = DateTime::<Utc>::from_str("2023-01-01T00:00:00Z").unwrap();
         let next_event = f.next_event(&date).unwrap();
@@ -538,8 +1027,9 @@ mod monthly_by_weekday {
     #[test]
     fn every_1st_wednesday_and_friday() {
         let f = Frequency::Monthly {
-            interval: 1,
+            interval: NonZeroU32::new(1).unwrap(),
             by_month_day: vec![],
+            by_day: vec![],
             nth_weekdays: vec![
                 NthWeekday {
                     week_number: 1,
@@ -550,6 +1040,8 @@ mod monthly_by_weekday {
                     weekday: Weekday::Fri,
                 },
             ],
+            by_time: vec![],
+            overflow: OverflowPolicy::Skip,
         };
         let date = DateTime::<Utc>::from_str("2023-01-01T00:00:00Z").unwrap();
         let next_event = f.next_event(&date).unwrap();
@@ -583,20 +1075,64 @@ mod monthly_by_weekday {
             "next event should be the 1st of the month"
         );
     }
+
+    #[test]
+    fn same_weekday_as_start() {
+        let start = DateTime::<Utc>::from_str("2023-01-17T10:00:00Z").unwrap();
+        let f = Frequency::monthly_same_weekday_as_start(NonZeroU32::new(2).unwrap(), &start);
+        assert_eq!(
+            f,
+            Frequency::Monthly {
+                interval: NonZeroU32::new(2).unwrap(),
+                by_month_day: vec![],
+                by_day: vec![],
+                nth_weekdays: vec![NthWeekday::new(Weekday::Tue, 3)],
+                by_time: vec![],
+                overflow: OverflowPolicy::Skip,
+            }
+        );
+        assert!(f.is_valid().is_ok());
+        assert_eq!(f.to_string(), "FREQ=MONTHLY;INTERVAL=2;BYDAY=3TU");
+        let next_event = f.next_event(&start).unwrap();
+        assert_eq!(
+            next_event,
+            DateTime::<Utc>::from_str("2023-03-21T10:00:00Z").unwrap()
+        );
+    }
+
+    #[test]
+    fn same_weekday_as_a_start_on_the_5th_weekday() {
+        // The 5th Sunday of January 2023, February doesn't have one
+        let start = DateTime::<Utc>::from_str("2023-01-29T08:00:00Z").unwrap();
+        let f = Frequency::monthly_same_weekday_as_start(NonZeroU32::new(1).unwrap(), &start);
+        assert_eq!(f.nth_weekdays(), [NthWeekday::new(Weekday::Sun, 5)]);
+        let next_event = f.next_event(&start).unwrap();
+        assert_eq!(
+            next_event,
+            DateTime::<Utc>::from_str("2023-04-30T08:00:00Z").unwrap()
+        );
+        assert_eq!(next_event.weekday(), Weekday::Sun);
+    }
 }
 
 #[cfg(test)]
 mod yearly_frequencies {
     use crate::frequencies::models::MonthlyDate;
-    use crate::frequencies::Frequency;
-    use chrono::{DateTime, Datelike, Month, Utc};
+    use crate::frequencies::{Frequency, NthWeekday, OverflowPolicy};
+    use chrono::{DateTime, Datelike, Month, Utc, Weekday};
+    use std::num::NonZeroU32;
     use std::str::FromStr;
 
     #[test]
     fn once_a_year() {
         let f = Frequency::Yearly {
-            interval: 1,
-            by_monthly_date: None,
+            interval: NonZeroU32::new(1).unwrap(),
+            by_month: vec![],
+            by_monthly_date: vec![],
+            nth_weekdays: vec![],
+            by_week_number: vec![],
+            by_time: vec![],
+            overflow: OverflowPolicy::Skip,
         };
         let date = DateTime::<Utc>::from_str("2023-01-01T00:00:00Z").unwrap();
         let next_event = f.next_event(&date).unwrap();
@@ -620,11 +1156,16 @@ mod yearly_frequencies {
     #[test]
     fn every_15th_january() {
         let f = Frequency::Yearly {
-            interval: 1,
-            by_monthly_date: Some(MonthlyDate {
+            interval: NonZeroU32::new(1).unwrap(),
+            by_month: vec![],
+            by_monthly_date: vec![MonthlyDate {
                 month: Month::January,
                 day: 15,
-            }),
+            }],
+            nth_weekdays: vec![],
+            by_week_number: vec![],
+            by_time: vec![],
+            overflow: OverflowPolicy::Skip,
         };
         let date = DateTime::<Utc>::from_str("2023-01-01T00:00:00Z").unwrap();
         let next_event = f.next_event(&date).unwrap();
@@ -657,18 +1198,220 @@ mod yearly_frequencies {
             "next event should be in the next year"
         );
     }
+
+    #[test]
+    fn test_twice_a_year() {
+        let f = Frequency::Yearly {
+            interval: NonZeroU32::new(1).unwrap(),
+            by_month: vec![],
+            by_monthly_date: vec![
+                MonthlyDate {
+                    month: Month::January,
+                    day: 15,
+                },
+                MonthlyDate {
+                    month: Month::June,
+                    day: 1,
+                },
+            ],
+            nth_weekdays: vec![],
+            by_week_number: vec![],
+            by_time: vec![],
+            overflow: OverflowPolicy::Skip,
+        };
+        let date = DateTime::<Utc>::from_str("2023-01-01T00:00:00Z").unwrap();
+        let next_event = f.next_event(&date).unwrap();
+        assert_eq!(
+            next_event,
+            DateTime::<Utc>::from_str("2023-01-15T00:00:00Z").unwrap()
+        );
+
+        let next_event = f.next_event(&next_event).unwrap();
+        assert_eq!(
+            next_event,
+            DateTime::<Utc>::from_str("2023-06-01T00:00:00Z").unwrap()
+        );
+
+        let next_event = f.next_event(&next_event).unwrap();
+        assert_eq!(
+            next_event,
+            DateTime::<Utc>::from_str("2024-01-15T00:00:00Z").unwrap()
+        );
+    }
+
+    #[test]
+    fn within_twice_a_year() {
+        let f = Frequency::Yearly {
+            interval: NonZeroU32::new(1).unwrap(),
+            by_month: vec![],
+            by_monthly_date: vec![
+                MonthlyDate {
+                    month: Month::January,
+                    day: 15,
+                },
+                MonthlyDate {
+                    month: Month::June,
+                    day: 1,
+                },
+            ],
+            nth_weekdays: vec![],
+            by_week_number: vec![],
+            by_time: vec![],
+            overflow: OverflowPolicy::Skip,
+        };
+        let june = DateTime::<Utc>::from_str("2023-06-01T00:00:00Z").unwrap();
+        assert!(f.contains(&june));
+
+        let july = DateTime::<Utc>::from_str("2023-07-01T00:00:00Z").unwrap();
+        assert!(!f.contains(&july));
+    }
+
+    #[test]
+    fn every_20th_monday_of_the_year() {
+        let f = Frequency::Yearly {
+            interval: NonZeroU32::new(1).unwrap(),
+            by_month: vec![],
+            by_monthly_date: vec![],
+            nth_weekdays: vec![NthWeekday::new(Weekday::Mon, 20)],
+            by_week_number: vec![],
+            by_time: vec![],
+            overflow: OverflowPolicy::Skip,
+        };
+        let date = DateTime::<Utc>::from_str("2023-01-01T09:00:00Z").unwrap();
+        let next_event = f.next_event(&date).unwrap();
+        assert_eq!(
+            next_event,
+            DateTime::<Utc>::from_str("2023-05-15T09:00:00Z").unwrap()
+        );
+
+        let next_event = f.next_event(&next_event).unwrap();
+        assert_eq!(
+            next_event,
+            DateTime::<Utc>::from_str("2024-05-13T09:00:00Z").unwrap()
+        );
+    }
+
+    #[test]
+    fn first_monday_of_the_20th_week() {
+        let f = Frequency::Yearly {
+            interval: NonZeroU32::new(1).unwrap(),
+            by_month: vec![],
+            by_monthly_date: vec![],
+            nth_weekdays: vec![NthWeekday::new(Weekday::Mon, 1)],
+            by_week_number: vec![20],
+            by_time: vec![],
+            overflow: OverflowPolicy::Skip,
+        };
+        let date = DateTime::<Utc>::from_str("2023-01-01T00:00:00Z").unwrap();
+        let next_event = f.next_event(&date).unwrap();
+        assert_eq!(
+            next_event,
+            DateTime::<Utc>::from_str("2023-05-15T00:00:00Z").unwrap()
+        );
+        assert!(f.contains(&next_event));
+
+        let next_event = f.next_event(&next_event).unwrap();
+        assert_eq!(
+            next_event,
+            DateTime::<Utc>::from_str("2024-05-13T00:00:00Z").unwrap()
+        );
+    }
+
+    #[test]
+    fn week_number_and_monthly_date_combined() {
+        let f = Frequency::Yearly {
+            interval: NonZeroU32::new(1).unwrap(),
+            by_month: vec![],
+            by_monthly_date: vec![
+                MonthlyDate {
+                    month: Month::January,
+                    day: 2,
+                },
+                MonthlyDate {
+                    month: Month::May,
+                    day: 15,
+                },
+            ],
+            nth_weekdays: vec![NthWeekday::new(Weekday::Mon, 20)],
+            by_week_number: vec![],
+            by_time: vec![],
+            overflow: OverflowPolicy::Skip,
+        };
+        let date = DateTime::<Utc>::from_str("2023-01-01T00:00:00Z").unwrap();
+        let next_event = f.next_event(&date).unwrap();
+        assert_eq!(
+            next_event,
+            DateTime::<Utc>::from_str("2023-05-15T00:00:00Z").unwrap()
+        );
+        let not_monday = DateTime::<Utc>::from_str("2023-01-02T00:00:00Z").unwrap();
+        assert!(!f.contains(&not_monday));
+    }
+
+    #[test]
+    fn same_date_as_start() {
+        let start = DateTime::<Utc>::from_str("2020-06-12T10:00:00Z").unwrap();
+        let f = Frequency::yearly_same_date_as_start(NonZeroU32::new(2).unwrap(), &start);
+        assert_eq!(
+            f.by_monthly_date(),
+            [MonthlyDate {
+                month: Month::June,
+                day: 12,
+            }]
+        );
+        assert!(f.is_valid().is_ok());
+        assert_eq!(
+            f.next_event(&start).unwrap(),
+            DateTime::<Utc>::from_str("2022-06-12T10:00:00Z").unwrap()
+        );
+    }
+
+    #[test]
+    fn same_date_as_a_start_on_a_leap_day() {
+        let start = DateTime::<Utc>::from_str("2024-02-29T00:00:00Z").unwrap();
+        let f = Frequency::yearly_same_date_as_start(NonZeroU32::new(1).unwrap(), &start);
+        assert_eq!(
+            f.next_event(&start).unwrap(),
+            DateTime::<Utc>::from_str("2028-02-29T00:00:00Z").unwrap()
+        );
+        let Frequency::Yearly {
+            interval,
+            by_month,
+            by_monthly_date,
+            nth_weekdays,
+            by_week_number,
+            ..
+        } = f
+        else {
+            panic!("Expected a yearly frequency");
+        };
+        let clamped = Frequency::Yearly {
+            interval,
+            by_month,
+            by_monthly_date,
+            nth_weekdays,
+            by_week_number,
+            by_time: vec![],
+            overflow: OverflowPolicy::ClampToLastDay,
+        };
+        assert_eq!(
+            clamped.next_event(&start).unwrap(),
+            DateTime::<Utc>::from_str("2025-02-28T00:00:00Z").unwrap()
+        );
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::frequencies::{Frequency, NthWeekday, Time};
+    use crate::frequencies::{Frequency, NthWeekday, OverflowPolicy, Time};
     use chrono::{DateTime, Utc, Weekday};
+    use std::num::NonZeroU32;
     use std::str::FromStr;
 
     #[test]
     fn test_date_within_frequency() {
         let f = Frequency::Daily {
-            interval: 1,
+            interval: NonZeroU32::new(1).unwrap(),
+            by_day: vec![],
             by_time: vec![],
         };
         let date = DateTime::<Utc>::from_str("2023-01-01T00:00:00Z").unwrap();
@@ -679,7 +1422,8 @@ mod tests {
     #[test]
     fn not_within_frequency() {
         let f = Frequency::Daily {
-            interval: 1,
+            interval: NonZeroU32::new(1).unwrap(),
+            by_day: vec![],
             by_time: vec![Time::from_str("12:00:00").unwrap()],
         };
         let date = DateTime::<Utc>::from_str("2023-01-01T00:00:00Z").unwrap();
@@ -690,7 +1434,8 @@ mod tests {
     #[test]
     fn within_daily_frequency() {
         let f = Frequency::Daily {
-            interval: 1,
+            interval: NonZeroU32::new(1).unwrap(),
+            by_day: vec![],
             by_time: vec![],
         };
         let date_within_frequency = DateTime::<Utc>::from_str("2023-01-01T00:00:00Z").unwrap();
@@ -701,7 +1446,8 @@ mod tests {
     #[test]
     fn within_twice_a_day_frequency() {
         let f = Frequency::Daily {
-            interval: 1,
+            interval: NonZeroU32::new(1).unwrap(),
+            by_day: vec![],
             by_time: vec![
                 Time::from_str("12:00:00").unwrap(),
                 Time::from_str("18:00:00").unwrap(),
@@ -719,8 +1465,10 @@ mod tests {
     #[test]
     fn within_weekly_frequency() {
         let f = Frequency::Weekly {
-            interval: 1,
+            interval: NonZeroU32::new(1).unwrap(),
             by_day: vec![Weekday::Mon, Weekday::Wed],
+            by_time: vec![],
+            by_day_time: vec![],
         };
         let date_within_frequency = DateTime::<Utc>::from_str("2023-01-02T00:00:00Z").unwrap();
         let result = f.contains(&date_within_frequency);
@@ -734,8 +1482,10 @@ mod tests {
     #[test]
     fn within_weekly_by_day_frequency() {
         let f = Frequency::Weekly {
-            interval: 1,
+            interval: NonZeroU32::new(1).unwrap(),
             by_day: vec![Weekday::Mon, Weekday::Wed],
+            by_time: vec![],
+            by_day_time: vec![],
         };
         let monday = DateTime::<Utc>::from_str("2023-01-02T00:00:00Z").unwrap();
         let result = f.contains(&monday);
@@ -749,9 +1499,12 @@ mod tests {
     #[test]
     fn within_monthly_frequency() {
         let f = Frequency::Monthly {
-            interval: 1,
+            interval: NonZeroU32::new(1).unwrap(),
             by_month_day: vec![],
+            by_day: vec![],
             nth_weekdays: vec![],
+            by_time: vec![],
+            overflow: OverflowPolicy::Skip,
         };
         let date_within_frequency = DateTime::<Utc>::from_str("2023-01-15T00:00:00Z").unwrap();
         let result = f.contains(&date_within_frequency);
@@ -761,9 +1514,12 @@ mod tests {
     #[test]
     fn within_monthly_by_month_day() {
         let f = Frequency::Monthly {
-            interval: 1,
+            interval: NonZeroU32::new(1).unwrap(),
             by_month_day: vec![15],
+            by_day: vec![],
             nth_weekdays: vec![],
+            by_time: vec![],
+            overflow: OverflowPolicy::Skip,
         };
         let date_within_frequency = DateTime::<Utc>::from_str("2023-01-15T00:00:00Z").unwrap();
         let result = f.contains(&date_within_frequency);
@@ -777,12 +1533,15 @@ mod tests {
     #[test]
     fn within_monthly_by_day() {
         let f = Frequency::Monthly {
-            interval: 1,
+            interval: NonZeroU32::new(1).unwrap(),
             by_month_day: vec![],
+            by_day: vec![],
             nth_weekdays: vec![
                 NthWeekday::new(Weekday::Wed, 1),
                 NthWeekday::new(Weekday::Fri, 1),
             ],
+            by_time: vec![],
+            overflow: OverflowPolicy::Skip,
         };
         let wednesday = DateTime::<Utc>::from_str("2023-01-04T00:00:00Z").unwrap();
         let result = f.contains(&wednesday);
@@ -793,3 +1552,1277 @@ mod tests {
         assert!(!result);
     }
 }
+
+#[cfg(test)]
+mod frequency_equality {
+    use crate::frequencies::{Frequency, NthWeekday, OverflowPolicy};
+    use chrono::Weekday;
+    use std::collections::HashSet;
+    use std::num::NonZeroU32;
+    use std::str::FromStr;
+
+    #[test]
+    fn equal_frequencies() {
+        let parsed = Frequency::from_str("FREQ=MONTHLY;INTERVAL=1;BYDAY=1MO").unwrap();
+        let defined = Frequency::Monthly {
+            interval: NonZeroU32::new(1).unwrap(),
+            by_month_day: vec![],
+            by_day: vec![],
+            nth_weekdays: vec![NthWeekday::new(Weekday::Mon, 1)],
+            by_time: vec![],
+            overflow: OverflowPolicy::Skip,
+        };
+        assert_eq!(parsed, defined);
+        assert_eq!(parsed.clone(), defined);
+
+        let mut frequencies = HashSet::new();
+        frequencies.insert(parsed);
+        assert!(!frequencies.insert(defined));
+        assert!(
+            frequencies.insert(Frequency::from_str("FREQ=MONTHLY;INTERVAL=2;BYDAY=1MO").unwrap())
+        );
+    }
+}
+
+#[cfg(test)]
+mod frequency_accessors {
+    use crate::frequencies::{FreqKind, Frequency, MonthlyDate, NthWeekday, OverflowPolicy};
+    use chrono::{Month, Weekday};
+    use std::str::FromStr;
+
+    #[test]
+    fn kind_and_interval() {
+        for (value, kind) in [
+            ("FREQ=SECONDLY;INTERVAL=3", FreqKind::Secondly),
+            ("FREQ=MINUTELY;INTERVAL=3", FreqKind::Minutely),
+            ("FREQ=HOURLY;INTERVAL=3", FreqKind::Hourly),
+            ("FREQ=DAILY;INTERVAL=3", FreqKind::Daily),
+            (
+                "FREQ=DAILY;INTERVAL=3;X-BUSINESS=TRUE",
+                FreqKind::BusinessDaily,
+            ),
+            ("FREQ=WEEKLY;INTERVAL=3", FreqKind::Weekly),
+            ("FREQ=MONTHLY;INTERVAL=3", FreqKind::Monthly),
+            ("FREQ=YEARLY;INTERVAL=3", FreqKind::Yearly),
+        ] {
+            let frequency = Frequency::from_str(value).unwrap();
+            assert_eq!(frequency.freq_kind(), kind);
+            assert_eq!(frequency.interval().get(), 3);
+            assert!(value.starts_with(&format!("FREQ={kind};")));
+        }
+    }
+
+    #[test]
+    fn rules_of_the_variant() {
+        let monthly = Frequency::from_str(
+            "FREQ=MONTHLY;INTERVAL=1;BYMONTHDAY=1,31;BYDAY=2TU;X-OVERFLOW=CLAMP",
+        )
+        .unwrap();
+        assert_eq!(monthly.by_month_day(), &[1, 31]);
+        assert_eq!(monthly.nth_weekdays(), &[NthWeekday::new(Weekday::Tue, 2)]);
+        assert_eq!(monthly.overflow(), OverflowPolicy::ClampToLastDay);
+        assert!(monthly.by_day().is_empty());
+        assert!(monthly.by_time().is_empty());
+
+        let yearly =
+            Frequency::from_str("FREQ=YEARLY;INTERVAL=1;X-BYMONTHDATE=FEB29;BYWEEKNO=10").unwrap();
+        assert_eq!(
+            yearly.by_monthly_date(),
+            &[MonthlyDate {
+                month: Month::February,
+                day: 29
+            }]
+        );
+        assert_eq!(yearly.by_week_number(), &[10]);
+        assert!(yearly.by_month_day().is_empty());
+
+        let hourly = Frequency::from_str("FREQ=HOURLY;INTERVAL=1;BYDAY=MO;BYTIME=09:30").unwrap();
+        assert_eq!(hourly.by_day(), &[Weekday::Mon]);
+        assert_eq!(hourly.by_time().len(), 1);
+        assert_eq!(hourly.overflow(), OverflowPolicy::Skip);
+    }
+}
+
+#[cfg(test)]
+mod out_of_range_dates {
+    use crate::frequencies::Frequency;
+    use chrono::{DateTime, Duration, Utc, Weekday};
+    use std::num::NonZeroU32;
+    use std::str::FromStr;
+
+    const RULES: [&str; 9] = [
+        "FREQ=SECONDLY;INTERVAL=1",
+        "FREQ=HOURLY;INTERVAL=1;BYDAY=MO;BYTIME=09:30",
+        "FREQ=DAILY;INTERVAL=1",
+        "FREQ=DAILY;INTERVAL=1;BYTIME=09:00,18:00",
+        "FREQ=WEEKLY;INTERVAL=1",
+        "FREQ=WEEKLY;INTERVAL=1;BYDAY=MO,FR",
+        "FREQ=MONTHLY;INTERVAL=1;BYMONTHDAY=1,31",
+        "FREQ=MONTHLY;INTERVAL=1;BYDAY=1MO",
+        "FREQ=YEARLY;INTERVAL=1;BYWEEKNO=1;BYDAY=MO",
+    ];
+
+    #[test]
+    fn no_next_event_after_the_last_date() {
+        let last_date = DateTime::<Utc>::MAX_UTC;
+        for rule in RULES {
+            let frequency = Frequency::from_str(rule).unwrap();
+            assert_eq!(frequency.next_event(&last_date), None, "{rule}");
+            let date = last_date - Duration::days(1);
+            assert!(
+                frequency.next_event(&date).is_none_or(|next| next > date),
+                "{rule}"
+            );
+        }
+    }
+
+    #[test]
+    fn no_next_event_with_huge_intervals() {
+        let date = DateTime::<Utc>::from_str("2023-01-01T00:00:00Z").unwrap();
+        for rule in RULES {
+            let rule = rule.replace("INTERVAL=1", &format!("INTERVAL={}", i32::MAX));
+            let frequency = Frequency::from_str(&rule).unwrap();
+            assert!(
+                frequency.next_event(&date).is_none_or(|next| next > date),
+                "{rule}"
+            );
+        }
+    }
+
+    #[test]
+    fn weeks_aligned_from_the_first_date() {
+        let first_date = DateTime::<Utc>::MIN_UTC;
+        let frequency = Frequency::Weekly {
+            interval: NonZeroU32::new(12).unwrap(),
+            by_day: vec![],
+            by_time: vec![],
+            by_day_time: vec![],
+        };
+        let next = frequency.next_event(&first_date).unwrap();
+        assert_eq!(next, first_date + Duration::weeks(12));
+        assert!(frequency.is_aligned(&first_date, &next));
+    }
+
+    #[test]
+    fn no_next_event_when_the_interval_overflows() {
+        let date = DateTime::<Utc>::from_str("2023-01-01T00:00:00Z").unwrap();
+        let frequency = Frequency::Weekly {
+            interval: NonZeroU32::MAX,
+            by_day: vec![Weekday::Mon],
+            by_time: vec![],
+            by_day_time: vec![],
+        };
+        assert_eq!(frequency.next_event(&date), None);
+        assert!(frequency.is_aligned(&date, &date));
+        assert_eq!(frequency.fixed_step(), None);
+
+        let frequency = Frequency::Hourly {
+            interval: NonZeroU32::MAX,
+            by_day: vec![],
+            by_time: vec![],
+            by_hour_range: None,
+        };
+        assert_eq!(frequency.next_event(&date), None);
+        assert_eq!(frequency.fixed_step(), None);
+    }
+}
+
+#[cfg(test)]
+mod normalization {
+    use crate::frequencies::{Frequency, OverflowPolicy, Time};
+    use crate::recurrences::Recurrence;
+    use chrono::{DateTime, Utc, Weekday};
+    use std::num::NonZeroU32;
+    use std::str::FromStr;
+
+    #[test]
+    fn unsorted_times_yield_the_events_in_order() {
+        let date = |value: &str| DateTime::<Utc>::from_str(value).unwrap();
+        let daily = Frequency::Daily {
+            interval: NonZeroU32::new(1).unwrap(),
+            by_day: vec![],
+            by_time: vec![
+                Time::from_str("18:00").unwrap(),
+                Time::from_str("09:00").unwrap(),
+                Time::from_str("18:00").unwrap(),
+            ],
+        };
+        assert_eq!(
+            daily.next_event(&date("2023-01-01T08:00:00Z")),
+            Some(date("2023-01-01T09:00:00Z"))
+        );
+        assert_eq!(
+            daily.next_event(&date("2023-01-01T18:00:00Z")),
+            Some(date("2023-01-02T09:00:00Z"))
+        );
+
+        let start = date("2023-01-01T00:00:00Z");
+        let expected = vec![
+            date("2023-01-01T09:00:00Z"),
+            date("2023-01-01T18:00:00Z"),
+            date("2023-01-02T09:00:00Z"),
+            date("2023-01-02T18:00:00Z"),
+        ];
+        let recurrence = Recurrence::new(daily.clone(), start, None, None).unwrap();
+        assert_eq!(recurrence.frequency.by_time().len(), 2);
+        assert_eq!(recurrence.iter().take(4).collect::<Vec<_>>(), expected);
+        let recurrence = Recurrence::from_engine(daily, start, None, None).unwrap();
+        assert_eq!(recurrence.frequency.by_time().len(), 2);
+        assert_eq!(recurrence.iter().take(4).collect::<Vec<_>>(), expected);
+    }
+
+    #[test]
+    fn parsed_values_are_sorted_without_duplicates() {
+        let weekly = Frequency::from_str("FREQ=WEEKLY;INTERVAL=1;BYDAY=MO,MO,SU,WE").unwrap();
+        assert_eq!(weekly.by_day(), &[Weekday::Mon, Weekday::Wed, Weekday::Sun]);
+        assert_eq!(weekly.to_string(), "FREQ=WEEKLY;INTERVAL=1;BYDAY=MO,WE,SU");
+
+        let monthly = Frequency::from_str("FREQ=MONTHLY;INTERVAL=1;BYMONTHDAY=15,1,15").unwrap();
+        assert_eq!(monthly.by_month_day(), &[1, 15]);
+
+        let daily = Frequency::from_str("FREQ=DAILY;INTERVAL=1;BYTIME=18:00,09:00,18:00").unwrap();
+        assert_eq!(
+            daily.by_time(),
+            &[
+                Time::from_str("09:00").unwrap(),
+                Time::from_str("18:00").unwrap()
+            ]
+        );
+
+        let yearly =
+            Frequency::from_str("FREQ=YEARLY;INTERVAL=1;BYWEEKNO=10,-1,10;BYDAY=FR,MO").unwrap();
+        assert_eq!(yearly.by_week_number(), &[-1, 10]);
+    }
+
+    #[test]
+    fn same_rules_in_another_order_are_equal() {
+        let a = Frequency::from_str("FREQ=MONTHLY;INTERVAL=1;BYDAY=2TU,1MO").unwrap();
+        let b = Frequency::from_str("FREQ=MONTHLY;INTERVAL=1;BYDAY=1MO,2TU,1MO").unwrap();
+        assert_eq!(a, b);
+        assert_eq!(a.to_string(), b.to_string());
+    }
+
+    #[test]
+    fn new_recurrences_are_normalized() {
+        let frequency = Frequency::Monthly {
+            interval: NonZeroU32::new(1).unwrap(),
+            by_month_day: vec![20, 5, 20],
+            by_day: vec![],
+            nth_weekdays: vec![],
+            by_time: vec![],
+            overflow: OverflowPolicy::Skip,
+        };
+        let start = DateTime::<Utc>::from_str("2023-01-01T09:00:00Z").unwrap();
+        let recurrence = Recurrence::new(frequency, start, None, None).unwrap();
+        assert_eq!(recurrence.frequency.by_month_day(), &[5, 20]);
+        let events: Vec<_> = recurrence.iter().take(4).collect();
+        assert_eq!(
+            events,
+            vec![
+                DateTime::<Utc>::from_str("2023-01-05T09:00:00Z").unwrap(),
+                DateTime::<Utc>::from_str("2023-01-20T09:00:00Z").unwrap(),
+                DateTime::<Utc>::from_str("2023-02-05T09:00:00Z").unwrap(),
+                DateTime::<Utc>::from_str("2023-02-20T09:00:00Z").unwrap(),
+            ]
+        );
+    }
+
+    #[test]
+    fn duplicated_weekdays_yield_every_event_once() {
+        let weekly = Frequency::Weekly {
+            interval: NonZeroU32::new(1).unwrap(),
+            by_day: vec![Weekday::Sun, Weekday::Mon, Weekday::Sun],
+            by_time: vec![],
+            by_day_time: vec![],
+        };
+        let start = DateTime::<Utc>::from_str("2023-01-02T09:00:00Z").unwrap();
+        let recurrence = Recurrence::new(weekly, start, None, None).unwrap();
+        let events: Vec<_> = recurrence.iter().take(3).collect();
+        assert_eq!(
+            events,
+            vec![
+                DateTime::<Utc>::from_str("2023-01-02T09:00:00Z").unwrap(),
+                DateTime::<Utc>::from_str("2023-01-08T09:00:00Z").unwrap(),
+                DateTime::<Utc>::from_str("2023-01-09T09:00:00Z").unwrap(),
+            ]
+        );
+        assert_eq!(recurrence.frequency.by_day(), &[Weekday::Mon, Weekday::Sun]);
+    }
+}
+
+#[cfg(test)]
+mod time_policies {
+    use crate::frequencies::{Frequency, ParseOptions, TimePolicy};
+    use chrono::{
+        DateTime, FixedOffset, LocalResult, NaiveDate, NaiveDateTime, NaiveTime, Offset, TimeZone,
+        Utc,
+    };
+    use std::str::FromStr;
+
+    /// A time zone one hour ahead of UTC in winter and two hours ahead in summer, with
+    /// the changes of 2023: 02:00 doesn't exist on March 26th and exists twice on October 29th.
+    #[derive(Debug, Clone, Copy)]
+    struct CentralEurope;
+
+    impl CentralEurope {
+        fn offset_at(utc: &NaiveDateTime) -> FixedOffset {
+            let summer_start = NaiveDate::from_ymd_opt(2023, 3, 26)
+                .unwrap()
+                .and_hms_opt(1, 0, 0)
+                .unwrap();
+            let summer_end = NaiveDate::from_ymd_opt(2023, 10, 29)
+                .unwrap()
+                .and_hms_opt(1, 0, 0)
+                .unwrap();
+            let hours = if *utc >= summer_start && *utc < summer_end {
+                2
+            } else {
+                1
+            };
+            FixedOffset::east_opt(hours * 3600).unwrap()
+        }
+    }
+
+    impl TimeZone for CentralEurope {
+        type Offset = FixedOffset;
+
+        fn from_offset(_: &FixedOffset) -> Self {
+            CentralEurope
+        }
+
+        fn offset_from_local_date(&self, local: &NaiveDate) -> LocalResult<FixedOffset> {
+            self.offset_from_local_datetime(&local.and_time(NaiveTime::MIN))
+        }
+
+        fn offset_from_local_datetime(&self, local: &NaiveDateTime) -> LocalResult<FixedOffset> {
+            let offsets: Vec<FixedOffset> = [1, 2]
+                .into_iter()
+                .map(|hours| FixedOffset::east_opt(hours * 3600).unwrap())
+                .filter(|offset| Self::offset_at(&(*local - *offset)) == *offset)
+                .collect();
+            match offsets[..] {
+                [offset] => LocalResult::Single(offset),
+                [summer, winter] => LocalResult::Ambiguous(winter, summer),
+                _ => LocalResult::None,
+            }
+        }
+
+        fn offset_from_utc_date(&self, utc: &NaiveDate) -> FixedOffset {
+            Self::offset_at(&utc.and_hms_opt(0, 0, 0).unwrap())
+        }
+
+        fn offset_from_utc_datetime(&self, utc: &NaiveDateTime) -> FixedOffset {
+            Self::offset_at(utc)
+        }
+    }
+
+    fn local(value: &str) -> NaiveDateTime {
+        NaiveDateTime::from_str(value).unwrap()
+    }
+
+    fn utc(value: &str) -> DateTime<Utc> {
+        DateTime::<Utc>::from_str(value).unwrap()
+    }
+
+    fn instants(result: LocalResult<DateTime<CentralEurope>>) -> Vec<DateTime<Utc>> {
+        match result {
+            LocalResult::Single(date) => vec![date.with_timezone(&Utc)],
+            LocalResult::Ambiguous(earliest, latest) => {
+                vec![earliest.with_timezone(&Utc), latest.with_timezone(&Utc)]
+            }
+            LocalResult::None => vec![],
+        }
+    }
+
+    #[test]
+    fn times_that_dont_exist() {
+        let time = local("2023-03-26T02:30:00");
+        assert!(instants(TimePolicy::Skip.resolve(&CentralEurope, &time)).is_empty());
+        // 02:30 in winter time is 03:30 in summer time
+        let shifted = vec![utc("2023-03-26T01:30:00Z")];
+        assert_eq!(
+            instants(TimePolicy::ShiftForward.resolve(&CentralEurope, &time)),
+            shifted
+        );
+        assert_eq!(
+            instants(TimePolicy::Both.resolve(&CentralEurope, &time)),
+            shifted
+        );
+        let shifted = TimePolicy::ShiftForward
+            .resolve(&CentralEurope, &time)
+            .single()
+            .unwrap();
+        assert_eq!(shifted.naive_local(), local("2023-03-26T03:30:00"));
+        assert_eq!(shifted.offset().fix(), FixedOffset::east_opt(7200).unwrap());
+    }
+
+    #[test]
+    fn times_that_exist_twice() {
+        let time = local("2023-10-29T02:30:00");
+        let first = vec![utc("2023-10-29T00:30:00Z")];
+        assert_eq!(
+            instants(TimePolicy::Skip.resolve(&CentralEurope, &time)),
+            first
+        );
+        assert_eq!(
+            instants(TimePolicy::ShiftForward.resolve(&CentralEurope, &time)),
+            first
+        );
+        assert_eq!(
+            instants(TimePolicy::Both.resolve(&CentralEurope, &time)),
+            vec![utc("2023-10-29T00:30:00Z"), utc("2023-10-29T01:30:00Z")]
+        );
+    }
+
+    #[test]
+    fn times_that_exist_once() {
+        let time = local("2023-06-01T02:30:00");
+        for policy in [TimePolicy::Skip, TimePolicy::ShiftForward, TimePolicy::Both] {
+            assert_eq!(
+                instants(policy.resolve(&CentralEurope, &time)),
+                vec![utc("2023-06-01T00:30:00Z")]
+            );
+        }
+    }
+
+    #[test]
+    fn time_policy_is_not_part_of_the_rules() {
+        let value = "FREQ=DAILY;INTERVAL=1;BYHOUR=2;BYMINUTE=30;X-TIMEPOLICY=SHIFT";
+        let daily = Frequency::from_str(value).unwrap();
+        assert_eq!(
+            daily.to_string(),
+            "FREQ=DAILY;INTERVAL=1;BYHOUR=2;BYMINUTE=30"
+        );
+        assert!(Frequency::from_str_with(value, ParseOptions::strict()).is_err());
+    }
+}
+
+#[cfg(test)]
+mod business_daily_frequency {
+    use crate::frequencies::{FreqKind, Frequency, ParseOptions};
+    use crate::test_utils::date;
+    use crate::Recurrence;
+    use std::num::NonZeroU32;
+    use std::str::FromStr;
+
+    #[test]
+    fn skips_the_weekends() {
+        let frequency = Frequency::BusinessDaily {
+            interval: NonZeroU32::new(1).unwrap(),
+        };
+        let friday = date("2023-01-06T09:00:00Z");
+        assert_eq!(
+            frequency.next_event(&friday),
+            Some(date("2023-01-09T09:00:00Z"))
+        );
+        assert_eq!(
+            frequency.next_event(&date("2023-01-07T09:00:00Z")),
+            Some(date("2023-01-09T09:00:00Z"))
+        );
+        assert!(frequency.contains(&friday));
+        assert!(!frequency.contains(&date("2023-01-08T09:00:00Z")));
+    }
+
+    #[test]
+    fn counts_the_interval_in_business_days() {
+        let recurrence = Recurrence::from_str(
+            "FREQ=DAILY;INTERVAL=2;X-BUSINESS=TRUE;DTSTART=2023-01-05T09:00:00Z;DTEND=2023-01-20T09:00:00Z",
+        )
+        .unwrap();
+        let events: Vec<_> = recurrence.iter().collect();
+        assert_eq!(
+            events,
+            vec![
+                date("2023-01-05T09:00:00Z"),
+                date("2023-01-09T09:00:00Z"),
+                date("2023-01-11T09:00:00Z"),
+                date("2023-01-13T09:00:00Z"),
+                date("2023-01-17T09:00:00Z"),
+                date("2023-01-19T09:00:00Z"),
+            ]
+        );
+        for event in &events {
+            assert!(recurrence.contains(event));
+        }
+        assert!(!recurrence.contains(&date("2023-01-10T09:00:00Z")));
+        assert_eq!(
+            recurrence.next_from(&date("2023-01-14T00:00:00Z")),
+            Some(date("2023-01-17T09:00:00Z"))
+        );
+    }
+
+    #[test]
+    fn starts_on_the_next_business_day() {
+        let recurrence = Recurrence::from_str(
+            "FREQ=DAILY;INTERVAL=3;X-BUSINESS=TRUE;DTSTART=2023-01-07T09:00:00Z",
+        )
+        .unwrap();
+        let events: Vec<_> = recurrence.iter().take(3).collect();
+        assert_eq!(
+            events,
+            vec![
+                date("2023-01-09T09:00:00Z"),
+                date("2023-01-12T09:00:00Z"),
+                date("2023-01-17T09:00:00Z"),
+            ]
+        );
+    }
+
+    #[test]
+    fn serialization() {
+        let value = "FREQ=DAILY;INTERVAL=2;X-BUSINESS=TRUE";
+        let frequency = Frequency::from_str(value).unwrap();
+        assert_eq!(
+            frequency,
+            Frequency::BusinessDaily {
+                interval: NonZeroU32::new(2).unwrap()
+            }
+        );
+        assert_eq!(frequency.freq_kind(), FreqKind::BusinessDaily);
+        assert_eq!(frequency.to_string(), value);
+        assert!(Frequency::from_str_with(value, ParseOptions::strict()).is_ok());
+
+        let daily = Frequency::from_str("FREQ=DAILY;INTERVAL=2;X-BUSINESS=FALSE").unwrap();
+        assert_eq!(daily.freq_kind(), FreqKind::Daily);
+        assert!(Frequency::from_str("FREQ=DAILY;INTERVAL=2;X-BUSINESS=YES").is_err());
+        assert!(Frequency::from_str("FREQ=DAILY;INTERVAL=0;X-BUSINESS=TRUE").is_err());
+    }
+}
+
+#[cfg(test)]
+mod yearly_by_month {
+    use crate::frequencies::{Frequency, NthWeekday, OverflowPolicy};
+    use crate::test_utils::date;
+    use crate::Recurrence;
+    use chrono::{Month, Weekday};
+    use std::num::NonZeroU32;
+    use std::str::FromStr;
+
+    #[test]
+    fn repeats_the_start_day_in_every_month() {
+        let recurrence = Recurrence::from_str(
+            "FREQ=YEARLY;INTERVAL=1;BYMONTH=1,4,9;DTSTART=2023-01-01T09:00:00Z;DTEND=2024-04-01T09:00:00Z",
+        )
+        .unwrap();
+        let events: Vec<_> = recurrence.iter().collect();
+        assert_eq!(
+            events,
+            vec![
+                date("2023-01-01T09:00:00Z"),
+                date("2023-04-01T09:00:00Z"),
+                date("2023-09-01T09:00:00Z"),
+                date("2024-01-01T09:00:00Z"),
+                date("2024-04-01T09:00:00Z"),
+            ]
+        );
+        for event in &events {
+            assert!(recurrence.contains(event));
+        }
+        assert!(!recurrence.contains(&date("2023-02-01T09:00:00Z")));
+        assert!(!recurrence.contains(&date("2023-04-02T09:00:00Z")));
+    }
+
+    #[test]
+    fn skips_the_months_without_the_start_day() {
+        let frequency = Frequency::Yearly {
+            interval: NonZeroU32::new(1).unwrap(),
+            by_month: vec![Month::January, Month::February, Month::March],
+            by_monthly_date: vec![],
+            nth_weekdays: vec![],
+            by_week_number: vec![],
+            by_time: vec![],
+            overflow: OverflowPolicy::ClampToLastDay,
+        };
+        assert_eq!(
+            frequency.next_event(&date("2023-01-31T00:00:00Z")),
+            Some(date("2023-03-31T00:00:00Z"))
+        );
+    }
+
+    #[test]
+    fn counts_the_nth_weekdays_within_the_months() {
+        let thanksgiving = Recurrence::from_str(
+            "FREQ=YEARLY;INTERVAL=1;BYMONTH=11;BYDAY=4TH;DTSTART=2023-01-01T12:00:00Z",
+        )
+        .unwrap();
+        let events: Vec<_> = thanksgiving.iter().take(2).collect();
+        assert_eq!(
+            events,
+            vec![date("2023-11-23T12:00:00Z"), date("2024-11-28T12:00:00Z")]
+        );
+        assert!(thanksgiving.contains(&date("2025-11-27T12:00:00Z")));
+        assert!(!thanksgiving.contains(&date("2025-11-20T12:00:00Z")));
+
+        let frequency = Frequency::Yearly {
+            interval: NonZeroU32::new(2).unwrap(),
+            by_month: vec![Month::March, Month::June],
+            by_monthly_date: vec![],
+            nth_weekdays: vec![NthWeekday::new(Weekday::Mon, 1)],
+            by_week_number: vec![],
+            by_time: vec![],
+            overflow: OverflowPolicy::Skip,
+        };
+        assert_eq!(
+            frequency.next_event(&date("2023-03-06T00:00:00Z")),
+            Some(date("2023-06-05T00:00:00Z"))
+        );
+        assert_eq!(
+            frequency.next_event(&date("2023-06-05T00:00:00Z")),
+            Some(date("2025-03-03T00:00:00Z"))
+        );
+    }
+
+    #[test]
+    fn restricts_the_week_numbers_to_the_months() {
+        let recurrence = Recurrence::from_str(
+            "FREQ=YEARLY;INTERVAL=1;BYMONTH=1;BYWEEKNO=1,5;BYDAY=MO;DTSTART=2023-01-01T00:00:00Z",
+        )
+        .unwrap();
+        let events: Vec<_> = recurrence.iter().take(2).collect();
+        assert_eq!(
+            events,
+            vec![date("2023-01-02T00:00:00Z"), date("2023-01-30T00:00:00Z")]
+        );
+    }
+
+    #[test]
+    fn serialization() {
+        for value in [
+            "FREQ=YEARLY;INTERVAL=1;BYMONTH=1,4,9",
+            "FREQ=YEARLY;INTERVAL=1;BYMONTH=11;BYDAY=4TH",
+            "FREQ=YEARLY;INTERVAL=1;BYMONTH=1,6;X-BYMONTHDATE=JAN15,JUN1",
+        ] {
+            let frequency = Frequency::from_str(value).unwrap();
+            assert_eq!(frequency.to_string(), value);
+        }
+        let frequency = Frequency::from_str("FREQ=YEARLY;INTERVAL=1;BYMONTH=9,1").unwrap();
+        assert_eq!(frequency.by_month(), &[Month::January, Month::September]);
+        let dates = Frequency::from_str("FREQ=YEARLY;INTERVAL=1;BYMONTH=1,6;BYMONTHDAY=1").unwrap();
+        assert!(dates.by_month().is_empty());
+        assert_eq!(dates.by_monthly_date().len(), 2);
+    }
+}
+
+#[cfg(test)]
+mod weekly_by_day_time {
+    use crate::frequencies::{Frequency, InvalidFrequency, ParseOptions, Time};
+    use crate::test_utils::date;
+    use crate::Recurrence;
+    use chrono::{DateTime, Utc, Weekday};
+    use std::num::NonZeroU32;
+    use std::str::FromStr;
+
+    fn day_time(weekday: Weekday, time: &str) -> (Weekday, Time) {
+        (weekday, Time::from_str(time).unwrap())
+    }
+
+    #[test]
+    fn yields_every_day_at_its_time() {
+        let frequency = Frequency::Weekly {
+            interval: NonZeroU32::new(2).unwrap(),
+            by_day: vec![],
+            by_time: vec![],
+            by_day_time: vec![
+                day_time(Weekday::Mon, "09:00"),
+                day_time(Weekday::Thu, "14:00"),
+            ],
+        };
+        assert!(frequency.is_valid().is_ok());
+        let recurrence = Recurrence::new(
+            frequency,
+            date("2023-01-02T00:00:00Z"),
+            Some(date("2023-01-31T00:00:00Z")),
+            None,
+        )
+        .unwrap();
+        let events: Vec<DateTime<Utc>> = recurrence.iter().collect();
+        assert_eq!(
+            events,
+            vec![
+                date("2023-01-02T09:00:00Z"),
+                date("2023-01-05T14:00:00Z"),
+                date("2023-01-16T09:00:00Z"),
+                date("2023-01-19T14:00:00Z"),
+                date("2023-01-30T09:00:00Z"),
+            ]
+        );
+        assert!(recurrence.contains(&date("2023-01-16T09:00:00Z")));
+        assert!(!recurrence.contains(&date("2023-01-16T14:00:00Z")));
+        assert!(!recurrence.contains(&date("2023-01-09T09:00:00Z")));
+    }
+
+    #[test]
+    fn starts_in_the_middle_of_the_week() {
+        let recurrence = Recurrence::from_str(
+            "FREQ=WEEKLY;INTERVAL=1;DTSTART=2023-01-05T15:00:00Z;X-BYDAYTIME=MO09:00,TH14:00,TH16:30",
+        )
+        .unwrap();
+        let events: Vec<DateTime<Utc>> = recurrence.iter().take(3).collect();
+        assert_eq!(
+            events,
+            vec![
+                date("2023-01-05T16:30:00Z"),
+                date("2023-01-09T09:00:00Z"),
+                date("2023-01-12T14:00:00Z"),
+            ]
+        );
+    }
+
+    #[test]
+    fn serializes_the_days_and_times() {
+        let value = "FREQ=WEEKLY;INTERVAL=1;X-BYDAYTIME=TH14:00,MO09:00:30,MO09:00:30";
+        let frequency = Frequency::from_str(value).unwrap();
+        assert_eq!(
+            frequency.by_day_time(),
+            &[
+                day_time(Weekday::Mon, "09:00:30"),
+                day_time(Weekday::Thu, "14:00")
+            ]
+        );
+        assert_eq!(
+            frequency.to_string(),
+            "FREQ=WEEKLY;INTERVAL=1;X-BYDAYTIME=MO09:00:30,TH14:00"
+        );
+        assert!(Frequency::from_str_with(value, ParseOptions::strict()).is_err());
+        assert!(Frequency::from_str("FREQ=WEEKLY;INTERVAL=1;X-BYDAYTIME=MO").is_err());
+        assert!(Frequency::from_str("FREQ=WEEKLY;INTERVAL=1;X-BYDAYTIME=XX09:00").is_err());
+        assert!(Frequency::from_str("FREQ=WEEKLY;INTERVAL=1;X-BYDAYTIME=MO25:00").is_err());
+    }
+
+    #[test]
+    fn cannot_be_combined_with_days_without_times() {
+        let value = "FREQ=WEEKLY;INTERVAL=1;BYDAY=TU;X-BYDAYTIME=MO09:00";
+        let frequency = Frequency::from_str(value).unwrap();
+        assert!(matches!(
+            frequency.is_valid(),
+            Err(InvalidFrequency::Day { .. })
+        ));
+        let value = format!("{value};DTSTART=2023-01-02T00:00:00Z");
+        assert!(Recurrence::from_str(&value).is_err());
+
+        let frequency = Frequency::Weekly {
+            interval: NonZeroU32::new(1).unwrap(),
+            by_day: vec![],
+            by_time: vec![],
+            by_day_time: vec![
+                day_time(Weekday::Mon, "09:00"),
+                day_time(Weekday::Mon, "09:00"),
+            ],
+        };
+        assert!(frequency.is_valid().is_err());
+        assert!(frequency.normalized().is_valid().is_ok());
+    }
+}
+
+#[cfg(test)]
+mod weekly_by_time {
+    use crate::frequencies::{Frequency, InvalidFrequency, Time};
+    use crate::test_utils::date;
+    use crate::Recurrence;
+    use chrono::{DateTime, Utc, Weekday};
+    use std::num::NonZeroU32;
+    use std::str::FromStr;
+
+    fn times(values: &[&str]) -> Vec<Time> {
+        values
+            .iter()
+            .map(|value| Time::from_str(value).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn yields_every_day_at_every_time() {
+        let recurrence = Recurrence::from_str(
+            "FREQ=WEEKLY;INTERVAL=1;BYDAY=MO,WE;BYTIME=09:00,17:00;DTSTART=2023-01-02T12:00:00Z",
+        )
+        .unwrap();
+        let events: Vec<DateTime<Utc>> = recurrence.iter().take(5).collect();
+        assert_eq!(
+            events,
+            vec![
+                date("2023-01-02T17:00:00Z"),
+                date("2023-01-04T09:00:00Z"),
+                date("2023-01-04T17:00:00Z"),
+                date("2023-01-09T09:00:00Z"),
+                date("2023-01-09T17:00:00Z"),
+            ]
+        );
+        assert!(recurrence.contains(&date("2023-01-11T09:00:00Z")));
+        assert!(!recurrence.contains(&date("2023-01-11T12:00:00Z")));
+        assert!(!recurrence.contains(&date("2023-01-10T09:00:00Z")));
+    }
+
+    #[test]
+    fn uses_the_weekday_of_the_start_date_without_days() {
+        let frequency = Frequency::Weekly {
+            interval: NonZeroU32::new(2).unwrap(),
+            by_day: vec![],
+            by_time: times(&["18:30", "08:15"]),
+            by_day_time: vec![],
+        };
+        let recurrence = Recurrence::new(
+            frequency,
+            date("2023-01-03T10:00:00Z"),
+            Some(date("2023-01-31T00:00:00Z")),
+            None,
+        )
+        .unwrap();
+        let events: Vec<DateTime<Utc>> = recurrence.iter().collect();
+        assert_eq!(
+            events,
+            vec![
+                date("2023-01-03T18:30:00Z"),
+                date("2023-01-17T08:15:00Z"),
+                date("2023-01-17T18:30:00Z"),
+            ]
+        );
+        assert_eq!(recurrence.event_time(), None);
+    }
+
+    #[test]
+    fn serializes_the_times() {
+        let frequency =
+            Frequency::from_str("FREQ=WEEKLY;INTERVAL=1;BYDAY=WE,MO;BYTIME=17:00,09:00").unwrap();
+        assert_eq!(frequency.by_day(), &[Weekday::Mon, Weekday::Wed]);
+        assert_eq!(frequency.by_time(), times(&["09:00", "17:00"]));
+        assert_eq!(
+            frequency.to_string(),
+            "FREQ=WEEKLY;INTERVAL=1;BYDAY=MO,WE;BYHOUR=9,17;BYMINUTE=0"
+        );
+        assert_eq!(Frequency::from_str(&frequency.to_string()), Ok(frequency));
+
+        let frequency = Frequency::from_str("FREQ=WEEKLY;INTERVAL=1;BYTIME=09:00,17:30").unwrap();
+        assert_eq!(
+            frequency.to_string(),
+            "FREQ=WEEKLY;INTERVAL=1;BYTIME=09:00,17:30"
+        );
+    }
+
+    #[test]
+    fn cannot_be_combined_with_days_with_times() {
+        let frequency =
+            Frequency::from_str("FREQ=WEEKLY;INTERVAL=1;BYTIME=09:00;X-BYDAYTIME=MO10:00").unwrap();
+        assert!(matches!(
+            frequency.is_valid(),
+            Err(InvalidFrequency::Time { .. })
+        ));
+
+        let frequency = Frequency::Weekly {
+            interval: NonZeroU32::new(1).unwrap(),
+            by_day: vec![Weekday::Mon],
+            by_time: times(&["09:00", "09:00"]),
+            by_day_time: vec![],
+        };
+        assert!(frequency.is_valid().is_err());
+        assert!(frequency.normalized().is_valid().is_ok());
+        assert!(Frequency::from_str("FREQ=WEEKLY;INTERVAL=1;BYTIME=24:00")
+            .and_then(|frequency| frequency.is_valid())
+            .is_err());
+    }
+}
+
+#[cfg(test)]
+mod monthly_and_yearly_by_time {
+    use crate::frequencies::{Frequency, OverflowPolicy, Time};
+    use crate::test_utils::date;
+    use crate::Recurrence;
+    use chrono::{DateTime, Utc};
+    use std::num::NonZeroU32;
+    use std::str::FromStr;
+
+    #[test]
+    fn monthly_days_at_every_time() {
+        let recurrence = Recurrence::from_str(
+            "FREQ=MONTHLY;INTERVAL=1;BYMONTHDAY=1;BYHOUR=8,20;DTSTART=2023-01-01T12:00:00Z",
+        )
+        .unwrap();
+        let events: Vec<DateTime<Utc>> = recurrence.iter().take(4).collect();
+        assert_eq!(
+            events,
+            vec![
+                date("2023-01-01T20:00:00Z"),
+                date("2023-02-01T08:00:00Z"),
+                date("2023-02-01T20:00:00Z"),
+                date("2023-03-01T08:00:00Z"),
+            ]
+        );
+        assert!(recurrence.contains(&date("2023-05-01T08:00:00Z")));
+        assert!(!recurrence.contains(&date("2023-05-01T12:00:00Z")));
+        assert!(!recurrence.contains(&date("2023-05-02T08:00:00Z")));
+        assert_eq!(recurrence.event_time(), None);
+    }
+
+    #[test]
+    fn monthly_nth_weekdays_at_every_time() {
+        let recurrence = Recurrence::from_str(
+            "FREQ=MONTHLY;INTERVAL=3;BYDAY=2TU;BYTIME=18:00,09:00;DTSTART=2023-01-01T00:00:00Z",
+        )
+        .unwrap();
+        let events: Vec<DateTime<Utc>> = recurrence
+            .between(&date("2023-05-01T00:00:00Z"), &date("2023-12-31T00:00:00Z"))
+            .collect();
+        assert_eq!(
+            events,
+            vec![
+                date("2023-07-11T09:00:00Z"),
+                date("2023-07-11T18:00:00Z"),
+                date("2023-10-10T09:00:00Z"),
+                date("2023-10-10T18:00:00Z"),
+            ]
+        );
+        let first_events: Vec<DateTime<Utc>> = recurrence.iter().take(3).collect();
+        assert_eq!(
+            first_events,
+            vec![
+                date("2023-01-10T09:00:00Z"),
+                date("2023-01-10T18:00:00Z"),
+                date("2023-04-11T09:00:00Z"),
+            ]
+        );
+    }
+
+    #[test]
+    fn yearly_dates_at_every_time() {
+        let frequency = Frequency::Yearly {
+            interval: NonZeroU32::new(1).unwrap(),
+            by_month: vec![],
+            by_monthly_date: vec![],
+            nth_weekdays: vec![],
+            by_week_number: vec![],
+            by_time: vec![
+                Time::from_str("20:00").unwrap(),
+                Time::from_str("08:00").unwrap(),
+            ],
+            overflow: OverflowPolicy::Skip,
+        };
+        let recurrence =
+            Recurrence::new(frequency, date("2023-03-05T12:00:00Z"), None, None).unwrap();
+        let events: Vec<DateTime<Utc>> = recurrence.iter().take(3).collect();
+        assert_eq!(
+            events,
+            vec![
+                date("2023-03-05T20:00:00Z"),
+                date("2024-03-05T08:00:00Z"),
+                date("2024-03-05T20:00:00Z"),
+            ]
+        );
+        assert!(recurrence.contains(&date("2025-03-05T08:00:00Z")));
+        assert!(!recurrence.contains(&date("2025-03-05T12:00:00Z")));
+    }
+
+    #[test]
+    fn serializes_the_times() {
+        for value in [
+            "FREQ=MONTHLY;INTERVAL=1;BYMONTHDAY=1;BYHOUR=8,20;BYMINUTE=0",
+            "FREQ=MONTHLY;INTERVAL=1;BYDAY=1MO;BYTIME=08:00,20:30;X-OVERFLOW=CLAMP",
+            "FREQ=YEARLY;INTERVAL=1;BYMONTH=6;BYMONTHDAY=12;BYHOUR=10;BYMINUTE=0",
+        ] {
+            let frequency = Frequency::from_str(value).unwrap();
+            assert_eq!(frequency.to_string(), value);
+        }
+        let frequency =
+            Frequency::from_str("FREQ=YEARLY;INTERVAL=1;BYTIME=20:00,08:00,08:00").unwrap();
+        assert_eq!(
+            frequency.by_time(),
+            &[
+                Time::from_str("08:00").unwrap(),
+                Time::from_str("20:00").unwrap()
+            ]
+        );
+        assert!(Frequency::from_str("FREQ=MONTHLY;INTERVAL=1;BYHOUR=25")
+            .and_then(|frequency| frequency.is_valid())
+            .is_err());
+    }
+}
+
+#[cfg(test)]
+mod monthly_by_month_day_and_weekday {
+    use crate::frequencies::{Frequency, NthWeekday, OverflowPolicy};
+    use crate::test_utils::date;
+    use crate::Recurrence;
+    use chrono::{DateTime, Utc, Weekday};
+    use std::num::NonZeroU32;
+    use std::str::FromStr;
+
+    #[test]
+    fn friday_the_13th() {
+        let recurrence = Recurrence::from_str(
+            "FREQ=MONTHLY;INTERVAL=1;BYMONTHDAY=13;BYDAY=FR;DTSTART=2023-01-13T12:00:00Z;DTEND=2024-12-31T00:00:00Z",
+        )
+        .unwrap();
+        let events: Vec<DateTime<Utc>> = recurrence.iter().collect();
+        assert_eq!(
+            events,
+            vec![
+                date("2023-01-13T12:00:00Z"),
+                date("2023-10-13T12:00:00Z"),
+                date("2024-09-13T12:00:00Z"),
+                date("2024-12-13T12:00:00Z"),
+            ]
+        );
+    }
+
+    #[test]
+    fn month_days_on_nth_weekdays() {
+        // The 2nd Monday of the month when it is between the 10th and the 12th
+        let frequency = Frequency::Monthly {
+            interval: NonZeroU32::new(1).unwrap(),
+            by_month_day: vec![10, 11, 12],
+            by_day: vec![],
+            nth_weekdays: vec![NthWeekday::new(Weekday::Mon, 2)],
+            by_time: vec![],
+            overflow: OverflowPolicy::Skip,
+        };
+        assert_eq!(
+            frequency.next_event(&date("2023-01-01T09:00:00Z")),
+            Some(date("2023-04-10T09:00:00Z"))
+        );
+        assert!(frequency.contains(&date("2023-04-10T09:00:00Z")));
+        assert!(!frequency.contains(&date("2023-04-11T09:00:00Z")));
+        assert!(!frequency.contains(&date("2023-01-09T09:00:00Z")));
+    }
+
+    #[test]
+    fn every_weekday_of_the_month() {
+        let recurrence = Recurrence::from_str(
+            "FREQ=MONTHLY;INTERVAL=2;BYDAY=TU,1FR;DTSTART=2023-01-01T09:00:00Z;DTEND=2023-03-10T00:00:00Z",
+        )
+        .unwrap();
+        let events: Vec<DateTime<Utc>> = recurrence.iter().collect();
+        assert_eq!(
+            events,
+            vec![
+                date("2023-01-03T09:00:00Z"),
+                date("2023-01-06T09:00:00Z"),
+                date("2023-01-10T09:00:00Z"),
+                date("2023-01-17T09:00:00Z"),
+                date("2023-01-24T09:00:00Z"),
+                date("2023-01-31T09:00:00Z"),
+                date("2023-03-03T09:00:00Z"),
+                date("2023-03-07T09:00:00Z"),
+            ]
+        );
+    }
+
+    #[test]
+    fn round_trips_the_weekdays() {
+        let rule = "FREQ=MONTHLY;INTERVAL=1;BYMONTHDAY=13;BYDAY=MO,FR,2SU";
+        let frequency =
+            Frequency::from_str("FREQ=MONTHLY;INTERVAL=1;BYDAY=FR,MO,2SU;BYMONTHDAY=13")
+                .unwrap()
+                .normalized();
+        assert_eq!(frequency.by_day(), &[Weekday::Mon, Weekday::Fri]);
+        assert_eq!(
+            frequency.nth_weekdays(),
+            &[NthWeekday::new(Weekday::Sun, 2)]
+        );
+        assert_eq!(frequency.to_string(), rule);
+        assert_eq!(Frequency::from_str(rule).unwrap(), frequency);
+    }
+
+    #[test]
+    fn repeated_weekdays_are_invalid() {
+        let frequency = Frequency::Monthly {
+            interval: NonZeroU32::new(1).unwrap(),
+            by_month_day: vec![13],
+            by_day: vec![Weekday::Fri, Weekday::Fri],
+            nth_weekdays: vec![],
+            by_time: vec![],
+            overflow: OverflowPolicy::Skip,
+        };
+        assert!(frequency.is_valid().is_err());
+    }
+
+    #[test]
+    fn month_days_that_are_never_on_the_weekdays() {
+        // The 1st Monday of the month is never after the 7th
+        let frequency = Frequency::Monthly {
+            interval: NonZeroU32::new(1).unwrap(),
+            by_month_day: vec![20],
+            by_day: vec![],
+            nth_weekdays: vec![NthWeekday::new(Weekday::Mon, 1)],
+            by_time: vec![],
+            overflow: OverflowPolicy::Skip,
+        };
+        assert_eq!(frequency.next_event(&date("2023-01-01T09:00:00Z")), None);
+    }
+}
+
+#[cfg(test)]
+mod quarterly_and_semi_annual {
+    use crate::frequencies::{Frequency, NthWeekday};
+    use crate::test_utils::date;
+    use crate::Recurrence;
+    use chrono::{DateTime, Utc, Weekday};
+    use std::str::FromStr;
+
+    #[test]
+    fn quarters_are_counted_from_the_start_month() {
+        let frequency = Frequency::quarterly(vec![1, 15], vec![]);
+        assert_eq!(
+            frequency,
+            Frequency::from_str("FREQ=MONTHLY;INTERVAL=3;BYMONTHDAY=1,15").unwrap()
+        );
+        // The quarter of the start date begins in February, its 1st is before the start
+        let start = date("2023-02-10T09:00:00Z");
+        let recurrence = Recurrence::new(frequency, start, None, None).unwrap();
+        let events: Vec<DateTime<Utc>> = recurrence.iter().take(4).collect();
+        assert_eq!(
+            events,
+            [
+                date("2023-02-15T09:00:00Z"),
+                date("2023-05-01T09:00:00Z"),
+                date("2023-05-15T09:00:00Z"),
+                date("2023-08-01T09:00:00Z"),
+            ]
+        );
+
+        // The events after a date in the middle of a quarter stay in the same months
+        assert_eq!(
+            recurrence.next_from(&date("2023-03-20T00:00:00Z")),
+            Some(date("2023-05-01T09:00:00Z"))
+        );
+        assert_eq!(
+            recurrence.next_from(&date("2031-12-31T00:00:00Z")),
+            Some(date("2032-02-01T09:00:00Z"))
+        );
+        let from = date("2030-01-01T00:00:00Z");
+        let to = date("2030-12-31T00:00:00Z");
+        let months: Vec<String> = recurrence
+            .between(&from, &to)
+            .map(|event| event.format("%m-%d").to_string())
+            .collect();
+        assert_eq!(
+            months,
+            ["02-01", "02-15", "05-01", "05-15", "08-01", "08-15", "11-01", "11-15"]
+        );
+    }
+
+    #[test]
+    fn half_years_on_nth_weekdays() {
+        let frequency = Frequency::semi_annual(vec![], vec![NthWeekday::new(Weekday::Mon, 1)]);
+        assert!(frequency.is_valid().is_ok());
+        let recurrence =
+            Recurrence::new(frequency, date("2023-01-01T08:00:00Z"), None, None).unwrap();
+        assert_eq!(
+            recurrence.next_from(&date("2023-01-03T00:00:00Z")),
+            Some(date("2023-07-03T08:00:00Z"))
+        );
+        assert_eq!(
+            recurrence.next_from(&date("2025-08-01T00:00:00Z")),
+            Some(date("2026-01-05T08:00:00Z"))
+        );
+    }
+
+    #[test]
+    fn quarters_on_the_day_of_the_start() {
+        let frequency = Frequency::quarterly(vec![], vec![]);
+        let recurrence =
+            Recurrence::new(frequency, date("2023-01-20T12:00:00Z"), None, None).unwrap();
+        assert_eq!(recurrence.nth(4), Some(date("2024-01-20T12:00:00Z")));
+    }
+}
+
+#[cfg(test)]
+mod sub_daily_by_hour_range {
+    use crate::frequencies::{Frequency, InvalidFrequency};
+    use crate::test_utils::date;
+    use crate::Recurrence;
+    use chrono::{DateTime, Utc};
+    use std::num::NonZeroU32;
+    use std::str::FromStr;
+
+    #[test]
+    fn events_during_working_hours() {
+        let rule = "FREQ=MINUTELY;INTERVAL=30;BYDAY=MO,TU,WE,TH,FR;X-BYHOURRANGE=9-18;DTSTART=2023-01-06T16:00:00Z";
+        let recurrence = Recurrence::from_str(rule).unwrap();
+        assert_eq!(recurrence.to_string(), rule);
+        assert_eq!(recurrence.frequency.by_hour_range(), Some((9, 18)));
+        let events: Vec<DateTime<Utc>> = recurrence.iter().take(6).collect();
+        assert_eq!(
+            events,
+            [
+                date("2023-01-06T16:00:00Z"),
+                date("2023-01-06T16:30:00Z"),
+                date("2023-01-06T17:00:00Z"),
+                date("2023-01-06T17:30:00Z"),
+                date("2023-01-09T09:00:00Z"),
+                date("2023-01-09T09:30:00Z"),
+            ]
+        );
+        assert!(recurrence.contains(&date("2023-01-10T17:30:00Z")));
+        assert!(!recurrence.contains(&date("2023-01-10T18:00:00Z")));
+        assert!(!recurrence.contains(&date("2023-01-10T08:30:00Z")));
+        assert!(!recurrence.contains(&date("2023-01-14T10:00:00Z")));
+        assert_eq!(
+            recurrence.next_from(&date("2023-01-10T18:10:00Z")),
+            Some(date("2023-01-11T09:00:00Z"))
+        );
+    }
+
+    #[test]
+    fn ranges_up_to_midnight() {
+        let recurrence = Recurrence::from_str(
+            "FREQ=HOURLY;INTERVAL=1;X-BYHOURRANGE=22-24;DTSTART=2023-01-01T00:00:00Z",
+        )
+        .unwrap();
+        let events: Vec<DateTime<Utc>> = recurrence.iter().take(3).collect();
+        assert_eq!(
+            events,
+            [
+                date("2023-01-01T22:00:00Z"),
+                date("2023-01-01T23:00:00Z"),
+                date("2023-01-02T22:00:00Z"),
+            ]
+        );
+    }
+
+    #[test]
+    fn ranges_keep_the_phase_of_the_interval() {
+        // Every 5 hours from midnight only reaches the range on some days, at different hours
+        let recurrence = Recurrence::from_str(
+            "FREQ=HOURLY;INTERVAL=5;X-BYHOURRANGE=9-12;DTSTART=2023-01-01T00:00:00Z",
+        )
+        .unwrap();
+        let events: Vec<DateTime<Utc>> = recurrence.iter().take(3).collect();
+        assert_eq!(
+            events,
+            [
+                date("2023-01-01T10:00:00Z"),
+                date("2023-01-02T11:00:00Z"),
+                date("2023-01-05T09:00:00Z"),
+            ]
+        );
+
+        // Every 24 hours from midnight never reaches the range
+        let recurrence = Recurrence::from_str(
+            "FREQ=HOURLY;INTERVAL=24;X-BYHOURRANGE=9-12;DTSTART=2023-01-01T00:00:00Z",
+        )
+        .unwrap();
+        assert_eq!(recurrence.iter().next(), None);
+    }
+
+    #[test]
+    fn invalid_ranges() {
+        for range in [(18, 9), (9, 9), (20, 25)] {
+            let frequency = Frequency::Hourly {
+                interval: NonZeroU32::new(1).unwrap(),
+                by_day: vec![],
+                by_time: vec![],
+                by_hour_range: Some(range),
+            };
+            assert!(
+                matches!(frequency.is_valid(), Err(InvalidFrequency::Time { .. })),
+                "{range:?}"
+            );
+        }
+        for rule in [
+            "FREQ=MINUTELY;X-BYHOURRANGE=9",
+            "FREQ=MINUTELY;X-BYHOURRANGE=9-25",
+            "FREQ=MINUTELY;X-BYHOURRANGE=18-9",
+        ] {
+            assert!(Frequency::from_str(rule).is_err(), "{rule}");
+        }
+        assert!(Frequency::from_str("FREQ=MINUTELY;X-BYHOURRANGE=0-24").is_ok());
+    }
+}
@@ -1,12 +1,14 @@
 #[cfg(test)]
 mod test_daily {
-    use crate::frequencies::{Frequency, Time};
+    use crate::frequencies::{Frequency, InvalidFrequency, Time};
+    use std::num::NonZeroU32;
     use std::str::FromStr;
 
     #[test]
     fn validate_daily() {
         let freq = Frequency::Daily {
-            interval: 1,
+            interval: NonZeroU32::new(1).unwrap(),
+            by_day: vec![],
             by_time: vec![],
         };
         assert!(freq.is_valid().is_ok());
@@ -14,17 +16,18 @@ mod test_daily {
 
     #[test]
     fn validate_daily_with_invalid_interval() {
-        let freq = Frequency::Daily {
-            interval: 0,
-            by_time: vec![],
-        };
-        assert!(freq.is_valid().is_err());
+        let freq = Frequency::from_str("FREQ=DAILY;INTERVAL=0");
+        assert!(matches!(freq, Err(InvalidFrequency::Interval { .. })));
+
+        let freq = Frequency::from_str("FREQ=DAILY;INTERVAL=4294967296");
+        assert!(matches!(freq, Err(InvalidFrequency::Parse { .. })));
     }
 
     #[test]
     fn validate_daily_with_repeated_times() {
         let freq = Frequency::Daily {
-            interval: 1,
+            interval: NonZeroU32::new(1).unwrap(),
+            by_day: vec![],
             by_time: vec![
                 Time::from_str("12:00:00").unwrap(),
                 Time::from_str("12:00:00").unwrap(),
@@ -32,36 +35,87 @@ mod test_daily {
         };
         assert!(freq.is_valid().is_err());
     }
+
+    #[test]
+    fn validate_daily_with_times_out_of_range() {
+        for (hour, minute, second) in [(24, 0, 0), (-1, 0, 0), (12, 60, 0), (12, 0, 60)] {
+            let freq = Frequency::Daily {
+                interval: NonZeroU32::new(1).unwrap(),
+                by_day: vec![],
+                by_time: vec![Time {
+                    hour,
+                    minute,
+                    second,
+                }],
+            };
+            assert!(
+                matches!(freq.is_valid(), Err(InvalidFrequency::Time { .. })),
+                "{hour}:{minute}:{second} should be invalid"
+            );
+        }
+    }
+
+    #[test]
+    fn validate_sub_daily_with_times_out_of_range() {
+        let freq = Frequency::Hourly {
+            interval: NonZeroU32::new(1).unwrap(),
+            by_day: vec![],
+            by_time: vec![Time {
+                hour: 25,
+                minute: 99,
+                second: 0,
+            }],
+            by_hour_range: None,
+        };
+        assert!(freq.is_valid().is_err());
+    }
+
+    #[test]
+    fn parse_times_out_of_range() {
+        assert!(Time::from_str("25:99").is_err());
+        assert!(Time::from_str("23:59:60").is_err());
+        assert_eq!(
+            Time::from_str("23:59:59").unwrap(),
+            Time {
+                hour: 23,
+                minute: 59,
+                second: 59
+            }
+        );
+    }
 }
 
 #[cfg(test)]
 mod test_weekly {
     use crate::frequencies::Frequency;
     use chrono::Weekday;
+    use std::num::NonZeroU32;
+    use std::str::FromStr;
 
     #[test]
     fn validate_weekly() {
         let freq = Frequency::Weekly {
-            interval: 1,
+            interval: NonZeroU32::new(1).unwrap(),
             by_day: vec![],
+            by_time: vec![],
+            by_day_time: vec![],
         };
         assert!(freq.is_valid().is_ok());
     }
 
     #[test]
     fn validate_weekly_with_invalid_interval() {
-        let freq = Frequency::Weekly {
-            interval: 0,
-            by_day: vec![],
-        };
-        assert!(freq.is_valid().is_err());
+        let freq = Frequency::from_str("FREQ=WEEKLY;INTERVAL=0");
+        assert!(freq.is_err());
     }
 
     #[test]
     fn validate_weekly_with_repeated_days() {
         let freq = Frequency::Weekly {
-            interval: 1,
+            interval: NonZeroU32::new(1).unwrap(),
             by_day: vec![Weekday::Mon, Weekday::Mon],
+            by_time: vec![],
+            by_day_time: vec![],
         };
         assert!(freq.is_valid().is_err());
     }
@@ -69,49 +123,349 @@ mod test_weekly {
 
 #[cfg(test)]
 mod test_monthly {
-    use crate::frequencies::{Frequency, NthWeekday};
+    use crate::frequencies::{Frequency, NthWeekday, OverflowPolicy};
     use chrono::Weekday;
+    use std::num::NonZeroU32;
+    use std::str::FromStr;
 
     #[test]
     fn validate_monthly() {
         let freq = Frequency::Monthly {
-            interval: 1,
+            interval: NonZeroU32::new(1).unwrap(),
             by_month_day: vec![],
+            by_day: vec![],
             nth_weekdays: vec![],
+            by_time: vec![],
+            overflow: OverflowPolicy::Skip,
         };
         assert!(freq.is_valid().is_ok());
     }
 
     #[test]
     fn validate_monthly_with_invalid_interval() {
+        let freq = Frequency::from_str("FREQ=MONTHLY;INTERVAL=0");
+        assert!(freq.is_err());
+    }
+
+    #[test]
+    fn validate_monthly_with_repeated_month_days() {
         let freq = Frequency::Monthly {
-            interval: 0,
-            by_month_day: vec![],
+            interval: NonZeroU32::new(1).unwrap(),
+            by_month_day: vec![15, 15],
+            by_day: vec![],
             nth_weekdays: vec![],
+            by_time: vec![],
+            overflow: OverflowPolicy::Skip,
         };
         assert!(freq.is_valid().is_err());
     }
 
     #[test]
-    fn validate_monthly_with_repeated_month_days() {
+    fn validate_monthly_with_invalid_month_days() {
+        for day in [0, 32, 45, -1] {
+            let freq = Frequency::Monthly {
+                interval: NonZeroU32::new(1).unwrap(),
+                by_month_day: vec![day],
+                by_day: vec![],
+                nth_weekdays: vec![],
+                by_time: vec![],
+                overflow: OverflowPolicy::Skip,
+            };
+            assert!(freq.is_valid().is_err(), "{day} should be invalid");
+        }
+    }
+
+    #[test]
+    fn monthly_warnings_for_days_missing_in_some_months() {
         let freq = Frequency::Monthly {
-            interval: 1,
-            by_month_day: vec![15, 15],
+            interval: NonZeroU32::new(1).unwrap(),
+            by_month_day: vec![1, 28],
+            by_day: vec![],
             nth_weekdays: vec![],
+            by_time: vec![],
+            overflow: OverflowPolicy::Skip,
         };
-        assert!(freq.is_valid().is_err());
+        assert!(freq.warnings().is_empty());
+
+        let freq = Frequency::Monthly {
+            interval: NonZeroU32::new(1).unwrap(),
+            by_month_day: vec![15, 29, 30, 31],
+            by_day: vec![],
+            nth_weekdays: vec![],
+            by_time: vec![],
+            overflow: OverflowPolicy::Skip,
+        };
+        assert!(freq.is_valid().is_ok());
+        let warnings = freq.warnings();
+        assert_eq!(warnings.len(), 3);
+        assert_eq!(
+            warnings[2],
+            "Month day 31 does not exist in February, April, June, September and November, so no event is generated on those months"
+        );
     }
 
     #[test]
     fn validate_monthly_with_repeated_nth_weekdays() {
         let freq = Frequency::Monthly {
-            interval: 1,
+            interval: NonZeroU32::new(1).unwrap(),
             by_month_day: vec![],
+            by_day: vec![],
             nth_weekdays: vec![
                 NthWeekday::new(Weekday::Mon, 1),
                 NthWeekday::new(Weekday::Mon, 1),
             ],
+            by_time: vec![],
+            overflow: OverflowPolicy::Skip,
         };
         assert!(freq.is_valid().is_err());
     }
+
+    #[test]
+    fn validate_monthly_with_nth_weekdays_out_of_the_month() {
+        let monthly = |week_number| Frequency::Monthly {
+            interval: NonZeroU32::new(1).unwrap(),
+            by_month_day: vec![],
+            by_day: vec![],
+            nth_weekdays: vec![NthWeekday::new(Weekday::Fri, week_number)],
+            by_time: vec![],
+            overflow: OverflowPolicy::Skip,
+        };
+        assert!(monthly(5).is_valid().is_ok());
+        for week_number in [0, 6, -1, i32::MAX] {
+            assert!(monthly(week_number).is_valid().is_err(), "{week_number}");
+            assert!(NthWeekday::try_new(Weekday::Fri, week_number).is_err());
+        }
+        assert_eq!(
+            NthWeekday::try_new(Weekday::Fri, 5),
+            Ok(NthWeekday::new(Weekday::Fri, 5))
+        );
+        assert_eq!(
+            NthWeekday::try_new(Weekday::Fri, 6)
+                .unwrap_err()
+                .to_string(),
+            "Invalid day: Invalid nth weekday 6FR, the ordinal must be between 1 and 5"
+        );
+    }
+
+    #[test]
+    fn parse_monthly_nth_weekdays_out_of_the_month() {
+        assert!(Frequency::from_str("FREQ=MONTHLY;INTERVAL=1;BYDAY=5FR").is_ok());
+        for value in ["6FR", "0FR", "54FR", "2147483648FR"] {
+            let rule = format!("FREQ=MONTHLY;INTERVAL=1;BYDAY={value}");
+            assert!(Frequency::from_str(&rule).is_err(), "{value}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_yearly {
+    use crate::frequencies::{
+        Frequency, InvalidFrequency, MonthlyDate, NthWeekday, OverflowPolicy, ParseErrorKind,
+    };
+    use chrono::{Month, Weekday};
+    use std::num::NonZeroU32;
+    use std::str::FromStr;
+
+    #[test]
+    fn validate_yearly() {
+        let freq = Frequency::Yearly {
+            interval: NonZeroU32::new(1).unwrap(),
+            by_month: vec![],
+            by_monthly_date: vec![],
+            nth_weekdays: vec![],
+            by_week_number: vec![],
+            by_time: vec![],
+            overflow: OverflowPolicy::Skip,
+        };
+        assert!(freq.is_valid().is_ok());
+    }
+
+    #[test]
+    fn validate_yearly_with_invalid_interval() {
+        let freq = Frequency::from_str("FREQ=YEARLY;INTERVAL=0");
+        assert!(freq.is_err());
+    }
+
+    #[test]
+    fn validate_yearly_with_repeated_monthly_dates() {
+        let freq = Frequency::Yearly {
+            interval: NonZeroU32::new(1).unwrap(),
+            by_month: vec![],
+            by_monthly_date: vec![
+                MonthlyDate {
+                    month: Month::January,
+                    day: 15,
+                },
+                MonthlyDate {
+                    month: Month::January,
+                    day: 15,
+                },
+            ],
+            nth_weekdays: vec![],
+            by_week_number: vec![],
+            by_time: vec![],
+            overflow: OverflowPolicy::Skip,
+        };
+        assert!(freq.is_valid().is_err());
+    }
+
+    #[test]
+    fn validate_yearly_with_repeated_months() {
+        let freq = Frequency::Yearly {
+            interval: NonZeroU32::new(1).unwrap(),
+            by_month: vec![Month::April, Month::January, Month::April],
+            by_monthly_date: vec![],
+            nth_weekdays: vec![],
+            by_week_number: vec![],
+            by_time: vec![],
+            overflow: OverflowPolicy::Skip,
+        };
+        assert!(freq.is_valid().is_err());
+    }
+
+    #[test]
+    fn validate_yearly_with_invalid_week_number() {
+        let freq = Frequency::Yearly {
+            interval: NonZeroU32::new(1).unwrap(),
+            by_month: vec![],
+            by_monthly_date: vec![],
+            nth_weekdays: vec![],
+            by_week_number: vec![54],
+            by_time: vec![],
+            overflow: OverflowPolicy::Skip,
+        };
+        assert!(freq.is_valid().is_err());
+    }
+
+    #[test]
+    fn validate_yearly_nth_weekdays() {
+        let yearly = |week_number| Frequency::Yearly {
+            interval: NonZeroU32::new(1).unwrap(),
+            by_month: vec![],
+            by_monthly_date: vec![],
+            nth_weekdays: vec![NthWeekday::new(Weekday::Mon, week_number)],
+            by_week_number: vec![],
+            by_time: vec![],
+            overflow: OverflowPolicy::Skip,
+        };
+        assert!(yearly(53).is_valid().is_ok());
+        assert!(yearly(54).is_valid().is_err());
+        assert!(yearly(0).is_valid().is_err());
+
+        assert!(Frequency::from_str("FREQ=YEARLY;INTERVAL=1;BYDAY=20MO").is_ok());
+        assert!(Frequency::from_str("FREQ=YEARLY;INTERVAL=1;BYMONTH=11;BYDAY=4TH").is_ok());
+        assert!(Frequency::from_str("FREQ=YEARLY;INTERVAL=1;BYMONTH=11;BYDAY=20TH").is_err());
+        let mut in_november = yearly(20);
+        if let Frequency::Yearly { by_month, .. } = &mut in_november {
+            by_month.push(Month::November);
+        }
+        assert!(in_november.is_valid().is_err());
+        match Frequency::from_str("FREQ=YEARLY;INTERVAL=1;BYDAY=MO,54MO") {
+            Err(InvalidFrequency::Parse { error }) => {
+                assert_eq!(error.kind, ParseErrorKind::InvalidValue);
+                assert_eq!(error.found, "54MO");
+            }
+            result => panic!("Unexpected result {result:?}"),
+        }
+    }
+
+    #[test]
+    fn validate_yearly_week_numbers_reject_ordinal_weekdays() {
+        assert!(Frequency::from_str("FREQ=YEARLY;INTERVAL=1;BYWEEKNO=20;BYDAY=MO").is_ok());
+        match Frequency::from_str("FREQ=YEARLY;INTERVAL=1;BYWEEKNO=20;BYDAY=MO,1mo") {
+            Err(error @ InvalidFrequency::Day { .. }) => assert_eq!(
+                error.to_string(),
+                "Invalid day: Ordinal BYDAY 1MO is not allowed with BYWEEKNO"
+            ),
+            result => panic!("Unexpected result {result:?}"),
+        }
+    }
+
+    #[test]
+    fn validate_yearly_weekdays_without_ordinal_are_unsupported() {
+        for value in [
+            "FREQ=YEARLY;INTERVAL=1;BYMONTH=1;BYDAY=MO",
+            "FREQ=YEARLY;INTERVAL=1;BYDAY=1MO,FR",
+        ] {
+            match Frequency::from_str(value) {
+                Err(error @ InvalidFrequency::Unsupported { .. }) => {
+                    assert!(error
+                        .to_string()
+                        .starts_with("Unsupported combination: BYDAY"))
+                }
+                result => panic!("Unexpected result {result:?} for {value}"),
+            }
+        }
+    }
+
+    #[test]
+    fn validate_yearly_monthly_dates_against_their_month() {
+        let yearly = |day, overflow| Frequency::Yearly {
+            interval: NonZeroU32::new(1).unwrap(),
+            by_month: vec![],
+            by_monthly_date: vec![MonthlyDate {
+                month: Month::February,
+                day,
+            }],
+            nth_weekdays: vec![],
+            by_week_number: vec![],
+            by_time: vec![],
+            overflow,
+        };
+        assert!(yearly(29, OverflowPolicy::Skip).is_valid().is_ok());
+        assert_eq!(yearly(29, OverflowPolicy::Skip).warnings().len(), 1);
+        assert!(yearly(29, OverflowPolicy::NextValid).warnings().is_empty());
+        assert!(yearly(31, OverflowPolicy::ClampToLastDay)
+            .is_valid()
+            .is_ok());
+        for (day, overflow) in [
+            (30, OverflowPolicy::Skip),
+            (0, OverflowPolicy::Skip),
+            (32, OverflowPolicy::NextValid),
+            (-1, OverflowPolicy::ClampToLastDay),
+        ] {
+            assert!(yearly(day, overflow).is_valid().is_err(), "{day}");
+        }
+    }
+
+    #[test]
+    fn create_monthly_dates() {
+        assert!(MonthlyDate::new(Month::April, 30).is_ok());
+        assert!(MonthlyDate::new(Month::April, 31).is_err());
+        assert!(MonthlyDate::new(Month::December, 31).is_ok());
+        assert!(MonthlyDate::new(Month::January, 0).is_err());
+        assert!(MonthlyDate::new_with(Month::April, 31, OverflowPolicy::NextValid).is_ok());
+        assert!(MonthlyDate::new_with(Month::April, 32, OverflowPolicy::NextValid).is_err());
+        let error = MonthlyDate::new(Month::February, 31).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Invalid day: Invalid monthly date FEB31, the day must be between 1 and 29 in February"
+        );
+    }
+
+    #[test]
+    fn parse_monthly_dates_of_their_month() {
+        assert!(Frequency::from_str("FREQ=YEARLY;INTERVAL=1;X-BYMONTHDATE=FEB30").is_err());
+        assert!(Frequency::from_str("FREQ=YEARLY;INTERVAL=1;BYMONTH=2;BYMONTHDAY=30").is_err());
+        assert!(
+            Frequency::from_str("FREQ=YEARLY;INTERVAL=1;X-BYMONTHDATE=FEB30;X-OVERFLOW=CLAMP")
+                .is_ok()
+        );
+
+        // Months without the day are dropped from the combinations
+        let freq =
+            Frequency::from_str("FREQ=YEARLY;INTERVAL=1;BYMONTH=1,2,4;BYMONTHDAY=30,31").unwrap();
+        assert_eq!(
+            freq.by_monthly_date(),
+            [
+                MonthlyDate::new(Month::January, 30).unwrap(),
+                MonthlyDate::new(Month::January, 31).unwrap(),
+                MonthlyDate::new(Month::April, 30).unwrap(),
+            ]
+        );
+        let freq =
+            Frequency::from_str("FREQ=YEARLY;INTERVAL=1;BYMONTH=2;BYMONTHDAY=30;SKIP=FORWARD")
+                .unwrap();
+        assert_eq!(freq.by_monthly_date().len(), 1);
+    }
 }
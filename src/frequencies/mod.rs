@@ -1,3 +1,5 @@
+pub mod cron;
+mod engine;
 mod errors;
 pub mod frequencies_tests;
 pub mod frequencies_validation_tests;
@@ -5,5 +7,7 @@ pub mod models;
 pub mod serializer;
 pub mod validations;
 
-pub use errors::InvalidFrequency;
-pub use models::{Frequency, MonthlyDate, NthWeekday, Time};
+pub use engine::OccurrenceEngine;
+pub use errors::{InvalidFrequency, ParseError, ParseErrorKind};
+pub use models::{FreqKind, Frequency, MonthlyDate, NthWeekday, OverflowPolicy, Time, TimePolicy};
+pub use serializer::{parse_byday_list, parse_bymonthday_list, parse_bytime_list, ParseOptions};
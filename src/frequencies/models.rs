@@ -1,59 +1,167 @@
-use std::cmp::Ordering;
+use core::cmp::Ordering;
 
+use crate::calendars::{CalendarScale, Gregorian};
+use crate::codes::month_from_number;
+use crate::date_utils::{weekday_ordinal, DateUtils};
 use crate::frequencies::errors::InvalidFrequency;
 use crate::frequencies::validations::{
-    validate_daily, validate_hourly, validate_minutely, validate_monthly, validate_secondly,
-    validate_weekly, validate_yearly,
+    monthly_date_warnings, monthly_day_warnings, validate_daily, validate_day_times,
+    validate_hour_range, validate_hourly, validate_minutely, validate_monthly,
+    validate_monthly_date, validate_nth_weekday, validate_secondly, validate_time, validate_weekly,
+    validate_weekly_times, validate_yearly, MONTH_WEEKS,
 };
-use crate::utils::{get_next_nth_weekday, weekday_ordinal, DateUtils};
-use chrono::{DateTime, Datelike, Duration, Month, Timelike, Utc, Weekday};
-use std::ops::{Add, Sub};
-use std::str::FromStr;
+use crate::trace;
+use crate::utils::{
+    get_next_aligned_nth_weekday, iso_week_date, nth_weekday_of_month, nth_weekday_of_year,
+    MAX_MONTHLY_JUMPS,
+};
+use alloc::string::String;
+use alloc::vec::Vec;
+use alloc::{format, vec};
+use chrono::{
+    DateTime, Datelike, Days, Duration, LocalResult, Month, Months, NaiveDate, NaiveDateTime,
+    Offset, TimeZone, Timelike, Utc, Weekday,
+};
+use core::num::NonZeroU32;
+use core::str::FromStr;
 
 /// Representation of the frequency of a recurrence.
 /// E.g. Once a day, Twice a week, etc.
 ///
 /// Examples:
 /// ```
-/// use rrules::{Frequency};
+/// use std::num::NonZeroU32;
+/// use rrules::{Frequency, OverflowPolicy};
 ///
-/// let once_a_day = Frequency::Daily {interval: 1, by_time: vec![]};
+/// let once_a_day = Frequency::Daily {interval: NonZeroU32::new(1).unwrap(), by_day: vec![], by_time: vec![]};
 /// assert_eq!(once_a_day.to_string(), "FREQ=DAILY;INTERVAL=1");
 ///
 /// let three_times_a_month = Frequency::Monthly {
-///     interval: 1,
+///     interval: NonZeroU32::new(1).unwrap(),
 ///     by_month_day: vec![1, 10, 20],
-///     nth_weekdays: vec![]
+///     by_day: vec![],
+///     nth_weekdays: vec![],
+///     by_time: vec![],
+///     overflow: OverflowPolicy::Skip,
 /// };
 /// assert_eq!(three_times_a_month.to_string(), "FREQ=MONTHLY;INTERVAL=1;BYMONTHDAY=1,10,20");
 /// ```
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum Frequency {
+    /// Secondly frequency.
+    ///
+    /// Sub-daily frequencies can be restricted to some days of the week with `by_day`
+    /// and to some times of the day with `by_time`, where an event matches a time
+    /// when both its hour and minute are the same. The seconds of the times are ignored
+    /// by the sub-daily frequencies, unlike the daily, weekly, monthly and yearly ones,
+    /// where they are the seconds of the events. The recurrences reject the times that
+    /// the interval never reaches from their start date.
     Secondly {
-        interval: i32,
+        interval: NonZeroU32,
+        by_day: Vec<Weekday>,
+        by_time: Vec<Time>,
     },
+    /// Minutely frequency.
+    ///
+    /// The `by_hour_range` restricts the events to the hours from its start, included, to its
+    /// end, excluded, E.g. `Some((9, 18))` for working hours.
     Minutely {
-        interval: i32,
+        interval: NonZeroU32,
+        by_day: Vec<Weekday>,
+        by_time: Vec<Time>,
+        by_hour_range: Option<(u8, u8)>,
     },
+    /// Hourly frequency, which can be restricted to a range of hours like minutely frequencies.
     Hourly {
-        interval: i32,
+        interval: NonZeroU32,
+        by_day: Vec<Weekday>,
+        by_time: Vec<Time>,
+        by_hour_range: Option<(u8, u8)>,
     },
+    /// Daily frequency.
+    ///
+    /// The `by_day` weekdays restrict the days of the events, which are still counted from
+    /// the day of the start date, like the `BYDAY` filter of RFC 5545.
     Daily {
-        interval: i32,
+        interval: NonZeroU32,
+        by_day: Vec<Weekday>,
         by_time: Vec<Time>,
     },
+    /// Business daily frequency.
+    ///
+    /// The events happen from Monday to Friday, at the time of the start date, and the
+    /// `interval` counts business days from the start date, so an interval of 2 on a Friday
+    /// is followed by Tuesday. It is serialized as a daily frequency with `X-BUSINESS=TRUE`.
+    BusinessDaily { interval: NonZeroU32 },
+    /// Weekly frequency.
+    ///
+    /// Weeks start on Monday, like the default `WKST=MO` of RFC 5545. The `interval`
+    /// applies to the weeks, so every event of a week on the `by_day` days is yielded
+    /// before jumping `interval` weeks to the week of the next events. The weeks are
+    /// counted from the week of the start date, so the events stay aligned to it.
+    ///
+    /// The `by_time` times are repeated on each of the `by_day` days, or on the weekday of
+    /// the start date without days, like Mondays and Wednesdays at 09:00 and 17:00.
+    ///
+    /// The `by_day_time` pairs define a time for each day instead, like Mondays at 09:00
+    /// and Thursdays at 14:00. They cannot be combined with `by_day` or `by_time`, and are
+    /// serialized with the `X-BYDAYTIME` key.
     Weekly {
-        interval: i32,
+        interval: NonZeroU32,
         by_day: Vec<Weekday>,
+        by_time: Vec<Time>,
+        by_day_time: Vec<(Weekday, Time)>,
     },
+    /// Monthly frequency.
+    ///
+    /// The `by_day` weekdays are every one of those weekdays in the month, and the
+    /// `nth_weekdays` the nth weekdays of the month (E.g. the 2nd Monday). When combined
+    /// with `by_month_day`, only the month days on those weekdays are yielded, like
+    /// Friday the 13th.
+    ///
+    /// The `overflow` policy defines what happens with the `by_month_day` values that
+    /// don't exist in some months, like the 31st in April. Without filters, it applies
+    /// to the day of the start date, like the `SKIP` parameter of RFC 7529.
+    ///
+    /// The events are at the time of the start date, or at every one of the `by_time`
+    /// times on each of the days.
     Monthly {
-        interval: i32,
+        interval: NonZeroU32,
         by_month_day: Vec<i32>,
+        by_day: Vec<Weekday>,
         nth_weekdays: Vec<NthWeekday>,
+        by_time: Vec<Time>,
+        overflow: OverflowPolicy,
     },
+    /// Yearly frequency.
+    ///
+    /// The `nth_weekdays` are the nth weekdays of the year (E.g. the 20th Monday) unless
+    /// `by_week_number` is set, in which case they select the weekdays inside the given
+    /// ISO week numbers. When combined with `by_monthly_date`, only the dates matching
+    /// both constraints are yielded.
+    ///
+    /// The `by_month` months restrict the events to the given months. On their own they
+    /// repeat the day of the start date in every month that has it, and with `nth_weekdays`
+    /// the nth weekdays are counted within each month (E.g. the 4th Thursday of November).
+    ///
+    /// The `overflow` policy defines what happens with the `by_monthly_date` values that
+    /// don't exist in some years, like the 29th of February. Without filters, it applies
+    /// to the date of the start date.
+    ///
+    /// The `interval` is counted from the year of the start date, or from its ISO year
+    /// when `by_week_number` is set, so years without any of the dates are skipped
+    /// without shifting the following events.
+    ///
+    /// Like monthly frequencies, the events are at the time of the start date, or at every
+    /// one of the `by_time` times on each of the days.
     Yearly {
-        interval: i32,
-        by_monthly_date: Option<MonthlyDate>,
+        interval: NonZeroU32,
+        by_month: Vec<Month>,
+        by_monthly_date: Vec<MonthlyDate>,
+        nth_weekdays: Vec<NthWeekday>,
+        by_week_number: Vec<i32>,
+        by_time: Vec<Time>,
+        overflow: OverflowPolicy,
     },
 }
 
@@ -72,6 +180,25 @@ impl NthWeekday {
             weekday,
         }
     }
+
+    /// Creates a weekday of a month with its ordinal, which must be between 1 and 5.
+    ///
+    /// The weekdays of a year, up to the 53rd, are created with `new` and validated with
+    /// their yearly frequency.
+    /// Examples:
+    /// ```
+    /// use chrono::Weekday;
+    /// use rrules::NthWeekday;
+    ///
+    /// assert!(NthWeekday::try_new(Weekday::Tue, 2).is_ok());
+    /// assert!(NthWeekday::try_new(Weekday::Tue, 6).is_err());
+    /// assert!(NthWeekday::try_new(Weekday::Tue, 0).is_err());
+    /// ```
+    pub fn try_new(weekday: Weekday, week_number: i32) -> Result<NthWeekday, InvalidFrequency> {
+        let nth_weekday = NthWeekday::new(weekday, week_number);
+        validate_nth_weekday(&nth_weekday, MONTH_WEEKS)?;
+        Ok(nth_weekday)
+    }
 }
 
 impl PartialOrd<Self> for NthWeekday {
@@ -93,150 +220,1013 @@ impl Ord for NthWeekday {
     }
 }
 
-/// Representation of a time containing hour:minute
-/// E.g. 12:00, 23:59, etc.
-#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+/// The kind of a frequency, without its rules.
+///
+/// New kinds may be added in the future, so matching on it requires a wildcard arm.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum FreqKind {
+    Secondly,
+    Minutely,
+    Hourly,
+    Daily,
+    BusinessDaily,
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+/// Representation of a time containing hour:minute and optionally seconds
+/// E.g. 12:00, 23:59, 09:30:15, etc.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Hash)]
 pub struct Time {
     pub hour: i32,
     pub minute: i32,
+    pub second: i32,
 }
 
 impl FromStr for Time {
     type Err = InvalidFrequency;
 
+    /// Parses times in the `HH:MM` or `HH:MM:SS` format.
     fn from_str(time_str: &str) -> Result<Self, InvalidFrequency> {
-        let mut parts = time_str.split(':');
-        let hour = match parts.next() {
-            None => {
-                return Err(InvalidFrequency::Time {
-                    message: format!("Invalid time: {time_str}"),
-                })
-            }
-            Some(hour) => match hour.parse::<i32>() {
-                Ok(hour) => hour,
-                Err(_) => {
-                    return Err(InvalidFrequency::Time {
-                        message: format!("Invalid time: {time_str}"),
-                    })
-                }
-            },
+        let invalid = || InvalidFrequency::Time {
+            message: format!("Invalid time: {time_str}"),
         };
-        let minute = match parts.next() {
-            None => {
-                return Err(InvalidFrequency::Time {
-                    message: format!("Invalid time: {time_str}"),
-                })
-            }
-            Some(minute) => match minute.parse::<i32>() {
-                Ok(minute) => minute,
-                Err(_) => {
-                    return Err(InvalidFrequency::Time {
-                        message: format!("Invalid time: {time_str}"),
-                    })
-                }
-            },
+        let parts: Vec<&str> = time_str.split(':').collect();
+        if parts.len() < 2 || parts.len() > 3 {
+            return Err(invalid());
+        }
+        let mut values = [0; 3];
+        for (value, part) in values.iter_mut().zip(&parts) {
+            *value = part.parse::<i32>().map_err(|_| invalid())?;
+        }
+        let [hour, minute, second] = values;
+        let time = Time {
+            hour,
+            minute,
+            second,
         };
-        Ok(Time { hour, minute })
+        validate_time(&time)?;
+        Ok(time)
+    }
+}
+
+impl Time {
+    /// Returns the date at this time of the given day.
+    pub(crate) fn on(&self, day: NaiveDate) -> Option<DateTime<Utc>> {
+        Some(Utc.from_utc_datetime(&self.naive_on(day)?))
+    }
+
+    /// Returns the local date and time at this time of the given day.
+    pub(crate) fn naive_on(&self, day: NaiveDate) -> Option<NaiveDateTime> {
+        day.and_hms_opt(self.hour as u32, self.minute as u32, self.second as u32)
     }
 }
 
 /// Representation of a monthly date
 /// E.g. 1st of January, 2nd of February, etc.
-#[derive(Debug, Clone)]
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Hash)]
 pub struct MonthlyDate {
     pub month: Month,
     pub day: i32,
 }
 
+/// The largest number of days of every month, in leap years.
+pub(crate) const MONTH_LENGTHS: [i32; 12] = [31, 29, 31, 30, 31, 30, 31, 31, 30, 31, 30, 31];
+
+impl MonthlyDate {
+    /// Creates a date of the year, when its month has the day, at least in leap years.
+    ///
+    /// Examples:
+    /// ```
+    /// use chrono::Month;
+    /// use rrules::{MonthlyDate, OverflowPolicy};
+    ///
+    /// assert!(MonthlyDate::new(Month::February, 29).is_ok());
+    /// assert!(MonthlyDate::new(Month::February, 31).is_err());
+    /// assert!(MonthlyDate::new_with(Month::February, 31, OverflowPolicy::ClampToLastDay).is_ok());
+    /// ```
+    pub fn new(month: Month, day: i32) -> Result<MonthlyDate, InvalidFrequency> {
+        MonthlyDate::new_with(month, day, OverflowPolicy::Skip)
+    }
+
+    /// Creates a date of the year for a frequency with the given overflow policy.
+    ///
+    /// The policies that don't skip the missing days also accept the days up to 31 in the
+    /// shorter months, E.g. February 31st is the last day of February with `ClampToLastDay`.
+    pub fn new_with(
+        month: Month,
+        day: i32,
+        overflow: OverflowPolicy,
+    ) -> Result<MonthlyDate, InvalidFrequency> {
+        let date = MonthlyDate { month, day };
+        validate_monthly_date(&date, &overflow)?;
+        Ok(date)
+    }
+
+    /// Returns the largest day of the month of the date, 29 for February.
+    pub(crate) fn last_day(&self) -> i32 {
+        MONTH_LENGTHS[self.month.number_from_month() as usize - 1]
+    }
+}
+
+/// Policy applied to the month days that don't exist in a month,
+/// like the 31st in April or the 29th of February in common years.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum OverflowPolicy {
+    /// The month is skipped
+    #[default]
+    Skip,
+    /// The last day of the month is used instead
+    ClampToLastDay,
+    /// The first day of the following month is used instead
+    NextValid,
+}
+
+impl OverflowPolicy {
+    /// Returns the date for the day of the month, applying the policy when the month doesn't have it.
+    pub fn resolve(&self, year: i32, month: u32, day: i32) -> Option<NaiveDate> {
+        Gregorian.resolve(year, month, day, *self)
+    }
+}
+
+/// Policy applied to the times of the day that don't exist on some days, or that exist twice,
+/// like the times skipped or repeated when the clocks change for daylight saving time.
+///
+/// The recurrences are in UTC, where every time exists once, so the policy is not part of
+/// the rules: it is applied with `resolve` to the local times of the caller's time zone.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum TimePolicy {
+    /// Times that don't exist are skipped, and repeated times are used once at their first instant
+    #[default]
+    Skip,
+    /// Times that don't exist are shifted forward by the length of the gap, and repeated
+    /// times are used once at their first instant
+    ShiftForward,
+    /// Times that don't exist are shifted forward by the length of the gap, and repeated
+    /// times are used at both of their instants
+    Both,
+}
+
+impl TimePolicy {
+    /// Returns the instants of the local date and time in the time zone, applying the policy
+    /// when it doesn't exist or exists twice.
+    ///
+    /// A shifted time can be on the following day, when the gap crosses midnight.
+    pub fn resolve<Tz: TimeZone>(
+        &self,
+        tz: &Tz,
+        local: &NaiveDateTime,
+    ) -> LocalResult<DateTime<Tz>> {
+        match (tz.from_local_datetime(local), self) {
+            (LocalResult::None, TimePolicy::Skip) => LocalResult::None,
+            (LocalResult::None, _) => shift_forward(tz, local),
+            (LocalResult::Ambiguous(earliest, _), TimePolicy::Skip | TimePolicy::ShiftForward) => {
+                LocalResult::Single(earliest)
+            }
+            (result, _) => result,
+        }
+    }
+}
+
+/// Returns the instant of a local time that doesn't exist, using the offset from before the
+/// gap, which moves it forward by the length of the gap.
+fn shift_forward<Tz: TimeZone>(tz: &Tz, local: &NaiveDateTime) -> LocalResult<DateTime<Tz>> {
+    let Some(before) = local.checked_sub_signed(Duration::days(1)) else {
+        return LocalResult::None;
+    };
+    let offset = tz.offset_from_utc_datetime(&before).fix();
+    match local.checked_sub_offset(offset) {
+        Some(utc) => LocalResult::Single(tz.from_utc_datetime(&utc)),
+        None => LocalResult::None,
+    }
+}
+
 impl Frequency {
+    /// Returns a monthly frequency on the same nth weekday of the month as the start date,
+    /// like the "monthly on the 3rd Tuesday" option of calendar apps.
+    ///
+    /// The nth weekday is the occurrence of the weekday of the start date in its month,
+    /// from 1 to 5, so a start date on a 5th weekday skips the months without one.
+    /// Examples:
+    /// ```
+    /// use std::num::NonZeroU32;
+    /// use std::str::FromStr;
+    /// use chrono::{DateTime, Utc, Weekday};
+    /// use rrules::{Frequency, NthWeekday, Recurrence};
+    ///
+    /// // The 3rd Tuesday of January 2023
+    /// let start = DateTime::<Utc>::from_str("2023-01-17T10:00:00Z").unwrap();
+    /// let frequency = Frequency::monthly_same_weekday_as_start(NonZeroU32::new(1).unwrap(), &start);
+    /// assert_eq!(frequency.nth_weekdays(), [NthWeekday::new(Weekday::Tue, 3)]);
+    ///
+    /// let recurrence = Recurrence::new(frequency, start, None, None).unwrap();
+    /// let events: Vec<DateTime<Utc>> = recurrence.iter().take(3).collect();
+    /// assert_eq!(events[1], DateTime::<Utc>::from_str("2023-02-21T10:00:00Z").unwrap());
+    /// assert_eq!(events[2], DateTime::<Utc>::from_str("2023-03-21T10:00:00Z").unwrap());
+    /// ```
+    pub fn monthly_same_weekday_as_start(interval: NonZeroU32, start: &DateTime<Utc>) -> Self {
+        Frequency::Monthly {
+            interval,
+            by_month_day: vec![],
+            by_day: vec![],
+            nth_weekdays: vec![NthWeekday::new(start.weekday(), weekday_ordinal(start))],
+            by_time: vec![],
+            overflow: OverflowPolicy::default(),
+        }
+    }
+
+    /// Returns a monthly frequency on the same day of the month as the start date.
+    ///
+    /// The months without the day are skipped, E.g. from a start date on the 31st, unless
+    /// the `overflow` policy of the frequency is changed.
+    /// Examples:
+    /// ```
+    /// use std::num::NonZeroU32;
+    /// use std::str::FromStr;
+    /// use chrono::{DateTime, Utc};
+    /// use rrules::Frequency;
+    ///
+    /// let start = DateTime::<Utc>::from_str("2023-01-15T10:00:00Z").unwrap();
+    /// let frequency = Frequency::monthly_same_day_as_start(NonZeroU32::new(1).unwrap(), &start);
+    /// assert_eq!(frequency.to_string(), "FREQ=MONTHLY;INTERVAL=1;BYMONTHDAY=15");
+    /// ```
+    pub fn monthly_same_day_as_start(interval: NonZeroU32, start: &DateTime<Utc>) -> Self {
+        Frequency::Monthly {
+            interval,
+            by_month_day: vec![start.day() as i32],
+            by_day: vec![],
+            nth_weekdays: vec![],
+            by_time: vec![],
+            overflow: OverflowPolicy::default(),
+        }
+    }
+
+    /// Returns a monthly frequency every 3 months, on the given days of the month and nth
+    /// weekdays, like the "once a quarter" option of calendar apps.
+    ///
+    /// The quarters are counted from the month of the start date of the recurrence, so the
+    /// recurrences starting in January, April, July or October follow the calendar quarters.
+    /// Without days, the events are on the day of the month of the start date.
+    /// Examples:
+    /// ```
+    /// use std::str::FromStr;
+    /// use chrono::{DateTime, Utc};
+    /// use rrules::{Frequency, Recurrence};
+    ///
+    /// let frequency = Frequency::quarterly(vec![15], vec![]);
+    /// assert_eq!(frequency.to_string(), "FREQ=MONTHLY;INTERVAL=3;BYMONTHDAY=15");
+    ///
+    /// let start = DateTime::<Utc>::from_str("2023-02-01T09:00:00Z").unwrap();
+    /// let recurrence = Recurrence::new(frequency, start, None, None).unwrap();
+    /// let events: Vec<DateTime<Utc>> = recurrence.iter().take(3).collect();
+    /// assert_eq!(events[0], DateTime::<Utc>::from_str("2023-02-15T09:00:00Z").unwrap());
+    /// assert_eq!(events[1], DateTime::<Utc>::from_str("2023-05-15T09:00:00Z").unwrap());
+    /// assert_eq!(events[2], DateTime::<Utc>::from_str("2023-08-15T09:00:00Z").unwrap());
+    /// ```
+    pub fn quarterly(by_month_day: Vec<i32>, nth_weekdays: Vec<NthWeekday>) -> Self {
+        Frequency::every_months(3, by_month_day, nth_weekdays)
+    }
+
+    /// Returns a monthly frequency every 6 months, on the given days of the month and nth
+    /// weekdays.
+    ///
+    /// The half years are counted from the month of the start date of the recurrence, like
+    /// the quarters of `Frequency::quarterly`.
+    /// Examples:
+    /// ```
+    /// use chrono::Weekday;
+    /// use rrules::{Frequency, NthWeekday};
+    ///
+    /// let frequency = Frequency::semi_annual(vec![], vec![NthWeekday::new(Weekday::Mon, 1)]);
+    /// assert_eq!(frequency.to_string(), "FREQ=MONTHLY;INTERVAL=6;BYDAY=1MO");
+    /// ```
+    pub fn semi_annual(by_month_day: Vec<i32>, nth_weekdays: Vec<NthWeekday>) -> Self {
+        Frequency::every_months(6, by_month_day, nth_weekdays)
+    }
+
+    fn every_months(months: u32, by_month_day: Vec<i32>, nth_weekdays: Vec<NthWeekday>) -> Self {
+        Frequency::Monthly {
+            interval: NonZeroU32::new(months).unwrap_or(NonZeroU32::MIN),
+            by_month_day,
+            by_day: vec![],
+            nth_weekdays,
+            by_time: vec![],
+            overflow: OverflowPolicy::default(),
+        }
+    }
+
+    /// Returns a yearly frequency on the same month and day as the start date, like the
+    /// anniversaries of the start date.
+    ///
+    /// From a start date on February 29th, the common years are skipped unless the
+    /// `overflow` policy of the frequency is changed.
+    /// Examples:
+    /// ```
+    /// use std::num::NonZeroU32;
+    /// use std::str::FromStr;
+    /// use chrono::{DateTime, Utc};
+    /// use rrules::{Frequency, Recurrence};
+    ///
+    /// let start = DateTime::<Utc>::from_str("2020-06-12T00:00:00Z").unwrap();
+    /// let frequency = Frequency::yearly_same_date_as_start(NonZeroU32::new(1).unwrap(), &start);
+    /// assert_eq!(frequency.to_string(), "FREQ=YEARLY;INTERVAL=1;BYMONTH=6;BYMONTHDAY=12");
+    ///
+    /// let anniversaries = Recurrence::new(frequency, start, None, None).unwrap();
+    /// let events: Vec<DateTime<Utc>> = anniversaries.iter().take(3).collect();
+    /// assert_eq!(events[2], DateTime::<Utc>::from_str("2022-06-12T00:00:00Z").unwrap());
+    /// ```
+    pub fn yearly_same_date_as_start(interval: NonZeroU32, start: &DateTime<Utc>) -> Self {
+        let by_monthly_date = month_from_number(start.month() as i32)
+            .map(|month| MonthlyDate {
+                month,
+                day: start.day() as i32,
+            })
+            .into_iter()
+            .collect();
+        Frequency::Yearly {
+            interval,
+            by_month: vec![],
+            by_monthly_date,
+            nth_weekdays: vec![],
+            by_week_number: vec![],
+            by_time: vec![],
+            overflow: OverflowPolicy::default(),
+        }
+    }
+
     /// Verifies if the frequency is valid.
     pub fn is_valid(&self) -> Result<(), InvalidFrequency> {
         match self {
-            Frequency::Secondly { interval } => validate_secondly(interval),
-            Frequency::Minutely { interval } => validate_minutely(interval),
-            Frequency::Hourly { interval } => validate_hourly(interval),
-            Frequency::Daily { interval, by_time } => validate_daily(interval, by_time),
-            Frequency::Weekly { interval, by_day } => validate_weekly(interval, by_day),
+            Frequency::Secondly {
+                by_day, by_time, ..
+            } => validate_secondly(by_day, by_time),
+            Frequency::Minutely {
+                by_day,
+                by_time,
+                by_hour_range,
+                ..
+            } => {
+                validate_minutely(by_day, by_time).and_then(|_| validate_hour_range(*by_hour_range))
+            }
+            Frequency::Hourly {
+                by_day,
+                by_time,
+                by_hour_range,
+                ..
+            } => validate_hourly(by_day, by_time).and_then(|_| validate_hour_range(*by_hour_range)),
+            Frequency::Daily {
+                by_day, by_time, ..
+            } => validate_weekly(by_day).and_then(|_| validate_daily(by_time)),
+            Frequency::BusinessDaily { .. } => Ok(()),
+            Frequency::Weekly {
+                by_day,
+                by_time,
+                by_day_time,
+                ..
+            } => validate_weekly(by_day)
+                .and_then(|_| validate_weekly_times(by_time, by_day_time))
+                .and_then(|_| validate_day_times(by_day, by_day_time)),
             Frequency::Monthly {
-                interval,
                 by_month_day,
+                by_day,
                 nth_weekdays,
-            } => validate_monthly(interval, by_month_day, nth_weekdays),
+                by_time,
+                ..
+            } => validate_monthly(by_month_day, by_day, nth_weekdays)
+                .and_then(|_| validate_daily(by_time)),
+            Frequency::Yearly {
+                by_month,
+                by_monthly_date,
+                nth_weekdays,
+                by_week_number,
+                by_time,
+                overflow,
+                ..
+            } => validate_yearly(
+                by_month,
+                by_monthly_date,
+                nth_weekdays,
+                by_week_number,
+                overflow,
+            )
+            .and_then(|_| validate_daily(by_time)),
+        }
+    }
+
+    /// Returns the kind of the frequency, E.g. `FreqKind::Daily` for daily frequencies.
+    ///
+    /// Examples:
+    /// ```
+    /// use std::num::NonZeroU32;
+    /// use rrules::{FreqKind, Frequency};
+    /// use chrono::Weekday;
+    ///
+    /// let every_monday = Frequency::Weekly {interval: NonZeroU32::new(2).unwrap(), by_day: vec![Weekday::Mon], by_time: vec![], by_day_time: vec![]};
+    /// assert_eq!(every_monday.freq_kind(), FreqKind::Weekly);
+    /// assert_eq!(every_monday.interval().get(), 2);
+    /// assert_eq!(every_monday.by_day(), &[Weekday::Mon]);
+    /// assert!(every_monday.by_month_day().is_empty());
+    /// ```
+    pub fn freq_kind(&self) -> FreqKind {
+        match self {
+            Frequency::Secondly { .. } => FreqKind::Secondly,
+            Frequency::Minutely { .. } => FreqKind::Minutely,
+            Frequency::Hourly { .. } => FreqKind::Hourly,
+            Frequency::Daily { .. } => FreqKind::Daily,
+            Frequency::BusinessDaily { .. } => FreqKind::BusinessDaily,
+            Frequency::Weekly { .. } => FreqKind::Weekly,
+            Frequency::Monthly { .. } => FreqKind::Monthly,
+            Frequency::Yearly { .. } => FreqKind::Yearly,
+        }
+    }
+
+    /// Returns the interval between the periods of the frequency.
+    pub fn interval(&self) -> NonZeroU32 {
+        match self {
+            Frequency::Secondly { interval, .. }
+            | Frequency::Minutely { interval, .. }
+            | Frequency::Hourly { interval, .. }
+            | Frequency::Daily { interval, .. }
+            | Frequency::BusinessDaily { interval }
+            | Frequency::Weekly { interval, .. }
+            | Frequency::Monthly { interval, .. }
+            | Frequency::Yearly { interval, .. } => *interval,
+        }
+    }
+
+    /// Returns the days of the week of the frequency, empty when it doesn't have them.
+    pub fn by_day(&self) -> &[Weekday] {
+        match self {
+            Frequency::Secondly { by_day, .. }
+            | Frequency::Minutely { by_day, .. }
+            | Frequency::Hourly { by_day, .. }
+            | Frequency::Daily { by_day, .. }
+            | Frequency::Weekly { by_day, .. }
+            | Frequency::Monthly { by_day, .. } => by_day,
+            _ => &[],
+        }
+    }
+
+    /// Returns the times of the day of the frequency, empty when it doesn't have them.
+    pub fn by_time(&self) -> &[Time] {
+        match self {
+            Frequency::Secondly { by_time, .. }
+            | Frequency::Minutely { by_time, .. }
+            | Frequency::Hourly { by_time, .. }
+            | Frequency::Daily { by_time, .. }
+            | Frequency::Weekly { by_time, .. }
+            | Frequency::Monthly { by_time, .. }
+            | Frequency::Yearly { by_time, .. } => by_time,
+            _ => &[],
+        }
+    }
+
+    /// Returns the range of hours of minutely and hourly frequencies, if any.
+    pub fn by_hour_range(&self) -> Option<(u8, u8)> {
+        match self {
+            Frequency::Minutely { by_hour_range, .. } | Frequency::Hourly { by_hour_range, .. } => {
+                *by_hour_range
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the days of the week with their times of the frequency, empty when it
+    /// doesn't have them.
+    pub fn by_day_time(&self) -> &[(Weekday, Time)] {
+        match self {
+            Frequency::Weekly { by_day_time, .. } => by_day_time,
+            _ => &[],
+        }
+    }
+
+    /// Returns the days of the month of the frequency, empty when it doesn't have them.
+    pub fn by_month_day(&self) -> &[i32] {
+        match self {
+            Frequency::Monthly { by_month_day, .. } => by_month_day,
+            _ => &[],
+        }
+    }
+
+    /// Returns the nth weekdays of the frequency, empty when it doesn't have them.
+    pub fn nth_weekdays(&self) -> &[NthWeekday] {
+        match self {
+            Frequency::Monthly { nth_weekdays, .. } | Frequency::Yearly { nth_weekdays, .. } => {
+                nth_weekdays
+            }
+            _ => &[],
+        }
+    }
+
+    /// Returns the months of the year of the frequency, empty when it doesn't have them.
+    pub fn by_month(&self) -> &[Month] {
+        match self {
+            Frequency::Yearly { by_month, .. } => by_month,
+            _ => &[],
+        }
+    }
+
+    /// Returns the dates of the year of the frequency, empty when it doesn't have them.
+    pub fn by_monthly_date(&self) -> &[MonthlyDate] {
+        match self {
+            Frequency::Yearly {
+                by_monthly_date, ..
+            } => by_monthly_date,
+            _ => &[],
+        }
+    }
+
+    /// Returns the ISO week numbers of the frequency, empty when it doesn't have them.
+    pub fn by_week_number(&self) -> &[i32] {
+        match self {
+            Frequency::Yearly { by_week_number, .. } => by_week_number,
+            _ => &[],
+        }
+    }
+
+    /// Returns the overflow policy of the frequency, `OverflowPolicy::Skip` when it doesn't have one.
+    pub fn overflow(&self) -> OverflowPolicy {
+        match self {
+            Frequency::Monthly { overflow, .. } | Frequency::Yearly { overflow, .. } => *overflow,
+            _ => OverflowPolicy::Skip,
+        }
+    }
+
+    /// Returns the warnings of a valid frequency, describing rules that may not behave as expected.
+    ///
+    /// E.g. A monthly frequency on day 31 skips the months with less than 31 days, unless
+    /// another overflow policy is used.
+    /// ```
+    /// use std::num::NonZeroU32;
+    /// use rrules::{Frequency, OverflowPolicy};
+    ///
+    /// let every_31st = Frequency::Monthly {
+    ///     interval: NonZeroU32::new(1).unwrap(),
+    ///     by_month_day: vec![31],
+    ///     by_day: vec![],
+    ///     nth_weekdays: vec![],
+    ///     by_time: vec![],
+    ///     overflow: OverflowPolicy::Skip,
+    /// };
+    /// assert!(every_31st.is_valid().is_ok());
+    /// assert_eq!(every_31st.warnings().len(), 1);
+    /// ```
+    pub fn warnings(&self) -> Vec<String> {
+        match self {
+            Frequency::Monthly {
+                by_month_day,
+                overflow: OverflowPolicy::Skip,
+                ..
+            } => monthly_day_warnings(by_month_day),
             Frequency::Yearly {
-                interval,
                 by_monthly_date,
-            } => validate_yearly(interval, by_monthly_date),
+                overflow: OverflowPolicy::Skip,
+                ..
+            } => monthly_date_warnings(by_monthly_date),
+            _ => vec![],
         }
     }
 
+    /// Returns the frequency with the values of its rules sorted and without duplicates.
+    ///
+    /// The days of the week are sorted from Monday. Parsed frequencies and the frequencies
+    /// of new recurrences are normalized, so the same rules written in a different order
+    /// are equal and serialized the same way.
+    /// ```
+    /// use std::num::NonZeroU32;
+    /// use rrules::Frequency;
+    /// use chrono::Weekday;
+    ///
+    /// let weekly = Frequency::Weekly {
+    ///     interval: NonZeroU32::new(1).unwrap(),
+    ///     by_day: vec![Weekday::Sun, Weekday::Mon, Weekday::Mon],
+    ///     by_time: vec![],
+    ///     by_day_time: vec![],
+    /// };
+    /// assert_eq!(weekly.normalized().by_day(), &[Weekday::Mon, Weekday::Sun]);
+    /// ```
+    pub fn normalized(mut self) -> Self {
+        match &mut self {
+            Frequency::Secondly {
+                by_day, by_time, ..
+            }
+            | Frequency::Minutely {
+                by_day, by_time, ..
+            }
+            | Frequency::Hourly {
+                by_day, by_time, ..
+            } => {
+                sort_weekdays(by_day);
+                sort_and_dedup(by_time);
+            }
+            Frequency::Daily {
+                by_day, by_time, ..
+            } => {
+                sort_weekdays(by_day);
+                sort_and_dedup(by_time);
+            }
+            Frequency::BusinessDaily { .. } => {}
+            Frequency::Weekly {
+                by_day,
+                by_time,
+                by_day_time,
+                ..
+            } => {
+                sort_weekdays(by_day);
+                sort_and_dedup(by_time);
+                sort_day_times(by_day_time);
+            }
+            Frequency::Monthly {
+                by_month_day,
+                by_day,
+                nth_weekdays,
+                by_time,
+                ..
+            } => {
+                sort_and_dedup(by_month_day);
+                sort_weekdays(by_day);
+                sort_and_dedup(nth_weekdays);
+                sort_and_dedup(by_time);
+            }
+            Frequency::Yearly {
+                by_month,
+                by_monthly_date,
+                nth_weekdays,
+                by_week_number,
+                by_time,
+                ..
+            } => {
+                sort_and_dedup(by_month);
+                sort_and_dedup(by_monthly_date);
+                sort_and_dedup(nth_weekdays);
+                sort_and_dedup(by_week_number);
+                sort_and_dedup(by_time);
+            }
+        }
+        self
+    }
+
     /// Returns the next event date for the current frequencies config given the current date.
-    /// Returns None if there is no next event.
+    /// Returns None if there is no next event, or if it is too far away to be represented.
     /// E.g. If the frequency is once a day and the current date is 2020-01-01, the next event date will be 2020-01-02.
     pub fn next_event(&self, current_date: &DateTime<Utc>) -> Option<DateTime<Utc>> {
-        match self {
-            Frequency::Secondly { interval } => {
-                let next_date = current_date.add(chrono::Duration::seconds(*interval as i64));
-                Some(next_date)
-            }
-            Frequency::Minutely { interval } => {
-                let next_date = current_date.add(chrono::Duration::minutes(*interval as i64));
-                Some(next_date)
+        self.next_aligned_event(current_date, current_date)
+    }
+
+    /// Returns the next event date after the current date, with the intervals counted from the anchor.
+    ///
+    /// Only the monthly nth weekdays, the daily times and the weekly days are looked for in the
+    /// months, days or weeks a whole number of intervals away from the anchor, the other frequencies count the
+    /// intervals from the current date, which must be the anchor or one of the events to stay aligned.
+    pub(crate) fn next_aligned_event(
+        &self,
+        anchor: &DateTime<Utc>,
+        current_date: &DateTime<Utc>,
+    ) -> Option<DateTime<Utc>> {
+        let by_time = self.times_of_dates();
+        if by_time.is_empty() {
+            return self.next_aligned_date_event(anchor, current_date);
+        }
+        // The days are looked for at the time of the anchor, then moved to the times
+        let today = current_date.date_naive();
+        let today_at_anchor = at_time_of(today, anchor);
+        if self.contains_date(&today_at_anchor) && self.is_aligned_date(anchor, &today_at_anchor) {
+            let next_date = times_on(today, by_time).find(|date| date > current_date);
+            if next_date.is_some() {
+                return next_date;
             }
-            Frequency::Hourly { interval } => {
-                let next_date = current_date.add(chrono::Duration::hours(*interval as i64));
-                Some(next_date)
+        }
+        let next_day = self.next_aligned_date_event(anchor, &today_at_anchor)?;
+        times_on(next_day.date_naive(), by_time).next()
+    }
+
+    /// Returns the times of the monthly and yearly frequencies, which are repeated on each
+    /// of the days of their events.
+    fn times_of_dates(&self) -> &[Time] {
+        match self {
+            Frequency::Monthly { by_time, .. } | Frequency::Yearly { by_time, .. } => by_time,
+            _ => &[],
+        }
+    }
+
+    /// Same as `next_aligned_event`, with the monthly and yearly events at the time of the
+    /// current date instead of their times.
+    fn next_aligned_date_event(
+        &self,
+        anchor: &DateTime<Utc>,
+        current_date: &DateTime<Utc>,
+    ) -> Option<DateTime<Utc>> {
+        match self {
+            Frequency::Secondly {
+                interval,
+                by_day,
+                by_time,
+            } => next_sub_daily_event(
+                current_date,
+                Duration::try_seconds(i64::from(interval.get()))?,
+                by_day,
+                by_time,
+                None,
+            ),
+            Frequency::Minutely {
+                interval,
+                by_day,
+                by_time,
+                by_hour_range,
+            } => next_sub_daily_event(
+                current_date,
+                Duration::try_minutes(i64::from(interval.get()))?,
+                by_day,
+                by_time,
+                *by_hour_range,
+            ),
+            Frequency::Hourly {
+                interval,
+                by_day,
+                by_time,
+                by_hour_range,
+            } => next_sub_daily_event(
+                current_date,
+                Duration::try_hours(i64::from(interval.get()))?,
+                by_day,
+                by_time,
+                *by_hour_range,
+            ),
+            Frequency::Daily {
+                interval,
+                by_day,
+                by_time,
+            } => {
+                // The weekdays of the days repeat every 7 intervals, so there are no events
+                // on the days when none of the events of 7 intervals are on them
+                let events_per_interval = by_time.len().max(1);
+                let mut date = *current_date;
+                for _ in 0..7 * events_per_interval {
+                    date = next_daily_event(&date, anchor, *interval, by_time)?;
+                    if by_day.is_empty() || by_day.contains(&date.weekday()) {
+                        return Some(date);
+                    }
+                }
+                None
             }
-            Frequency::Daily { interval, by_time } => {
-                next_daily_event(current_date, *interval, by_time)
+            Frequency::BusinessDaily { interval } => {
+                next_business_day_event(current_date, anchor, *interval)
             }
-            Frequency::Weekly { interval, by_day } => {
-                next_weekly_event(current_date, *interval, by_day)
+            Frequency::Weekly {
+                interval,
+                by_day_time,
+                ..
+            } if !by_day_time.is_empty() => {
+                next_weekly_day_time_event(current_date, anchor, *interval, by_day_time)
             }
+            Frequency::Weekly {
+                interval,
+                by_day,
+                by_time,
+                ..
+            } if !by_time.is_empty() => next_weekly_day_time_event(
+                current_date,
+                anchor,
+                *interval,
+                &weekly_day_times(anchor, by_day, by_time),
+            ),
+            Frequency::Weekly {
+                interval, by_day, ..
+            } => next_weekly_event(current_date, anchor, *interval, by_day),
             Frequency::Monthly {
                 interval,
                 by_month_day,
+                by_day,
+                nth_weekdays,
+                overflow,
+                ..
+            } => _next_monthly_event(
+                current_date,
+                anchor,
+                *interval,
+                by_month_day,
+                by_day,
                 nth_weekdays,
-            } => _next_monthly_event(current_date, *interval, by_month_day, nth_weekdays),
+                overflow,
+            ),
             Frequency::Yearly {
                 interval,
+                by_month,
                 by_monthly_date,
-            } => next_yearly_event(current_date, *interval, by_monthly_date),
+                nth_weekdays,
+                by_week_number,
+                overflow,
+                ..
+            } => {
+                // Without filters, the events are on the date of the anchor,
+                // with the overflow policy applied to the years that don't have it
+                let anchor_date;
+                let by_monthly_date = if by_month.is_empty()
+                    && by_monthly_date.is_empty()
+                    && nth_weekdays.is_empty()
+                    && by_week_number.is_empty()
+                {
+                    anchor_date = [MonthlyDate {
+                        month: month_from_number(anchor.month() as i32)?,
+                        day: anchor.day() as i32,
+                    }];
+                    &anchor_date[..]
+                } else {
+                    by_monthly_date
+                };
+                next_yearly_event(
+                    current_date,
+                    *interval,
+                    by_month,
+                    by_monthly_date,
+                    nth_weekdays,
+                    by_week_number,
+                    overflow,
+                )
+            }
+        }
+    }
+
+    /// Returns the constant time between events, when the frequency has no filters
+    /// and its events are evenly spaced.
+    pub(crate) fn fixed_step(&self) -> Option<Duration> {
+        let (interval, step) = match self {
+            Frequency::Secondly {
+                interval,
+                by_day,
+                by_time,
+            } if by_day.is_empty() && by_time.is_empty() => (interval, Duration::seconds(1)),
+            Frequency::Minutely {
+                interval,
+                by_day,
+                by_time,
+                by_hour_range: None,
+            } if by_day.is_empty() && by_time.is_empty() => (interval, Duration::minutes(1)),
+            Frequency::Hourly {
+                interval,
+                by_day,
+                by_time,
+                by_hour_range: None,
+            } if by_day.is_empty() && by_time.is_empty() => (interval, Duration::hours(1)),
+            Frequency::Daily {
+                interval,
+                by_day,
+                by_time,
+                ..
+            } if by_day.is_empty() && by_time.is_empty() => (interval, Duration::days(1)),
+            Frequency::Weekly {
+                interval,
+                by_day,
+                by_time,
+                by_day_time,
+            } if by_day.is_empty() && by_time.is_empty() && by_day_time.is_empty() => {
+                (interval, Duration::weeks(1))
+            }
+            _ => return None,
+        };
+        step.checked_mul(i32::try_from(interval.get()).ok()?)
+            .filter(|step| *step > Duration::zero())
+    }
+
+    /// Returns the frequency whose events from the start date are the events of this frequency
+    /// moved by the offset, or None when the moved events don't follow the same kind of rules,
+    /// like the events on the 31st of each month moved to the next day.
+    pub(crate) fn shifted(&self, start: &DateTime<Utc>, offset: Duration) -> Option<Frequency> {
+        let whole_days = offset == Duration::days(offset.num_days());
+        let whole_weeks = whole_days && offset.num_days() % 7 == 0;
+        match self {
+            // Sub-daily events happen at any time of the day, so they only stay on their
+            // day when they are moved by whole days
+            Frequency::Secondly { .. } | Frequency::Minutely { .. } | Frequency::Hourly { .. } => {
+                if whole_weeks || (whole_days && self.by_day().is_empty()) {
+                    Some(self.clone())
+                } else if self.by_day().is_empty() {
+                    self.with_times_shifted(offset, true)
+                } else {
+                    None
+                }
+            }
+            // Events that stay on their day only need their times moved
+            _ if start.checked_add_signed(offset)?.date_naive() == start.date_naive() => {
+                self.with_times_shifted(offset, false)
+            }
+            Frequency::Monthly { .. } | Frequency::Yearly { .. } => None,
+            _ if whole_weeks => Some(self.clone()),
+            _ if whole_days => (!matches!(self, Frequency::BusinessDaily { .. })
+                && self.by_day().is_empty()
+                && self.by_day_time().is_empty())
+            .then(|| self.clone()),
+            _ => self.fixed_step().map(|_| self.clone()),
         }
     }
 
+    /// Returns the frequency with its times of the day moved by the offset, wrapping them
+    /// around midnight or returning None when they would move to another day.
+    fn with_times_shifted(&self, offset: Duration, wrap: bool) -> Option<Frequency> {
+        let mut shifted = self.clone();
+        if let Frequency::Minutely { by_hour_range, .. } | Frequency::Hourly { by_hour_range, .. } =
+            &mut shifted
+        {
+            if let Some((start, end)) = *by_hour_range {
+                if offset != Duration::hours(offset.num_hours()) {
+                    return None;
+                }
+                // The ranges can't go past midnight
+                let shifted_start = (i64::from(start) + offset.num_hours()).rem_euclid(24);
+                let shifted_end = shifted_start + i64::from(end - start);
+                if shifted_end > 24 {
+                    return None;
+                }
+                *by_hour_range = Some((shifted_start as u8, shifted_end as u8));
+            }
+        }
+        let (by_time, day_times) = match &mut shifted {
+            Frequency::Secondly { by_time, .. }
+            | Frequency::Minutely { by_time, .. }
+            | Frequency::Hourly { by_time, .. }
+            | Frequency::Daily { by_time, .. }
+            | Frequency::Monthly { by_time, .. }
+            | Frequency::Yearly { by_time, .. } => (by_time, None),
+            Frequency::Weekly {
+                by_time,
+                by_day_time,
+                ..
+            } => (by_time, Some(by_day_time)),
+            Frequency::BusinessDaily { .. } => return Some(shifted),
+        };
+        let times = by_time
+            .iter_mut()
+            .chain(day_times.into_iter().flatten().map(|(_, time)| time));
+        for time in times {
+            *time = shift_time(time, offset, wrap)?;
+        }
+        Some(shifted)
+    }
+
     /// Verifies if the specified date is a valid event date for the current frequency.
     /// E.g. If the frequency is once a day and the date is 2023-01-01, the method will return true.
     /// If the frequency is Once a week on Monday and the date is 2023-01-01, the method will return false
     /// because the date is not a Monday.
     ///
     /// ```
+    /// use std::num::NonZeroU32;
     /// use std::str::FromStr;
     /// use rrules::Frequency;
     /// use chrono::{Utc, DateTime, Duration, Weekday};
     ///
-    /// let once_a_day = Frequency::Daily {interval: 1,by_time: vec![]};
+    /// let once_a_day = Frequency::Daily {interval: NonZeroU32::new(1).unwrap(), by_day: vec![],by_time: vec![]};
     /// let sunday = DateTime::<Utc>::from_str("2023-01-01T00:00:00Z").unwrap();
     /// assert!(once_a_day.contains(&sunday));
     ///
-    /// let every_monday = Frequency::Weekly {interval: 1, by_day: vec![Weekday::Mon]};
+    /// let every_monday = Frequency::Weekly {interval: NonZeroU32::new(1).unwrap(), by_day: vec![Weekday::Mon], by_time: vec![], by_day_time: vec![]};
     /// assert!(!every_monday.contains(&sunday));
     /// ```
     pub fn contains(&self, date: &DateTime<Utc>) -> bool {
+        let by_time = self.times_of_dates();
+        if by_time.is_empty() {
+            return self.contains_date(date);
+        }
+        times_on(date.date_naive(), by_time).any(|event| event == *date) && self.contains_date(date)
+    }
+
+    /// Same as `contains`, without checking the times of the monthly and yearly frequencies.
+    fn contains_date(&self, date: &DateTime<Utc>) -> bool {
         match self {
-            Frequency::Secondly { .. } => true,
-            Frequency::Minutely { .. } => true,
-            Frequency::Hourly { .. } => true,
-            Frequency::Daily { by_time, .. } => {
-                if by_time.is_empty() {
-                    return true;
-                }
-                // Return 1 minute from current date to confirm if the current date could be
-                // the next event date.
-                let start = date.sub(Duration::minutes(1));
-                match self.next_event(&start) {
-                    None => false,
-                    Some(next_date) => next_date == *date,
-                }
+            Frequency::Secondly {
+                by_day, by_time, ..
+            }
+            | Frequency::Minutely {
+                by_day, by_time, ..
+            }
+            | Frequency::Hourly {
+                by_day, by_time, ..
+            } => {
+                (by_day.is_empty() || by_day.contains(&date.weekday()))
+                    && (by_time.is_empty() || matches_time(date, by_time))
+                    && self
+                        .by_hour_range()
+                        .is_none_or(|range| in_hour_range(date, range))
+            }
+            Frequency::Daily {
+                by_day, by_time, ..
+            } => {
+                (by_day.is_empty() || by_day.contains(&date.weekday()))
+                    && (by_time.is_empty()
+                        || times_on(date.date_naive(), by_time).any(|event| event == *date))
+            }
+            Frequency::BusinessDaily { .. } => is_business_day(&date.date_naive()),
+            Frequency::Weekly { by_day_time, .. } if !by_day_time.is_empty() => {
+                matches_day_time(date, by_day_time)
+            }
+            Frequency::Weekly {
+                by_day, by_time, ..
+            } if !by_time.is_empty() => {
+                (by_day.is_empty() || by_day.contains(&date.weekday()))
+                    && by_time
+                        .iter()
+                        .any(|time| time.on(date.date_naive()).as_ref() == Some(date))
             }
             Frequency::Weekly { by_day, .. } => {
                 by_day.is_empty() || by_day.contains(&date.weekday())
@@ -244,15 +1234,35 @@ impl Frequency {
             Frequency::Monthly {
                 nth_weekdays,
                 by_month_day,
+                by_day,
+                overflow,
                 ..
             } => {
-                if by_month_day.is_empty() && nth_weekdays.is_empty() {
+                if by_month_day.is_empty() && by_day.is_empty() && nth_weekdays.is_empty() {
                     return true;
                 }
-                let day = date.day() as i32;
 
-                if !by_month_day.is_empty() {
-                    return by_month_day.contains(&day);
+                if !by_month_day.is_empty() || !by_day.is_empty() {
+                    // With the NextValid policy, the dates can come from the previous month
+                    let Some(month_start) = date.with_day(1) else {
+                        return false;
+                    };
+                    return [
+                        month_start.checked_sub_months(Months::new(1)),
+                        Some(month_start),
+                    ]
+                    .into_iter()
+                    .flatten()
+                    .any(|month_start| {
+                        month_day_candidates(
+                            &month_start,
+                            by_month_day,
+                            by_day,
+                            nth_weekdays,
+                            overflow,
+                        )
+                        .contains(date)
+                    });
                 }
                 let weekday = date.weekday();
                 let week_number = weekday_ordinal(date);
@@ -264,117 +1274,775 @@ impl Frequency {
                 false
             }
             Frequency::Yearly {
-                by_monthly_date, ..
+                by_month,
+                by_monthly_date,
+                nth_weekdays,
+                by_week_number,
+                overflow,
+                ..
             } => {
-                if let Some(by_monthly_date) = by_monthly_date {
-                    let month = date.month() as i32;
-                    let day = date.day() as i32;
-                    return by_monthly_date.month.number_from_month() == month as u32
-                        && by_monthly_date.day == day;
+                if by_month.is_empty()
+                    && by_monthly_date.is_empty()
+                    && nth_weekdays.is_empty()
+                    && by_week_number.is_empty()
+                {
+                    return true;
                 }
-                true
+                // ISO weeks can start in the previous year or end in the next one
+                (date.year() - 1..=date.year() + 1).any(|year| {
+                    yearly_candidates(
+                        date,
+                        year,
+                        by_month,
+                        by_monthly_date,
+                        nth_weekdays,
+                        by_week_number,
+                        overflow,
+                    )
+                    .contains(date)
+                })
+            }
+        }
+    }
+
+    /// Verifies if the date is a whole number of intervals away from the anchor date,
+    /// which is usually the start date of a recurrence.
+    ///
+    /// `contains` only checks the filters of the frequency, so an every 2 weeks frequency
+    /// contains every week. The events keep the time of the anchor unless the frequency
+    /// defines its own times, and without filters they keep its day too.
+    pub(crate) fn is_aligned(&self, anchor: &DateTime<Utc>, date: &DateTime<Utc>) -> bool {
+        let by_time = self.times_of_dates();
+        if by_time.is_empty() {
+            return self.is_aligned_date(anchor, date);
+        }
+        times_on(date.date_naive(), by_time).any(|event| event == *date)
+            && self.is_aligned_date(anchor, &at_time_of(date.date_naive(), anchor))
+    }
+
+    /// Same as `is_aligned`, with the monthly and yearly events at the time of the anchor
+    /// instead of their times.
+    fn is_aligned_date(&self, anchor: &DateTime<Utc>, date: &DateTime<Utc>) -> bool {
+        let same_time = date.time() == anchor.time();
+        match self {
+            Frequency::Secondly { interval, .. } => {
+                Duration::try_seconds(i64::from(interval.get()))
+                    .is_some_and(|step| is_aligned_to_step(anchor, date, step))
+            }
+            Frequency::Minutely { interval, .. } => {
+                Duration::try_minutes(i64::from(interval.get()))
+                    .is_some_and(|step| is_aligned_to_step(anchor, date, step))
+            }
+            Frequency::Hourly { interval, .. } => Duration::try_hours(i64::from(interval.get()))
+                .is_some_and(|step| is_aligned_to_step(anchor, date, step)),
+            Frequency::Daily {
+                interval, by_time, ..
+            } => {
+                let days = (date.date_naive() - anchor.date_naive()).num_days();
+                days.rem_euclid(i64::from(interval.get())) == 0
+                    && if by_time.is_empty() {
+                        same_time
+                    } else {
+                        times_on(date.date_naive(), by_time).any(|event| event == *date)
+                    }
+            }
+            Frequency::BusinessDaily { interval } => {
+                let day = date.date_naive();
+                let business_days =
+                    business_day_index(&day) - business_day_index(&anchor.date_naive());
+                same_time
+                    && is_business_day(&day)
+                    && business_days.rem_euclid(i64::from(interval.get())) == 0
+            }
+            Frequency::Weekly {
+                interval,
+                by_day,
+                by_time,
+                by_day_time,
+            } => {
+                // Weeks start on Monday, counted from the week of the anchor
+                let weeks = weeks_from(&anchor.date_naive(), &date.date_naive());
+                if weeks.rem_euclid(i64::from(interval.get())) != 0 {
+                    return false;
+                }
+                if !by_day_time.is_empty() {
+                    return matches_day_time(date, by_day_time);
+                }
+                if !by_time.is_empty() {
+                    return matches_day_time(date, &weekly_day_times(anchor, by_day, by_time));
+                }
+                same_time && (!by_day.is_empty() || date.weekday() == anchor.weekday())
+            }
+            Frequency::Monthly {
+                interval,
+                by_month_day,
+                by_day,
+                nth_weekdays,
+                overflow,
+                ..
+            } => {
+                // Without filters, the events are on the day of the anchor
+                let anchor_day = [anchor.day() as i32];
+                let by_month_day =
+                    if by_month_day.is_empty() && by_day.is_empty() && nth_weekdays.is_empty() {
+                        &anchor_day[..]
+                    } else {
+                        by_month_day
+                    };
+                let month_index = |d: &DateTime<Utc>| d.year() as i64 * 12 + d.month0() as i64;
+                let anchor_month = if by_month_day.is_empty() {
+                    Some(*anchor)
+                } else {
+                    month_day_source(anchor, by_month_day, by_day, nth_weekdays, overflow)
+                };
+                let anchor_index = anchor_month.as_ref().map_or(0, month_index);
+                let is_aligned_month = |d: &DateTime<Utc>| {
+                    (month_index(d) - anchor_index).rem_euclid(i64::from(interval.get())) == 0
+                };
+                if !same_time {
+                    return false;
+                }
+                if !by_month_day.is_empty() || !by_day.is_empty() {
+                    // With the NextValid policy, the dates can come from the previous month
+                    let Some(month_start) = date.with_day(1) else {
+                        return false;
+                    };
+                    return [
+                        month_start.checked_sub_months(Months::new(1)),
+                        Some(month_start),
+                    ]
+                    .into_iter()
+                    .flatten()
+                    .filter(is_aligned_month)
+                    .any(|month_start| {
+                        month_day_candidates(
+                            &month_start,
+                            by_month_day,
+                            by_day,
+                            nth_weekdays,
+                            overflow,
+                        )
+                        .contains(date)
+                    });
+                }
+                is_aligned_month(date)
+            }
+            Frequency::Yearly {
+                interval,
+                by_month,
+                by_monthly_date,
+                nth_weekdays,
+                by_week_number,
+                overflow,
+                ..
+            } => {
+                let year = |d: &DateTime<Utc>| yearly_period(d, by_week_number);
+                let only_months = by_monthly_date.is_empty()
+                    && nth_weekdays.is_empty()
+                    && by_week_number.is_empty();
+                let unfiltered = only_months && by_month.is_empty();
+                if (i64::from(year(date)) - i64::from(year(anchor)))
+                    .rem_euclid(i64::from(interval.get()))
+                    != 0
+                    || !same_time
+                {
+                    return false;
+                }
+                if unfiltered {
+                    return overflow.resolve(date.year(), anchor.month(), anchor.day() as i32)
+                        == Some(date.date_naive());
+                }
+                // The months alone repeat the day of the anchor
+                !only_months || date.day() == anchor.day()
             }
         }
     }
 }
 
-fn next_daily_event(
+/// Returns the day at the time of the date.
+fn at_time_of(day: NaiveDate, date: &DateTime<Utc>) -> DateTime<Utc> {
+    Utc.from_utc_datetime(&day.and_time(date.time()))
+}
+
+/// Returns the dates of the day at the times, sorted.
+fn times_on(day: NaiveDate, by_time: &[Time]) -> impl Iterator<Item = DateTime<Utc>> {
+    let mut dates: Vec<DateTime<Utc>> = by_time.iter().filter_map(|time| time.on(day)).collect();
+    dates.sort();
+    dates.into_iter()
+}
+
+fn sort_and_dedup<T: Ord>(values: &mut Vec<T>) {
+    values.sort();
+    values.dedup();
+}
+
+/// Sorts the days of the week from Monday, removing the duplicates.
+fn sort_weekdays(weekdays: &mut Vec<Weekday>) {
+    weekdays.sort_by_key(|weekday| weekday.num_days_from_monday());
+    weekdays.dedup();
+}
+
+/// Sorts the days and times of the week from Monday, removing the duplicates.
+fn sort_day_times(day_times: &mut Vec<(Weekday, Time)>) {
+    day_times.sort_by_key(|(weekday, time)| (weekday.num_days_from_monday(), time.clone()));
+    day_times.dedup();
+}
+
+/// Maximum number of jumps done looking for a sub-daily event matching the filters.
+const MAX_SUB_DAILY_JUMPS: usize = 1000;
+
+fn next_sub_daily_event(
     current_date: &DateTime<Utc>,
-    interval: i32,
-    by_time: &Vec<Time>,
+    step: Duration,
+    by_day: &[Weekday],
+    by_time: &[Time],
+    by_hour_range: Option<(u8, u8)>,
 ) -> Option<DateTime<Utc>> {
-    let mut next_date = current_date.add(chrono::Duration::days(interval as i64));
+    let mut next_date = current_date.checked_add_signed(step)?;
+    if by_day.is_empty() && by_time.is_empty() && by_hour_range.is_none() {
+        return Some(next_date);
+    }
 
-    if !by_time.is_empty() {
-        for time in by_time {
-            let d = current_date
-                .with_hour(time.hour as u32)
-                .unwrap()
-                .with_minute(time.minute as u32)
-                .unwrap();
-            if d > *current_date {
-                return Some(d);
-            }
+    // Jump straight to the next day or time window that could match, aligned to the interval
+    for _ in 0..MAX_SUB_DAILY_JUMPS {
+        if !by_day.is_empty() && !by_day.contains(&next_date.weekday()) {
+            let next_day = next_date.date_naive().succ_opt()?.and_hms_opt(0, 0, 0)?;
+            next_date = align_to_step(current_date, step, &Utc.from_utc_datetime(&next_day))?;
+            continue;
+        }
+        if let Some(range) = by_hour_range.filter(|range| !in_hour_range(&next_date, *range)) {
+            next_date = align_to_step(current_date, step, &next_hour_window(&next_date, range)?)?;
+            continue;
+        }
+        if !by_time.is_empty() && !matches_time(&next_date, by_time) {
+            next_date = align_to_step(current_date, step, &next_time_window(&next_date, by_time)?)?;
+            continue;
         }
+        return Some(next_date);
+    }
+    None
+}
 
-        // No hours left in the day, so we need to add a day
-        next_date = next_date
-            .with_hour(by_time[0].hour as u32)
-            .unwrap()
-            .with_minute(by_time[0].minute as u32)
-            .unwrap();
+/// Returns the first date after or at the target that is a multiple of steps from the current date.
+fn align_to_step(
+    current_date: &DateTime<Utc>,
+    step: Duration,
+    target: &DateTime<Utc>,
+) -> Option<DateTime<Utc>> {
+    let step_seconds = step.num_seconds();
+    let diff_seconds = target.signed_duration_since(*current_date).num_seconds();
+    let steps = ((diff_seconds + step_seconds - 1) / step_seconds).max(1);
+    current_date.checked_add_signed(Duration::seconds(steps.checked_mul(step_seconds)?))
+}
+
+/// Verifies if the date is a whole number of steps away from the anchor date.
+fn is_aligned_to_step(anchor: &DateTime<Utc>, date: &DateTime<Utc>, step: Duration) -> bool {
+    let diff = date.signed_duration_since(*anchor);
+    diff.subsec_nanos() == 0 && diff.num_seconds().rem_euclid(step.num_seconds()) == 0
+}
+
+/// Returns the start of the next time window after the given date.
+fn next_time_window(date: &DateTime<Utc>, by_time: &[Time]) -> Option<DateTime<Utc>> {
+    let mut times = by_time.to_vec();
+    times.sort_by_key(|time| (time.hour, time.minute));
+    let current = (date.hour() as i32, date.minute() as i32);
+    let (day, time) = match times.iter().find(|t| (t.hour, t.minute) > current) {
+        Some(time) => (date.date_naive(), time),
+        None => (date.date_naive().succ_opt()?, &times[0]),
+    };
+    let next_window = day.and_hms_opt(time.hour as u32, time.minute as u32, 0)?;
+    Some(Utc.from_utc_datetime(&next_window))
+}
+
+/// Returns the start of the next range of hours after the given date, today or tomorrow.
+fn next_hour_window(date: &DateTime<Utc>, (start, _): (u8, u8)) -> Option<DateTime<Utc>> {
+    let day = if date.hour() < u32::from(start) {
+        date.date_naive()
+    } else {
+        date.date_naive().succ_opt()?
+    };
+    Some(Utc.from_utc_datetime(&day.and_hms_opt(u32::from(start), 0, 0)?))
+}
+
+/// Verifies if the hour of the date is within the range of hours, excluding its end.
+fn in_hour_range(date: &DateTime<Utc>, (start, end): (u8, u8)) -> bool {
+    (u32::from(start)..u32::from(end)).contains(&date.hour())
+}
+
+/// Returns the time moved by the offset, wrapping it around midnight or returning None when
+/// it would move to another day. Sub-daily times are matched by their minute, so they can't
+/// be moved by fractions of minutes.
+fn shift_time(time: &Time, offset: Duration, wrap: bool) -> Option<Time> {
+    const DAY_SECONDS: i64 = 24 * 60 * 60;
+    let step = if wrap { 60 } else { 1 };
+    if offset.subsec_nanos() != 0 || offset.num_seconds() % step != 0 {
+        return None;
     }
-    Some(next_date)
+    let seconds =
+        i64::from(time.hour * 3600 + time.minute * 60 + time.second) + offset.num_seconds();
+    let seconds = if wrap {
+        seconds.rem_euclid(DAY_SECONDS)
+    } else if (0..DAY_SECONDS).contains(&seconds) {
+        seconds
+    } else {
+        return None;
+    };
+    let seconds = i32::try_from(seconds).ok()?;
+    Some(Time {
+        hour: seconds / 3600,
+        minute: seconds / 60 % 60,
+        second: seconds % 60,
+    })
 }
 
-fn next_weekly_event(
+/// Verifies if the hour and minute of the date match one of the times.
+fn matches_time(date: &DateTime<Utc>, by_time: &[Time]) -> bool {
+    by_time
+        .iter()
+        .any(|time| time.hour == date.hour() as i32 && time.minute == date.minute() as i32)
+}
+
+fn next_daily_event(
     current_date: &DateTime<Utc>,
-    interval: i32,
-    by_day: &Vec<Weekday>,
+    anchor: &DateTime<Utc>,
+    interval: NonZeroU32,
+    by_time: &[Time],
 ) -> Option<DateTime<Utc>> {
-    let next_date = current_date.add(chrono::Duration::weeks(interval as i64));
+    if by_time.is_empty() {
+        return current_date.checked_add_signed(Duration::try_days(i64::from(interval.get()))?);
+    }
 
-    if !by_day.is_empty() {
-        let current_weekday_num = current_date.weekday().num_days_from_sunday() + 1;
-        let _d = current_date.format("%Y-%m-%d").to_string();
-        for day in by_day {
-            let day_num = day.num_days_from_sunday() + 1;
-            if day_num > current_weekday_num {
-                let diff = day_num - current_weekday_num;
-                return Some(current_date.add(chrono::Duration::days(diff as i64)));
-            }
-        }
-        // No days left in the week, so we need to add a week
-        if let Some(d) = current_date.with_weekday(by_day[0]) {
-            return d.shift_weeks(interval as i64);
-        }
+    // The days of the events are a whole number of intervals away from the day of the anchor
+    let today = current_date.date_naive();
+    let days = (today - anchor.date_naive()).num_days();
+    let day =
+        today.checked_sub_days(Days::new(days.rem_euclid(i64::from(interval.get())) as u64))?;
+    if let Some(next_date) = times_on(day, by_time).find(|date| date > current_date) {
+        return Some(next_date);
+    }
+    // No times left in the day, so we need to move to the first time of the next day
+    let next_day = day.checked_add_days(Days::new(u64::from(interval.get())))?;
+    times_on(next_day, by_time).next()
+}
+
+/// Returns the first date after the current one on a business day a whole number of
+/// intervals away from the day of the anchor, keeping the time of the current date.
+fn next_business_day_event(
+    current_date: &DateTime<Utc>,
+    anchor: &DateTime<Utc>,
+    interval: NonZeroU32,
+) -> Option<DateTime<Utc>> {
+    let today = current_date.date_naive();
+    // Weekends share the index of the following Monday
+    let mut index = business_day_index(&today);
+    if is_business_day(&today) {
+        index += 1;
     }
-    Some(next_date)
+    index +=
+        (business_day_index(&anchor.date_naive()) - index).rem_euclid(i64::from(interval.get()));
+    let day = business_day(index)?;
+    Some(Utc.from_utc_datetime(&day.and_time(current_date.time())))
+}
+
+fn is_business_day(day: &NaiveDate) -> bool {
+    !matches!(day.weekday(), Weekday::Sat | Weekday::Sun)
+}
+
+/// Returns the number of business days before the day, counted from the 1st of January
+/// of year 1, which is a Monday.
+fn business_day_index(day: &NaiveDate) -> i64 {
+    let days = day.num_days_from_ce() as i64 - 1;
+    days.div_euclid(7) * 5 + days.rem_euclid(7).min(5)
+}
+
+/// Returns the business day with the given index, the opposite of `business_day_index`.
+fn business_day(index: i64) -> Option<NaiveDate> {
+    let days = index.div_euclid(5) * 7 + index.rem_euclid(5) + 1;
+    NaiveDate::from_num_days_from_ce_opt(i32::try_from(days).ok()?)
+}
+
+/// First day of the weeks, the default `WKST=MO` of RFC 5545.
+const WEEK_START: Weekday = Weekday::Mon;
+
+/// Returns the days from the start of the week of the date to the date.
+fn days_into_week(date: &NaiveDate) -> i64 {
+    i64::from(date.weekday().days_since(WEEK_START))
+}
+
+/// Returns the number of weeks from the week of the anchor to the week of the date.
+fn weeks_from(anchor: &NaiveDate, date: &NaiveDate) -> i64 {
+    ((*date - *anchor).num_days() - days_into_week(date) + days_into_week(anchor)) / 7
+}
+
+/// Returns the days from the current date to the start of the last week a whole number of
+/// intervals away from the week of the anchor, on or before the week of the current date,
+/// and to the start of the one after it.
+fn aligned_weeks(
+    current_date: &DateTime<Utc>,
+    anchor: &DateTime<Utc>,
+    interval: NonZeroU32,
+) -> [i64; 2] {
+    let today = current_date.date_naive();
+    let interval = i64::from(interval.get());
+    let offset = weeks_from(&anchor.date_naive(), &today).rem_euclid(interval);
+    let week = -days_into_week(&today) - 7 * offset;
+    [week, week + 7 * interval]
+}
+
+/// Returns the day on the weekday of the week that starts the given days after the date.
+fn day_of_week(date: NaiveDate, week: i64, weekday: &Weekday) -> Option<NaiveDate> {
+    let days = week + i64::from(weekday.days_since(WEEK_START));
+    date.checked_add_signed(Duration::try_days(days)?)
+}
+
+/// Returns the first date after the current one on the days of the weeks a whole number
+/// of intervals away from the week of the anchor, keeping the time of the current date.
+///
+/// Without days, the events are on the weekday of the anchor.
+fn next_weekly_event(
+    current_date: &DateTime<Utc>,
+    anchor: &DateTime<Utc>,
+    interval: NonZeroU32,
+    by_day: &[Weekday],
+) -> Option<DateTime<Utc>> {
+    let anchor_day = [anchor.weekday()];
+    let by_day = if by_day.is_empty() {
+        &anchor_day[..]
+    } else {
+        by_day
+    };
+    let (today, time) = (current_date.date_naive(), current_date.time());
+    aligned_weeks(current_date, anchor, interval)
+        .into_iter()
+        .flat_map(|week| {
+            by_day
+                .iter()
+                .filter_map(move |weekday| day_of_week(today, week, weekday))
+        })
+        .map(|day| Utc.from_utc_datetime(&day.and_time(time)))
+        .filter(|date| date > current_date)
+        .min()
+}
+
+/// Returns the first of the days and times after the current date, in the weeks a whole
+/// number of intervals away from the week of the anchor.
+fn next_weekly_day_time_event(
+    current_date: &DateTime<Utc>,
+    anchor: &DateTime<Utc>,
+    interval: NonZeroU32,
+    by_day_time: &[(Weekday, Time)],
+) -> Option<DateTime<Utc>> {
+    let today = current_date.date_naive();
+    aligned_weeks(current_date, anchor, interval)
+        .into_iter()
+        .flat_map(|week| {
+            by_day_time
+                .iter()
+                .filter_map(move |(weekday, time)| time.on(day_of_week(today, week, weekday)?))
+        })
+        .filter(|date| date > current_date)
+        .min()
+}
+
+/// Returns the `by_time` times on each of the `by_day` days, or on the weekday of the
+/// anchor without days, sorted from Monday.
+pub(crate) fn weekly_day_times(
+    anchor: &DateTime<Utc>,
+    by_day: &[Weekday],
+    by_time: &[Time],
+) -> Vec<(Weekday, Time)> {
+    let anchor_day = [anchor.weekday()];
+    let by_day = if by_day.is_empty() {
+        &anchor_day[..]
+    } else {
+        by_day
+    };
+    let mut day_times = by_day
+        .iter()
+        .flat_map(|weekday| by_time.iter().map(|time| (*weekday, time.clone())))
+        .collect();
+    sort_day_times(&mut day_times);
+    day_times
+}
+
+/// Verifies if the date is on one of the days of the week, at its time.
+fn matches_day_time(date: &DateTime<Utc>, by_day_time: &[(Weekday, Time)]) -> bool {
+    by_day_time.iter().any(|(weekday, time)| {
+        *weekday == date.weekday() && time.on(date.date_naive()).as_ref() == Some(date)
+    })
 }
 
 fn _next_monthly_event(
     current_date: &DateTime<Utc>,
-    interval: i32,
-    by_month_day: &Vec<i32>,
-    nth_weekdays: &Vec<NthWeekday>,
+    anchor: &DateTime<Utc>,
+    interval: NonZeroU32,
+    by_month_day: &[i32],
+    by_day: &[Weekday],
+    nth_weekdays: &[NthWeekday],
+    overflow: &OverflowPolicy,
 ) -> Option<DateTime<Utc>> {
-    let next_date = current_date.shift_months(interval as i64);
-    if !by_month_day.is_empty() {
-        let current_month_day = current_date.day() as i32;
-        for day in by_month_day {
-            if *day > current_month_day {
-                if let Some(d) = current_date.with_day(*day as u32) {
-                    return Some(d);
-                }
-            }
+    if !by_month_day.is_empty() || !by_day.is_empty() {
+        return next_month_day_event(
+            current_date,
+            interval,
+            by_month_day,
+            by_day,
+            nth_weekdays,
+            overflow,
+        );
+    }
+    if !nth_weekdays.is_empty() {
+        return get_next_aligned_nth_weekday(
+            current_date,
+            anchor,
+            i64::from(interval.get()),
+            nth_weekdays,
+        );
+    }
+    // Without filters, the events are on the day of the anchor,
+    // with the overflow policy applied to the months that don't have it
+    next_month_day_event(
+        current_date,
+        interval,
+        &[anchor.day() as i32],
+        &[],
+        &[],
+        overflow,
+    )
+}
+
+/// Returns the next date on one of the month days, applying the overflow policy
+/// to the months that don't have them.
+fn next_month_day_event(
+    current_date: &DateTime<Utc>,
+    interval: NonZeroU32,
+    by_month_day: &[i32],
+    by_day: &[Weekday],
+    nth_weekdays: &[NthWeekday],
+    overflow: &OverflowPolicy,
+) -> Option<DateTime<Utc>> {
+    let mut month_start =
+        month_day_source(current_date, by_month_day, by_day, nth_weekdays, overflow)?;
+    // Every month day exists at least once in 12 months, but the month days on some
+    // weekdays can take years, like Friday the 13th every 12 months
+    let max_jumps = if by_day.is_empty() && nth_weekdays.is_empty() {
+        12
+    } else {
+        MAX_MONTHLY_JUMPS
+    };
+    for _ in 0..=max_jumps {
+        let next_date =
+            month_day_candidates(&month_start, by_month_day, by_day, nth_weekdays, overflow)
+                .into_iter()
+                .find(|date| date > current_date);
+        if next_date.is_some() {
+            return next_date;
         }
-        // No days left in the month, so we need to add a month
-        if let Some(d) = current_date.with_day(by_month_day[0] as u32) {
-            return d.shift_months(interval as i64);
+        trace::trace!(month = %month_start.date_naive(), "No event in the month");
+        month_start = month_start
+            .shift_months_with(i64::from(interval.get()), OverflowPolicy::ClampToLastDay)?;
+    }
+    trace::debug!(months = max_jumps, "No event in the following months");
+    None
+}
+
+/// Returns the start of the month whose events include the date.
+///
+/// With the NextValid policy, the dates can come from the previous month, which must be
+/// kept to stay aligned to the interval.
+fn month_day_source(
+    date: &DateTime<Utc>,
+    by_month_day: &[i32],
+    by_day: &[Weekday],
+    nth_weekdays: &[NthWeekday],
+    overflow: &OverflowPolicy,
+) -> Option<DateTime<Utc>> {
+    let month_start = date.with_day(1)?;
+    match month_start.checked_sub_months(Months::new(1)) {
+        Some(previous_month)
+            if month_day_candidates(
+                &previous_month,
+                by_month_day,
+                by_day,
+                nth_weekdays,
+                overflow,
+            )
+            .contains(date) =>
+        {
+            Some(previous_month)
         }
+        _ => Some(month_start),
     }
-    if !nth_weekdays.is_empty() {
-        return get_next_nth_weekday(current_date, interval as i64, nth_weekdays);
+}
+
+/// Returns all the sorted event dates of the month, keeping the time of its first day.
+///
+/// The weekdays restrict the month days when both are set, and without month days every
+/// day of the month on the weekdays is an event.
+fn month_day_candidates(
+    month_start: &DateTime<Utc>,
+    by_month_day: &[i32],
+    by_day: &[Weekday],
+    nth_weekdays: &[NthWeekday],
+    overflow: &OverflowPolicy,
+) -> Vec<DateTime<Utc>> {
+    let mut days: Vec<NaiveDate> = if by_month_day.is_empty() {
+        month_start
+            .date_naive()
+            .iter_days()
+            .take_while(|day| day.month() == month_start.month())
+            .collect()
+    } else {
+        by_month_day
+            .iter()
+            .filter_map(|day| overflow.resolve(month_start.year(), month_start.month(), *day))
+            .collect()
+    };
+    // Days moved to the first day of the following month are left to that month when it
+    // has its own event on the 1st, so that every date belongs to a single month
+    if by_month_day.contains(&1) {
+        days.retain(|day| day.month() == month_start.month());
     }
-    next_date
+    days.sort();
+    days.dedup();
+    let is_on_weekday = |date: &DateTime<Utc>| {
+        (by_day.is_empty() && nth_weekdays.is_empty())
+            || by_day.contains(&date.weekday())
+            || nth_weekdays.iter().any(|nth| {
+                nth.weekday == date.weekday() && nth.week_number == weekday_ordinal(date)
+            })
+    };
+    days.into_iter()
+        .map(|day| Utc.from_utc_datetime(&day.and_time(month_start.time())))
+        .filter(is_on_weekday)
+        .collect()
 }
 
+/// Maximum number of years jumped looking for a yearly event.
+/// The Gregorian calendar repeats itself every 400 years.
+const MAX_YEARLY_JUMPS: i64 = 400;
+
 fn next_yearly_event(
     current_date: &DateTime<Utc>,
-    interval: i32,
-    by_monthly_date: &Option<MonthlyDate>,
+    interval: NonZeroU32,
+    by_month: &[Month],
+    by_monthly_date: &[MonthlyDate],
+    nth_weekdays: &[NthWeekday],
+    by_week_number: &[i32],
+    overflow: &OverflowPolicy,
 ) -> Option<DateTime<Utc>> {
-    if let Some(by_monthly_date) = by_monthly_date {
-        let month_number = by_monthly_date.month.number_from_month();
-        let result = current_date
-            .with_month(month_number)
-            .unwrap()
-            .with_day(by_monthly_date.day as u32)?;
-        return if result > *current_date {
-            Some(result)
+    // Look for a date left in the current year before moving to the next ones,
+    // skipping the years without any of the dates. The current date is either the
+    // start date or an event, so the years stay a number of intervals away from the start.
+    let current_year = yearly_period(current_date, by_week_number);
+    for n in 0..=MAX_YEARLY_JUMPS {
+        let year = i32::try_from(current_year as i64 + n * i64::from(interval.get())).ok()?;
+        let next_date = yearly_candidates(
+            current_date,
+            year,
+            by_month,
+            by_monthly_date,
+            nth_weekdays,
+            by_week_number,
+            overflow,
+        )
+        .into_iter()
+        .find(|date| date > current_date);
+        if next_date.is_some() {
+            return next_date;
+        }
+        trace::trace!(year, "No event in the year");
+    }
+    trace::debug!(years = MAX_YEARLY_JUMPS, "No event in the following years");
+    None
+}
+
+/// Returns the year the intervals of a yearly frequency are counted in.
+/// Week numbers belong to the ISO year, which can differ from the calendar year.
+fn yearly_period(date: &DateTime<Utc>, by_week_number: &[i32]) -> i32 {
+    if by_week_number.is_empty() {
+        date.year()
+    } else {
+        date.iso_week().year()
+    }
+}
+
+/// Returns all the sorted event dates of the given year, keeping the time of the given date.
+fn yearly_candidates(
+    date: &DateTime<Utc>,
+    year: i32,
+    by_month: &[Month],
+    by_monthly_date: &[MonthlyDate],
+    nth_weekdays: &[NthWeekday],
+    by_week_number: &[i32],
+    overflow: &OverflowPolicy,
+) -> Vec<DateTime<Utc>> {
+    let mut days: Vec<NaiveDate> = if !by_week_number.is_empty() {
+        let weekdays: Vec<Weekday> = if nth_weekdays.is_empty() {
+            vec![date.weekday()]
         } else {
-            result.shift_years(interval as i64)
+            nth_weekdays.iter().map(|nth| nth.weekday).collect()
         };
+        by_week_number
+            .iter()
+            .flat_map(|week| {
+                weekdays
+                    .iter()
+                    .filter_map(move |weekday| iso_week_date(year, *week, *weekday))
+            })
+            .collect()
+    } else if !nth_weekdays.is_empty() && !by_month.is_empty() {
+        by_month
+            .iter()
+            .flat_map(|month| {
+                nth_weekdays
+                    .iter()
+                    .filter_map(move |nth| nth_weekday_of_month(year, *month, nth))
+            })
+            .collect()
+    } else if !nth_weekdays.is_empty() {
+        nth_weekdays
+            .iter()
+            .filter_map(|nth| nth_weekday_of_year(year, nth))
+            .collect()
+    } else if by_monthly_date.is_empty() {
+        by_month
+            .iter()
+            .filter_map(|month| {
+                NaiveDate::from_ymd_opt(year, month.number_from_month(), date.day())
+            })
+            .collect()
+    } else {
+        by_monthly_date
+            .iter()
+            .filter_map(|monthly_date| {
+                overflow.resolve(
+                    year,
+                    monthly_date.month.number_from_month(),
+                    monthly_date.day,
+                )
+            })
+            .collect()
+    };
+
+    if !by_monthly_date.is_empty() && (!by_week_number.is_empty() || !nth_weekdays.is_empty()) {
+        days.retain(|day| {
+            by_monthly_date.iter().any(|monthly_date| {
+                monthly_date.month.number_from_month() == day.month()
+                    && monthly_date.day == day.day() as i32
+            })
+        });
+    }
+    if !by_month.is_empty() && (!by_week_number.is_empty() || !by_monthly_date.is_empty()) {
+        days.retain(|day| {
+            by_month
+                .iter()
+                .any(|month| month.number_from_month() == day.month())
+        });
     }
-    current_date.shift_years(interval as i64)
+    days.sort();
+    days.dedup();
+    days.into_iter()
+        .map(|day| Utc.from_utc_datetime(&day.and_time(date.time())))
+        .collect()
 }
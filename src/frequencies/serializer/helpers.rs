@@ -1,472 +1,648 @@
-use crate::frequencies::serializer::{MonthUtils, WeekdayUtils};
+use crate::codes::weekday_code;
+use crate::frequencies::serializer::tokenizer::{tokenize, RuleParam};
+use crate::frequencies::validations::validate_monthly_date;
 use crate::frequencies::InvalidFrequency;
-use crate::{MonthlyDate, NthWeekday, Time};
+use crate::{MonthlyDate, NthWeekday, OverflowPolicy, Time};
+use alloc::string::ToString;
+use alloc::vec::Vec;
+use alloc::{format, vec};
 use chrono::{Month, Weekday};
-use std::str::FromStr;
-
-pub fn extract_frequency(s: &str) -> Option<(String, String)> {
-    use regex::Regex;
-    let re = Regex::new(r"FREQ=[A-Z]*;").unwrap();
-    match re.find(s) {
-        Some(pair) => {
-            let (key, value) = split_key_value(&pair)?;
-            Some((value.clone(), s.replace(&format!("{key}={value};"), "")))
-        }
-        None => None,
-    }
-}
 
-pub fn extract_interval(s: &str) -> Option<(i32, String)> {
-    use regex::Regex;
-    let re = Regex::new(r"INTERVAL=[0-9]*").unwrap();
-    match re.find(s) {
-        Some(pair) => {
-            let (_, value) = split_key_value(&pair)?;
-            match value.parse::<i32>() {
-                Ok(v) => Some((v, s.replace(&format!("INTERVAL={value};"), ""))),
-                Err(_) => None,
-            }
-        }
-        None => None,
-    }
+/// The frequency parameters found in a rule string.
+///
+/// Parts that are not `KEY=VALUE` pairs and keys that are not frequency parameters are ignored.
+/// When a key is repeated, its first value is used.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RuleParams {
+    params: Vec<RuleParam>,
 }
 
-pub fn extract_times(s: &str) -> Result<(Vec<Time>, String), InvalidFrequency> {
-    use regex::Regex;
-    if !s.contains("BYTIME") {
-        return Ok((vec![], s.to_string()));
-    }
-    let re = Regex::new(r"BYTIME=[0-9|:|,]*").unwrap();
-    match re.find(s) {
-        Some(pair) => {
-            let (_, value) = match split_key_value(&pair) {
-                Some(res) => res,
-                None => {
-                    return Err(InvalidFrequency::Format {
-                        message: format!("Cannot parse by_time from value {s}"),
-                    })
-                }
-            };
-            let mut times: Vec<Time> = vec![];
-            for time in value.split(',') {
-                match Time::from_str(time) {
-                    Ok(t) => times.push(t),
-                    Err(_) => {
-                        return Err(InvalidFrequency::Format {
-                            message: format!("Cannot parse time from value {time}"),
-                        })
-                    }
-                }
+impl RuleParams {
+    pub fn parse(s: &str) -> Result<Self, InvalidFrequency> {
+        let mut params = vec![];
+        for token in tokenize(s).flatten() {
+            if let Some(param) = RuleParam::from_token(&token) {
+                params.push(param.map_err(InvalidFrequency::from)?);
             }
-            Ok((times, s.replace(&format!("BYTIME={value}"), "")))
         }
-        None => Err(InvalidFrequency::Format {
-            message: format!("Cannot parse by_time from value {s}"),
-        }),
-    }
-}
-
-pub fn extract_weekdays(s: &str) -> Result<(Vec<Weekday>, String), InvalidFrequency> {
-    use regex::Regex;
-    if !s.contains("BYDAY") {
-        return Ok((vec![], s.to_string()));
-    }
-    let re = Regex::new(r"BYDAY=[A-Z|,]*").unwrap();
-    match re.find(s) {
-        Some(pair) => {
-            let (_, value) = match split_key_value(&pair) {
-                Some(res) => res,
-                None => {
-                    return Err(InvalidFrequency::Format {
-                        message: format!("Cannot parse by_day from value {s}"),
-                    })
-                }
-            };
-            let mut weekdays: Vec<Weekday> = vec![];
-            for weekday in value.split(',') {
-                match Weekday::from_str_short(weekday) {
-                    Ok(w) => weekdays.push(w),
-                    Err(_) => {
-                        return Err(InvalidFrequency::Format {
-                            message: format!("Cannot parse weekday from value {weekday}"),
-                        })
-                    }
-                }
+        Ok(RuleParams { params })
+    }
+
+    pub fn frequency(&self) -> Option<&str> {
+        self.params.iter().find_map(|param| match param {
+            RuleParam::Freq(frequency) => Some(frequency.as_str()),
+            _ => None,
+        })
+    }
+
+    pub fn interval(&self) -> Option<u32> {
+        self.params.iter().find_map(|param| match param {
+            RuleParam::Interval(interval) => Some(*interval),
+            _ => None,
+        })
+    }
+
+    fn days(&self) -> &[(Option<i32>, Weekday)] {
+        self.params
+            .iter()
+            .find_map(|param| match param {
+                RuleParam::ByDay(days) => Some(days.as_slice()),
+                _ => None,
+            })
+            .unwrap_or_default()
+    }
+
+    /// Days of the week defined without ordinals.
+    pub fn weekdays(&self) -> Result<Vec<Weekday>, InvalidFrequency> {
+        self.days()
+            .iter()
+            .map(|(nth, weekday)| match nth {
+                None => Ok(*weekday),
+                Some(nth) => Err(InvalidFrequency::Format {
+                    message: format!(
+                        "Cannot parse weekday from value {nth}{}",
+                        weekday_code(*weekday)
+                    ),
+                }),
+            })
+            .collect()
+    }
+
+    /// Days of the week defined with ordinals.
+    pub fn nth_weekdays(&self) -> Result<Vec<NthWeekday>, InvalidFrequency> {
+        self.days()
+            .iter()
+            .map(|(nth, weekday)| match nth {
+                Some(nth) => Ok(NthWeekday::new(*weekday, *nth)),
+                None => Err(InvalidFrequency::Format {
+                    message: format!(
+                        "Cannot parse nth_weekday from value {}",
+                        weekday_code(*weekday)
+                    ),
+                }),
+            })
+            .collect()
+    }
+
+    /// Days of the week split between the ones defined without ordinals and the ones
+    /// defined with them.
+    pub fn weekdays_and_nth_weekdays(&self) -> (Vec<Weekday>, Vec<NthWeekday>) {
+        let mut weekdays = Vec::new();
+        let mut nth_weekdays = Vec::new();
+        for (nth, weekday) in self.days() {
+            match nth {
+                Some(nth) => nth_weekdays.push(NthWeekday::new(*weekday, *nth)),
+                None => weekdays.push(*weekday),
             }
-            Ok((weekdays, s.replace(&format!("BYDAY={value}"), "")))
         }
-        None => Err(InvalidFrequency::Format {
-            message: format!("Cannot parse by_day from value {s}"),
-        }),
-    }
-}
-
-pub fn extract_monthdays(s: &str) -> Result<(Vec<i32>, String), InvalidFrequency> {
-    use regex::Regex;
-    if !s.contains("BYMONTHDAY") {
-        return Ok((vec![], s.to_string()));
-    }
-    let re = Regex::new(r"BYMONTHDAY=[0-9|,]*").unwrap();
-    match re.find(s) {
-        Some(pair) => {
-            let (_, value) = match split_key_value(&pair) {
-                Some(res) => res,
-                None => {
-                    return Err(InvalidFrequency::Format {
-                        message: format!("Cannot parse by_monthday from value {s}"),
-                    })
-                }
-            };
-            let mut monthdays: Vec<i32> = vec![];
-            for monthday in value.split(',') {
-                match monthday.parse::<i32>() {
-                    Ok(m) => monthdays.push(m),
-                    Err(_) => {
-                        return Err(InvalidFrequency::Format {
-                            message: format!("Cannot parse monthday from value {monthday}"),
-                        })
-                    }
+        (weekdays, nth_weekdays)
+    }
+
+    /// Days of the week with their time, defined by the X-BYDAYTIME key.
+    pub fn day_times(&self) -> Vec<(Weekday, Time)> {
+        self.params
+            .iter()
+            .find_map(|param| match param {
+                RuleParam::ByDayTime(day_times) => Some(day_times.clone()),
+                _ => None,
+            })
+            .unwrap_or_default()
+    }
+
+    pub fn month_days(&self) -> Vec<i32> {
+        self.params
+            .iter()
+            .find_map(|param| match param {
+                RuleParam::ByMonthDay(days) => Some(days.clone()),
+                _ => None,
+            })
+            .unwrap_or_default()
+    }
+
+    pub fn months(&self) -> Vec<Month> {
+        self.params
+            .iter()
+            .find_map(|param| match param {
+                RuleParam::ByMonth(months) => Some(months.clone()),
+                _ => None,
+            })
+            .unwrap_or_default()
+    }
+
+    pub fn week_numbers(&self) -> Vec<i32> {
+        self.params
+            .iter()
+            .find_map(|param| match param {
+                RuleParam::ByWeekNo(week_numbers) => Some(week_numbers.clone()),
+                _ => None,
+            })
+            .unwrap_or_default()
+    }
+
+    /// Policy for the month days that don't exist in some months, defined by the X-OVERFLOW
+    /// or the SKIP key.
+    pub fn overflow(&self) -> OverflowPolicy {
+        self.params
+            .iter()
+            .find_map(|param| match param {
+                RuleParam::Overflow(overflow) => Some(*overflow),
+                _ => None,
+            })
+            .unwrap_or_default()
+    }
+
+    /// Range of hours of minutely and hourly frequencies, defined by the X-BYHOURRANGE key.
+    pub fn hour_range(&self) -> Option<(u8, u8)> {
+        self.params.iter().find_map(|param| match param {
+            RuleParam::ByHourRange(range) => Some(*range),
+            _ => None,
+        })
+    }
+
+    /// Whether a daily frequency only has events on business days, defined by the X-BUSINESS key.
+    pub fn business(&self) -> bool {
+        self.params
+            .iter()
+            .find_map(|param| match param {
+                RuleParam::Business(business) => Some(*business),
+                _ => None,
+            })
+            .unwrap_or_default()
+    }
+
+    /// Dates of the year defined by the X-BYMONTHDATE key together with the ones
+    /// combining every BYMONTH with every BYMONTHDAY value. BYMONTH values without
+    /// BYMONTHDAY ones are months of the year, so they don't define any date.
+    ///
+    /// The combinations that never exist, like February 30th, are dropped unless the
+    /// overflow policy moves them to another day, and an error is returned when none exists.
+    /// The dates of the X-BYMONTHDATE key are validated against their month.
+    pub fn monthly_dates(&self) -> Result<Vec<MonthlyDate>, InvalidFrequency> {
+        let mut monthly_dates = self
+            .params
+            .iter()
+            .find_map(|param| match param {
+                RuleParam::ByMonthDate(dates) => Some(dates.clone()),
+                _ => None,
+            })
+            .unwrap_or_default();
+
+        let months = self.months();
+        let days = self.month_days();
+        if months.is_empty() && !days.is_empty() {
+            return Err(InvalidFrequency::Format {
+                message: "BYMONTHDAY requires BYMONTH in yearly frequencies".to_string(),
+            });
+        }
+        let overflow = self.overflow();
+        for monthly_date in &monthly_dates {
+            validate_monthly_date(monthly_date, &overflow)?;
+        }
+        let explicit_dates = monthly_dates.len();
+        for month in &months {
+            for day in &days {
+                let monthly_date = MonthlyDate {
+                    month: *month,
+                    day: *day,
+                };
+                if overflow != OverflowPolicy::Skip || *day <= monthly_date.last_day() {
+                    monthly_dates.push(monthly_date);
                 }
             }
-            Ok((monthdays, s.replace(&format!("BYMONTHDAY={value}"), "")))
         }
-        None => Err(InvalidFrequency::Format {
-            message: format!("Cannot parse by_monthday from value {s}"),
-        }),
-    }
-}
-
-pub fn extract_nth_weekdays(s: &str) -> Result<(Vec<NthWeekday>, String), InvalidFrequency> {
-    use regex::Regex;
-    if !s.contains("BYDAY") {
-        return Ok((vec![], s.to_string()));
-    }
-    let re = Regex::new(r"BYDAY=[0-9|A-Z|,]*").unwrap();
-    match re.find(s) {
-        Some(pair) => {
-            let (_, value) = match split_key_value(&pair) {
-                Some(res) => res,
-                None => {
-                    return Err(InvalidFrequency::Format {
-                        message: format!("Cannot parse by_day from value {s}"),
-                    })
-                }
-            };
-            let mut nth_weekdays: Vec<NthWeekday> = vec![];
-            for nth_weekday in value.split(',') {
-                match NthWeekday::from_str(nth_weekday) {
-                    Ok(n) => nth_weekdays.push(n),
-                    Err(_) => {
-                        return Err(InvalidFrequency::Format {
-                            message: format!("Cannot parse nth_weekday from value {nth_weekday}"),
-                        })
-                    }
-                }
+        if !days.is_empty() && monthly_dates.len() == explicit_dates {
+            return Err(InvalidFrequency::Day {
+                message: "BYMONTHDAY values don't exist in any of the BYMONTH months".to_string(),
+            });
+        }
+        Ok(monthly_dates)
+    }
+
+    /// Times of the day defined by the standard BYHOUR, BYMINUTE and BYSECOND keys
+    /// together with the legacy BYTIME key.
+    ///
+    /// BYHOUR, BYMINUTE and BYSECOND values are combined so every minute and second is
+    /// applied to every hour, and minutes and seconds default to 0 when they are not defined.
+    pub fn times(&self) -> Result<Vec<Time>, InvalidFrequency> {
+        let (times, hours, minutes, seconds) = self.time_components();
+        if hours.is_empty() && !minutes.is_empty() {
+            return Err(InvalidFrequency::Format {
+                message: "BYMINUTE requires BYHOUR to be defined".to_string(),
+            });
+        }
+        if hours.is_empty() && seconds.iter().any(|second| *second != 0) {
+            return Err(InvalidFrequency::Format {
+                message: "BYSECOND requires BYHOUR to be defined".to_string(),
+            });
+        }
+        let minutes = if minutes.is_empty() { vec![0] } else { minutes };
+        let seconds = if seconds.is_empty() { vec![0] } else { seconds };
+        Ok(combine_times(times, &hours, &minutes, &seconds))
+    }
+
+    /// Times of the hours and minutes that limit the events of the secondly, minutely and
+    /// hourly frequencies, defined by the BYHOUR and BYMINUTE keys together with BYTIME.
+    ///
+    /// As in RFC 5545, BYHOUR alone keeps every minute of the hours and BYMINUTE alone keeps
+    /// the minutes of every hour. BYSECOND, and BYMINUTE in hourly frequencies, set the time
+    /// of the events instead of limiting them, so they are rejected.
+    pub fn sub_daily_times(&self, hourly: bool) -> Result<Vec<Time>, InvalidFrequency> {
+        let (times, hours, minutes, seconds) = self.time_components();
+        if !seconds.is_empty() {
+            return Err(InvalidFrequency::Format {
+                message: "BYSECOND is not supported by sub-daily frequencies, use BYTIME"
+                    .to_string(),
+            });
+        }
+        if hourly && !minutes.is_empty() {
+            return Err(InvalidFrequency::Format {
+                message: "BYMINUTE is not supported by hourly frequencies, use BYTIME".to_string(),
+            });
+        }
+        if hours.is_empty() && minutes.is_empty() {
+            return Ok(times);
+        }
+        let hours = if hours.is_empty() {
+            (0..24).collect()
+        } else {
+            hours
+        };
+        let minutes = if minutes.is_empty() {
+            (0..60).collect()
+        } else {
+            minutes
+        };
+        Ok(combine_times(times, &hours, &minutes, &[0]))
+    }
+
+    /// The BYTIME, BYHOUR, BYMINUTE and BYSECOND values of the rule.
+    fn time_components(&self) -> (Vec<Time>, Vec<i32>, Vec<i32>, Vec<i32>) {
+        let mut times = vec![];
+        let mut hours = vec![];
+        let mut minutes = vec![];
+        let mut seconds = vec![];
+        for param in &self.params {
+            match param {
+                RuleParam::ByTime(values) if times.is_empty() => times = values.clone(),
+                RuleParam::ByHour(values) if hours.is_empty() => hours = values.clone(),
+                RuleParam::ByMinute(values) if minutes.is_empty() => minutes = values.clone(),
+                RuleParam::BySecond(values) if seconds.is_empty() => seconds = values.clone(),
+                _ => {}
             }
-            Ok((nth_weekdays, s.replace(&format!("BYDAY={value}"), "")))
         }
-        None => Err(InvalidFrequency::Format {
-            message: format!("Cannot parse by_day from value {s}"),
-        }),
+        (times, hours, minutes, seconds)
     }
 }
 
-pub fn extract_months(s: &str) -> Result<(Vec<Month>, String), InvalidFrequency> {
-    use regex::Regex;
-    if !s.contains("BYMONTH") {
-        return Ok((vec![], s.to_string()));
-    }
-    let re = Regex::new(r"BYMONTH=[0-9|,]*").unwrap();
-    match re.find(s) {
-        Some(pair) => {
-            let (_, value) = match split_key_value(&pair) {
-                Some(res) => res,
-                None => {
-                    return Err(InvalidFrequency::Format {
-                        message: format!("Cannot parse by_month from value {s}"),
-                    })
-                }
-            };
-            let mut months: Vec<Month> = vec![];
-            for month in value.split(',') {
-                match month.parse::<i32>() {
-                    Ok(m) => months.push(Month::from_i32(m)?),
-                    Err(_) => {
-                        return Err(InvalidFrequency::Format {
-                            message: format!("Cannot parse month from value {month}"),
-                        })
-                    }
+/// Adds every combination of the hours, minutes and seconds to the times that are not in them yet.
+fn combine_times(
+    mut times: Vec<Time>,
+    hours: &[i32],
+    minutes: &[i32],
+    seconds: &[i32],
+) -> Vec<Time> {
+    for hour in hours {
+        for minute in minutes {
+            for second in seconds {
+                let time = Time {
+                    hour: *hour,
+                    minute: *minute,
+                    second: *second,
+                };
+                if !times.contains(&time) {
+                    times.push(time);
                 }
             }
-            Ok((months, s.replace(&format!("BYMONTH={value}"), "")))
         }
-        None => Err(InvalidFrequency::Format {
-            message: format!("Cannot parse by_month from value {s}"),
-        }),
     }
+    times
 }
 
-pub fn extract_monthly_date(s: &str) -> Result<(Option<MonthlyDate>, String), InvalidFrequency> {
-    if !s.contains("BYMONTHDAY") && !s.contains("BYMONTH") {
-        return Ok((None, s.to_string()));
-    }
-    let (days, s) = extract_monthdays(s)?;
-    if days.len() != 1 {
-        return Err(InvalidFrequency::Format {
-            message: format!("Cannot parse monthly_date from value {s}"),
-        });
-    }
-    let (months, s) = extract_months(&s)?;
-    if months.len() != 1 {
-        return Err(InvalidFrequency::Format {
-            message: format!("Cannot parse monthly_date from value {s}"),
-        });
-    }
-    let monthly_date = MonthlyDate {
-        day: days[0],
-        month: months[0],
-    };
-    Ok((Some(monthly_date), s))
-}
+#[cfg(test)]
+mod test_helpers {
+    use crate::codes::weekday_code;
+    use crate::frequencies::serializer::helpers::RuleParams;
+    use crate::NthWeekday;
+    use chrono::{Month, Weekday};
 
-fn split_key_value(pair: &regex::Match) -> Option<(String, String)> {
-    let res = pair.as_str().to_string();
-    let key_value: Vec<&str> = res.split('=').collect();
-    if key_value.len() != 2 {
-        return None;
-    }
-    let value = key_value[1];
-    if value == ";" {
-        return None;
+    #[test]
+    fn test_frequency() {
+        let params = RuleParams::parse("FREQ=SECONDLY;INTERVAL=1").unwrap();
+        assert_eq!(params.frequency(), Some("SECONDLY"));
     }
-    Some((key_value[0].to_string(), value.replace(';', "")))
-}
 
-#[cfg(test)]
-mod test_helpers {
-    use crate::frequencies::serializer::helpers::{
-        extract_frequency, extract_interval, extract_monthdays, extract_monthly_date,
-        extract_months, extract_nth_weekdays, extract_times, extract_weekdays,
-    };
-    use crate::frequencies::serializer::WeekdayUtils;
-    use chrono::Month;
+    #[test]
+    fn test_interval() {
+        let params = RuleParams::parse("FREQ=SECONDLY;INTERVAL=1").unwrap();
+        assert_eq!(params.interval(), Some(1));
+    }
 
     #[test]
-    fn test_extract_frequency() {
-        let value = "FREQ=SECONDLY;INTERVAL=1";
-        let (freq, remainder) = extract_frequency(&value).unwrap();
-        assert_eq!(freq, "SECONDLY");
-        assert_eq!(remainder, "INTERVAL=1");
+    fn test_interval_invalid() {
+        assert!(RuleParams::parse("FREQ=SECONDLY;INTERVAL=INVALID").is_err());
     }
 
     #[test]
-    fn test_extract_interval() {
-        let value = "FREQ=SECONDLY;INTERVAL=1";
-        let (interval, remainder) = extract_interval(&value).unwrap();
-        assert_eq!(interval, 1);
+    fn test_interval_empty() {
+        assert!(RuleParams::parse("FREQ=SECONDLY;INTERVAL=").is_err());
     }
 
     #[test]
-    fn test_extract_interval_invalid() {
-        let value = "FREQ=SECONDLY;INTERVAL=INVALID";
-        let res = extract_interval(&value);
-        assert!(res.is_none());
+    fn test_interval_with_semicolon() {
+        let params = RuleParams::parse("FREQ=SECONDLY;INTERVAL=1;").unwrap();
+        assert_eq!(params.interval(), Some(1));
     }
 
     #[test]
-    fn test_extract_interval_empty() {
-        let value = "FREQ=SECONDLY;INTERVAL=";
-        let res = extract_interval(&value);
-        assert!(res.is_none());
+    fn test_interval_not_present() {
+        let params = RuleParams::parse("FREQ=SECONDLY").unwrap();
+        assert_eq!(params.interval(), None);
     }
 
     #[test]
-    fn test_extract_interval_with_semicolon() {
-        let value = "FREQ=SECONDLY;INTERVAL=1;";
-        let res = extract_interval(&value);
-        assert!(res.is_some());
-        assert_eq!(res.unwrap().0, 1);
+    fn test_ignores_unknown_and_malformed_parts() {
+        let params =
+            RuleParams::parse("FREQ=YEARLY;BY;DTSTART=2020-01-01T00:00:00Z;INTERVAL=2").unwrap();
+        assert_eq!(params.frequency(), Some("YEARLY"));
+        assert_eq!(params.interval(), Some(2));
     }
 
     #[test]
-    fn test_extract_times() {
-        let value = "FREQ=DAILY;INTERVAL=1;BYTIME=10:00";
-        let (times, remainder) = extract_times(&value).unwrap();
+    fn test_times() {
+        let params = RuleParams::parse("FREQ=DAILY;INTERVAL=1;BYTIME=10:00").unwrap();
+        let times = params.times().unwrap();
         assert_eq!(times.len(), 1);
         assert_eq!(times[0].to_string(), "10:00");
-        assert_eq!(remainder, "FREQ=DAILY;INTERVAL=1;");
     }
 
     #[test]
-    fn test_extract_times_multiple() {
-        let value = "FREQ=DAILY;INTERVAL=1;BYTIME=10:00,11:00";
-        let (times, remainder) = extract_times(&value).unwrap();
-        assert_eq!(times.len(), 2);
-        assert_eq!(times[0].to_string(), "10:00");
-        assert_eq!(times[1].to_string(), "11:00");
-        assert_eq!(remainder, "FREQ=DAILY;INTERVAL=1;");
+    fn test_times_multiple() {
+        let params = RuleParams::parse("FREQ=DAILY;INTERVAL=1;BYTIME=10:00,11:00;").unwrap();
+        let times: Vec<String> = params
+            .times()
+            .unwrap()
+            .iter()
+            .map(|t| t.to_string())
+            .collect();
+        assert_eq!(times, vec!["10:00", "11:00"]);
+    }
+
+    #[test]
+    fn test_times_with_seconds() {
+        let params = RuleParams::parse("FREQ=DAILY;INTERVAL=1;BYTIME=09:30:15,10:00:00").unwrap();
+        let times = params.times().unwrap();
+        assert_eq!(
+            (times[0].hour, times[0].minute, times[0].second),
+            (9, 30, 15)
+        );
+        assert_eq!(times[0].to_string(), "09:30:15");
+        assert_eq!(times[1].to_string(), "10:00");
+    }
+
+    #[test]
+    fn test_times_out_of_range() {
+        for value in ["24:00", "10:60", "10:00:60", "10", "10:00:00:00"] {
+            let rule = format!("FREQ=DAILY;INTERVAL=1;BYTIME={value}");
+            assert!(
+                RuleParams::parse(&rule).is_err(),
+                "{value} should not be parsed"
+            );
+        }
     }
 
     #[test]
-    fn test_extract_times_empty() {
-        let value = "FREQ=DAILY;INTERVAL=1;BYTIME=";
-        let res = extract_times(&value);
-        assert!(res.is_err());
+    fn test_times_empty() {
+        assert!(RuleParams::parse("FREQ=DAILY;INTERVAL=1;BYTIME=").is_err());
     }
 
     #[test]
-    fn test_extract_times_invalid() {
-        let value = "FREQ=DAILY;INTERVAL=1;BYTIME=INVALID";
-        let res = extract_times(&value);
-        assert!(res.is_err());
+    fn test_times_invalid() {
+        assert!(RuleParams::parse("FREQ=DAILY;INTERVAL=1;BYTIME=INVALID").is_err());
     }
 
     #[test]
-    fn test_extract_times_with_semicolon() {
-        let value = "FREQ=DAILY;INTERVAL=1;BYTIME=10:00;";
-        let (times, remainder) = extract_times(&value).unwrap();
+    fn test_time_components() {
+        let params = RuleParams::parse("FREQ=DAILY;INTERVAL=1;BYHOUR=9,17;BYMINUTE=0,30").unwrap();
+        let times: Vec<String> = params
+            .times()
+            .unwrap()
+            .iter()
+            .map(|t| t.to_string())
+            .collect();
+        assert_eq!(times, vec!["09:00", "09:30", "17:00", "17:30"]);
+    }
+
+    #[test]
+    fn test_time_components_default_minutes() {
+        let params = RuleParams::parse("FREQ=DAILY;INTERVAL=1;BYHOUR=9;BYSECOND=0").unwrap();
+        let times = params.times().unwrap();
         assert_eq!(times.len(), 1);
-        assert_eq!(times[0].to_string(), "10:00");
+        assert_eq!(times[0].to_string(), "09:00");
     }
 
     #[test]
-    fn test_extract_weekdays() {
-        let value = "FREQ=WEEKLY;INTERVAL=1;BYDAY=MO,TU";
-        let (weekdays, remainder) = extract_weekdays(&value).unwrap();
-        assert_eq!(weekdays.len(), 2);
-        assert_eq!(WeekdayUtils::to_string(&weekdays[0]), "MO");
-        assert_eq!(WeekdayUtils::to_string(&weekdays[1]), "TU");
+    fn test_time_components_with_legacy_by_time() {
+        let params = RuleParams::parse("FREQ=DAILY;INTERVAL=1;BYTIME=10:15;BYHOUR=9").unwrap();
+        let times: Vec<String> = params
+            .times()
+            .unwrap()
+            .iter()
+            .map(|t| t.to_string())
+            .collect();
+        assert_eq!(times, vec!["10:15", "09:00"]);
     }
 
     #[test]
-    fn test_extract_weekdays_empty() {
-        let value = "FREQ=WEEKLY;INTERVAL=1;BYDAY=";
-        let res = extract_weekdays(&value);
-        assert!(res.is_err());
+    fn test_time_components_minutes_without_hours() {
+        let params = RuleParams::parse("FREQ=DAILY;INTERVAL=1;BYMINUTE=30").unwrap();
+        assert!(params.times().is_err());
+    }
+
+    #[test]
+    fn test_time_components_invalid_hour() {
+        assert!(RuleParams::parse("FREQ=DAILY;INTERVAL=1;BYHOUR=24").is_err());
+    }
+
+    #[test]
+    fn test_time_components_with_seconds() {
+        let params =
+            RuleParams::parse("FREQ=DAILY;INTERVAL=1;BYHOUR=9;BYMINUTE=30;BYSECOND=0,15").unwrap();
+        let times: Vec<String> = params
+            .times()
+            .unwrap()
+            .iter()
+            .map(|t| t.to_string())
+            .collect();
+        assert_eq!(times, vec!["09:30", "09:30:15"]);
+    }
+
+    #[test]
+    fn test_sub_daily_time_components_limit_the_events() {
+        let params = RuleParams::parse("FREQ=HOURLY;INTERVAL=1;BYHOUR=9,17").unwrap();
+        let times = params.sub_daily_times(true).unwrap();
+        assert_eq!(times.len(), 120);
+        assert_eq!(times[0].to_string(), "09:00");
+        assert_eq!(times[119].to_string(), "17:59");
+
+        let params = RuleParams::parse("FREQ=MINUTELY;INTERVAL=1;BYMINUTE=0,30").unwrap();
+        let times = params.sub_daily_times(false).unwrap();
+        assert_eq!(times.len(), 48);
+        assert_eq!(times[1].to_string(), "00:30");
+
+        let params = RuleParams::parse("FREQ=HOURLY;INTERVAL=1;BYHOUR=9;BYMINUTE=0").unwrap();
+        assert!(params.sub_daily_times(true).is_err());
+        assert_eq!(params.sub_daily_times(false).unwrap().len(), 1);
+        let params = RuleParams::parse("FREQ=SECONDLY;INTERVAL=1;BYHOUR=9;BYSECOND=0").unwrap();
+        assert!(params.sub_daily_times(false).is_err());
     }
 
     #[test]
-    fn test_extract_weekdays_invalid() {
-        let value = "FREQ=WEEKLY;INTERVAL=1;BYDAY=INVALID";
-        let res = extract_weekdays(&value);
-        assert!(res.is_err());
+    fn test_time_components_seconds_without_hours() {
+        let params = RuleParams::parse("FREQ=DAILY;INTERVAL=1;BYSECOND=30").unwrap();
+        assert!(params.times().is_err());
     }
 
     #[test]
-    fn test_extract_weekdays_with_semicolon() {
-        let value = "FREQ=WEEKLY;INTERVAL=1;BYDAY=MO,TU;";
-        let (weekdays, remainder) = extract_weekdays(&value).unwrap();
+    fn test_weekdays() {
+        let params = RuleParams::parse("FREQ=WEEKLY;INTERVAL=1;BYDAY=MO,TU;").unwrap();
+        let weekdays = params.weekdays().unwrap();
         assert_eq!(weekdays.len(), 2);
-        assert_eq!(WeekdayUtils::to_string(&weekdays[0]), "MO");
-        assert_eq!(WeekdayUtils::to_string(&weekdays[1]), "TU");
+        assert_eq!(weekday_code(weekdays[0]), "MO");
+        assert_eq!(weekday_code(weekdays[1]), "TU");
+    }
+
+    #[test]
+    fn test_weekdays_empty() {
+        assert!(RuleParams::parse("FREQ=WEEKLY;INTERVAL=1;BYDAY=").is_err());
+    }
+
+    #[test]
+    fn test_weekdays_invalid() {
+        assert!(RuleParams::parse("FREQ=WEEKLY;INTERVAL=1;BYDAY=INVALID").is_err());
+    }
+
+    #[test]
+    fn test_weekdays_with_ordinals() {
+        let params = RuleParams::parse("FREQ=WEEKLY;INTERVAL=1;BYDAY=1MO").unwrap();
+        assert!(params.weekdays().is_err());
+    }
+
+    #[test]
+    fn test_weekdays_not_present() {
+        let params = RuleParams::parse("FREQ=WEEKLY;INTERVAL=1").unwrap();
+        assert_eq!(params.weekdays().unwrap().len(), 0);
+    }
+
+    #[test]
+    fn test_month_days() {
+        let params = RuleParams::parse("FREQ=MONTHLY;INTERVAL=1;BYMONTHDAY=1,2").unwrap();
+        assert_eq!(params.month_days(), vec![1, 2]);
+    }
+
+    #[test]
+    fn test_month_days_empty() {
+        assert!(RuleParams::parse("FREQ=MONTHLY;INTERVAL=1;BYMONTHDAY=").is_err());
+    }
+
+    #[test]
+    fn test_month_days_invalid() {
+        assert!(RuleParams::parse("FREQ=MONTHLY;INTERVAL=1;BYMONTHDAY=INVALID").is_err());
     }
 
     #[test]
-    fn test_extract_weekdays_not_present() {
-        let value = "FREQ=WEEKLY;INTERVAL=1";
-        let (weekdays, _) = extract_weekdays(&value).unwrap();
-        assert_eq!(weekdays.len(), 0);
+    fn test_nth_weekdays() {
+        let params = RuleParams::parse("FREQ=MONTHLY;INTERVAL=1;BYDAY=1MO,2TU").unwrap();
+        assert_eq!(params.nth_weekdays().unwrap().len(), 2);
     }
 
     #[test]
-    fn test_extract_monthdays() {
-        let value = "FREQ=MONTHLY;INTERVAL=1;BYMONTHDAY=1,2";
-        let (monthdays, remainder) = extract_monthdays(&value).unwrap();
-        assert_eq!(monthdays.len(), 2);
-        assert_eq!(monthdays[0], 1);
-        assert_eq!(monthdays[1], 2);
+    fn test_nth_weekdays_without_ordinals() {
+        let params = RuleParams::parse("FREQ=MONTHLY;INTERVAL=1;BYDAY=MO").unwrap();
+        assert!(params.nth_weekdays().is_err());
     }
 
     #[test]
-    fn test_extract_monthdays_empty() {
-        let value = "FREQ=MONTHLY;INTERVAL=1;BYMONTHDAY=";
-        let res = extract_monthdays(&value);
-        assert!(res.is_err());
+    fn test_weekdays_and_nth_weekdays() {
+        let params = RuleParams::parse("FREQ=MONTHLY;INTERVAL=1;BYDAY=MO,2TU,FR").unwrap();
+        let (weekdays, nth_weekdays) = params.weekdays_and_nth_weekdays();
+        assert_eq!(weekdays, vec![Weekday::Mon, Weekday::Fri]);
+        assert_eq!(nth_weekdays, vec![NthWeekday::new(Weekday::Tue, 2)]);
     }
 
     #[test]
-    fn test_extract_monthdays_invalid() {
-        let value = "FREQ=MONTHLY;INTERVAL=1;BYMONTHDAY=INVALID";
-        let res = extract_monthdays(&value);
-        assert!(res.is_err());
+    fn test_months() {
+        let params = RuleParams::parse("FREQ=YEARLY;INTERVAL=1;BYMONTH=1,2").unwrap();
+        assert_eq!(params.months(), vec![Month::January, Month::February]);
     }
 
     #[test]
-    fn test_extract_nth_weekdays() {
-        let value = "FREQ=MONTHLY;INTERVAL=1;BYDAY=1MO,2TU";
-        let (nth_weekdays, remainder) = extract_nth_weekdays(&value).unwrap();
-        assert_eq!(nth_weekdays.len(), 2);
+    fn test_months_empty() {
+        assert!(RuleParams::parse("FREQ=YEARLY;INTERVAL=1;BYMONTH=").is_err());
     }
 
     #[test]
-    fn test_extract_nth_weekdays_empty() {
-        let value = "FREQ=MONTHLY;INTERVAL=1;BYDAY=";
-        let res = extract_nth_weekdays(&value);
-        assert!(res.is_err());
+    fn test_months_invalid() {
+        assert!(RuleParams::parse("FREQ=YEARLY;INTERVAL=1;BYMONTH=INVALID").is_err());
     }
 
     #[test]
-    fn test_extract_nth_weekdays_invalid() {
-        let value = "FREQ=MONTHLY;INTERVAL=1;BYDAY=INVALID";
-        let res = extract_nth_weekdays(&value);
-        assert!(res.is_err());
+    fn test_months_not_present() {
+        let params = RuleParams::parse("FREQ=YEARLY;INTERVAL=1").unwrap();
+        assert_eq!(params.months().len(), 0);
     }
 
     #[test]
-    fn test_extract_months() {
-        let value = "FREQ=YEARLY;INTERVAL=1;BYMONTH=1,2";
-        let (months, remainder) = extract_months(&value).unwrap();
-        assert_eq!(months.len(), 2);
+    fn test_yearly_month_date() {
+        let params = RuleParams::parse("FREQ=YEARLY;INTERVAL=1;BYMONTH=1;BYMONTHDAY=1").unwrap();
+        let monthly_dates = params.monthly_dates().unwrap();
+        assert_eq!(monthly_dates.len(), 1);
+        assert_eq!(monthly_dates[0].day, 1);
+        assert_eq!(monthly_dates[0].month, Month::January);
     }
 
     #[test]
-    fn test_extract_months_empty() {
-        let value = "FREQ=YEARLY;INTERVAL=1;BYMONTH=";
-        let res = extract_months(&value);
-        assert!(res.is_err());
+    fn test_yearly_month_dates_multiple() {
+        let params = RuleParams::parse("FREQ=YEARLY;INTERVAL=1;BYMONTH=1,6;BYMONTHDAY=1").unwrap();
+        let monthly_dates = params.monthly_dates().unwrap();
+        assert_eq!(monthly_dates.len(), 2);
+        assert_eq!(monthly_dates[0].month, Month::January);
+        assert_eq!(monthly_dates[1].month, Month::June);
     }
 
     #[test]
-    fn test_extract_months_invalid() {
-        let value = "FREQ=YEARLY;INTERVAL=1;BYMONTH=INVALID";
-        let res = extract_months(&value);
-        assert!(res.is_err());
+    fn test_yearly_explicit_month_dates() {
+        let params = RuleParams::parse("FREQ=YEARLY;INTERVAL=1;X-BYMONTHDATE=JAN15,JUN1").unwrap();
+        let monthly_dates = params.monthly_dates().unwrap();
+        assert_eq!(monthly_dates.len(), 2);
+        assert_eq!(monthly_dates[0].to_string(), "JAN15");
+        assert_eq!(monthly_dates[1].to_string(), "JUN1");
     }
 
     #[test]
-    fn test_extract_months_not_present() {
-        let value = "FREQ=YEARLY;INTERVAL=1";
-        let (months, _) = extract_months(&value).unwrap();
-        assert_eq!(months.len(), 0);
+    fn test_yearly_month_date_empty() {
+        assert!(RuleParams::parse("FREQ=YEARLY;INTERVAL=1;BYMONTH=1;BYMONTHDAY=").is_err());
     }
 
     #[test]
-    fn test_extract_yearly_month_date() {
-        let value = "FREQ=YEARLY;INTERVAL=1;BYMONTH=1;BYMONTHDAY=1";
-        let (monthly_date, remainder) = extract_monthly_date(&value).unwrap();
-        let monthly_date = monthly_date.unwrap();
-        assert_eq!(monthly_date.day, 1);
-        assert_eq!(monthly_date.month, Month::January);
+    fn test_yearly_month_date_not_present() {
+        let params = RuleParams::parse("FREQ=YEARLY;INTERVAL=1;BYMONTH=1").unwrap();
+        assert!(params.monthly_dates().unwrap().is_empty());
     }
 
     #[test]
-    fn test_extract_yearly_month_date_empty() {
-        let value = "FREQ=YEARLY;INTERVAL=1;BYMONTH=1;BYMONTHDAY=";
-        let res = extract_monthly_date(&value);
-        assert!(res.is_err());
+    fn test_yearly_month_day_without_month() {
+        let params = RuleParams::parse("FREQ=YEARLY;INTERVAL=1;BYMONTHDAY=1").unwrap();
+        assert!(params.monthly_dates().is_err());
     }
 
     #[test]
-    fn test_extract_yearly_month_date_invalid() {
-        let value = "FREQ=YEARLY;INTERVAL=1;BYMONTH=1;BYMONTHDAY=INVALID";
-        let res = extract_monthly_date(&value);
-        assert!(res.is_err());
+    fn test_week_numbers() {
+        let params = RuleParams::parse("FREQ=YEARLY;INTERVAL=1;BYWEEKNO=20,-1").unwrap();
+        assert_eq!(params.week_numbers(), vec![20, -1]);
     }
 
     #[test]
-    fn test_extract_yearly_month_date_not_present() {
-        let value = "FREQ=YEARLY;INTERVAL=1;BYMONTH=1";
-        let res = extract_monthly_date(&value);
-        assert!(res.is_err());
+    fn test_week_numbers_invalid() {
+        assert!(RuleParams::parse("FREQ=YEARLY;INTERVAL=1;BYWEEKNO=INVALID").is_err());
     }
 }
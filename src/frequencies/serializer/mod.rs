@@ -1,102 +1,176 @@
 mod helpers;
+mod options;
+mod tokenizer;
 
-use crate::frequencies::serializer::helpers::{
-    extract_frequency, extract_interval, extract_monthdays, extract_monthly_date,
-    extract_nth_weekdays, extract_times, extract_weekdays,
-};
+use crate::codes::{month_code, month_from_code, weekday_code, weekday_from_code};
+use crate::frequencies::serializer::helpers::RuleParams;
+use crate::frequencies::validations::{validate_nth_weekday, MONTH_WEEKS};
 use crate::frequencies::InvalidFrequency;
-use crate::{Frequency, MonthlyDate, NthWeekday, Time};
+use crate::frequencies::ParseError;
+use crate::trace;
+use crate::{FreqKind, Frequency, MonthlyDate, NthWeekday, OverflowPolicy, Time};
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use alloc::{format, vec};
 use chrono::{Month, Weekday};
-use std::fmt::{Display, Formatter};
-use std::str::FromStr;
+use core::fmt::{Display, Formatter};
+use core::num::NonZeroU32;
+use core::str::FromStr;
+pub use options::ParseOptions;
+pub(crate) use options::{validate_strict, FREQUENCY_KEYS};
+use tokenizer::{parse_by_day, parse_by_month_day, parse_by_time, parse_component};
+pub(crate) use tokenizer::{tokenize, Normalized};
+
+impl Display for FreqKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        let name = match self {
+            FreqKind::Secondly => "SECONDLY",
+            FreqKind::Minutely => "MINUTELY",
+            FreqKind::Hourly => "HOURLY",
+            // Business daily frequencies are daily frequencies with the X-BUSINESS key
+            FreqKind::Daily | FreqKind::BusinessDaily => "DAILY",
+            FreqKind::Weekly => "WEEKLY",
+            FreqKind::Monthly => "MONTHLY",
+            FreqKind::Yearly => "YEARLY",
+        };
+        write!(f, "{name}")
+    }
+}
 
 impl Display for Time {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{:02}:{:02}", self.hour, self.minute)
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{:02}:{:02}", self.hour, self.minute)?;
+        if self.second != 0 {
+            write!(f, ":{:02}", self.second)?;
+        }
+        Ok(())
     }
 }
 
-trait WeekdayUtils {
-    fn to_string(&self) -> String;
-    fn from_str_short(s: &str) -> Result<Weekday, InvalidFrequency>;
+impl Display for NthWeekday {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}{}", self.week_number, weekday_code(self.weekday))
+    }
 }
 
-impl WeekdayUtils for Weekday {
-    fn to_string(&self) -> String {
-        match self {
-            Weekday::Mon => "MO".to_string(),
-            Weekday::Tue => "TU".to_string(),
-            Weekday::Wed => "WE".to_string(),
-            Weekday::Thu => "TH".to_string(),
-            Weekday::Fri => "FR".to_string(),
-            Weekday::Sat => "SA".to_string(),
-            Weekday::Sun => "SU".to_string(),
-        }
+impl Display for MonthlyDate {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}{}", month_code(self.month), self.day)
     }
+}
 
-    fn from_str_short(s: &str) -> Result<Weekday, InvalidFrequency> {
-        match s {
-            "MO" => Ok(Weekday::Mon),
-            "TU" => Ok(Weekday::Tue),
-            "WE" => Ok(Weekday::Wed),
-            "TH" => Ok(Weekday::Thu),
-            "FR" => Ok(Weekday::Fri),
-            "SA" => Ok(Weekday::Sat),
-            "SU" => Ok(Weekday::Sun),
-            _ => Err(InvalidFrequency::Day {
-                message: format!("Invalid day: {s}"),
-            })?,
-        }
+impl Display for Frequency {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        self.write_rule(f, false)
     }
 }
 
-impl Display for NthWeekday {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(
-            f,
-            "{}{}",
-            self.week_number,
-            WeekdayUtils::to_string(&self.weekday)
-        )
+/// Writes the rule of a frequency with the RFC 7529 keys for the overflow policy.
+struct RfcRule<'a>(&'a Frequency);
+
+impl Display for RfcRule<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        self.0.write_rule(f, true)
     }
 }
 
-impl Display for Frequency {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+impl Frequency {
+    /// Returns the rule of the frequency using the `RSCALE` and `SKIP` keys of RFC 7529
+    /// for the overflow policy, instead of the `X-OVERFLOW` extension.
+    ///
+    /// Both keys are parsed, and the default policy is omitted.
+    /// ```
+    /// use std::str::FromStr;
+    /// use rrules::Frequency;
+    ///
+    /// let frequency = Frequency::from_str("FREQ=MONTHLY;INTERVAL=1;X-OVERFLOW=NEXT").unwrap();
+    /// assert_eq!(
+    ///     frequency.to_rfc_string(),
+    ///     "FREQ=MONTHLY;INTERVAL=1;RSCALE=GREGORIAN;SKIP=FORWARD"
+    /// );
+    /// assert_eq!(Frequency::from_str(&frequency.to_rfc_string()).unwrap(), frequency);
+    /// ```
+    pub fn to_rfc_string(&self) -> String {
+        RfcRule(self).to_string()
+    }
+
+    /// Writes the rule of the frequency, with the RFC 7529 keys for the overflow policy
+    /// when `rfc` is set.
+    fn write_rule(&self, f: &mut Formatter<'_>, rfc: bool) -> core::fmt::Result {
         match self {
-            Frequency::Secondly { interval } => {
-                write!(f, "FREQ=SECONDLY;INTERVAL={interval}")
+            Frequency::Secondly {
+                interval,
+                by_day,
+                by_time,
+            } => {
+                let value = format!("FREQ=SECONDLY;INTERVAL={interval}");
+                let times = sub_daily_times_to_string(by_time, false);
+                write!(f, "{value}{}{times}", days_to_string(by_day))
             }
-            Frequency::Minutely { interval } => {
-                write!(f, "FREQ=MINUTELY;INTERVAL={interval}")
+            Frequency::Minutely {
+                interval,
+                by_day,
+                by_time,
+                by_hour_range,
+            } => {
+                let mut value = format!("FREQ=MINUTELY;INTERVAL={interval}");
+                value.push_str(&days_to_string(by_day));
+                value.push_str(&sub_daily_times_to_string(by_time, false));
+                if let Some((start, end)) = by_hour_range {
+                    value.push_str(&format!(";X-BYHOURRANGE={start}-{end}"));
+                }
+                write!(f, "{value}")
             }
-            Frequency::Hourly { interval } => {
-                write!(f, "FREQ=HOURLY;INTERVAL={interval}")
+            Frequency::Hourly {
+                interval,
+                by_day,
+                by_time,
+                by_hour_range,
+            } => {
+                let mut value = format!("FREQ=HOURLY;INTERVAL={interval}");
+                value.push_str(&days_to_string(by_day));
+                value.push_str(&sub_daily_times_to_string(by_time, true));
+                if let Some((start, end)) = by_hour_range {
+                    value.push_str(&format!(";X-BYHOURRANGE={start}-{end}"));
+                }
+                write!(f, "{value}")
             }
-            Frequency::Daily { interval, by_time } => {
+            Frequency::Daily {
+                interval,
+                by_day,
+                by_time,
+            } => {
                 let mut value = format!("FREQ=DAILY;INTERVAL={interval}");
-                if by_time.is_empty() {
-                    return write!(f, "{value}");
-                }
-                let by_time_values: Vec<String> =
-                    by_time.iter().map(|time| time.to_string()).collect();
-                value.push_str(&format!(";BYTIME={}", by_time_values.join(",")));
+                value.push_str(&days_and_times_to_string(by_day, by_time));
                 write!(f, "{value}")
             }
-            Frequency::Weekly { interval, by_day } => {
+            Frequency::BusinessDaily { interval } => {
+                write!(f, "FREQ=DAILY;INTERVAL={interval};X-BUSINESS=TRUE")
+            }
+            Frequency::Weekly {
+                interval,
+                by_day,
+                by_time,
+                by_day_time,
+            } => {
                 let mut value = format!("FREQ=WEEKLY;INTERVAL={interval}");
-                if by_day.is_empty() {
-                    return write!(f, "{value}");
+                value.push_str(&days_and_times_to_string(by_day, by_time));
+                if !by_day_time.is_empty() {
+                    let by_day_time_values: Vec<String> = by_day_time
+                        .iter()
+                        .map(|(day, time)| format!("{}{time}", weekday_code(*day)))
+                        .collect();
+                    value.push_str(&format!(";X-BYDAYTIME={}", by_day_time_values.join(",")));
                 }
-                let by_day_values: Vec<String> =
-                    by_day.iter().map(WeekdayUtils::to_string).collect();
-                value.push_str(&format!(";BYDAY={}", by_day_values.join(",")));
                 write!(f, "{value}")
             }
             Frequency::Monthly {
                 interval,
                 by_month_day,
+                by_day,
                 nth_weekdays,
+                by_time,
+                overflow,
             } => {
                 let mut value = format!("FREQ=MONTHLY;INTERVAL={interval}");
 
@@ -106,57 +180,123 @@ impl Display for Frequency {
                     value.push_str(&format!(";BYMONTHDAY={}", by_month_day_values.join(",")));
                 }
 
-                if !nth_weekdays.is_empty() {
-                    let nth_weekdays_values: Vec<String> = nth_weekdays
+                if !by_day.is_empty() || !nth_weekdays.is_empty() {
+                    let by_day_values: Vec<String> = by_day
                         .iter()
-                        .map(|nth_weekday| nth_weekday.to_string())
+                        .map(|day| weekday_code(*day).to_string())
+                        .chain(
+                            nth_weekdays
+                                .iter()
+                                .map(|nth_weekday| nth_weekday.to_string()),
+                        )
                         .collect();
-                    value.push_str(&format!(";BYDAY={}", nth_weekdays_values.join(",")));
+                    value.push_str(&format!(";BYDAY={}", by_day_values.join(",")));
+                }
+                if !by_time.is_empty() {
+                    value.push_str(&times_to_string(by_time));
                 }
+                value.push_str(&overflow_to_string(overflow, rfc));
 
                 write!(f, "{value}")
             }
             Frequency::Yearly {
                 interval,
+                by_month,
                 by_monthly_date,
+                nth_weekdays,
+                by_week_number,
+                by_time,
+                overflow,
             } => {
                 let mut value = format!("FREQ=YEARLY;INTERVAL={interval}");
-                if let Some(by_monthly_date) = by_monthly_date {
-                    value.push_str(&format!(
-                        ";BYMONTH={};BYMONTHDAY={}",
-                        by_monthly_date.month.number_from_month(),
-                        by_monthly_date.day
+                if !by_month.is_empty() {
+                    let by_month_values: Vec<String> = by_month
+                        .iter()
+                        .map(|month| month.number_from_month().to_string())
+                        .collect();
+                    value.push_str(&format!(";BYMONTH={}", by_month_values.join(",")));
+                }
+                if !by_monthly_date.is_empty() {
+                    // BYMONTH is taken by the months, so the dates are listed one by one
+                    value.push_str(&monthly_dates_to_string(
+                        by_monthly_date,
+                        by_month.is_empty(),
                     ));
                 }
+                if !nth_weekdays.is_empty() {
+                    // Weekdays inside ISO weeks happen once, so there is no ordinal to write
+                    let nth_weekdays_values: Vec<String> = if by_week_number.is_empty() {
+                        nth_weekdays
+                            .iter()
+                            .map(|nth_weekday| nth_weekday.to_string())
+                            .collect()
+                    } else {
+                        nth_weekdays
+                            .iter()
+                            .map(|nth_weekday| weekday_code(nth_weekday.weekday).to_string())
+                            .collect()
+                    };
+                    value.push_str(&format!(";BYDAY={}", nth_weekdays_values.join(",")));
+                }
+                if !by_week_number.is_empty() {
+                    let by_week_number_values: Vec<String> = by_week_number
+                        .iter()
+                        .map(|week_number| week_number.to_string())
+                        .collect();
+                    value.push_str(&format!(";BYWEEKNO={}", by_week_number_values.join(",")));
+                }
+                if !by_time.is_empty() {
+                    value.push_str(&times_to_string(by_time));
+                }
+                value.push_str(&overflow_to_string(overflow, rfc));
                 write!(f, "{value}")
             }
         }
     }
 }
 
+impl Frequency {
+    /// Parses a frequency from a string using the given parse options.
+    ///
+    /// With lenient options this is the same as `Frequency::from_str`.
+    pub fn from_str_with(s: &str, options: ParseOptions) -> Result<Self, InvalidFrequency> {
+        if options.strict {
+            validate_strict(s, &FREQUENCY_KEYS)?;
+        }
+        Frequency::from_str(s)
+    }
+}
+
 impl FromStr for Frequency {
     type Err = InvalidFrequency;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let (frequency, s) = match extract_frequency(s) {
-            Some(frequency) => frequency,
-            None => {
-                return Err(InvalidFrequency::Format {
-                    message: format!("Cannot parse frequency from value {s}"),
-                })
-            }
+        let _span = trace::debug_span!("parse_frequency", rule = s);
+        trace::debug_error(parse_frequency(s), "Invalid frequency")
+    }
+}
+
+impl Display for OverflowPolicy {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        let value = match self {
+            OverflowPolicy::Skip => "SKIP",
+            OverflowPolicy::ClampToLastDay => "CLAMP",
+            OverflowPolicy::NextValid => "NEXT",
         };
+        write!(f, "{value}")
+    }
+}
+
+impl FromStr for OverflowPolicy {
+    type Err = InvalidFrequency;
 
-        match frequency.as_ref() {
-            "SECONDLY" => parse_secondly(&s),
-            "MINUTELY" => parse_minutely(&s),
-            "HOURLY" => parse_hourly(&s),
-            "DAILY" => parse_daily(&s),
-            "WEEKLY" => parse_weekly(&s),
-            "MONTHLY" => parse_monthly(&s),
-            "YEARLY" => parse_yearly(&s),
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "SKIP" => Ok(OverflowPolicy::Skip),
+            "CLAMP" => Ok(OverflowPolicy::ClampToLastDay),
+            "NEXT" => Ok(OverflowPolicy::NextValid),
             _ => Err(InvalidFrequency::Format {
-                message: format!("Frequency {frequency} is not supported"),
+                message: format!("Cannot parse overflow policy from value {s}"),
             }),
         }
     }
@@ -166,35 +306,28 @@ impl FromStr for NthWeekday {
     type Err = InvalidFrequency;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        use regex::Regex;
-        let re = Regex::new(r"^(?P<week_number>\d+)(?P<weekday>[A-Z]{2})$").unwrap();
-        match re.captures(s) {
-            Some(captures) => {
-                let week_number = match captures
-                    .name("week_number")
-                    .unwrap()
-                    .as_str()
-                    .parse::<i32>()
-                {
-                    Ok(week_number) => week_number,
-                    Err(_) => {
-                        return Err(InvalidFrequency::Format {
-                            message: format!("Cannot parse week number from value {s}"),
-                        })
-                    }
-                };
-                let weekday = Weekday::from_str_short(captures.name("weekday").unwrap().as_str())?;
-                Ok(NthWeekday {
-                    week_number,
-                    weekday,
-                })
-            }
-            None => {
+        let split = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+        let (week_number, weekday) = s.split_at(split);
+        if week_number.is_empty() || weekday.len() != 2 {
+            return Err(InvalidFrequency::Format {
+                message: format!("Cannot parse nth weekday from value {s}"),
+            });
+        }
+        let week_number = match week_number.parse::<i32>() {
+            Ok(week_number) => week_number,
+            Err(_) => {
                 return Err(InvalidFrequency::Format {
-                    message: format!("Cannot parse nth weekday from value {s}"),
+                    message: format!("Cannot parse week number from value {s}"),
                 })
             }
-        }
+        };
+        let weekday = weekday_from_code(weekday).ok_or(InvalidFrequency::Day {
+            message: format!("Invalid day: {weekday}"),
+        })?;
+        Ok(NthWeekday {
+            week_number,
+            weekday,
+        })
     }
 }
 
@@ -202,197 +335,512 @@ impl FromStr for MonthlyDate {
     type Err = InvalidFrequency;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        use regex::Regex;
-        let re = Regex::new(r"^(?P<month>[A-Z]{3})(?P<day>\d+)$").unwrap();
-        match re.captures(s) {
-            Some(captures) => {
-                let month_number = match captures.name("month").unwrap().as_str().parse::<i32>() {
-                    Ok(month_number) => month_number,
-                    Err(_) => {
-                        return Err(InvalidFrequency::Format {
-                            message: format!("Cannot parse month number from value {s}"),
-                        })
-                    }
-                };
-                let month = Month::from_i32(month_number)?;
-                let day = match captures.name("day").unwrap().as_str().parse::<u8>() {
-                    Ok(day) => day,
-                    Err(_) => {
-                        return Err(InvalidFrequency::Format {
-                            message: format!("Cannot parse day from value {s}"),
-                        })
-                    }
-                };
-                Ok(MonthlyDate {
-                    month,
-                    day: day.into(),
+        let (month, day) = match (s.get(..3), s.get(3..)) {
+            (Some(month), Some(day)) if !day.is_empty() => (month, day),
+            _ => {
+                return Err(InvalidFrequency::Format {
+                    message: format!("Cannot parse monthly date from value {s}"),
                 })
             }
-            None => {
+        };
+        let month = month_from_code(month).ok_or(InvalidFrequency::Format {
+            message: format!("Cannot parse month from value {month}"),
+        })?;
+        let is_number = day.bytes().all(|b| b.is_ascii_digit());
+        let day = match day.parse::<u8>() {
+            Ok(day) if is_number => day,
+            _ => {
                 return Err(InvalidFrequency::Format {
-                    message: format!("Cannot parse monthly date from value {s}"),
+                    message: format!("Cannot parse day from value {s}"),
                 })
             }
+        };
+        Ok(MonthlyDate {
+            month,
+            day: day.into(),
+        })
+    }
+}
+
+/// Serializes the times of the day.
+///
+/// Times that can be expressed as every combination of BYHOUR, BYMINUTE and BYSECOND values
+/// use the standard keys, otherwise they are listed with the legacy BYTIME key.
+/// BYSECOND is omitted when all the times are at the start of a minute.
+fn times_to_string(times: &[Time]) -> String {
+    let mut hours: Vec<i32> = vec![];
+    let mut minutes: Vec<i32> = vec![];
+    let mut seconds: Vec<i32> = vec![];
+    for time in times {
+        if !hours.contains(&time.hour) {
+            hours.push(time.hour);
+        }
+        if !minutes.contains(&time.minute) {
+            minutes.push(time.minute);
+        }
+        if !seconds.contains(&time.second) {
+            seconds.push(time.second);
         }
     }
+    let is_cross_product = hours.len() * minutes.len() * seconds.len() == times.len()
+        && hours.iter().all(|hour| {
+            minutes.iter().all(|minute| {
+                seconds.iter().all(|second| {
+                    times
+                        .iter()
+                        .any(|t| t.hour == *hour && t.minute == *minute && t.second == *second)
+                })
+            })
+        });
+
+    if is_cross_product {
+        let join = |values: &[i32]| -> String {
+            let values: Vec<String> = values.iter().map(|value| value.to_string()).collect();
+            values.join(",")
+        };
+        let mut res = format!(";BYHOUR={};BYMINUTE={}", join(&hours), join(&minutes));
+        if seconds != [0] {
+            res.push_str(&format!(";BYSECOND={}", join(&seconds)));
+        }
+        return res;
+    }
+    let values: Vec<String> = times.iter().map(|time| time.to_string()).collect();
+    format!(";BYTIME={}", values.join(","))
 }
 
-trait MonthUtils {
-    fn from_i32(month: i32) -> Result<Month, InvalidFrequency>;
+/// Returns the X-OVERFLOW part of the rule, or the RSCALE and SKIP parts of RFC 7529,
+/// which are omitted for the default policy.
+fn overflow_to_string(overflow: &OverflowPolicy, rfc: bool) -> String {
+    match (overflow, rfc) {
+        (OverflowPolicy::Skip, _) => String::new(),
+        (OverflowPolicy::ClampToLastDay, true) => ";RSCALE=GREGORIAN;SKIP=BACKWARD".to_string(),
+        (OverflowPolicy::NextValid, true) => ";RSCALE=GREGORIAN;SKIP=FORWARD".to_string(),
+        (_, false) => format!(";X-OVERFLOW={overflow}"),
+    }
 }
 
-impl MonthUtils for Month {
-    fn from_i32(month: i32) -> Result<Month, InvalidFrequency> {
-        match month {
-            1 => Ok(Month::January),
-            2 => Ok(Month::February),
-            3 => Ok(Month::March),
-            4 => Ok(Month::April),
-            5 => Ok(Month::May),
-            6 => Ok(Month::June),
-            7 => Ok(Month::July),
-            8 => Ok(Month::August),
-            9 => Ok(Month::September),
-            10 => Ok(Month::October),
-            11 => Ok(Month::November),
-            12 => Ok(Month::December),
-            _ => Err(InvalidFrequency::Format {
-                message: format!("Cannot parse month from value {month}"),
-            }),
+/// Serializes the yearly monthly dates.
+///
+/// Dates that can be expressed as every combination of BYMONTH and BYMONTHDAY values
+/// use the standard keys when allowed, otherwise each date is listed with the
+/// X-BYMONTHDATE extension.
+fn monthly_dates_to_string(monthly_dates: &[MonthlyDate], allow_standard_keys: bool) -> String {
+    let mut months: Vec<Month> = vec![];
+    let mut days: Vec<i32> = vec![];
+    for monthly_date in monthly_dates {
+        if !months.contains(&monthly_date.month) {
+            months.push(monthly_date.month);
+        }
+        if !days.contains(&monthly_date.day) {
+            days.push(monthly_date.day);
         }
     }
+    let is_cross_product = allow_standard_keys
+        && months.len() * days.len() == monthly_dates.len()
+        && months.iter().all(|month| {
+            days.iter().all(|day| {
+                monthly_dates
+                    .iter()
+                    .any(|date| date.month == *month && date.day == *day)
+            })
+        });
+
+    if is_cross_product {
+        let months: Vec<String> = months
+            .iter()
+            .map(|month| month.number_from_month().to_string())
+            .collect();
+        let days: Vec<String> = days.iter().map(|day| day.to_string()).collect();
+        return format!(
+            ";BYMONTH={};BYMONTHDAY={}",
+            months.join(","),
+            days.join(",")
+        );
+    }
+    let values: Vec<String> = monthly_dates.iter().map(|date| date.to_string()).collect();
+    format!(";X-BYMONTHDATE={}", values.join(","))
 }
 
-fn parse_secondly(s: &str) -> Result<Frequency, InvalidFrequency> {
-    let (interval, _) = match extract_interval(s) {
-        Some(interval) => interval,
-        None => {
-            return Err(InvalidFrequency::Format {
-                message: format!("Cannot parse interval from value {s}"),
-            })
-        }
-    };
-    Ok(Frequency::Secondly { interval })
+/// Returns the `BYDAY` and time keys of the sub-daily and weekly frequencies.
+fn days_and_times_to_string(by_day: &[Weekday], by_time: &[Time]) -> String {
+    let mut value = days_to_string(by_day);
+    if !by_time.is_empty() {
+        value.push_str(&times_to_string(by_time));
+    }
+    value
 }
 
-fn parse_minutely(s: &String) -> Result<Frequency, InvalidFrequency> {
-    let (interval, _) = match extract_interval(s) {
-        Some(interval) => interval,
-        None => {
-            return Err(InvalidFrequency::Format {
-                message: format!("Cannot parse interval from value {s}"),
-            })
-        }
-    };
-    Ok(Frequency::Minutely { interval })
+fn days_to_string(by_day: &[Weekday]) -> String {
+    if by_day.is_empty() {
+        return String::new();
+    }
+    let by_day_values: Vec<String> = by_day
+        .iter()
+        .map(|day| weekday_code(*day).to_string())
+        .collect();
+    format!(";BYDAY={}", by_day_values.join(","))
 }
 
-fn parse_hourly(s: &String) -> Result<Frequency, InvalidFrequency> {
-    let (interval, _) = match extract_interval(s) {
-        Some(interval) => interval,
-        None => {
-            return Err(InvalidFrequency::Format {
-                message: format!("Cannot parse interval from value {s}"),
-            })
+/// Times that limit the events of the sub-daily frequencies, matched by their hour and minute.
+///
+/// They are written with the RFC 5545 BYHOUR and BYMINUTE keys only when these keys limit the
+/// events the same way: BYHOUR when the times have every minute of their hours, and BYMINUTE
+/// when the frequency is not hourly, as BYMINUTE sets the minutes of the hourly events instead.
+/// The other times are written with BYTIME.
+fn sub_daily_times_to_string(times: &[Time], hourly: bool) -> String {
+    if times.is_empty() {
+        return String::new();
+    }
+    let mut hours: Vec<i32> = vec![];
+    let mut minutes: Vec<i32> = vec![];
+    for time in times {
+        if !hours.contains(&time.hour) {
+            hours.push(time.hour);
+        }
+        if !minutes.contains(&time.minute) {
+            minutes.push(time.minute);
         }
+    }
+    let is_cross_product = times.iter().all(|time| time.second == 0)
+        && hours.len() * minutes.len() == times.len()
+        && hours.iter().all(|hour| {
+            minutes.iter().all(|minute| {
+                times
+                    .iter()
+                    .any(|time| time.hour == *hour && time.minute == *minute)
+            })
+        });
+    let join = |values: &[i32]| -> String {
+        let values: Vec<String> = values.iter().map(|value| value.to_string()).collect();
+        values.join(",")
     };
-    Ok(Frequency::Hourly { interval })
+    match is_cross_product {
+        true if minutes.len() == 60 => format!(";BYHOUR={}", join(&hours)),
+        true if !hourly && hours.len() == 24 => format!(";BYMINUTE={}", join(&minutes)),
+        true if !hourly => format!(";BYHOUR={};BYMINUTE={}", join(&hours), join(&minutes)),
+        _ => {
+            let values: Vec<String> = times.iter().map(|time| time.to_string()).collect();
+            format!(";BYTIME={}", values.join(","))
+        }
+    }
 }
 
-fn parse_daily(s: &String) -> Result<Frequency, InvalidFrequency> {
-    let (interval, s) = match extract_interval(s) {
-        Some(interval) => interval,
-        None => {
-            return Err(InvalidFrequency::Format {
-                message: format!("Cannot parse interval from value {s}"),
-            })
+/// Parses the value of a `BYDAY` key, E.g. to validate a field of a rule builder as it is
+/// typed, into its days of the week and its nth weekdays, in the order they are written.
+///
+/// The value is read as in a lenient rule string, so lowercase values and spaces around the
+/// commas are accepted. The span of the error is a byte range of the value.
+/// Examples:
+/// ```
+/// use chrono::Weekday;
+/// use rrules::{parse_byday_list, NthWeekday};
+///
+/// let (by_day, nth_weekdays) = parse_byday_list("MO, we,2FR").unwrap();
+/// assert_eq!(by_day, vec![Weekday::Mon, Weekday::Wed]);
+/// assert_eq!(nth_weekdays, vec![NthWeekday::new(Weekday::Fri, 2)]);
+///
+/// let error = parse_byday_list("MO,XX").unwrap_err();
+/// assert_eq!(error.span, 3..5);
+/// assert_eq!(error.found, "XX");
+/// ```
+pub fn parse_byday_list(value: &str) -> Result<(Vec<Weekday>, Vec<NthWeekday>), ParseError> {
+    let days = parse_component("BYDAY", value, parse_by_day)?;
+    let mut by_day = vec![];
+    let mut nth_weekdays = vec![];
+    for (week_number, weekday) in days {
+        match week_number {
+            Some(week_number) => nth_weekdays.push(NthWeekday::new(weekday, week_number)),
+            None => by_day.push(weekday),
         }
-    };
+    }
+    Ok((by_day, nth_weekdays))
+}
+
+/// Parses the value of a `BYMONTHDAY` key into its days of the month, in the order they are
+/// written, the same way as `parse_byday_list`.
+/// Examples:
+/// ```
+/// use rrules::parse_bymonthday_list;
+///
+/// assert_eq!(parse_bymonthday_list("1,15, 31").unwrap(), vec![1, 15, 31]);
+/// assert_eq!(parse_bymonthday_list("1,32").unwrap_err().expected, "a day between 1 and 31");
+/// ```
+pub fn parse_bymonthday_list(value: &str) -> Result<Vec<i32>, ParseError> {
+    parse_component("BYMONTHDAY", value, parse_by_month_day)
+}
 
-    let (by_time, _s) = extract_times(&s)?;
-    Ok(Frequency::Daily { interval, by_time })
+/// Parses the value of a `BYTIME` key into its times, in the order they are written, the
+/// same way as `parse_byday_list`.
+/// Examples:
+/// ```
+/// use std::str::FromStr;
+/// use rrules::{parse_bytime_list, Time};
+///
+/// assert_eq!(
+///     parse_bytime_list("18:00,09:30").unwrap(),
+///     vec![Time::from_str("18:00").unwrap(), Time::from_str("09:30").unwrap()]
+/// );
+/// assert_eq!(parse_bytime_list("09:30,9h").unwrap_err().span, 6..8);
+/// ```
+pub fn parse_bytime_list(value: &str) -> Result<Vec<Time>, ParseError> {
+    parse_component("BYTIME", value, parse_by_time)
 }
 
-fn parse_weekly(s: &String) -> Result<Frequency, InvalidFrequency> {
-    let (interval, s) = match extract_interval(s) {
-        Some(interval) => interval,
+fn parse_frequency(s: &str) -> Result<Frequency, InvalidFrequency> {
+    let normalized = Normalized::new(s, &FREQUENCY_KEYS);
+    let params = RuleParams::parse(&normalized.value).map_err(|error| match error {
+        InvalidFrequency::Parse { error } => InvalidFrequency::Parse {
+            error: normalized.restore(error, s),
+        },
+        error => error,
+    })?;
+    let frequency = match params.frequency() {
+        Some(frequency) => frequency,
         None => {
             return Err(InvalidFrequency::Format {
-                message: format!("Cannot parse interval from value {s}"),
+                message: format!("Cannot parse frequency from value {s}"),
             })
         }
     };
 
-    let (by_day, _) = extract_weekdays(&s)?;
-    Ok(Frequency::Weekly { interval, by_day })
+    let frequency = match frequency {
+        "SECONDLY" => parse_secondly(&params),
+        "MINUTELY" => parse_minutely(&params),
+        "HOURLY" => parse_hourly(&params),
+        "DAILY" => parse_daily(&params),
+        "WEEKLY" => parse_weekly(&params),
+        "MONTHLY" => parse_monthly(&params),
+        "YEARLY" => parse_yearly(&params),
+        _ => Err(InvalidFrequency::Format {
+            message: format!("Frequency {frequency} is not supported"),
+        }),
+    }?;
+    Ok(frequency.normalized())
 }
 
-fn parse_monthly(s: &String) -> Result<Frequency, InvalidFrequency> {
-    let (interval, s) = match extract_interval(s) {
-        Some(interval) => interval,
-        None => {
-            return Err(InvalidFrequency::Format {
-                message: format!("Cannot parse interval from value {s}"),
-            })
-        }
-    };
+fn parse_secondly(params: &RuleParams) -> Result<Frequency, InvalidFrequency> {
+    Ok(Frequency::Secondly {
+        interval: parse_interval(params)?,
+        by_day: params.weekdays()?,
+        by_time: params.sub_daily_times(false)?,
+    })
+}
 
-    let (by_month_day, s) = extract_monthdays(&s)?;
-    let (nth_weekdays, _) = extract_nth_weekdays(&s)?;
+fn parse_minutely(params: &RuleParams) -> Result<Frequency, InvalidFrequency> {
+    Ok(Frequency::Minutely {
+        interval: parse_interval(params)?,
+        by_day: params.weekdays()?,
+        by_time: params.sub_daily_times(false)?,
+        by_hour_range: params.hour_range(),
+    })
+}
+
+fn parse_hourly(params: &RuleParams) -> Result<Frequency, InvalidFrequency> {
+    Ok(Frequency::Hourly {
+        interval: parse_interval(params)?,
+        by_day: params.weekdays()?,
+        by_time: params.sub_daily_times(true)?,
+        by_hour_range: params.hour_range(),
+    })
+}
+
+fn parse_daily(params: &RuleParams) -> Result<Frequency, InvalidFrequency> {
+    if params.business() {
+        return Ok(Frequency::BusinessDaily {
+            interval: parse_interval(params)?,
+        });
+    }
+    Ok(Frequency::Daily {
+        interval: parse_interval(params)?,
+        by_day: params.weekdays()?,
+        by_time: params.times()?,
+    })
+}
+
+fn parse_weekly(params: &RuleParams) -> Result<Frequency, InvalidFrequency> {
+    Ok(Frequency::Weekly {
+        interval: parse_interval(params)?,
+        by_day: params.weekdays()?,
+        by_time: params.times()?,
+        by_day_time: params.day_times(),
+    })
+}
 
+fn parse_monthly(params: &RuleParams) -> Result<Frequency, InvalidFrequency> {
+    // Dropping the months would yield the events of every month
+    if !params.months().is_empty() {
+        return Err(InvalidFrequency::Format {
+            message: "BYMONTH is not supported by monthly frequencies, use FREQ=YEARLY instead"
+                .to_string(),
+        });
+    }
+    let (by_day, nth_weekdays) = params.weekdays_and_nth_weekdays();
+    for nth_weekday in &nth_weekdays {
+        validate_nth_weekday(nth_weekday, MONTH_WEEKS)?;
+    }
     Ok(Frequency::Monthly {
-        interval,
-        by_month_day,
+        interval: parse_interval(params)?,
+        by_month_day: params.month_days(),
+        by_day,
         nth_weekdays,
+        by_time: params.times()?,
+        overflow: params.overflow(),
     })
 }
 
-fn parse_yearly(s: &String) -> Result<Frequency, InvalidFrequency> {
-    let (interval, s) = match extract_interval(s) {
-        Some(interval) => interval,
-        None => {
-            return Err(InvalidFrequency::Format {
-                message: format!("Cannot parse interval from value {s}"),
-            })
+fn parse_yearly(params: &RuleParams) -> Result<Frequency, InvalidFrequency> {
+    let by_week_number = params.week_numbers();
+    let nth_weekdays = if by_week_number.is_empty() {
+        // Days without an ordinal would be all of these days of the year or of the months
+        if let Some(weekday) = params.weekdays_and_nth_weekdays().0.first() {
+            return Err(InvalidFrequency::Unsupported {
+                message: format!(
+                    "BYDAY {} needs an ordinal in yearly rules without BYWEEKNO, E.g. 1{}",
+                    weekday_code(*weekday),
+                    weekday_code(*weekday)
+                ),
+            });
         }
+        let nth_weekdays = params.nth_weekdays()?;
+        if !params.months().is_empty() {
+            for nth_weekday in &nth_weekdays {
+                validate_nth_weekday(nth_weekday, MONTH_WEEKS)?;
+            }
+        }
+        nth_weekdays
+    } else {
+        // The weeks of BYWEEKNO already select the days, which have no ordinal in them
+        let (weekdays, nth_weekdays) = params.weekdays_and_nth_weekdays();
+        if let Some(nth_weekday) = nth_weekdays.first() {
+            return Err(InvalidFrequency::Day {
+                message: format!("Ordinal BYDAY {nth_weekday} is not allowed with BYWEEKNO"),
+            });
+        }
+        weekdays
+            .into_iter()
+            .map(|weekday| NthWeekday::new(weekday, 1))
+            .collect()
     };
 
-    let (by_monthly_date, _) = extract_monthly_date(&s)?;
+    // Months combined with days of the month are already part of the monthly dates
+    let by_month = if params.month_days().is_empty() {
+        params.months()
+    } else {
+        vec![]
+    };
 
     Ok(Frequency::Yearly {
-        interval,
-        by_monthly_date,
+        interval: parse_interval(params)?,
+        by_month,
+        by_monthly_date: params.monthly_dates()?,
+        nth_weekdays,
+        by_week_number,
+        by_time: params.times()?,
+        overflow: params.overflow(),
+    })
+}
+
+/// Returns the INTERVAL of the rule, 1 when it is not defined, as in RFC 5545.
+fn parse_interval(params: &RuleParams) -> Result<NonZeroU32, InvalidFrequency> {
+    let interval = params.interval().unwrap_or(1);
+    NonZeroU32::new(interval).ok_or(InvalidFrequency::Interval {
+        message: "Interval must be greater than 0".to_string(),
     })
 }
 
 #[cfg(test)]
 mod test_serialize {
+    use crate::frequencies::OverflowPolicy;
     use crate::{Frequency, MonthlyDate, NthWeekday, Time};
     use chrono::{Month, Weekday};
+    use std::num::NonZeroU32;
     use std::str::FromStr;
 
     #[test]
     fn test_serialize_secondly() {
-        let frequency = Frequency::Secondly { interval: 1 };
+        let frequency = Frequency::Secondly {
+            interval: NonZeroU32::new(1).unwrap(),
+            by_day: vec![],
+            by_time: vec![],
+        };
         assert_eq!(frequency.to_string(), "FREQ=SECONDLY;INTERVAL=1");
     }
 
     #[test]
     fn test_serialize_minutely() {
-        let frequency = Frequency::Minutely { interval: 1 };
+        let frequency = Frequency::Minutely {
+            interval: NonZeroU32::new(1).unwrap(),
+            by_day: vec![],
+            by_time: vec![],
+            by_hour_range: None,
+        };
         assert_eq!(frequency.to_string(), "FREQ=MINUTELY;INTERVAL=1");
     }
 
     #[test]
     fn test_serialize_hourly() {
-        let frequency = Frequency::Hourly { interval: 1 };
+        let frequency = Frequency::Hourly {
+            interval: NonZeroU32::new(1).unwrap(),
+            by_day: vec![],
+            by_time: vec![],
+            by_hour_range: None,
+        };
         assert_eq!(frequency.to_string(), "FREQ=HOURLY;INTERVAL=1");
     }
 
+    #[test]
+    fn test_serialize_sub_daily_times_with_rfc_keys_when_they_limit_the_events() {
+        for (rule, expected) in [
+            (
+                "FREQ=HOURLY;BYHOUR=17,9",
+                "FREQ=HOURLY;INTERVAL=1;BYHOUR=9,17",
+            ),
+            (
+                "FREQ=HOURLY;BYTIME=09:00",
+                "FREQ=HOURLY;INTERVAL=1;BYTIME=09:00",
+            ),
+            (
+                "FREQ=MINUTELY;BYMINUTE=30,0",
+                "FREQ=MINUTELY;INTERVAL=1;BYMINUTE=0,30",
+            ),
+            (
+                "FREQ=MINUTELY;BYHOUR=9;BYMINUTE=0,30",
+                "FREQ=MINUTELY;INTERVAL=1;BYHOUR=9;BYMINUTE=0,30",
+            ),
+            (
+                "FREQ=SECONDLY;BYTIME=09:00,17:30",
+                "FREQ=SECONDLY;INTERVAL=1;BYTIME=09:00,17:30",
+            ),
+            (
+                "FREQ=SECONDLY;BYTIME=09:00:30",
+                "FREQ=SECONDLY;INTERVAL=1;BYTIME=09:00:30",
+            ),
+        ] {
+            let frequency = Frequency::from_str(rule).unwrap();
+            assert_eq!(frequency.to_string(), expected, "{rule}");
+            assert_eq!(Frequency::from_str(expected).unwrap(), frequency, "{rule}");
+        }
+    }
+
+    #[test]
+    fn test_serialize_hourly_by_day_and_time() {
+        let frequency = Frequency::Hourly {
+            interval: NonZeroU32::new(1).unwrap(),
+            by_day: vec![Weekday::Mon, Weekday::Fri],
+            by_time: vec![Time::from_str("09:00").unwrap()],
+            by_hour_range: None,
+        };
+        assert_eq!(
+            frequency.to_string(),
+            "FREQ=HOURLY;INTERVAL=1;BYDAY=MO,FR;BYTIME=09:00"
+        );
+    }
+
     #[test]
     fn test_serialize_daily() {
         let frequency = Frequency::Daily {
-            interval: 1,
+            interval: NonZeroU32::new(1).unwrap(),
+            by_day: vec![],
             by_time: vec![],
         };
         assert_eq!(frequency.to_string(), "FREQ=DAILY;INTERVAL=1");
@@ -401,16 +849,48 @@ mod test_serialize {
     #[test]
     fn test_serialize_daily_by_time() {
         let frequency = Frequency::Daily {
-            interval: 1,
+            interval: NonZeroU32::new(1).unwrap(),
+            by_day: vec![],
             by_time: vec![Time::from_str("09:00").unwrap()],
         };
-        assert_eq!(frequency.to_string(), "FREQ=DAILY;INTERVAL=1;BYTIME=09:00");
+        assert_eq!(
+            frequency.to_string(),
+            "FREQ=DAILY;INTERVAL=1;BYHOUR=9;BYMINUTE=0"
+        );
+    }
+
+    #[test]
+    fn test_serialize_daily_by_time_with_seconds() {
+        let frequency = Frequency::Daily {
+            interval: NonZeroU32::new(1).unwrap(),
+            by_day: vec![],
+            by_time: vec![
+                Time::from_str("09:30:00").unwrap(),
+                Time::from_str("09:30:15").unwrap(),
+            ],
+        };
+        let value = "FREQ=DAILY;INTERVAL=1;BYHOUR=9;BYMINUTE=30;BYSECOND=0,15";
+        assert_eq!(frequency.to_string(), value);
+        assert_eq!(Frequency::from_str(value).unwrap(), frequency);
+
+        let frequency = Frequency::Daily {
+            interval: NonZeroU32::new(1).unwrap(),
+            by_day: vec![],
+            by_time: vec![
+                Time::from_str("09:30:15").unwrap(),
+                Time::from_str("17:00").unwrap(),
+            ],
+        };
+        let value = "FREQ=DAILY;INTERVAL=1;BYTIME=09:30:15,17:00";
+        assert_eq!(frequency.to_string(), value);
+        assert_eq!(Frequency::from_str(value).unwrap(), frequency);
     }
 
     #[test]
     fn test_serialize_daily_by_time_multiple() {
         let frequency = Frequency::Daily {
-            interval: 1,
+            interval: NonZeroU32::new(1).unwrap(),
+            by_day: vec![],
             by_time: vec![
                 Time::from_str("09:00").unwrap(),
                 Time::from_str("10:00").unwrap(),
@@ -418,15 +898,33 @@ mod test_serialize {
         };
         assert_eq!(
             frequency.to_string(),
-            "FREQ=DAILY;INTERVAL=1;BYTIME=09:00,10:00"
+            "FREQ=DAILY;INTERVAL=1;BYHOUR=9,10;BYMINUTE=0"
+        );
+    }
+
+    #[test]
+    fn test_serialize_daily_by_time_not_combinable() {
+        let frequency = Frequency::Daily {
+            interval: NonZeroU32::new(1).unwrap(),
+            by_day: vec![],
+            by_time: vec![
+                Time::from_str("09:00").unwrap(),
+                Time::from_str("10:30").unwrap(),
+            ],
+        };
+        assert_eq!(
+            frequency.to_string(),
+            "FREQ=DAILY;INTERVAL=1;BYTIME=09:00,10:30"
         );
     }
 
     #[test]
     fn test_serialize_weekly() {
         let frequency = Frequency::Weekly {
-            interval: 1,
+            interval: NonZeroU32::new(1).unwrap(),
             by_day: vec![],
+            by_time: vec![],
+            by_day_time: vec![],
         };
         assert_eq!(frequency.to_string(), "FREQ=WEEKLY;INTERVAL=1");
     }
@@ -434,8 +932,10 @@ mod test_serialize {
     #[test]
     fn test_serialize_weekly_by_day() {
         let frequency = Frequency::Weekly {
-            interval: 1,
+            interval: NonZeroU32::new(1).unwrap(),
             by_day: vec![Weekday::Mon],
+            by_time: vec![],
+            by_day_time: vec![],
         };
         assert_eq!(frequency.to_string(), "FREQ=WEEKLY;INTERVAL=1;BYDAY=MO");
     }
@@ -443,8 +943,10 @@ mod test_serialize {
     #[test]
     fn test_serialize_weekly_by_day_multiple() {
         let frequency = Frequency::Weekly {
-            interval: 1,
+            interval: NonZeroU32::new(1).unwrap(),
             by_day: vec![Weekday::Mon, Weekday::Tue],
+            by_time: vec![],
+            by_day_time: vec![],
         };
         assert_eq!(frequency.to_string(), "FREQ=WEEKLY;INTERVAL=1;BYDAY=MO,TU");
     }
@@ -452,9 +954,12 @@ mod test_serialize {
     #[test]
     fn test_serialize_monthly() {
         let frequency = Frequency::Monthly {
-            interval: 1,
+            interval: NonZeroU32::new(1).unwrap(),
             by_month_day: vec![],
+            by_day: vec![],
             nth_weekdays: vec![],
+            by_time: vec![],
+            overflow: OverflowPolicy::Skip,
         };
         assert_eq!(frequency.to_string(), "FREQ=MONTHLY;INTERVAL=1");
     }
@@ -462,9 +967,12 @@ mod test_serialize {
     #[test]
     fn test_serialize_monthly_by_month_day() {
         let frequency = Frequency::Monthly {
-            interval: 1,
+            interval: NonZeroU32::new(1).unwrap(),
             by_month_day: vec![1],
+            by_day: vec![],
             nth_weekdays: vec![],
+            by_time: vec![],
+            overflow: OverflowPolicy::Skip,
         };
         assert_eq!(
             frequency.to_string(),
@@ -475,9 +983,12 @@ mod test_serialize {
     #[test]
     fn test_serialize_monthly_by_month_day_multiple() {
         let frequency = Frequency::Monthly {
-            interval: 1,
+            interval: NonZeroU32::new(1).unwrap(),
             by_month_day: vec![1, 2],
+            by_day: vec![],
             nth_weekdays: vec![],
+            by_time: vec![],
+            overflow: OverflowPolicy::Skip,
         };
         assert_eq!(
             frequency.to_string(),
@@ -488,9 +999,12 @@ mod test_serialize {
     #[test]
     fn test_serialize_monthly_by_nth_weekday() {
         let frequency = Frequency::Monthly {
-            interval: 1,
+            interval: NonZeroU32::new(1).unwrap(),
             by_month_day: vec![],
+            by_day: vec![],
             nth_weekdays: vec![NthWeekday::new(Weekday::Mon, 1)],
+            by_time: vec![],
+            overflow: OverflowPolicy::Skip,
         };
         assert_eq!(frequency.to_string(), "FREQ=MONTHLY;INTERVAL=1;BYDAY=1MO");
     }
@@ -498,12 +1012,15 @@ mod test_serialize {
     #[test]
     fn test_serialize_monthly_by_nth_weekday_multiple() {
         let frequency = Frequency::Monthly {
-            interval: 1,
+            interval: NonZeroU32::new(1).unwrap(),
             by_month_day: vec![],
+            by_day: vec![],
             nth_weekdays: vec![
                 NthWeekday::new(Weekday::Mon, 1),
                 NthWeekday::new(Weekday::Tue, 2),
             ],
+            by_time: vec![],
+            overflow: OverflowPolicy::Skip,
         };
         assert_eq!(
             frequency.to_string(),
@@ -514,8 +1031,13 @@ mod test_serialize {
     #[test]
     fn test_serialize_yearly() {
         let frequency = Frequency::Yearly {
-            interval: 1,
-            by_monthly_date: None,
+            interval: NonZeroU32::new(1).unwrap(),
+            by_month: vec![],
+            by_monthly_date: vec![],
+            nth_weekdays: vec![],
+            by_week_number: vec![],
+            by_time: vec![],
+            overflow: OverflowPolicy::Skip,
         };
         assert_eq!(frequency.to_string(), "FREQ=YEARLY;INTERVAL=1");
     }
@@ -523,26 +1045,140 @@ mod test_serialize {
     #[test]
     fn test_serialize_yearly_by_monthly_date() {
         let frequency = Frequency::Yearly {
-            interval: 1,
-            by_monthly_date: Some(MonthlyDate {
+            interval: NonZeroU32::new(1).unwrap(),
+            by_month: vec![],
+            by_monthly_date: vec![MonthlyDate {
                 month: Month::January,
                 day: 1,
-            }),
+            }],
+            nth_weekdays: vec![],
+            by_week_number: vec![],
+            by_time: vec![],
+            overflow: OverflowPolicy::Skip,
         };
         assert_eq!(
             frequency.to_string(),
             "FREQ=YEARLY;INTERVAL=1;BYMONTH=1;BYMONTHDAY=1"
         );
     }
+
+    #[test]
+    fn test_serialize_yearly_by_monthly_date_same_day() {
+        let frequency = Frequency::Yearly {
+            interval: NonZeroU32::new(1).unwrap(),
+            by_month: vec![],
+            by_monthly_date: vec![
+                MonthlyDate {
+                    month: Month::January,
+                    day: 15,
+                },
+                MonthlyDate {
+                    month: Month::June,
+                    day: 15,
+                },
+            ],
+            nth_weekdays: vec![],
+            by_week_number: vec![],
+            by_time: vec![],
+            overflow: OverflowPolicy::Skip,
+        };
+        assert_eq!(
+            frequency.to_string(),
+            "FREQ=YEARLY;INTERVAL=1;BYMONTH=1,6;BYMONTHDAY=15"
+        );
+    }
+
+    #[test]
+    fn test_serialize_yearly_by_monthly_date_multiple() {
+        let frequency = Frequency::Yearly {
+            interval: NonZeroU32::new(1).unwrap(),
+            by_month: vec![],
+            by_monthly_date: vec![
+                MonthlyDate {
+                    month: Month::January,
+                    day: 15,
+                },
+                MonthlyDate {
+                    month: Month::June,
+                    day: 1,
+                },
+            ],
+            nth_weekdays: vec![],
+            by_week_number: vec![],
+            by_time: vec![],
+            overflow: OverflowPolicy::Skip,
+        };
+        assert_eq!(
+            frequency.to_string(),
+            "FREQ=YEARLY;INTERVAL=1;X-BYMONTHDATE=JAN15,JUN1"
+        );
+    }
+
+    #[test]
+    fn test_serialize_yearly_by_nth_weekday() {
+        let frequency = Frequency::Yearly {
+            interval: NonZeroU32::new(1).unwrap(),
+            by_month: vec![],
+            by_monthly_date: vec![],
+            nth_weekdays: vec![NthWeekday::new(Weekday::Mon, 20)],
+            by_week_number: vec![],
+            by_time: vec![],
+            overflow: OverflowPolicy::Skip,
+        };
+        assert_eq!(frequency.to_string(), "FREQ=YEARLY;INTERVAL=1;BYDAY=20MO");
+    }
+
+    #[test]
+    fn test_serialize_yearly_by_week_number() {
+        let frequency = Frequency::Yearly {
+            interval: NonZeroU32::new(1).unwrap(),
+            by_month: vec![],
+            by_monthly_date: vec![],
+            nth_weekdays: vec![NthWeekday::new(Weekday::Mon, 1)],
+            by_week_number: vec![20],
+            by_time: vec![],
+            overflow: OverflowPolicy::Skip,
+        };
+        assert_eq!(
+            frequency.to_string(),
+            "FREQ=YEARLY;INTERVAL=1;BYDAY=MO;BYWEEKNO=20"
+        );
+    }
 }
 
 #[cfg(test)]
 mod test_deserialize_from_str {
-    use crate::frequencies::InvalidFrequency;
-    use crate::Frequency;
-    use chrono::{DateTime, Utc};
+    use crate::{
+        parse_byday_list, parse_bymonthday_list, parse_bytime_list, Frequency, InvalidFrequency,
+        NthWeekday, ParseErrorKind, ParseOptions, Time,
+    };
+    use chrono::{DateTime, Utc, Weekday};
     use std::str::FromStr;
 
+    #[test]
+    fn components_are_parsed_on_their_own() {
+        let error = parse_byday_list(" su,1mo ,-").unwrap_err();
+        assert_eq!((error.span, error.found.as_str()), (9..10, "-"));
+        assert_eq!(
+            parse_byday_list("TU,53TH").unwrap(),
+            (vec![Weekday::Tue], vec![NthWeekday::new(Weekday::Thu, 53)])
+        );
+        let error = parse_byday_list("TU,54TH").unwrap_err();
+        assert_eq!(error.kind, ParseErrorKind::InvalidValue);
+        assert_eq!((error.span, error.found.as_str()), (3..7, "54TH"));
+
+        let error = parse_bymonthday_list("").unwrap_err();
+        assert_eq!((error.span, error.found.as_str()), (0..0, ""));
+        assert_eq!(parse_bymonthday_list("31,1,31").unwrap(), vec![31, 1, 31]);
+
+        let error = parse_bytime_list("09:00;18:00").unwrap_err();
+        assert_eq!((error.span, error.found.as_str()), (0..11, "09:00;18:00"));
+        assert_eq!(
+            parse_bytime_list("09:00:30").unwrap(),
+            vec![Time::from_str("09:00:30").unwrap()]
+        );
+    }
+
     #[test]
     fn test_invalid_format() {
         let value = "blabla";
@@ -562,6 +1198,75 @@ mod test_deserialize_from_str {
         assert!(frequency.is_err());
     }
 
+    #[test]
+    fn interval_defaults_to_1() {
+        for frequency in [
+            "SECONDLY", "MINUTELY", "HOURLY", "DAILY", "WEEKLY", "MONTHLY", "YEARLY",
+        ] {
+            let value = format!("FREQ={frequency}");
+            let parsed = Frequency::from_str(&value).unwrap();
+            assert_eq!(parsed.interval().get(), 1);
+            assert_eq!(
+                parsed,
+                Frequency::from_str(&format!("{value};INTERVAL=1")).unwrap()
+            );
+        }
+        let frequency = Frequency::from_str("FREQ=DAILY;X-BUSINESS=TRUE").unwrap();
+        assert_eq!(
+            frequency.to_string(),
+            "FREQ=DAILY;INTERVAL=1;X-BUSINESS=TRUE"
+        );
+        for value in [
+            "FREQ=DAILY;INTERVAL=0",
+            "FREQ=DAILY;INTERVAL=",
+            "FREQ=DAILY;INTERVAL=-1",
+        ] {
+            assert!(Frequency::from_str(value).is_err(), "{value}");
+        }
+    }
+
+    #[test]
+    fn lenient_parsing_accepts_lowercase_and_spaces() {
+        let value = "freq=weekly; interval=1; byday=mo,we";
+        let frequency = Frequency::from_str(value).unwrap();
+        assert_eq!(frequency.to_string(), "FREQ=WEEKLY;INTERVAL=1;BYDAY=MO,WE");
+        assert!(Frequency::from_str_with(value, ParseOptions::strict()).is_err());
+        assert!(
+            Frequency::from_str_with("FREQ=WEEKLY; INTERVAL=1", ParseOptions::strict()).is_err()
+        );
+
+        let frequency =
+            Frequency::from_str(" Freq = Yearly ; x-bymonthdate = jan15 , jun1 ").unwrap();
+        assert_eq!(
+            frequency.to_string(),
+            "FREQ=YEARLY;INTERVAL=1;X-BYMONTHDATE=JAN15,JUN1"
+        );
+
+        // The errors point at the value as it was written
+        let value = "freq=weekly; byday=mo, xx";
+        match Frequency::from_str(value).unwrap_err() {
+            InvalidFrequency::Parse { error } => {
+                assert_eq!(error.span, 23..25);
+                assert_eq!(error.found, "xx");
+            }
+            error => panic!("Unexpected error {error:?}"),
+        }
+    }
+
+    #[test]
+    fn strict_from_str_with() {
+        use crate::ParseOptions;
+        let value = "FREQ=WEEKLY;INTERVAL=1;BYDAY=MO;BYDAY=TU";
+        assert!(Frequency::from_str_with(value, ParseOptions::default()).is_ok());
+        let frequency = Frequency::from_str_with(value, ParseOptions::strict());
+        assert_eq!(
+            frequency.unwrap_err().to_string(),
+            "Invalid format: Duplicated key BYDAY at 32..40"
+        );
+        let value = "FREQ=WEEKLY;INTERVAL=1;BYDAY=MO,TU";
+        assert!(Frequency::from_str_with(value, ParseOptions::strict()).is_ok());
+    }
+
     #[test]
     fn secondly_from_str() {
         let value = "FREQ=SECONDLY;INTERVAL=1";
@@ -595,6 +1300,17 @@ mod test_deserialize_from_str {
         assert_eq!(next, now + chrono::Duration::hours(1));
     }
 
+    #[test]
+    fn minutely_by_day_and_hour_from_str() {
+        let value = "FREQ=MINUTELY;INTERVAL=30;BYDAY=MO;BYHOUR=9,10;BYMINUTE=0,30";
+        let frequency = Frequency::from_str(value).unwrap();
+        assert_eq!(frequency.to_string(), value);
+        let date = DateTime::<Utc>::from_str("2023-01-02T09:30:00Z").unwrap();
+        let next = frequency.next_event(&date).unwrap();
+        let expected = DateTime::<Utc>::from_str("2023-01-02T10:00:00Z").unwrap();
+        assert_eq!(next, expected);
+    }
+
     #[test]
     fn daily_from_str() {
         let value = "FREQ=DAILY;INTERVAL=1";
@@ -615,6 +1331,18 @@ mod test_deserialize_from_str {
         let date = DateTime::<Utc>::from_str("2020-01-01T00:00:00Z").unwrap();
         let next = frequency.next_event(&date).unwrap();
         let expected = DateTime::<Utc>::from_str("2020-01-01T10:00:00Z").unwrap();
+        assert_eq!(next, expected);
+    }
+
+    #[test]
+    fn daily_by_hour_from_str() {
+        let value = "FREQ=DAILY;INTERVAL=1;BYHOUR=9,17;BYMINUTE=30";
+        let frequency = Frequency::from_str(value).unwrap();
+        assert_eq!(frequency.to_string(), value);
+        let date = DateTime::<Utc>::from_str("2020-01-01T10:00:00Z").unwrap();
+        let next = frequency.next_event(&date).unwrap();
+        let expected = DateTime::<Utc>::from_str("2020-01-01T17:30:00Z").unwrap();
+        assert_eq!(next, expected);
     }
 
     #[test]
@@ -650,6 +1378,7 @@ mod test_deserialize_from_str {
         let date = DateTime::<Utc>::from_str("2020-01-01T00:00:00Z").unwrap();
         let next = frequency.next_event(&date).unwrap();
         let expected = DateTime::<Utc>::from_str("2020-02-01T00:00:00Z").unwrap();
+        assert_eq!(next, expected);
     }
 
     #[test]
@@ -668,6 +1397,66 @@ mod test_deserialize_from_str {
         assert_eq!(next, expected);
     }
 
+    #[test]
+    fn monthly_with_overflow_from_str() {
+        let value = "FREQ=MONTHLY;INTERVAL=1;BYMONTHDAY=31;X-OVERFLOW=CLAMP";
+        let frequency = Frequency::from_str(value).unwrap();
+        assert_eq!(frequency.to_string(), value);
+        let date = DateTime::<Utc>::from_str("2023-01-31T00:00:00Z").unwrap();
+        let next = frequency.next_event(&date).unwrap();
+        let expected = DateTime::<Utc>::from_str("2023-02-28T00:00:00Z").unwrap();
+        assert_eq!(next, expected);
+
+        let value = "FREQ=MONTHLY;INTERVAL=1;BYMONTHDAY=31;X-OVERFLOW=SKIP";
+        let frequency = Frequency::from_str(value).unwrap();
+        assert_eq!(
+            frequency.to_string(),
+            "FREQ=MONTHLY;INTERVAL=1;BYMONTHDAY=31"
+        );
+        assert!(Frequency::from_str("FREQ=MONTHLY;INTERVAL=1;X-OVERFLOW=LAST").is_err());
+    }
+
+    #[test]
+    fn yearly_with_overflow_from_str() {
+        let value = "FREQ=YEARLY;INTERVAL=1;BYMONTH=2;BYMONTHDAY=29;X-OVERFLOW=NEXT";
+        let frequency = Frequency::from_str(value).unwrap();
+        assert_eq!(frequency.to_string(), value);
+        let date = DateTime::<Utc>::from_str("2021-01-01T00:00:00Z").unwrap();
+        let next = frequency.next_event(&date).unwrap();
+        let expected = DateTime::<Utc>::from_str("2021-03-01T00:00:00Z").unwrap();
+        assert_eq!(next, expected);
+        assert!(frequency.contains(&next));
+    }
+
+    #[test]
+    fn skip_from_str() {
+        use crate::{OverflowPolicy, ParseOptions};
+
+        let value = "FREQ=MONTHLY;INTERVAL=1;BYMONTHDAY=31;RSCALE=GREGORIAN;SKIP=BACKWARD";
+        let frequency = Frequency::from_str(value).unwrap();
+        assert_eq!(frequency.overflow(), OverflowPolicy::ClampToLastDay);
+        assert_eq!(frequency.to_rfc_string(), value);
+        assert_eq!(
+            frequency.to_string(),
+            "FREQ=MONTHLY;INTERVAL=1;BYMONTHDAY=31;X-OVERFLOW=CLAMP"
+        );
+
+        let frequency = Frequency::from_str("FREQ=YEARLY;INTERVAL=1;SKIP=FORWARD").unwrap();
+        assert_eq!(frequency.overflow(), OverflowPolicy::NextValid);
+        let frequency =
+            Frequency::from_str("FREQ=YEARLY;INTERVAL=1;RSCALE=gregorian;SKIP=OMIT").unwrap();
+        assert_eq!(frequency.overflow(), OverflowPolicy::Skip);
+        assert_eq!(frequency.to_rfc_string(), "FREQ=YEARLY;INTERVAL=1");
+
+        assert!(Frequency::from_str("FREQ=MONTHLY;INTERVAL=1;SKIP=CLAMP").is_err());
+        assert!(Frequency::from_str("FREQ=MONTHLY;INTERVAL=1;RSCALE=HEBREW").is_err());
+        assert!(Frequency::from_str_with(
+            "FREQ=MONTHLY;INTERVAL=1;RSCALE=GREGORIAN;SKIP=FORWARD",
+            ParseOptions::strict()
+        )
+        .is_ok());
+    }
+
     #[test]
     fn monthly_by_nth_weekday_from_str() {
         let value = "FREQ=MONTHLY;INTERVAL=1;BYDAY=1MO";
@@ -705,4 +1494,33 @@ mod test_deserialize_from_str {
         let expected = DateTime::<Utc>::from_str("2020-01-15T00:00:00Z").unwrap();
         assert_eq!(next, expected);
     }
+
+    #[test]
+    fn yearly_by_multiple_monthly_dates_from_str() {
+        let value = "FREQ=YEARLY;INTERVAL=1;X-BYMONTHDATE=JAN15,JUN1";
+        let frequency = Frequency::from_str(value).unwrap();
+        assert_eq!(frequency.to_string(), value);
+        let date = DateTime::<Utc>::from_str("2020-02-01T00:00:00Z").unwrap();
+        let next = frequency.next_event(&date).unwrap();
+        let expected = DateTime::<Utc>::from_str("2020-06-01T00:00:00Z").unwrap();
+        assert_eq!(next, expected);
+    }
+
+    #[test]
+    fn yearly_by_week_number_from_str() {
+        let value = "FREQ=YEARLY;INTERVAL=1;BYDAY=MO;BYWEEKNO=20";
+        let frequency = Frequency::from_str(value).unwrap();
+        assert_eq!(frequency.to_string(), value);
+        let date = DateTime::<Utc>::from_str("2023-01-01T00:00:00Z").unwrap();
+        let next = frequency.next_event(&date).unwrap();
+        let expected = DateTime::<Utc>::from_str("2023-05-15T00:00:00Z").unwrap();
+        assert_eq!(next, expected);
+    }
+
+    #[test]
+    fn yearly_by_nth_weekday_from_str() {
+        let value = "FREQ=YEARLY;INTERVAL=1;BYDAY=20MO";
+        let frequency = Frequency::from_str(value).unwrap();
+        assert_eq!(frequency.to_string(), value);
+    }
 }
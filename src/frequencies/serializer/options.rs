@@ -0,0 +1,246 @@
+use crate::frequencies::serializer::tokenizer::{tokenize, RuleParam};
+use crate::frequencies::{InvalidFrequency, ParseError, ParseErrorKind};
+use crate::recurrences::validations::ValidationOptions;
+use alloc::collections::BTreeSet;
+use alloc::format;
+use alloc::string::ToString;
+use alloc::vec::Vec;
+use chrono::{DateTime, Utc};
+
+/// Keys accepted when parsing a frequency.
+pub(crate) const FREQUENCY_KEYS: [&str; 17] = [
+    "FREQ",
+    "INTERVAL",
+    "BYDAY",
+    "BYMONTH",
+    "BYMONTHDAY",
+    "X-BYMONTHDATE",
+    "BYWEEKNO",
+    "BYTIME",
+    "BYHOUR",
+    "BYMINUTE",
+    "BYSECOND",
+    "X-OVERFLOW",
+    "SKIP",
+    "RSCALE",
+    "X-BUSINESS",
+    "X-BYDAYTIME",
+    "X-BYHOURRANGE",
+];
+
+/// Options that control how rule strings are parsed.
+///
+/// By default parsing is lenient: unknown keys and malformed parts are ignored, and the keys
+/// and codes can be in lowercase, with spaces around the `;`, `=` and `,` separators.
+/// In strict mode, the whole string must be made of known `KEY=VALUE` pairs separated
+/// by `;`, supported by the frequency, without duplicated keys, duplicated values in a list, or values that cannot
+/// be fully parsed. Lenient parsing sorts the values of the lists and drops the duplicates.
+///
+/// Recurrences without a `DTSTART` are rejected, unless a default start date is given,
+/// and the parsed recurrences are validated with the given validation options.
+///
+/// Examples:
+/// ```
+/// use std::str::FromStr;
+/// use rrules::{Frequency, ParseOptions};
+///
+/// let value = "FREQ=YEARLY;INTERVAL=1;BYMONTH=1;BY;BYMONTHDAY=15";
+/// assert!(Frequency::from_str(value).is_ok());
+/// assert!(Frequency::from_str_with(value, ParseOptions::strict()).is_err());
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ParseOptions {
+    pub strict: bool,
+
+    /// Start date of the recurrences parsed without a `DTSTART`
+    pub default_start: Option<DateTime<Utc>>,
+
+    /// Validation rules of the parsed recurrences
+    pub validation: ValidationOptions,
+}
+
+impl ParseOptions {
+    /// Options that reject unknown keys, duplicated keys and values, and trailing garbage.
+    pub fn strict() -> Self {
+        ParseOptions {
+            strict: true,
+            default_start: None,
+            validation: ValidationOptions::default(),
+        }
+    }
+
+    /// Options that keep the default permissive behavior.
+    pub fn lenient() -> Self {
+        ParseOptions {
+            strict: false,
+            default_start: None,
+            validation: ValidationOptions::default(),
+        }
+    }
+
+    /// Sets the start date of the recurrences parsed without a `DTSTART`, like the
+    /// current date for the rules stored without one.
+    pub fn with_default_start(mut self, default_start: DateTime<Utc>) -> Self {
+        self.default_start = Some(default_start);
+        self
+    }
+
+    /// Sets the validation rules of the parsed recurrences, E.g. to accept the rules
+    /// stored with overlapping occurrences.
+    pub fn with_validation(mut self, validation: ValidationOptions) -> Self {
+        self.validation = validation;
+        self
+    }
+}
+
+/// Returns the keys of the date filters that the frequency doesn't support, which are ignored
+/// by lenient parsing like unknown keys.
+fn unsupported_keys(frequency: &str) -> &'static [&'static str] {
+    match frequency {
+        "SECONDLY" | "MINUTELY" | "HOURLY" | "DAILY" | "WEEKLY" => {
+            &["BYMONTH", "BYMONTHDAY", "X-BYMONTHDATE", "BYWEEKNO"]
+        }
+        "MONTHLY" => &["X-BYMONTHDATE", "BYWEEKNO"],
+        _ => &[],
+    }
+}
+
+/// Verifies that the string is only made of the allowed `KEY=VALUE` pairs supported by its
+/// frequency, and that the values of the frequency keys can be fully parsed.
+pub(crate) fn validate_strict(s: &str, allowed_keys: &[&str]) -> Result<(), InvalidFrequency> {
+    let unsupported_keys = tokenize(s)
+        .flatten()
+        .find(|token| token.key == "FREQ")
+        .map_or(&[][..], |token| unsupported_keys(token.value));
+    let mut seen = BTreeSet::new();
+    for token in tokenize(s) {
+        let token = token.map_err(|malformed| malformed.to_error())?;
+        if unsupported_keys.contains(&token.key) {
+            let supported: Vec<&str> = allowed_keys
+                .iter()
+                .copied()
+                .filter(|key| !unsupported_keys.contains(key))
+                .collect();
+            return Err(ParseError {
+                kind: ParseErrorKind::UnknownKey,
+                span: token.offset..token.offset + token.key.len(),
+                found: token.key.to_string(),
+                expected: supported.join(", "),
+            }
+            .into());
+        }
+        if !allowed_keys.contains(&token.key) {
+            return Err(ParseError {
+                kind: ParseErrorKind::UnknownKey,
+                span: token.offset..token.offset + token.key.len(),
+                found: token.key.to_string(),
+                expected: allowed_keys.join(", "),
+            }
+            .into());
+        }
+        if !seen.insert(token.key) {
+            return Err(ParseError {
+                kind: ParseErrorKind::DuplicatedKey,
+                span: token.span(),
+                found: token.key.to_string(),
+                expected: format!("a single {} value", token.key),
+            }
+            .into());
+        }
+        if let Some(param) = RuleParam::from_token(&token) {
+            if let Some(index) = param?.duplicate_index() {
+                let span = token.list_value_span(index);
+                return Err(ParseError {
+                    kind: ParseErrorKind::DuplicatedValue,
+                    found: s[span.clone()].to_string(),
+                    span,
+                    expected: format!("different {} values", token.key),
+                }
+                .into());
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test_strict_validation {
+    use super::{validate_strict, FREQUENCY_KEYS};
+    use crate::frequencies::{InvalidFrequency, ParseErrorKind};
+
+    #[test]
+    fn accepts_known_keys() {
+        let value = "FREQ=YEARLY;INTERVAL=1;BYMONTH=1;BYMONTHDAY=15;BYDAY=20MO";
+        assert!(validate_strict(value, &FREQUENCY_KEYS).is_ok());
+        assert!(validate_strict("FREQ=DAILY;INTERVAL=1;", &FREQUENCY_KEYS).is_ok());
+    }
+
+    #[test]
+    fn rejects_malformed_parts() {
+        let value = "FREQ=YEARLY;INTERVAL=1;BYMONTH=1;BY;BYMONTHDAY=15";
+        assert!(validate_strict(value, &FREQUENCY_KEYS).is_err());
+    }
+
+    #[test]
+    fn rejects_unknown_keys() {
+        let value = "FREQ=DAILY;INTERVAL=1;FOO=BAR";
+        assert!(validate_strict(value, &FREQUENCY_KEYS).is_err());
+    }
+
+    #[test]
+    fn rejects_the_keys_unsupported_by_the_frequency() {
+        for value in [
+            "FREQ=DAILY;INTERVAL=1;BYMONTHDAY=15",
+            "FREQ=WEEKLY;INTERVAL=1;BYDAY=MO;BYWEEKNO=20",
+            "FREQ=HOURLY;INTERVAL=1;BYMONTH=1",
+            "FREQ=MONTHLY;INTERVAL=1;BYWEEKNO=20",
+        ] {
+            match validate_strict(value, &FREQUENCY_KEYS) {
+                Err(InvalidFrequency::Parse { error }) => {
+                    assert_eq!(error.kind, ParseErrorKind::UnknownKey, "{value}");
+                    assert_eq!(&value[error.span], error.found);
+                }
+                _ => panic!("Expected an unknown key error: {value}"),
+            }
+        }
+        let value = "FREQ=YEARLY;INTERVAL=1;BYWEEKNO=20;BYDAY=MO";
+        assert!(validate_strict(value, &FREQUENCY_KEYS).is_ok());
+    }
+
+    #[test]
+    fn rejects_duplicated_keys() {
+        let value = "FREQ=DAILY;INTERVAL=1;INTERVAL=2";
+        match validate_strict(value, &FREQUENCY_KEYS) {
+            Err(InvalidFrequency::Parse { error }) => {
+                assert_eq!(error.kind, ParseErrorKind::DuplicatedKey);
+                assert_eq!(&value[error.span], "INTERVAL=2");
+            }
+            _ => panic!("Expected a duplicated key error"),
+        }
+    }
+
+    #[test]
+    fn rejects_duplicated_values() {
+        let value = "FREQ=WEEKLY;INTERVAL=1;BYDAY=MO,SU,MO";
+        match validate_strict(value, &FREQUENCY_KEYS) {
+            Err(InvalidFrequency::Parse { error }) => {
+                assert_eq!(error.kind, ParseErrorKind::DuplicatedValue);
+                assert_eq!(error.span, 35..37);
+                assert_eq!(error.found, "MO");
+            }
+            _ => panic!("Expected a duplicated value error"),
+        }
+        let value = "FREQ=DAILY;INTERVAL=1;BYTIME=09:30,09:30:00";
+        assert!(validate_strict(value, &FREQUENCY_KEYS).is_err());
+        let value = "FREQ=MONTHLY;INTERVAL=1;BYMONTHDAY=15,1";
+        assert!(validate_strict(value, &FREQUENCY_KEYS).is_ok());
+    }
+
+    #[test]
+    fn rejects_trailing_garbage() {
+        let value = "FREQ=DAILY;INTERVAL=1abc";
+        assert!(validate_strict(value, &FREQUENCY_KEYS).is_err());
+        let value = "FREQ=WEEKLY;INTERVAL=1;BYDAY=MO,";
+        assert!(validate_strict(value, &FREQUENCY_KEYS).is_err());
+    }
+}
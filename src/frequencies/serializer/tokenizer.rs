@@ -0,0 +1,546 @@
+use crate::codes::{month_from_number, weekday_from_code};
+use crate::frequencies::validations::{validate_nth_weekday, YEAR_WEEKS};
+use crate::frequencies::{ParseError, ParseErrorKind};
+use crate::{MonthlyDate, NthWeekday, OverflowPolicy, Time};
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use alloc::{format, vec};
+use chrono::{Month, Weekday};
+use core::ops::{Range, RangeInclusive};
+use core::str::FromStr;
+
+/// A `KEY=VALUE` pair of a rule string.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Token<'a> {
+    pub key: &'a str,
+    pub value: &'a str,
+    /// Byte offset of the key in the rule string
+    pub offset: usize,
+}
+
+/// A part of a rule string that is not a `KEY=VALUE` pair.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Malformed<'a> {
+    pub text: &'a str,
+    /// Byte offset of the text in the rule string
+    pub offset: usize,
+}
+
+/// Splits a rule string on `;` and then on the first `=` of every part, in a single pass.
+///
+/// A trailing `;` is allowed, any other part without a key and a value is returned as `Malformed`.
+pub fn tokenize(s: &str) -> impl Iterator<Item = Result<Token<'_>, Malformed<'_>>> {
+    let trimmed = s.strip_suffix(';').unwrap_or(s);
+    let mut offset = 0;
+    trimmed.split(';').map(move |part| {
+        let part_offset = offset;
+        offset += part.len() + 1;
+        match part.split_once('=') {
+            Some((key, value)) if !key.is_empty() => Ok(Token {
+                key,
+                value,
+                offset: part_offset,
+            }),
+            _ => Err(Malformed {
+                text: part,
+                offset: part_offset,
+            }),
+        }
+    })
+}
+
+/// A rule string in the form read by the parser, with the whitespace around its separators
+/// removed, its keys in uppercase, and the values of the given keys in uppercase.
+///
+/// The values of the other keys, like the unknown parameters, keep their case.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Normalized {
+    pub value: String,
+    /// Byte offset in the original string of every byte of the value, followed by its length
+    offsets: Vec<usize>,
+}
+
+impl Normalized {
+    pub fn new(s: &str, uppercase_keys: &[&str]) -> Self {
+        let is_separator = |c: char| matches!(c, ';' | '=' | ',');
+        let chars: Vec<(usize, char)> = s.char_indices().collect();
+        let mut value = String::with_capacity(s.len());
+        let mut offsets = Vec::with_capacity(s.len() + 1);
+        let mut i = 0;
+        while i < chars.len() {
+            let (offset, c) = chars[i];
+            if !c.is_whitespace() {
+                value.push(c);
+                offsets.extend(offset..offset + c.len_utf8());
+                i += 1;
+                continue;
+            }
+            // Whitespace is kept between two characters of a value, E.g. in unknown parameters
+            let end = (i..chars.len())
+                .find(|&j| !chars[j].1.is_whitespace())
+                .unwrap_or(chars.len());
+            let before = i.checked_sub(1).map(|j| chars[j].1);
+            let after = chars.get(end).map(|(_, c)| *c);
+            if !before.is_none_or(is_separator) && !after.is_none_or(is_separator) {
+                for &(offset, c) in &chars[i..end] {
+                    value.push(c);
+                    offsets.extend(offset..offset + c.len_utf8());
+                }
+            }
+            i = end;
+        }
+        offsets.push(s.len());
+
+        let ranges: Vec<Range<usize>> = tokenize(&value)
+            .flatten()
+            .map(|token| {
+                let uppercase_value = uppercase_keys
+                    .iter()
+                    .any(|key| key.eq_ignore_ascii_case(token.key));
+                let end = match uppercase_value {
+                    true => token.value_offset() + token.value.len(),
+                    false => token.offset + token.key.len(),
+                };
+                token.offset..end
+            })
+            .collect();
+        for range in ranges {
+            value[range].make_ascii_uppercase();
+        }
+        Normalized { value, offsets }
+    }
+
+    /// Moves the span of the error to the original string, with the text found there.
+    pub fn restore(&self, error: ParseError, original: &str) -> ParseError {
+        let start = self.offsets[error.span.start];
+        let end = match error.span.end > error.span.start {
+            true => self.offsets[error.span.end - 1] + 1,
+            false => start,
+        };
+        ParseError {
+            found: original[start..end].to_string(),
+            span: start..end,
+            ..error
+        }
+    }
+}
+
+/// A typed frequency parameter of a rule string.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RuleParam {
+    Freq(String),
+    Interval(u32),
+    /// Days of the week, optionally preceded by their ordinal (E.g. `MO` or `1MO`)
+    ByDay(Vec<(Option<i32>, Weekday)>),
+    /// Days of the week with their time (E.g. `MO09:00`)
+    ByDayTime(Vec<(Weekday, Time)>),
+    ByMonth(Vec<Month>),
+    ByMonthDay(Vec<i32>),
+    ByMonthDate(Vec<MonthlyDate>),
+    ByWeekNo(Vec<i32>),
+    ByTime(Vec<Time>),
+    ByHour(Vec<i32>),
+    ByMinute(Vec<i32>),
+    BySecond(Vec<i32>),
+    /// Policy for the invalid dates, from the `X-OVERFLOW` or the RFC 7529 `SKIP` key
+    Overflow(OverflowPolicy),
+    /// The calendar scale of RFC 7529, which can only be `GREGORIAN`
+    Rscale,
+    Business(bool),
+    /// Hours from the first included to the last excluded (E.g. `9-18`)
+    ByHourRange((u8, u8)),
+}
+
+impl<'a> Token<'a> {
+    /// Byte range of the whole `KEY=VALUE` pair
+    pub fn span(&self) -> Range<usize> {
+        self.offset..self.value_offset() + self.value.len()
+    }
+
+    /// Byte offset of the value in the rule string
+    pub fn value_offset(&self) -> usize {
+        self.offset + self.key.len() + 1
+    }
+
+    /// Byte range of the value at the index of a comma separated list
+    pub fn list_value_span(&self, index: usize) -> Range<usize> {
+        let mut offset = self.value_offset();
+        for (i, value) in self.value.split(',').enumerate() {
+            if i == index {
+                return offset..offset + value.len();
+            }
+            offset += value.len() + 1;
+        }
+        offset..offset
+    }
+}
+
+impl<'a> Malformed<'a> {
+    pub fn to_error(self) -> ParseError {
+        ParseError {
+            kind: ParseErrorKind::Malformed,
+            span: self.offset..self.offset + self.text.len(),
+            found: self.text.to_string(),
+            expected: "KEY=VALUE".to_string(),
+        }
+    }
+}
+
+impl RuleParam {
+    /// Parses the typed parameter of a token.
+    /// Returns None when the key is not a frequency parameter.
+    pub fn from_token(token: &Token) -> Option<Result<RuleParam, ParseError>> {
+        let param = match token.key {
+            "FREQ" => parse_value(token, FREQUENCIES.join(", "), |value| {
+                FREQUENCIES
+                    .contains(&value)
+                    .then(|| value.to_string())
+                    .ok_or(())
+            })
+            .map(RuleParam::Freq),
+            "INTERVAL" => parse_value(
+                token,
+                format!("a positive number up to {}", u32::MAX),
+                |value| match value.bytes().all(|b| b.is_ascii_digit()) {
+                    true => value.parse::<u32>().map_err(|_| ()),
+                    false => Err(()),
+                },
+            )
+            .map(RuleParam::Interval),
+            "BYDAY" => parse_by_day(token).map(RuleParam::ByDay),
+            "X-BYDAYTIME" => parse_list(token, "a weekday and a time like MO09:30", parse_day_time)
+                .map(RuleParam::ByDayTime),
+            "BYMONTH" => parse_list(token, "a month between 1 and 12", |month| {
+                month_from_number(parse_number(month, 1..=12)?).ok_or(())
+            })
+            .map(RuleParam::ByMonth),
+            "BYMONTHDAY" => parse_by_month_day(token).map(RuleParam::ByMonthDay),
+            "X-BYMONTHDATE" => parse_list(token, "a month date like JAN15", |date| {
+                MonthlyDate::from_str(date).map_err(|_| ())
+            })
+            .map(RuleParam::ByMonthDate),
+            "BYWEEKNO" => parse_list(token, "a week number", |week| {
+                parse_number(week, i32::MIN..=i32::MAX)
+            })
+            .map(RuleParam::ByWeekNo),
+            "BYTIME" => parse_by_time(token).map(RuleParam::ByTime),
+            "BYHOUR" => parse_list(token, "an hour between 0 and 23", |hour| {
+                parse_number(hour, 0..=23)
+            })
+            .map(RuleParam::ByHour),
+            "BYMINUTE" => parse_list(token, "a minute between 0 and 59", |minute| {
+                parse_number(minute, 0..=59)
+            })
+            .map(RuleParam::ByMinute),
+            "BYSECOND" => parse_list(token, "a second between 0 and 59", |second| {
+                parse_number(second, 0..=59)
+            })
+            .map(RuleParam::BySecond),
+            "X-OVERFLOW" => parse_value(token, "SKIP, CLAMP or NEXT".to_string(), |value| {
+                OverflowPolicy::from_str(value).map_err(|_| ())
+            })
+            .map(RuleParam::Overflow),
+            "SKIP" => {
+                parse_value(
+                    token,
+                    "OMIT, BACKWARD or FORWARD".to_string(),
+                    |value| match value {
+                        "OMIT" => Ok(OverflowPolicy::Skip),
+                        "BACKWARD" => Ok(OverflowPolicy::ClampToLastDay),
+                        "FORWARD" => Ok(OverflowPolicy::NextValid),
+                        _ => Err(()),
+                    },
+                )
+                .map(RuleParam::Overflow)
+            }
+            // Calendar scale names are case-insensitive
+            "RSCALE" => parse_value(token, "GREGORIAN".to_string(), |value| {
+                value
+                    .eq_ignore_ascii_case("GREGORIAN")
+                    .then_some(())
+                    .ok_or(())
+            })
+            .map(|_| RuleParam::Rscale),
+            "X-BUSINESS" => parse_value(token, "TRUE or FALSE".to_string(), |value| match value {
+                "TRUE" => Ok(true),
+                "FALSE" => Ok(false),
+                _ => Err(()),
+            })
+            .map(RuleParam::Business),
+            "X-BYHOURRANGE" => {
+                parse_value(token, "a range of hours like 9-18".to_string(), |value| {
+                    let (start, end) = value.split_once('-').ok_or(())?;
+                    let (start, end) = (parse_number(start, 0..=23)?, parse_number(end, 1..=24)?);
+                    (start < end).then_some((start as u8, end as u8)).ok_or(())
+                })
+                .map(RuleParam::ByHourRange)
+            }
+            _ => return None,
+        };
+        Some(param)
+    }
+
+    /// Returns the index of the first value of a list that was already in the list.
+    pub fn duplicate_index(&self) -> Option<usize> {
+        match self {
+            RuleParam::Freq(_)
+            | RuleParam::Interval(_)
+            | RuleParam::Overflow(_)
+            | RuleParam::Rscale
+            | RuleParam::Business(_)
+            | RuleParam::ByHourRange(_) => None,
+            RuleParam::ByDay(days) => first_duplicate(days),
+            RuleParam::ByDayTime(day_times) => first_duplicate(day_times),
+            RuleParam::ByMonth(months) => first_duplicate(months),
+            RuleParam::ByMonthDay(values)
+            | RuleParam::ByWeekNo(values)
+            | RuleParam::ByHour(values)
+            | RuleParam::ByMinute(values)
+            | RuleParam::BySecond(values) => first_duplicate(values),
+            RuleParam::ByMonthDate(dates) => first_duplicate(dates),
+            RuleParam::ByTime(times) => first_duplicate(times),
+        }
+    }
+}
+
+/// Parses the value of a single key the same way as in a rule string, E.g. a value typed in
+/// a form, with the spans of the errors in the value.
+pub fn parse_component<T>(
+    key: &str,
+    value: &str,
+    parse: impl Fn(&Token) -> Result<T, ParseError>,
+) -> Result<T, ParseError> {
+    let rule = format!("{key}={value}");
+    let normalized = Normalized::new(&rule, &[key]);
+    let token = Token {
+        key,
+        value: &normalized.value[key.len() + 1..],
+        offset: 0,
+    };
+    parse(&token).map_err(|error| {
+        let error = normalized.restore(error, &rule);
+        let value_offset = token.value_offset();
+        ParseError {
+            span: error.span.start - value_offset..error.span.end - value_offset,
+            ..error
+        }
+    })
+}
+
+pub fn parse_by_day(token: &Token) -> Result<Vec<(Option<i32>, Weekday)>, ParseError> {
+    parse_list(token, "a weekday like MO or 1MO", parse_day)
+}
+
+pub fn parse_by_month_day(token: &Token) -> Result<Vec<i32>, ParseError> {
+    parse_list(token, "a day between 1 and 31", |day| {
+        parse_number(day, 1..=31)
+    })
+}
+
+pub fn parse_by_time(token: &Token) -> Result<Vec<Time>, ParseError> {
+    parse_list(token, "a time like 09:30", |time| {
+        Time::from_str(time).map_err(|_| ())
+    })
+}
+
+fn first_duplicate<T: PartialEq>(values: &[T]) -> Option<usize> {
+    (1..values.len()).find(|i| values[..*i].contains(&values[*i]))
+}
+
+const FREQUENCIES: [&str; 7] = [
+    "SECONDLY", "MINUTELY", "HOURLY", "DAILY", "WEEKLY", "MONTHLY", "YEARLY",
+];
+
+fn parse_value<T>(
+    token: &Token,
+    expected: String,
+    parse: impl Fn(&str) -> Result<T, ()>,
+) -> Result<T, ParseError> {
+    parse(token.value).map_err(|_| ParseError {
+        kind: ParseErrorKind::InvalidValue,
+        span: token.value_offset()..token.value_offset() + token.value.len(),
+        found: token.value.to_string(),
+        expected,
+    })
+}
+
+/// Parses every comma separated value of the token, reporting the span of the first invalid one.
+fn parse_list<T>(
+    token: &Token,
+    expected: &str,
+    parse: impl Fn(&str) -> Result<T, ()>,
+) -> Result<Vec<T>, ParseError> {
+    let mut offset = token.value_offset();
+    let mut values = vec![];
+    for value in token.value.split(',') {
+        match parse(value) {
+            Ok(v) => values.push(v),
+            Err(_) => {
+                return Err(ParseError {
+                    kind: ParseErrorKind::InvalidValue,
+                    span: offset..offset + value.len(),
+                    found: value.to_string(),
+                    expected: expected.to_string(),
+                })
+            }
+        }
+        offset += value.len() + 1;
+    }
+    Ok(values)
+}
+
+fn parse_number(value: &str, range: RangeInclusive<i32>) -> Result<i32, ()> {
+    let is_number = value
+        .strip_prefix('-')
+        .filter(|_| *range.start() < 0)
+        .unwrap_or(value)
+        .bytes()
+        .all(|b| b.is_ascii_digit());
+    match value.parse::<i32>() {
+        Ok(n) if is_number && range.contains(&n) => Ok(n),
+        _ => Err(()),
+    }
+}
+
+fn parse_day_time(value: &str) -> Result<(Weekday, Time), ()> {
+    if !value.is_char_boundary(2) {
+        return Err(());
+    }
+    let (day, time) = value.split_at(2);
+    let weekday = weekday_from_code(day).ok_or(())?;
+    Ok((weekday, Time::from_str(time).map_err(|_| ())?))
+}
+
+fn parse_day(value: &str) -> Result<(Option<i32>, Weekday), ()> {
+    if value.starts_with(|c: char| c.is_ascii_digit()) {
+        let nth_weekday = NthWeekday::from_str(value).map_err(|_| ())?;
+        validate_nth_weekday(&nth_weekday, YEAR_WEEKS).map_err(|_| ())?;
+        return Ok((Some(nth_weekday.week_number), nth_weekday.weekday));
+    }
+    Ok((None, weekday_from_code(value).ok_or(())?))
+}
+
+#[cfg(test)]
+mod test_tokenizer {
+    use crate::frequencies::serializer::tokenizer::{
+        tokenize, Malformed, Normalized, RuleParam, Token,
+    };
+    use crate::frequencies::{ParseError, ParseErrorKind};
+    use chrono::{Month, Weekday};
+
+    #[test]
+    fn test_tokenize() {
+        let tokens: Vec<_> = tokenize("FREQ=DAILY;INTERVAL=1;").collect();
+        assert_eq!(
+            tokens,
+            vec![
+                Ok(Token {
+                    key: "FREQ",
+                    value: "DAILY",
+                    offset: 0
+                }),
+                Ok(Token {
+                    key: "INTERVAL",
+                    value: "1",
+                    offset: 11
+                }),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_tokenize_malformed() {
+        let tokens: Vec<_> = tokenize("FREQ=YEARLY;BY;DTSTART=2020-01-01T00:00:00Z").collect();
+        assert_eq!(
+            tokens[1],
+            Err(Malformed {
+                text: "BY",
+                offset: 12
+            })
+        );
+        assert_eq!(tokens[2].unwrap().value, "2020-01-01T00:00:00Z");
+    }
+
+    #[test]
+    fn test_param_from_token() {
+        let params: Vec<_> = tokenize("BYDAY=MO,1TU;BYMONTH=1,6;BYWEEKNO=-1;DTSTART=2020")
+            .map(|token| RuleParam::from_token(&token.unwrap()))
+            .collect();
+        assert_eq!(
+            params[0].as_ref().unwrap().as_ref().unwrap(),
+            &RuleParam::ByDay(vec![(None, Weekday::Mon), (Some(1), Weekday::Tue)])
+        );
+        assert_eq!(
+            params[1].as_ref().unwrap().as_ref().unwrap(),
+            &RuleParam::ByMonth(vec![Month::January, Month::June])
+        );
+        assert_eq!(
+            params[2].as_ref().unwrap().as_ref().unwrap(),
+            &RuleParam::ByWeekNo(vec![-1])
+        );
+        assert!(params[3].is_none());
+    }
+
+    #[test]
+    fn test_param_from_token_invalid() {
+        for value in [
+            "INTERVAL=",
+            "INTERVAL=1abc",
+            "BYDAY=",
+            "BYDAY=MO,",
+            "BYMONTH=13",
+            "BYMONTHDAY=-1",
+            "BYHOUR=24",
+            "BYTIME=INVALID",
+            "X-BYMONTHDATE=FOO1",
+        ] {
+            let token = tokenize(value).next().unwrap().unwrap();
+            let param = RuleParam::from_token(&token).unwrap();
+            assert!(param.is_err(), "{value} should not be parsed");
+        }
+    }
+
+    #[test]
+    fn test_param_error_span() {
+        let value = "FREQ=WEEKLY;INTERVAL=1;BYDAY=MO,XX,TU";
+        let token = tokenize(value).nth(2).unwrap().unwrap();
+        assert_eq!(token.span(), 23..37);
+        let error = RuleParam::from_token(&token).unwrap().unwrap_err();
+        assert_eq!(error.kind, ParseErrorKind::InvalidValue);
+        assert_eq!(error.span, 32..34);
+        assert_eq!(error.found, "XX");
+        assert_eq!(error.expected, "a weekday like MO or 1MO");
+        assert_eq!(
+            error.to_string(),
+            "Invalid value \"XX\", expected a weekday like MO or 1MO at 32..34"
+        );
+    }
+
+    #[test]
+    fn test_unsupported_frequency_span() {
+        let token = tokenize("FREQ=FOO").next().unwrap().unwrap();
+        let error = RuleParam::from_token(&token).unwrap().unwrap_err();
+        assert_eq!(error.span, 5..8);
+        assert_eq!(error.found, "FOO");
+    }
+
+    #[test]
+    fn normalizes_case_and_whitespace() {
+        let value = " freq = weekly ; byday=mo, we ;x-name=Team  sync ; ";
+        let normalized = Normalized::new(value, &["FREQ", "BYDAY"]);
+        assert_eq!(
+            normalized.value,
+            "FREQ=WEEKLY;BYDAY=MO,WE;X-NAME=Team  sync;"
+        );
+
+        let error = ParseError {
+            kind: ParseErrorKind::InvalidValue,
+            span: 18..20,
+            found: "MO".to_string(),
+            expected: "a weekday".to_string(),
+        };
+        let error = normalized.restore(error, value);
+        assert_eq!(error.span, 23..25);
+        assert_eq!(error.found, "mo");
+    }
+}
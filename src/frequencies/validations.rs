@@ -1,51 +1,33 @@
 use crate::frequencies::errors::InvalidFrequency;
-use crate::frequencies::{MonthlyDate, NthWeekday, Time};
-use chrono::Weekday;
-use std::collections::HashSet;
+use crate::frequencies::{MonthlyDate, NthWeekday, OverflowPolicy, Time};
+use alloc::collections::BTreeSet;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use chrono::{Month, Weekday};
 
-pub fn validate_secondly(interval: &i32) -> Result<(), InvalidFrequency> {
-    if *interval > 0 {
-        Ok(())
-    } else {
-        Err(InvalidFrequency::Interval {
-            message: "Interval must be greater than 0".to_string(),
-        })
-    }
+pub fn validate_secondly(by_day: &[Weekday], by_time: &[Time]) -> Result<(), InvalidFrequency> {
+    validate_sub_daily(by_day, by_time)
 }
 
-pub fn validate_minutely(interval: &i32) -> Result<(), InvalidFrequency> {
-    if *interval > 0 {
-        Ok(())
-    } else {
-        Err(InvalidFrequency::Interval {
-            message: "Interval must be greater than 0".to_string(),
-        })
-    }
+pub fn validate_minutely(by_day: &[Weekday], by_time: &[Time]) -> Result<(), InvalidFrequency> {
+    validate_sub_daily(by_day, by_time)
 }
 
-pub fn validate_hourly(interval: &i32) -> Result<(), InvalidFrequency> {
-    if *interval > 0 {
-        Ok(())
-    } else {
-        Err(InvalidFrequency::Interval {
-            message: "Interval must be greater than 0".to_string(),
-        })
-    }
+pub fn validate_hourly(by_day: &[Weekday], by_time: &[Time]) -> Result<(), InvalidFrequency> {
+    validate_sub_daily(by_day, by_time)
 }
 
-pub fn validate_daily(interval: &i32, by_time: &[Time]) -> Result<(), InvalidFrequency> {
-    if *interval <= 0 {
-        return Err(InvalidFrequency::Interval {
-            message: "Interval must be greater than 0".to_string(),
-        });
-    }
-    let mut unique_times: HashSet<Time> = HashSet::new();
+fn validate_sub_daily(by_day: &[Weekday], by_time: &[Time]) -> Result<(), InvalidFrequency> {
+    validate_daily(by_time)?;
+    validate_weekly(by_day)
+}
+
+pub fn validate_daily(by_time: &[Time]) -> Result<(), InvalidFrequency> {
+    let mut unique_times: BTreeSet<Time> = BTreeSet::new();
     for time in by_time {
-        let t = Time {
-            hour: time.hour,
-            minute: time.minute,
-        };
-        if !unique_times.insert(t) {
+        validate_time(time)?;
+        if !unique_times.insert(time.clone()) {
             return Err(InvalidFrequency::Time {
                 message: "Repeated time".to_string(),
             });
@@ -54,15 +36,36 @@ pub fn validate_daily(interval: &i32, by_time: &[Time]) -> Result<(), InvalidFre
     Ok(())
 }
 
-pub fn validate_weekly(interval: &i32, by_day: &[Weekday]) -> Result<(), InvalidFrequency> {
-    if *interval <= 0 {
-        return Err(InvalidFrequency::Interval {
-            message: "Interval must be greater than 0".to_string(),
+/// Verifies that the range of hours starts before it ends, and ends at the latest at 24.
+pub fn validate_hour_range(by_hour_range: Option<(u8, u8)>) -> Result<(), InvalidFrequency> {
+    match by_hour_range {
+        Some((start, end)) if start >= end || end > 24 => Err(InvalidFrequency::Time {
+            message: format!("Invalid range of hours: {}-{}", start, end),
+        }),
+        _ => Ok(()),
+    }
+}
+
+/// Verifies that the hour is between 0 and 23, and the minute and second between 0 and 59.
+pub fn validate_time(time: &Time) -> Result<(), InvalidFrequency> {
+    if !(0..24).contains(&time.hour)
+        || !(0..60).contains(&time.minute)
+        || !(0..60).contains(&time.second)
+    {
+        return Err(InvalidFrequency::Time {
+            message: format!(
+                "Time out of range: {}:{}:{}",
+                time.hour, time.minute, time.second
+            ),
         });
     }
-    let mut unique_days: HashSet<Weekday> = HashSet::new();
+    Ok(())
+}
+
+pub fn validate_weekly(by_day: &[Weekday]) -> Result<(), InvalidFrequency> {
+    let mut unique_days: BTreeSet<u32> = BTreeSet::new();
     for day in by_day {
-        if !unique_days.insert(*day) {
+        if !unique_days.insert(day.num_days_from_monday()) {
             return Err(InvalidFrequency::Day {
                 message: "Repeated day".to_string(),
             });
@@ -71,18 +74,55 @@ pub fn validate_weekly(interval: &i32, by_day: &[Weekday]) -> Result<(), Invalid
     Ok(())
 }
 
+/// Verifies that the times of a weekly frequency are unique and valid, and that they are
+/// not combined with days with times.
+pub fn validate_weekly_times(
+    by_time: &[Time],
+    by_day_time: &[(Weekday, Time)],
+) -> Result<(), InvalidFrequency> {
+    if !by_time.is_empty() && !by_day_time.is_empty() {
+        return Err(InvalidFrequency::Time {
+            message: "Days with times cannot be combined with BYTIME".to_string(),
+        });
+    }
+    validate_daily(by_time)
+}
+
+/// Verifies that the days with times are unique and valid, and that they are not
+/// combined with days without times.
+pub fn validate_day_times(
+    by_day: &[Weekday],
+    by_day_time: &[(Weekday, Time)],
+) -> Result<(), InvalidFrequency> {
+    if !by_day.is_empty() && !by_day_time.is_empty() {
+        return Err(InvalidFrequency::Day {
+            message: "Days with times cannot be combined with BYDAY".to_string(),
+        });
+    }
+    let mut unique_day_times: BTreeSet<(u32, Time)> = BTreeSet::new();
+    for (day, time) in by_day_time {
+        validate_time(time)?;
+        if !unique_day_times.insert((day.num_days_from_monday(), time.clone())) {
+            return Err(InvalidFrequency::Day {
+                message: "Repeated day and time".to_string(),
+            });
+        }
+    }
+    Ok(())
+}
+
 pub fn validate_monthly(
-    interval: &i32,
     by_month_day: &[i32],
+    by_day: &[Weekday],
     nth_weekdays: &[NthWeekday],
 ) -> Result<(), InvalidFrequency> {
-    if *interval <= 0 {
-        return Err(InvalidFrequency::Interval {
-            message: "Interval must be greater than 0".to_string(),
-        });
-    }
-    let mut unique_month_days: HashSet<i32> = HashSet::new();
+    let mut unique_month_days: BTreeSet<i32> = BTreeSet::new();
     for day in by_month_day {
+        if !(1..=31).contains(day) {
+            return Err(InvalidFrequency::Day {
+                message: format!("Invalid month day {day}, it must be between 1 and 31"),
+            });
+        }
         if !unique_month_days.insert(*day) {
             return Err(InvalidFrequency::Day {
                 message: "Repeated day".to_string(),
@@ -90,8 +130,11 @@ pub fn validate_monthly(
         }
     }
 
-    let mut unique_nth_weekdays: HashSet<NthWeekday> = HashSet::new();
+    validate_weekly(by_day)?;
+
+    let mut unique_nth_weekdays: BTreeSet<NthWeekday> = BTreeSet::new();
     for nth_weekday in nth_weekdays {
+        validate_nth_weekday(nth_weekday, MONTH_WEEKS)?;
         let nth_weekday = NthWeekday {
             week_number: nth_weekday.week_number,
             weekday: nth_weekday.weekday,
@@ -106,10 +149,129 @@ pub fn validate_monthly(
     Ok(())
 }
 
+/// The largest ordinal of the weekdays of a month, like the 5th Monday.
+pub const MONTH_WEEKS: i32 = 5;
+
+/// The largest ordinal of the weekdays of a year, like the 53rd Monday.
+pub const YEAR_WEEKS: i32 = 53;
+
+/// Verifies that the ordinal of the weekday is between 1 and the given largest ordinal.
+pub fn validate_nth_weekday(nth_weekday: &NthWeekday, max: i32) -> Result<(), InvalidFrequency> {
+    if !(1..=max).contains(&nth_weekday.week_number) {
+        return Err(InvalidFrequency::Day {
+            message: format!(
+                "Invalid nth weekday {nth_weekday}, the ordinal must be between 1 and {max}"
+            ),
+        });
+    }
+    Ok(())
+}
+
+/// Verifies that the month of the date has its day, at least in leap years, or that the
+/// overflow policy moves the missing days to another date.
+pub fn validate_monthly_date(
+    monthly_date: &MonthlyDate,
+    overflow: &OverflowPolicy,
+) -> Result<(), InvalidFrequency> {
+    let last_day = match overflow {
+        OverflowPolicy::Skip => monthly_date.last_day(),
+        _ => 31,
+    };
+    if !(1..=last_day).contains(&monthly_date.day) {
+        return Err(InvalidFrequency::Day {
+            message: format!(
+                "Invalid monthly date {monthly_date}, the day must be between 1 and {last_day} in {}",
+                monthly_date.month.name()
+            ),
+        });
+    }
+    Ok(())
+}
+
+/// Describes the dates of the year that only happen in leap years.
+pub fn monthly_date_warnings(by_monthly_date: &[MonthlyDate]) -> Vec<String> {
+    by_monthly_date
+        .iter()
+        .filter(|date| date.month == Month::February && date.day == 29)
+        .map(|date| {
+            format!("{date} only exists in leap years, so no event is generated in the other years")
+        })
+        .collect()
+}
+
+/// Describes the months that are skipped because they don't have some of the month days.
+pub fn monthly_day_warnings(by_month_day: &[i32]) -> Vec<String> {
+    by_month_day
+        .iter()
+        .filter_map(|day| {
+            let months = match day {
+                29 => "February in common years",
+                30 => "February",
+                31 => "February, April, June, September and November",
+                _ => return None,
+            };
+            Some(format!(
+                "Month day {day} does not exist in {months}, so no event is generated on those months"
+            ))
+        })
+        .collect()
+}
+
 pub fn validate_yearly(
-    _interval: &i32,
-    _by_monthly_date: &Option<MonthlyDate>,
+    by_month: &[Month],
+    by_monthly_date: &[MonthlyDate],
+    nth_weekdays: &[NthWeekday],
+    by_week_number: &[i32],
+    overflow: &OverflowPolicy,
 ) -> Result<(), InvalidFrequency> {
-    // Todo: Implement
+    let mut unique_months: BTreeSet<u32> = BTreeSet::new();
+    for month in by_month {
+        if !unique_months.insert(month.number_from_month()) {
+            return Err(InvalidFrequency::Day {
+                message: "Repeated month".to_string(),
+            });
+        }
+    }
+    let mut unique_monthly_dates: BTreeSet<&MonthlyDate> = BTreeSet::new();
+    for monthly_date in by_monthly_date {
+        validate_monthly_date(monthly_date, overflow)?;
+        if !unique_monthly_dates.insert(monthly_date) {
+            return Err(InvalidFrequency::Day {
+                message: "Repeated monthly date".to_string(),
+            });
+        }
+    }
+
+    // The weekdays are counted in their month when the months are restricted
+    let max_weeks = if by_month.is_empty() {
+        YEAR_WEEKS
+    } else {
+        MONTH_WEEKS
+    };
+    let mut unique_nth_weekdays: BTreeSet<&NthWeekday> = BTreeSet::new();
+    for nth_weekday in nth_weekdays {
+        validate_nth_weekday(nth_weekday, max_weeks)?;
+        if !unique_nth_weekdays.insert(nth_weekday) {
+            return Err(InvalidFrequency::Day {
+                message: "Repeated day".to_string(),
+            });
+        }
+    }
+
+    let mut unique_week_numbers: BTreeSet<i32> = BTreeSet::new();
+    for week_number in by_week_number {
+        if *week_number == 0 || !(-53..=53).contains(week_number) {
+            return Err(InvalidFrequency::Day {
+                message: format!(
+                    "Week number must be between 1 and 53 or -53 and -1, got {week_number}"
+                ),
+            });
+        }
+        if !unique_week_numbers.insert(*week_number) {
+            return Err(InvalidFrequency::Day {
+                message: "Repeated week number".to_string(),
+            });
+        }
+    }
     Ok(())
 }
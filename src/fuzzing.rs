@@ -0,0 +1,122 @@
+//! Entry points for fuzzing the parsers, E.g. with `cargo fuzz`.
+//!
+//! Every function takes arbitrary bytes, reads them as a lossy UTF-8 string and gives it to
+//! the parsers. They return nothing: the parsers must never panic, whatever the input is.
+//! A `cargo fuzz` target only needs to call one of them:
+//!
+//! ```ignore
+//! #![no_main]
+//! libfuzzer_sys::fuzz_target!(|data: &[u8]| rrules::fuzzing::fuzz_parse(data));
+//! ```
+
+use crate::recurrences::serializers::parse_duration;
+use crate::{Frequency, ParseOptions, Recurrence};
+use alloc::string::{String, ToString};
+use core::str::FromStr;
+
+/// Runs every parser on the data.
+pub fn fuzz_parse(data: &[u8]) {
+    fuzz_parse_frequency(data);
+    fuzz_parse_recurrence(data);
+    fuzz_parse_duration(data);
+    fuzz_parse_cron(data);
+}
+
+/// Parses a frequency, leniently and strictly, serializing and parsing it again when it is valid.
+pub fn fuzz_parse_frequency(data: &[u8]) {
+    let value = to_string(data);
+    if let Ok(frequency) = Frequency::from_str(&value) {
+        let _ = Frequency::from_str(&frequency.to_string());
+        let _ = Frequency::from_str(&frequency.to_rfc_string());
+    }
+    let _ = Frequency::from_str_with(&value, ParseOptions::strict());
+}
+
+/// Parses a recurrence, leniently and strictly, serializing and parsing it again when it is valid.
+///
+/// Rule strings and content lines are both parsed, depending on the data.
+pub fn fuzz_parse_recurrence(data: &[u8]) {
+    let value = to_string(data);
+    if let Ok(recurrence) = Recurrence::from_str(&value) {
+        let _ = Recurrence::from_str(&recurrence.to_string());
+        let _ = recurrence.canonical_string();
+    }
+    let _ = Recurrence::from_str_with(&value, ParseOptions::strict());
+}
+
+/// Parses an ISO 8601 duration, like the `DURATION` value of a recurrence.
+pub fn fuzz_parse_duration(data: &[u8]) {
+    let _ = parse_duration(&to_string(data));
+}
+
+/// Parses a cron expression, writing it back when it is valid.
+pub fn fuzz_parse_cron(data: &[u8]) {
+    if let Ok(frequency) = Frequency::from_cron(&to_string(data)) {
+        let _ = frequency.to_cron();
+    }
+}
+
+fn to_string(data: &[u8]) -> String {
+    String::from_utf8_lossy(data).to_string()
+}
+
+#[cfg(test)]
+mod test_fuzzing {
+    use super::fuzz_parse;
+
+    #[test]
+    fn adversarial_rules_do_not_panic() {
+        let values = [
+            "",
+            "=",
+            ";;;",
+            "FREQ",
+            "FREQ=;INTERVAL=",
+            "FREQ=DAILY;INTERVAL=4294967296;DTSTART=2023-01-01T00:00:00Z",
+            "FREQ=YEARLY;INTERVAL=4294967295;DTSTART=+262143-12-31T23:59:59Z",
+            "FREQ=MONTHLY;INTERVAL=1;BYDAY=2147483647MO,-5FR;DTSTART=-262143-01-01T00:00:00Z",
+            "FREQ=MONTHLY;INTERVAL=1;BYMONTHDAY=31;X-OVERFLOW=NEXT;DTSTART=VALUE=DATE:99991231",
+            "FREQ=WEEKLY;INTERVAL=1;X-BYDAYTIME=MO25:00,SU23:59:60;DTSTART=20230101T000000Z",
+            "FREQ=DAILY;INTERVAL=1;BYTIME=99:99;BYHOUR=-1;BYSECOND=60;DTSTART=2023-01-01T00:00:00Z",
+            "FREQ=DAILY;INTERVAL=1;DTSTART=2023-01-01T00:00:00Z;DURATION=P2147483647DT2147483647H",
+            "FREQ=DAILY;INTERVAL=1;DTSTART=+262143-12-31T23:59:59Z;DURATION=P2147483647W",
+            "FREQ=YEARLY;INTERVAL=1;BYWEEKNO=-53,53;BYDAY=SU;DTSTART=+262143-12-31T00:00:00Z",
+            "FREQ=YEARLY;INTERVAL=1;X-BYMONTHDATE=FEB30;RSCALE=GREGORIAN;SKIP=FORWARD;DTSTART=é",
+            "DTSTART:20230101T000000Z\r\n RRULE:FREQ=DAILY;INTERVAL=1\nEXDATE:99999999T999999Z",
+            "BEGIN:VEVENT\nDTSTART;TZID=Europe/Paris:\nRRULE:\nEND:VEVENT",
+            "P",
+            "PT",
+            "P-1D",
+            "P2147483648W",
+            "PT1H1H1S",
+            "P1\u{10FFFF}",
+            "*/18446744073709551616 * * * *",
+            "0-59/4294967295 23-0 31 2 7",
+            "\u{3000}0 9 * * M\u{00d3}N",
+        ];
+        for value in values {
+            fuzz_parse(value.as_bytes());
+        }
+        fuzz_parse(&[0xff, 0xfe, b'F', b'R', b'E', b'Q', b'=', 0xc3]);
+    }
+}
+
+#[cfg(all(test, feature = "proptest"))]
+mod test_fuzzing_properties {
+    use super::fuzz_parse;
+    use proptest::prelude::*;
+
+    proptest! {
+        #[test]
+        fn arbitrary_bytes_do_not_panic(data in prop::collection::vec(any::<u8>(), 0..256)) {
+            fuzz_parse(&data);
+        }
+
+        #[test]
+        fn arbitrary_rules_do_not_panic(
+            value in "(FREQ=(DAILY|WEEKLY|MONTHLY|YEARLY)|INTERVAL=-?[0-9]{1,11}|BY[A-Z]{2,8}=[-+0-9A-Z,:]{0,12}|X-[A-Z]{1,10}=[0-9A-Z,:]{0,8}|DTSTART=[-+0-9TZ:]{0,22}|DURATION=P[0-9WDTHMS]{0,12}|;){0,10}"
+        ) {
+            fuzz_parse(value.as_bytes());
+        }
+    }
+}
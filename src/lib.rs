@@ -13,11 +13,11 @@
 //!
 //! - FREQ
 //!   - Defines the type of frequency (E.g. DAILY, WEEKLY, MONTHLY, etc)
-//! - INTERVAL
-//!     - Defines the interval of the frequency (E.g. every 2 days, every 3 months, etc)
 //! - DTSTART
 //!     - Defines the start date of the recurrence
 //!
+//! The `INTERVAL` of the frequency is optional, and defaults to 1 like in RFC 5545.
+//!
 //! Examples:
 //!
 //! ```rust
@@ -29,30 +29,229 @@
 //!
 //! let recurrence = Recurrence::from_str("FREQ=DAILY;INTERVAL=1;DTSTART=2023-01-01T12:00:00Z").unwrap();
 //!
-//! // You can then use the `Recurrence` as an iterator by looping over it or collecting the results into a `Vec`
+//! // You can then iterate over the `Recurrence` events by looping over them or collecting the results into a `Vec`
 //!
-//! for event in recurrence.clone().take(100) { // cloning to avoid moving the iterator
+//! for event in recurrence.iter().take(100) {
 //!     // ...
 //! }
 //! // or
-//! let events: Vec<DateTime<Utc>> = recurrence.take(100).collect();
+//! let events: Vec<DateTime<Utc>> = recurrence.iter().take(100).collect();
+//!
+//! ```
+//!
+//! > `Recurrence::iter()` returns an iterator that will yield all the dates that match the recurrence rules defined.
+//!
+//! > The `Recurrence` itself is not consumed, so it can be iterated as many times as needed.
+//!
+//! ### Strict parsing
+//!
+//! By default, parsing is lenient: malformed parts of the rule are ignored, and the unknown `KEY=VALUE`
+//! pairs of a recurrence, like `BYSETPOS` or `X-` parameters, are kept in `unknown_params` and serialized again.
+//! The values of the lists, like `BYDAY=SU,MO,MO`, are sorted and their duplicates dropped.
+//! Use `ParseOptions::strict()` to reject unknown keys, the date filters that the frequency doesn't support
+//! like `BYMONTHDAY` in daily rules, duplicated keys or values and trailing garbage:
+//!
+//! ```rust
+//! use rrules::{ParseOptions, Recurrence};
+//!
+//! let value = "FREQ=DAILY;INTERVAL=1;DTSTART=2023-01-01T12:00:00Z;BY";
+//! assert!(Recurrence::from_str_with(value, ParseOptions::strict()).is_err());
+//! ```
+//!
+//! Lenient parsing also accepts the rules pasted from user input, in lowercase and with spaces around
+//! the `;`, `=` and `,` separators, while strict parsing requires the canonical uppercase form:
+//!
+//! ```rust
+//! use std::str::FromStr;
+//! use rrules::{Frequency, ParseOptions};
+//!
+//! let value = "freq=weekly; interval=1; byday=mo,we";
+//! let frequency = Frequency::from_str(value).unwrap();
+//! assert_eq!(frequency.to_string(), "FREQ=WEEKLY;INTERVAL=1;BYDAY=MO,WE");
+//! assert!(Frequency::from_str_with(value, ParseOptions::strict()).is_err());
+//! ```
+//!
+//! Rules without a `DTSTART` are rejected, unless a default start date is given with
+//! `Recurrence::from_str_with_default_start()` or `ParseOptions::with_default_start()`:
+//!
+//! ```rust
+//! use chrono::Utc;
+//! use rrules::Recurrence;
+//!
+//! let now = Utc::now();
+//! let recurrence = Recurrence::from_str_with_default_start("FREQ=DAILY;INTERVAL=1", now).unwrap();
+//! assert_eq!(recurrence.start(), now);
+//! ```
+//!
+//!
+//! ### Parsing many rules
+//!
+//! `parse_lines()` parses a recurrence from every line of a reader, lazily, so big files of rules
+//! can be imported without loading them at once. Empty lines are skipped, and the errors carry the
+//! number of their line:
+//!
+//! ```rust
+//! use std::io::Cursor;
+//! use rrules::parse_lines;
+//!
+//! let rules = "FREQ=DAILY;INTERVAL=1;DTSTART=2023-01-01T12:00:00Z\nFREQ=WEEKLY;INTERVAL=0;DTSTART=2023-01-01T12:00:00Z\n";
+//! for result in parse_lines(Cursor::new(rules)) {
+//!     match result {
+//!         Ok(recurrence) => println!("{recurrence}"),
+//!         Err(error) => assert_eq!(error.line(), 2),
+//!     }
+//! }
+//! ```
+//!
+//!
+//! ### Parsing rule components
+//!
+//! `parse_byday_list`, `parse_bymonthday_list` and `parse_bytime_list` parse the value of a single key,
+//! E.g. to validate the fields of a rule builder as they are typed. The values are read as in a lenient
+//! rule string, and the span of the `ParseError` is a byte range of the value:
+//!
+//! ```rust
+//! use chrono::Weekday;
+//! use rrules::{parse_byday_list, parse_bytime_list, NthWeekday};
+//!
+//! let (by_day, nth_weekdays) = parse_byday_list("MO,WE,1FR").unwrap();
+//! assert_eq!(by_day, vec![Weekday::Mon, Weekday::Wed]);
+//! assert_eq!(nth_weekdays, vec![NthWeekday::new(Weekday::Fri, 1)]);
+//!
+//! let error = parse_bytime_list("09:00,25:00").unwrap_err();
+//! assert_eq!(error.span, 6..11);
+//! ```
+//!
+//! ### Property-based testing
+//!
+//! The `proptest` feature enables the `rrules::testing` module, with [proptest](https://docs.rs/proptest) strategies
+//! that generate valid frequencies and recurrences, like `arb_frequency()` and `arb_recurrence()`:
+//!
+//! ```toml
+//! [dev-dependencies]
+//! rrules = { version = "0.2", features = ["proptest"] }
+//! ```
+//!
+//! ### Testing with a clock
+//!
+//! The methods that depend on the current time, like `Recurrence::next_from_now()`, have a `_with` variant
+//! that reads it from a `Clock`. The `rrules::clock::MockClock` is stopped at the time it is set to, so
+//! the scheduling logic of an application can be tested deterministically:
+//!
+//! ```rust
+//! use std::str::FromStr;
+//! use chrono::{DateTime, Duration, Utc};
+//! use rrules::clock::MockClock;
+//! use rrules::Recurrence;
+//!
+//! let recurrence = Recurrence::from_str("FREQ=DAILY;INTERVAL=1;DTSTART=2023-01-01T09:00:00Z").unwrap();
+//! let clock = MockClock::new(DateTime::<Utc>::from_str("2023-01-01T12:00:00Z").unwrap());
+//! assert_eq!(recurrence.next_from_now_with(&clock), DateTime::<Utc>::from_str("2023-01-02T09:00:00Z").ok());
+//! clock.advance(Duration::days(1));
+//! assert_eq!(recurrence.next_from_now_with(&clock), DateTime::<Utc>::from_str("2023-01-03T09:00:00Z").ok());
+//! ```
+//!
+//! ### Scheduling tasks
+//!
+//! The `tokio` feature adds `Recurrence::into_stream()`, a stream that yields every event date
+//! once it is reached, so the recurrence can drive periodic tasks. `Recurrence::into_stream_with()`
+//! reads the time from a `Clock` instead:
+//!
+//! ```toml
+//! [dependencies]
+//! rrules = { version = "0.2", features = ["tokio"] }
+//! ```
+//!
+//! ### Parallel expansion
+//!
+//! The `rayon` feature adds `Recurrence::par_between()`, which splits a long range in smaller ones
+//! and computes their events in parallel, for bulk expansions of complex rules over decades:
+//!
+//! ```toml
+//! [dependencies]
+//! rrules = { version = "0.2", features = ["rayon"] }
+//! ```
+//!
+//! To expand many rules into a window, `Recurrence::expand_into` appends the events to a buffer given by the caller,
+//! up to a limit, and returns how many events were added and whether the limit left some of them out:
+//!
+//! ```rust
+//! use std::str::FromStr;
+//! use chrono::{DateTime, Utc};
+//! use rrules::Recurrence;
+//!
+//! let rules = ["FREQ=DAILY;INTERVAL=1;DTSTART=2023-01-01T09:00:00Z", "FREQ=HOURLY;INTERVAL=6;DTSTART=2023-01-01T00:00:00Z"];
+//! let from = DateTime::<Utc>::from_str("2023-03-01T00:00:00Z").unwrap();
+//! let to = DateTime::<Utc>::from_str("2023-03-08T00:00:00Z").unwrap();
+//! let mut events = Vec::with_capacity(1024);
+//! for rule in rules {
+//!     let stats = Recurrence::from_str(rule).unwrap().expand_into(from..to, &mut events, 100);
+//!     assert!(!stats.truncated);
+//! }
+//! assert_eq!(events.len(), 7 + 28);
+//! ```
+//!
+//! ### Tracing
+//!
+//! The `tracing` feature logs the errors of the parsing and the validation of the rules at the debug
+//! level, and the generated events and the skipped periods, like the months without the day of
+//! a `BYMONTHDAY`, at the trace level, with the [tracing](https://docs.rs/tracing) crate:
+//!
+//! ```toml
+//! [dependencies]
+//! rrules = { version = "0.2", features = ["tracing"] }
+//! ```
+//!
+//! ### no_std support
+//!
+//! The parser, the frequencies and the iterators only need `alloc`, so the crate can be used in
+//! `no_std` environments by disabling the default `std` feature. `Recurrence::next_from_now()`,
+//! `parse_lines()`, the `SystemClock` and `MockClock` clocks and the `tokio`, `rayon` and
+//! `proptest` features require `std`:
+//!
+//! ```toml
+//! [dependencies]
+//! rrules = { version = "0.2", default-features = false }
+//! ```
 //!
+//! ### WebAssembly
+//!
+//! The `wasm` feature exposes `parse_rrule(value)` and `occurrences_between(value, from, to)` to
+//! JavaScript through [wasm-bindgen](https://docs.rs/wasm-bindgen), exchanging the dates as RFC3339 strings:
+//!
+//! ```toml
+//! [dependencies]
+//! rrules = { version = "0.2", features = ["wasm"] }
 //! ```
 //!
-//! > The `Recurrence` struct is an iterator that will yield all the dates that match the recurrence rules defined.
+//! ### Command line tool
 //!
+//! The `cli` feature builds the `rrules` binary, to inspect rules from the shell. It prints the
+//! next event dates of a rule, validates rules given as arguments or read line by line from stdin,
+//! and converts rules to plain English descriptions and back:
+//!
+//! ```sh
+//! cargo install rrules --features cli
+//! rrules next "FREQ=WEEKLY;INTERVAL=2;BYDAY=MO,FR;DTSTART=2023-01-02T09:00:00Z" --count 5
+//! rrules validate --strict < rules.txt
+//! rrules describe "FREQ=MONTHLY;INTERVAL=1;BYDAY=2TU;DTSTART=2023-01-10T18:00:00Z"
+//! # every month on the 2nd Tuesday from 2023-01-10T18:00:00Z
+//! rrules parse every 2 weeks on Monday at 09:00 and Friday at 17:00 from 2023-01-02
+//! ```
 //!
 //! ### How to use it with structs definition
 //!
 //! To define a recurrence rule, start by creating the desired `frequency` rule definition:
 //!
 //! ```rust
+//! use std::num::NonZeroU32;
 //! use chrono::{Duration, Utc};
 //! use rrules::Frequency;
 //! use rrules::Recurrence;
 //!
 //! let daily = Frequency::Daily {
-//!     interval: 1,
+//!     interval: NonZeroU32::new(1).unwrap(),
+//!     by_day: vec![],
 //!     by_time: vec![],
 //! };
 //!
@@ -66,24 +265,373 @@
 //! );
 //! ```
 //!
-//! The `end` attribute of a `Recurrence` is optional, and if not specified, it will yield events until the `MAX_DATE`.
-//! > The `MAX_DATE` is defined as `9999-12-31T23:59:59Z`
+//! The end date of a `Recurrence` is optional, and if not specified, it will yield events indefinitely.
+//! Use `end()` to get it, and `is_infinite()` to know if the recurrence has no end date.
+//!
+//! To guard against endless iterations, `with_max_occurrences` and `with_max_horizon` stop the iteration
+//! after a number of events or a date, and `OccurrenceIter::is_truncated()` tells if a limit was reached.
+//! The limits are not part of the rules and are not serialized. The `COUNT` of a rule, kept in the `count`
+//! attribute, ends the recurrence instead, and counts the excluded dates of `EXDATE` like RFC 5545 does.
 //!
 //! The `duration` attribute of a `Recurrence` is optional, and if not specified, it will use the default as 0 seconds `Duration::seconds(0)`.
 //!
+//! The events of a `Recurrence` cannot last past the start of the next event. To allow overlapping occurrences,
+//! like a longer time blocked for every event, create it with `Recurrence::new_with` and
+//! `ValidationOptions::default().allow_overlapping_occurrences()`, or parse it with `ParseOptions::with_validation`.
+//! The frequency and the period are still validated.
+//!
+//! #### All-day recurrences
+//!
+//! For events without a time, like birthdays or holidays, use `Recurrence::all_day` with dates instead of instants,
+//! and iterate over the `NaiveDate` of every event with `dates()`:
+//!
+//! ```rust
+//! use std::num::NonZeroU32;
+//! use chrono::NaiveDate;
+//! use rrules::{Frequency, OverflowPolicy, Recurrence};
+//!
+//! let monthly = Frequency::Monthly {
+//!     interval: NonZeroU32::new(1).unwrap(),
+//!     by_month_day: vec![1, 15],
+//!     by_day: vec![],
+//!     nth_weekdays: vec![],
+//!     by_time: vec![],
+//!     overflow: OverflowPolicy::Skip,
+//! };
+//! let recurrence = Recurrence::all_day(monthly, NaiveDate::from_ymd_opt(2023, 1, 1).unwrap(), None).unwrap();
+//! let dates: Vec<NaiveDate> = recurrence.dates().take(2).collect();
+//! assert_eq!(dates[1], NaiveDate::from_ymd_opt(2023, 1, 15).unwrap());
+//! ```
+//!
+//! Date-only start dates, like `DTSTART=2023-01-01` or `DTSTART=20230101`, are parsed as all-day recurrences, which
+//! are written with plain dates like `DTSTART=2023-01-01`. The `DTSTART=VALUE=DATE:20230101` form written by previous
+//! versions is still accepted.
+//!
+//! #### Merging recurrences
+//!
+//! To build a combined agenda of many recurrences, `merge` iterates over the events of all of them in chronological order,
+//! along with the index of the recurrence of every event:
+//!
+//! ```rust
+//! use std::str::FromStr;
+//! use rrules::{merge, Recurrence};
+//!
+//! let standup = Recurrence::from_str("FREQ=DAILY;INTERVAL=1;DTSTART=2023-01-02T09:00:00Z").unwrap();
+//! let review = Recurrence::from_str("FREQ=WEEKLY;INTERVAL=1;DTSTART=2023-01-02T08:00:00Z").unwrap();
+//! let agenda: Vec<usize> = merge(vec![standup, review]).take(3).map(|(index, _)| index).collect();
+//! assert_eq!(agenda, vec![1, 0, 0]);
+//! ```
+//!
+//! To combine the events of two recurrences in a window, `a.intersect(&b, window)` keeps the events of both recurrences,
+//! and `a.difference(&b, window)` keeps the events of `a` that are not events of `b`, like every weekday except the holidays.
+//!
+//! #### Free time
+//!
+//! The `availability` module finds the free time slots of a window, between the events of many recurrences,
+//! where every event is busy for the `duration` of its recurrence:
+//!
+//! ```rust
+//! use std::str::FromStr;
+//! use chrono::{DateTime, Duration, Utc};
+//! use rrules::availability::free_slots;
+//! use rrules::Recurrence;
+//!
+//! let lunch = Recurrence::from_str("FREQ=DAILY;INTERVAL=1;BYTIME=12:00;DTSTART=2023-01-02T00:00:00Z;DURATION=PT1H").unwrap();
+//! let from = DateTime::<Utc>::from_str("2023-01-02T09:00:00Z").unwrap();
+//! let to = DateTime::<Utc>::from_str("2023-01-02T17:00:00Z").unwrap();
+//! let slots = free_slots(&[lunch], from..to, Duration::minutes(30));
+//! assert_eq!(slots.len(), 2);
+//! ```
+//!
+//! To know if a single instant is busy, `contains_instant` checks whether it falls within an event, from its date
+//! included to its end excluded, and `occurrence_covering` returns that event:
+//!
+//! ```rust
+//! use std::str::FromStr;
+//! use chrono::{DateTime, Utc};
+//! use rrules::Recurrence;
+//!
+//! let meeting = Recurrence::from_str("FREQ=WEEKLY;INTERVAL=1;DTSTART=2023-01-02T10:00:00Z;DURATION=PT1H").unwrap();
+//! let instant = DateTime::<Utc>::from_str("2023-01-09T10:30:00Z").unwrap();
+//! assert!(meeting.contains_instant(&instant));
+//! assert_eq!(meeting.occurrence_covering(&instant).unwrap().index, 1);
+//! ```
+//!
+//! #### Materialized recurrences
+//!
+//! `MaterializedRecurrence` keeps the events of a rolling horizon in memory, for backends that
+//! query the same rule many times. `contains`, `between` and `next_after` are binary searches
+//! over the cached events, the window is moved with `advance_to` computing only the new events,
+//! and the events are computed again when the recurrence is changed with `update`:
+//!
+//! ```rust
+//! use std::str::FromStr;
+//! use chrono::{DateTime, Duration, Utc};
+//! use rrules::{MaterializedRecurrence, Recurrence};
+//!
+//! let recurrence = Recurrence::from_str("FREQ=WEEKLY;INTERVAL=1;BYDAY=MO;DTSTART=2023-01-02T09:00:00Z").unwrap();
+//! let now = DateTime::<Utc>::from_str("2023-01-01T00:00:00Z").unwrap();
+//! let mut materialized = MaterializedRecurrence::new(recurrence, now, Duration::days(365));
+//! assert_eq!(materialized.events().len(), 52);
+//! materialized.advance_to(DateTime::<Utc>::from_str("2023-07-01T00:00:00Z").unwrap());
+//! assert!(materialized.contains(&DateTime::<Utc>::from_str("2024-01-01T09:00:00Z").unwrap()));
+//! ```
+//!
+//! #### Paging events
+//!
+//! `page` returns the events of a recurrence one page at a time, along with an `OccurrenceCursor` to get the
+//! next page, or `None` after the last one. The cursor keeps the last event of the page, so the next page is
+//! computed from it rather than from the start date, and can be written as an opaque string for web APIs:
+//!
+//! ```rust
+//! use std::str::FromStr;
+//! use rrules::{OccurrenceCursor, Recurrence};
+//!
+//! let weekly = Recurrence::from_str("FREQ=WEEKLY;INTERVAL=1;BYDAY=MO,WE;DTSTART=2023-01-02T09:00:00Z").unwrap();
+//! let (first_page, cursor) = weekly.page(None, 20);
+//! assert_eq!(first_page.len(), 20);
+//! let token = cursor.unwrap().to_string();
+//!
+//! let (second_page, _) = weekly.page(Some(OccurrenceCursor::from_str(&token).unwrap()), 20);
+//! assert_eq!(second_page[0], weekly.nth(20).unwrap());
+//! ```
+//!
+//! #### Archiving finished recurrences
+//!
+//! `is_expired` verifies if a recurrence has no events after a date, and `truncate_before` drops the
+//! events before a date, keeping the start date and skipping the previous events with `X-NOTBEFORE` so
+//! the intervals stay aligned. The excluded dates before the date are dropped:
+//!
+//! ```rust
+//! use std::str::FromStr;
+//! use chrono::{DateTime, Utc};
+//! use rrules::Recurrence;
+//!
+//! let every_2_weeks = Recurrence::from_str("FREQ=WEEKLY;INTERVAL=2;BYDAY=MO;DTSTART=2023-01-02T09:00:00Z").unwrap();
+//! let now = DateTime::<Utc>::from_str("2023-03-01T00:00:00Z").unwrap();
+//! assert!(!every_2_weeks.is_expired(&now));
+//! let current = every_2_weeks.truncate_before(&now).unwrap();
+//! assert_eq!(current.iter().next(), Some(DateTime::<Utc>::from_str("2023-03-13T09:00:00Z").unwrap()));
+//! ```
+//!
+//! #### Rescheduling recurrences
+//!
+//! `shift_by` moves every event of a recurrence by a duration, with its start and end dates, excluded
+//! dates and the times of its frequency, E.g. to move a series one hour later. It returns an error when
+//! the moved events don't follow the same rules, like the events on the 31st of each month moved a day
+//! later. `rebase_start` keeps the days and times of the frequency but counts its intervals from a new
+//! start date:
+//!
+//! ```rust
+//! use std::str::FromStr;
+//! use chrono::{DateTime, Duration, Utc};
+//! use rrules::Recurrence;
+//!
+//! let every_2_weeks = Recurrence::from_str("FREQ=WEEKLY;INTERVAL=2;BYDAY=MO;BYHOUR=9;DTSTART=2023-01-02T00:00:00Z").unwrap();
+//! let later = every_2_weeks.shift_by(Duration::hours(1)).unwrap();
+//! assert_eq!(later.iter().next(), Some(DateTime::<Utc>::from_str("2023-01-02T10:00:00Z").unwrap()));
+//!
+//! let other_weeks = every_2_weeks.rebase_start(DateTime::<Utc>::from_str("2023-01-09T00:00:00Z").unwrap()).unwrap();
+//! assert_eq!(other_weeks.iter().nth(1), Some(DateTime::<Utc>::from_str("2023-01-23T09:00:00Z").unwrap()));
+//! ```
+//!
+//! #### Holidays
+//!
+//! `skipping` iterates over the events of a recurrence while skipping the ones that land on a holiday,
+//! or moving them to the next or previous business day, that is neither a weekend nor a holiday.
+//! Holidays are given by any `HolidaySource`, like a list of dates or a closure:
+//!
+//! ```rust
+//! use std::str::FromStr;
+//! use chrono::{DateTime, Datelike, NaiveDate, Utc};
+//! use rrules::{Recurrence, ShiftPolicy};
+//!
+//! let payday = Recurrence::from_str("FREQ=MONTHLY;INTERVAL=1;BYMONTHDAY=25;DTSTART=2023-01-25T09:00:00Z").unwrap();
+//! let christmas = |date: NaiveDate| date.month() == 12 && date.day() == 25;
+//! let events: Vec<DateTime<Utc>> = payday.skipping(christmas, ShiftPolicy::PreviousBusinessDay).take(12).collect();
+//! assert_eq!(events[11], DateTime::<Utc>::from_str("2023-12-22T09:00:00Z").unwrap());
+//! ```
+//!
+//! #### Jitter
+//!
+//! `with_jitter` offsets every event of a recurrence by a reproducible amount of up to a maximum duration,
+//! before or after its date, to spread the load of many jobs scheduled at the same time. The offsets only
+//! depend on the seed and the date of the events, so every iteration yields the same dates, while the rules
+//! of the recurrence, like its end date, and its serialization keep using the dates without the offset:
+//!
+//! ```rust
+//! use std::str::FromStr;
+//! use chrono::{DateTime, Duration, Utc};
+//! use rrules::Recurrence;
+//!
+//! let backup = Recurrence::from_str("FREQ=DAILY;INTERVAL=1;DTSTART=2023-01-01T02:00:00Z").unwrap();
+//! let jittered = backup.clone().with_jitter(Duration::minutes(15), 1234);
+//! let first = jittered.iter().next().unwrap();
+//! assert!((first - backup.start).abs() <= Duration::minutes(15));
+//! assert_eq!(jittered.iter().next(), Some(first));
+//! assert_eq!(jittered.to_string(), backup.to_string());
+//! ```
+//!
+//! #### Calendar views
+//!
+//! `occurrences_by_month` groups the events of a year by their month, and `occurrences_in_week` returns the
+//! events of an ISO week, to render month and week views of a calendar:
+//!
+//! ```rust
+//! use std::str::FromStr;
+//! use chrono::Month;
+//! use rrules::Recurrence;
+//!
+//! let standup = Recurrence::from_str("FREQ=WEEKLY;INTERVAL=1;BYDAY=MO,WE;DTSTART=2023-01-02T09:00:00Z").unwrap();
+//! let by_month = standup.occurrences_by_month(2023);
+//! assert_eq!(by_month[&Month::March].len(), 9);
+//! assert_eq!(standup.occurrences_in_week(2023, 10).len(), 2);
+//! ```
+//!
+//! #### Calendar scales
+//!
+//! The `calendars` module defines the `CalendarScale` trait, the months and days of a calendar
+//! and the conversions of its dates from and to Gregorian dates, as the `RSCALE` key of RFC 7529.
+//! The rules use the `Gregorian` scale, and the `calendars` feature adds the `Hebrew` and
+//! `IslamicCivil` scales, to find the dates of religious holidays:
+//!
+//! ```toml
+//! [dependencies]
+//! rrules = { version = "0.2", features = ["calendars"] }
+//! ```
+//!
+//! #### Custom occurrence engines
+//!
+//! The events of a `Recurrence` are generated by an `OccurrenceEngine`, which `Frequency` implements
+//! for the RFC 5545 rules. Business rules that are not frequencies, E.g. a rotation of shifts, implement
+//! `next_event` from the start date or an event, `previous_event`, `contains` and `validate` with the intervals counted from the start
+//! date, and `Recurrence::from_engine` gives them the end date, the excluded dates, the iteration limits
+//! and the methods of the recurrences. Parsing and serializing are only available for the frequencies:
+//!
+//! ```rust
+//! use std::str::FromStr;
+//! use chrono::{DateTime, Duration, Utc};
+//! use rrules::{Frequency, OccurrenceEngine, Recurrence};
+//!
+//! let every_2_weeks = Frequency::from_str("FREQ=WEEKLY;INTERVAL=2;BYDAY=MO").unwrap();
+//! let anchor = DateTime::<Utc>::from_str("2023-01-02T09:00:00Z").unwrap();
+//! let date = DateTime::<Utc>::from_str("2023-02-01T00:00:00Z").unwrap();
+//! assert_eq!(
+//!     every_2_weeks.previous_event(&anchor, &date),
+//!     Some(DateTime::<Utc>::from_str("2023-01-30T09:00:00Z").unwrap())
+//! );
+//!
+//! let recurrence = Recurrence::from_engine(every_2_weeks, anchor, None, Some(Duration::hours(1))).unwrap();
+//! assert_eq!(recurrence.next_from(&date), Some(DateTime::<Utc>::from_str("2023-02-13T09:00:00Z").unwrap()));
+//! ```
+//!
+//! #### Date utilities
+//!
+//! The `date_utils` module exposes the date arithmetic of the frequencies: the `DateUtils` trait moves
+//! `NaiveDate`, `NaiveDateTime` and `DateTime<Utc>` dates by days, weeks, months and years, and to the
+//! weekdays around them, and `weekday_ordinal` tells which occurrence of its weekday in the month a date is.
+//! The methods return `None` instead of panicking when the day doesn't exist in the resulting month,
+//! unless `shift_months_with` clamps it to the last day or moves it to the next day with an `OverflowPolicy`:
+//!
+//! ```rust
+//! use chrono::{NaiveDate, Weekday};
+//! use rrules::date_utils::{weekday_ordinal, DateUtils};
+//! use rrules::OverflowPolicy;
+//!
+//! let date = NaiveDate::from_ymd_opt(2024, 1, 31).unwrap();
+//! assert_eq!(date.shift_months(1), None);
+//! assert_eq!(date.shift_months_with(1, OverflowPolicy::ClampToLastDay), NaiveDate::from_ymd_opt(2024, 2, 29));
+//! assert_eq!(date.next_weekday(Weekday::Mon), NaiveDate::from_ymd_opt(2024, 2, 5));
+//! assert_eq!(weekday_ordinal(&date), 5);
+//! ```
+//!
+//! #### ISO 8601 repeating intervals
+//!
+//! `Recurrence::from_iso8601_repeat` reads the repeating intervals of ISO 8601, with the number of events after the `R`,
+//! the start date and the period between the events, and `to_iso8601_repeat` writes them back. The period is repeated
+//! by the frequency with the largest unit that divides it, and the recurrences with filters, exdates or a duration of
+//! their events cannot be written as one. The periods of months and years end on the last day of the shorter months,
+//! like `X-OVERFLOW=CLAMP`:
+//!
+//! ```rust
+//! use rrules::{FreqKind, Recurrence};
+//!
+//! let recurrence = Recurrence::from_iso8601_repeat("R5/2023-01-01T09:00:00Z/P14D").unwrap();
+//! assert_eq!(recurrence.frequency().freq_kind(), FreqKind::Weekly);
+//! assert_eq!(recurrence.iter().count(), 5);
+//! assert_eq!(recurrence.to_iso8601_repeat().unwrap(), "R5/2023-01-01T09:00:00Z/P2W");
+//! ```
+//!
+//! #### Cron expressions
+//!
+//! `Frequency::from_cron` reads the cron expressions of the scheduled jobs, like `0 9 * * MON-FRI`, and
+//! `to_cron` writes the frequencies that cron can represent. Cron has no start date nor interval, so the
+//! frequencies must define their times and days, otherwise the `CronUnsupported` error lists the parts of
+//! the rule that cannot be written. The steps of the minutes and the hours, like `*/15`, are the intervals
+//! of minutely and hourly frequencies, which match cron when the recurrence starts at midnight:
+//!
+//! ```rust
+//! use std::str::FromStr;
+//! use rrules::Frequency;
+//!
+//! let weekdays = Frequency::from_cron("0 9 * * MON-FRI").unwrap();
+//! assert_eq!(weekdays, Frequency::from_str("FREQ=WEEKLY;INTERVAL=1;BYDAY=MO,TU,WE,TH,FR;BYHOUR=9").unwrap());
+//! assert_eq!(weekdays.to_cron().unwrap(), "0 9 * * 1-5");
+//!
+//! let every_two_weeks = Frequency::from_str("FREQ=WEEKLY;INTERVAL=2;BYDAY=MO").unwrap();
+//! assert_eq!(every_two_weeks.to_cron().unwrap_err().features, vec!["DTSTART", "INTERVAL"]);
+//!
+//! let every_quarter_hour = Frequency::from_cron("*/15 * * * *").unwrap();
+//! assert_eq!(every_quarter_hour.to_string(), "FREQ=MINUTELY;INTERVAL=15");
+//! assert_eq!(every_quarter_hour.to_cron().unwrap(), "*/15 * * * *");
+//! ```
+//!
 //! <span id="standards"></span>
 //! ## Attribute standards
 //!
 //! | Attribute  | Description                                                                                                    | Example                                                             |
 //! |------------|----------------------------------------------------------------------------------------------------------------|---------------------------------------------------------------------|
 //! | FREQ       | Defines the type of frequency (E.g. DAILY, WEEKLY, MONTHLY, etc)                                               | FREQ=DAILY                                                          |
-//! | INTERVAL   | Defines the interval of the frequency (E.g. every 2 days, every 3 months, etc)                                 | INTERVAL=2                                                          |
+//! | INTERVAL   | Defines the interval of the frequency (E.g. every 2 days, every 3 months, etc), 1 by default                   | INTERVAL=2                                                          |
 //! | DTSTART    | Defines the start date of the recurrence                                                                       | DTSTART=2023-01-01T12:00:00Z                                        |
 //! | DTEND      | Defines the end date of the recurrence                                                                         | DTEND=2023-01-01T12:00:00Z                                          |
 //! | DURATION   | Defines the duration of the recurrence                                                                         | DURATION=PT1H                                                       |
+//! | COUNT      | Defines the number of events of the recurrence, counting the excluded dates                                    | COUNT=10                                                            |
+//! | EXDATE     | Defines the event dates excluded from the recurrence                                                           | EXDATE=2023-01-02T12:00:00Z,2023-01-09T12:00:00Z                    |
+//! | X-NOTBEFORE | Defines the date of the first event yielded, skipping the events before it while the intervals are counted from DTSTART | X-NOTBEFORE=2023-03-01T12:00:00Z |
 //! | BYDAY      | Defines the days of the week that the recurrence will happen                                                   | BYDAY=MO,TU -> When FREQ=WEEKLY; BYDAY=1MO,3WE -> When FREQ=MONTHLY |
 //! | BYMONTHDAY | Defines the days of the month that the recurrence will happen                                                  | BYMONTHDAY=1,2,3,4, etc                                             |
 //! | BYMONTH    | Defines the months of the year that the recurrence will happen                                                 | BYMONTH=1,2,3,4,5,6,7,8,9,10,11,12                                  |
+//! | BYWEEKNO   | Defines the ISO week numbers of the year that the recurrence will happen, on weekdays without ordinals         | BYWEEKNO=20;BYDAY=MO -> When FREQ=YEARLY                            |
+//! | BYHOUR     | Defines the hours of the day that the recurrence will happen                                                   | BYHOUR=9,17                                                         |
+//! | BYMINUTE   | Defines the minutes of the hours that the recurrence will happen (defaults to 0 when BYHOUR is set in daily and longer frequencies) | BYMINUTE=0,30                                                       |
+//! | X-OVERFLOW | Defines what happens with month days missing in some months: SKIP (default), CLAMP to the last day or NEXT day | BYMONTHDAY=31;X-OVERFLOW=CLAMP -> When FREQ=MONTHLY or FREQ=YEARLY  |
+//! | SKIP       | Same as X-OVERFLOW with the values of RFC 7529: OMIT (default), BACKWARD to the last day or FORWARD to the next day. It also applies to the day of DTSTART without other filters, and RSCALE=GREGORIAN is accepted | RSCALE=GREGORIAN;SKIP=FORWARD -> When FREQ=MONTHLY or FREQ=YEARLY |
+//! | X-BUSINESS | Restricts a daily frequency to business days, from Monday to Friday, counting the interval in business days: TRUE or FALSE (default) | INTERVAL=2;X-BUSINESS=TRUE -> When FREQ=DAILY |
+//! | X-BYDAYTIME | Defines a time for each day of the week, instead of BYDAY | X-BYDAYTIME=MO09:00,TH14:00 -> When FREQ=WEEKLY |
+//! | X-BYHOURRANGE | Restricts a minutely or hourly frequency to the hours from the first, included, to the last, excluded | X-BYHOURRANGE=9-18 -> When FREQ=MINUTELY or FREQ=HOURLY |
+//!
+//! Recurrences are written by `to_string()` in a canonical form that is always parsed back into the same
+//! recurrence, whatever the order of the keys of the parsed rule. The keys of the frequency come first, in the
+//! order `FREQ`, `INTERVAL`, `BYMONTH`, `BYMONTHDAY`, `X-BYMONTHDATE`, `BYDAY`, `BYWEEKNO`, `BYHOUR`, `BYMINUTE`,
+//! `BYSECOND`, `BYTIME`, `X-BYDAYTIME`, `X-BYHOURRANGE`, `X-BUSINESS` and `X-OVERFLOW`, followed by
+//! `DTSTART`, `DTEND`, `DURATION`, `COUNT`, `EXDATE`, `X-NOTBEFORE` and the unknown parameters. Fractions of seconds of the dates and
+//! durations are kept:
+//!
+//! ```rust
+//! use std::str::FromStr;
+//! use rrules::Recurrence;
+//!
+//! let value = "DURATION=PT1.5S;BYDAY=FR,MO;DTSTART=2023-01-02T09:00:00.250Z;FREQ=WEEKLY";
+//! let recurrence = Recurrence::from_str(value).unwrap();
+//! let serialized = recurrence.to_string();
+//! assert_eq!(serialized, "FREQ=WEEKLY;INTERVAL=1;BYDAY=MO,FR;DTSTART=2023-01-02T09:00:00.250Z;DURATION=PT1.5S");
+//! assert_eq!(Recurrence::from_str(&serialized).unwrap().to_string(), serialized);
+//! ```
+//!
+//! The iCalendar content lines, like `DTSTART:20230102T090000Z` and `RRULE:FREQ=WEEKLY;BYDAY=MO` on separate lines, are
+//! parsed as well, and the recurrences parsed from them are written back as content lines by `to_string()`. Their
+//! `canonical_string()` keeps the form above.
 //!
 //!
 //! ## Supported recurrence rule types + examples
@@ -94,12 +642,18 @@
 //! - [hourly](#hourly)
 //! - [Daily](#daily)
 //! - [Weekly](#weekly)
+//!     - [By time](#weekly-by-time)
+//!     - [By day and time](#weekly-by-day-time)
 //! - [Monthly](#monthly)
 //!     - [By month day](#monthly-by-month-day)
 //!     - [By nth weekday](#monthly-by-day)
+//!     - [By month day and weekday](#monthly-by-month-day-and-day)
+//!     - [By time](#monthly-by-time)
+//!     - [Quarterly and semi-annual](#monthly-quarterly)
 //! - [Yearly](#yearly)
 //!     - [By day](#yearly-by-day)
 //!     - [By month day](#yearly-by-month-day)
+//!     - [By month](#yearly-by-month)
 //!
 //!
 //! <span id="secondly"></span>
@@ -140,6 +694,38 @@
 //!     "FREQ=HOURLY;INTERVAL=6;DTSTART=2023-01-01T12:00:00Z"
 //! ).unwrap();
 //! ```
+//!//!
+//! In secondly, minutely and hourly frequencies, `BYHOUR` and `BYMINUTE` limit the events to their hours and minutes as in
+//! RFC 5545, E.g. `BYHOUR=9` keeps every event from 09:00 to 09:59. `BYSECOND`, and `BYMINUTE` in hourly frequencies, set
+//! the time of the events in RFC 5545 instead, so they are rejected. `BYTIME` keeps the events at the given hours and
+//! minutes, and recurrences whose interval never reaches any of them from the start date are rejected:
+//!
+//! ```rust
+//! use std::str::FromStr;
+//! use chrono::{DateTime, Utc};
+//! use rrules::Recurrence;
+//!
+//! let mornings = Recurrence::from_str("FREQ=HOURLY;INTERVAL=1;BYHOUR=9;DTSTART=2023-01-01T08:30:00Z").unwrap();
+//! assert_eq!(mornings.iter().next(), Some(DateTime::<Utc>::from_str("2023-01-01T09:30:00Z").unwrap()));
+//! assert!(Recurrence::from_str("FREQ=HOURLY;INTERVAL=1;BYHOUR=9;BYMINUTE=0;DTSTART=2023-01-01T08:30:00Z").is_err());
+//! assert!(Recurrence::from_str("FREQ=HOURLY;INTERVAL=1;BYTIME=09:00;DTSTART=2023-01-01T08:30:00Z").is_err());
+//! ```
+//!
+//! When specifying `X-BYHOURRANGE`, minutely and hourly events only happen from the first hour of the range to the
+//! last one, excluded, with the interval still counted from the start date. E.g. every 30 minutes during working hours:
+//!
+//! ```rust
+//! use std::str::FromStr;
+//! use chrono::{DateTime, Utc};
+//! use rrules::Recurrence;
+//!
+//! let working_hours = Recurrence::from_str(
+//!     "FREQ=MINUTELY;INTERVAL=30;BYDAY=MO,TU,WE,TH,FR;X-BYHOURRANGE=9-18;DTSTART=2023-01-06T17:00:00Z"
+//! ).unwrap();
+//! let mut events = working_hours.iter().skip(1);
+//! assert_eq!(events.next(), Some(DateTime::<Utc>::from_str("2023-01-06T17:30:00Z").unwrap()));
+//! assert_eq!(events.next(), Some(DateTime::<Utc>::from_str("2023-01-09T09:00:00Z").unwrap()));
+//! ```
 //!
 //! <span id="daily"></span>
 //! ### Daily Frequencies
@@ -158,10 +744,26 @@
 //! ).unwrap();
 //!
 //! let every_other_day_at_12pm_and_16pm = Recurrence::from_str(
-//!     "FREQ=DAILY;INTERVAL=2;DTSTART=2023-01-01T00:00:00Z;BYTIME=12:00,16:00"
+//!     "FREQ=DAILY;INTERVAL=2;DTSTART=2023-01-01T00:00:00Z;BYHOUR=12,16;BYMINUTE=0"
 //! ).unwrap();
 //! ```
 //!
+//! When specifying `BYDAY`, only the days on the days of the week specified are events, with the interval still
+//! counted from the day of the start date:
+//!
+//! ```rust
+//! use std::str::FromStr;
+//! use chrono::{DateTime, Utc};
+//! use rrules::Recurrence;
+//!
+//! let every_other_monday_or_wednesday = Recurrence::from_str(
+//!     "FREQ=DAILY;INTERVAL=2;BYDAY=MO,WE;DTSTART=2023-01-02T09:00:00Z"
+//! ).unwrap();
+//! let mut events = every_other_monday_or_wednesday.iter().skip(1);
+//! assert_eq!(events.next(), Some(DateTime::<Utc>::from_str("2023-01-04T09:00:00Z").unwrap()));
+//! assert_eq!(events.next(), Some(DateTime::<Utc>::from_str("2023-01-16T09:00:00Z").unwrap()));
+//! ```
+//!
 //! <span id="weekly"></span>
 //! ### Weekly Frequencies
 //! Represents the rules for a recurrence that happens x times every x weeks.
@@ -178,6 +780,45 @@
 //!     "FREQ=WEEKLY;INTERVAL=1;DTSTART=2023-01-01T12:00:00Z;BYDAY=MO,TU"
 //! ).unwrap();
 //! ```
+//!
+//! <span id="weekly-by-time"></span>
+//! #### Weekly by time
+//!
+//! When specifying `BYTIME` or `BYHOUR` and `BYMINUTE`, the events happen at every time on each of the days,
+//! or on the weekday of the start date without `BYDAY`. E.g. Mondays and Wednesdays at 09:00 and 17:00:
+//!
+//! ```rust
+//! use std::str::FromStr;
+//! use chrono::{DateTime, Utc};
+//! use rrules::Recurrence;
+//!
+//! let twice_on_mondays_and_wednesdays = Recurrence::from_str(
+//!     "FREQ=WEEKLY;INTERVAL=1;DTSTART=2023-01-02T00:00:00Z;BYDAY=MO,WE;BYTIME=09:00,17:00"
+//! ).unwrap();
+//! let events: Vec<DateTime<Utc>> = twice_on_mondays_and_wednesdays.iter().take(3).collect();
+//! assert_eq!(events[0], DateTime::<Utc>::from_str("2023-01-02T09:00:00Z").unwrap());
+//! assert_eq!(events[1], DateTime::<Utc>::from_str("2023-01-02T17:00:00Z").unwrap());
+//! assert_eq!(events[2], DateTime::<Utc>::from_str("2023-01-04T09:00:00Z").unwrap());
+//! ```
+//!
+//! <span id="weekly-by-day-time"></span>
+//! #### Weekly by day and time
+//!
+//! When specifying `X-BYDAYTIME`, every day of the week has its own time, instead of the time of the start date.
+//! E.g. Mondays at 09:00 and Thursdays at 14:00:
+//!
+//! ```rust
+//! use std::str::FromStr;
+//! use chrono::{DateTime, Utc};
+//! use rrules::Recurrence;
+//!
+//! let mondays_and_thursdays = Recurrence::from_str(
+//!     "FREQ=WEEKLY;INTERVAL=1;DTSTART=2023-01-02T00:00:00Z;X-BYDAYTIME=MO09:00,TH14:00"
+//! ).unwrap();
+//! let events: Vec<DateTime<Utc>> = mondays_and_thursdays.iter().take(2).collect();
+//! assert_eq!(events[0], DateTime::<Utc>::from_str("2023-01-02T09:00:00Z").unwrap());
+//! assert_eq!(events[1], DateTime::<Utc>::from_str("2023-01-05T14:00:00Z").unwrap());
+//! ```
 //! <span id="monthly"></span>
 //! ### Monthly Frequencies
 //! Represents the rules for a recurrence that happens x times every x months.
@@ -225,6 +866,77 @@
 //! ).unwrap();
 //! ```
 //!
+//! The ordinals of the weekdays go from 1 to 5 in the months, and from 1 to 53 in yearly frequencies, or 1 to 5
+//! when they are restricted to some months with `BYMONTH`. Other ordinals are rejected when parsing, and by
+//! `NthWeekday::try_new` for the weekdays built in code.
+//!
+//! To repeat on the same nth weekday of the month as the start date, like the "monthly on the 3rd Tuesday"
+//! option of calendar apps, `Frequency::monthly_same_weekday_as_start` computes it from the start date.
+//!
+//! <span id="monthly-by-month-day-and-day"></span>
+//! #### Monthly by month day and weekday
+//!
+//! When specifying both `BYMONTHDAY` and `BYDAY`, it will only yield the days of the month that are also on
+//! the days of the week specified, like every Friday the 13th. Without `BYMONTHDAY`, the days of the week
+//! without ordinals yield every one of those days in the month.
+//!
+//! ```rust
+//! use std::str::FromStr;
+//! use chrono::{DateTime, Utc};
+//! use rrules::Recurrence;
+//! let friday_the_13th = Recurrence::from_str(
+//!     "FREQ=MONTHLY;INTERVAL=1;DTSTART=2023-01-01T12:00:00Z;BYMONTHDAY=13;BYDAY=FR"
+//! ).unwrap();
+//! let mut events = friday_the_13th.iter();
+//! assert_eq!(events.next(), Some(DateTime::<Utc>::from_str("2023-01-13T12:00:00Z").unwrap()));
+//! assert_eq!(events.next(), Some(DateTime::<Utc>::from_str("2023-10-13T12:00:00Z").unwrap()));
+//!
+//! let every_tuesday = Recurrence::from_str(
+//!     "FREQ=MONTHLY;INTERVAL=1;DTSTART=2023-01-01T12:00:00Z;BYDAY=TU"
+//! ).unwrap();
+//! ```
+//!
+//! <span id="monthly-by-time"></span>
+//! #### Monthly by time
+//!
+//! When specifying `BYHOUR` and `BYMINUTE`, or `BYTIME`, the events happen at every time on each of the days,
+//! instead of the time of the start date. Yearly frequencies accept the same times.
+//! E.g. the 1st of every month at 08:00 and 20:00:
+//!
+//! ```rust
+//! use std::str::FromStr;
+//! use chrono::{DateTime, Utc};
+//! use rrules::Recurrence;
+//! let twice_on_the_1st = Recurrence::from_str(
+//!     "FREQ=MONTHLY;INTERVAL=1;DTSTART=2023-01-01T00:00:00Z;BYMONTHDAY=1;BYHOUR=8,20"
+//! ).unwrap();
+//! let mut events = twice_on_the_1st.iter();
+//! assert_eq!(events.next(), Some(DateTime::<Utc>::from_str("2023-01-01T08:00:00Z").unwrap()));
+//! assert_eq!(events.next(), Some(DateTime::<Utc>::from_str("2023-01-01T20:00:00Z").unwrap()));
+//! assert_eq!(events.next(), Some(DateTime::<Utc>::from_str("2023-02-01T08:00:00Z").unwrap()));
+//! ```
+//!
+//! <span id="monthly-quarterly"></span>
+//! #### Quarterly and semi-annual
+//!
+//! Quarterly rules are monthly rules with an interval of 3 months, and semi-annual rules with an interval of
+//! 6 months. `Frequency::quarterly` and `Frequency::semi_annual` build them from the days of the month and
+//! nth weekdays. The quarters are counted from the month of the start date, so the recurrences starting in
+//! January, April, July or October follow the calendar quarters. The command line tool describes them
+//! as `every quarter` and `every half year`.
+//!
+//! ```rust
+//! use std::str::FromStr;
+//! use chrono::{DateTime, Utc};
+//! use rrules::{Frequency, Recurrence};
+//! let start = DateTime::<Utc>::from_str("2023-01-01T12:00:00Z").unwrap();
+//! let quarterly = Recurrence::new(Frequency::quarterly(vec![15], vec![]), start, None, None).unwrap();
+//! assert_eq!(quarterly.to_string(), "FREQ=MONTHLY;INTERVAL=3;BYMONTHDAY=15;DTSTART=2023-01-01T12:00:00Z");
+//! let mut events = quarterly.iter();
+//! assert_eq!(events.next(), Some(DateTime::<Utc>::from_str("2023-01-15T12:00:00Z").unwrap()));
+//! assert_eq!(events.next(), Some(DateTime::<Utc>::from_str("2023-04-15T12:00:00Z").unwrap()));
+//! ```
+//!
 //! <span id="yearly"></span>
 //! ### Yearly Frequencies
 //! Represents the rules for a recurrence that happens x times every x years.
@@ -250,13 +962,70 @@
 //!     "FREQ=YEARLY;INTERVAL=1;DTSTART=2023-01-01T12:00:00Z;BYMONTH=1;BYMONTHDAY=15"
 //! ).unwrap();
 //! ```
+//!
+//! The dates must exist in their month, at least in leap years like February 29th, unless `X-OVERFLOW` moves the
+//! missing days to another date. The combinations of `BYMONTH` and `BYMONTHDAY` that never exist are dropped, and
+//! `MonthlyDate::new` validates the dates built in code:
+//!
+//! ```rust
+//! use std::str::FromStr;
+//! use chrono::Month;
+//! use rrules::{Frequency, MonthlyDate};
+//!
+//! assert!(Frequency::from_str("FREQ=YEARLY;INTERVAL=1;X-BYMONTHDATE=FEB30").is_err());
+//! assert!(Frequency::from_str("FREQ=YEARLY;INTERVAL=1;X-BYMONTHDATE=FEB30;X-OVERFLOW=CLAMP").is_ok());
+//! assert!(MonthlyDate::new(Month::April, 31).is_err());
+//! ```
+//!
+//! `Frequency::yearly_same_date_as_start` and `Frequency::monthly_same_day_as_start` build these rules from the
+//! month and day of the start date, like the anniversaries of an event.
+//!
+//! <span id="yearly-by-month"></span>
+//! #### Yearly by month
+//!
+//! When specifying `BYMONTH` alone, it will yield the day of the start date in every month specified.
+//! Together with `BYDAY`, the nth weekdays are counted within each month, E.g. Thanksgiving:
+//!
+//! ```rust
+//! use std::str::FromStr;
+//! use rrules::Recurrence;
+//! let thanksgiving = Recurrence::from_str(
+//!     "FREQ=YEARLY;INTERVAL=1;DTSTART=2023-01-01T12:00:00Z;BYMONTH=11;BYDAY=4TH"
+//! ).unwrap();
+//! ```
+
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
 
+extern crate alloc;
 extern crate core;
 
+pub mod availability;
+pub mod calendars;
+pub mod clock;
+pub mod codes;
+pub mod date_utils;
 pub mod frequencies;
+#[doc(hidden)]
+pub mod fuzzing;
 pub mod recurrences;
+#[cfg(feature = "tokio")]
+pub mod scheduler;
+#[cfg(test)]
+pub(crate) mod test_utils;
+#[cfg(feature = "proptest")]
+pub mod testing;
+mod trace;
 mod utils;
+#[cfg(feature = "wasm")]
+pub mod wasm;
 pub use frequencies::*;
-pub use recurrences::*;
-pub use frequencies::{Frequency, MonthlyDate, NthWeekday, Time};
-pub use recurrences::{Recurrence, RecurrenceInvalid};
+pub use frequencies::{
+    FreqKind, Frequency, MonthlyDate, NthWeekday, OverflowPolicy, ParseOptions, Time, TimePolicy,
+};
+pub use recurrences::{
+    merge, DateIter, DetailedIter, ExpandStats, HolidaySource, Jitter, MaterializedRecurrence,
+    Merge, Occurrence, OccurrenceCursor, OccurrenceIter, Recurrence, RecurrenceErrorKind,
+    RecurrenceInvalid, ShiftPolicy, Skipping, ValidationOptions,
+};
+#[cfg(feature = "std")]
+pub use recurrences::{parse_lines, LineError, ParseLines};
@@ -0,0 +1,192 @@
+use crate::recurrences::serializers::invalid;
+use crate::recurrences::{Recurrence, RecurrenceInvalid};
+use alloc::format;
+use alloc::vec::Vec;
+use chrono::{DateTime, Utc};
+use core::fmt::{Display, Formatter};
+use core::str::FromStr;
+
+/// The position of a page of events of a recurrence, to get the following page.
+///
+/// It keeps the last event of the page and the number of events until it, so the next page
+/// starts right after it. It is written as an opaque string with `to_string()`, E.g. to be
+/// given to the clients of a web API, and read again with `from_str`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct OccurrenceCursor {
+    /// The last event of the page, before its jitter
+    last: DateTime<Utc>,
+
+    /// The number of events until the last one, included
+    count: usize,
+}
+
+impl Display for OccurrenceCursor {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "{:x}.{:x}.{:x}",
+            self.last.timestamp(),
+            self.last.timestamp_subsec_nanos(),
+            self.count
+        )
+    }
+}
+
+impl FromStr for OccurrenceCursor {
+    type Err = RecurrenceInvalid;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let cursor = match value.split('.').collect::<Vec<&str>>()[..] {
+            [seconds, nanos, count] => u64::from_str_radix(seconds, 16)
+                .ok()
+                .zip(u32::from_str_radix(nanos, 16).ok())
+                .and_then(|(seconds, nanos)| DateTime::from_timestamp(seconds as i64, nanos))
+                .zip(usize::from_str_radix(count, 16).ok())
+                .map(|(last, count)| OccurrenceCursor { last, count }),
+            _ => None,
+        };
+        cursor.ok_or_else(|| {
+            RecurrenceInvalid::new(format!("Invalid cursor: {value}")).with_kind(invalid(value))
+        })
+    }
+}
+
+impl Recurrence {
+    /// Returns a page of up to `page_size` events, after the page of the cursor or from the
+    /// first event without it, along with the cursor of the next page, or None when there are
+    /// no more events.
+    ///
+    /// The next page is computed from the last event of the cursor, without going through the
+    /// previous events. The cursor must come from the same recurrence. An empty page size
+    /// returns no events and the same cursor.
+    /// Examples:
+    /// ```
+    /// use std::str::FromStr;
+    /// use chrono::{DateTime, Utc};
+    /// use rrules::{OccurrenceCursor, Recurrence};
+    ///
+    /// let recurrence = Recurrence::from_str("FREQ=DAILY;INTERVAL=1;DTSTART=2023-01-01T09:00:00Z;DTEND=2023-01-05T09:00:00Z").unwrap();
+    /// let (first_page, cursor) = recurrence.page(None, 3);
+    /// assert_eq!(first_page.len(), 3);
+    ///
+    /// // The cursor is given to the client as a string, and read back on its next request
+    /// let cursor = OccurrenceCursor::from_str(&cursor.unwrap().to_string()).unwrap();
+    /// let (second_page, cursor) = recurrence.page(Some(cursor), 3);
+    /// assert_eq!(second_page, vec![
+    ///     DateTime::<Utc>::from_str("2023-01-04T09:00:00Z").unwrap(),
+    ///     DateTime::<Utc>::from_str("2023-01-05T09:00:00Z").unwrap(),
+    /// ]);
+    /// assert_eq!(cursor, None);
+    /// ```
+    pub fn page(
+        &self,
+        cursor: Option<OccurrenceCursor>,
+        page_size: usize,
+    ) -> (Vec<DateTime<Utc>>, Option<OccurrenceCursor>) {
+        if page_size == 0 {
+            return (Vec::new(), cursor);
+        }
+        // The cursor keeps the dates of the rules, the jitter is applied to the returned events
+        let rules = self.unjittered();
+        let mut events = rules.iter();
+        let mut count = 0;
+        if let Some(cursor) = cursor {
+            events.resume_after(&cursor.last, cursor.count);
+            count = cursor.count;
+        }
+        let page: Vec<DateTime<Utc>> = events.by_ref().take(page_size).collect();
+        count += page.len();
+        let next_cursor = match page.last() {
+            Some(last) if page.len() == page_size && events.next().is_some() => {
+                Some(OccurrenceCursor { last: *last, count })
+            }
+            _ => None,
+        };
+        let page = page.into_iter().map(|event| self.jittered(event)).collect();
+        (page, next_cursor)
+    }
+}
+
+#[cfg(test)]
+mod test_cursor {
+    use crate::test_utils::date;
+    use crate::{OccurrenceCursor, Recurrence, RecurrenceErrorKind};
+    use chrono::{DateTime, Duration, Utc};
+    use std::str::FromStr;
+
+    /// Returns the events of every page, resuming from the string of their cursors.
+    fn all_pages(recurrence: &Recurrence, page_size: usize) -> Vec<Vec<DateTime<Utc>>> {
+        let mut pages = vec![];
+        let mut cursor = None;
+        loop {
+            let (page, next_cursor) = recurrence.page(cursor, page_size);
+            pages.push(page);
+            match next_cursor {
+                Some(next_cursor) => {
+                    cursor = Some(OccurrenceCursor::from_str(&next_cursor.to_string()).unwrap())
+                }
+                None => return pages,
+            }
+        }
+    }
+
+    #[test]
+    fn pages_yield_every_event_once() {
+        let recurrence = Recurrence::from_str(
+            "FREQ=WEEKLY;INTERVAL=2;BYDAY=MO,TH;DTSTART=2023-01-05T09:30:00Z;DTEND=2023-06-01T00:00:00Z",
+        )
+        .unwrap()
+        .with_exdates(vec![date("2023-02-02T09:30:00Z")]);
+        let events: Vec<DateTime<Utc>> = recurrence.iter().collect();
+        for page_size in [1, 3, events.len(), events.len() + 1] {
+            let pages = all_pages(&recurrence, page_size);
+            assert!(pages.iter().all(|page| page.len() <= page_size));
+            assert_eq!(pages.concat(), events, "{page_size}");
+        }
+        assert_eq!(all_pages(&recurrence, events.len()).len(), 1);
+    }
+
+    #[test]
+    fn pages_respect_the_limits_and_the_jitter() {
+        let recurrence =
+            Recurrence::from_str("FREQ=HOURLY;INTERVAL=3;DTSTART=2023-01-01T00:00:00Z")
+                .unwrap()
+                .with_max_occurrences(10)
+                .with_jitter(Duration::minutes(30), 9);
+        let pages = all_pages(&recurrence, 4);
+        assert_eq!(pages.len(), 3);
+        assert_eq!(pages.concat(), recurrence.iter().collect::<Vec<_>>());
+
+        let (first_page, cursor) = recurrence.page(None, 0);
+        assert!(first_page.is_empty());
+        assert_eq!(cursor, None);
+    }
+
+    #[test]
+    fn resumes_far_from_the_start() {
+        let recurrence =
+            Recurrence::from_str("FREQ=SECONDLY;INTERVAL=1;DTSTART=2023-01-01T00:00:00Z").unwrap();
+        let cursor = OccurrenceCursor::from_str(&format!(
+            "{:x}.0.{:x}",
+            date("2100-01-01T00:00:00Z").timestamp(),
+            1_000_000_000_000usize
+        ))
+        .unwrap();
+        let (page, _) = recurrence.page(Some(cursor), 2);
+        assert_eq!(
+            page,
+            vec![date("2100-01-01T00:00:01Z"), date("2100-01-01T00:00:02Z")]
+        );
+    }
+
+    #[test]
+    fn invalid_cursors() {
+        for value in ["", "1.2", "x.0.1", "1.0.-1", "1.77359400.1", "1.0.1.0"] {
+            let error = OccurrenceCursor::from_str(value).unwrap_err();
+            assert!(matches!(
+                error.kind,
+                RecurrenceErrorKind::InvalidValue { .. }
+            ));
+        }
+    }
+}
@@ -1,12 +1,264 @@
-use std::fmt::{Display, Formatter};
+use crate::frequencies::{InvalidFrequency, NthWeekday, Time};
+use alloc::boxed::Box;
+use alloc::format;
+use alloc::string::String;
+use chrono::{DateTime, Duration, Utc, Weekday};
+use core::error::Error;
+use core::fmt::{Display, Formatter};
 
+/// The kind of problem that makes a recurrence invalid, with the values involved.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum RecurrenceErrorKind {
+    /// The frequency is invalid, the `source` of the error has the details
+    InvalidFrequency,
+    /// The start date is not before the end date
+    InvalidPeriod {
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    },
+    /// The events last longer than the interval of the frequency
+    DurationTooLong { allowed: Duration, actual: Duration },
+    /// The events at the first time of the day last until after the second one
+    OverlappingTimes { first: Time, second: Time },
+    /// The events on the first weekday last until after the second one
+    OverlappingWeekdays { first: Weekday, second: Weekday },
+    /// The events on the first day of the month last until after the second one
+    OverlappingMonthDays { first: i32, second: i32 },
+    /// The events on the first nth weekday last until after the second one
+    OverlappingNthWeekdays {
+        first: NthWeekday,
+        second: NthWeekday,
+    },
+    /// All-day recurrences cannot have events more often than daily
+    AllDayTooFrequent,
+    /// All-day recurrences cannot define times of the day
+    AllDayWithTimes,
+    /// A required part of the rule is missing, like the `DTSTART`
+    Missing { name: String },
+    /// A value of the rule, like a date or a duration, cannot be parsed
+    InvalidValue { value: String },
+    /// A date or a duration is out of the supported range
+    OutOfRange,
+    /// There are no events where they are required
+    NoEvents,
+    /// Any other problem, described by the message of the error
+    Other,
+}
+
+impl RecurrenceErrorKind {
+    /// Returns a stable machine-readable code of the kind, like `duration_too_long`.
+    pub fn code(&self) -> &'static str {
+        match self {
+            RecurrenceErrorKind::InvalidFrequency => "invalid_frequency",
+            RecurrenceErrorKind::InvalidPeriod { .. } => "invalid_period",
+            RecurrenceErrorKind::DurationTooLong { .. } => "duration_too_long",
+            RecurrenceErrorKind::OverlappingTimes { .. } => "overlapping_times",
+            RecurrenceErrorKind::OverlappingWeekdays { .. } => "overlapping_weekdays",
+            RecurrenceErrorKind::OverlappingMonthDays { .. } => "overlapping_month_days",
+            RecurrenceErrorKind::OverlappingNthWeekdays { .. } => "overlapping_nth_weekdays",
+            RecurrenceErrorKind::AllDayTooFrequent => "all_day_too_frequent",
+            RecurrenceErrorKind::AllDayWithTimes => "all_day_with_times",
+            RecurrenceErrorKind::Missing { .. } => "missing",
+            RecurrenceErrorKind::InvalidValue { .. } => "invalid_value",
+            RecurrenceErrorKind::OutOfRange => "out_of_range",
+            RecurrenceErrorKind::NoEvents => "no_events",
+            RecurrenceErrorKind::Other => "other",
+        }
+    }
+}
+
+/// The error returned when a recurrence is invalid.
+///
+/// The `message` is meant for humans, while the `kind` carries the values involved so
+/// applications can tell the problems apart.
+///
+/// Examples:
+/// ```
+/// use std::str::FromStr;
+/// use chrono::Duration;
+/// use rrules::{Recurrence, RecurrenceErrorKind};
+///
+/// let error = Recurrence::from_str("FREQ=HOURLY;INTERVAL=1;DTSTART=2023-01-01T00:00:00Z;DURATION=PT2H").unwrap_err();
+/// assert_eq!(error.to_string(), "Duration must be smaller than interval");
+/// assert_eq!(error.kind.code(), "duration_too_long");
+/// assert_eq!(
+///     error.kind,
+///     RecurrenceErrorKind::DurationTooLong { allowed: Duration::hours(1), actual: Duration::hours(2) }
+/// );
+/// ```
 #[derive(Debug)]
+#[non_exhaustive]
 pub struct RecurrenceInvalid {
     pub message: String,
+
+    /// The kind of problem, `RecurrenceErrorKind::Other` when it is not known
+    pub kind: RecurrenceErrorKind,
+
+    /// Frequency error that caused the recurrence to be invalid, if any
+    pub source: Option<Box<InvalidFrequency>>,
+}
+
+impl RecurrenceInvalid {
+    pub fn new(message: impl Into<String>) -> Self {
+        RecurrenceInvalid {
+            message: message.into(),
+            kind: RecurrenceErrorKind::Other,
+            source: None,
+        }
+    }
+
+    /// Creates an error caused by an invalid frequency.
+    pub fn with_source(message: impl Into<String>, source: InvalidFrequency) -> Self {
+        RecurrenceInvalid {
+            message: message.into(),
+            kind: RecurrenceErrorKind::InvalidFrequency,
+            source: Some(Box::new(source)),
+        }
+    }
+
+    /// Sets the kind of problem of the error.
+    pub fn with_kind(mut self, kind: RecurrenceErrorKind) -> Self {
+        self.kind = kind;
+        self
+    }
 }
 
 impl Display for RecurrenceInvalid {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         write!(f, "{}", self.message)
     }
 }
+
+impl Error for RecurrenceInvalid {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        self.source.as_deref().map(|e| e as &(dyn Error + 'static))
+    }
+}
+
+impl From<InvalidFrequency> for RecurrenceInvalid {
+    fn from(error: InvalidFrequency) -> Self {
+        RecurrenceInvalid::with_source(format!("Invalid frequency: {error}"), error)
+    }
+}
+
+#[cfg(test)]
+mod test_errors {
+    use crate::{Frequency, ParseErrorKind, Recurrence, RecurrenceErrorKind, Time};
+    use chrono::{DateTime, Duration, NaiveDate, Utc, Weekday};
+    use std::error::Error;
+    use std::num::NonZeroU32;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_source_chaining() {
+        let value = "FREQ=DAILY;INTERVAL=1;DTSTART=2023-01-01T00:00:00Z;BYHOUR=25";
+        let error = Recurrence::from_str(value).unwrap_err();
+        let source = error.source().unwrap();
+        assert_eq!(
+            source.to_string(),
+            "Invalid format: Invalid value \"25\", expected an hour between 0 and 23 at 58..60"
+        );
+        let parse_error = source.source().unwrap();
+        let parse_error = parse_error.downcast_ref::<crate::ParseError>().unwrap();
+        assert_eq!(parse_error.kind, ParseErrorKind::InvalidValue);
+    }
+
+    #[test]
+    fn test_boxed_error() {
+        fn parse(value: &str) -> Result<Recurrence, Box<dyn Error>> {
+            Ok(Recurrence::from_str(value)?)
+        }
+        assert!(parse("FREQ=DAILY;INTERVAL=0;DTSTART=2023-01-01T00:00:00Z").is_err());
+        assert!(parse("FREQ=DAILY;INTERVAL=1;DTSTART=2023-01-01T00:00:00Z").is_ok());
+    }
+
+    #[test]
+    fn test_error_kinds() {
+        let start = DateTime::<Utc>::from_str("2023-01-02T00:00:00Z").unwrap();
+        let error = Recurrence::from_str(
+            "FREQ=DAILY;INTERVAL=1;DTSTART=2023-01-02T00:00:00Z;DTEND=2023-01-01T00:00:00Z",
+        )
+        .unwrap_err();
+        assert_eq!(error.to_string(), "Start date must be before end date");
+        assert_eq!(
+            error.kind,
+            RecurrenceErrorKind::InvalidPeriod {
+                start,
+                end: start - Duration::days(1),
+            }
+        );
+        assert_eq!(error.kind.code(), "invalid_period");
+
+        let error = Recurrence::from_str(
+            "FREQ=DAILY;INTERVAL=1;BYTIME=09:00,10:00;DTSTART=2023-01-01T00:00:00Z;DURATION=PT2H",
+        )
+        .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "There is an overlap of events with the current times and duration defined."
+        );
+        assert_eq!(
+            error.kind,
+            RecurrenceErrorKind::OverlappingTimes {
+                first: Time::from_str("09:00").unwrap(),
+                second: Time::from_str("10:00").unwrap(),
+            }
+        );
+
+        let error = Recurrence::from_str(
+            "FREQ=WEEKLY;INTERVAL=1;BYDAY=MO,TU;DTSTART=2023-01-02T00:00:00Z;DURATION=P2D",
+        )
+        .unwrap_err();
+        assert_eq!(
+            error.kind,
+            RecurrenceErrorKind::OverlappingWeekdays {
+                first: Weekday::Mon,
+                second: Weekday::Tue,
+            }
+        );
+
+        let frequency = Frequency::Hourly {
+            interval: NonZeroU32::new(1).unwrap(),
+            by_day: vec![],
+            by_time: vec![],
+            by_hour_range: None,
+        };
+        let day = NaiveDate::from_ymd_opt(2023, 1, 1).unwrap();
+        let error = Recurrence::all_day(frequency, day, None).unwrap_err();
+        assert_eq!(error.kind, RecurrenceErrorKind::AllDayTooFrequent);
+        let frequency = Frequency::Daily {
+            interval: NonZeroU32::new(1).unwrap(),
+            by_day: vec![],
+            by_time: vec![Time::from_str("09:00").unwrap()],
+        };
+        let error = Recurrence::all_day(frequency, day, None).unwrap_err();
+        assert_eq!(error.kind, RecurrenceErrorKind::AllDayWithTimes);
+    }
+
+    #[test]
+    fn test_parse_error_kinds() {
+        let error = Recurrence::from_str("FREQ=DAILY;INTERVAL=1").unwrap_err();
+        assert_eq!(error.to_string(), "No DTSTART found");
+        assert_eq!(
+            error.kind,
+            RecurrenceErrorKind::Missing {
+                name: "DTSTART".to_string()
+            }
+        );
+        let error =
+            Recurrence::from_str("FREQ=DAILY;INTERVAL=1;DTSTART=2023-01-01T00:00:00Z;DURATION=P1")
+                .unwrap_err();
+        assert_eq!(error.to_string(), "Invalid duration: P1");
+        assert_eq!(
+            error.kind,
+            RecurrenceErrorKind::InvalidValue {
+                value: "P1".to_string()
+            }
+        );
+        let error =
+            Recurrence::from_str("FREQ=DAILY;INTERVAL=0;DTSTART=2023-01-01T00:00:00Z").unwrap_err();
+        assert_eq!(error.kind, RecurrenceErrorKind::InvalidFrequency);
+        assert!(error.source.is_some());
+    }
+}
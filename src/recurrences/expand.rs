@@ -0,0 +1,147 @@
+use crate::frequencies::OccurrenceEngine;
+use crate::recurrences::Recurrence;
+use alloc::vec::Vec;
+use chrono::{DateTime, Utc};
+use core::ops::Range;
+
+/// The outcome of `Recurrence::expand_into`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct ExpandStats {
+    /// Number of event dates appended to the output
+    pub produced: usize,
+
+    /// Whether the window has more events than the limit, which were left out
+    pub truncated: bool,
+}
+
+impl<E: OccurrenceEngine> Recurrence<E> {
+    /// Appends the event dates from the start of the window, excluding its end, to the output,
+    /// up to `limit` dates, E.g. to expand many recurrences into the same buffer without
+    /// allocating a list for each of them.
+    ///
+    /// The dates already in the output are kept. Frequencies with evenly spaced events reserve
+    /// the room for the dates of the window up front.
+    ///
+    /// Examples:
+    /// ```
+    /// use std::str::FromStr;
+    /// use chrono::{DateTime, Utc};
+    /// use rrules::Recurrence;
+    ///
+    /// let rules = [
+    ///     "FREQ=DAILY;INTERVAL=1;DTSTART=2023-01-01T09:00:00Z",
+    ///     "FREQ=WEEKLY;INTERVAL=1;BYDAY=MO,TH;DTSTART=2023-01-02T14:00:00Z",
+    /// ];
+    /// let from = DateTime::<Utc>::from_str("2023-03-01T00:00:00Z").unwrap();
+    /// let to = DateTime::<Utc>::from_str("2023-04-01T00:00:00Z").unwrap();
+    ///
+    /// let mut events = Vec::with_capacity(64);
+    /// let stats: Vec<_> = rules
+    ///     .iter()
+    ///     .map(|rule| Recurrence::from_str(rule).unwrap().expand_into(from..to, &mut events, 20))
+    ///     .collect();
+    /// // The 31 daily events of March are truncated, the 9 Mondays and Thursdays are not
+    /// assert_eq!((stats[0].produced, stats[0].truncated), (20, true));
+    /// assert_eq!((stats[1].produced, stats[1].truncated), (9, false));
+    /// assert_eq!(events.len(), 29);
+    /// ```
+    pub fn expand_into(
+        &self,
+        window: Range<DateTime<Utc>>,
+        out: &mut Vec<DateTime<Utc>>,
+        limit: usize,
+    ) -> ExpandStats {
+        if let Some(step) = self.frequency.fixed_step() {
+            let span = window.end.signed_duration_since(window.start);
+            let expected = span.num_milliseconds() / step.num_milliseconds().max(1) + 1;
+            out.reserve(usize::try_from(expected).unwrap_or(0).min(limit));
+        }
+        let mut events = self.within(&window);
+        let length = out.len();
+        out.extend(events.by_ref().take(limit));
+        let produced = out.len() - length;
+        ExpandStats {
+            produced,
+            truncated: produced == limit && events.next().is_some(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_expand {
+    use crate::recurrences::ExpandStats;
+    use crate::test_utils::date;
+    use crate::Recurrence;
+    use std::str::FromStr;
+
+    #[test]
+    fn appends_the_events_of_the_window() {
+        let hourly = Recurrence::from_str(
+            "FREQ=HOURLY;INTERVAL=1;DTSTART=2023-01-01T00:00:00Z;DTEND=2023-01-02T00:00:00Z",
+        )
+        .unwrap();
+        let mut out = vec![date("2000-01-01T00:00:00Z")];
+        let window = date("2023-01-01T10:00:00Z")..date("2023-01-01T13:00:00Z");
+        let stats = hourly.expand_into(window, &mut out, 10);
+        assert_eq!(
+            stats,
+            ExpandStats {
+                produced: 3,
+                truncated: false,
+            }
+        );
+        assert_eq!(
+            out,
+            [
+                date("2000-01-01T00:00:00Z"),
+                date("2023-01-01T10:00:00Z"),
+                date("2023-01-01T11:00:00Z"),
+                date("2023-01-01T12:00:00Z"),
+            ]
+        );
+
+        // The end date of the recurrence ends the window too
+        let window = date("2023-01-01T20:00:00Z")..date("2023-01-03T00:00:00Z");
+        let stats = hourly.expand_into(window, &mut out, 5);
+        assert_eq!(stats.produced, 5);
+        assert!(!stats.truncated);
+    }
+
+    #[test]
+    fn truncates_at_the_limit() {
+        let recurrence = Recurrence::from_str(
+            "FREQ=MONTHLY;INTERVAL=1;BYMONTHDAY=13;BYDAY=FR;DTSTART=2000-01-01T00:00:00Z",
+        )
+        .unwrap();
+        let window = date("2000-01-01T00:00:00Z")..date("2010-01-01T00:00:00Z");
+        let mut out = Vec::new();
+        let stats = recurrence.expand_into(window.clone(), &mut out, 3);
+        assert_eq!(stats.produced, 3);
+        assert!(stats.truncated);
+        assert_eq!(out, recurrence.within(&window).take(3).collect::<Vec<_>>());
+
+        let stats = recurrence.expand_into(window.clone(), &mut out, 0);
+        assert_eq!(stats.produced, 0);
+        assert!(stats.truncated);
+
+        let all = recurrence.within(&window).count();
+        out.clear();
+        let stats = recurrence.expand_into(window, &mut out, all);
+        assert_eq!(stats.produced, all);
+        assert!(!stats.truncated);
+    }
+
+    #[test]
+    fn empty_windows() {
+        let daily =
+            Recurrence::from_str("FREQ=DAILY;INTERVAL=1;DTSTART=2023-01-01T00:00:00Z").unwrap();
+        let mut out = Vec::new();
+        let stats = daily.expand_into(
+            date("2023-02-01T00:00:00Z")..date("2023-01-01T00:00:00Z"),
+            &mut out,
+            10,
+        );
+        assert_eq!(stats, ExpandStats::default());
+        assert!(out.is_empty() && out.capacity() == 0);
+    }
+}
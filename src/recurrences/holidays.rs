@@ -0,0 +1,282 @@
+use crate::recurrences::{OccurrenceIter, Recurrence};
+use alloc::collections::BTreeSet;
+use alloc::vec::Vec;
+use chrono::{DateTime, Datelike, Days, NaiveDate, Utc, Weekday};
+
+/// Maximum number of days an event is shifted to find a business day, the events that
+/// cannot be shifted within them are skipped.
+const MAX_SHIFT_DAYS: u64 = 366;
+
+/// A calendar of holidays, used to skip or shift the events that land on them.
+///
+/// It is implemented for lists and sets of dates, and for closures.
+///
+/// Examples:
+/// ```
+/// use chrono::{Datelike, NaiveDate};
+/// use rrules::HolidaySource;
+///
+/// let christmas = |date: NaiveDate| date.month() == 12 && date.day() == 25;
+/// assert!(christmas.is_holiday(NaiveDate::from_ymd_opt(2023, 12, 25).unwrap()));
+/// ```
+pub trait HolidaySource {
+    /// Verifies if the date is a holiday.
+    fn is_holiday(&self, date: NaiveDate) -> bool;
+}
+
+impl<F: Fn(NaiveDate) -> bool> HolidaySource for F {
+    fn is_holiday(&self, date: NaiveDate) -> bool {
+        self(date)
+    }
+}
+
+impl HolidaySource for [NaiveDate] {
+    fn is_holiday(&self, date: NaiveDate) -> bool {
+        self.contains(&date)
+    }
+}
+
+impl HolidaySource for Vec<NaiveDate> {
+    fn is_holiday(&self, date: NaiveDate) -> bool {
+        self.contains(&date)
+    }
+}
+
+impl HolidaySource for BTreeSet<NaiveDate> {
+    fn is_holiday(&self, date: NaiveDate) -> bool {
+        self.contains(&date)
+    }
+}
+
+/// Policy applied to the events that land on a holiday.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum ShiftPolicy {
+    /// The event is skipped
+    #[default]
+    Skip,
+    /// The event is moved to the next business day, at the same time
+    NextBusinessDay,
+    /// The event is moved to the previous business day, at the same time
+    PreviousBusinessDay,
+}
+
+impl ShiftPolicy {
+    /// Returns the date of the event after applying the policy, or None when it is skipped.
+    fn apply<H: HolidaySource>(&self, holidays: &H, event: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let is_business_day = |date: &DateTime<Utc>| {
+            !matches!(date.weekday(), Weekday::Sat | Weekday::Sun)
+                && !holidays.is_holiday(date.date_naive())
+        };
+        if !holidays.is_holiday(event.date_naive()) {
+            return Some(event);
+        }
+        let shift = |days: u64| match self {
+            ShiftPolicy::Skip => None,
+            ShiftPolicy::NextBusinessDay => event.checked_add_days(Days::new(days)),
+            ShiftPolicy::PreviousBusinessDay => event.checked_sub_days(Days::new(days)),
+        };
+        (1..=MAX_SHIFT_DAYS).map_while(shift).find(is_business_day)
+    }
+}
+
+impl Recurrence {
+    /// Returns an iterator over the event dates of the recurrence, skipping or shifting the
+    /// events whose UTC date is a holiday.
+    ///
+    /// Events are shifted to the closest day that is neither a weekend nor a holiday. Shifted
+    /// events are yielded in order, and only once when they land on another event.
+    ///
+    /// Examples:
+    /// ```
+    /// use std::str::FromStr;
+    /// use chrono::{DateTime, NaiveDate, Utc};
+    /// use rrules::{Recurrence, ShiftPolicy};
+    ///
+    /// let monthly = Recurrence::from_str("FREQ=MONTHLY;INTERVAL=1;DTSTART=2023-12-25T09:00:00Z").unwrap();
+    /// let holidays = vec![NaiveDate::from_ymd_opt(2023, 12, 25).unwrap()];
+    /// let mut events = monthly.skipping(holidays, ShiftPolicy::NextBusinessDay);
+    /// assert_eq!(events.next(), Some(DateTime::<Utc>::from_str("2023-12-26T09:00:00Z").unwrap()));
+    /// ```
+    pub fn skipping<H: HolidaySource>(&self, holidays: H, policy: ShiftPolicy) -> Skipping<'_, H> {
+        Skipping {
+            events: self.iter(),
+            holidays,
+            policy,
+            pending: BTreeSet::new(),
+            last_event: None,
+            exhausted: false,
+            last_yielded: None,
+        }
+    }
+}
+
+/// An iterator over the event dates of a `Recurrence` that skips or shifts the holidays.
+///
+/// It is created by `Recurrence::skipping()`.
+#[derive(Debug, Clone)]
+pub struct Skipping<'a, H> {
+    events: OccurrenceIter<'a>,
+    holidays: H,
+    policy: ShiftPolicy,
+
+    /// Shifted events waiting for the events before them to be yielded
+    pending: BTreeSet<DateTime<Utc>>,
+
+    /// Last event taken from the recurrence
+    last_event: Option<DateTime<Utc>>,
+
+    /// Whether all the events of the recurrence were taken
+    exhausted: bool,
+
+    /// Last event yielded, so the shifted events are not yielded twice nor out of order
+    last_yielded: Option<DateTime<Utc>>,
+}
+
+impl<'a, H: HolidaySource> Iterator for Skipping<'a, H> {
+    type Item = DateTime<Utc>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            // Events shifted forward land after the event they come from, so the pending
+            // events up to the last event taken can be yielded. Events shifted back to or
+            // before the last event yielded are dropped.
+            let is_ready = match self.pending.first() {
+                Some(first) => self.exhausted || self.last_event.is_some_and(|last| *first <= last),
+                None if self.exhausted => return None,
+                None => false,
+            };
+            if is_ready {
+                let event = self.pending.pop_first()?;
+                if self
+                    .last_yielded
+                    .is_some_and(|last_yielded| event <= last_yielded)
+                {
+                    continue;
+                }
+                self.last_yielded = Some(event);
+                return Some(event);
+            }
+            match self.events.next() {
+                Some(event) => {
+                    self.last_event = Some(event);
+                    if let Some(shifted) = self.policy.apply(&self.holidays, event) {
+                        self.pending.insert(shifted);
+                    }
+                }
+                None => self.exhausted = true,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_holidays {
+    use crate::test_utils::date;
+    use crate::{HolidaySource, Recurrence, ShiftPolicy};
+    use chrono::{Datelike, NaiveDate};
+    use std::collections::BTreeSet;
+    use std::str::FromStr;
+
+    fn day(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    #[test]
+    fn holiday_sources() {
+        let holidays = vec![day(2023, 12, 25), day(2023, 12, 26)];
+        assert!(holidays.is_holiday(day(2023, 12, 26)));
+        assert!(!holidays.is_holiday(day(2023, 12, 27)));
+        assert!(holidays[..].is_holiday(day(2023, 12, 25)));
+        let set: BTreeSet<NaiveDate> = holidays.into_iter().collect();
+        assert!(set.is_holiday(day(2023, 12, 25)));
+        let new_year = |date: NaiveDate| date.ordinal() == 1;
+        assert!(new_year.is_holiday(day(2024, 1, 1)));
+    }
+
+    #[test]
+    fn skips_holidays() {
+        let recurrence = Recurrence::from_str(
+            "FREQ=DAILY;INTERVAL=1;DTSTART=2023-12-24T09:00:00Z;DTEND=2023-12-28T09:00:00Z",
+        )
+        .unwrap();
+        let holidays = vec![day(2023, 12, 25), day(2023, 12, 26)];
+        let events: Vec<_> = recurrence.skipping(holidays, ShiftPolicy::Skip).collect();
+        assert_eq!(
+            events,
+            vec![
+                date("2023-12-24T09:00:00Z"),
+                date("2023-12-27T09:00:00Z"),
+                date("2023-12-28T09:00:00Z"),
+            ]
+        );
+    }
+
+    #[test]
+    fn shifts_to_the_next_business_day() {
+        // Friday the 29th is a holiday, so its event moves to Monday, past the weekend
+        let recurrence = Recurrence::from_str(
+            "FREQ=WEEKLY;INTERVAL=1;BYDAY=MO,FR;DTSTART=2023-12-25T12:00:00Z;DTEND=2024-01-08T12:00:00Z",
+        )
+        .unwrap();
+        let holidays = vec![day(2023, 12, 25), day(2023, 12, 29)];
+        let events: Vec<_> = recurrence
+            .skipping(holidays, ShiftPolicy::NextBusinessDay)
+            .collect();
+        assert_eq!(
+            events,
+            vec![
+                date("2023-12-26T12:00:00Z"),
+                date("2024-01-01T12:00:00Z"),
+                date("2024-01-05T12:00:00Z"),
+                date("2024-01-08T12:00:00Z"),
+            ]
+        );
+    }
+
+    #[test]
+    fn shifted_events_stay_in_order_and_are_not_repeated() {
+        let recurrence = Recurrence::from_str(
+            "FREQ=DAILY;INTERVAL=1;BYTIME=08:00,18:00;DTSTART=2023-12-25T00:00:00Z;DTEND=2023-12-26T23:00:00Z",
+        )
+        .unwrap();
+        let holidays = vec![day(2023, 12, 25)];
+        let events: Vec<_> = recurrence
+            .skipping(holidays, ShiftPolicy::NextBusinessDay)
+            .collect();
+        assert_eq!(
+            events,
+            vec![date("2023-12-26T08:00:00Z"), date("2023-12-26T18:00:00Z")]
+        );
+    }
+
+    #[test]
+    fn shifts_to_the_previous_business_day() {
+        let recurrence = Recurrence::from_str(
+            "FREQ=MONTHLY;INTERVAL=1;BYMONTHDAY=1;DTSTART=2024-01-01T09:00:00Z;DTEND=2024-03-01T09:00:00Z",
+        )
+        .unwrap();
+        let holidays = vec![day(2024, 1, 1)];
+        let events: Vec<_> = recurrence
+            .skipping(holidays, ShiftPolicy::PreviousBusinessDay)
+            .collect();
+        assert_eq!(
+            events,
+            vec![
+                date("2023-12-29T09:00:00Z"),
+                date("2024-02-01T09:00:00Z"),
+                date("2024-03-01T09:00:00Z"),
+            ]
+        );
+    }
+
+    #[test]
+    fn skips_events_without_business_days_around() {
+        let recurrence = Recurrence::from_str(
+            "FREQ=DAILY;INTERVAL=1;DTSTART=2023-01-01T00:00:00Z;DTEND=2023-01-10T00:00:00Z",
+        )
+        .unwrap();
+        let every_day = |_: NaiveDate| true;
+        let policy = ShiftPolicy::NextBusinessDay;
+        assert_eq!(recurrence.skipping(every_day, policy).count(), 0);
+    }
+}
@@ -0,0 +1,488 @@
+//! Parsing and generation of iCalendar (RFC 5545) `VEVENT` components.
+//!
+//! Only the properties that define the recurrence are handled:
+//! `DTSTART`, `DTEND`, `DURATION`, `RRULE` (with its `UNTIL` and `COUNT` parts) and `EXDATE`,
+//! along with the `X-NOTBEFORE` part of the `RRULE` of the recurrences that skip their first events.
+//! Other properties are ignored when parsing, while unknown `RRULE` parts are kept.
+
+use crate::frequencies::serializer::{tokenize, FREQUENCY_KEYS};
+use crate::recurrences::serializers::{
+    extract_unknown_params, format_duration, invalid, missing, parse_compact_date, parse_count,
+    parse_duration,
+};
+use crate::recurrences::validations::validate_duration_with;
+use crate::{Frequency, ParseOptions, Recurrence, RecurrenceInvalid};
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use alloc::{format, vec};
+use chrono::{DateTime, Duration, Utc};
+use core::str::FromStr;
+
+/// Maximum length in octets of a content line, longer lines are folded.
+const MAX_LINE_LENGTH: usize = 75;
+
+const ICS_DATE_FORMAT: &str = "%Y%m%dT%H%M%SZ";
+const ICS_DAY_FORMAT: &str = "%Y%m%d";
+
+impl Recurrence {
+    /// Parses a recurrence from an iCalendar `VEVENT` block.
+    ///
+    /// The `DTEND` or `DURATION` properties define the duration of every event, the
+    /// `UNTIL` part of the `RRULE` defines the end of the recurrence and its `COUNT` part
+    /// the maximum number of occurrences, counting the excluded dates.
+    /// Examples:
+    /// ```
+    /// use std::str::FromStr;
+    /// use chrono::{DateTime, Utc};
+    /// use rrules::Recurrence;
+    ///
+    /// let vevent = "BEGIN:VEVENT\r\n\
+    ///     DTSTART:20230101T090000Z\r\n\
+    ///     DTEND:20230101T100000Z\r\n\
+    ///     RRULE:FREQ=DAILY;INTERVAL=1;UNTIL=20230105T090000Z\r\n\
+    ///     EXDATE:20230102T090000Z\r\n\
+    ///     END:VEVENT";
+    /// let recurrence = Recurrence::from_ics_vevent(vevent).unwrap();
+    /// let events: Vec<DateTime<Utc>> = recurrence.iter().collect();
+    /// assert_eq!(events.len(), 4);
+    /// assert_eq!(events[1], DateTime::<Utc>::from_str("2023-01-03T09:00:00Z").unwrap());
+    /// ```
+    pub fn from_ics_vevent(s: &str) -> Result<Self, RecurrenceInvalid> {
+        let mut lines = unfold_lines(s)
+            .into_iter()
+            .skip_while(|line| !line.eq_ignore_ascii_case("BEGIN:VEVENT"));
+        if lines.next().is_none() {
+            return Err(missing("VEVENT"));
+        }
+        let lines: Vec<String> = lines
+            .take_while(|line| !line.eq_ignore_ascii_case("END:VEVENT"))
+            .collect();
+        Recurrence::from_content_lines(&lines, ParseOptions::default())
+    }
+
+    /// Parses a recurrence from iCalendar content lines like `DTSTART:...` and `RRULE:...`.
+    ///
+    /// When there is no `DTSTART` line, the `RRULE` value is parsed as a rule string,
+    /// so it can still define its own `DTSTART=` part or start at the default start date
+    /// of the options.
+    pub(crate) fn from_content_lines(
+        lines: &[String],
+        options: ParseOptions,
+    ) -> Result<Self, RecurrenceInvalid> {
+        let mut start = None;
+        let mut all_day = false;
+        let mut event_end = None;
+        let mut duration = None;
+        let mut rule = None;
+        let mut exdates = vec![];
+
+        for line in lines {
+            let (name, params, value) = split_content_line(line)?;
+            match name.as_str() {
+                "DTSTART" => {
+                    start = Some(parse_ics_date(value, params)?);
+                    all_day = is_date_value(value, params);
+                }
+                "DTEND" => event_end = Some(parse_ics_date(value, params)?),
+                "DURATION" => {
+                    duration = Some(parse_duration(value).ok_or_else(|| {
+                        RecurrenceInvalid::new(format!("Invalid duration: {value}"))
+                            .with_kind(invalid(value))
+                    })?)
+                }
+                "RRULE" => rule = Some(value.to_string()),
+                "EXDATE" => {
+                    for date in value.split(',') {
+                        exdates.push(parse_ics_date(date, params)?);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let rule = rule.ok_or_else(|| missing("RRULE"))?;
+        let end = match tokenize(&rule).flatten().find(|token| token.key == "UNTIL") {
+            Some(token) => Some(parse_ics_date(token.value, "")?),
+            None => None,
+        };
+        let start = match start {
+            Some(start) => start,
+            None => {
+                let mut recurrence = Recurrence::parse(&rule, options)?;
+                let exdates = [recurrence.exdates.clone(), exdates].concat();
+                // Rule strings have no UNTIL part, so it was kept as an unknown parameter
+                recurrence.unknown_params.retain(|(key, _)| key != "UNTIL");
+                let recurrence = recurrence.with_exdates(exdates);
+                return match end {
+                    Some(end) => recurrence.with_end(Some(end)),
+                    None => Ok(recurrence),
+                };
+            }
+        };
+        let frequency = Frequency::from_str(&rule)?;
+        let known_keys = [&FREQUENCY_KEYS[..], &["UNTIL", "COUNT", "X-NOTBEFORE"]].concat();
+        let unknown_params = extract_unknown_params(&rule, &known_keys);
+        let count = match tokenize(&rule).flatten().find(|token| token.key == "COUNT") {
+            Some(token) => Some(parse_count(token.value)?),
+            None => None,
+        };
+        let not_before = match tokenize(&rule)
+            .flatten()
+            .find(|token| token.key == "X-NOTBEFORE")
+        {
+            Some(token) => Some(parse_ics_date(token.value, "")?),
+            None => None,
+        };
+        let duration = match (duration, event_end) {
+            (Some(duration), _) => Some(duration),
+            (None, Some(event_end)) => Some(event_end - start),
+            (None, None) => None,
+        };
+        if !all_day {
+            let mut recurrence =
+                Recurrence::new_with(frequency, start, end, duration, options.validation)?
+                    .with_exdates(exdates)
+                    .with_unknown_params(unknown_params);
+            recurrence.not_before = not_before;
+            return Ok(recurrence.with_rule_count(count));
+        }
+        let mut recurrence = Recurrence::all_day(
+            frequency,
+            start.date_naive(),
+            end.map(|date| date.date_naive()),
+        )?;
+        recurrence.validation = options.validation;
+        if let Some(duration) = duration {
+            validate_duration_with(
+                &recurrence.frequency,
+                &recurrence.start,
+                &duration,
+                &recurrence.validation,
+            )?;
+            recurrence.duration = duration;
+        }
+        let mut recurrence = recurrence
+            .with_exdates(exdates)
+            .with_unknown_params(unknown_params);
+        recurrence.not_before = not_before;
+        Ok(recurrence.with_rule_count(count))
+    }
+
+    /// Generates an iCalendar `VEVENT` block for the recurrence, with folded lines.
+    ///
+    /// The `COUNT` of the rule replaces the `UNTIL` part of the `RRULE` when the events stop at
+    /// the count before the end date.
+    /// Examples:
+    /// ```
+    /// use std::str::FromStr;
+    /// use rrules::Recurrence;
+    ///
+    /// let recurrence = Recurrence::from_str(
+    ///     "FREQ=WEEKLY;INTERVAL=1;DTSTART=2023-01-02T09:00:00Z;DTEND=2023-03-01T00:00:00Z;BYDAY=MO"
+    /// ).unwrap();
+    /// assert_eq!(
+    ///     recurrence.to_ics_vevent(),
+    ///     "BEGIN:VEVENT\r\n\
+    ///     DTSTART:20230102T090000Z\r\n\
+    ///     RRULE:FREQ=WEEKLY;INTERVAL=1;BYDAY=MO;UNTIL=20230301T000000Z\r\n\
+    ///     END:VEVENT\r\n"
+    /// );
+    /// ```
+    pub fn to_ics_vevent(&self) -> String {
+        let mut lines = vec!["BEGIN:VEVENT".to_string()];
+        lines.extend(self.content_lines());
+        lines.push("END:VEVENT".to_string());
+
+        lines.iter().map(|line| fold_line(line) + "\r\n").collect()
+    }
+
+    /// Generates the iCalendar content lines of the recurrence, like `DTSTART:...` and
+    /// `RRULE:...`, one per line and without a `VEVENT` block.
+    ///
+    /// The recurrences parsed from content lines are written this way by `to_string()`.
+    /// Examples:
+    /// ```
+    /// use std::str::FromStr;
+    /// use rrules::Recurrence;
+    ///
+    /// let recurrence = Recurrence::from_str(
+    ///     "FREQ=DAILY;INTERVAL=1;DTSTART=2023-01-01T09:00:00Z;DURATION=PT1H"
+    /// ).unwrap();
+    /// assert_eq!(
+    ///     recurrence.to_content_lines(),
+    ///     "DTSTART:20230101T090000Z\nDURATION:PT1H\nRRULE:FREQ=DAILY;INTERVAL=1"
+    /// );
+    /// ```
+    pub fn to_content_lines(&self) -> String {
+        self.content_lines().join("\n")
+    }
+
+    /// Returns the content lines of the properties that define the recurrence.
+    fn content_lines(&self) -> Vec<String> {
+        let (date_format, date_params, default_duration) = if self.all_day {
+            (ICS_DAY_FORMAT, ";VALUE=DATE", Duration::days(1))
+        } else {
+            (ICS_DATE_FORMAT, "", Duration::zero())
+        };
+        let mut lines = vec![format!(
+            "DTSTART{date_params}:{}",
+            self.start.format(date_format)
+        )];
+        if self.duration != default_duration && self.duration > Duration::zero() {
+            lines.push(format!("DURATION:{}", format_duration(&self.duration)));
+        }
+        let mut rule = format!("RRULE:{}", self.frequency.to_rfc_string());
+        if let Some(not_before) = self.not_before {
+            rule.push_str(&format!(";X-NOTBEFORE={}", not_before.format(date_format)));
+        }
+        for (key, value) in &self.unknown_params {
+            rule.push_str(&format!(";{key}={value}"));
+        }
+        // The rules cannot have both a COUNT and an UNTIL part
+        match (self.count, self.end()) {
+            (Some(count), end) if end.is_none() || self.stops_before_end() => {
+                rule.push_str(&format!(";COUNT={count}"));
+            }
+            (_, Some(end)) => rule.push_str(&format!(";UNTIL={}", end.format(date_format))),
+            (_, None) => {}
+        }
+        lines.push(rule);
+        if !self.exdates.is_empty() {
+            let exdates: Vec<String> = self
+                .exdates
+                .iter()
+                .map(|date| date.format(date_format).to_string())
+                .collect();
+            lines.push(format!("EXDATE{date_params}:{}", exdates.join(",")));
+        }
+        lines
+    }
+}
+
+/// Joins the content lines that were folded into many lines.
+pub(crate) fn unfold_lines(s: &str) -> Vec<String> {
+    let mut lines: Vec<String> = vec![];
+    for line in s.lines() {
+        match (line.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(continuation), Some(last)) => last.push_str(continuation),
+            _ => {
+                let line = line.trim();
+                if !line.is_empty() {
+                    lines.push(line.to_string());
+                }
+            }
+        }
+    }
+    lines
+}
+
+/// Verifies if the value is made of iCalendar content lines rather than a single rule string.
+pub(crate) fn is_content_lines(s: &str) -> bool {
+    s.lines().any(|line| {
+        let name = line
+            .trim_start()
+            .split([':', ';'])
+            .next()
+            .unwrap_or_default();
+        ["RRULE", "DTSTART"].contains(&name.to_uppercase().as_str())
+            && line.trim_start()[name.len()..].contains(':')
+    })
+}
+
+/// Splits content lines longer than 75 octets, without breaking characters.
+fn fold_line(line: &str) -> String {
+    let mut folded = String::new();
+    let mut length = 0;
+    for c in line.chars() {
+        if length + c.len_utf8() > MAX_LINE_LENGTH {
+            folded.push_str("\r\n ");
+            length = 1;
+        }
+        folded.push(c);
+        length += c.len_utf8();
+    }
+    folded
+}
+
+/// Verifies if the property value is a date without time.
+fn is_date_value(value: &str, params: &str) -> bool {
+    params
+        .split(';')
+        .any(|param| param.eq_ignore_ascii_case("VALUE=DATE"))
+        || value.len() == 8
+}
+
+/// Splits a content line into its upper-cased name, parameters and value.
+fn split_content_line(line: &str) -> Result<(String, &str, &str), RecurrenceInvalid> {
+    let (head, value) = line.split_once(':').ok_or_else(|| {
+        RecurrenceInvalid::new(format!("Invalid content line: {line}")).with_kind(invalid(line))
+    })?;
+    let (name, params) = head.split_once(';').unwrap_or((head, ""));
+    Ok((name.to_uppercase(), params, value))
+}
+
+fn parse_ics_date(value: &str, params: &str) -> Result<DateTime<Utc>, RecurrenceInvalid> {
+    if params.to_uppercase().contains("TZID=") {
+        return Err(RecurrenceInvalid::new(format!(
+            "Dates with time zones are not supported: {params}:{value}"
+        ))
+        .with_kind(invalid(value)));
+    }
+    parse_compact_date(value).ok_or_else(|| {
+        RecurrenceInvalid::new(format!("Invalid date: {value}")).with_kind(invalid(value))
+    })
+}
+
+#[cfg(test)]
+mod test_ics {
+    use crate::recurrences::ics::{fold_line, unfold_lines};
+    use crate::test_utils::date;
+    use crate::{ParseOptions, Recurrence};
+    use chrono::{DateTime, Duration, Utc};
+    use std::str::FromStr;
+
+    #[test]
+    fn test_unfold_lines() {
+        let lines =
+            unfold_lines("BEGIN:VEVENT\r\nRRULE:FREQ=DAILY;\r\n INTERVAL=1\r\nEND:VEVENT\r\n");
+        assert_eq!(
+            lines,
+            vec!["BEGIN:VEVENT", "RRULE:FREQ=DAILY;INTERVAL=1", "END:VEVENT"]
+        );
+    }
+
+    #[test]
+    fn test_fold_line() {
+        let line = format!("RRULE:{}", "A".repeat(100));
+        let folded = fold_line(&line);
+        let lines: Vec<&str> = folded.split("\r\n").collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0].len(), 75);
+        assert_eq!(unfold_lines(&folded), vec![line]);
+    }
+
+    #[test]
+    fn test_from_ics_vevent() {
+        let vevent = "BEGIN:VCALENDAR\nBEGIN:VEVENT\nUID:123@example.com\nSUMMARY:Standup\n\
+            DTSTART:20230102T090000Z\nDURATION:PT15M\n\
+            RRULE:FREQ=WEEKLY;INTERVAL=1;BYDAY=MO,WE;UNTIL=20230115T000000Z\n\
+            EXDATE;VALUE=DATE-TIME:20230104T090000Z\nEND:VEVENT\nEND:VCALENDAR";
+        let recurrence = Recurrence::from_ics_vevent(vevent).unwrap();
+        assert_eq!(recurrence.duration, Duration::minutes(15));
+        let events: Vec<DateTime<Utc>> = recurrence.iter().collect();
+        assert_eq!(
+            events,
+            vec![
+                DateTime::<Utc>::from_str("2023-01-02T09:00:00Z").unwrap(),
+                DateTime::<Utc>::from_str("2023-01-09T09:00:00Z").unwrap(),
+                DateTime::<Utc>::from_str("2023-01-11T09:00:00Z").unwrap(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_from_ics_vevent_duration_from_dtend() {
+        let vevent = "BEGIN:VEVENT\nDTSTART:20230101T090000Z\nDTEND:20230101T103000Z\n\
+            RRULE:FREQ=DAILY;INTERVAL=1\nEND:VEVENT";
+        let recurrence = Recurrence::from_ics_vevent(vevent).unwrap();
+        assert_eq!(recurrence.duration, Duration::minutes(90));
+        assert!(recurrence.is_infinite());
+    }
+
+    #[test]
+    fn test_from_ics_vevent_errors() {
+        assert!(Recurrence::from_ics_vevent("DTSTART:20230101T090000Z").is_err());
+        let without_rule = "BEGIN:VEVENT\nDTSTART:20230101T090000Z\nEND:VEVENT";
+        assert!(Recurrence::from_ics_vevent(without_rule).is_err());
+        let with_time_zone = "BEGIN:VEVENT\nDTSTART;TZID=Europe/Paris:20230101T090000\n\
+            RRULE:FREQ=DAILY;INTERVAL=1\nEND:VEVENT";
+        assert!(Recurrence::from_ics_vevent(with_time_zone).is_err());
+    }
+
+    #[test]
+    fn test_ics_uses_the_skip_parameter() {
+        let recurrence = Recurrence::from_str(
+            "FREQ=MONTHLY;INTERVAL=1;X-OVERFLOW=CLAMP;DTSTART=2023-01-31T00:00:00Z",
+        )
+        .unwrap();
+        let vevent = recurrence.to_ics_vevent();
+        assert!(vevent.contains("RRULE:FREQ=MONTHLY;INTERVAL=1;RSCALE=GREGORIAN;SKIP=BACKWARD\r\n"));
+        assert_eq!(Recurrence::from_ics_vevent(&vevent).unwrap(), recurrence);
+    }
+
+    #[test]
+    fn test_ics_round_trip() {
+        let recurrence = Recurrence::from_str(
+            "FREQ=MONTHLY;INTERVAL=1;DTSTART=2023-01-01T00:00:00Z;DTEND=2024-01-01T00:00:00Z;BYDAY=1MO;DURATION=PT1H",
+        )
+        .unwrap()
+        .with_exdates(vec![
+            DateTime::<Utc>::from_str("2023-02-06T00:00:00Z").unwrap()
+        ]);
+        let vevent = recurrence.to_ics_vevent();
+        assert!(vevent.contains("EXDATE:20230206T000000Z\r\n"));
+        assert_eq!(Recurrence::from_ics_vevent(&vevent).unwrap(), recurrence);
+    }
+
+    #[test]
+    fn test_ics_count() {
+        let vevent = "BEGIN:VEVENT\nDTSTART:20230101T090000Z\nRRULE:FREQ=DAILY;INTERVAL=1;COUNT=3\nEND:VEVENT";
+        let recurrence = Recurrence::from_ics_vevent(vevent).unwrap();
+        let events: Vec<String> = recurrence.iter().map(|date| date.to_rfc3339()).collect();
+        assert_eq!(
+            events,
+            vec![
+                "2023-01-01T09:00:00+00:00",
+                "2023-01-02T09:00:00+00:00",
+                "2023-01-03T09:00:00+00:00",
+            ]
+        );
+        assert_eq!(
+            recurrence.to_ics_vevent(),
+            "BEGIN:VEVENT\r\nDTSTART:20230101T090000Z\r\nRRULE:FREQ=DAILY;INTERVAL=1;COUNT=3\r\nEND:VEVENT\r\n"
+        );
+
+        // The count includes the excluded dates
+        let vevent = "BEGIN:VEVENT\nDTSTART:20230101T090000Z\nRRULE:FREQ=DAILY;INTERVAL=1;COUNT=3\nEXDATE:20230102T090000Z\nEND:VEVENT";
+        let recurrence = Recurrence::from_ics_vevent(vevent).unwrap();
+        assert_eq!(recurrence.iter().count(), 2);
+        assert!(recurrence.to_ics_vevent().contains(";COUNT=3\r\n"));
+        assert_eq!(
+            Recurrence::from_ics_vevent(&recurrence.to_ics_vevent()).unwrap(),
+            recurrence
+        );
+
+        // The count is only written when it stops the events before the end date
+        let vevent = "BEGIN:VEVENT\nDTSTART:20230101T090000Z\nRRULE:FREQ=DAILY;INTERVAL=1;COUNT=3;UNTIL=20230110T000000Z\nEND:VEVENT";
+        let recurrence = Recurrence::from_ics_vevent(vevent).unwrap();
+        assert_eq!(recurrence.iter().count(), 3);
+        assert!(recurrence.to_ics_vevent().contains(";COUNT=3\r\n"));
+        let vevent = "BEGIN:VEVENT\nDTSTART:20230101T090000Z\nRRULE:FREQ=DAILY;INTERVAL=1;COUNT=30;UNTIL=20230103T000000Z\nEND:VEVENT";
+        let recurrence = Recurrence::from_ics_vevent(vevent).unwrap();
+        assert_eq!(recurrence.iter().count(), 2);
+        assert!(recurrence
+            .to_ics_vevent()
+            .contains(";UNTIL=20230103T000000Z\r\n"));
+
+        assert!(Recurrence::from_ics_vevent(
+            "BEGIN:VEVENT\nDTSTART:20230101T090000Z\nRRULE:FREQ=DAILY;INTERVAL=1;COUNT=-1\nEND:VEVENT"
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn the_end_date_without_start_line() {
+        let vevent = "BEGIN:VEVENT\nRRULE:FREQ=DAILY;INTERVAL=1;DTSTART=2023-01-01T09:00:00Z;\
+            UNTIL=20230103T090000Z\nEND:VEVENT";
+        let recurrence = Recurrence::from_ics_vevent(vevent).unwrap();
+        assert_eq!(recurrence.end(), Some(date("2023-01-03T09:00:00Z")));
+        assert_eq!(recurrence.iter().count(), 3);
+        assert!(recurrence.unknown_params.is_empty());
+
+        let options = ParseOptions::default().with_default_start(date("2023-01-01T09:00:00Z"));
+        let lines = "RRULE:FREQ=DAILY;INTERVAL=1;UNTIL=20230102T090000Z";
+        let recurrence = Recurrence::from_str_with(lines, options).unwrap();
+        assert_eq!(recurrence.end(), Some(date("2023-01-02T09:00:00Z")));
+        assert_eq!(recurrence.iter().count(), 2);
+    }
+}
@@ -0,0 +1,498 @@
+//! Conversions from and to ISO 8601 repeating intervals, like `R5/2023-01-01T00:00:00Z/P1D`.
+//!
+//! A repeating interval has an optional number of events, a start date and the period between
+//! the events, either as a duration or as the end date of the first period. Only the
+//! recurrences without filters, exdates nor duration of their events can be written as one.
+
+use crate::recurrences::serializers::invalid;
+use crate::{FreqKind, Frequency, OverflowPolicy, Recurrence, RecurrenceInvalid};
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveTime, TimeZone, Utc};
+use core::num::NonZeroU32;
+use core::str::FromStr;
+
+const ISO_DATE_FORMAT: &str = "%Y-%m-%dT%H:%M:%SZ";
+const ISO_DAY_FORMAT: &str = "%Y-%m-%d";
+
+const SECONDS_PER_MINUTE: i64 = 60;
+const SECONDS_PER_HOUR: i64 = 60 * SECONDS_PER_MINUTE;
+const SECONDS_PER_DAY: i64 = 24 * SECONDS_PER_HOUR;
+const SECONDS_PER_WEEK: i64 = 7 * SECONDS_PER_DAY;
+
+impl Recurrence {
+    /// Parses a recurrence from an ISO 8601 repeating interval, like `R5/2023-01-01T00:00:00Z/P1D`
+    /// for 5 daily events.
+    ///
+    /// Without a number after the `R`, the events don't end. The period can be a duration or the
+    /// end date of the first period, and is repeated by the frequency with the largest unit that
+    /// divides it, so `PT36H` is every 36 hours and `P14D` every 2 weeks. Years and months cannot
+    /// be combined with the other units, and end on the last day of the months without the day of
+    /// the start, so `P1M` from January 31st is on February 28th, like `X-OVERFLOW=CLAMP`.
+    /// A start date without time defines an all-day recurrence.
+    /// Examples:
+    /// ```
+    /// use std::str::FromStr;
+    /// use chrono::{DateTime, Utc};
+    /// use rrules::Recurrence;
+    ///
+    /// let recurrence = Recurrence::from_iso8601_repeat("R3/2023-01-01T09:00:00Z/P1W").unwrap();
+    /// let events: Vec<DateTime<Utc>> = recurrence.iter().collect();
+    /// assert_eq!(events.len(), 3);
+    /// assert_eq!(events[2], DateTime::<Utc>::from_str("2023-01-15T09:00:00Z").unwrap());
+    /// assert_eq!(recurrence.to_iso8601_repeat().unwrap(), "R3/2023-01-01T09:00:00Z/P1W");
+    /// ```
+    pub fn from_iso8601_repeat(s: &str) -> Result<Self, RecurrenceInvalid> {
+        let value = s.trim();
+        let [repetitions, start, period] = value.split('/').collect::<Vec<&str>>()[..] else {
+            return Err(
+                RecurrenceInvalid::new(format!("Invalid repeating interval: {value}"))
+                    .with_kind(invalid(value)),
+            );
+        };
+        let count = parse_repetitions(repetitions)?;
+        let (start, all_day) = parse_iso_date(start)?;
+        let frequency = if period.starts_with('P') {
+            parse_period(period)?
+        } else {
+            let (end, _) = parse_iso_date(period)?;
+            frequency_of_seconds((end - start).num_seconds()).ok_or_else(|| {
+                RecurrenceInvalid::new(format!("The period must end after its start: {period}"))
+                    .with_kind(invalid(period))
+            })?
+        };
+        let recurrence = if all_day {
+            Recurrence::all_day(frequency, start.date_naive(), None)?
+        } else {
+            Recurrence::new(frequency, start, None, None)?
+        };
+        match count {
+            Some(count) => recurrence.with_count(count),
+            None => Ok(recurrence),
+        }
+    }
+
+    /// Generates the ISO 8601 repeating interval of the recurrence, with the number of its
+    /// events when it has an end date.
+    ///
+    /// Returns an error when the recurrence cannot be represented, because its frequency has
+    /// filters or it has exdates, unknown parameters, skipped events or a duration for its events.
+    /// Examples:
+    /// ```
+    /// use std::str::FromStr;
+    /// use rrules::Recurrence;
+    ///
+    /// let hourly = Recurrence::from_str("FREQ=HOURLY;INTERVAL=6;DTSTART=2023-01-01T00:00:00Z").unwrap();
+    /// assert_eq!(hourly.to_iso8601_repeat().unwrap(), "R/2023-01-01T00:00:00Z/PT6H");
+    ///
+    /// let mondays = Recurrence::from_str("FREQ=WEEKLY;INTERVAL=1;BYDAY=MO;DTSTART=2023-01-01T00:00:00Z").unwrap();
+    /// assert!(mondays.to_iso8601_repeat().is_err());
+    /// ```
+    pub fn to_iso8601_repeat(&self) -> Result<String, RecurrenceInvalid> {
+        let period = format_period(&self.frequency)
+            .ok_or_else(|| not_representable("The filters of the frequency"))?;
+        if skips_short_months(&self.frequency, &self.start) {
+            return Err(not_representable("The skipped days of the shorter months"));
+        }
+        if !self.exdates.is_empty() {
+            return Err(not_representable("The exdates"));
+        }
+        if !self.unknown_params.is_empty() {
+            return Err(not_representable("The unknown parameters"));
+        }
+        if self.not_before.is_some() {
+            return Err(not_representable("The skipped events"));
+        }
+        let (date_format, default_duration) = if self.all_day {
+            (ISO_DAY_FORMAT, Duration::days(1))
+        } else {
+            (ISO_DATE_FORMAT, Duration::zero())
+        };
+        if self.duration != default_duration {
+            return Err(not_representable("The duration of the events"));
+        }
+        let repetitions = match (self.count, self.end()) {
+            (None, None) => String::new(),
+            _ => self.iter().count().to_string(),
+        };
+        Ok(format!(
+            "R{repetitions}/{}/{period}",
+            self.start.format(date_format)
+        ))
+    }
+}
+
+fn not_representable(what: &str) -> RecurrenceInvalid {
+    RecurrenceInvalid::new(format!(
+        "{what} cannot be represented in an ISO 8601 repeating interval"
+    ))
+}
+
+/// Parses the `R` part, returning the number of events, or None when they don't end.
+fn parse_repetitions(value: &str) -> Result<Option<usize>, RecurrenceInvalid> {
+    let invalid_repetitions = || {
+        RecurrenceInvalid::new(format!("Invalid number of repetitions: {value}"))
+            .with_kind(invalid(value))
+    };
+    match value.strip_prefix('R').ok_or_else(invalid_repetitions)? {
+        "" => Ok(None),
+        count => count.parse().map(Some).map_err(|_| invalid_repetitions()),
+    }
+}
+
+/// Parses an RFC 3339 date, or a date without time, returning whether it has no time.
+fn parse_iso_date(value: &str) -> Result<(DateTime<Utc>, bool), RecurrenceInvalid> {
+    if let Ok(day) = NaiveDate::parse_from_str(value, ISO_DAY_FORMAT) {
+        return Ok((Utc.from_utc_datetime(&day.and_time(NaiveTime::MIN)), true));
+    }
+    DateTime::<Utc>::from_str(value)
+        .map(|date| (date, false))
+        .map_err(|e| RecurrenceInvalid::new(format!("Invalid date: {e}")).with_kind(invalid(value)))
+}
+
+/// Parses a duration like `P1Y2M` or `P1DT12H` into the frequency repeating it.
+fn parse_period(value: &str) -> Result<Frequency, RecurrenceInvalid> {
+    let invalid_period =
+        || RecurrenceInvalid::new(format!("Invalid period: {value}")).with_kind(invalid(value));
+    let period = value.strip_prefix('P').ok_or_else(invalid_period)?;
+    let (date, time) = match period.split_once('T') {
+        Some((date, time)) if !time.is_empty() => (date, time),
+        Some(_) => return Err(invalid_period()),
+        None => (period, ""),
+    };
+    let date_units = [
+        ('Y', 0, 12),
+        ('M', 0, 1),
+        ('W', SECONDS_PER_WEEK, 0),
+        ('D', SECONDS_PER_DAY, 0),
+    ];
+    let time_units = [
+        ('H', SECONDS_PER_HOUR, 0),
+        ('M', SECONDS_PER_MINUTE, 0),
+        ('S', 1, 0),
+    ];
+    let (mut seconds, mut months) = (0i64, 0i64);
+    for (part, units) in [(date, &date_units[..]), (time, &time_units[..])] {
+        for (amount, unit_seconds, unit_months) in
+            parse_units(part, units).ok_or_else(invalid_period)?
+        {
+            seconds = amount
+                .checked_mul(unit_seconds)
+                .and_then(|amount| seconds.checked_add(amount))
+                .ok_or_else(invalid_period)?;
+            months = amount
+                .checked_mul(unit_months)
+                .and_then(|amount| months.checked_add(amount))
+                .ok_or_else(invalid_period)?;
+        }
+    }
+    let frequency = match (months, seconds) {
+        (0, seconds) => frequency_of_seconds(seconds),
+        (months, 0) if months % 12 == 0 => plain_frequency(
+            FreqKind::Yearly,
+            months / 12,
+            OverflowPolicy::ClampToLastDay,
+        ),
+        (months, 0) => plain_frequency(FreqKind::Monthly, months, OverflowPolicy::ClampToLastDay),
+        _ => {
+            return Err(RecurrenceInvalid::new(format!(
+                "Periods combining years or months with other units cannot be repeated: {value}"
+            ))
+            .with_kind(invalid(value)))
+        }
+    };
+    frequency.ok_or_else(invalid_period)
+}
+
+/// Parses the amounts of the units, which must be written in the order of the units and
+/// only once, with their seconds and months. Returns None when the value is not made of those units.
+fn parse_units(value: &str, units: &[(char, i64, i64)]) -> Option<Vec<(i64, i64, i64)>> {
+    let mut amounts = vec![];
+    let mut remaining = units.iter();
+    let mut digits = String::new();
+    for c in value.chars() {
+        if c.is_ascii_digit() {
+            digits.push(c);
+            continue;
+        }
+        let (_, seconds, months) = remaining.find(|(name, _, _)| *name == c)?;
+        amounts.push((digits.parse().ok()?, *seconds, *months));
+        digits.clear();
+    }
+    digits.is_empty().then_some(amounts)
+}
+
+/// Returns the frequency repeating the number of seconds, with the largest unit dividing it.
+fn frequency_of_seconds(seconds: i64) -> Option<Frequency> {
+    [
+        (SECONDS_PER_WEEK, FreqKind::Weekly),
+        (SECONDS_PER_DAY, FreqKind::Daily),
+        (SECONDS_PER_HOUR, FreqKind::Hourly),
+        (SECONDS_PER_MINUTE, FreqKind::Minutely),
+        (1, FreqKind::Secondly),
+    ]
+    .into_iter()
+    .find(|(unit, _)| seconds % unit == 0)
+    .and_then(|(unit, kind)| plain_frequency(kind, seconds / unit, OverflowPolicy::Skip))
+}
+
+/// Returns the frequency of the kind without filters, with the overflow policy of the monthly
+/// and yearly frequencies, or None when the interval is not valid.
+fn plain_frequency(kind: FreqKind, interval: i64, overflow: OverflowPolicy) -> Option<Frequency> {
+    let interval = u32::try_from(interval).ok().and_then(NonZeroU32::new)?;
+    let frequency = match kind {
+        FreqKind::Secondly => Frequency::Secondly {
+            interval,
+            by_day: vec![],
+            by_time: vec![],
+        },
+        FreqKind::Minutely => Frequency::Minutely {
+            interval,
+            by_day: vec![],
+            by_time: vec![],
+            by_hour_range: None,
+        },
+        FreqKind::Hourly => Frequency::Hourly {
+            interval,
+            by_day: vec![],
+            by_time: vec![],
+            by_hour_range: None,
+        },
+        FreqKind::Daily => Frequency::Daily {
+            interval,
+            by_day: vec![],
+            by_time: vec![],
+        },
+        FreqKind::Weekly => Frequency::Weekly {
+            interval,
+            by_day: vec![],
+            by_time: vec![],
+            by_day_time: vec![],
+        },
+        FreqKind::Monthly => Frequency::Monthly {
+            interval,
+            by_month_day: vec![],
+            by_day: vec![],
+            nth_weekdays: vec![],
+            by_time: vec![],
+            overflow,
+        },
+        FreqKind::Yearly => Frequency::Yearly {
+            interval,
+            by_month: vec![],
+            by_monthly_date: vec![],
+            nth_weekdays: vec![],
+            by_week_number: vec![],
+            by_time: vec![],
+            overflow,
+        },
+        FreqKind::BusinessDaily => return None,
+    };
+    Some(frequency)
+}
+
+/// Verifies if the frequency skips the months or the years without the day of the start, which
+/// the durations of months and years end on their last day instead.
+fn skips_short_months(frequency: &Frequency, start: &DateTime<Utc>) -> bool {
+    frequency.overflow() == OverflowPolicy::Skip
+        && match frequency.freq_kind() {
+            FreqKind::Monthly => start.day() > 28,
+            FreqKind::Yearly => start.month() == 2 && start.day() == 29,
+            _ => false,
+        }
+}
+
+/// Returns the duration repeated by the frequency, or None when it has filters.
+fn format_period(frequency: &Frequency) -> Option<String> {
+    let kind = frequency.freq_kind();
+    let interval = frequency.interval();
+    // The days of the start missing in some months are skipped or clamped, not moved
+    let overflow = match frequency.overflow() {
+        OverflowPolicy::NextValid => return None,
+        overflow => overflow,
+    };
+    if plain_frequency(kind, i64::from(interval.get()), overflow).as_ref() != Some(frequency) {
+        return None;
+    }
+    let period = match kind {
+        FreqKind::Secondly => format!("PT{interval}S"),
+        FreqKind::Minutely => format!("PT{interval}M"),
+        FreqKind::Hourly => format!("PT{interval}H"),
+        FreqKind::Daily => format!("P{interval}D"),
+        FreqKind::Weekly => format!("P{interval}W"),
+        FreqKind::Monthly => format!("P{interval}M"),
+        FreqKind::Yearly => format!("P{interval}Y"),
+        FreqKind::BusinessDaily => return None,
+    };
+    Some(period)
+}
+
+#[cfg(test)]
+mod test_iso8601 {
+    use crate::test_utils::date;
+    use crate::{FreqKind, Recurrence, RecurrenceErrorKind};
+    use chrono::{DateTime, Duration, Utc};
+    use std::str::FromStr;
+
+    #[test]
+    fn parses_the_repetitions_and_the_period() {
+        let recurrence = Recurrence::from_iso8601_repeat("R5/2023-01-01T00:00:00Z/P1D").unwrap();
+        let events: Vec<DateTime<Utc>> = recurrence.iter().collect();
+        assert_eq!(events.len(), 5);
+        assert_eq!(events[4], date("2023-01-05T00:00:00Z"));
+
+        let recurrence = Recurrence::from_iso8601_repeat("R/2023-01-31T10:00:00Z/P1M").unwrap();
+        assert!(recurrence.is_infinite());
+        assert_eq!(recurrence.frequency.freq_kind(), FreqKind::Monthly);
+        assert_eq!(
+            recurrence.next_from(&recurrence.start),
+            Some(date("2023-02-28T10:00:00Z"))
+        );
+    }
+
+    #[test]
+    fn months_and_years_end_on_the_last_day_of_the_shorter_months() {
+        let recurrence = Recurrence::from_iso8601_repeat("R3/2023-01-31T00:00:00Z/P1M").unwrap();
+        let events: Vec<DateTime<Utc>> = recurrence.iter().collect();
+        assert_eq!(
+            events,
+            vec![
+                date("2023-01-31T00:00:00Z"),
+                date("2023-02-28T00:00:00Z"),
+                date("2023-03-31T00:00:00Z"),
+            ]
+        );
+        let recurrence = Recurrence::from_iso8601_repeat("R2/2024-02-29/P12M").unwrap();
+        let events: Vec<DateTime<Utc>> = recurrence.iter().collect();
+        assert_eq!(
+            events,
+            vec![date("2024-02-29T00:00:00Z"), date("2025-02-28T00:00:00Z")]
+        );
+        assert_eq!(recurrence.to_iso8601_repeat().unwrap(), "R2/2024-02-29/P1Y");
+
+        // Skipping the shorter months is not a duration
+        let rule = "FREQ=MONTHLY;INTERVAL=1;DTSTART=2023-01-31T00:00:00Z";
+        assert!(Recurrence::from_str(rule)
+            .unwrap()
+            .to_iso8601_repeat()
+            .is_err());
+        let rule = "FREQ=MONTHLY;INTERVAL=1;DTSTART=2023-01-28T00:00:00Z";
+        assert_eq!(
+            Recurrence::from_str(rule)
+                .unwrap()
+                .to_iso8601_repeat()
+                .unwrap(),
+            "R/2023-01-28T00:00:00Z/P1M"
+        );
+    }
+
+    #[test]
+    fn uses_the_largest_unit_of_the_period() {
+        for (value, kind, interval) in [
+            ("R/2023-01-01T00:00:00Z/P14D", FreqKind::Weekly, 2),
+            ("R/2023-01-01T00:00:00Z/P1DT12H", FreqKind::Hourly, 36),
+            ("R/2023-01-01T00:00:00Z/PT90S", FreqKind::Secondly, 90),
+            ("R/2023-01-01T00:00:00Z/PT120M", FreqKind::Hourly, 2),
+            ("R/2023-01-01T00:00:00Z/P1Y6M", FreqKind::Monthly, 18),
+            ("R/2023-01-01T00:00:00Z/P2Y", FreqKind::Yearly, 2),
+            (
+                "R/2023-01-01T00:00:00Z/2023-01-01T00:15:00Z",
+                FreqKind::Minutely,
+                15,
+            ),
+        ] {
+            let recurrence = Recurrence::from_iso8601_repeat(value).unwrap();
+            assert_eq!(recurrence.frequency.freq_kind(), kind, "{value}");
+            assert_eq!(recurrence.frequency.interval().get(), interval, "{value}");
+        }
+    }
+
+    #[test]
+    fn all_day_recurrences() {
+        let recurrence = Recurrence::from_iso8601_repeat("R2/2023-06-12/P1Y").unwrap();
+        assert!(recurrence.all_day);
+        let events: Vec<DateTime<Utc>> = recurrence.iter().collect();
+        assert_eq!(
+            events,
+            vec![date("2023-06-12T00:00:00Z"), date("2024-06-12T00:00:00Z")]
+        );
+        assert_eq!(recurrence.to_iso8601_repeat().unwrap(), "R2/2023-06-12/P1Y");
+        assert!(Recurrence::from_iso8601_repeat("R/2023-06-12/PT1H").is_err());
+    }
+
+    #[test]
+    fn invalid_repeating_intervals() {
+        for value in [
+            "",
+            "R5",
+            "R5/2023-01-01T00:00:00Z",
+            "5/2023-01-01T00:00:00Z/P1D",
+            "R-1/2023-01-01T00:00:00Z/P1D",
+            "R/2023-01-01/P1D/P1D",
+            "R/yesterday/P1D",
+            "R/2023-01-01T00:00:00Z/P",
+            "R/2023-01-01T00:00:00Z/PT",
+            "R/2023-01-01T00:00:00Z/P0D",
+            "R/2023-01-01T00:00:00Z/P1D2W",
+            "R/2023-01-01T00:00:00Z/P1DD",
+            "R/2023-01-01T00:00:00Z/P1H",
+            "R/2023-01-01T00:00:00Z/P1M1D",
+            "R/2023-01-01T00:00:00Z/P99999999999W",
+            "R/2023-01-01T00:00:00Z/2022-12-31T00:00:00Z",
+        ] {
+            assert!(Recurrence::from_iso8601_repeat(value).is_err(), "{value}");
+        }
+        let error = Recurrence::from_iso8601_repeat("R0/2023-01-01T00:00:00Z/P1D").unwrap_err();
+        assert_eq!(error.kind, RecurrenceErrorKind::NoEvents);
+    }
+
+    #[test]
+    fn round_trips() {
+        for value in [
+            "R/2023-01-01T00:00:00Z/PT15S",
+            "R10/2023-01-01T08:30:00Z/PT30M",
+            "R1/2023-01-01T00:00:00Z/P3D",
+            "R4/2023-01-31T00:00:00Z/P1M",
+        ] {
+            let recurrence = Recurrence::from_iso8601_repeat(value).unwrap();
+            assert_eq!(recurrence.to_iso8601_repeat().unwrap(), value);
+        }
+        let recurrence = Recurrence::from_str(
+            "FREQ=DAILY;INTERVAL=2;DTSTART=2023-01-01T00:00:00Z;DTEND=2023-01-08T12:00:00Z",
+        )
+        .unwrap();
+        assert_eq!(
+            recurrence.to_iso8601_repeat().unwrap(),
+            "R4/2023-01-01T00:00:00Z/P2D"
+        );
+        let recurrence =
+            Recurrence::from_str("FREQ=DAILY;INTERVAL=2;DTSTART=2023-01-01T00:00:00Z;COUNT=3")
+                .unwrap();
+        assert_eq!(
+            recurrence.to_iso8601_repeat().unwrap(),
+            "R3/2023-01-01T00:00:00Z/P2D"
+        );
+    }
+
+    #[test]
+    fn cannot_represent_filters_nor_exdates() {
+        let rule = "FREQ=DAILY;INTERVAL=1;DTSTART=2023-01-01T00:00:00Z";
+        let recurrence = Recurrence::from_str(rule).unwrap();
+        for recurrence in [
+            recurrence
+                .clone()
+                .with_exdates(vec![date("2023-01-02T00:00:00Z")]),
+            recurrence
+                .clone()
+                .with_unknown_params(vec![("X-NAME".to_string(), "standup".to_string())]),
+            Recurrence::from_str(&format!("{rule};DURATION=PT1H")).unwrap(),
+            Recurrence::from_str(&format!("{rule};X-BUSINESS=TRUE")).unwrap(),
+            Recurrence::from_str(&format!("{rule};BYHOUR=9")).unwrap(),
+        ] {
+            assert!(recurrence.to_iso8601_repeat().is_err());
+        }
+        assert_eq!(recurrence.duration, Duration::zero());
+    }
+}
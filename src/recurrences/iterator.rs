@@ -0,0 +1,324 @@
+use crate::frequencies::{Frequency, OccurrenceEngine};
+use crate::recurrences::models::max_date;
+use crate::recurrences::Recurrence;
+use crate::trace;
+use alloc::borrow::Cow;
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use core::iter::Peekable;
+
+/// An iterator over the event dates of a `Recurrence`.
+///
+/// It is created by `Recurrence::iter()` or `Recurrence::into_iter()`, and keeps the
+/// iteration state apart from the recurrence definition.
+#[derive(Debug, Clone)]
+pub struct OccurrenceIter<'a, E: OccurrenceEngine = Frequency> {
+    recurrence: Cow<'a, Recurrence<E>>,
+
+    /// Next candidate date, or None once the recurrence is exhausted
+    current_date: Option<DateTime<Utc>>,
+
+    /// Last date of the events, the maximum date when the recurrence doesn't end
+    end: DateTime<Utc>,
+
+    /// Number of events yielded or skipped so far
+    count: usize,
+
+    /// Number of events of the rules so far, counting the excluded and the skipped ones like
+    /// the `COUNT` of the rule does
+    rule_events: u64,
+
+    /// Whether the iteration stopped at a limit of the recurrence before its end
+    truncated: bool,
+}
+
+impl<'a, E: OccurrenceEngine> OccurrenceIter<'a, E> {
+    pub(crate) fn new(recurrence: Cow<'a, Recurrence<E>>) -> Self {
+        let current_date = Some(recurrence.start);
+        let end = recurrence.end().unwrap_or_else(max_date);
+        let not_before = recurrence.not_before();
+        let mut occurrences = OccurrenceIter {
+            recurrence,
+            current_date,
+            end,
+            count: 0,
+            rule_events: 0,
+            truncated: false,
+        };
+        if let Some(not_before) = not_before {
+            occurrences.fast_forward(&not_before);
+        }
+        occurrences
+    }
+
+    /// Moves the iteration forward towards the date, without going through the previous
+    /// events nor skipping the events at or after the date. Recurrences with a maximum number
+    /// of occurrences are not moved, as the previous events must be counted, nor the ones with
+    /// a `COUNT` unless their events are evenly spaced.
+    pub(crate) fn fast_forward(&mut self, date: &DateTime<Utc>) {
+        // The events before the date can be moved after it by the jitter
+        let date = &match &self.recurrence.jitter {
+            Some(jitter) => date.checked_sub_signed(jitter.max).unwrap_or(*date),
+            None => *date,
+        };
+        let current_date = match self.current_date {
+            Some(current_date) if *date > current_date => current_date,
+            _ => return,
+        };
+        if self.recurrence.max_occurrences.is_some() {
+            return;
+        }
+        let frequency = &self.recurrence.frequency;
+        if let Some(step) = frequency.fixed_step() {
+            let span = date.signed_duration_since(current_date);
+            let steps = span.num_milliseconds() / step.num_milliseconds();
+            self.advance(step, steps);
+            self.rule_events = self.rule_events.saturating_add(steps.unsigned_abs());
+            return;
+        }
+        if self.recurrence.count.is_some() {
+            return;
+        }
+        // The iteration goes on from the last event before the date, which the frequency
+        // finds without going through every previous event when it can
+        let previous_event = frequency
+            .previous_event(&self.recurrence.anchor(), date)
+            .filter(|event| *event > current_date);
+        if let Some(previous_event) = previous_event {
+            trace::debug!(from = %current_date, to = %previous_event, "Fast forward");
+            self.current_date = Some(previous_event);
+        }
+    }
+
+    /// Resumes the iteration after the event at the date, as if the given number of events were
+    /// already yielded, without going through the previous events.
+    pub(crate) fn resume_after(&mut self, date: &DateTime<Utc>, count: usize) {
+        if *date >= self.recurrence.start {
+            self.current_date = self
+                .recurrence
+                .frequency
+                .next_event(&self.recurrence.anchor(), date)
+                .filter(|next_date| next_date > date);
+        }
+        self.count = count;
+        if self.recurrence.count.is_some() {
+            self.rule_events = self.recurrence.rule_events_until(date);
+        }
+    }
+
+    /// Moves the next candidate date the given number of evenly spaced steps forward.
+    fn advance(&mut self, step: Duration, steps: i64) {
+        self.current_date = self.current_date.and_then(|current_date| {
+            let span = steps.checked_mul(step.num_milliseconds())?;
+            current_date.checked_add_signed(Duration::milliseconds(span))
+        });
+    }
+
+    /// Returns the recurrence being iterated.
+    pub fn recurrence(&self) -> &Recurrence<E> {
+        &self.recurrence
+    }
+
+    /// Verifies if the iteration stopped because the `max_occurrences` or the `max_horizon`
+    /// limit of the recurrence was reached, while there were more events before its end.
+    ///
+    /// Reaching the `COUNT` of the rule is the end of the recurrence, not a truncation.
+    /// Examples:
+    /// ```
+    /// use std::str::FromStr;
+    /// use rrules::Recurrence;
+    ///
+    /// let recurrence = Recurrence::from_str("FREQ=DAILY;INTERVAL=1;DTSTART=2023-01-01T00:00:00Z")
+    ///     .unwrap()
+    ///     .with_max_occurrences(3);
+    /// let mut events = recurrence.iter();
+    /// assert_eq!(events.by_ref().count(), 3);
+    /// assert!(events.is_truncated());
+    /// ```
+    pub fn is_truncated(&self) -> bool {
+        self.truncated
+    }
+
+    /// Verifies if the next candidate date is before the `not_before` date of the recurrence,
+    /// so its events are skipped instead of yielded.
+    fn is_skipping(&self) -> bool {
+        self.current_date
+            .zip(self.recurrence.not_before())
+            .is_some_and(|(current_date, not_before)| current_date < not_before)
+    }
+
+    /// Verifies if the event is past one of the limits of the recurrence.
+    fn exceeds_limits(&self, date: &DateTime<Utc>) -> bool {
+        let recurrence = &self.recurrence;
+        recurrence
+            .max_occurrences
+            .is_some_and(|max_occurrences| self.count >= max_occurrences)
+            || recurrence
+                .max_horizon
+                .is_some_and(|max_horizon| *date > max_horizon)
+    }
+}
+
+impl<'a, E: OccurrenceEngine> Iterator for OccurrenceIter<'a, E> {
+    type Item = DateTime<Utc>;
+
+    /// Returns the next event date in the recurrence.
+    /// Returns None if there are no more events in the recurrence.
+    fn next(&mut self) -> Option<Self::Item> {
+        let _span = trace::trace_span!("occurrences", start = %self.recurrence.start);
+        let frequency = &self.recurrence.frequency;
+        while let Some(current_date) = self.current_date {
+            if current_date > self.end {
+                trace::debug!(end = %self.end, "Reached the end of the recurrence");
+                self.current_date = None;
+                return None;
+            }
+            // A next date that doesn't move forward ends the iteration rather than looping
+            self.current_date = frequency
+                .next_event(&self.recurrence.anchor(), &current_date)
+                .filter(|next_date| *next_date > current_date);
+            // The start date is only an event when it matches the frequency, the following
+            // dates are events of the frequency
+            let recurrence = &self.recurrence;
+            if current_date == recurrence.start
+                && !frequency.contains(&recurrence.start, &current_date)
+            {
+                trace::trace!(date = %current_date, "Skipped date");
+                continue;
+            }
+            if recurrence
+                .count
+                .is_some_and(|count| self.rule_events >= u64::from(count))
+            {
+                trace::debug!(count = self.rule_events, "Reached the count of the rule");
+                self.current_date = None;
+                return None;
+            }
+            // The excluded dates are counted by the rule, but not yielded
+            self.rule_events += 1;
+            if recurrence.exdates.contains(&current_date) {
+                trace::trace!(date = %current_date, "Skipped date");
+                continue;
+            }
+            if self.exceeds_limits(&current_date) {
+                trace::debug!(count = self.count, "Reached the limits of the iteration");
+                self.current_date = None;
+                self.truncated = true;
+                return None;
+            }
+            self.count += 1;
+            // The events before the not_before date are counted by the limits, but not yielded
+            if self
+                .recurrence
+                .not_before()
+                .is_some_and(|not_before| current_date < not_before)
+            {
+                trace::trace!(date = %current_date, "Skipped event");
+                continue;
+            }
+            trace::trace!(date = %current_date, "Event");
+            return Some(self.recurrence.jittered(current_date));
+        }
+        None
+    }
+
+    /// Returns the nth next event date, computing it directly when the events are evenly
+    /// spaced and none of them are excluded.
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        match self.recurrence.frequency.fixed_step() {
+            Some(step) if self.recurrence.exdates.is_empty() && !self.is_skipping() => {
+                self.advance(step, i64::try_from(n).ok()?);
+                self.count = self.count.saturating_add(n);
+                self.rule_events = self.rule_events.saturating_add(n as u64);
+                self.next()
+            }
+            _ => {
+                for _ in 0..n {
+                    self.next()?;
+                }
+                self.next()
+            }
+        }
+    }
+}
+
+/// An iterator over the event dates of a `Recurrence`, without their time.
+///
+/// It is created by `Recurrence::dates()`, and is mostly useful for all-day recurrences.
+#[derive(Debug, Clone)]
+pub struct DateIter<'a, E: OccurrenceEngine = Frequency> {
+    occurrences: OccurrenceIter<'a, E>,
+}
+
+impl<'a, E: OccurrenceEngine> DateIter<'a, E> {
+    pub(crate) fn new(occurrences: OccurrenceIter<'a, E>) -> Self {
+        DateIter { occurrences }
+    }
+}
+
+impl<'a, E: OccurrenceEngine> Iterator for DateIter<'a, E> {
+    type Item = NaiveDate;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.occurrences.next().map(|date| date.date_naive())
+    }
+}
+
+/// An event of a `Recurrence`, along with its position among the events.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Occurrence {
+    /// Position of the event, 0 for the first one
+    pub index: usize,
+
+    /// Start date of the event
+    pub start: DateTime<Utc>,
+
+    /// End date of the event, its start plus the duration of the recurrence
+    pub end: DateTime<Utc>,
+
+    /// Whether it is the first event
+    pub is_first: bool,
+
+    /// Whether it is the last event, only known when the recurrence has an end date or
+    /// a limit of occurrences
+    pub is_last: bool,
+}
+
+/// An iterator over the events of a `Recurrence`, yielding every event as an `Occurrence`.
+///
+/// It is created by `Recurrence::detailed_iter()`, and looks one event ahead to know
+/// which one is the last.
+#[derive(Debug, Clone)]
+pub struct DetailedIter<'a, E: OccurrenceEngine = Frequency> {
+    occurrences: Peekable<OccurrenceIter<'a, E>>,
+    duration: Duration,
+    index: usize,
+}
+
+impl<'a, E: OccurrenceEngine> DetailedIter<'a, E> {
+    pub(crate) fn new(occurrences: OccurrenceIter<'a, E>) -> Self {
+        DetailedIter {
+            duration: occurrences.recurrence().duration,
+            occurrences: occurrences.peekable(),
+            index: 0,
+        }
+    }
+}
+
+impl<'a, E: OccurrenceEngine> Iterator for DetailedIter<'a, E> {
+    type Item = Occurrence;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let start = self.occurrences.next()?;
+        let occurrence = Occurrence {
+            index: self.index,
+            start,
+            end: start
+                .checked_add_signed(self.duration)
+                .unwrap_or(DateTime::<Utc>::MAX_UTC),
+            is_first: self.index == 0,
+            is_last: self.occurrences.peek().is_none(),
+        };
+        self.index += 1;
+        Some(occurrence)
+    }
+}
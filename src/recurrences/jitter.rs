@@ -0,0 +1,203 @@
+use crate::frequencies::OccurrenceEngine;
+use crate::recurrences::Recurrence;
+use alloc::borrow::Cow;
+use chrono::{DateTime, Duration, Utc};
+
+/// A reproducible offset of the events of a recurrence, E.g. to spread the load of recurring jobs.
+///
+/// Every event is moved by a pseudo-random amount between `-max` and `max`, in milliseconds,
+/// computed from the seed and the date of the event, so the same event is always moved by the
+/// same amount, whatever the iteration it is yielded by.
+///
+/// Examples:
+/// ```
+/// use std::str::FromStr;
+/// use chrono::{DateTime, Duration, Utc};
+/// use rrules::Jitter;
+///
+/// let jitter = Jitter::new(Duration::minutes(5), 42);
+/// let event = DateTime::<Utc>::from_str("2023-01-01T09:00:00Z").unwrap();
+/// let offset = jitter.offset(&event);
+/// assert!(offset.abs() <= Duration::minutes(5));
+/// assert_eq!(jitter.offset(&event), offset);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Jitter {
+    /// Largest offset of the events, before or after their date
+    pub max: Duration,
+
+    /// Seed of the offsets, the same seed gives the same offsets
+    pub seed: u64,
+}
+
+impl Jitter {
+    /// Creates a jitter of up to the given offset, before or after the events.
+    pub fn new(max: Duration, seed: u64) -> Self {
+        Jitter {
+            max: max.abs(),
+            seed,
+        }
+    }
+
+    /// Returns the offset of the event at the given date.
+    pub fn offset(&self, date: &DateTime<Utc>) -> Duration {
+        let max = self.max.abs().num_milliseconds();
+        if max == 0 {
+            return Duration::zero();
+        }
+        let hash = split_mix(self.seed ^ split_mix(date.timestamp_millis() as u64));
+        // The range has at most 2^64 - 1 values, as the offsets fit in an i64 of milliseconds
+        let range = (max as u64).saturating_mul(2).saturating_add(1);
+        let offset = i128::from(hash % range) - i128::from(max);
+        Duration::milliseconds(offset as i64)
+    }
+
+    /// Returns the date of the event after the offset, or the date itself when it is out of range.
+    pub(crate) fn apply(&self, date: DateTime<Utc>) -> DateTime<Utc> {
+        date.checked_add_signed(self.offset(&date)).unwrap_or(date)
+    }
+}
+
+/// Mixes the bits of the value, from the SplitMix64 generator.
+fn split_mix(value: u64) -> u64 {
+    let mut z = value.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+impl<E: OccurrenceEngine> Recurrence<E> {
+    /// Offsets every event by a reproducible amount between `-max` and `max`, E.g. to avoid that
+    /// many recurring jobs start at the same instant.
+    ///
+    /// The iterations yield the events after their offset, and the same seed always gives the
+    /// same offsets. The date rules of the recurrence, like `contains`, `position_of`, the end
+    /// date or `with_count`, keep using the dates before the offset, and the jitter is not
+    /// serialized. Events stay in order while `max` is less than half the time between them.
+    /// Examples:
+    /// ```
+    /// use std::str::FromStr;
+    /// use chrono::{DateTime, Duration, Utc};
+    /// use rrules::Recurrence;
+    ///
+    /// let daily = Recurrence::from_str("FREQ=DAILY;INTERVAL=1;DTSTART=2023-01-01T09:00:00Z").unwrap();
+    /// let jittered = daily.clone().with_jitter(Duration::minutes(10), 7);
+    /// let events: Vec<DateTime<Utc>> = jittered.iter().take(3).collect();
+    /// assert_eq!(events, jittered.iter().take(3).collect::<Vec<_>>());
+    /// for (event, date) in events.iter().zip(daily.iter()) {
+    ///     assert!((*event - date).abs() <= Duration::minutes(10));
+    /// }
+    /// assert_eq!(jittered.to_string(), daily.to_string());
+    /// ```
+    pub fn with_jitter(mut self, max: Duration, seed: u64) -> Self {
+        self.jitter = Some(Jitter::new(max, seed));
+        self
+    }
+
+    /// Removes the jitter of the events.
+    pub fn without_jitter(mut self) -> Self {
+        self.jitter = None;
+        self
+    }
+
+    /// Returns the recurrence whose events are the dates of its rules, without their jitter.
+    pub(crate) fn unjittered(&self) -> Cow<'_, Self> {
+        match self.jitter {
+            Some(_) => Cow::Owned(self.clone().without_jitter()),
+            None => Cow::Borrowed(self),
+        }
+    }
+
+    /// Returns the date of the event after its jitter, if any.
+    pub(crate) fn jittered(&self, date: DateTime<Utc>) -> DateTime<Utc> {
+        match &self.jitter {
+            Some(jitter) => jitter.apply(date),
+            None => date,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_jitter {
+    use crate::test_utils::date;
+    use crate::{Jitter, Recurrence};
+    use chrono::{DateTime, Duration, Utc};
+    use std::str::FromStr;
+
+    fn hourly() -> Recurrence {
+        Recurrence::from_str("FREQ=HOURLY;INTERVAL=1;DTSTART=2023-01-01T00:00:00Z").unwrap()
+    }
+
+    #[test]
+    fn offsets_are_within_the_max_and_reproducible() {
+        let jitter = Jitter::new(Duration::seconds(-90), 1);
+        assert_eq!(jitter.max, Duration::seconds(90));
+        let offsets: Vec<Duration> = hourly()
+            .iter()
+            .take(1000)
+            .map(|event| jitter.offset(&event))
+            .collect();
+        assert!(offsets
+            .iter()
+            .all(|offset| offset.abs() <= Duration::seconds(90)));
+        assert!(offsets.iter().any(|offset| *offset < Duration::zero()));
+        assert!(offsets.iter().any(|offset| *offset > Duration::zero()));
+        let again: Vec<Duration> = hourly()
+            .iter()
+            .take(1000)
+            .map(|event| jitter.offset(&event))
+            .collect();
+        assert_eq!(offsets, again);
+        assert_ne!(
+            offsets[0..10],
+            hourly()
+                .iter()
+                .take(10)
+                .map(|event| Jitter::new(Duration::seconds(90), 2).offset(&event))
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            Jitter::new(Duration::zero(), 1).offset(&date("2023-01-01T00:00:00Z")),
+            Duration::zero()
+        );
+    }
+
+    #[test]
+    fn iterations_yield_the_same_offsets() {
+        let recurrence = hourly().with_jitter(Duration::minutes(5), 3);
+        let events: Vec<DateTime<Utc>> = recurrence.iter().take(48).collect();
+        assert!(events.windows(2).all(|pair| pair[0] < pair[1]));
+
+        let from = date("2023-01-01T12:00:00Z");
+        let to = date("2023-01-01T18:00:00Z");
+        let expected: Vec<DateTime<Utc>> = events
+            .iter()
+            .copied()
+            .filter(|event| *event >= from && *event <= to)
+            .collect();
+        assert_eq!(recurrence.between(&from, &to).collect::<Vec<_>>(), expected);
+        assert_eq!(recurrence.nth(20), Some(events[20]));
+        assert_eq!(recurrence.next_from(&events[30]), Some(events[31]));
+    }
+
+    #[test]
+    fn rules_use_the_dates_before_the_offset() {
+        let recurrence = hourly().with_jitter(Duration::minutes(5), 3);
+        let bounded = recurrence.with_count(10).unwrap();
+        assert_eq!(bounded.end(), Some(date("2023-01-01T09:00:00Z")));
+        assert_eq!(bounded.iter().count(), 10);
+        assert_eq!(bounded.jitter, recurrence.jitter);
+        assert_eq!(
+            recurrence.position_of(&date("2023-01-01T05:00:00Z")),
+            Some(5)
+        );
+
+        let (before, after) = recurrence.split_at(&date("2023-01-01T05:30:00Z")).unwrap();
+        assert_eq!(after.not_before(), Some(date("2023-01-01T06:00:00Z")));
+        assert_eq!(before.iter().count(), 6);
+
+        assert_eq!(recurrence.to_string(), hourly().to_string());
+        assert_ne!(recurrence, hourly());
+        assert_eq!(recurrence.clone().without_jitter(), hourly());
+    }
+}
@@ -0,0 +1,190 @@
+use crate::recurrences::{Recurrence, RecurrenceInvalid};
+use core::fmt::{Display, Formatter};
+use core::str::FromStr;
+use std::error::Error;
+use std::io::{self, BufRead, Lines};
+
+/// Parses a recurrence from every line of the reader, lazily.
+///
+/// Empty lines are skipped, and the errors carry the number of their line, starting at 1.
+/// The iteration stops after the first error reading from the reader, while the lines
+/// that are not valid recurrences are reported and the following ones parsed.
+///
+/// Examples:
+/// ```
+/// use std::io::Cursor;
+/// use rrules::parse_lines;
+///
+/// let rules = "FREQ=DAILY;INTERVAL=1;DTSTART=2023-01-01T12:00:00Z\n\nFREQ=DAILY;INTERVAL=0\n";
+/// let results: Vec<_> = parse_lines(Cursor::new(rules)).collect();
+/// assert_eq!(results.len(), 2);
+/// assert!(results[0].is_ok());
+/// assert_eq!(results[1].as_ref().unwrap_err().line(), 3);
+/// ```
+pub fn parse_lines<R: BufRead>(reader: R) -> ParseLines<R> {
+    ParseLines {
+        lines: reader.lines(),
+        line: 0,
+        failed: false,
+    }
+}
+
+/// An iterator over the recurrences parsed from the lines of a reader.
+///
+/// It is created by `parse_lines()`.
+#[derive(Debug)]
+pub struct ParseLines<R> {
+    lines: Lines<R>,
+
+    /// Number of the last line read
+    line: usize,
+
+    /// Whether reading from the reader failed, which ends the iteration
+    failed: bool,
+}
+
+impl<R: BufRead> Iterator for ParseLines<R> {
+    type Item = Result<Recurrence, LineError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed {
+            return None;
+        }
+        loop {
+            let content = self.lines.next()?;
+            self.line += 1;
+            let line = self.line;
+            let content = match content {
+                Ok(content) => content,
+                Err(error) => {
+                    self.failed = true;
+                    return Some(Err(LineError::Io { line, error }));
+                }
+            };
+            let content = content.trim();
+            if content.is_empty() {
+                continue;
+            }
+            return Some(
+                Recurrence::from_str(content).map_err(|error| LineError::Invalid { line, error }),
+            );
+        }
+    }
+}
+
+/// The error of a line that cannot be read or parsed by `parse_lines()`.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum LineError {
+    /// The line cannot be read from the reader
+    Io { line: usize, error: io::Error },
+    /// The line is not a valid recurrence
+    Invalid {
+        line: usize,
+        error: RecurrenceInvalid,
+    },
+}
+
+impl LineError {
+    /// Returns the number of the line of the error, starting at 1.
+    pub fn line(&self) -> usize {
+        match self {
+            LineError::Io { line, .. } | LineError::Invalid { line, .. } => *line,
+        }
+    }
+}
+
+impl Display for LineError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            LineError::Io { line, error } => write!(f, "Cannot read line {line}: {error}"),
+            LineError::Invalid { line, error } => write!(f, "Invalid line {line}: {error}"),
+        }
+    }
+}
+
+impl Error for LineError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            LineError::Io { error, .. } => Some(error),
+            LineError::Invalid { error, .. } => Some(error),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_lines {
+    use crate::{parse_lines, LineError, RecurrenceErrorKind};
+    use std::io::{BufReader, Cursor, Read};
+
+    #[test]
+    fn parses_every_line() {
+        let rules = "FREQ=DAILY;INTERVAL=1;DTSTART=2023-01-01T12:00:00Z\r\n  \r\n\
+            FREQ=WEEKLY;INTERVAL=2;BYDAY=MO;DTSTART=2023-01-02T09:00:00Z\r\n";
+        let recurrences: Vec<_> = parse_lines(Cursor::new(rules))
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(recurrences.len(), 2);
+        assert_eq!(
+            recurrences[1].to_string(),
+            "FREQ=WEEKLY;INTERVAL=2;BYDAY=MO;DTSTART=2023-01-02T09:00:00Z"
+        );
+    }
+
+    #[test]
+    fn reports_the_invalid_lines_and_keeps_parsing() {
+        let rules = "FREQ=DAILY;INTERVAL=1\n\
+            FREQ=DAILY;INTERVAL=1;DTSTART=2023-01-01T12:00:00Z\n\
+            \n\
+            FREQ=DAILY;INTERVAL=1;DTSTART=2023-01-01T12:00:00Z;DURATION=PT49H";
+        let results: Vec<_> = parse_lines(Cursor::new(rules)).collect();
+        assert_eq!(results.len(), 3);
+        assert!(results[1].is_ok());
+        let lines: Vec<usize> = results
+            .iter()
+            .filter_map(|result| result.as_ref().err())
+            .map(LineError::line)
+            .collect();
+        assert_eq!(lines, vec![1, 4]);
+        match &results[2] {
+            Err(LineError::Invalid { line: 4, error }) => {
+                assert_eq!(error.kind.code(), "duration_too_long");
+            }
+            other => panic!("Expected an invalid line, got {other:?}"),
+        }
+        assert!(matches!(
+            &results[0],
+            Err(LineError::Invalid { error, .. }) if matches!(error.kind, RecurrenceErrorKind::Missing { .. })
+        ));
+        assert!(results[2]
+            .as_ref()
+            .unwrap_err()
+            .to_string()
+            .starts_with("Invalid line 4: "));
+    }
+
+    #[test]
+    fn stops_after_a_read_error() {
+        let mut bytes = b"FREQ=DAILY;INTERVAL=1;DTSTART=2023-01-01T12:00:00Z\n".to_vec();
+        bytes.extend_from_slice(&[0xff, 0xfe, b'\n']);
+        bytes.extend_from_slice(b"FREQ=DAILY;INTERVAL=1;DTSTART=2023-01-01T12:00:00Z\n");
+        let reader = BufReader::new(Cursor::new(bytes).take(1024));
+        let results: Vec<_> = parse_lines(reader).collect();
+        assert_eq!(results.len(), 2);
+        assert!(matches!(results[1], Err(LineError::Io { line: 2, .. })));
+    }
+
+    #[test]
+    fn parses_lazily() {
+        let endless = Cursor::new("FREQ=DAILY;INTERVAL=1;DTSTART=2023-01-01T12:00:00Z\n")
+            .chain(std::io::repeat(b'\n').take(u64::MAX));
+        let mut results = parse_lines(BufReader::new(endless));
+        assert!(results.next().unwrap().is_ok());
+    }
+
+    #[test]
+    fn empty_readers_have_no_recurrences() {
+        assert_eq!(parse_lines(Cursor::new("")).count(), 0);
+        assert_eq!(parse_lines(Cursor::new("\n \n")).count(), 0);
+    }
+}
@@ -0,0 +1,343 @@
+use crate::recurrences::Recurrence;
+use alloc::borrow::Cow;
+use alloc::vec::Vec;
+use chrono::{DateTime, Duration, Utc};
+use core::ops::Range;
+
+/// A recurrence with its event dates computed in advance for a rolling horizon, for
+/// backends that query the same recurrence many times.
+///
+/// The events of the window, from its start to the start plus the horizon, are kept sorted
+/// so that `contains`, `between` and `next_after` are binary searches. Queries outside the
+/// window are answered by the recurrence itself. The window is moved with `advance_to` and
+/// grown with `extend_to`, computing only the new events, and the events are computed
+/// again when the recurrence changes.
+///
+/// Examples:
+/// ```
+/// use std::str::FromStr;
+/// use chrono::{DateTime, Duration, Utc};
+/// use rrules::{MaterializedRecurrence, Recurrence};
+///
+/// let recurrence = Recurrence::from_str("FREQ=DAILY;INTERVAL=1;DTSTART=2023-01-01T09:00:00Z").unwrap();
+/// let now = DateTime::<Utc>::from_str("2023-03-01T00:00:00Z").unwrap();
+/// let mut materialized = MaterializedRecurrence::new(recurrence, now, Duration::days(365));
+/// assert_eq!(materialized.events().len(), 365);
+/// assert_eq!(
+///     materialized.next_after(&now),
+///     Some(DateTime::<Utc>::from_str("2023-03-01T09:00:00Z").unwrap())
+/// );
+///
+/// materialized.advance_to(DateTime::<Utc>::from_str("2023-04-01T00:00:00Z").unwrap());
+/// assert_eq!(materialized.events()[0], DateTime::<Utc>::from_str("2023-04-01T09:00:00Z").unwrap());
+/// ```
+#[derive(Debug, Clone)]
+pub struct MaterializedRecurrence {
+    recurrence: Recurrence,
+
+    /// Length of the window kept when it is moved
+    horizon: Duration,
+
+    /// Dates the events are computed for, excluding the end
+    window: Range<DateTime<Utc>>,
+
+    /// Sorted event dates of the window
+    events: Vec<DateTime<Utc>>,
+}
+
+impl MaterializedRecurrence {
+    /// Creates a materialized recurrence with the events from the given date until the
+    /// end of the horizon.
+    pub fn new(recurrence: Recurrence, from: DateTime<Utc>, horizon: Duration) -> Self {
+        let window = from..window_end(&from, horizon);
+        let events = recurrence.within(&window).collect();
+        MaterializedRecurrence {
+            recurrence,
+            horizon,
+            window,
+            events,
+        }
+    }
+
+    /// Returns the recurrence of the events.
+    pub fn recurrence(&self) -> &Recurrence {
+        &self.recurrence
+    }
+
+    /// Returns the dates of the cached events, excluding the end.
+    pub fn window(&self) -> Range<DateTime<Utc>> {
+        self.window.clone()
+    }
+
+    /// Returns the sorted event dates of the window.
+    pub fn events(&self) -> &[DateTime<Utc>] {
+        &self.events
+    }
+
+    /// Moves the window to start at the given date and last the horizon, dropping the
+    /// events before it and computing the events after the previous window.
+    ///
+    /// Moving the window backwards computes all its events again.
+    pub fn advance_to(&mut self, from: DateTime<Utc>) {
+        if from < self.window.start {
+            self.window = from..window_end(&from, self.horizon);
+            self.refresh();
+            return;
+        }
+        let dropped = self.events.partition_point(|event| *event < from);
+        self.events.drain(..dropped);
+        self.window.start = from;
+        self.extend_to(window_end(&from, self.horizon));
+    }
+
+    /// Grows the end of the window up to the given date, computing only the new events.
+    /// The window is never shrunk.
+    pub fn extend_to(&mut self, until: DateTime<Utc>) {
+        let start = self.window.end.max(self.window.start);
+        if until <= start {
+            return;
+        }
+        self.events.extend(self.recurrence.within(&(start..until)));
+        self.window.end = until;
+    }
+
+    /// Replaces the recurrence, computing the events of the window again.
+    pub fn set_recurrence(&mut self, recurrence: Recurrence) {
+        self.recurrence = recurrence;
+        self.refresh();
+    }
+
+    /// Changes the recurrence, computing the events of the window again.
+    ///
+    /// Examples:
+    /// ```
+    /// use std::str::FromStr;
+    /// use chrono::{DateTime, Duration, Utc};
+    /// use rrules::{MaterializedRecurrence, Recurrence};
+    ///
+    /// let recurrence = Recurrence::from_str("FREQ=DAILY;INTERVAL=1;DTSTART=2023-01-01T09:00:00Z").unwrap();
+    /// let from = DateTime::<Utc>::from_str("2023-01-01T00:00:00Z").unwrap();
+    /// let mut materialized = MaterializedRecurrence::new(recurrence, from, Duration::days(10));
+    /// let holiday = DateTime::<Utc>::from_str("2023-01-06T09:00:00Z").unwrap();
+    /// materialized.update(|recurrence| recurrence.exdates.push(holiday));
+    /// assert!(!materialized.contains(&holiday));
+    /// assert_eq!(materialized.events().len(), 9);
+    /// ```
+    pub fn update(&mut self, change: impl FnOnce(&mut Recurrence)) {
+        change(&mut self.recurrence);
+        self.refresh();
+    }
+
+    /// Verifies if the date is one of the event dates, after their jitter like in the
+    /// iterations, inside and outside the window.
+    pub fn contains(&self, date: &DateTime<Utc>) -> bool {
+        if !self.window.contains(date) {
+            return match self.recurrence.jitter {
+                Some(_) => self.recurrence.between(date, date).next().is_some(),
+                None => self.recurrence.contains(date),
+            };
+        }
+        self.events.binary_search(date).is_ok()
+    }
+
+    /// Returns the event dates between the given dates, both included, borrowed from the
+    /// cached events when the window has all of them.
+    pub fn between(&self, from: &DateTime<Utc>, to: &DateTime<Utc>) -> Cow<'_, [DateTime<Utc>]> {
+        if !self.window.contains(from) || !self.window.contains(to) {
+            return Cow::Owned(self.recurrence.between(from, to).collect());
+        }
+        let start = self.events.partition_point(|event| event < from);
+        let end = self.events.partition_point(|event| event <= to);
+        Cow::Borrowed(&self.events[start..end.max(start)])
+    }
+
+    /// Returns the first event date after the given date, or None when there are no
+    /// more events.
+    pub fn next_after(&self, date: &DateTime<Utc>) -> Option<DateTime<Utc>> {
+        if self.window.contains(date) {
+            let next = self.events.partition_point(|event| event <= date);
+            if let Some(event) = self.events.get(next) {
+                return Some(*event);
+            }
+        }
+        self.recurrence.next_from(date)
+    }
+
+    fn refresh(&mut self) {
+        self.events = self.recurrence.within(&self.window).collect();
+    }
+}
+
+/// Returns the end of the window that starts at the given date and lasts the horizon.
+fn window_end(from: &DateTime<Utc>, horizon: Duration) -> DateTime<Utc> {
+    from.checked_add_signed(horizon.max(Duration::zero()))
+        .unwrap_or(DateTime::<Utc>::MAX_UTC)
+}
+
+#[cfg(test)]
+mod test_materialized {
+    use crate::test_utils::date;
+    use crate::{MaterializedRecurrence, Recurrence};
+    use chrono::{DateTime, Duration, Utc};
+    use std::borrow::Cow;
+    use std::str::FromStr;
+
+    fn weekdays() -> Recurrence {
+        Recurrence::from_str(
+            "FREQ=WEEKLY;INTERVAL=1;BYDAY=MO,WE,FR;DTSTART=2023-01-02T09:00:00Z;DTEND=2024-12-31T00:00:00Z",
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn caches_the_events_of_the_window() {
+        let from = date("2023-03-01T00:00:00Z");
+        let materialized = MaterializedRecurrence::new(weekdays(), from, Duration::days(60));
+        let expected: Vec<_> = weekdays()
+            .between(&from, &date("2023-04-30T00:00:00Z"))
+            .collect();
+        assert_eq!(materialized.events(), expected);
+        assert_eq!(materialized.window(), from..date("2023-04-30T00:00:00Z"));
+    }
+
+    #[test]
+    fn lookups_match_the_recurrence() {
+        let recurrence = weekdays();
+        let materialized = MaterializedRecurrence::new(
+            recurrence.clone(),
+            date("2023-03-01T00:00:00Z"),
+            Duration::days(60),
+        );
+        // Dates inside the window, at its edges and outside it
+        let dates = [
+            "2023-01-02T09:00:00Z",
+            "2023-03-01T00:00:00Z",
+            "2023-03-01T09:00:00Z",
+            "2023-03-02T09:00:00Z",
+            "2023-04-28T09:00:00Z",
+            "2023-04-29T12:00:00Z",
+            "2023-05-01T09:00:00Z",
+            "2024-12-30T09:00:00Z",
+        ]
+        .map(date);
+        for date in &dates {
+            assert_eq!(materialized.contains(date), recurrence.contains(date));
+            assert_eq!(materialized.next_after(date), recurrence.next_from(date));
+            for to in &dates {
+                let expected: Vec<_> = recurrence.between(date, to).collect();
+                assert_eq!(materialized.between(date, to).to_vec(), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn jittered_events_are_found_on_both_sides_of_the_window() {
+        let recurrence =
+            Recurrence::from_str("FREQ=HOURLY;INTERVAL=1;DTSTART=2023-01-01T00:00:00Z")
+                .unwrap()
+                .with_jitter(Duration::minutes(10), 42);
+        let from = date("2023-01-02T00:00:00Z");
+        let materialized = MaterializedRecurrence::new(recurrence.clone(), from, Duration::days(1));
+        let window = materialized.window();
+        let events: Vec<DateTime<Utc>> = recurrence
+            .between(&date("2023-01-01T20:00:00Z"), &date("2023-01-03T04:00:00Z"))
+            .collect();
+        assert!(events.iter().any(|event| *event < window.start));
+        assert!(events.iter().any(|event| window.contains(event)));
+        assert!(events.iter().any(|event| *event >= window.end));
+        for event in &events {
+            assert!(materialized.contains(event), "{event}");
+        }
+        // The dates of the rules are only events when their jitter is zero
+        let rules = recurrence.without_jitter();
+        for rule_date in rules.between(&events[0], &events[events.len() - 1]) {
+            assert_eq!(
+                materialized.contains(&rule_date),
+                events.contains(&rule_date),
+                "{rule_date}"
+            );
+        }
+    }
+
+    #[test]
+    fn between_borrows_the_cached_events() {
+        let materialized = MaterializedRecurrence::new(
+            weekdays(),
+            date("2023-03-01T00:00:00Z"),
+            Duration::days(60),
+        );
+        let events =
+            materialized.between(&date("2023-03-06T09:00:00Z"), &date("2023-03-10T09:00:00Z"));
+        assert!(matches!(events, Cow::Borrowed(_)));
+        assert_eq!(events.len(), 3);
+        let events =
+            materialized.between(&date("2023-04-24T00:00:00Z"), &date("2023-05-05T00:00:00Z"));
+        assert!(matches!(events, Cow::Owned(_)));
+        assert_eq!(events.len(), 5);
+    }
+
+    #[test]
+    fn extending_and_advancing_compute_the_same_events() {
+        let mut materialized = MaterializedRecurrence::new(
+            weekdays(),
+            date("2023-01-01T00:00:00Z"),
+            Duration::days(30),
+        );
+        materialized.extend_to(date("2023-06-01T00:00:00Z"));
+        materialized.extend_to(date("2023-05-01T00:00:00Z"));
+        let full = MaterializedRecurrence::new(
+            weekdays(),
+            date("2023-01-01T00:00:00Z"),
+            Duration::days(151),
+        );
+        assert_eq!(materialized.events(), full.events());
+        assert_eq!(materialized.window(), full.window());
+
+        materialized.advance_to(date("2023-05-03T09:00:00Z"));
+        let advanced = MaterializedRecurrence::new(
+            weekdays(),
+            date("2023-05-03T09:00:00Z"),
+            Duration::days(30),
+        );
+        assert_eq!(materialized.events(), advanced.events());
+        assert_eq!(materialized.window(), advanced.window());
+
+        materialized.advance_to(date("2023-02-01T00:00:00Z"));
+        let back = MaterializedRecurrence::new(
+            weekdays(),
+            date("2023-02-01T00:00:00Z"),
+            Duration::days(30),
+        );
+        assert_eq!(materialized.events(), back.events());
+    }
+
+    #[test]
+    fn changing_the_recurrence_computes_the_events_again() {
+        let from = date("2023-03-01T00:00:00Z");
+        let mut materialized = MaterializedRecurrence::new(weekdays(), from, Duration::days(60));
+        let daily =
+            Recurrence::from_str("FREQ=DAILY;INTERVAL=1;DTSTART=2023-01-01T09:00:00Z").unwrap();
+        materialized.set_recurrence(daily.clone());
+        assert_eq!(materialized.events().len(), 60);
+        assert_eq!(materialized.recurrence(), &daily);
+
+        materialized.update(|recurrence| {
+            *recurrence = recurrence
+                .clone()
+                .with_end(Some(date("2023-03-10T09:00:00Z")))
+                .unwrap()
+        });
+        assert_eq!(materialized.events().len(), 10);
+        assert_eq!(materialized.next_after(&date("2023-03-10T09:00:00Z")), None);
+    }
+
+    #[test]
+    fn horizons_past_the_last_date_are_clamped() {
+        let recurrence =
+            Recurrence::from_str("FREQ=YEARLY;INTERVAL=1;DTSTART=2023-01-01T09:00:00Z").unwrap();
+        let materialized =
+            MaterializedRecurrence::new(recurrence, date("2023-01-01T00:00:00Z"), Duration::MAX);
+        assert_eq!(materialized.window().end, DateTime::<Utc>::MAX_UTC);
+        assert!(materialized.contains(&date("2100-01-01T09:00:00Z")));
+    }
+}
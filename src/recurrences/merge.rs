@@ -0,0 +1,132 @@
+use crate::recurrences::{OccurrenceIter, Recurrence};
+use alloc::collections::BinaryHeap;
+use alloc::vec::Vec;
+use chrono::{DateTime, Utc};
+use core::cmp::Reverse;
+
+/// Merges the events of many recurrences in chronological order, tagging every event
+/// with the index of its recurrence.
+///
+/// Only the next event of every recurrence is kept in memory, so any number of endless
+/// recurrences can be merged. Events at the same date are yielded in the order of their
+/// recurrences.
+///
+/// Examples:
+/// ```
+/// use std::str::FromStr;
+/// use chrono::{DateTime, Utc};
+/// use rrules::{merge, Recurrence};
+///
+/// let daily = Recurrence::from_str("FREQ=DAILY;INTERVAL=1;DTSTART=2023-01-01T09:00:00Z").unwrap();
+/// let weekly = Recurrence::from_str("FREQ=WEEKLY;INTERVAL=1;DTSTART=2023-01-01T12:00:00Z").unwrap();
+/// let agenda: Vec<(usize, DateTime<Utc>)> = merge(vec![daily, weekly]).take(3).collect();
+/// assert_eq!(agenda[0], (0, DateTime::from_str("2023-01-01T09:00:00Z").unwrap()));
+/// assert_eq!(agenda[1], (1, DateTime::from_str("2023-01-01T12:00:00Z").unwrap()));
+/// assert_eq!(agenda[2], (0, DateTime::from_str("2023-01-02T09:00:00Z").unwrap()));
+/// ```
+pub fn merge(recurrences: Vec<Recurrence>) -> Merge {
+    let mut sources: Vec<OccurrenceIter<'static>> =
+        recurrences.into_iter().map(Recurrence::into_iter).collect();
+    let next_events = sources
+        .iter_mut()
+        .enumerate()
+        .filter_map(|(index, source)| source.next().map(|date| Reverse((date, index))))
+        .collect();
+    Merge {
+        sources,
+        next_events,
+    }
+}
+
+/// An iterator over the events of many recurrences in chronological order.
+///
+/// It is created by `merge()`, and yields the index of the recurrence of every event
+/// along with its date.
+#[derive(Debug, Clone)]
+pub struct Merge {
+    sources: Vec<OccurrenceIter<'static>>,
+
+    /// Next event of every recurrence that is not exhausted, the earliest one first
+    next_events: BinaryHeap<Reverse<(DateTime<Utc>, usize)>>,
+}
+
+impl Merge {
+    /// Returns the iterators of the merged recurrences, in the order they were given.
+    pub fn sources(&self) -> &[OccurrenceIter<'static>] {
+        &self.sources
+    }
+}
+
+impl Iterator for Merge {
+    type Item = (usize, DateTime<Utc>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let Reverse((date, index)) = self.next_events.pop()?;
+        if let Some(next_date) = self.sources[index].next() {
+            self.next_events.push(Reverse((next_date, index)));
+        }
+        Some((index, date))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.next_events.len(), None)
+    }
+}
+
+#[cfg(test)]
+mod test_merge {
+    use crate::test_utils::date;
+    use crate::{merge, Recurrence};
+    use std::str::FromStr;
+
+    #[test]
+    fn merges_in_chronological_order() {
+        let hourly = Recurrence::from_str(
+            "FREQ=HOURLY;INTERVAL=6;DTSTART=2023-01-01T00:00:00Z;DTEND=2023-01-01T12:00:00Z",
+        )
+        .unwrap();
+        let daily = Recurrence::from_str(
+            "FREQ=DAILY;INTERVAL=1;DTSTART=2023-01-01T03:00:00Z;DTEND=2023-01-02T03:00:00Z",
+        )
+        .unwrap();
+        let events: Vec<_> = merge(vec![hourly, daily]).collect();
+        assert_eq!(
+            events,
+            vec![
+                (0, date("2023-01-01T00:00:00Z")),
+                (1, date("2023-01-01T03:00:00Z")),
+                (0, date("2023-01-01T06:00:00Z")),
+                (0, date("2023-01-01T12:00:00Z")),
+                (1, date("2023-01-02T03:00:00Z")),
+            ]
+        );
+    }
+
+    #[test]
+    fn yields_simultaneous_events_in_the_order_of_the_recurrences() {
+        let daily =
+            Recurrence::from_str("FREQ=DAILY;INTERVAL=1;DTSTART=2023-01-01T09:00:00Z").unwrap();
+        let weekly =
+            Recurrence::from_str("FREQ=WEEKLY;INTERVAL=1;DTSTART=2023-01-01T09:00:00Z").unwrap();
+        let events: Vec<_> = merge(vec![weekly, daily]).take(3).collect();
+        assert_eq!(
+            events,
+            vec![
+                (0, date("2023-01-01T09:00:00Z")),
+                (1, date("2023-01-01T09:00:00Z")),
+                (1, date("2023-01-02T09:00:00Z")),
+            ]
+        );
+    }
+
+    #[test]
+    fn merges_empty_and_exhausted_recurrences() {
+        assert_eq!(merge(vec![]).next(), None);
+        let once = Recurrence::from_str(
+            "FREQ=DAILY;INTERVAL=1;DTSTART=2023-01-01T00:00:00Z;DTEND=2023-01-01T12:00:00Z",
+        )
+        .unwrap();
+        let events: Vec<_> = merge(vec![once.clone(), once]).collect();
+        assert_eq!(events.len(), 2);
+    }
+}
@@ -1,10 +1,34 @@
+mod cursor;
 mod errors;
+mod expand;
+mod holidays;
+pub mod ics;
+pub mod iso8601;
+mod iterator;
+mod jitter;
+#[cfg(feature = "std")]
+mod lines;
+mod materialized;
+mod merge;
 mod models;
+#[cfg(feature = "rayon")]
+mod parallel;
 mod recurrence_validation_tests;
 mod recurrences_tests;
 pub mod serializers;
 pub mod validations;
+mod views;
 
-pub use models::{Recurrence, MAX_DATE};
+pub use cursor::OccurrenceCursor;
+pub use expand::ExpandStats;
+pub use holidays::{HolidaySource, ShiftPolicy, Skipping};
+pub use iterator::{DateIter, DetailedIter, Occurrence, OccurrenceIter};
+pub use jitter::Jitter;
+#[cfg(feature = "std")]
+pub use lines::{parse_lines, LineError, ParseLines};
+pub use materialized::MaterializedRecurrence;
+pub use merge::{merge, Merge};
+pub use models::Recurrence;
+pub use validations::ValidationOptions;
 
-pub use errors::RecurrenceInvalid;
+pub use errors::{RecurrenceErrorKind, RecurrenceInvalid};
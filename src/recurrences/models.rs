@@ -1,14 +1,33 @@
-use crate::frequencies::Frequency;
-use crate::recurrences::errors::RecurrenceInvalid;
-use crate::recurrences::validations::{validate_duration, validate_recurrence_period};
-use chrono::{DateTime, Duration, Utc};
-use std::str::FromStr;
+use crate::clock::Clock;
+#[cfg(feature = "std")]
+use crate::clock::SystemClock;
+use crate::frequencies::{Frequency, OccurrenceEngine};
+use crate::recurrences::errors::{RecurrenceErrorKind, RecurrenceInvalid};
+use crate::recurrences::iterator::{DateIter, DetailedIter, Occurrence, OccurrenceIter};
+use crate::recurrences::jitter::Jitter;
+use crate::recurrences::validations::{
+    validate_all_day_frequency, validate_duration_with, validate_recurrence_period,
+    validate_sub_daily_times, ValidationOptions,
+};
+use crate::trace;
+use alloc::borrow::Cow;
+use alloc::collections::BTreeSet;
+use alloc::string::String;
+use alloc::vec::Vec;
+use alloc::{format, vec};
+use chrono::{DateTime, Duration, NaiveDate, NaiveTime, TimeZone, Utc};
+use core::hash::{Hash, Hasher};
+use core::ops::Range;
 
-pub const MAX_DATE: &str = "9999-12-31T23:59:59Z";
+/// Last date of the events of recurrences without an end date.
+pub(crate) fn max_date() -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(9999, 12, 31, 23, 59, 59).unwrap()
+}
 
-/// An Iterator-type struct that represents a recurrence of events.
-/// It can be used to collect/iterate over all the events that match the recurrence rules
-/// between a start and end date.
+/// The definition of a recurrence of events.
+/// Use `iter()` or `into_iter()` to collect/iterate over all the events that match the
+/// recurrence rules between a start and end date. The same recurrence can be iterated
+/// many times, as the iteration state is kept by the returned `OccurrenceIter`.
 ///
 /// Examples:
 /// ```
@@ -21,14 +40,22 @@ pub const MAX_DATE: &str = "9999-12-31T23:59:59Z";
 /// );
 /// let events: Vec<DateTime<Utc>> = everyday_during_first_3_days_of_january
 ///     .unwrap()
+///     .iter()
 ///     .collect();
 /// assert_eq!(events.len(), 3);
 ///
 /// ```
+///
+/// Recurrences are equal when they have the same `canonical_string()`, the same exdates
+/// regardless of their order, and the same iteration limits.
+///
+/// The events are generated by the RFC 5545 rules of a `Frequency`, or by any other
+/// `OccurrenceEngine` given to `Recurrence::from_engine`. Parsing, serializing and the
+/// validation of the durations are only available for the frequencies.
 #[derive(Debug, Clone)]
-pub struct Recurrence {
-    /// Represents the frequency rules of the recurrence
-    pub frequency: Frequency,
+pub struct Recurrence<E = Frequency> {
+    /// Represents the frequency rules of the recurrence, or the engine generating its events
+    pub frequency: E,
 
     /// Start date of the recurrences
     ///
@@ -36,13 +63,44 @@ pub struct Recurrence {
     /// configuration defined.
     pub start: DateTime<Utc>,
 
-    /// Current event date to be returned by next()
-    /// Starts as None, and is set to the first event date once next() is called
-    pub current_date: Option<DateTime<Utc>>,
-
-    /// End date of the recurrences
-    pub end: DateTime<Utc>,
+    /// End date of the recurrences, or None when they don't end
+    end: Option<DateTime<Utc>>,
     pub duration: Duration,
+
+    /// Event dates excluded from the recurrence, written as the `EXDATE` of the rule
+    pub exdates: Vec<DateTime<Utc>>,
+
+    /// Date of the first event yielded, the events of the rules before it are skipped while
+    /// the intervals stay counted from the start date, written as the `X-NOTBEFORE` of the rule
+    pub(crate) not_before: Option<DateTime<Utc>>,
+
+    /// Number of events of the rules, counting the excluded dates and the skipped events like
+    /// RFC 5545 does, written as the `COUNT` of the rule
+    pub count: Option<u32>,
+
+    /// Whether the events last whole days, starting at midnight UTC
+    pub all_day: bool,
+
+    /// `KEY=VALUE` pairs of the parsed rule that are not supported, like `BYSETPOS` or
+    /// `X-` parameters, kept in their original order to be serialized again
+    pub unknown_params: Vec<(String, String)>,
+
+    /// Maximum number of events yielded when iterating, to guard against endless iterations,
+    /// not serialized
+    pub max_occurrences: Option<usize>,
+
+    /// Last date of the events yielded when iterating, to guard against endless iterations
+    pub max_horizon: Option<DateTime<Utc>>,
+
+    /// Validation rules applied when the recurrence is changed, not serialized
+    pub validation: ValidationOptions,
+
+    /// Reproducible offset of the yielded events, not serialized
+    pub jitter: Option<Jitter>,
+
+    /// Whether the recurrence was parsed from iCalendar content lines, which are written again
+    /// by `to_string()`
+    pub(crate) content_lines: bool,
 }
 
 impl Recurrence {
@@ -65,62 +123,1161 @@ impl Recurrence {
         end: Option<DateTime<Utc>>,
         duration: Option<Duration>,
     ) -> Result<Self, RecurrenceInvalid> {
-        let end = end.unwrap_or_else(|| DateTime::<Utc>::from_str(MAX_DATE).unwrap());
-        if frequency.is_valid().is_err() {
-            return Err(RecurrenceInvalid {
-                message: format!("{}", frequency.is_valid().unwrap_err()),
-            });
+        Recurrence::new_with(
+            frequency,
+            start,
+            end,
+            duration,
+            ValidationOptions::default(),
+        )
+    }
+
+    /// Validates and creates a new Recurrence instance using the given validation options,
+    /// which are kept to validate the later changes of the recurrence.
+    ///
+    /// With the default options this is the same as `Recurrence::new`.
+    pub fn new_with(
+        frequency: Frequency,
+        start: DateTime<Utc>,
+        end: Option<DateTime<Utc>>,
+        duration: Option<Duration>,
+        validation: ValidationOptions,
+    ) -> Result<Self, RecurrenceInvalid> {
+        let _span = trace::debug_span!("validate_recurrence", %start);
+        let frequency = frequency.normalized();
+        if let Err(e) = trace::debug_error(frequency.is_valid(), "Invalid frequency") {
+            return Err(RecurrenceInvalid::with_source(format!("{e}"), e));
+        }
+        trace::debug_error(
+            validate_sub_daily_times(&frequency, &start),
+            "Unreachable times",
+        )?;
+        if let Some(end) = &end {
+            trace::debug_error(validate_recurrence_period(&start, end), "Invalid period")?;
         }
-        validate_recurrence_period(&start, &end)?;
 
         let duration = duration.unwrap_or_else(|| Duration::seconds(0));
-        validate_duration(&frequency, &duration)?;
+        trace::debug_error(
+            validate_duration_with(&frequency, &start, &duration, &validation),
+            "Invalid duration",
+        )?;
         Ok(Recurrence {
             frequency,
             start,
-            current_date: Some(start),
             end,
             duration,
+            exdates: vec![],
+            not_before: None,
+            count: None,
+            all_day: false,
+            unknown_params: vec![],
+            max_occurrences: None,
+            max_horizon: None,
+            validation,
+            jitter: None,
+            content_lines: false,
         })
     }
 
-    pub fn set_current_date(&mut self, current_date: Option<DateTime<Utc>>) {
-        self.current_date = current_date;
+    /// Validates and creates a new all-day Recurrence, with events lasting one day.
+    ///
+    /// The end date is inclusive. Frequencies with events more often than daily, or with
+    /// times of the day defined, are not valid for all-day recurrences.
+    /// Examples:
+    /// ```
+    /// use std::num::NonZeroU32;
+    /// use chrono::NaiveDate;
+    /// use rrules::{Frequency, OverflowPolicy, Recurrence};
+    ///
+    /// let yearly = Frequency::Yearly {
+    ///     interval: NonZeroU32::new(1).unwrap(),
+    ///     by_month: vec![],
+    ///     by_monthly_date: vec![],
+    ///     nth_weekdays: vec![],
+    ///     by_week_number: vec![],
+    ///     by_time: vec![],
+    ///     overflow: OverflowPolicy::Skip,
+    /// };
+    /// let birthday = Recurrence::all_day(
+    ///     yearly,
+    ///     NaiveDate::from_ymd_opt(2020, 3, 14).unwrap(),
+    ///     Some(NaiveDate::from_ymd_opt(2023, 3, 14).unwrap()),
+    /// ).unwrap();
+    /// let dates: Vec<NaiveDate> = birthday.dates().collect();
+    /// assert_eq!(dates.len(), 4);
+    /// assert_eq!(dates[3], NaiveDate::from_ymd_opt(2023, 3, 14).unwrap());
+    /// ```
+    pub fn all_day(
+        frequency: Frequency,
+        start_date: NaiveDate,
+        end_date: Option<NaiveDate>,
+    ) -> Result<Self, RecurrenceInvalid> {
+        validate_all_day_frequency(&frequency)?;
+        let start = start_of_day(start_date);
+        let end = end_date.map(start_of_day);
+        let mut recurrence = Recurrence::new(frequency, start, end, Some(Duration::days(1)))?;
+        recurrence.all_day = true;
+        Ok(recurrence)
+    }
+
+    /// Replaces the frequency of the recurrence, keeping the rest of its definition.
+    /// Returns an error if the frequency is invalid or not valid for the recurrence.
+    pub fn with_frequency(mut self, frequency: Frequency) -> Result<Self, RecurrenceInvalid> {
+        let frequency = frequency.normalized();
+        if let Err(e) = frequency.is_valid() {
+            return Err(RecurrenceInvalid::with_source(format!("{e}"), e));
+        }
+        if self.all_day {
+            validate_all_day_frequency(&frequency)?;
+        }
+        validate_sub_daily_times(&frequency, &self.start)?;
+        validate_duration_with(&frequency, &self.start, &self.duration, &self.validation)?;
+        self.frequency = frequency;
+        Ok(self)
+    }
+
+    /// Replaces the start date of the recurrence.
+    /// Returns an error if it is not before the end date, or if the events overlap
+    /// from the new start date.
+    pub fn with_start(mut self, start: DateTime<Utc>) -> Result<Self, RecurrenceInvalid> {
+        let start = if self.all_day {
+            start_of_day(start.date_naive())
+        } else {
+            start
+        };
+        if let Some(end) = &self.end {
+            validate_recurrence_period(&start, end)?;
+        }
+        validate_sub_daily_times(&self.frequency, &start)?;
+        validate_duration_with(&self.frequency, &start, &self.duration, &self.validation)?;
+        self.start = start;
+        Ok(self)
+    }
+
+    /// Returns a copy of the recurrence with every event moved by the offset, E.g. to move a
+    /// whole series of meetings one hour later.
+    ///
+    /// The start and end dates, the excluded dates, the horizon and the times of the frequency
+    /// are moved together. Returns an error when the moved events cannot be defined by the same
+    /// rules, like the events on the 31st of each month moved to the next day, or when all-day
+    /// events are not moved by whole days.
+    /// Examples:
+    /// ```
+    /// use std::str::FromStr;
+    /// use chrono::{DateTime, Duration, Utc};
+    /// use rrules::Recurrence;
+    ///
+    /// let standup = Recurrence::from_str(
+    ///     "FREQ=WEEKLY;INTERVAL=1;BYDAY=MO,WE;BYHOUR=9;DTSTART=2023-01-02T00:00:00Z;DTEND=2023-02-01T00:00:00Z"
+    /// ).unwrap();
+    /// let later = standup.shift_by(Duration::hours(1)).unwrap();
+    /// assert_eq!(
+    ///     later.to_string(),
+    ///     "FREQ=WEEKLY;INTERVAL=1;BYDAY=MO,WE;BYHOUR=10;BYMINUTE=0;DTSTART=2023-01-02T01:00:00Z;DTEND=2023-02-01T01:00:00Z"
+    /// );
+    /// assert!(standup.iter().zip(later.iter()).all(|(event, moved)| moved == event + Duration::hours(1)));
+    ///
+    /// let monthly = Recurrence::from_str("FREQ=MONTHLY;INTERVAL=1;BYMONTHDAY=31;DTSTART=2023-01-01T09:00:00Z").unwrap();
+    /// assert!(monthly.shift_by(Duration::days(1)).is_err());
+    /// ```
+    pub fn shift_by(&self, offset: Duration) -> Result<Self, RecurrenceInvalid> {
+        if self.all_day && offset != Duration::days(offset.num_days()) {
+            return Err(RecurrenceInvalid::new(
+                "All-day recurrences can only be shifted by whole days",
+            ));
+        }
+        let shift = |date: &DateTime<Utc>| {
+            date.checked_add_signed(offset).ok_or_else(|| {
+                RecurrenceInvalid::new("The shifted dates are out of range")
+                    .with_kind(RecurrenceErrorKind::OutOfRange)
+            })
+        };
+        let mut shifted = self.clone();
+        shifted.start = shift(&self.start)?;
+        let frequency = self.frequency.shifted(&self.start, offset).ok_or_else(|| {
+            RecurrenceInvalid::new("The shifted events cannot be defined by the frequency rules")
+        })?;
+        shifted.frequency = frequency.normalized();
+        shifted.end = self.end.as_ref().map(shift).transpose()?;
+        shifted.exdates = self.exdates.iter().map(shift).collect::<Result<_, _>>()?;
+        shifted.not_before = self.not_before.as_ref().map(shift).transpose()?;
+        shifted.max_horizon = self.max_horizon.as_ref().map(shift).transpose()?;
+        validate_duration_with(
+            &shifted.frequency,
+            &shifted.start,
+            &shifted.duration,
+            &shifted.validation,
+        )?;
+        Ok(shifted)
+    }
+
+    /// Returns a copy of the recurrence whose intervals are counted from the new start date,
+    /// E.g. to move an every other week recurrence to the other weeks.
+    ///
+    /// Unlike `shift_by`, the events keep the days and times of the frequency rules, only the
+    /// phase of the intervals and the first event change. The end date is kept, and the excluded
+    /// dates that are not events of the rebased recurrence are dropped.
+    /// Returns an error if the new start date is not before the end date, or if the events
+    /// overlap from it.
+    /// Examples:
+    /// ```
+    /// use std::str::FromStr;
+    /// use chrono::{DateTime, Utc};
+    /// use rrules::Recurrence;
+    ///
+    /// let every_2_weeks = Recurrence::from_str(
+    ///     "FREQ=WEEKLY;INTERVAL=2;BYDAY=MO;BYHOUR=9;DTSTART=2023-01-02T00:00:00Z"
+    /// ).unwrap();
+    /// let other_weeks = every_2_weeks.rebase_start(DateTime::<Utc>::from_str("2023-01-09T00:00:00Z").unwrap()).unwrap();
+    /// let events: Vec<DateTime<Utc>> = other_weeks.iter().take(2).collect();
+    /// assert_eq!(events, [
+    ///     DateTime::<Utc>::from_str("2023-01-09T09:00:00Z").unwrap(),
+    ///     DateTime::<Utc>::from_str("2023-01-23T09:00:00Z").unwrap(),
+    /// ]);
+    /// ```
+    pub fn rebase_start(&self, new_start: DateTime<Utc>) -> Result<Self, RecurrenceInvalid> {
+        let mut rebased = self.clone().with_exdates(vec![]).with_start(new_start)?;
+        rebased.exdates = self
+            .exdates
+            .iter()
+            .filter(|exdate| rebased.contains(exdate))
+            .copied()
+            .collect();
+        Ok(rebased)
+    }
+
+    /// Returns the time of the day of every event, which is the time of the start date, or
+    /// None when the frequency defines its own times or has many events a day.
+    ///
+    /// The events filtered by weekdays, month days or nth weekdays keep the time of the
+    /// start date too, only `BYTIME`, `BYHOUR` and `X-BYDAYTIME` override it.
+    /// Examples:
+    /// ```
+    /// use std::str::FromStr;
+    /// use chrono::{NaiveTime, Timelike};
+    /// use rrules::Recurrence;
+    ///
+    /// let weekly = Recurrence::from_str("FREQ=WEEKLY;INTERVAL=1;BYDAY=MO,FR;DTSTART=2023-01-04T09:30:00Z").unwrap();
+    /// assert_eq!(weekly.event_time(), NaiveTime::from_hms_opt(9, 30, 0));
+    /// assert!(weekly.iter().take(10).all(|event| event.hour() == 9 && event.minute() == 30));
+    ///
+    /// let by_time = Recurrence::from_str("FREQ=DAILY;INTERVAL=1;BYTIME=18:00;DTSTART=2023-01-04T09:30:00Z").unwrap();
+    /// assert_eq!(by_time.event_time(), None);
+    /// ```
+    pub fn event_time(&self) -> Option<NaiveTime> {
+        match self.frequency {
+            Frequency::Secondly { .. } | Frequency::Minutely { .. } | Frequency::Hourly { .. } => {
+                None
+            }
+            _ if !self.frequency.by_time().is_empty()
+                || !self.frequency.by_day_time().is_empty() =>
+            {
+                None
+            }
+            _ => Some(self.start.time()),
+        }
     }
 }
 
-impl Iterator for Recurrence {
-    type Item = DateTime<Utc>;
+impl<E: OccurrenceEngine> Recurrence<E> {
+    /// Validates and creates a recurrence whose events are generated by the given engine,
+    /// E.g. for business rules that are not RFC 5545 frequencies.
+    ///
+    /// Unlike the frequencies, the duration of the events is not validated against the time
+    /// between them. Returns an error if the engine rules or the end date are invalid.
+    pub fn from_engine(
+        engine: E,
+        start: DateTime<Utc>,
+        end: Option<DateTime<Utc>>,
+        duration: Option<Duration>,
+    ) -> Result<Self, RecurrenceInvalid> {
+        let engine = engine.normalized();
+        if let Err(e) = engine.validate() {
+            return Err(RecurrenceInvalid::with_source(format!("{e}"), e));
+        }
+        if let Some(end) = &end {
+            validate_recurrence_period(&start, end)?;
+        }
+        Ok(Recurrence {
+            frequency: engine,
+            start,
+            end,
+            duration: duration.unwrap_or_else(|| Duration::seconds(0)),
+            exdates: vec![],
+            not_before: None,
+            count: None,
+            all_day: false,
+            unknown_params: vec![],
+            max_occurrences: None,
+            max_horizon: None,
+            validation: ValidationOptions::default(),
+            jitter: None,
+            content_lines: false,
+        })
+    }
+
+    /// Excludes the given event dates from the recurrence.
+    pub fn with_exdates(mut self, exdates: Vec<DateTime<Utc>>) -> Self {
+        self.exdates = exdates;
+        self
+    }
+
+    /// Keeps the given `KEY=VALUE` pairs, to be serialized along with the recurrence rules.
+    pub fn with_unknown_params(mut self, unknown_params: Vec<(String, String)>) -> Self {
+        self.unknown_params = unknown_params;
+        self
+    }
+
+    /// Stops the iteration after the given number of events.
+    ///
+    /// Unlike the `COUNT` of a rule, the limit is not part of the recurrence rules, so it is not
+    /// serialized. Use `OccurrenceIter::is_truncated()` to know if an iteration stopped at the limit.
+    /// Examples:
+    /// ```
+    /// use std::str::FromStr;
+    /// use rrules::Recurrence;
+    ///
+    /// let every_second = Recurrence::from_str("FREQ=SECONDLY;INTERVAL=1;DTSTART=2023-01-01T00:00:00Z")
+    ///     .unwrap()
+    ///     .with_max_occurrences(10_000);
+    /// assert_eq!(every_second.iter().count(), 10_000);
+    /// assert_eq!(every_second.to_string(), "FREQ=SECONDLY;INTERVAL=1;DTSTART=2023-01-01T00:00:00Z");
+    /// ```
+    pub fn with_max_occurrences(mut self, max_occurrences: usize) -> Self {
+        self.max_occurrences = Some(max_occurrences);
+        self
+    }
+
+    /// Limits the events to the given `COUNT` of a rule, if any.
+    pub(crate) fn with_rule_count(mut self, count: Option<u32>) -> Self {
+        self.count = count;
+        self
+    }
+
+    /// Verifies if the `COUNT` of the rule stops the events before the end of the recurrence.
+    pub(crate) fn stops_before_end(&self) -> bool {
+        let Some(count) = self.count else {
+            return false;
+        };
+        let mut rules = self.unjittered().into_owned();
+        rules.exdates.clear();
+        rules.not_before = None;
+        rules.count = None;
+        rules.max_occurrences = None;
+        rules.max_horizon = None;
+        rules.iter().nth(count as usize).is_some()
+    }
+
+    /// Returns the number of events of the rules until the date, included, counting the excluded
+    /// dates and the skipped events as the `COUNT` of the rule does.
+    pub(crate) fn rule_events_until(&self, date: &DateTime<Utc>) -> u64 {
+        if *date < self.start {
+            return 0;
+        }
+        if let Some(step) = self.frequency.fixed_step() {
+            let span = date.signed_duration_since(self.start);
+            return (span.num_milliseconds() / step.num_milliseconds()) as u64 + 1;
+        }
+        let mut rules = self.unjittered().into_owned();
+        rules.exdates.clear();
+        rules.not_before = None;
+        rules.count = None;
+        rules.max_occurrences = None;
+        rules.max_horizon = None;
+        rules.iter().take_while(|event| event <= date).count() as u64
+    }
 
-    /// Returns the next event date in the recurrence.
-    /// Returns None if there are no more events in the recurrence.
+    /// Stops the iteration at the events after the given date.
+    ///
+    /// Unlike the end date, the limit is not part of the recurrence rules, so it is not
+    /// serialized. Use `OccurrenceIter::is_truncated()` to know if an iteration stopped at the limit.
+    pub fn with_max_horizon(mut self, max_horizon: DateTime<Utc>) -> Self {
+        self.max_horizon = Some(max_horizon);
+        self
+    }
+
+    /// Replaces the end date of the recurrence, removing it when None.
+    /// Returns an error if it is not after the start date.
+    pub fn with_end(mut self, end: Option<DateTime<Utc>>) -> Result<Self, RecurrenceInvalid> {
+        if let Some(end) = &end {
+            validate_recurrence_period(&self.start, end)?;
+        }
+        self.end = end;
+        Ok(self)
+    }
+
+    /// Splits the recurrence in the events before the date and the events from the date on,
+    /// E.g. to change "this and the following events" of a recurrence.
+    ///
+    /// The second recurrence keeps the start date and skips the events before the date, so that
+    /// its intervals and the days taken from the start date stay the same as in the original
+    /// recurrence. The excluded dates are kept in the recurrence they belong to, and the skipped
+    /// events still count towards the `COUNT` of the rule and the maximum number of occurrences,
+    /// so together the recurrences yield the events of the recurrence.
+    /// Returns an error if there are no events before or after the date.
     /// Examples:
     /// ```
     /// use std::str::FromStr;
     /// use chrono::{DateTime, Utc};
-    /// use rrules::{Recurrence, Frequency};
+    /// use rrules::Recurrence;
+    ///
+    /// let every_2_days = Recurrence::from_str("FREQ=DAILY;INTERVAL=2;DTSTART=2023-01-01T10:00:00Z").unwrap();
+    /// let date = DateTime::<Utc>::from_str("2023-01-10T00:00:00Z").unwrap();
+    /// let (before, after) = every_2_days.split_at(&date).unwrap();
+    /// assert_eq!(before.iter().last().unwrap(), DateTime::<Utc>::from_str("2023-01-09T10:00:00Z").unwrap());
+    /// assert_eq!(after.start, every_2_days.start);
+    /// assert_eq!(after.iter().next().unwrap(), DateTime::<Utc>::from_str("2023-01-11T10:00:00Z").unwrap());
+    /// ```
+    pub fn split_at(&self, date: &DateTime<Utc>) -> Result<(Self, Self), RecurrenceInvalid> {
+        let no_events_before = || {
+            RecurrenceInvalid::new("There are no events before the split date")
+                .with_kind(RecurrenceErrorKind::NoEvents)
+        };
+        if *date <= self.start {
+            return Err(no_events_before());
+        }
+        let rules = self.unjittered();
+        if rules.iter().next().is_none_or(|event| event >= *date) {
+            return Err(no_events_before());
+        }
+        // Both recurrences are split at the same instant, the last one before the date
+        let boundary = date
+            .checked_sub_signed(Duration::nanoseconds(1))
+            .ok_or_else(no_events_before)?;
+        let next_start = rules.next_from(&boundary).ok_or_else(|| {
+            RecurrenceInvalid::new("There are no events after the split date")
+                .with_kind(RecurrenceErrorKind::NoEvents)
+        })?;
+
+        let (before_exdates, after_exdates) =
+            self.exdates.iter().partition(|exdate| *exdate < date);
+        let before = self
+            .clone()
+            .with_end(Some(boundary))?
+            .with_exdates(before_exdates);
+        let mut after = self.clone().with_exdates(after_exdates);
+        after.not_before = self.not_before.max(Some(next_start));
+        Ok((before, after))
+    }
+
+    /// Verifies if the recurrence has no events after the given date,
+    /// E.g. to archive the finished recurrences.
+    ///
+    /// The end date, the excluded dates and the iteration limits are taken into account.
+    /// An event that started before the date is not a remaining event, even if it is not over.
+    /// Examples:
+    /// ```
+    /// use std::str::FromStr;
+    /// use chrono::{DateTime, Utc};
+    /// use rrules::Recurrence;
+    ///
+    /// let daily = Recurrence::from_str(
+    ///     "FREQ=DAILY;INTERVAL=1;DTSTART=2023-01-01T10:00:00Z;DTEND=2023-01-05T10:00:00Z"
+    /// ).unwrap();
+    /// assert!(!daily.is_expired(&DateTime::<Utc>::from_str("2023-01-04T12:00:00Z").unwrap()));
+    /// assert!(daily.is_expired(&DateTime::<Utc>::from_str("2023-01-05T10:00:00Z").unwrap()));
+    /// ```
+    pub fn is_expired(&self, as_of: &DateTime<Utc>) -> bool {
+        self.next_from(as_of).is_none()
+    }
+
+    /// Returns a copy of the recurrence without the events before the date, yielding its events
+    /// from the first one from the date on, E.g. to drop the past events of a long running
+    /// recurrence.
+    ///
+    /// As in `split_at`, the copy keeps the start date and skips the previous events, so the
+    /// intervals and the days taken from the start date stay the same. The excluded dates before
+    /// the date are dropped, and the skipped events still count towards the `COUNT` of the rule,
+    /// so the copy yields the same events as the recurrence from the date on.
+    /// The recurrence is returned unchanged when the date is not after its start.
+    /// Returns an error if there are no events from the date on.
+    /// Examples:
+    /// ```
+    /// use std::str::FromStr;
+    /// use chrono::{DateTime, Utc};
+    /// use rrules::Recurrence;
+    ///
+    /// let every_2_days = Recurrence::from_str("FREQ=DAILY;INTERVAL=2;DTSTART=2023-01-01T10:00:00Z").unwrap();
+    /// let date = DateTime::<Utc>::from_str("2023-01-10T00:00:00Z").unwrap();
+    /// let truncated = every_2_days.truncate_before(&date).unwrap();
+    /// assert_eq!(truncated.start, every_2_days.start);
+    /// assert_eq!(truncated.iter().next().unwrap(), DateTime::<Utc>::from_str("2023-01-11T10:00:00Z").unwrap());
+    /// ```
+    pub fn truncate_before(&self, date: &DateTime<Utc>) -> Result<Self, RecurrenceInvalid> {
+        let mut truncated = self.clone();
+        if *date <= self.start {
+            return Ok(truncated);
+        }
+        let next_start = date
+            .checked_sub_signed(Duration::nanoseconds(1))
+            .and_then(|date| self.unjittered().next_from(&date))
+            .ok_or_else(|| {
+                RecurrenceInvalid::new("There are no events after the truncation date")
+                    .with_kind(RecurrenceErrorKind::NoEvents)
+            })?;
+        truncated.exdates.retain(|exdate| exdate >= date);
+        truncated.not_before = self.not_before.max(Some(next_start));
+        Ok(truncated)
+    }
+
+    /// Returns a copy of the recurrence that ends at its last event until the given date,
+    /// E.g. to export an open-ended recurrence to systems that only accept bounded rules.
+    ///
+    /// The copy yields the same events as the recurrence until the date, its iteration limits
+    /// are replaced by the end date and the excluded dates after it are dropped, so its
+    /// `count_occurrences` is the COUNT of the exported rule. The recurrence is not changed.
+    /// Returns an error if there are no events until the date.
+    /// Examples:
+    /// ```
+    /// use std::str::FromStr;
+    /// use chrono::{DateTime, Utc};
+    /// use rrules::Recurrence;
+    ///
+    /// let weekly = Recurrence::from_str("FREQ=WEEKLY;INTERVAL=1;DTSTART=2023-01-02T10:00:00Z").unwrap();
+    /// let until = DateTime::<Utc>::from_str("2023-03-01T00:00:00Z").unwrap();
+    /// let bounded = weekly.bounded(&until).unwrap();
+    /// assert_eq!(bounded.end(), Some(DateTime::<Utc>::from_str("2023-02-27T10:00:00Z").unwrap()));
+    /// assert_eq!(bounded.count_occurrences(), Some(9));
+    /// assert!(weekly.is_infinite());
+    /// ```
+    pub fn bounded(&self, until: &DateTime<Utc>) -> Result<Self, RecurrenceInvalid> {
+        let last = self.unjittered().between(&self.start, until).last();
+        let last = last.ok_or_else(|| {
+            RecurrenceInvalid::new("There are no events before the bound date")
+                .with_kind(RecurrenceErrorKind::NoEvents)
+        })?;
+        self.ending_at(last)
+    }
+
+    /// Returns a copy of the recurrence that ends at its nth event, yielding the first `count`
+    /// events of the recurrence, or all of them when it has less events.
     ///
-    /// let mut recurrence = Recurrence::from_str("FREQ=DAILY;INTERVAL=1;DTSTART=2023-01-01T12:00:00Z").unwrap();
-    /// let first_event = recurrence.next().unwrap();
-    /// assert_eq!(first_event, DateTime::<Utc>::from_str("2023-01-01T12:00:00Z").unwrap());
+    /// The same as `bounded` at the date of the nth event. The recurrence is not changed.
+    /// Returns an error if the count is 0 or the recurrence has no events.
+    /// Examples:
+    /// ```
+    /// use std::str::FromStr;
+    /// use chrono::{DateTime, Utc};
+    /// use rrules::Recurrence;
     ///
-    /// let second_event = recurrence.next().unwrap(); assert_eq!(second_event, DateTime::<Utc>::from_str("2023-01-02T12:00:00Z").unwrap());
+    /// let daily = Recurrence::from_str("FREQ=DAILY;INTERVAL=1;DTSTART=2023-01-01T10:00:00Z").unwrap();
+    /// let first_10 = daily.with_count(10).unwrap();
+    /// assert_eq!(first_10.end(), Some(DateTime::<Utc>::from_str("2023-01-10T10:00:00Z").unwrap()));
+    /// assert_eq!(first_10.to_string(), "FREQ=DAILY;INTERVAL=1;DTSTART=2023-01-01T10:00:00Z;DTEND=2023-01-10T10:00:00Z");
     /// ```
-    fn next(&mut self) -> Option<Self::Item> {
-        if let Some(current_date) = self.current_date {
-            if current_date > self.end {
-                return None;
+    pub fn with_count(&self, count: usize) -> Result<Self, RecurrenceInvalid> {
+        let last = self.unjittered().iter().take(count).last();
+        let last = last.ok_or_else(|| {
+            RecurrenceInvalid::new("The count must include at least one event")
+                .with_kind(RecurrenceErrorKind::NoEvents)
+        })?;
+        self.ending_at(last)
+    }
+
+    /// Returns a copy of the recurrence whose last event is the given event.
+    fn ending_at(&self, last: DateTime<Utc>) -> Result<Self, RecurrenceInvalid> {
+        // The end date must be after the start date, so a single event ends a second later
+        // when the next event is not in between
+        let end = match last.checked_add_signed(Duration::seconds(1)) {
+            Some(after)
+                if last == self.start
+                    && self
+                        .unjittered()
+                        .next_from(&last)
+                        .is_none_or(|next| next > after) =>
+            {
+                after
             }
+            _ => last,
+        };
+        let mut bounded = self.clone().with_end(Some(end))?;
+        bounded.exdates.retain(|exdate| *exdate <= end);
+        bounded.count = None;
+        bounded.max_occurrences = None;
+        bounded.max_horizon = None;
+        Ok(bounded)
+    }
 
-            let next_event = self.frequency.next_event(&current_date);
-            self.set_current_date(next_event);
+    /// Returns the date the intervals of the frequency are counted from, which is the start date.
+    ///
+    /// E.g. every 3 months on the 1st Monday from January yields events in January, April,
+    /// July and October, even when looking for the events after a date in February.
+    pub fn anchor(&self) -> DateTime<Utc> {
+        self.start
+    }
 
-            return if self.frequency.contains(&current_date) {
-                Some(current_date)
-            } else {
-                self.next()
-            };
+    /// Returns the frequency rules of the recurrence.
+    pub fn frequency(&self) -> &E {
+        &self.frequency
+    }
+
+    /// Returns the start date of the recurrence.
+    pub fn start(&self) -> DateTime<Utc> {
+        self.start
+    }
+
+    /// Returns the end date of the recurrence, or None when it doesn't end.
+    pub fn end(&self) -> Option<DateTime<Utc>> {
+        self.end
+    }
+
+    /// Returns the date the events are yielded from, or None when they are yielded from the
+    /// start date, E.g. for the second recurrence returned by `split_at`.
+    pub fn not_before(&self) -> Option<DateTime<Utc>> {
+        self.not_before
+    }
+
+    /// Returns the duration of the events.
+    pub fn duration(&self) -> Duration {
+        self.duration
+    }
+
+    /// Splits the recurrence into its frequency, start date, end date and duration, the
+    /// values given to `Recurrence::new()`.
+    /// Examples:
+    /// ```
+    /// use std::str::FromStr;
+    /// use chrono::{DateTime, Duration, Utc};
+    /// use rrules::{Frequency, Recurrence};
+    ///
+    /// let recurrence = Recurrence::from_str("FREQ=DAILY;INTERVAL=2;DTSTART=2023-01-01T09:00:00Z;DURATION=PT1H").unwrap();
+    /// let (frequency, start, end, duration) = recurrence.into_parts();
+    /// assert_eq!(frequency.interval().get(), 2);
+    /// assert_eq!(start, DateTime::<Utc>::from_str("2023-01-01T09:00:00Z").unwrap());
+    /// assert_eq!(end, None);
+    /// assert_eq!(duration, Duration::hours(1));
+    /// ```
+    pub fn into_parts(self) -> (E, DateTime<Utc>, Option<DateTime<Utc>>, Duration) {
+        (self.frequency, self.start, self.end, self.duration)
+    }
+
+    /// Verifies if the recurrence has no end date, so its events go on indefinitely.
+    /// Examples:
+    /// ```
+    /// use std::str::FromStr;
+    /// use rrules::Recurrence;
+    ///
+    /// let recurrence = Recurrence::from_str("FREQ=DAILY;INTERVAL=1;DTSTART=2023-01-01T00:00:00Z").unwrap();
+    /// assert!(recurrence.is_infinite());
+    /// assert!(recurrence.end().is_none());
+    /// ```
+    pub fn is_infinite(&self) -> bool {
+        self.end.is_none()
+    }
+
+    /// Returns an iterator over the event dates of the recurrence.
+    /// Examples:
+    /// ```
+    /// use std::str::FromStr;
+    /// use chrono::{DateTime, Utc};
+    /// use rrules::Recurrence;
+    ///
+    /// let recurrence = Recurrence::from_str("FREQ=DAILY;INTERVAL=1;DTSTART=2023-01-01T12:00:00Z").unwrap();
+    /// let mut events = recurrence.iter();
+    /// assert_eq!(events.next().unwrap(), DateTime::<Utc>::from_str("2023-01-01T12:00:00Z").unwrap());
+    /// assert_eq!(events.next().unwrap(), DateTime::<Utc>::from_str("2023-01-02T12:00:00Z").unwrap());
+    ///
+    /// // The recurrence itself is not consumed, so it can be iterated again
+    /// assert_eq!(recurrence.iter().next().unwrap(), DateTime::<Utc>::from_str("2023-01-01T12:00:00Z").unwrap());
+    /// ```
+    pub fn iter(&self) -> OccurrenceIter<'_, E> {
+        OccurrenceIter::new(Cow::Borrowed(self))
+    }
+
+    /// Returns an iterator over the event dates of the recurrence, without their time.
+    ///
+    /// This is mostly useful for all-day recurrences.
+    pub fn dates(&self) -> DateIter<'_, E> {
+        DateIter::new(self.iter())
+    }
+
+    /// Returns an iterator over the events of the recurrence, with their index, their end
+    /// date and whether they are the first or the last event.
+    ///
+    /// Examples:
+    /// ```
+    /// use std::str::FromStr;
+    /// use chrono::{DateTime, Utc};
+    /// use rrules::Recurrence;
+    ///
+    /// let recurrence = Recurrence::from_str("FREQ=DAILY;INTERVAL=1;DTSTART=2023-01-01T09:00:00Z;DTEND=2023-01-03T09:00:00Z;DURATION=PT1H").unwrap();
+    /// let occurrences: Vec<_> = recurrence.detailed_iter().collect();
+    /// assert_eq!(occurrences.len(), 3);
+    /// assert!(occurrences[0].is_first);
+    /// assert_eq!(occurrences[2].index, 2);
+    /// assert_eq!(occurrences[2].end, DateTime::<Utc>::from_str("2023-01-03T10:00:00Z").unwrap());
+    /// assert!(occurrences[2].is_last);
+    /// ```
+    pub fn detailed_iter(&self) -> DetailedIter<'_, E> {
+        DetailedIter::new(self.iter())
+    }
+
+    /// Verifies if the date is an event date of the recurrence.
+    ///
+    /// Besides the frequency rules, the date must be between the start and end dates,
+    /// not excluded, and a whole number of intervals away from the start date.
+    /// Examples:
+    /// ```
+    /// use std::str::FromStr;
+    /// use chrono::{DateTime, Utc};
+    /// use rrules::Recurrence;
+    ///
+    /// let every_2_weeks = Recurrence::from_str(
+    ///     "FREQ=WEEKLY;INTERVAL=2;BYDAY=MO;DTSTART=2023-01-02T10:00:00Z"
+    /// ).unwrap();
+    /// assert!(every_2_weeks.contains(&DateTime::<Utc>::from_str("2023-01-16T10:00:00Z").unwrap()));
+    /// assert!(!every_2_weeks.contains(&DateTime::<Utc>::from_str("2023-01-09T10:00:00Z").unwrap()));
+    /// ```
+    pub fn contains(&self, date: &DateTime<Utc>) -> bool {
+        self.not_before.is_none_or(|not_before| *date >= not_before)
+            && self.is_rule_event(date)
+            && self
+                .count
+                .is_none_or(|count| u64::from(count) >= self.rule_events_until(date))
+    }
+
+    /// Verifies if the date is an event date of the recurrence, including the events skipped
+    /// before its `not_before` date.
+    pub(crate) fn is_rule_event(&self, date: &DateTime<Utc>) -> bool {
+        *date >= self.start
+            && self.end.is_none_or(|end| *date <= end)
+            && !self.exdates.contains(date)
+            && self.frequency.contains(&self.start, date)
+    }
+
+    /// Verifies if the instant is during an event of the recurrence, from the event date
+    /// included to its end excluded, E.g. to know if a time is busy.
+    ///
+    /// Events without a duration only contain their own date.
+    /// Examples:
+    /// ```
+    /// use std::str::FromStr;
+    /// use chrono::{DateTime, Utc};
+    /// use rrules::Recurrence;
+    ///
+    /// let meeting = Recurrence::from_str(
+    ///     "FREQ=WEEKLY;INTERVAL=1;DTSTART=2023-01-02T10:00:00Z;DURATION=PT1H"
+    /// ).unwrap();
+    /// assert!(meeting.contains_instant(&DateTime::<Utc>::from_str("2023-01-09T10:30:00Z").unwrap()));
+    /// assert!(!meeting.contains_instant(&DateTime::<Utc>::from_str("2023-01-09T11:00:00Z").unwrap()));
+    /// ```
+    pub fn contains_instant(&self, date: &DateTime<Utc>) -> bool {
+        self.event_covering(date).is_some()
+    }
+
+    /// Returns the event taking place at the instant, from its date included to its end
+    /// excluded, or None when the instant is outside of the events.
+    ///
+    /// When the events overlap, the one that started last is returned.
+    /// Examples:
+    /// ```
+    /// use std::str::FromStr;
+    /// use chrono::{DateTime, Utc};
+    /// use rrules::Recurrence;
+    ///
+    /// let meeting = Recurrence::from_str(
+    ///     "FREQ=WEEKLY;INTERVAL=1;DTSTART=2023-01-02T10:00:00Z;DURATION=PT1H"
+    /// ).unwrap();
+    /// let occurrence = meeting
+    ///     .occurrence_covering(&DateTime::<Utc>::from_str("2023-01-09T10:30:00Z").unwrap())
+    ///     .unwrap();
+    /// assert_eq!(occurrence.index, 1);
+    /// assert_eq!(occurrence.start, DateTime::<Utc>::from_str("2023-01-09T10:00:00Z").unwrap());
+    /// assert_eq!(occurrence.end, DateTime::<Utc>::from_str("2023-01-09T11:00:00Z").unwrap());
+    /// ```
+    pub fn occurrence_covering(&self, date: &DateTime<Utc>) -> Option<Occurrence> {
+        let event = self.event_covering(date)?;
+        let rules = self.unjittered();
+        let index = rules.position_of(&event)?;
+        let start = self.jittered(event);
+        Some(Occurrence {
+            index,
+            start,
+            end: start
+                .checked_add_signed(self.duration)
+                .unwrap_or(DateTime::<Utc>::MAX_UTC),
+            is_first: index == 0,
+            is_last: rules.next_from(&event).is_none(),
+        })
+    }
+
+    /// Returns the date of the last event taking place at the instant, before its jitter.
+    fn event_covering(&self, date: &DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let jitter = self.jitter.map_or(Duration::zero(), |jitter| jitter.max);
+        let from = date
+            .checked_sub_signed(self.duration)
+            .and_then(|from| from.checked_sub_signed(jitter))
+            .unwrap_or(DateTime::<Utc>::MIN_UTC);
+        let to = date.checked_add_signed(jitter).unwrap_or(*date);
+        self.unjittered()
+            .between(&from, &to)
+            .filter(|event| {
+                let start = self.jittered(*event);
+                let end = start
+                    .checked_add_signed(self.duration)
+                    .unwrap_or(DateTime::<Utc>::MAX_UTC);
+                start == *date || (start < *date && *date < end)
+            })
+            .last()
+    }
+
+    /// Returns the first event date after the given date, or None when there are no
+    /// more events. Excluded dates and the end date of the recurrence are respected.
+    /// Examples:
+    /// ```
+    /// use std::str::FromStr;
+    /// use chrono::{DateTime, Utc};
+    /// use rrules::Recurrence;
+    ///
+    /// let recurrence = Recurrence::from_str(
+    ///     "FREQ=HOURLY;INTERVAL=2;DTSTART=2023-01-01T10:00:00Z;DTEND=2023-01-01T14:00:00Z"
+    /// ).unwrap();
+    /// let date = DateTime::<Utc>::from_str("2023-01-01T11:00:00Z").unwrap();
+    /// assert_eq!(
+    ///     recurrence.next_from(&date),
+    ///     Some(DateTime::<Utc>::from_str("2023-01-01T12:00:00Z").unwrap())
+    /// );
+    /// let date = DateTime::<Utc>::from_str("2023-01-01T14:00:00Z").unwrap();
+    /// assert_eq!(recurrence.next_from(&date), None);
+    /// ```
+    pub fn next_from(&self, date: &DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let mut events = self.iter();
+        events.fast_forward(date);
+        events.find(|event| event > date)
+    }
+
+    /// Returns the nth event date of the recurrence, starting from 0, or None when the
+    /// recurrence has less events.
+    ///
+    /// Frequencies without BY* filters that have evenly spaced events compute it directly,
+    /// the others by iterating over the previous events.
+    /// Examples:
+    /// ```
+    /// use std::str::FromStr;
+    /// use chrono::{DateTime, Utc};
+    /// use rrules::Recurrence;
+    ///
+    /// let every_second = Recurrence::from_str("FREQ=SECONDLY;INTERVAL=1;DTSTART=2023-01-01T00:00:00Z").unwrap();
+    /// assert_eq!(
+    ///     every_second.nth(10_000),
+    ///     Some(DateTime::<Utc>::from_str("2023-01-01T02:46:40Z").unwrap())
+    /// );
+    /// ```
+    pub fn nth(&self, n: usize) -> Option<DateTime<Utc>> {
+        self.iter().nth(n)
+    }
+
+    /// Returns the nth event date of the recurrence, the same as `nth`.
+    pub fn nth_occurrence(&self, n: usize) -> Option<DateTime<Utc>> {
+        self.nth(n)
+    }
+
+    /// Returns the position of the event date in the recurrence, starting from 0, or None
+    /// when the date is not an event of the recurrence.
+    ///
+    /// Frequencies without BY* filters that have evenly spaced events compute it directly,
+    /// the others by iterating over the previous events.
+    /// Examples:
+    /// ```
+    /// use std::str::FromStr;
+    /// use chrono::{DateTime, Utc};
+    /// use rrules::Recurrence;
+    ///
+    /// let course = Recurrence::from_str("FREQ=WEEKLY;INTERVAL=1;BYDAY=TU,TH;DTSTART=2023-01-03T18:00:00Z").unwrap();
+    /// let session = DateTime::<Utc>::from_str("2023-02-09T18:00:00Z").unwrap();
+    /// assert_eq!(course.position_of(&session), Some(11));
+    /// assert_eq!(course.nth(11), Some(session));
+    /// ```
+    pub fn position_of(&self, date: &DateTime<Utc>) -> Option<usize> {
+        if !self.contains(date)
+            || self
+                .max_horizon
+                .is_some_and(|max_horizon| *date > max_horizon)
+        {
+            return None;
+        }
+        let Some(step) = self.frequency.fixed_step() else {
+            return self
+                .unjittered()
+                .iter()
+                .take_while(|event| event <= date)
+                .position(|event| event == *date);
+        };
+        let steps_from_start = |date: &DateTime<Utc>| {
+            let span = date.signed_duration_since(self.start);
+            span.num_milliseconds() / step.num_milliseconds()
+        };
+        if self
+            .count
+            .is_some_and(|count| steps_from_start(date) >= i64::from(count))
+        {
+            return None;
+        }
+        let rule_position = |date: &DateTime<Utc>| {
+            let steps = steps_from_start(date);
+            // The excluded events before the date don't take a position
+            let excluded: BTreeSet<&DateTime<Utc>> = self
+                .exdates
+                .iter()
+                .filter(|exdate| {
+                    *exdate >= &self.start
+                        && *exdate < date
+                        && self.frequency.contains(&self.start, exdate)
+                })
+                .collect();
+            usize::try_from(steps).ok()?.checked_sub(excluded.len())
+        };
+        let position = rule_position(date)?;
+        if self
+            .max_occurrences
+            .is_some_and(|max_occurrences| position >= max_occurrences)
+        {
+            return None;
+        }
+        // Neither do the events skipped before the first yielded one
+        match self.skipped_steps(step) {
+            0 => Some(position),
+            skipped => {
+                let span = i64::try_from(skipped)
+                    .ok()?
+                    .checked_mul(step.num_milliseconds())?;
+                let first = self
+                    .start
+                    .checked_add_signed(Duration::milliseconds(span))?;
+                position.checked_sub(rule_position(&first)?)
+            }
+        }
+    }
+
+    /// Returns the number of evenly spaced steps of the events skipped before the `not_before`
+    /// date, 0 when there is none.
+    fn skipped_steps(&self, step: Duration) -> u64 {
+        let Some(not_before) = self
+            .not_before
+            .filter(|not_before| *not_before > self.start)
+        else {
+            return 0;
+        };
+        let span = not_before
+            .signed_duration_since(self.start)
+            .num_milliseconds();
+        let step = step.num_milliseconds();
+        ((span + step - 1) / step) as u64
+    }
+
+    /// Returns an iterator over the event dates between the given dates, both included.
+    /// Examples:
+    /// ```
+    /// use std::str::FromStr;
+    /// use chrono::{DateTime, Utc};
+    /// use rrules::Recurrence;
+    ///
+    /// let recurrence = Recurrence::from_str("FREQ=DAILY;INTERVAL=1;DTSTART=2023-01-01T00:00:00Z").unwrap();
+    /// let from = DateTime::<Utc>::from_str("2023-02-01T00:00:00Z").unwrap();
+    /// let to = DateTime::<Utc>::from_str("2023-02-28T00:00:00Z").unwrap();
+    /// assert_eq!(recurrence.between(&from, &to).count(), 28);
+    /// ```
+    pub fn between(
+        &self,
+        from: &DateTime<Utc>,
+        to: &DateTime<Utc>,
+    ) -> impl Iterator<Item = DateTime<Utc>> + '_ {
+        let (from, to) = (*from, *to);
+        let mut events = self.iter();
+        events.fast_forward(&from);
+        events
+            .skip_while(move |event| *event < from)
+            .take_while(move |event| *event <= to)
+    }
+
+    /// Returns an iterator over the event dates of the window that are also event dates
+    /// of the other recurrence.
+    /// Examples:
+    /// ```
+    /// use std::str::FromStr;
+    /// use chrono::{DateTime, Utc};
+    /// use rrules::Recurrence;
+    ///
+    /// let every_2_days = Recurrence::from_str("FREQ=DAILY;INTERVAL=2;DTSTART=2023-01-01T00:00:00Z").unwrap();
+    /// let every_3_days = Recurrence::from_str("FREQ=DAILY;INTERVAL=3;DTSTART=2023-01-01T00:00:00Z").unwrap();
+    /// let from = DateTime::<Utc>::from_str("2023-01-01T00:00:00Z").unwrap();
+    /// let to = DateTime::<Utc>::from_str("2023-01-31T00:00:00Z").unwrap();
+    /// assert_eq!(every_2_days.intersect(&every_3_days, from..to).count(), 5);
+    /// ```
+    pub fn intersect<'a, O: OccurrenceEngine>(
+        &'a self,
+        other: &'a Recurrence<O>,
+        window: Range<DateTime<Utc>>,
+    ) -> impl Iterator<Item = DateTime<Utc>> + 'a {
+        let mut others = other.within(&window).peekable();
+        self.within(&window).filter(move |event| {
+            while others.next_if(|other| other < event).is_some() {}
+            others.peek() == Some(event)
+        })
+    }
+
+    /// Returns an iterator over the event dates of the window that are not event dates
+    /// of the other recurrence.
+    /// Examples:
+    /// ```
+    /// use std::str::FromStr;
+    /// use chrono::{DateTime, Utc};
+    /// use rrules::Recurrence;
+    ///
+    /// let weekdays = Recurrence::from_str("FREQ=WEEKLY;INTERVAL=1;BYDAY=MO,TU,WE,TH,FR;DTSTART=2023-01-02T09:00:00Z").unwrap();
+    /// let holiday = Recurrence::from_str("FREQ=YEARLY;INTERVAL=1;DTSTART=2023-01-02T09:00:00Z").unwrap();
+    /// let from = DateTime::<Utc>::from_str("2024-01-01T00:00:00Z").unwrap();
+    /// let to = DateTime::<Utc>::from_str("2024-01-08T00:00:00Z").unwrap();
+    /// let working_days: Vec<_> = weekdays.difference(&holiday, from..to).collect();
+    /// assert_eq!(working_days.len(), 4);
+    /// assert_eq!(working_days[1], DateTime::<Utc>::from_str("2024-01-03T09:00:00Z").unwrap());
+    /// ```
+    pub fn difference<'a, O: OccurrenceEngine>(
+        &'a self,
+        other: &'a Recurrence<O>,
+        window: Range<DateTime<Utc>>,
+    ) -> impl Iterator<Item = DateTime<Utc>> + 'a {
+        let mut others = other.within(&window).peekable();
+        self.within(&window).filter(move |event| {
+            while others.next_if(|other| other < event).is_some() {}
+            others.peek() != Some(event)
+        })
+    }
+
+    /// Returns an iterator over the event dates from the start of the window, excluding its end.
+    pub(crate) fn within(
+        &self,
+        window: &Range<DateTime<Utc>>,
+    ) -> impl Iterator<Item = DateTime<Utc>> + '_ {
+        let end = window.end;
+        self.between(&window.start, &end)
+            .take_while(move |event| *event < end)
+    }
+
+    /// Returns the first event date after the current time, or None when there are no
+    /// more events.
+    #[cfg(feature = "std")]
+    pub fn next_from_now(&self) -> Option<DateTime<Utc>> {
+        self.next_from_now_with(&SystemClock)
+    }
+
+    /// Returns the first event date after the current time of the clock, or None when there
+    /// are no more events, E.g. with a `MockClock` in tests.
+    pub fn next_from_now_with<C: Clock + ?Sized>(&self, clock: &C) -> Option<DateTime<Utc>> {
+        self.next_from(&clock.now())
+    }
+
+    /// Returns the total number of events of the recurrence, or None when it has no end date
+    /// nor iteration limits.
+    ///
+    /// Frequencies without BY* filters that have evenly spaced events are counted
+    /// arithmetically, the others by iterating over the events until the end date.
+    /// Examples:
+    /// ```
+    /// use std::str::FromStr;
+    /// use rrules::Recurrence;
+    ///
+    /// let every_minute_in_2023 = Recurrence::from_str(
+    ///     "FREQ=MINUTELY;INTERVAL=1;DTSTART=2023-01-01T00:00:00Z;DTEND=2023-12-31T23:59:00Z"
+    /// ).unwrap();
+    /// assert_eq!(every_minute_in_2023.count_occurrences(), Some(525_600));
+    ///
+    /// let without_end = Recurrence::from_str("FREQ=DAILY;INTERVAL=1;DTSTART=2023-01-01T00:00:00Z");
+    /// assert_eq!(without_end.unwrap().count_occurrences(), None);
+    /// ```
+    pub fn count_occurrences(&self) -> Option<u64> {
+        let end = match (self.end, self.max_horizon) {
+            (Some(end), Some(max_horizon)) => Some(end.min(max_horizon)),
+            (end, max_horizon) => end.or(max_horizon),
+        };
+        let end = match end {
+            Some(end) => end,
+            None if self.count.is_some() || self.max_occurrences.is_some() => max_date(),
+            None => return None,
+        };
+        match self.frequency.fixed_step() {
+            Some(_) if end < self.start => Some(0),
+            Some(step) if self.exdates.is_empty() => {
+                let span = end.signed_duration_since(self.start);
+                let steps = span.num_milliseconds() / step.num_milliseconds();
+                let count = steps as u64 + 1;
+                let count = self
+                    .count
+                    .map_or(count, |rule_count| count.min(u64::from(rule_count)));
+                let count = self
+                    .max_occurrences
+                    .map_or(count, |max| count.min(max as u64));
+                Some(count.saturating_sub(self.skipped_steps(step)))
+            }
+            _ => Some(self.iter().count() as u64),
         }
-        None
     }
 }
+
+/// Returns the midnight UTC instant of the date.
+pub(crate) fn start_of_day(date: NaiveDate) -> DateTime<Utc> {
+    Utc.from_utc_datetime(&date.and_time(NaiveTime::MIN))
+}
+
+impl Recurrence {
+    /// Returns the values that identify the recurrence: its canonical rule along with the
+    /// values that are not serialized in it.
+    fn canonical_key(&self) -> CanonicalKey {
+        (
+            self.canonical_string(),
+            self.exdates.iter().copied().collect(),
+            self.max_occurrences,
+            self.max_horizon,
+            self.jitter,
+        )
+    }
+}
+
+type CanonicalKey = (
+    String,
+    BTreeSet<DateTime<Utc>>,
+    Option<usize>,
+    Option<DateTime<Utc>>,
+    Option<Jitter>,
+);
+
+impl PartialEq for Recurrence {
+    fn eq(&self, other: &Self) -> bool {
+        self.canonical_key() == other.canonical_key()
+    }
+}
+
+impl Eq for Recurrence {}
+
+impl Hash for Recurrence {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.canonical_key().hash(state);
+    }
+}
+
+impl<E: OccurrenceEngine + 'static> IntoIterator for Recurrence<E> {
+    type Item = DateTime<Utc>;
+    type IntoIter = OccurrenceIter<'static, E>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        OccurrenceIter::new(Cow::Owned(self))
+    }
+}
+
+impl<'a, E: OccurrenceEngine> IntoIterator for &'a Recurrence<E> {
+    type Item = DateTime<Utc>;
+    type IntoIter = OccurrenceIter<'a, E>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+// The rules are immutable values, so they can be shared between threads and evaluated
+// concurrently. Adding a field that is not Send or Sync fails to compile here.
+const _: () = {
+    const fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<Recurrence>();
+    assert_send_sync::<Frequency>();
+    assert_send_sync::<OccurrenceIter<'static>>();
+};
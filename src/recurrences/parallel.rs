@@ -0,0 +1,106 @@
+use crate::recurrences::Recurrence;
+use alloc::vec::Vec;
+use chrono::{DateTime, Duration, Utc};
+use rayon::prelude::*;
+
+/// Number of ranges given to every thread, so that the threads that finish early can take
+/// the ranges of the others when the events are unevenly spread.
+const RANGES_PER_THREAD: i32 = 4;
+
+impl Recurrence {
+    /// Returns the event dates between the given dates, both included, the same as `between`,
+    /// computing the events of parts of the range in parallel.
+    ///
+    /// The range is split in smaller ranges of the same length, whose events are computed
+    /// on the threads of the rayon pool. It is worth it for long ranges of rules whose events
+    /// are slow to compute, like the monthly and yearly rules with many filters.
+    ///
+    /// Examples:
+    /// ```
+    /// use std::str::FromStr;
+    /// use chrono::{DateTime, Utc};
+    /// use rrules::Recurrence;
+    ///
+    /// let recurrence = Recurrence::from_str(
+    ///     "FREQ=MONTHLY;INTERVAL=1;BYMONTHDAY=13;BYDAY=FR;DTSTART=2000-01-01T00:00:00Z"
+    /// ).unwrap();
+    /// let from = DateTime::<Utc>::from_str("2000-01-01T00:00:00Z").unwrap();
+    /// let to = DateTime::<Utc>::from_str("2099-12-31T00:00:00Z").unwrap();
+    /// let events = recurrence.par_between(&from, &to);
+    /// assert_eq!(events, recurrence.between(&from, &to).collect::<Vec<_>>());
+    /// ```
+    pub fn par_between(&self, from: &DateTime<Utc>, to: &DateTime<Utc>) -> Vec<DateTime<Utc>> {
+        if from > to {
+            return Vec::new();
+        }
+        let threads = i32::try_from(rayon::current_num_threads()).unwrap_or(i32::MAX);
+        let ranges = threads.saturating_mul(RANGES_PER_THREAD).max(1);
+        let length = (*to - *from) / ranges;
+        if length <= Duration::zero() {
+            return self.between(from, to).collect();
+        }
+        (0..ranges)
+            .into_par_iter()
+            .flat_map_iter(|index| {
+                let start = *from + length * index;
+                // The last range ends at the end date, which is included
+                if index == ranges - 1 {
+                    self.between(&start, to).collect::<Vec<_>>()
+                } else {
+                    self.within(&(start..start + length)).collect()
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test_parallel {
+    use crate::test_utils::date;
+    use crate::Recurrence;
+    use std::str::FromStr;
+
+    #[test]
+    fn computes_the_same_events_as_between() {
+        let rules = [
+            "FREQ=YEARLY;INTERVAL=1;BYMONTH=11;BYDAY=4TH;DTSTART=1990-01-01T12:00:00Z",
+            "FREQ=MONTHLY;INTERVAL=2;BYMONTHDAY=1,31;X-OVERFLOW=CLAMP;DTSTART=1990-01-31T08:00:00Z",
+            "FREQ=DAILY;INTERVAL=1;BYTIME=09:00,17:30;DTSTART=1990-01-01T00:00:00Z",
+            "FREQ=WEEKLY;INTERVAL=3;BYDAY=TU;DTSTART=1990-01-02T10:00:00Z;DTEND=2000-01-01T00:00:00Z",
+        ];
+        let (from, to) = (date("1995-06-15T09:00:00Z"), date("2030-01-01T12:00:00Z"));
+        for rule in rules {
+            let recurrence = Recurrence::from_str(rule).unwrap();
+            let expected: Vec<_> = recurrence.between(&from, &to).collect();
+            assert_eq!(recurrence.par_between(&from, &to), expected, "{rule}");
+        }
+    }
+
+    #[test]
+    fn short_and_empty_ranges() {
+        let recurrence =
+            Recurrence::from_str("FREQ=SECONDLY;INTERVAL=1;DTSTART=2023-01-01T00:00:00Z").unwrap();
+        let from = date("2023-01-01T00:00:00Z");
+        assert_eq!(recurrence.par_between(&from, &from), vec![from]);
+        assert!(recurrence
+            .par_between(&date("2023-01-01T00:00:01Z"), &from)
+            .is_empty());
+        assert_eq!(
+            recurrence
+                .par_between(&from, &date("2023-01-01T00:01:00Z"))
+                .len(),
+            61
+        );
+    }
+
+    #[test]
+    fn respects_the_iteration_limits() {
+        let recurrence = Recurrence::from_str("FREQ=DAILY;INTERVAL=1;DTSTART=2023-01-01T00:00:00Z")
+            .unwrap()
+            .with_max_occurrences(100);
+        let (from, to) = (date("2023-02-01T00:00:00Z"), date("2024-01-01T00:00:00Z"));
+        let events = recurrence.par_between(&from, &to);
+        assert_eq!(events, recurrence.between(&from, &to).collect::<Vec<_>>());
+        assert_eq!(events.len(), 69);
+    }
+}
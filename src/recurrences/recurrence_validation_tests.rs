@@ -3,11 +3,16 @@ mod secondly_validations {
     use crate::frequencies::Frequency;
     use crate::recurrences::Recurrence;
     use chrono::{DateTime, Duration, Utc};
+    use std::num::NonZeroU32;
     use std::str::FromStr;
 
     #[test]
     fn every_second() {
-        let freq = Frequency::Secondly { interval: 1 };
+        let freq = Frequency::Secondly {
+            interval: NonZeroU32::new(1).unwrap(),
+            by_day: vec![],
+            by_time: vec![],
+        };
         let recurrence = Recurrence::new(
             freq,
             DateTime::<Utc>::from_str("2023-01-01T00:00:00Z").unwrap(),
@@ -19,7 +24,11 @@ mod secondly_validations {
 
     #[test]
     fn every_second_with_invalid_duration() {
-        let freq = Frequency::Secondly { interval: 1 };
+        let freq = Frequency::Secondly {
+            interval: NonZeroU32::new(1).unwrap(),
+            by_day: vec![],
+            by_time: vec![],
+        };
         let recurrence = Recurrence::new(
             freq,
             DateTime::<Utc>::from_str("2023-01-01T00:00:00Z").unwrap(),
@@ -35,11 +44,17 @@ mod minutely_validations {
     use crate::frequencies::Frequency;
     use crate::recurrences::Recurrence;
     use chrono::{DateTime, Duration, Utc};
+    use std::num::NonZeroU32;
     use std::str::FromStr;
 
     #[test]
     fn every_minute() {
-        let freq = Frequency::Minutely { interval: 1 };
+        let freq = Frequency::Minutely {
+            interval: NonZeroU32::new(1).unwrap(),
+            by_day: vec![],
+            by_time: vec![],
+            by_hour_range: None,
+        };
         let recurrence = Recurrence::new(
             freq,
             DateTime::<Utc>::from_str("2023-01-01T00:00:00Z").unwrap(),
@@ -51,7 +66,12 @@ mod minutely_validations {
 
     #[test]
     fn every_minute_with_invalid_duration() {
-        let freq = Frequency::Minutely { interval: 1 };
+        let freq = Frequency::Minutely {
+            interval: NonZeroU32::new(1).unwrap(),
+            by_day: vec![],
+            by_time: vec![],
+            by_hour_range: None,
+        };
         let recurrence = Recurrence::new(
             freq,
             DateTime::<Utc>::from_str("2023-01-01T00:00:00Z").unwrap(),
@@ -65,13 +85,19 @@ mod minutely_validations {
 #[cfg(test)]
 mod hourly_validations {
     use crate::frequencies::Frequency;
-    use crate::recurrences::Recurrence;
+    use crate::recurrences::{Recurrence, RecurrenceErrorKind};
     use chrono::{DateTime, Duration, Utc};
+    use std::num::NonZeroU32;
     use std::str::FromStr;
 
     #[test]
     fn every_hour() {
-        let freq = Frequency::Hourly { interval: 1 };
+        let freq = Frequency::Hourly {
+            interval: NonZeroU32::new(1).unwrap(),
+            by_day: vec![],
+            by_time: vec![],
+            by_hour_range: None,
+        };
         let recurrence = Recurrence::new(
             freq,
             DateTime::<Utc>::from_str("2023-01-01T00:00:00Z").unwrap(),
@@ -83,7 +109,12 @@ mod hourly_validations {
 
     #[test]
     fn every_hour_with_invalid_duration() {
-        let freq = Frequency::Hourly { interval: 1 };
+        let freq = Frequency::Hourly {
+            interval: NonZeroU32::new(1).unwrap(),
+            by_day: vec![],
+            by_time: vec![],
+            by_hour_range: None,
+        };
         let recurrence = Recurrence::new(
             freq,
             DateTime::<Utc>::from_str("2023-01-01T00:00:00Z").unwrap(),
@@ -92,34 +123,56 @@ mod hourly_validations {
         );
         assert!(recurrence.is_err());
     }
+
+    #[test]
+    fn every_hour_by_time_the_interval_never_reaches() {
+        let rule = "FREQ=HOURLY;INTERVAL=1;BYTIME=09:00;DTSTART=2023-01-01T08:30:00Z";
+        let error = Recurrence::from_str(rule).unwrap_err();
+        assert_eq!(
+            error.kind,
+            RecurrenceErrorKind::InvalidValue {
+                value: "09:00".to_string()
+            }
+        );
+        assert!(Recurrence::from_str(
+            "FREQ=HOURLY;INTERVAL=1;BYTIME=09:00,09:30;DTSTART=2023-01-01T08:30:00Z"
+        )
+        .is_ok());
+        assert!(Recurrence::from_str(
+            "FREQ=HOURLY;INTERVAL=1;BYHOUR=9;DTSTART=2023-01-01T08:30:00Z"
+        )
+        .is_ok());
+
+        let recurrence = Recurrence::from_str(
+            "FREQ=HOURLY;INTERVAL=1;BYTIME=09:30;DTSTART=2023-01-01T08:30:00Z",
+        )
+        .unwrap();
+        assert!(recurrence
+            .with_start(DateTime::<Utc>::from_str("2023-01-01T08:00:00Z").unwrap())
+            .is_err());
+    }
 }
 
 #[cfg(test)]
 mod daily_validations {
     use crate::frequencies::{Frequency, Time};
+    use crate::recurrences::validations::validate_duration;
     use crate::recurrences::Recurrence;
     use chrono::{DateTime, Duration, Utc};
+    use std::num::NonZeroU32;
     use std::str::FromStr;
 
     #[test]
     fn invalid_interval() {
-        let freq = Frequency::Daily {
-            interval: 0,
-            by_time: vec![],
-        };
-        let recurrence = Recurrence::new(
-            freq,
-            DateTime::<Utc>::from_str("2023-01-01T00:00:00Z").unwrap(),
-            None,
-            None,
-        );
+        let recurrence = Recurrence::from_str("FREQ=DAILY;INTERVAL=0;DTSTART=2023-01-01T00:00:00Z");
         assert!(recurrence.is_err());
     }
 
     #[test]
     fn every_day() {
         let freq = Frequency::Daily {
-            interval: 1,
+            interval: NonZeroU32::new(1).unwrap(),
+            by_day: vec![],
             by_time: vec![],
         };
         let recurrence = Recurrence::new(
@@ -134,7 +187,8 @@ mod daily_validations {
     #[test]
     fn every_day_with_invalid_duration() {
         let freq = Frequency::Daily {
-            interval: 1,
+            interval: NonZeroU32::new(1).unwrap(),
+            by_day: vec![],
             by_time: vec![],
         };
         let recurrence = Recurrence::new(
@@ -149,7 +203,8 @@ mod daily_validations {
     #[test]
     fn every_day_by_time_with_invalid_duration() {
         let freq = Frequency::Daily {
-            interval: 1,
+            interval: NonZeroU32::new(1).unwrap(),
+            by_day: vec![],
             by_time: vec![
                 Time::from_str("12:00:00").unwrap(),
                 Time::from_str("14:00:00").unwrap(),
@@ -167,7 +222,8 @@ mod daily_validations {
     #[test]
     fn every_day_by_time_with_invalid_duration_over_day() {
         let freq = Frequency::Daily {
-            interval: 1,
+            interval: NonZeroU32::new(1).unwrap(),
+            by_day: vec![],
             by_time: vec![
                 Time::from_str("02:00:00").unwrap(),
                 Time::from_str("22:00:00").unwrap(),
@@ -181,20 +237,39 @@ mod daily_validations {
         );
         assert!(recurrence.is_err());
     }
+
+    #[test]
+    fn unsorted_times_with_invalid_duration() {
+        let freq = Frequency::Daily {
+            interval: NonZeroU32::new(1).unwrap(),
+            by_day: vec![],
+            by_time: vec![
+                Time::from_str("09:00:00").unwrap(),
+                Time::from_str("18:00:00").unwrap(),
+                Time::from_str("12:00:00").unwrap(),
+            ],
+        };
+        let start = DateTime::<Utc>::from_str("2023-01-01T00:00:00Z").unwrap();
+        assert!(validate_duration(&freq, &start, &Duration::hours(5)).is_err());
+        assert!(validate_duration(&freq, &start, &Duration::hours(3)).is_ok());
+    }
 }
 
 #[cfg(test)]
 mod weekly_validations {
-    use crate::frequencies::Frequency;
-    use crate::recurrences::Recurrence;
+    use crate::frequencies::{Frequency, Time};
+    use crate::recurrences::{Recurrence, RecurrenceErrorKind};
     use chrono::{DateTime, Duration, Utc, Weekday};
+    use std::num::NonZeroU32;
     use std::str::FromStr;
 
     #[test]
     fn every_week() {
         let freq = Frequency::Weekly {
-            interval: 1,
+            interval: NonZeroU32::new(1).unwrap(),
             by_day: vec![],
+            by_time: vec![],
+            by_day_time: vec![],
         };
         let recurrence = Recurrence::new(
             freq,
@@ -208,8 +283,10 @@ mod weekly_validations {
     #[test]
     fn every_week_with_invalid_duration() {
         let freq = Frequency::Weekly {
-            interval: 1,
+            interval: NonZeroU32::new(1).unwrap(),
             by_day: vec![],
+            by_time: vec![],
+            by_day_time: vec![],
         };
         let recurrence = Recurrence::new(
             freq,
@@ -223,8 +300,10 @@ mod weekly_validations {
     #[test]
     fn every_week_by_day_with_invalid_duration() {
         let freq = Frequency::Weekly {
-            interval: 1,
+            interval: NonZeroU32::new(1).unwrap(),
             by_day: vec![Weekday::Mon, Weekday::Wed, Weekday::Fri],
+            by_time: vec![],
+            by_day_time: vec![],
         };
         let recurrence = Recurrence::new(
             freq,
@@ -234,21 +313,122 @@ mod weekly_validations {
         );
         assert!(recurrence.is_err());
     }
+
+    #[test]
+    fn every_week_by_day_time_with_invalid_duration() {
+        let start = DateTime::<Utc>::from_str("2023-01-02T00:00:00Z").unwrap();
+        let freq = Frequency::Weekly {
+            interval: NonZeroU32::new(1).unwrap(),
+            by_day: vec![],
+            by_time: vec![],
+            by_day_time: vec![
+                (Weekday::Mon, Time::from_str("09:00").unwrap()),
+                (Weekday::Mon, Time::from_str("14:00").unwrap()),
+                (Weekday::Thu, Time::from_str("14:00").unwrap()),
+            ],
+        };
+        let recurrence = Recurrence::new(freq.clone(), start, None, Some(Duration::hours(5)));
+        assert!(recurrence.is_ok());
+
+        let error = Recurrence::new(freq.clone(), start, None, Some(Duration::hours(6)));
+        assert_eq!(
+            error.unwrap_err().kind,
+            RecurrenceErrorKind::OverlappingTimes {
+                first: Time::from_str("09:00").unwrap(),
+                second: Time::from_str("14:00").unwrap(),
+            }
+        );
+
+        // The last event of the week lasts until the first one of the next week
+        let freq = Frequency::Weekly {
+            interval: NonZeroU32::new(1).unwrap(),
+            by_day: vec![],
+            by_time: vec![],
+            by_day_time: vec![
+                (Weekday::Mon, Time::from_str("09:00").unwrap()),
+                (Weekday::Fri, Time::from_str("09:00").unwrap()),
+            ],
+        };
+        let duration = Duration::days(3) + Duration::hours(12);
+        let error = Recurrence::new(freq, start, None, Some(duration));
+        assert_eq!(
+            error.unwrap_err().kind,
+            RecurrenceErrorKind::OverlappingWeekdays {
+                first: Weekday::Fri,
+                second: Weekday::Mon,
+            }
+        );
+    }
+
+    #[test]
+    fn every_week_by_time_with_invalid_duration() {
+        let start = DateTime::<Utc>::from_str("2023-01-02T00:00:00Z").unwrap();
+        let freq = Frequency::Weekly {
+            interval: NonZeroU32::new(1).unwrap(),
+            by_day: vec![Weekday::Mon, Weekday::Wed],
+            by_time: vec![
+                Time::from_str("09:00").unwrap(),
+                Time::from_str("17:00").unwrap(),
+            ],
+            by_day_time: vec![],
+        };
+        let recurrence = Recurrence::new(freq.clone(), start, None, Some(Duration::hours(8)));
+        assert!(recurrence.is_ok());
+
+        let error = Recurrence::new(freq, start, None, Some(Duration::hours(9)));
+        assert_eq!(
+            error.unwrap_err().kind,
+            RecurrenceErrorKind::OverlappingTimes {
+                first: Time::from_str("09:00").unwrap(),
+                second: Time::from_str("17:00").unwrap(),
+            }
+        );
+
+        // Without days, the times are on the weekday of the start date
+        let freq = Frequency::Weekly {
+            interval: NonZeroU32::new(1).unwrap(),
+            by_day: vec![],
+            by_time: vec![
+                Time::from_str("09:00").unwrap(),
+                Time::from_str("17:00").unwrap(),
+            ],
+            by_day_time: vec![],
+        };
+        assert!(Recurrence::new(freq.clone(), start, None, Some(Duration::hours(8))).is_ok());
+        assert!(Recurrence::new(freq, start, None, Some(Duration::hours(9))).is_err());
+    }
+
+    #[test]
+    fn all_day_by_day_time_is_invalid() {
+        let freq = Frequency::Weekly {
+            interval: NonZeroU32::new(1).unwrap(),
+            by_day: vec![],
+            by_time: vec![],
+            by_day_time: vec![(Weekday::Mon, Time::from_str("09:00").unwrap())],
+        };
+        let start = DateTime::<Utc>::from_str("2023-01-02T00:00:00Z").unwrap();
+        let error = Recurrence::all_day(freq, start.date_naive(), None).unwrap_err();
+        assert_eq!(error.kind, RecurrenceErrorKind::AllDayWithTimes);
+    }
 }
 
 #[cfg(test)]
 mod monthly_validations {
-    use crate::frequencies::{Frequency, NthWeekday};
-    use crate::recurrences::Recurrence;
+    use crate::frequencies::{Frequency, NthWeekday, OverflowPolicy, Time};
+    use crate::recurrences::{Recurrence, RecurrenceErrorKind};
     use chrono::{DateTime, Duration, Utc, Weekday};
+    use std::num::NonZeroU32;
     use std::str::FromStr;
 
     #[test]
     fn every_month() {
         let freq = Frequency::Monthly {
-            interval: 1,
+            interval: NonZeroU32::new(1).unwrap(),
             by_month_day: vec![],
+            by_day: vec![],
             nth_weekdays: vec![],
+            by_time: vec![],
+            overflow: OverflowPolicy::Skip,
         };
         let recurrence = Recurrence::new(
             freq,
@@ -262,12 +442,15 @@ mod monthly_validations {
     #[test]
     fn every_month_by_nth_weekday() {
         let every_mon_and_fri = Frequency::Monthly {
-            interval: 1,
+            interval: NonZeroU32::new(1).unwrap(),
             by_month_day: vec![],
+            by_day: vec![],
             nth_weekdays: vec![
                 NthWeekday::new(Weekday::Wed, 1),
                 NthWeekday::new(Weekday::Fri, 1),
             ],
+            by_time: vec![],
+            overflow: OverflowPolicy::Skip,
         };
         let recurrence = Recurrence::new(
             every_mon_and_fri,
@@ -281,9 +464,12 @@ mod monthly_validations {
     #[test]
     fn every_month_with_invalid_duration() {
         let freq = Frequency::Monthly {
-            interval: 1,
+            interval: NonZeroU32::new(1).unwrap(),
             by_month_day: vec![],
+            by_day: vec![],
             nth_weekdays: vec![],
+            by_time: vec![],
+            overflow: OverflowPolicy::Skip,
         };
         let recurrence = Recurrence::new(
             freq,
@@ -297,9 +483,12 @@ mod monthly_validations {
     #[test]
     fn every_month_by_month_day_invalid_duration() {
         let freq = Frequency::Monthly {
-            interval: 1,
+            interval: NonZeroU32::new(1).unwrap(),
             by_month_day: vec![1, 15],
+            by_day: vec![],
             nth_weekdays: vec![],
+            by_time: vec![],
+            overflow: OverflowPolicy::Skip,
         };
         let recurrence = Recurrence::new(
             freq,
@@ -313,13 +502,16 @@ mod monthly_validations {
     #[test]
     fn every_month_by_weekday_invalid_duration() {
         let freq = Frequency::Monthly {
-            interval: 1,
+            interval: NonZeroU32::new(1).unwrap(),
             by_month_day: vec![],
+            by_day: vec![],
             nth_weekdays: vec![
                 NthWeekday::new(Weekday::Mon, 1),
                 NthWeekday::new(Weekday::Wed, 1),
                 NthWeekday::new(Weekday::Fri, 1),
             ],
+            by_time: vec![],
+            overflow: OverflowPolicy::Skip,
         };
         let recurrence = Recurrence::new(
             freq,
@@ -329,4 +521,300 @@ mod monthly_validations {
         );
         assert!(recurrence.is_err());
     }
+
+    #[test]
+    fn every_month_by_time_invalid_duration() {
+        let freq = Frequency::Monthly {
+            interval: NonZeroU32::new(1).unwrap(),
+            by_month_day: vec![1, 15],
+            by_day: vec![],
+            nth_weekdays: vec![],
+            by_time: vec![
+                Time::from_str("08:00").unwrap(),
+                Time::from_str("20:00").unwrap(),
+            ],
+            overflow: OverflowPolicy::Skip,
+        };
+        let start = DateTime::<Utc>::from_str("2023-01-01T00:00:00Z").unwrap();
+        let recurrence = Recurrence::new(freq.clone(), start, None, Some(Duration::hours(12)));
+        assert!(recurrence.is_ok());
+
+        let error = Recurrence::new(freq.clone(), start, None, Some(Duration::hours(13)));
+        assert_eq!(
+            error.unwrap_err().kind,
+            RecurrenceErrorKind::OverlappingTimes {
+                first: Time::from_str("08:00").unwrap(),
+                second: Time::from_str("20:00").unwrap(),
+            }
+        );
+
+        let error = Recurrence::all_day(freq, start.date_naive(), None).unwrap_err();
+        assert_eq!(error.kind, RecurrenceErrorKind::AllDayWithTimes);
+    }
+}
+
+#[cfg(test)]
+mod out_of_range_validations {
+    use crate::frequencies::{Frequency, NthWeekday, OverflowPolicy};
+    use crate::recurrences::validations::validate_duration;
+    use chrono::{DateTime, Duration, Utc, Weekday};
+    use std::num::NonZeroU32;
+    use std::str::FromStr;
+
+    fn start() -> DateTime<Utc> {
+        DateTime::<Utc>::from_str("2023-01-01T00:00:00Z").unwrap()
+    }
+
+    #[test]
+    fn invalid_month_days_are_errors() {
+        for by_month_day in [vec![1, 32], vec![-1, 15], vec![0, 1]] {
+            let freq = Frequency::Monthly {
+                interval: NonZeroU32::new(1).unwrap(),
+                by_month_day,
+                by_day: vec![],
+                nth_weekdays: vec![],
+                by_time: vec![],
+                overflow: OverflowPolicy::Skip,
+            };
+            assert!(validate_duration(&freq, &start(), &Duration::hours(1)).is_err());
+        }
+    }
+
+    #[test]
+    fn invalid_week_numbers_are_errors() {
+        let freq = Frequency::Monthly {
+            interval: NonZeroU32::new(1).unwrap(),
+            by_month_day: vec![],
+            by_day: vec![],
+            nth_weekdays: vec![
+                NthWeekday::new(Weekday::Mon, 0),
+                NthWeekday::new(Weekday::Fri, 1),
+            ],
+            by_time: vec![],
+            overflow: OverflowPolicy::Skip,
+        };
+        assert!(validate_duration(&freq, &start(), &Duration::hours(1)).is_err());
+    }
+
+    #[test]
+    fn overflowing_durations_are_errors() {
+        let freq = Frequency::Weekly {
+            interval: NonZeroU32::MAX,
+            by_day: vec![Weekday::Mon, Weekday::Fri],
+            by_time: vec![],
+            by_day_time: vec![],
+        };
+        assert!(validate_duration(&freq, &start(), &Duration::weeks(i32::MAX as i64)).is_err());
+
+        let freq = Frequency::Monthly {
+            interval: NonZeroU32::MAX,
+            by_month_day: vec![],
+            by_day: vec![],
+            nth_weekdays: vec![],
+            by_time: vec![],
+            overflow: OverflowPolicy::Skip,
+        };
+        assert!(validate_duration(&freq, &start(), &Duration::weeks(i32::MAX as i64)).is_ok());
+    }
+}
+
+#[cfg(test)]
+mod deterministic_validations {
+    use crate::frequencies::{Frequency, NthWeekday, OverflowPolicy};
+    use crate::recurrences::Recurrence;
+    use crate::test_utils::date;
+    use chrono::{Duration, Weekday};
+    use std::num::NonZeroU32;
+    use std::str::FromStr;
+
+    fn monthly(by_month_day: Vec<i32>, nth_weekdays: Vec<NthWeekday>) -> Frequency {
+        Frequency::Monthly {
+            interval: NonZeroU32::new(1).unwrap(),
+            by_month_day,
+            by_day: vec![],
+            nth_weekdays,
+            by_time: vec![],
+            overflow: OverflowPolicy::Skip,
+        }
+    }
+
+    #[test]
+    fn month_days_are_validated_from_any_start_date() {
+        // Starting in short months doesn't change the gaps between the month days
+        for start in [
+            "2023-02-28T00:00:00Z",
+            "2024-02-29T00:00:00Z",
+            "2023-04-30T00:00:00Z",
+            "2023-07-31T00:00:00Z",
+        ] {
+            let frequency = monthly(vec![1, 31], vec![]);
+            let valid = Recurrence::new(
+                frequency.clone(),
+                date(start),
+                None,
+                Some(Duration::days(1)),
+            );
+            assert!(valid.is_ok(), "{start}");
+            let invalid = Recurrence::new(frequency, date(start), None, Some(Duration::days(2)));
+            assert!(invalid.is_err(), "{start}");
+        }
+    }
+
+    #[test]
+    fn nth_weekdays_are_validated_in_the_month_of_the_start_date() {
+        let frequency = monthly(
+            vec![],
+            vec![
+                NthWeekday::new(Weekday::Mon, 1),
+                NthWeekday::new(Weekday::Mon, 4),
+            ],
+        );
+        // January 2023 has 5 Mondays, so the 1st Monday of February is 14 days after the 4th
+        let january = Recurrence::new(
+            frequency.clone(),
+            date("2023-01-02T00:00:00Z"),
+            None,
+            Some(Duration::days(10)),
+        );
+        assert!(january.is_ok());
+        // February 2023 has 4 Mondays, so the 1st Monday of March is 7 days after the 4th
+        let february = Recurrence::new(
+            frequency.clone(),
+            date("2023-02-06T00:00:00Z"),
+            None,
+            Some(Duration::days(10)),
+        );
+        assert!(february.is_err());
+        assert!(january
+            .unwrap()
+            .with_start(date("2023-02-06T00:00:00Z"))
+            .is_err());
+    }
+
+    #[test]
+    fn gaps_end_where_the_next_event_starts() {
+        let weekly = Frequency::Weekly {
+            interval: NonZeroU32::new(1).unwrap(),
+            by_day: vec![Weekday::Mon, Weekday::Wed],
+            by_time: vec![],
+            by_day_time: vec![],
+        };
+        let start = date("2023-01-02T09:00:00Z");
+        assert!(Recurrence::new(weekly.clone(), start, None, Some(Duration::days(2))).is_ok());
+        assert!(Recurrence::new(
+            weekly,
+            start,
+            None,
+            Some(Duration::days(2) + Duration::seconds(1))
+        )
+        .is_err());
+
+        let daily = Frequency::from_str("FREQ=DAILY;INTERVAL=1;BYTIME=09:00,17:30:30").unwrap();
+        let gap = Duration::hours(8) + Duration::minutes(30) + Duration::seconds(30);
+        assert!(Recurrence::new(daily.clone(), start, None, Some(gap)).is_ok());
+        assert!(Recurrence::new(daily, start, None, Some(gap + Duration::seconds(1))).is_err());
+    }
+}
+
+#[cfg(test)]
+mod overlapping_occurrences {
+    use crate::frequencies::{Frequency, OverflowPolicy};
+    use crate::recurrences::{Recurrence, ValidationOptions};
+    use crate::test_utils::date;
+    use chrono::{Duration, Weekday};
+    use std::num::NonZeroU32;
+    use std::str::FromStr;
+
+    fn allow_overlaps() -> ValidationOptions {
+        ValidationOptions::default().allow_overlapping_occurrences()
+    }
+
+    fn weekly() -> Frequency {
+        Frequency::Weekly {
+            interval: NonZeroU32::new(1).unwrap(),
+            by_day: vec![Weekday::Mon, Weekday::Wed],
+            by_time: vec![],
+            by_day_time: vec![],
+        }
+    }
+
+    #[test]
+    fn overlapping_occurrences_are_rejected_by_default() {
+        let start = date("2023-01-02T09:00:00Z");
+        let duration = Some(Duration::days(3));
+        assert!(Recurrence::new(weekly(), start, None, duration).is_err());
+        let options = ValidationOptions::default();
+        assert!(!options.allow_overlaps);
+        assert!(Recurrence::new_with(weekly(), start, None, duration, options).is_err());
+    }
+
+    #[test]
+    fn overlapping_occurrences_can_be_allowed() {
+        let start = date("2023-01-02T09:00:00Z");
+        let recurrence = Recurrence::new_with(
+            weekly(),
+            start,
+            None,
+            Some(Duration::days(3)),
+            allow_overlaps(),
+        )
+        .unwrap();
+        let events: Vec<_> = recurrence.iter().take(3).collect();
+        assert_eq!(
+            events,
+            vec![
+                date("2023-01-02T09:00:00Z"),
+                date("2023-01-04T09:00:00Z"),
+                date("2023-01-09T09:00:00Z"),
+            ]
+        );
+
+        // Longer than the interval
+        let daily = Frequency::from_str("FREQ=DAILY;INTERVAL=1").unwrap();
+        let duration = Some(Duration::days(2));
+        assert!(Recurrence::new(daily.clone(), start, None, duration).is_err());
+        assert!(Recurrence::new_with(daily, start, None, duration, allow_overlaps()).is_ok());
+    }
+
+    #[test]
+    fn the_frequency_and_the_period_are_still_validated() {
+        let start = date("2023-01-02T09:00:00Z");
+        let duration = Some(Duration::days(3));
+        let end = Some(date("2023-01-01T09:00:00Z"));
+        assert!(Recurrence::new_with(weekly(), start, end, duration, allow_overlaps()).is_err());
+
+        let monthly = Frequency::Monthly {
+            interval: NonZeroU32::new(1).unwrap(),
+            by_month_day: vec![32],
+            by_day: vec![],
+            nth_weekdays: vec![],
+            by_time: vec![],
+            overflow: OverflowPolicy::Skip,
+        };
+        assert!(Recurrence::new_with(monthly, start, None, duration, allow_overlaps()).is_err());
+    }
+
+    #[test]
+    fn changes_keep_the_validation_options() {
+        let start = date("2023-01-02T09:00:00Z");
+        let recurrence = Recurrence::new_with(
+            weekly(),
+            start,
+            None,
+            Some(Duration::days(3)),
+            allow_overlaps(),
+        )
+        .unwrap();
+        let recurrence = recurrence
+            .with_start(date("2023-01-03T09:00:00Z"))
+            .unwrap()
+            .with_frequency(Frequency::from_str("FREQ=DAILY;INTERVAL=1").unwrap())
+            .unwrap();
+        assert!(recurrence.validation.allow_overlaps);
+
+        let strict = Recurrence::new(weekly(), start, None, Some(Duration::days(2))).unwrap();
+        assert!(strict
+            .with_frequency(Frequency::from_str("FREQ=DAILY;INTERVAL=1").unwrap())
+            .is_err());
+    }
 }
@@ -3,6 +3,7 @@ mod tests {
     use crate::frequencies::Frequency;
     use crate::recurrences::{Recurrence, RecurrenceInvalid};
     use chrono::{DateTime, Duration, Utc};
+    use std::num::NonZeroU32;
     use std::str::FromStr;
 
     fn every_second_recurrence(
@@ -12,7 +13,11 @@ mod tests {
     ) -> Result<Recurrence, RecurrenceInvalid> {
         let duration = duration.unwrap_or_else(|| Duration::seconds(1));
         Recurrence::new(
-            Frequency::Secondly { interval: 1 },
+            Frequency::Secondly {
+                interval: NonZeroU32::new(1).unwrap(),
+                by_day: vec![],
+                by_time: vec![],
+            },
             start,
             end,
             Some(duration),
@@ -48,6 +53,35 @@ mod tests {
         );
         assert!(recurrence.is_err());
     }
+
+    #[test]
+    fn end_date() {
+        let end = DateTime::<Utc>::from_str("2023-01-01T00:00:02Z").unwrap();
+        let start = DateTime::<Utc>::from_str("2023-01-01T00:00:00Z").unwrap();
+        let recurrence = every_second_recurrence(start, Some(end), None).unwrap();
+        assert_eq!(recurrence.end(), Some(end));
+        assert!(!recurrence.is_infinite());
+
+        let recurrence = every_second_recurrence(start, None, None).unwrap();
+        assert_eq!(recurrence.end(), None);
+        assert!(recurrence.is_infinite());
+    }
+
+    #[test]
+    fn accessors() {
+        let start = DateTime::<Utc>::from_str("2023-01-01T00:00:00Z").unwrap();
+        let end = DateTime::<Utc>::from_str("2023-01-01T00:00:10Z").unwrap();
+        let recurrence = every_second_recurrence(start, Some(end), None).unwrap();
+        assert_eq!(recurrence.start(), start);
+        assert_eq!(recurrence.duration(), Duration::seconds(1));
+        assert_eq!(recurrence.frequency().interval().get(), 1);
+
+        let rebuilt = {
+            let (frequency, start, end, duration) = recurrence.clone().into_parts();
+            Recurrence::new(frequency, start, end, Some(duration)).unwrap()
+        };
+        assert_eq!(rebuilt, recurrence);
+    }
 }
 
 #[cfg(test)]
@@ -55,16 +89,21 @@ mod secondly_recurrences {
     use crate::frequencies::Frequency;
     use crate::recurrences::Recurrence;
     use chrono::{DateTime, Duration, Utc};
+    use std::num::NonZeroU32;
     use std::str::FromStr;
 
     #[test]
     fn every_second() {
         let start = DateTime::<Utc>::from_str("2023-01-01T00:00:00Z").unwrap();
         let end = DateTime::<Utc>::from_str("2023-01-01T00:00:02Z").unwrap();
-        let frequency = Frequency::Secondly { interval: 1 };
+        let frequency = Frequency::Secondly {
+            interval: NonZeroU32::new(1).unwrap(),
+            by_day: vec![],
+            by_time: vec![],
+        };
         let recurrence =
             Recurrence::new(frequency, start, Some(end), Some(Duration::seconds(1))).unwrap();
-        let dates: Vec<DateTime<Utc>> = recurrence.collect();
+        let dates: Vec<DateTime<Utc>> = recurrence.into_iter().collect();
         assert_eq!(dates.len(), 3);
         assert_eq!(
             dates,
@@ -82,16 +121,22 @@ mod minutely_recurrences {
     use crate::frequencies::Frequency;
     use crate::recurrences::Recurrence;
     use chrono::{DateTime, Duration, Utc};
+    use std::num::NonZeroU32;
     use std::str::FromStr;
 
     #[test]
     fn every_minute() {
         let start = DateTime::<Utc>::from_str("2023-01-01T00:00:00Z").unwrap();
         let end = DateTime::<Utc>::from_str("2023-01-01T00:02:00Z").unwrap();
-        let frequency = Frequency::Minutely { interval: 1 };
+        let frequency = Frequency::Minutely {
+            interval: NonZeroU32::new(1).unwrap(),
+            by_day: vec![],
+            by_time: vec![],
+            by_hour_range: None,
+        };
         let recurrence =
             Recurrence::new(frequency, start, Some(end), Some(Duration::minutes(1))).unwrap();
-        let dates: Vec<DateTime<Utc>> = recurrence.collect();
+        let dates: Vec<DateTime<Utc>> = recurrence.into_iter().collect();
         assert_eq!(dates.len(), 3);
         assert_eq!(
             dates,
@@ -109,16 +154,22 @@ mod hourly_recurrences {
     use crate::frequencies::Frequency;
     use crate::recurrences::Recurrence;
     use chrono::{DateTime, Duration, Utc};
+    use std::num::NonZeroU32;
     use std::str::FromStr;
 
     #[test]
     fn every_hour() {
         let start = DateTime::<Utc>::from_str("2023-01-01T00:00:00Z").unwrap();
         let end = DateTime::<Utc>::from_str("2023-01-01T02:00:00Z").unwrap();
-        let frequency = Frequency::Hourly { interval: 1 };
+        let frequency = Frequency::Hourly {
+            interval: NonZeroU32::new(1).unwrap(),
+            by_day: vec![],
+            by_time: vec![],
+            by_hour_range: None,
+        };
         let recurrence =
             Recurrence::new(frequency, start, Some(end), Some(Duration::hours(1))).unwrap();
-        let dates: Vec<DateTime<Utc>> = recurrence.collect();
+        let dates: Vec<DateTime<Utc>> = recurrence.into_iter().collect();
         assert_eq!(dates.len(), 3);
         assert_eq!(
             dates,
@@ -136,6 +187,7 @@ mod daily_recurrences {
     use crate::frequencies::{Frequency, Time};
     use crate::recurrences::Recurrence;
     use chrono::{DateTime, Duration, Utc};
+    use std::num::NonZeroU32;
     use std::str::FromStr;
 
     #[test]
@@ -143,12 +195,13 @@ mod daily_recurrences {
         let start = DateTime::<Utc>::from_str("2023-01-01T00:00:00Z").unwrap();
         let end = DateTime::<Utc>::from_str("2023-01-03T00:00:00Z").unwrap();
         let frequency = Frequency::Daily {
-            interval: 1,
+            interval: NonZeroU32::new(1).unwrap(),
+            by_day: vec![],
             by_time: vec![],
         };
         let recurrence =
             Recurrence::new(frequency, start, Some(end), Some(Duration::days(1))).unwrap();
-        let dates: Vec<DateTime<Utc>> = recurrence.collect();
+        let dates: Vec<DateTime<Utc>> = recurrence.into_iter().collect();
         assert_eq!(dates.len(), 3);
         assert_eq!(
             dates,
@@ -165,7 +218,8 @@ mod daily_recurrences {
         let start = DateTime::<Utc>::from_str("2023-01-01T00:00:00Z").unwrap();
         let end = DateTime::<Utc>::from_str("2023-01-03T00:00:00Z").unwrap();
         let frequency = Frequency::Daily {
-            interval: 1,
+            interval: NonZeroU32::new(1).unwrap(),
+            by_day: vec![],
             by_time: vec![
                 Time::from_str("09:00:00").unwrap(),
                 Time::from_str("18:00:00").unwrap(),
@@ -173,7 +227,7 @@ mod daily_recurrences {
         };
         let recurrence =
             Recurrence::new(frequency, start, Some(end), Some(Duration::hours(1))).unwrap();
-        let dates: Vec<DateTime<Utc>> = recurrence.collect();
+        let dates: Vec<DateTime<Utc>> = recurrence.into_iter().collect();
         assert_eq!(dates.len(), 4);
         assert_eq!(
             dates,
@@ -192,6 +246,7 @@ mod weekly_recurrences {
     use crate::frequencies::Frequency;
     use crate::recurrences::Recurrence;
     use chrono::{DateTime, Duration, Utc, Weekday};
+    use std::num::NonZeroU32;
     use std::str::FromStr;
 
     #[test]
@@ -199,12 +254,14 @@ mod weekly_recurrences {
         let start = DateTime::<Utc>::from_str("2023-01-01T00:00:00Z").unwrap();
         let end = DateTime::<Utc>::from_str("2023-01-15T00:00:00Z").unwrap();
         let frequency = Frequency::Weekly {
-            interval: 1,
+            interval: NonZeroU32::new(1).unwrap(),
             by_day: vec![],
+            by_time: vec![],
+            by_day_time: vec![],
         };
         let recurrence =
             Recurrence::new(frequency, start, Some(end), Some(Duration::weeks(1))).unwrap();
-        let dates: Vec<DateTime<Utc>> = recurrence.collect();
+        let dates: Vec<DateTime<Utc>> = recurrence.into_iter().collect();
         assert_eq!(dates.len(), 3);
         assert_eq!(
             dates,
@@ -221,12 +278,14 @@ mod weekly_recurrences {
         let start = DateTime::<Utc>::from_str("2023-01-01T00:00:00Z").unwrap();
         let end = DateTime::<Utc>::from_str("2023-01-15T00:00:00Z").unwrap();
         let frequency = Frequency::Weekly {
-            interval: 1,
+            interval: NonZeroU32::new(1).unwrap(),
             by_day: vec![Weekday::Mon, Weekday::Wed, Weekday::Fri],
+            by_time: vec![],
+            by_day_time: vec![],
         };
         let recurrence =
             Recurrence::new(frequency, start, Some(end), Some(Duration::hours(1))).unwrap();
-        let dates: Vec<DateTime<Utc>> = recurrence.collect();
+        let dates: Vec<DateTime<Utc>> = recurrence.into_iter().collect();
         assert_eq!(dates.len(), 6);
         assert_eq!(
             dates,
@@ -240,13 +299,75 @@ mod weekly_recurrences {
             ]
         );
     }
+
+    #[test]
+    fn every_other_monday_across_month_and_year_boundaries() {
+        let recurrence = Recurrence::from_str(
+            "FREQ=WEEKLY;INTERVAL=2;BYDAY=MO;DTSTART=2023-10-30T09:00:00Z;DTEND=2025-03-31T09:00:00Z",
+        )
+        .unwrap();
+        let dates: Vec<DateTime<Utc>> = recurrence.iter().collect();
+        assert_eq!(dates.len(), 38);
+        assert_eq!(dates[0], recurrence.start());
+        assert!(dates
+            .windows(2)
+            .all(|pair| pair[1] - pair[0] == Duration::weeks(2)));
+        assert!(dates.iter().all(|date| recurrence.contains(date)));
+    }
+
+    #[test]
+    fn weeks_are_counted_from_the_week_of_the_start_date() {
+        // Sunday is the last day of the weeks, so the next week of events starts 8 days later
+        let recurrence = Recurrence::from_str(
+            "FREQ=WEEKLY;INTERVAL=2;BYDAY=MO,SU;DTSTART=2023-01-01T09:00:00Z;DTEND=2023-02-28T00:00:00Z",
+        )
+        .unwrap();
+        let dates: Vec<DateTime<Utc>> = recurrence.iter().collect();
+        let expected: Vec<DateTime<Utc>> = [
+            "2023-01-01T09:00:00Z",
+            "2023-01-09T09:00:00Z",
+            "2023-01-15T09:00:00Z",
+            "2023-01-23T09:00:00Z",
+            "2023-01-29T09:00:00Z",
+            "2023-02-06T09:00:00Z",
+            "2023-02-12T09:00:00Z",
+            "2023-02-20T09:00:00Z",
+            "2023-02-26T09:00:00Z",
+        ]
+        .iter()
+        .map(|date| DateTime::<Utc>::from_str(date).unwrap())
+        .collect();
+        assert_eq!(dates, expected);
+        assert!(!recurrence.contains(&DateTime::<Utc>::from_str("2023-01-02T09:00:00Z").unwrap()));
+    }
+
+    #[test]
+    fn every_third_week_on_day_times_from_a_start_without_events() {
+        let recurrence = Recurrence::from_str(
+            "FREQ=WEEKLY;INTERVAL=3;X-BYDAYTIME=MO09:00,FR17:00;DTSTART=2023-01-04T00:00:00Z",
+        )
+        .unwrap();
+        let dates: Vec<DateTime<Utc>> = recurrence.iter().take(4).collect();
+        let expected: Vec<DateTime<Utc>> = [
+            "2023-01-06T17:00:00Z",
+            "2023-01-23T09:00:00Z",
+            "2023-01-27T17:00:00Z",
+            "2023-02-13T09:00:00Z",
+        ]
+        .iter()
+        .map(|date| DateTime::<Utc>::from_str(date).unwrap())
+        .collect();
+        assert_eq!(dates, expected);
+        assert!(dates.iter().all(|date| recurrence.contains(date)));
+    }
 }
 
 #[cfg(test)]
 mod monthly_recurrences {
-    use crate::frequencies::{Frequency, NthWeekday};
+    use crate::frequencies::{Frequency, NthWeekday, OverflowPolicy};
     use crate::recurrences::Recurrence;
     use chrono::{DateTime, Duration, Utc, Weekday};
+    use std::num::NonZeroU32;
     use std::str::FromStr;
 
     #[test]
@@ -254,13 +375,16 @@ mod monthly_recurrences {
         let start = DateTime::<Utc>::from_str("2023-01-01T00:00:00Z").unwrap();
         let end = DateTime::<Utc>::from_str("2023-03-01T00:00:00Z").unwrap();
         let frequency = Frequency::Monthly {
-            interval: 1,
+            interval: NonZeroU32::new(1).unwrap(),
             by_month_day: vec![],
+            by_day: vec![],
             nth_weekdays: vec![],
+            by_time: vec![],
+            overflow: OverflowPolicy::Skip,
         };
         let recurrence =
             Recurrence::new(frequency, start, Some(end), Some(Duration::weeks(1))).unwrap();
-        let dates: Vec<DateTime<Utc>> = recurrence.collect();
+        let dates: Vec<DateTime<Utc>> = recurrence.into_iter().collect();
         assert_eq!(dates.len(), 3);
         assert_eq!(
             dates,
@@ -277,13 +401,16 @@ mod monthly_recurrences {
         let start = DateTime::<Utc>::from_str("2023-01-01T00:00:00Z").unwrap();
         let end = DateTime::<Utc>::from_str("2023-02-20T00:00:00Z").unwrap();
         let frequency = Frequency::Monthly {
-            interval: 1,
+            interval: NonZeroU32::new(1).unwrap(),
             by_month_day: vec![1, 15],
+            by_day: vec![],
             nth_weekdays: vec![],
+            by_time: vec![],
+            overflow: OverflowPolicy::Skip,
         };
         let recurrence =
             Recurrence::new(frequency, start, Some(end), Some(Duration::weeks(1))).unwrap();
-        let dates: Vec<DateTime<Utc>> = recurrence.collect();
+        let dates: Vec<DateTime<Utc>> = recurrence.into_iter().collect();
         assert_eq!(dates.len(), 4);
         assert_eq!(
             dates,
@@ -296,21 +423,59 @@ mod monthly_recurrences {
         );
     }
 
+    #[test]
+    fn monthly_recurrence_skips_months_without_the_start_day() {
+        let recurrence = Recurrence::from_str(
+            "FREQ=MONTHLY;INTERVAL=1;DTSTART=2023-01-31T09:00:00Z;DTEND=2023-08-01T00:00:00Z",
+        )
+        .unwrap();
+        let dates: Vec<DateTime<Utc>> = recurrence.into_iter().collect();
+        assert_eq!(
+            dates,
+            vec![
+                DateTime::<Utc>::from_str("2023-01-31T09:00:00Z").unwrap(),
+                DateTime::<Utc>::from_str("2023-03-31T09:00:00Z").unwrap(),
+                DateTime::<Utc>::from_str("2023-05-31T09:00:00Z").unwrap(),
+                DateTime::<Utc>::from_str("2023-07-31T09:00:00Z").unwrap(),
+            ]
+        );
+    }
+
+    #[test]
+    fn monthly_recurrence_continues_across_years() {
+        let recurrence = Recurrence::from_str(
+            "FREQ=MONTHLY;INTERVAL=1;BYMONTHDAY=31;DTSTART=2023-10-01T00:00:00Z;DTEND=2024-02-01T00:00:00Z",
+        )
+        .unwrap();
+        let dates: Vec<DateTime<Utc>> = recurrence.into_iter().collect();
+        assert_eq!(
+            dates,
+            vec![
+                DateTime::<Utc>::from_str("2023-10-31T00:00:00Z").unwrap(),
+                DateTime::<Utc>::from_str("2023-12-31T00:00:00Z").unwrap(),
+                DateTime::<Utc>::from_str("2024-01-31T00:00:00Z").unwrap(),
+            ]
+        );
+    }
+
     #[test]
     fn monthly_recurrence_by_week_number() {
         let start = DateTime::<Utc>::from_str("2023-01-01T00:00:00Z").unwrap();
         let end = DateTime::<Utc>::from_str("2023-02-20T00:00:00Z").unwrap();
         let frequency = Frequency::Monthly {
-            interval: 1,
+            interval: NonZeroU32::new(1).unwrap(),
             by_month_day: vec![],
+            by_day: vec![],
             nth_weekdays: vec![
                 NthWeekday::new(Weekday::Wed, 1),
                 NthWeekday::new(Weekday::Fri, 1),
             ],
+            by_time: vec![],
+            overflow: OverflowPolicy::Skip,
         };
         let recurrence =
             Recurrence::new(frequency, start, Some(end), Some(Duration::hours(1))).unwrap();
-        let dates: Vec<DateTime<Utc>> = recurrence.collect();
+        let dates: Vec<DateTime<Utc>> = recurrence.into_iter().collect();
         assert_eq!(dates.len(), 4);
         assert_eq!(
             dates,
@@ -322,13 +487,41 @@ mod monthly_recurrences {
             ]
         );
     }
+
+    #[test]
+    fn monthly_without_filters_applies_the_skip_parameter() {
+        let events = |skip: &str| -> Vec<String> {
+            let value = format!(
+                "FREQ=MONTHLY;INTERVAL=1;{skip}DTSTART=2023-01-31T09:00:00Z;DTEND=2023-06-01T00:00:00Z"
+            );
+            let recurrence = Recurrence::from_str(&value).unwrap();
+            let events: Vec<DateTime<Utc>> = recurrence.clone().into_iter().collect();
+            assert!(events.iter().all(|event| recurrence.contains(event)));
+            events
+                .iter()
+                .map(|event| event.format("%m-%d").to_string())
+                .collect()
+        };
+        assert_eq!(events(""), ["01-31", "03-31", "05-31"]);
+        assert_eq!(events("SKIP=OMIT;"), ["01-31", "03-31", "05-31"]);
+        assert_eq!(
+            events("RSCALE=GREGORIAN;SKIP=BACKWARD;"),
+            ["01-31", "02-28", "03-31", "04-30", "05-31"]
+        );
+        // The example of RFC 7529, where the events moved forward don't shift the following ones
+        assert_eq!(
+            events("RSCALE=GREGORIAN;SKIP=FORWARD;"),
+            ["01-31", "03-01", "03-31", "05-01", "05-31"]
+        );
+    }
 }
 
 #[cfg(test)]
 mod yearly_recurrences {
-    use crate::frequencies::{Frequency, MonthlyDate};
+    use crate::frequencies::{Frequency, MonthlyDate, OverflowPolicy};
     use crate::recurrences::Recurrence;
     use chrono::{DateTime, Duration, Month, Utc};
+    use std::num::NonZeroU32;
     use std::str::FromStr;
 
     #[test]
@@ -336,12 +529,17 @@ mod yearly_recurrences {
         let start = DateTime::<Utc>::from_str("2023-01-01T00:00:00Z").unwrap();
         let end = DateTime::<Utc>::from_str("2025-01-01T00:00:00Z").unwrap();
         let frequency = Frequency::Yearly {
-            interval: 1,
-            by_monthly_date: None,
+            interval: NonZeroU32::new(1).unwrap(),
+            by_month: vec![],
+            by_monthly_date: vec![],
+            nth_weekdays: vec![],
+            by_week_number: vec![],
+            by_time: vec![],
+            overflow: OverflowPolicy::Skip,
         };
         let recurrence =
             Recurrence::new(frequency, start, Some(end), Some(Duration::weeks(1))).unwrap();
-        let dates: Vec<DateTime<Utc>> = recurrence.collect();
+        let dates: Vec<DateTime<Utc>> = recurrence.into_iter().collect();
         assert_eq!(dates.len(), 3);
         assert_eq!(
             dates,
@@ -352,4 +550,1842 @@ mod yearly_recurrences {
             ]
         );
     }
+
+    #[test]
+    fn test_leap_day_skips_common_years() {
+        let recurrence = Recurrence::from_str(
+            "FREQ=YEARLY;INTERVAL=1;DTSTART=2096-02-29T00:00:00Z;DTEND=2110-01-01T00:00:00Z",
+        )
+        .unwrap();
+        let dates: Vec<DateTime<Utc>> = recurrence.into_iter().collect();
+        assert_eq!(
+            dates,
+            vec![
+                DateTime::<Utc>::from_str("2096-02-29T00:00:00Z").unwrap(),
+                DateTime::<Utc>::from_str("2104-02-29T00:00:00Z").unwrap(),
+                DateTime::<Utc>::from_str("2108-02-29T00:00:00Z").unwrap(),
+            ]
+        );
+
+        let recurrence = Recurrence::from_str(
+            "FREQ=YEARLY;INTERVAL=1;BYMONTH=2;BYMONTHDAY=29;DTSTART=2021-01-01T00:00:00Z;DTEND=2029-01-01T00:00:00Z",
+        )
+        .unwrap();
+        let dates: Vec<DateTime<Utc>> = recurrence.into_iter().collect();
+        assert_eq!(
+            dates,
+            vec![
+                DateTime::<Utc>::from_str("2024-02-29T00:00:00Z").unwrap(),
+                DateTime::<Utc>::from_str("2028-02-29T00:00:00Z").unwrap(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_twice_a_year() {
+        let start = DateTime::<Utc>::from_str("2023-01-01T00:00:00Z").unwrap();
+        let end = DateTime::<Utc>::from_str("2024-12-31T00:00:00Z").unwrap();
+        let frequency = Frequency::Yearly {
+            interval: NonZeroU32::new(1).unwrap(),
+            by_month: vec![],
+            by_monthly_date: vec![
+                MonthlyDate {
+                    month: Month::January,
+                    day: 15,
+                },
+                MonthlyDate {
+                    month: Month::June,
+                    day: 1,
+                },
+            ],
+            nth_weekdays: vec![],
+            by_week_number: vec![],
+            by_time: vec![],
+            overflow: OverflowPolicy::Skip,
+        };
+        let recurrence =
+            Recurrence::new(frequency, start, Some(end), Some(Duration::weeks(1))).unwrap();
+        let dates: Vec<DateTime<Utc>> = recurrence.into_iter().collect();
+        assert_eq!(
+            dates,
+            vec![
+                DateTime::<Utc>::from_str("2023-01-15T00:00:00Z").unwrap(),
+                DateTime::<Utc>::from_str("2023-06-01T00:00:00Z").unwrap(),
+                DateTime::<Utc>::from_str("2024-01-15T00:00:00Z").unwrap(),
+                DateTime::<Utc>::from_str("2024-06-01T00:00:00Z").unwrap(),
+            ]
+        );
+    }
+
+    fn events(value: &str) -> Vec<DateTime<Utc>> {
+        Recurrence::from_str(value).unwrap().into_iter().collect()
+    }
+
+    fn dates(values: &[&str]) -> Vec<DateTime<Utc>> {
+        values
+            .iter()
+            .map(|value| DateTime::<Utc>::from_str(value).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_leap_day_every_4_years() {
+        assert_eq!(
+            events("FREQ=YEARLY;INTERVAL=4;BYMONTH=2;BYMONTHDAY=29;DTSTART=2020-01-01T00:00:00Z;DTEND=2032-12-31T00:00:00Z"),
+            dates(&[
+                "2020-02-29T00:00:00Z",
+                "2024-02-29T00:00:00Z",
+                "2028-02-29T00:00:00Z",
+                "2032-02-29T00:00:00Z",
+            ])
+        );
+        // Starting after the leap day, the next event is 4 years later and not in 2021
+        assert_eq!(
+            events("FREQ=YEARLY;INTERVAL=4;BYMONTH=2;BYMONTHDAY=29;DTSTART=2020-03-01T00:00:00Z;DTEND=2028-12-31T00:00:00Z"),
+            dates(&["2024-02-29T00:00:00Z", "2028-02-29T00:00:00Z"])
+        );
+    }
+
+    #[test]
+    fn test_leap_day_interval_out_of_phase_with_leap_years() {
+        let value = "FREQ=YEARLY;INTERVAL=4;BYMONTH=2;BYMONTHDAY=29;DTSTART=2021-01-01T00:00:00Z;DTEND=2033-12-31T00:00:00Z";
+        assert!(events(value).is_empty());
+        assert_eq!(
+            events(&format!("{value};X-OVERFLOW=CLAMP")),
+            dates(&[
+                "2021-02-28T00:00:00Z",
+                "2025-02-28T00:00:00Z",
+                "2029-02-28T00:00:00Z",
+                "2033-02-28T00:00:00Z",
+            ])
+        );
+        assert_eq!(
+            events(&format!("{value};X-OVERFLOW=NEXT")),
+            dates(&[
+                "2021-03-01T00:00:00Z",
+                "2025-03-01T00:00:00Z",
+                "2029-03-01T00:00:00Z",
+                "2033-03-01T00:00:00Z",
+            ])
+        );
+    }
+
+    #[test]
+    fn test_yearly_without_filters_from_a_leap_day() {
+        let value =
+            "FREQ=YEARLY;INTERVAL=1;DTSTART=2024-02-29T00:00:00Z;DTEND=2028-12-31T00:00:00Z";
+        assert_eq!(
+            events(value),
+            dates(&["2024-02-29T00:00:00Z", "2028-02-29T00:00:00Z"])
+        );
+        assert_eq!(
+            events(&format!("{value};SKIP=BACKWARD")),
+            dates(&[
+                "2024-02-29T00:00:00Z",
+                "2025-02-28T00:00:00Z",
+                "2026-02-28T00:00:00Z",
+                "2027-02-28T00:00:00Z",
+                "2028-02-29T00:00:00Z",
+            ])
+        );
+        let recurrence = Recurrence::from_str(&format!("{value};SKIP=FORWARD")).unwrap();
+        assert_eq!(
+            recurrence.clone().into_iter().collect::<Vec<_>>(),
+            dates(&[
+                "2024-02-29T00:00:00Z",
+                "2025-03-01T00:00:00Z",
+                "2026-03-01T00:00:00Z",
+                "2027-03-01T00:00:00Z",
+                "2028-02-29T00:00:00Z",
+            ])
+        );
+        assert!(recurrence.contains(&dates(&["2025-03-01T00:00:00Z"])[0]));
+        assert!(!recurrence.contains(&dates(&["2028-03-01T00:00:00Z"])[0]));
+    }
+
+    #[test]
+    fn test_leap_day_every_2_years_with_clamp() {
+        assert_eq!(
+            events("FREQ=YEARLY;INTERVAL=2;BYMONTH=2;BYMONTHDAY=29;X-OVERFLOW=CLAMP;DTSTART=2023-01-01T00:00:00Z;DTEND=2029-12-31T00:00:00Z"),
+            dates(&[
+                "2023-02-28T00:00:00Z",
+                "2025-02-28T00:00:00Z",
+                "2027-02-28T00:00:00Z",
+                "2029-02-28T00:00:00Z",
+            ])
+        );
+    }
+
+    #[test]
+    fn test_week_numbers_every_2_iso_years() {
+        // The first ISO week of 2025 starts on 2024-12-30, the next event is in the ISO year 2027
+        assert_eq!(
+            events("FREQ=YEARLY;INTERVAL=2;BYWEEKNO=1;BYDAY=MO;DTSTART=2024-12-30T00:00:00Z;DTEND=2029-12-31T00:00:00Z"),
+            dates(&[
+                "2024-12-30T00:00:00Z",
+                "2027-01-04T00:00:00Z",
+                "2029-01-01T00:00:00Z",
+            ])
+        );
+    }
+}
+
+#[cfg(test)]
+mod recurrence_equality {
+    use crate::recurrences::Recurrence;
+    use crate::Frequency;
+    use chrono::{DateTime, Utc, Weekday};
+    use std::collections::HashSet;
+    use std::num::NonZeroU32;
+    use std::str::FromStr;
+
+    #[test]
+    fn equal_rules_ignore_iteration_state() {
+        let value = "FREQ=DAILY;INTERVAL=1;DTSTART=2023-01-01T00:00:00Z";
+        let recurrence = Recurrence::from_str(value).unwrap();
+        let mut events = recurrence.iter();
+        events.next();
+        events.next();
+        assert_eq!(&recurrence, events.recurrence());
+
+        let other = Recurrence::from_str("FREQ=DAILY;INTERVAL=2;DTSTART=2023-01-01T00:00:00Z");
+        assert_ne!(recurrence, other.unwrap());
+    }
+
+    #[test]
+    fn recurrences_in_hash_set() {
+        let mut rules = HashSet::new();
+        let value = "FREQ=WEEKLY;INTERVAL=1;DTSTART=2023-01-01T00:00:00Z;BYDAY=MO";
+        let recurrence = Recurrence::from_str(value).unwrap();
+        rules.insert(recurrence.clone());
+        assert!(!rules.insert(recurrence));
+        assert!(rules.insert(
+            Recurrence::from_str("FREQ=WEEKLY;INTERVAL=1;DTSTART=2023-01-01T00:00:00Z;BYDAY=TU")
+                .unwrap()
+        ));
+        assert_eq!(rules.len(), 2);
+    }
+
+    #[test]
+    fn equal_rules_written_in_a_different_order() {
+        let recurrence = Recurrence::from_str(
+            "FREQ=WEEKLY;INTERVAL=1;BYDAY=MO,TU;DTSTART=2023-01-02T09:00:00Z;X-A=1;X-B=2",
+        )
+        .unwrap();
+        let other = Recurrence::from_str(
+            "X-B=2;BYDAY=TU,MO;DTSTART=2023-01-02T09:00:00Z;X-A=1;INTERVAL=1;FREQ=WEEKLY",
+        )
+        .unwrap();
+        assert_eq!(
+            recurrence.canonical_string(),
+            "FREQ=WEEKLY;INTERVAL=1;BYDAY=MO,TU;DTSTART=2023-01-02T09:00:00Z;X-A=1;X-B=2"
+        );
+        assert_eq!(recurrence.canonical_string(), other.canonical_string());
+        assert_eq!(recurrence, other);
+
+        let mut rules = HashSet::new();
+        rules.insert(recurrence);
+        assert!(!rules.insert(other));
+    }
+
+    #[test]
+    fn equality_normalizes_the_fields_outside_the_rule() {
+        let date = |value: &str| DateTime::<Utc>::from_str(value).unwrap();
+        let value = "FREQ=DAILY;INTERVAL=1;DTSTART=2023-01-01T00:00:00Z";
+        let exdates = vec![date("2023-01-03T00:00:00Z"), date("2023-01-02T00:00:00Z")];
+        let recurrence = Recurrence::from_str(value)
+            .unwrap()
+            .with_exdates(exdates.clone());
+        let mut reversed = exdates.clone();
+        reversed.reverse();
+        let other = Recurrence::from_str(value).unwrap().with_exdates(reversed);
+        assert_eq!(recurrence, other);
+
+        let mut unsorted = recurrence.clone();
+        unsorted.frequency = Frequency::Weekly {
+            interval: NonZeroU32::new(1).unwrap(),
+            by_day: vec![Weekday::Tue, Weekday::Mon],
+            by_time: vec![],
+            by_day_time: vec![],
+        };
+        let mut sorted = recurrence.clone();
+        sorted.frequency = unsorted.frequency.clone().normalized();
+        assert_eq!(unsorted, sorted);
+
+        assert_ne!(recurrence, Recurrence::from_str(value).unwrap());
+        assert_ne!(recurrence, recurrence.clone().with_max_occurrences(10));
+    }
+
+    #[test]
+    fn recurrence_iterated_many_times() {
+        let value = "FREQ=DAILY;INTERVAL=1;DTSTART=2023-01-01T00:00:00Z";
+        let recurrence = Recurrence::from_str(value).unwrap();
+        let mut first = recurrence.iter();
+        let mut second = recurrence.iter();
+        assert_eq!(first.next(), second.next());
+        assert_eq!(first.next(), second.next());
+        let owned: Vec<_> = recurrence.clone().into_iter().take(3).collect();
+        let borrowed: Vec<_> = (&recurrence).into_iter().take(3).collect();
+        assert_eq!(owned, borrowed);
+    }
+}
+
+#[cfg(test)]
+mod occurrence_counting {
+    use crate::recurrences::Recurrence;
+    use std::str::FromStr;
+
+    fn assert_count_matches_iteration(value: &str) {
+        let recurrence = Recurrence::from_str(value).unwrap();
+        let expected = recurrence.iter().count() as u64;
+        assert_eq!(recurrence.count_occurrences(), Some(expected), "{value}");
+    }
+
+    #[test]
+    fn counts_interval_based_frequencies() {
+        assert_count_matches_iteration(
+            "FREQ=SECONDLY;INTERVAL=7;DTSTART=2023-01-01T00:00:00Z;DTEND=2023-01-01T01:00:00Z",
+        );
+        assert_count_matches_iteration(
+            "FREQ=HOURLY;INTERVAL=5;DTSTART=2023-01-01T00:00:00Z;DTEND=2023-02-01T00:00:00Z",
+        );
+        assert_count_matches_iteration(
+            "FREQ=DAILY;INTERVAL=2;DTSTART=2023-01-01T12:00:00Z;DTEND=2023-12-31T11:59:59Z",
+        );
+        assert_count_matches_iteration(
+            "FREQ=WEEKLY;INTERVAL=1;DTSTART=2023-01-01T00:00:00Z;DTEND=2023-12-31T00:00:00Z",
+        );
+    }
+
+    #[test]
+    fn counts_frequencies_with_filters() {
+        assert_count_matches_iteration(
+            "FREQ=WEEKLY;INTERVAL=1;DTSTART=2023-01-01T00:00:00Z;DTEND=2023-12-31T00:00:00Z;BYDAY=MO,FR",
+        );
+        assert_count_matches_iteration(
+            "FREQ=MONTHLY;INTERVAL=1;DTSTART=2023-01-01T00:00:00Z;DTEND=2024-01-01T00:00:00Z;BYMONTHDAY=1,15",
+        );
+        let recurrence = Recurrence::from_str(
+            "FREQ=YEARLY;INTERVAL=1;DTSTART=2023-01-01T00:00:00Z;DTEND=2033-01-01T00:00:00Z",
+        )
+        .unwrap();
+        assert_eq!(recurrence.count_occurrences(), Some(11));
+    }
+
+    #[test]
+    fn no_count_without_end_date() {
+        let recurrence =
+            Recurrence::from_str("FREQ=SECONDLY;INTERVAL=1;DTSTART=2023-01-01T00:00:00Z").unwrap();
+        assert_eq!(recurrence.count_occurrences(), None);
+    }
+}
+
+#[cfg(test)]
+mod bounded_recurrences {
+    use crate::recurrences::{Recurrence, RecurrenceErrorKind};
+    use crate::test_utils::date;
+    use std::str::FromStr;
+
+    #[test]
+    fn bounded_yields_the_same_events_until_the_date() {
+        let recurrence = Recurrence::from_str(
+            "FREQ=MONTHLY;INTERVAL=1;BYMONTHDAY=1,15;DTSTART=2023-01-01T09:00:00Z",
+        )
+        .unwrap()
+        .with_exdates(vec![
+            date("2023-02-15T09:00:00Z"),
+            date("2023-08-01T09:00:00Z"),
+        ]);
+        let until = date("2023-06-10T00:00:00Z");
+        let bounded = recurrence.bounded(&until).unwrap();
+        let expected: Vec<_> = recurrence.between(&recurrence.start, &until).collect();
+        assert_eq!(bounded.iter().collect::<Vec<_>>(), expected);
+        assert_eq!(bounded.end(), Some(date("2023-06-01T09:00:00Z")));
+        assert_eq!(bounded.exdates, vec![date("2023-02-15T09:00:00Z")]);
+        assert_eq!(bounded.count_occurrences(), Some(10));
+        assert!(recurrence.is_infinite());
+        assert_eq!(recurrence.exdates.len(), 2);
+    }
+
+    #[test]
+    fn bounded_keeps_an_earlier_end_and_replaces_the_iteration_limits() {
+        let recurrence = Recurrence::from_str(
+            "FREQ=DAILY;INTERVAL=1;DTSTART=2023-01-01T09:00:00Z;DTEND=2023-01-20T12:00:00Z",
+        )
+        .unwrap();
+        let bounded = recurrence.bounded(&date("2024-01-01T00:00:00Z")).unwrap();
+        assert_eq!(bounded.end(), Some(date("2023-01-20T09:00:00Z")));
+
+        let limited = recurrence.clone().with_max_occurrences(5);
+        let bounded = limited.bounded(&date("2024-01-01T00:00:00Z")).unwrap();
+        assert_eq!(bounded.end(), Some(date("2023-01-05T09:00:00Z")));
+        assert_eq!(bounded.max_occurrences, None);
+        assert_eq!(
+            bounded.iter().collect::<Vec<_>>(),
+            limited.iter().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn with_count_yields_the_first_events() {
+        let recurrence =
+            Recurrence::from_str("FREQ=WEEKLY;INTERVAL=2;BYDAY=TU,TH;DTSTART=2023-01-03T18:00:00Z")
+                .unwrap();
+        let bounded = recurrence.with_count(7).unwrap();
+        assert_eq!(
+            bounded.iter().collect::<Vec<_>>(),
+            recurrence.iter().take(7).collect::<Vec<_>>()
+        );
+        assert_eq!(bounded.count_occurrences(), Some(7));
+        assert_eq!(Recurrence::from_str(&bounded.to_string()).unwrap(), bounded);
+
+        let shorter = bounded.with_count(100).unwrap();
+        assert_eq!(shorter, bounded);
+    }
+
+    #[test]
+    fn a_single_event_ends_after_the_start() {
+        let recurrence =
+            Recurrence::from_str("FREQ=DAILY;INTERVAL=1;DTSTART=2023-01-01T09:00:00Z").unwrap();
+        let bounded = recurrence.with_count(1).unwrap();
+        assert_eq!(bounded.end(), Some(date("2023-01-01T09:00:01Z")));
+        assert_eq!(
+            bounded.iter().collect::<Vec<_>>(),
+            vec![date("2023-01-01T09:00:00Z")]
+        );
+    }
+
+    #[test]
+    fn no_events_in_the_window() {
+        let recurrence =
+            Recurrence::from_str("FREQ=DAILY;INTERVAL=1;DTSTART=2023-01-01T09:00:00Z").unwrap();
+        let error = recurrence
+            .bounded(&date("2023-01-01T08:00:00Z"))
+            .unwrap_err();
+        assert_eq!(error.kind, RecurrenceErrorKind::NoEvents);
+        let error = recurrence.with_count(0).unwrap_err();
+        assert_eq!(error.kind, RecurrenceErrorKind::NoEvents);
+    }
+}
+
+#[cfg(test)]
+mod all_day_recurrences {
+    use crate::frequencies::{Frequency, Time};
+    use crate::recurrences::Recurrence;
+    use chrono::{DateTime, Duration, NaiveDate, Utc};
+    use std::num::NonZeroU32;
+    use std::str::FromStr;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    #[test]
+    fn yields_dates_until_inclusive_end_date() {
+        let weekly = Frequency::Weekly {
+            interval: NonZeroU32::new(1).unwrap(),
+            by_day: vec![],
+            by_time: vec![],
+            by_day_time: vec![],
+        };
+        let recurrence =
+            Recurrence::all_day(weekly, date(2023, 1, 1), Some(date(2023, 1, 15))).unwrap();
+        assert!(recurrence.all_day);
+        assert_eq!(recurrence.duration, Duration::days(1));
+        let dates: Vec<NaiveDate> = recurrence.dates().collect();
+        assert_eq!(
+            dates,
+            vec![date(2023, 1, 1), date(2023, 1, 8), date(2023, 1, 15)]
+        );
+        assert_eq!(
+            recurrence.iter().next().unwrap(),
+            DateTime::<Utc>::from_str("2023-01-01T00:00:00Z").unwrap()
+        );
+    }
+
+    #[test]
+    fn rejects_frequencies_with_times() {
+        let hourly = Frequency::Hourly {
+            interval: NonZeroU32::new(1).unwrap(),
+            by_day: vec![],
+            by_time: vec![],
+            by_hour_range: None,
+        };
+        assert!(Recurrence::all_day(hourly, date(2023, 1, 1), None).is_err());
+        let daily_with_time = Frequency::Daily {
+            interval: NonZeroU32::new(1).unwrap(),
+            by_day: vec![],
+            by_time: vec![Time::from_str("10:00").unwrap()],
+        };
+        assert!(Recurrence::all_day(daily_with_time, date(2023, 1, 1), None).is_err());
+    }
+
+    #[test]
+    fn serializes_date_only_start() {
+        let recurrence = Recurrence::from_str(
+            "FREQ=YEARLY;INTERVAL=1;DTSTART=VALUE=DATE:20200314;DTEND=VALUE=DATE:20230314",
+        )
+        .unwrap();
+        assert!(recurrence.all_day);
+        assert_eq!(
+            recurrence.to_string(),
+            "FREQ=YEARLY;INTERVAL=1;DTSTART=2020-03-14;DTEND=2023-03-14"
+        );
+        assert_eq!(
+            Recurrence::from_str(&recurrence.to_string()).unwrap(),
+            recurrence
+        );
+        assert_eq!(recurrence.dates().count(), 4);
+    }
+
+    #[test]
+    fn serializes_date_typed_ics_start() {
+        let recurrence =
+            Recurrence::from_str("FREQ=DAILY;INTERVAL=3;DTSTART=20230101;DTEND=20230110")
+                .unwrap()
+                .with_exdates(vec![
+                    DateTime::<Utc>::from_str("2023-01-04T00:00:00Z").unwrap()
+                ]);
+        let vevent = recurrence.to_ics_vevent();
+        assert_eq!(
+            vevent,
+            "BEGIN:VEVENT\r\n\
+            DTSTART;VALUE=DATE:20230101\r\n\
+            RRULE:FREQ=DAILY;INTERVAL=3;UNTIL=20230110\r\n\
+            EXDATE;VALUE=DATE:20230104\r\n\
+            END:VEVENT\r\n"
+        );
+        assert_eq!(Recurrence::from_ics_vevent(&vevent).unwrap(), recurrence);
+    }
+}
+
+#[cfg(test)]
+mod recurrence_membership {
+    use crate::recurrences::Recurrence;
+    use crate::test_utils::date;
+    use chrono::{DateTime, Duration, Utc};
+    use std::str::FromStr;
+
+    #[test]
+    fn sub_daily_intervals_are_aligned_to_start() {
+        let every_5_seconds =
+            Recurrence::from_str("FREQ=SECONDLY;INTERVAL=5;DTSTART=2023-01-01T10:00:00Z").unwrap();
+        assert!(every_5_seconds.contains(&date("2023-01-01T10:00:05Z")));
+        assert!(!every_5_seconds.contains(&date("2023-01-01T10:00:07Z")));
+
+        let every_15_minutes =
+            Recurrence::from_str("FREQ=MINUTELY;INTERVAL=15;DTSTART=2023-01-01T10:05:00Z").unwrap();
+        assert!(every_15_minutes.contains(&date("2023-01-02T10:50:00Z")));
+        assert!(!every_15_minutes.contains(&date("2023-01-02T10:45:00Z")));
+
+        let every_2_hours =
+            Recurrence::from_str("FREQ=HOURLY;INTERVAL=2;BYDAY=MO;DTSTART=2023-01-01T09:30:00Z")
+                .unwrap();
+        assert!(every_2_hours.contains(&date("2023-01-02T11:30:00Z")));
+        assert!(!every_2_hours.contains(&date("2023-01-02T10:30:00Z")));
+        assert!(!every_2_hours.contains(&date("2023-01-03T11:30:00Z")));
+        assert!(!every_2_hours.contains(&date("2023-01-02T11:30:01Z")));
+    }
+
+    #[test]
+    fn daily_interval_is_aligned_to_start() {
+        let recurrence =
+            Recurrence::from_str("FREQ=DAILY;INTERVAL=3;DTSTART=2023-01-01T10:00:00Z").unwrap();
+        assert!(recurrence.contains(&date("2023-01-01T10:00:00Z")));
+        assert!(recurrence.contains(&date("2023-01-07T10:00:00Z")));
+        assert!(!recurrence.contains(&date("2023-01-02T10:00:00Z")));
+        assert!(!recurrence.contains(&date("2023-01-07T11:00:00Z")));
+        assert!(!recurrence.contains(&date("2022-12-29T10:00:00Z")));
+    }
+
+    #[test]
+    fn weekly_interval_is_aligned_to_start() {
+        let recurrence =
+            Recurrence::from_str("FREQ=WEEKLY;INTERVAL=2;BYDAY=MO,FR;DTSTART=2023-01-04T10:00:00Z")
+                .unwrap();
+        assert!(recurrence.contains(&date("2023-01-06T10:00:00Z")));
+        assert!(recurrence.contains(&date("2023-01-16T10:00:00Z")));
+        assert!(!recurrence.contains(&date("2023-01-09T10:00:00Z")));
+        assert!(!recurrence.contains(&date("2023-01-13T10:00:00Z")));
+        for event in recurrence.iter().take(10) {
+            assert!(recurrence.contains(&event));
+        }
+    }
+
+    #[test]
+    fn weeks_start_on_monday() {
+        let recurrence =
+            Recurrence::from_str("FREQ=WEEKLY;INTERVAL=2;BYDAY=WE;DTSTART=2023-01-01T10:00:00Z")
+                .unwrap();
+        let events: Vec<DateTime<Utc>> = recurrence.iter().take(2).collect();
+        assert_eq!(
+            events,
+            vec![date("2023-01-11T10:00:00Z"), date("2023-01-25T10:00:00Z")]
+        );
+    }
+
+    #[test]
+    fn monthly_interval_is_aligned_to_start() {
+        let recurrence = Recurrence::from_str(
+            "FREQ=MONTHLY;INTERVAL=2;BYMONTHDAY=1,15;DTSTART=2023-01-01T00:00:00Z",
+        )
+        .unwrap();
+        assert!(recurrence.contains(&date("2023-03-15T00:00:00Z")));
+        assert!(!recurrence.contains(&date("2023-02-15T00:00:00Z")));
+
+        let unfiltered =
+            Recurrence::from_str("FREQ=MONTHLY;INTERVAL=1;DTSTART=2023-01-31T00:00:00Z").unwrap();
+        assert!(unfiltered.contains(&date("2023-03-31T00:00:00Z")));
+        assert!(!unfiltered.contains(&date("2023-02-28T00:00:00Z")));
+    }
+
+    #[test]
+    fn next_valid_days_stay_aligned_to_start() {
+        let recurrence = Recurrence::from_str(
+            "FREQ=MONTHLY;INTERVAL=2;BYMONTHDAY=1,30;X-OVERFLOW=NEXT;DTSTART=2022-12-01T00:00:00Z",
+        )
+        .unwrap();
+        let events: Vec<DateTime<Utc>> = recurrence.iter().take(5).collect();
+        assert_eq!(
+            events,
+            vec![
+                date("2022-12-01T00:00:00Z"),
+                date("2022-12-30T00:00:00Z"),
+                date("2023-02-01T00:00:00Z"),
+                date("2023-04-01T00:00:00Z"),
+                date("2023-04-30T00:00:00Z"),
+            ]
+        );
+        assert!(!recurrence.contains(&date("2023-03-01T00:00:00Z")));
+
+        let without_first_day = Recurrence::from_str(
+            "FREQ=MONTHLY;INTERVAL=2;BYMONTHDAY=30;X-OVERFLOW=NEXT;DTSTART=2022-12-01T00:00:00Z",
+        )
+        .unwrap();
+        assert!(without_first_day.contains(&date("2023-03-01T00:00:00Z")));
+        assert_eq!(
+            without_first_day.iter().nth(2),
+            Some(date("2023-04-30T00:00:00Z"))
+        );
+    }
+
+    #[test]
+    fn yearly_interval_is_aligned_to_start() {
+        let recurrence =
+            Recurrence::from_str("FREQ=YEARLY;INTERVAL=2;DTSTART=2020-03-14T00:00:00Z").unwrap();
+        assert!(recurrence.contains(&date("2024-03-14T00:00:00Z")));
+        assert!(!recurrence.contains(&date("2023-03-14T00:00:00Z")));
+        assert!(!recurrence.contains(&date("2024-03-15T00:00:00Z")));
+    }
+
+    #[test]
+    fn excluded_dates_and_bounds_are_not_contained() {
+        let recurrence = Recurrence::from_str(
+            "FREQ=DAILY;INTERVAL=1;DTSTART=2023-01-01T00:00:00Z;DTEND=2023-01-05T00:00:00Z",
+        )
+        .unwrap()
+        .with_exdates(vec![date("2023-01-02T00:00:00Z")]);
+        assert!(!recurrence.contains(&date("2023-01-02T00:00:00Z")));
+        assert!(!recurrence.contains(&date("2023-01-06T00:00:00Z")));
+        assert!(recurrence.contains(&date("2023-01-05T00:00:00Z")));
+    }
+
+    #[test]
+    fn start_date_is_only_an_event_when_contained() {
+        let recurrence = Recurrence::from_str("FREQ=DAILY;INTERVAL=1;DTSTART=2023-01-01T00:00:00Z")
+            .unwrap()
+            .with_exdates(vec![date("2023-01-01T00:00:00Z")]);
+        assert_eq!(recurrence.iter().next(), Some(date("2023-01-02T00:00:00Z")));
+    }
+
+    #[test]
+    fn instants_during_the_events_are_contained() {
+        let meeting = Recurrence::from_str(
+            "FREQ=WEEKLY;INTERVAL=1;BYDAY=MO,TH;DTSTART=2023-01-02T10:00:00Z;DTEND=2023-01-12T10:00:00Z;DURATION=PT1H",
+        )
+        .unwrap()
+        .with_exdates(vec![date("2023-01-05T10:00:00Z")]);
+        assert!(meeting.contains_instant(&date("2023-01-02T10:00:00Z")));
+        assert!(meeting.contains_instant(&date("2023-01-09T10:59:59Z")));
+        assert!(!meeting.contains_instant(&date("2023-01-09T11:00:00Z")));
+        assert!(!meeting.contains_instant(&date("2023-01-09T09:59:59Z")));
+        assert!(!meeting.contains_instant(&date("2023-01-05T10:30:00Z")));
+
+        let occurrence = meeting
+            .occurrence_covering(&date("2023-01-12T10:15:00Z"))
+            .unwrap();
+        assert_eq!(occurrence.index, 2);
+        assert_eq!(occurrence.start, date("2023-01-12T10:00:00Z"));
+        assert_eq!(occurrence.end, date("2023-01-12T11:00:00Z"));
+        assert!(!occurrence.is_first && occurrence.is_last);
+        assert_eq!(
+            occurrence,
+            meeting.detailed_iter().nth(occurrence.index).unwrap()
+        );
+        assert_eq!(
+            meeting.occurrence_covering(&date("2023-01-16T10:15:00Z")),
+            None
+        );
+    }
+
+    #[test]
+    fn overlapping_and_instant_events() {
+        let overlapping = Recurrence::from_str(
+            "FREQ=HOURLY;INTERVAL=1;DTSTART=2023-01-01T00:00:00Z;DURATION=PT90M",
+        )
+        .unwrap();
+        let occurrence = overlapping
+            .occurrence_covering(&date("2023-01-01T05:15:00Z"))
+            .unwrap();
+        assert_eq!(occurrence.start, date("2023-01-01T05:00:00Z"));
+        assert_eq!(occurrence.index, 5);
+
+        let instant =
+            Recurrence::from_str("FREQ=DAILY;INTERVAL=1;DTSTART=2023-01-01T09:00:00Z").unwrap();
+        assert!(instant.contains_instant(&date("2023-01-03T09:00:00Z")));
+        assert!(!instant.contains_instant(&date("2023-01-03T09:00:01Z")));
+    }
+
+    #[test]
+    fn instants_during_jittered_events() {
+        let recurrence = Recurrence::from_str(
+            "FREQ=HOURLY;INTERVAL=2;DTSTART=2023-01-01T00:00:00Z;DURATION=PT30M",
+        )
+        .unwrap()
+        .with_jitter(Duration::minutes(20), 3);
+        for occurrence in recurrence.detailed_iter().take(50) {
+            assert_eq!(
+                recurrence.occurrence_covering(&occurrence.start),
+                Some(occurrence)
+            );
+            assert!(!recurrence.contains_instant(&occurrence.end));
+        }
+    }
+}
+
+#[cfg(test)]
+mod next_occurrence {
+    use crate::clock::MockClock;
+    use crate::recurrences::Recurrence;
+    use crate::test_utils::date;
+    use chrono::{DateTime, Duration, Utc};
+    use std::str::FromStr;
+
+    #[test]
+    fn next_from_before_start() {
+        let recurrence =
+            Recurrence::from_str("FREQ=DAILY;INTERVAL=1;DTSTART=2023-01-01T10:00:00Z").unwrap();
+        assert_eq!(
+            recurrence.next_from(&date("2020-01-01T00:00:00Z")),
+            Some(date("2023-01-01T10:00:00Z"))
+        );
+    }
+
+    #[test]
+    fn next_from_is_after_the_date() {
+        let recurrence =
+            Recurrence::from_str("FREQ=WEEKLY;INTERVAL=2;BYDAY=MO,FR;DTSTART=2023-01-02T10:00:00Z")
+                .unwrap();
+        assert_eq!(
+            recurrence.next_from(&date("2023-01-06T10:00:00Z")),
+            Some(date("2023-01-16T10:00:00Z"))
+        );
+    }
+
+    #[test]
+    fn next_from_skips_excluded_dates() {
+        let recurrence =
+            Recurrence::from_str("FREQ=SECONDLY;INTERVAL=5;DTSTART=2000-01-01T00:00:00Z")
+                .unwrap()
+                .with_exdates(vec![date("2023-01-01T00:00:05Z")]);
+        assert_eq!(
+            recurrence.next_from(&date("2023-01-01T00:00:02Z")),
+            Some(date("2023-01-01T00:00:10Z"))
+        );
+    }
+
+    #[test]
+    fn next_from_respects_the_end_date() {
+        let recurrence = Recurrence::from_str(
+            "FREQ=MONTHLY;INTERVAL=1;DTSTART=2023-01-15T00:00:00Z;DTEND=2023-03-15T00:00:00Z",
+        )
+        .unwrap();
+        assert_eq!(
+            recurrence.next_from(&date("2023-02-20T00:00:00Z")),
+            Some(date("2023-03-15T00:00:00Z"))
+        );
+        assert_eq!(recurrence.next_from(&date("2023-03-15T00:00:00Z")), None);
+    }
+
+    #[test]
+    fn between_includes_both_dates() {
+        let recurrence =
+            Recurrence::from_str("FREQ=WEEKLY;INTERVAL=1;BYDAY=MO,WE;DTSTART=2023-01-02T10:00:00Z")
+                .unwrap();
+        let events: Vec<DateTime<Utc>> = recurrence
+            .between(&date("2023-01-09T10:00:00Z"), &date("2023-01-16T10:00:00Z"))
+            .collect();
+        assert_eq!(
+            events,
+            vec![
+                date("2023-01-09T10:00:00Z"),
+                date("2023-01-11T10:00:00Z"),
+                date("2023-01-16T10:00:00Z"),
+            ]
+        );
+    }
+
+    #[test]
+    fn between_skips_to_the_first_date() {
+        let recurrence =
+            Recurrence::from_str("FREQ=SECONDLY;INTERVAL=3;DTSTART=2000-01-01T00:00:00Z").unwrap();
+        let events: Vec<DateTime<Utc>> = recurrence
+            .between(&date("2023-01-01T00:00:01Z"), &date("2023-01-01T00:00:07Z"))
+            .collect();
+        assert_eq!(
+            events,
+            vec![date("2023-01-01T00:00:03Z"), date("2023-01-01T00:00:06Z")]
+        );
+    }
+
+    #[test]
+    fn next_from_stays_in_the_monthly_windows_of_the_anchor() {
+        let recurrence =
+            Recurrence::from_str("FREQ=MONTHLY;INTERVAL=3;BYDAY=1MO;DTSTART=2023-01-01T09:00:00Z")
+                .unwrap();
+        assert_eq!(recurrence.anchor(), date("2023-01-01T09:00:00Z"));
+        assert_eq!(
+            recurrence.next_from(&date("2023-02-10T00:00:00Z")),
+            Some(date("2023-04-03T09:00:00Z"))
+        );
+        assert_eq!(
+            recurrence.next_from(&date("2043-05-10T00:00:00Z")),
+            Some(date("2043-07-06T09:00:00Z"))
+        );
+        let events: Vec<DateTime<Utc>> = recurrence
+            .between(&date("2030-01-01T00:00:00Z"), &date("2030-12-31T00:00:00Z"))
+            .collect();
+        assert_eq!(
+            events,
+            vec![
+                date("2030-01-07T09:00:00Z"),
+                date("2030-04-01T09:00:00Z"),
+                date("2030-07-01T09:00:00Z"),
+                date("2030-10-07T09:00:00Z"),
+            ]
+        );
+    }
+
+    #[test]
+    fn next_from_now_is_in_the_future() {
+        let recurrence =
+            Recurrence::from_str("FREQ=MINUTELY;INTERVAL=7;DTSTART=2020-01-01T00:00:03Z").unwrap();
+        let now = Utc::now();
+        let next = recurrence.next_from_now().unwrap();
+        assert!(next > now && next <= now + Duration::minutes(7));
+        assert!(recurrence.contains(&next));
+    }
+
+    #[test]
+    fn next_from_now_with_a_clock() {
+        let recurrence = Recurrence::from_str(
+            "FREQ=MINUTELY;INTERVAL=7;DTSTART=2020-01-01T00:00:03Z;DTEND=2020-01-01T01:00:00Z",
+        )
+        .unwrap();
+        let clock = MockClock::new(date("2020-01-01T00:10:00Z"));
+        assert_eq!(
+            recurrence.next_from_now_with(&clock),
+            Some(date("2020-01-01T00:14:03Z"))
+        );
+        clock.set(date("2020-01-01T00:56:03Z"));
+        assert_eq!(recurrence.next_from_now_with(&clock), None);
+    }
+}
+
+#[cfg(test)]
+mod nth_occurrence {
+    use crate::recurrences::Recurrence;
+    use chrono::{DateTime, Utc};
+    use std::str::FromStr;
+
+    fn assert_nth_matches_iteration(recurrence: &Recurrence) {
+        let events: Vec<DateTime<Utc>> = recurrence.iter().take(101).collect();
+        for n in [0, 1, 7, 100] {
+            assert_eq!(
+                recurrence.nth_occurrence(n),
+                events.get(n).copied(),
+                "{n}th event of {recurrence}"
+            );
+        }
+    }
+
+    #[test]
+    fn nth_of_evenly_spaced_events() {
+        for value in [
+            "FREQ=SECONDLY;INTERVAL=7;DTSTART=2023-01-01T00:00:00Z",
+            "FREQ=MINUTELY;INTERVAL=15;DTSTART=2023-01-01T00:00:00Z",
+            "FREQ=HOURLY;INTERVAL=5;DTSTART=2023-01-01T00:00:00Z",
+            "FREQ=DAILY;INTERVAL=2;DTSTART=2023-01-01T00:00:00Z",
+            "FREQ=WEEKLY;INTERVAL=3;DTSTART=2023-01-01T00:00:00Z",
+        ] {
+            assert_nth_matches_iteration(&Recurrence::from_str(value).unwrap());
+        }
+    }
+
+    #[test]
+    fn nth_of_filtered_and_excluded_events() {
+        let filtered =
+            Recurrence::from_str("FREQ=WEEKLY;INTERVAL=2;BYDAY=MO,TH;DTSTART=2023-01-01T00:00:00Z")
+                .unwrap();
+        assert_nth_matches_iteration(&filtered);
+
+        let excluded = Recurrence::from_str("FREQ=DAILY;INTERVAL=1;DTSTART=2023-01-01T00:00:00Z")
+            .unwrap()
+            .with_exdates(vec![
+                DateTime::<Utc>::from_str("2023-01-03T00:00:00Z").unwrap()
+            ]);
+        assert_nth_matches_iteration(&excluded);
+    }
+
+    #[test]
+    fn nth_after_the_end_date() {
+        let recurrence = Recurrence::from_str(
+            "FREQ=DAILY;INTERVAL=1;DTSTART=2023-01-01T00:00:00Z;DTEND=2023-01-10T00:00:00Z",
+        )
+        .unwrap();
+        assert!(recurrence.nth_occurrence(9).is_some());
+        assert_eq!(recurrence.nth_occurrence(10), None);
+        assert_eq!(recurrence.nth_occurrence(usize::MAX), None);
+    }
+
+    #[test]
+    fn nth_continues_the_iteration() {
+        let recurrence =
+            Recurrence::from_str("FREQ=HOURLY;INTERVAL=1;DTSTART=2023-01-01T00:00:00Z").unwrap();
+        let mut events = recurrence.iter();
+        events.next();
+        assert_eq!(
+            events.nth(2),
+            Some(DateTime::<Utc>::from_str("2023-01-01T03:00:00Z").unwrap())
+        );
+        assert_eq!(
+            events.next(),
+            Some(DateTime::<Utc>::from_str("2023-01-01T04:00:00Z").unwrap())
+        );
+    }
+}
+
+#[cfg(test)]
+mod recurrence_updates {
+    use crate::frequencies::{Frequency, Time};
+    use crate::recurrences::{Recurrence, RecurrenceErrorKind};
+    use crate::test_utils::date;
+    use chrono::{DateTime, Utc};
+    use std::num::NonZeroU32;
+    use std::str::FromStr;
+
+    fn every_2_days() -> Recurrence {
+        Recurrence::from_str("FREQ=DAILY;INTERVAL=2;DTSTART=2023-01-01T10:00:00Z").unwrap()
+    }
+
+    #[test]
+    fn with_frequency_replaces_the_frequency() {
+        let recurrence = every_2_days()
+            .with_frequency(Frequency::Hourly {
+                interval: NonZeroU32::new(6).unwrap(),
+                by_day: vec![],
+                by_time: vec![],
+                by_hour_range: None,
+            })
+            .unwrap();
+        let events: Vec<DateTime<Utc>> = recurrence.iter().take(2).collect();
+        assert_eq!(
+            events,
+            vec![date("2023-01-01T10:00:00Z"), date("2023-01-01T16:00:00Z")]
+        );
+    }
+
+    #[test]
+    fn with_invalid_frequency() {
+        let result = every_2_days().with_frequency(Frequency::Daily {
+            interval: NonZeroU32::new(1).unwrap(),
+            by_day: vec![],
+            by_time: vec![Time {
+                hour: 24,
+                minute: 0,
+                second: 0,
+            }],
+        });
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn with_start_and_end() {
+        let recurrence = every_2_days()
+            .with_start(date("2023-02-01T08:00:00Z"))
+            .unwrap()
+            .with_end(Some(date("2023-02-05T08:00:00Z")))
+            .unwrap();
+        let events: Vec<DateTime<Utc>> = recurrence.iter().collect();
+        assert_eq!(
+            events,
+            vec![
+                date("2023-02-01T08:00:00Z"),
+                date("2023-02-03T08:00:00Z"),
+                date("2023-02-05T08:00:00Z"),
+            ]
+        );
+        let unbounded = recurrence.with_end(None).unwrap();
+        assert!(unbounded.is_infinite());
+    }
+
+    #[test]
+    fn with_invalid_period() {
+        let recurrence = every_2_days()
+            .with_end(Some(date("2023-01-05T00:00:00Z")))
+            .unwrap();
+        assert!(recurrence
+            .clone()
+            .with_start(date("2023-01-06T00:00:00Z"))
+            .is_err());
+        assert!(recurrence
+            .with_end(Some(date("2022-12-31T00:00:00Z")))
+            .is_err());
+    }
+
+    #[test]
+    fn split_keeps_the_events() {
+        let recurrence = every_2_days()
+            .with_end(Some(date("2023-01-31T10:00:00Z")))
+            .unwrap();
+        let (before, after) = recurrence.split_at(&date("2023-01-09T10:00:00Z")).unwrap();
+        assert_eq!(before.iter().last(), Some(date("2023-01-07T10:00:00Z")));
+        assert_eq!(after.start, recurrence.start);
+        assert_eq!(after.not_before(), Some(date("2023-01-09T10:00:00Z")));
+        let events: Vec<DateTime<Utc>> = before.iter().chain(after.iter()).collect();
+        assert_eq!(events, recurrence.iter().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn split_partitions_the_exdates() {
+        let recurrence = every_2_days().with_exdates(vec![
+            date("2023-01-03T10:00:00Z"),
+            date("2023-01-13T10:00:00Z"),
+        ]);
+        let (before, after) = recurrence.split_at(&date("2023-01-10T00:00:00Z")).unwrap();
+        assert_eq!(before.exdates, vec![date("2023-01-03T10:00:00Z")]);
+        assert_eq!(after.exdates, vec![date("2023-01-13T10:00:00Z")]);
+        let events: Vec<DateTime<Utc>> = after.iter().take(2).collect();
+        assert_eq!(
+            events,
+            vec![date("2023-01-11T10:00:00Z"), date("2023-01-15T10:00:00Z")]
+        );
+    }
+
+    #[test]
+    fn split_without_events_before_or_after() {
+        let recurrence = every_2_days()
+            .with_end(Some(date("2023-01-05T10:00:00Z")))
+            .unwrap();
+        assert!(recurrence.split_at(&date("2023-01-06T00:00:00Z")).is_err());
+        assert!(recurrence.split_at(&date("2023-01-01T10:00:00Z")).is_err());
+    }
+
+    #[test]
+    fn split_at_or_before_the_start() {
+        let recurrence = every_2_days();
+        for split_date in ["2023-01-01T10:00:00Z", "2022-12-01T00:00:00Z"] {
+            let error = recurrence.split_at(&date(split_date)).unwrap_err();
+            assert_eq!(error.kind, RecurrenceErrorKind::NoEvents);
+            assert_eq!(error.message, "There are no events before the split date");
+        }
+        let first_on_friday =
+            Recurrence::from_str("FREQ=WEEKLY;INTERVAL=1;BYDAY=FR;DTSTART=2023-01-02T10:00:00Z")
+                .unwrap();
+        let error = first_on_friday
+            .split_at(&date("2023-01-04T00:00:00Z"))
+            .unwrap_err();
+        assert_eq!(error.message, "There are no events before the split date");
+    }
+
+    #[test]
+    fn split_counts_the_skipped_events_towards_the_max_occurrences() {
+        let recurrence = every_2_days().with_max_occurrences(5);
+        let (before, after) = recurrence.split_at(&date("2023-01-05T10:00:00Z")).unwrap();
+        assert_eq!(before.iter().count(), 2);
+        assert_eq!(after.iter().count(), 3);
+        assert_eq!(after.count_occurrences(), Some(3));
+        let events: Vec<DateTime<Utc>> = before.iter().chain(after.iter()).collect();
+        assert_eq!(events, recurrence.iter().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn split_keeps_the_events_just_before_the_date() {
+        let recurrence =
+            Recurrence::from_str("FREQ=DAILY;INTERVAL=2;DTSTART=2023-01-01T10:00:00.500Z")
+                .unwrap()
+                .with_max_occurrences(10);
+        let (before, after) = recurrence.split_at(&date("2023-01-09T10:00:01Z")).unwrap();
+        assert_eq!(before.iter().last(), Some(date("2023-01-09T10:00:00.500Z")));
+        assert_eq!(after.iter().next(), Some(date("2023-01-11T10:00:00.500Z")));
+        let events: Vec<DateTime<Utc>> = before.iter().chain(after.iter()).collect();
+        assert_eq!(events, recurrence.iter().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn split_keeps_the_anchor_of_the_rules() {
+        let rules = [
+            "FREQ=MONTHLY;INTERVAL=1;SKIP=BACKWARD;DTSTART=2023-01-31T09:00:00Z",
+            "FREQ=MONTHLY;INTERVAL=1;BYMONTHDAY=31;SKIP=BACKWARD;DTSTART=2023-01-31T09:00:00Z",
+            "FREQ=WEEKLY;INTERVAL=2;BYDAY=MO,FR;DTSTART=2023-01-04T09:00:00Z",
+        ];
+        let split_date = date("2023-02-01T00:00:00Z");
+        for rule in rules {
+            let recurrence = Recurrence::from_str(rule).unwrap();
+            let (_, after) = recurrence.split_at(&split_date).unwrap();
+            let expected: Vec<DateTime<Utc>> = recurrence
+                .iter()
+                .skip_while(|event| *event < split_date)
+                .take(12)
+                .collect();
+            assert_eq!(
+                after.iter().take(12).collect::<Vec<_>>(),
+                expected,
+                "{rule}"
+            );
+            assert_eq!(after.next_from(&split_date), Some(expected[0]), "{rule}");
+            assert_eq!(after.nth(5), Some(expected[5]), "{rule}");
+            assert_eq!(after.position_of(&expected[5]), Some(5), "{rule}");
+            assert!(
+                !after.contains(&recurrence.iter().next().unwrap()),
+                "{rule}"
+            );
+
+            let parsed = Recurrence::from_str(&after.to_string()).unwrap();
+            assert_eq!(parsed, after, "{rule}");
+            let parsed = Recurrence::from_ics_vevent(&after.to_ics_vevent()).unwrap();
+            assert_eq!(
+                parsed.iter().take(12).collect::<Vec<_>>(),
+                expected,
+                "{rule}"
+            );
+        }
+    }
+
+    #[test]
+    fn split_skips_the_evenly_spaced_events_before_the_date() {
+        let recurrence = Recurrence::from_str(
+            "FREQ=HOURLY;INTERVAL=5;DTSTART=2023-01-01T00:00:00Z;DTEND=2023-01-03T00:00:00Z",
+        )
+        .unwrap();
+        let (before, after) = recurrence.split_at(&date("2023-01-01T12:00:00Z")).unwrap();
+        assert_eq!(before.count_occurrences(), Some(3));
+        assert_eq!(after.count_occurrences(), Some(7));
+        assert_eq!(after.nth(0), Some(date("2023-01-01T15:00:00Z")));
+        assert_eq!(after.nth(6), Some(date("2023-01-02T21:00:00Z")));
+        assert_eq!(after.position_of(&date("2023-01-02T21:00:00Z")), Some(6));
+        assert_eq!(after.position_of(&date("2023-01-01T10:00:00Z")), None);
+    }
+
+    #[test]
+    fn truncate_keeps_the_following_events() {
+        let recurrence = Recurrence::from_str(
+            "FREQ=WEEKLY;INTERVAL=2;BYDAY=MO,WE;DTSTART=2023-01-02T10:00:00Z;COUNT=8",
+        )
+        .unwrap()
+        .with_exdates(vec![
+            date("2023-01-04T10:00:00Z"),
+            date("2023-01-30T10:00:00Z"),
+        ]);
+        let truncated = recurrence
+            .truncate_before(&date("2023-01-17T00:00:00Z"))
+            .unwrap();
+        assert_eq!(truncated.start, recurrence.start);
+        assert_eq!(truncated.not_before(), Some(date("2023-01-18T10:00:00Z")));
+        assert_eq!(truncated.exdates, vec![date("2023-01-30T10:00:00Z")]);
+        assert_eq!(truncated.count, Some(8));
+        let expected: Vec<DateTime<Utc>> = recurrence
+            .iter()
+            .filter(|event| *event >= date("2023-01-17T00:00:00Z"))
+            .collect();
+        assert_eq!(truncated.iter().collect::<Vec<_>>(), expected);
+        assert_eq!(expected.len(), 4);
+    }
+
+    #[test]
+    fn truncate_keeps_the_anchor_of_the_rules() {
+        let rules = [
+            "FREQ=MONTHLY;INTERVAL=1;SKIP=BACKWARD;DTSTART=2023-01-31T09:00:00Z",
+            "FREQ=MONTHLY;INTERVAL=1;BYMONTHDAY=31;X-OVERFLOW=NEXT;DTSTART=2023-01-31T09:00:00Z",
+            "FREQ=WEEKLY;INTERVAL=2;BYDAY=MO,FR;DTSTART=2023-01-04T09:00:00Z",
+        ];
+        for truncation_date in ["2023-02-01T00:00:00Z", "2023-03-01T12:00:00Z"] {
+            let truncation_date = date(truncation_date);
+            for rule in rules {
+                let recurrence = Recurrence::from_str(rule)
+                    .unwrap()
+                    .with_end(Some(date("2024-01-01T00:00:00Z")))
+                    .unwrap();
+                let truncated = recurrence.truncate_before(&truncation_date).unwrap();
+                let expected: Vec<DateTime<Utc>> = recurrence
+                    .iter()
+                    .skip_while(|event| *event < truncation_date)
+                    .collect();
+                assert_eq!(truncated.iter().collect::<Vec<_>>(), expected, "{rule}");
+                assert_eq!(
+                    truncated.count_occurrences(),
+                    Some(expected.len() as u64),
+                    "{rule}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn truncate_before_the_start_or_after_the_end() {
+        let recurrence = every_2_days()
+            .with_end(Some(date("2023-01-05T10:00:00Z")))
+            .unwrap();
+        let truncated = recurrence
+            .truncate_before(&date("2022-12-01T00:00:00Z"))
+            .unwrap();
+        assert_eq!(truncated.start, recurrence.start);
+        let truncated = recurrence
+            .truncate_before(&date("2023-01-05T10:00:00Z"))
+            .unwrap();
+        assert_eq!(
+            truncated.iter().collect::<Vec<_>>(),
+            [date("2023-01-05T10:00:00Z")]
+        );
+        assert!(recurrence
+            .truncate_before(&date("2023-01-05T10:00:01Z"))
+            .is_err());
+    }
+
+    #[test]
+    fn expired_recurrences() {
+        let recurrence = every_2_days()
+            .with_end(Some(date("2023-01-05T10:00:00Z")))
+            .unwrap();
+        assert!(!recurrence.is_expired(&date("2022-12-01T00:00:00Z")));
+        assert!(!recurrence.is_expired(&date("2023-01-05T09:59:59Z")));
+        assert!(recurrence.is_expired(&date("2023-01-05T10:00:00Z")));
+
+        let excluded = recurrence.with_exdates(vec![date("2023-01-05T10:00:00Z")]);
+        assert!(excluded.is_expired(&date("2023-01-04T00:00:00Z")));
+        let limited = every_2_days().with_max_occurrences(2);
+        assert!(limited.is_expired(&date("2023-01-03T10:00:00Z")));
+        assert!(!every_2_days().is_expired(&date("2099-01-01T00:00:00Z")));
+    }
+}
+
+#[cfg(test)]
+mod iteration_limits {
+    use crate::recurrences::Recurrence;
+    use crate::test_utils::date;
+    use chrono::{DateTime, Utc};
+    use std::str::FromStr;
+
+    #[test]
+    fn stops_at_max_occurrences() {
+        let recurrence =
+            Recurrence::from_str("FREQ=WEEKLY;INTERVAL=1;BYDAY=MO,FR;DTSTART=2023-01-01T00:00:00Z")
+                .unwrap()
+                .with_max_occurrences(3);
+        let mut events = recurrence.iter();
+        assert!(!events.is_truncated());
+        let collected: Vec<DateTime<Utc>> = events.by_ref().collect();
+        assert_eq!(
+            collected,
+            vec![
+                date("2023-01-02T00:00:00Z"),
+                date("2023-01-06T00:00:00Z"),
+                date("2023-01-09T00:00:00Z"),
+            ]
+        );
+        assert!(events.is_truncated());
+        assert_eq!(recurrence.count_occurrences(), Some(3));
+        assert_eq!(recurrence.nth_occurrence(3), None);
+        assert_eq!(recurrence.next_from(&date("2023-01-09T00:00:00Z")), None);
+    }
+
+    #[test]
+    fn stops_at_max_horizon() {
+        let recurrence = Recurrence::from_str("FREQ=DAILY;INTERVAL=1;DTSTART=2023-01-01T10:00:00Z")
+            .unwrap()
+            .with_max_horizon(date("2023-01-03T12:00:00Z"));
+        let mut events = recurrence.iter();
+        assert_eq!(events.by_ref().count(), 3);
+        assert!(events.is_truncated());
+        assert_eq!(recurrence.count_occurrences(), Some(3));
+        assert_eq!(recurrence.nth_occurrence(3), None);
+    }
+
+    #[test]
+    fn limits_after_the_end_do_not_truncate() {
+        let recurrence = Recurrence::from_str(
+            "FREQ=DAILY;INTERVAL=1;DTSTART=2023-01-01T00:00:00Z;DTEND=2023-01-03T00:00:00Z",
+        )
+        .unwrap()
+        .with_max_occurrences(3)
+        .with_max_horizon(date("2023-02-01T00:00:00Z"));
+        let mut events = recurrence.iter();
+        assert_eq!(events.by_ref().count(), 3);
+        assert!(!events.is_truncated());
+    }
+
+    #[test]
+    fn nth_counts_the_skipped_events() {
+        let recurrence =
+            Recurrence::from_str("FREQ=HOURLY;INTERVAL=1;DTSTART=2023-01-01T00:00:00Z")
+                .unwrap()
+                .with_max_occurrences(10);
+        let mut events = recurrence.iter();
+        assert_eq!(events.nth(9), Some(date("2023-01-01T09:00:00Z")));
+        assert_eq!(events.next(), None);
+        assert!(events.is_truncated());
+    }
+
+    #[test]
+    fn limits_are_not_serialized() {
+        let value = "FREQ=DAILY;INTERVAL=1;DTSTART=2023-01-01T00:00:00Z";
+        let recurrence = Recurrence::from_str(value)
+            .unwrap()
+            .with_max_occurrences(10_000)
+            .with_max_horizon(date("2024-01-01T00:00:00Z"));
+        assert_eq!(recurrence.to_string(), value);
+        assert_eq!(recurrence.count, None);
+    }
+
+    #[test]
+    fn rule_count_does_not_truncate() {
+        let recurrence =
+            Recurrence::from_str("FREQ=DAILY;INTERVAL=1;DTSTART=2023-01-01T00:00:00Z;COUNT=3")
+                .unwrap();
+        assert_eq!(recurrence.count, Some(3));
+        assert_eq!(recurrence.max_occurrences, None);
+        let mut events = recurrence.iter();
+        assert_eq!(events.by_ref().count(), 3);
+        assert!(!events.is_truncated());
+        assert_eq!(recurrence.count_occurrences(), Some(3));
+        assert!(!recurrence.contains(&date("2023-01-04T00:00:00Z")));
+        assert_eq!(recurrence.position_of(&date("2023-01-04T00:00:00Z")), None);
+
+        let limited = recurrence.with_max_occurrences(2);
+        let mut events = limited.iter();
+        assert_eq!(events.by_ref().count(), 2);
+        assert!(events.is_truncated());
+        assert_eq!(limited.count_occurrences(), Some(2));
+    }
+
+    #[test]
+    fn rule_count_includes_the_excluded_dates() {
+        let value = "FREQ=DAILY;INTERVAL=1;DTSTART=2023-01-01T10:00:00Z;COUNT=4;\
+            EXDATE=2023-01-02T10:00:00Z,2023-01-10T10:00:00Z";
+        let recurrence = Recurrence::from_str(value).unwrap();
+        assert_eq!(recurrence.count, Some(4));
+        assert_eq!(recurrence.iter().count(), 3);
+        assert_eq!(
+            recurrence.exdates,
+            vec![date("2023-01-02T10:00:00Z"), date("2023-01-10T10:00:00Z")]
+        );
+        let start = date("2023-01-01T10:00:00Z");
+        assert_eq!(
+            recurrence.next_from(&start),
+            Some(date("2023-01-03T10:00:00Z"))
+        );
+        assert_eq!(
+            recurrence.next_from(&date("2023-01-03T10:00:00Z")),
+            Some(date("2023-01-04T10:00:00Z"))
+        );
+        assert_eq!(recurrence.next_from(&date("2023-01-04T10:00:00Z")), None);
+        assert_eq!(
+            recurrence.to_string(),
+            "FREQ=DAILY;INTERVAL=1;DTSTART=2023-01-01T10:00:00Z;COUNT=4;\
+            EXDATE=2023-01-02T10:00:00Z,2023-01-10T10:00:00Z"
+        );
+        assert_eq!(
+            Recurrence::from_str(&recurrence.to_string()).unwrap(),
+            recurrence
+        );
+
+        let all_day =
+            Recurrence::from_str("FREQ=DAILY;INTERVAL=1;DTSTART=20230101;COUNT=2;EXDATE=20230101")
+                .unwrap();
+        assert_eq!(all_day.iter().count(), 1);
+        assert_eq!(Recurrence::from_str(&all_day.to_string()).unwrap(), all_day);
+
+        let daily = "FREQ=DAILY;INTERVAL=1;DTSTART=2023-01-01T10:00:00Z";
+        assert!(Recurrence::from_str(&format!("{daily};COUNT=+1")).is_err());
+        assert!(Recurrence::from_str(&format!("{daily};EXDATE=2023-01-02T10:00:00Z,")).is_err());
+    }
+}
+
+#[cfg(test)]
+mod recurrence_arithmetic {
+    use crate::recurrences::Recurrence;
+    use crate::test_utils::date;
+    use std::str::FromStr;
+
+    #[test]
+    fn intersection_of_rules() {
+        let weekends =
+            Recurrence::from_str("FREQ=WEEKLY;INTERVAL=1;BYDAY=SA,SU;DTSTART=2023-01-01T10:00:00Z")
+                .unwrap();
+        let first_days = Recurrence::from_str(
+            "FREQ=MONTHLY;INTERVAL=1;BYMONTHDAY=1;DTSTART=2023-01-01T10:00:00Z",
+        )
+        .unwrap();
+        let window = date("2023-01-01T00:00:00Z")..date("2024-01-01T00:00:00Z");
+        let events: Vec<_> = weekends.intersect(&first_days, window.clone()).collect();
+        assert_eq!(
+            events,
+            vec![
+                date("2023-01-01T10:00:00Z"),
+                date("2023-04-01T10:00:00Z"),
+                date("2023-07-01T10:00:00Z"),
+                date("2023-10-01T10:00:00Z"),
+            ]
+        );
+        let reversed: Vec<_> = first_days.intersect(&weekends, window).collect();
+        assert_eq!(reversed, events);
+    }
+
+    #[test]
+    fn intersection_needs_the_same_instants() {
+        let mornings =
+            Recurrence::from_str("FREQ=DAILY;INTERVAL=1;DTSTART=2023-01-01T09:00:00Z").unwrap();
+        let evenings =
+            Recurrence::from_str("FREQ=DAILY;INTERVAL=1;DTSTART=2023-01-01T18:00:00Z").unwrap();
+        let window = date("2023-01-01T00:00:00Z")..date("2023-02-01T00:00:00Z");
+        assert_eq!(mornings.intersect(&evenings, window).count(), 0);
+    }
+
+    #[test]
+    fn difference_of_rules() {
+        let weekdays = Recurrence::from_str(
+            "FREQ=WEEKLY;INTERVAL=1;BYDAY=MO,TU,WE,TH,FR;DTSTART=2023-01-02T09:00:00Z",
+        )
+        .unwrap();
+        let holidays = Recurrence::from_str(
+            "FREQ=YEARLY;INTERVAL=1;X-BYMONTHDATE=JAN1,DEC25,DEC26;DTSTART=2023-01-01T09:00:00Z",
+        )
+        .unwrap();
+        let window = date("2023-12-22T00:00:00Z")..date("2024-01-03T00:00:00Z");
+        let events: Vec<_> = weekdays.difference(&holidays, window.clone()).collect();
+        assert_eq!(
+            events,
+            vec![
+                date("2023-12-22T09:00:00Z"),
+                date("2023-12-27T09:00:00Z"),
+                date("2023-12-28T09:00:00Z"),
+                date("2023-12-29T09:00:00Z"),
+                date("2024-01-02T09:00:00Z"),
+            ]
+        );
+        let holidays_off: Vec<_> = holidays.difference(&weekdays, window).collect();
+        assert!(holidays_off.is_empty());
+    }
+
+    #[test]
+    fn window_end_is_excluded() {
+        let daily =
+            Recurrence::from_str("FREQ=DAILY;INTERVAL=1;DTSTART=2023-01-01T00:00:00Z").unwrap();
+        let window = date("2023-01-01T00:00:00Z")..date("2023-01-03T00:00:00Z");
+        assert_eq!(daily.intersect(&daily, window.clone()).count(), 2);
+        assert_eq!(daily.difference(&daily, window).count(), 0);
+    }
+}
+
+#[cfg(test)]
+mod position_of {
+    use crate::recurrences::Recurrence;
+    use crate::test_utils::date;
+    use std::str::FromStr;
+
+    fn assert_position_matches_iteration(recurrence: &Recurrence) {
+        for (n, event) in recurrence.iter().take(101).enumerate() {
+            assert_eq!(
+                recurrence.position_of(&event),
+                Some(n),
+                "{event} of {recurrence}"
+            );
+            assert_eq!(recurrence.nth(n), Some(event));
+        }
+    }
+
+    #[test]
+    fn position_of_evenly_spaced_events() {
+        for value in [
+            "FREQ=SECONDLY;INTERVAL=7;DTSTART=2023-01-01T00:00:00Z",
+            "FREQ=HOURLY;INTERVAL=5;DTSTART=2023-01-01T00:00:00Z",
+            "FREQ=DAILY;INTERVAL=2;DTSTART=2023-01-01T00:00:00Z",
+            "FREQ=WEEKLY;INTERVAL=3;DTSTART=2023-01-01T00:00:00Z",
+        ] {
+            assert_position_matches_iteration(&Recurrence::from_str(value).unwrap());
+        }
+        let every_second =
+            Recurrence::from_str("FREQ=SECONDLY;INTERVAL=1;DTSTART=2023-01-01T00:00:00Z").unwrap();
+        assert_eq!(
+            every_second.position_of(&date("2024-01-01T00:00:00Z")),
+            Some(31_536_000)
+        );
+    }
+
+    #[test]
+    fn position_of_filtered_and_excluded_events() {
+        let filtered = Recurrence::from_str(
+            "FREQ=MONTHLY;INTERVAL=1;BYDAY=1MO,3FR;DTSTART=2023-01-01T09:00:00Z",
+        )
+        .unwrap();
+        assert_position_matches_iteration(&filtered);
+
+        let excluded = Recurrence::from_str("FREQ=DAILY;INTERVAL=1;DTSTART=2023-01-01T00:00:00Z")
+            .unwrap()
+            .with_exdates(vec![
+                date("2023-01-03T00:00:00Z"),
+                date("2023-01-03T00:00:00Z"),
+                date("2023-01-05T12:00:00Z"),
+            ]);
+        assert_position_matches_iteration(&excluded);
+        assert_eq!(excluded.position_of(&date("2023-01-03T00:00:00Z")), None);
+    }
+
+    #[test]
+    fn position_of_dates_that_are_not_events() {
+        let recurrence = Recurrence::from_str(
+            "FREQ=DAILY;INTERVAL=2;DTSTART=2023-01-01T00:00:00Z;DTEND=2023-01-31T00:00:00Z",
+        )
+        .unwrap();
+        assert_eq!(recurrence.position_of(&date("2023-01-02T00:00:00Z")), None);
+        assert_eq!(recurrence.position_of(&date("2023-01-03T12:00:00Z")), None);
+        assert_eq!(recurrence.position_of(&date("2022-12-30T00:00:00Z")), None);
+        assert_eq!(recurrence.position_of(&date("2023-02-02T00:00:00Z")), None);
+    }
+
+    #[test]
+    fn position_of_events_past_the_limits() {
+        let recurrence = Recurrence::from_str("FREQ=DAILY;INTERVAL=1;DTSTART=2023-01-01T00:00:00Z")
+            .unwrap()
+            .with_max_occurrences(3);
+        assert_eq!(
+            recurrence.position_of(&date("2023-01-03T00:00:00Z")),
+            Some(2)
+        );
+        assert_eq!(recurrence.position_of(&date("2023-01-04T00:00:00Z")), None);
+
+        let recurrence = Recurrence::from_str("FREQ=DAILY;INTERVAL=1;DTSTART=2023-01-01T00:00:00Z")
+            .unwrap()
+            .with_max_horizon(date("2023-01-02T00:00:00Z"));
+        assert_eq!(
+            recurrence.position_of(&date("2023-01-02T00:00:00Z")),
+            Some(1)
+        );
+        assert_eq!(recurrence.position_of(&date("2023-01-03T00:00:00Z")), None);
+    }
+}
+
+#[cfg(test)]
+mod detailed_iteration {
+    use crate::recurrences::{Occurrence, Recurrence};
+    use crate::test_utils::date;
+    use std::str::FromStr;
+
+    #[test]
+    fn yields_the_index_end_and_bounds_of_every_event() {
+        let recurrence = Recurrence::from_str(
+            "FREQ=WEEKLY;INTERVAL=1;BYDAY=MO,FR;DTSTART=2023-01-02T09:00:00Z;DTEND=2023-01-09T09:00:00Z;DURATION=PT30M",
+        )
+        .unwrap();
+        let occurrences: Vec<Occurrence> = recurrence.detailed_iter().collect();
+        assert_eq!(
+            occurrences,
+            vec![
+                Occurrence {
+                    index: 0,
+                    start: date("2023-01-02T09:00:00Z"),
+                    end: date("2023-01-02T09:30:00Z"),
+                    is_first: true,
+                    is_last: false,
+                },
+                Occurrence {
+                    index: 1,
+                    start: date("2023-01-06T09:00:00Z"),
+                    end: date("2023-01-06T09:30:00Z"),
+                    is_first: false,
+                    is_last: false,
+                },
+                Occurrence {
+                    index: 2,
+                    start: date("2023-01-09T09:00:00Z"),
+                    end: date("2023-01-09T09:30:00Z"),
+                    is_first: false,
+                    is_last: true,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn last_event_of_limited_and_endless_recurrences() {
+        let recurrence =
+            Recurrence::from_str("FREQ=DAILY;INTERVAL=1;DTSTART=2023-01-01T00:00:00Z").unwrap();
+        let occurrence = recurrence.detailed_iter().nth(99).unwrap();
+        assert_eq!(occurrence.index, 99);
+        assert_eq!(occurrence.end, occurrence.start);
+        assert!(!occurrence.is_last);
+
+        let recurrence = recurrence.with_max_occurrences(2);
+        let last = recurrence.detailed_iter().last().unwrap();
+        assert_eq!(last.index, 1);
+        assert!(last.is_last);
+
+        let recurrence = Recurrence::from_str(
+            "FREQ=DAILY;INTERVAL=1;DTSTART=2023-01-01T00:00:00Z;DTEND=2023-01-01T12:00:00Z",
+        )
+        .unwrap();
+        let only: Vec<Occurrence> = recurrence.detailed_iter().collect();
+        assert_eq!(only.len(), 1);
+        assert!(only[0].is_first && only[0].is_last);
+    }
+}
+
+#[cfg(test)]
+mod event_times {
+    use crate::recurrences::Recurrence;
+    use crate::test_utils::date;
+    use chrono::NaiveTime;
+    use std::str::FromStr;
+
+    #[test]
+    fn events_keep_the_time_of_the_start_date() {
+        let rules = [
+            "FREQ=DAILY;INTERVAL=3",
+            "FREQ=DAILY;INTERVAL=2;X-BUSINESS=TRUE",
+            "FREQ=WEEKLY;INTERVAL=1",
+            "FREQ=WEEKLY;INTERVAL=2;BYDAY=MO,SU",
+            "FREQ=WEEKLY;INTERVAL=1;BYDAY=TU,TH,SA",
+            "FREQ=MONTHLY;INTERVAL=1;BYDAY=2WE",
+            "FREQ=MONTHLY;INTERVAL=1;BYMONTHDAY=31;X-OVERFLOW=NEXT",
+            "FREQ=MONTHLY;INTERVAL=1;BYMONTHDAY=13;BYDAY=FR",
+            "FREQ=YEARLY;INTERVAL=1;BYWEEKNO=1,52;BYDAY=MO",
+        ];
+        for rule in rules {
+            let recurrence =
+                Recurrence::from_str(&format!("{rule};DTSTART=2023-01-04T21:47:13Z")).unwrap();
+            let time = NaiveTime::from_hms_opt(21, 47, 13);
+            assert_eq!(recurrence.event_time(), time, "{rule}");
+            for event in recurrence.iter().take(50) {
+                assert_eq!(Some(event.time()), time, "{rule}");
+            }
+            // Looking for the events from dates at other times of the day keeps the time too
+            let next = recurrence.next_from(&date("2024-06-18T03:00:00Z")).unwrap();
+            assert_eq!(Some(next.time()), time, "{rule}");
+        }
+    }
+
+    #[test]
+    fn frequencies_with_times_have_no_event_time() {
+        let rules = [
+            "FREQ=HOURLY;INTERVAL=1",
+            "FREQ=MINUTELY;INTERVAL=30",
+            "FREQ=SECONDLY;INTERVAL=30",
+            "FREQ=DAILY;INTERVAL=1;BYTIME=09:00",
+            "FREQ=DAILY;INTERVAL=1;BYHOUR=9,17",
+            "FREQ=WEEKLY;INTERVAL=1;X-BYDAYTIME=MO09:00,TH14:00",
+        ];
+        for rule in rules {
+            let recurrence =
+                Recurrence::from_str(&format!("{rule};DTSTART=2023-01-04T21:47:13Z")).unwrap();
+            assert_eq!(recurrence.event_time(), None, "{rule}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod shifting_and_rebasing {
+    use crate::test_utils::date;
+    use crate::{Recurrence, RecurrenceErrorKind};
+    use chrono::Duration;
+    use std::str::FromStr;
+
+    #[test]
+    fn shifting_moves_every_event() {
+        let rules = [
+            "FREQ=SECONDLY;INTERVAL=45",
+            "FREQ=MINUTELY;INTERVAL=7;BYTIME=23:00,23:07",
+            "FREQ=HOURLY;INTERVAL=3;BYDAY=MO,FR",
+            "FREQ=HOURLY;INTERVAL=1;X-BYHOURRANGE=9-18",
+            "FREQ=DAILY;INTERVAL=2",
+            "FREQ=DAILY;INTERVAL=3;BYDAY=MO,TH;BYHOUR=9,13",
+            "FREQ=DAILY;INTERVAL=1;X-BUSINESS=TRUE",
+            "FREQ=WEEKLY;INTERVAL=2;BYDAY=TU,SA",
+            "FREQ=WEEKLY;INTERVAL=1;X-BYDAYTIME=MO09:00,TH14:30",
+            "FREQ=MONTHLY;INTERVAL=1;BYMONTHDAY=31;BYHOUR=8",
+            "FREQ=MONTHLY;INTERVAL=2;BYDAY=2TU",
+            "FREQ=YEARLY;INTERVAL=1;BYMONTH=2;BYMONTHDAY=29",
+        ];
+        let offsets = [
+            Duration::hours(1),
+            Duration::minutes(-30),
+            Duration::days(1),
+            Duration::weeks(2),
+            Duration::hours(-30),
+        ];
+        let mut shifted_count = 0;
+        for rule in rules {
+            let recurrence = Recurrence::from_str(&format!(
+                "{rule};DTSTART=2023-01-04T10:00:00Z;DTEND=2026-01-01T00:00:00Z"
+            ))
+            .unwrap();
+            for offset in offsets {
+                let Ok(shifted) = recurrence.shift_by(offset) else {
+                    continue;
+                };
+                shifted_count += 1;
+                let expected: Vec<_> = recurrence
+                    .iter()
+                    .take(100)
+                    .map(|event| event + offset)
+                    .collect();
+                let events: Vec<_> = shifted.iter().take(100).collect();
+                assert_eq!(events, expected, "{rule} shifted by {offset}");
+                let parsed = Recurrence::from_str(&shifted.to_string()).unwrap();
+                assert_eq!(parsed, shifted, "{rule} shifted by {offset}");
+            }
+        }
+        // The other shifts move events to days that the rules of their frequency don't define
+        assert_eq!(shifted_count, 38);
+    }
+
+    #[test]
+    fn shifting_the_times_of_the_frequency() {
+        let recurrence = Recurrence::from_str(
+            "FREQ=HOURLY;INTERVAL=1;X-BYHOURRANGE=9-18;DTSTART=2023-01-02T00:00:00Z",
+        )
+        .unwrap();
+        let shifted = recurrence.shift_by(Duration::hours(-9)).unwrap();
+        assert_eq!(shifted.frequency.by_hour_range(), Some((0, 9)));
+        let shifted = recurrence.shift_by(Duration::hours(6)).unwrap();
+        assert_eq!(shifted.frequency.by_hour_range(), Some((15, 24)));
+        // The ranges can't go past midnight
+        assert!(recurrence.shift_by(Duration::hours(-10)).is_err());
+
+        let recurrence = Recurrence::from_str(
+            "FREQ=MINUTELY;INTERVAL=15;BYTIME=23:30,23:45;DTSTART=2023-01-02T00:00:00Z",
+        )
+        .unwrap();
+        let shifted = recurrence.shift_by(Duration::minutes(30)).unwrap();
+        assert_eq!(
+            shifted.to_string(),
+            "FREQ=MINUTELY;INTERVAL=15;BYHOUR=0;BYMINUTE=0,15;DTSTART=2023-01-02T00:30:00Z"
+        );
+    }
+
+    #[test]
+    fn shifting_dates_and_limits() {
+        let recurrence = Recurrence::from_str(
+            "FREQ=DAILY;INTERVAL=1;DTSTART=2023-01-01T09:00:00Z;DTEND=2023-01-10T09:00:00Z",
+        )
+        .unwrap()
+        .with_exdates(vec![date("2023-01-03T09:00:00Z")])
+        .with_max_horizon(date("2023-01-05T09:00:00Z"));
+        let shifted = recurrence.shift_by(Duration::minutes(90)).unwrap();
+        assert_eq!(shifted.start, date("2023-01-01T10:30:00Z"));
+        assert_eq!(shifted.end(), Some(date("2023-01-10T10:30:00Z")));
+        assert_eq!(shifted.exdates, [date("2023-01-03T10:30:00Z")]);
+        assert_eq!(shifted.max_horizon, Some(date("2023-01-05T10:30:00Z")));
+        assert_eq!(shifted.iter().count(), 4);
+        assert_eq!(recurrence.shift_by(Duration::zero()).unwrap(), recurrence);
+    }
+
+    #[test]
+    fn shifts_that_change_the_rules() {
+        for (rule, offset) in [
+            // The 31st moved a day later is not the 1st of every month
+            ("FREQ=MONTHLY;INTERVAL=1;BYMONTHDAY=31", Duration::days(1)),
+            ("FREQ=MONTHLY;INTERVAL=1", Duration::hours(-11)),
+            // Mondays and Thursdays at 23:00 moved 2 hours later are on other weekdays
+            (
+                "FREQ=WEEKLY;INTERVAL=1;BYDAY=MO,TH;BYHOUR=23",
+                Duration::hours(2),
+            ),
+            ("FREQ=HOURLY;INTERVAL=1;BYDAY=MO", Duration::hours(1)),
+            (
+                "FREQ=HOURLY;INTERVAL=1;X-BYHOURRANGE=9-18",
+                Duration::hours(8),
+            ),
+            (
+                "FREQ=MINUTELY;INTERVAL=1;BYTIME=09:00",
+                Duration::seconds(30),
+            ),
+            ("FREQ=DAILY;INTERVAL=1;X-BUSINESS=TRUE", Duration::days(1)),
+        ] {
+            let recurrence =
+                Recurrence::from_str(&format!("{rule};DTSTART=2023-01-02T10:00:00Z")).unwrap();
+            assert!(recurrence.shift_by(offset).is_err(), "{rule} by {offset}");
+        }
+
+        let all_day =
+            Recurrence::from_str("FREQ=WEEKLY;INTERVAL=1;DTSTART=VALUE=DATE:20230102").unwrap();
+        assert!(all_day.shift_by(Duration::hours(1)).is_err());
+        let shifted = all_day.shift_by(Duration::days(2)).unwrap();
+        assert_eq!(shifted.start, date("2023-01-04T00:00:00Z"));
+        assert!(shifted.all_day);
+
+        let error = Recurrence::from_str("FREQ=DAILY;INTERVAL=1;DTSTART=2023-01-02T10:00:00Z")
+            .unwrap()
+            .shift_by(Duration::MAX)
+            .unwrap_err();
+        assert_eq!(error.kind, RecurrenceErrorKind::OutOfRange);
+    }
+
+    #[test]
+    fn rebasing_keeps_the_rules() {
+        let recurrence = Recurrence::from_str(
+            "FREQ=DAILY;INTERVAL=3;BYHOUR=9;DTSTART=2023-01-01T00:00:00Z;DTEND=2023-02-01T00:00:00Z",
+        )
+        .unwrap()
+        .with_exdates(vec![
+            date("2023-01-04T09:00:00Z"),
+            date("2023-01-11T09:00:00Z"),
+            date("2023-01-13T09:00:00Z"),
+        ]);
+        let rebased = recurrence
+            .rebase_start(date("2023-01-02T12:00:00Z"))
+            .unwrap();
+        assert_eq!(rebased.frequency, recurrence.frequency);
+        assert_eq!(rebased.end(), recurrence.end());
+        // Only the excluded dates of the new phase are kept
+        assert_eq!(rebased.exdates, [date("2023-01-11T09:00:00Z")]);
+        let events: Vec<_> = rebased.iter().take(3).collect();
+        assert_eq!(
+            events,
+            [
+                date("2023-01-05T09:00:00Z"),
+                date("2023-01-08T09:00:00Z"),
+                date("2023-01-14T09:00:00Z"),
+            ]
+        );
+    }
+
+    #[test]
+    fn invalid_rebases() {
+        let recurrence = Recurrence::from_str(
+            "FREQ=WEEKLY;INTERVAL=1;DTSTART=2023-01-02T10:00:00Z;DTEND=2023-02-01T00:00:00Z",
+        )
+        .unwrap();
+        let error = recurrence
+            .rebase_start(date("2023-03-01T00:00:00Z"))
+            .unwrap_err();
+        assert!(matches!(
+            error.kind,
+            RecurrenceErrorKind::InvalidPeriod { .. }
+        ));
+
+        let all_day =
+            Recurrence::from_str("FREQ=DAILY;INTERVAL=2;DTSTART=VALUE=DATE:20230102").unwrap();
+        let rebased = all_day.rebase_start(date("2023-01-03T15:00:00Z")).unwrap();
+        assert_eq!(rebased.start, date("2023-01-03T00:00:00Z"));
+    }
 }
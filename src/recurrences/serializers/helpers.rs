@@ -1,134 +1,277 @@
-use crate::RecurrenceInvalid;
-use chrono::{DateTime, Duration, Utc};
-use std::str::FromStr;
-
-pub fn extract_start_date(s: &str) -> Result<DateTime<Utc>, RecurrenceInvalid> {
-    use regex::Regex;
-    let re =
-        Regex::new(r"DTSTART=(?P<date>[0-9]{4}-[0-9]{2}-[0-9]{2}T[0-9]{2}:[0-9]{2}:[0-9]{2}Z)")
-            .unwrap();
-    let caps = re.captures(s).ok_or(RecurrenceInvalid {
-        message: "No DTSTART found".to_string(),
-    })?;
-    let date = caps.name("date").ok_or(RecurrenceInvalid {
-        message: "No date found".to_string(),
-    })?;
-    let date = DateTime::<Utc>::from_str(date.as_str()).map_err(|e| RecurrenceInvalid {
-        message: format!("Invalid date: {e}"),
-    })?;
-    Ok(date)
+use crate::frequencies::serializer::tokenize;
+use crate::{RecurrenceErrorKind, RecurrenceInvalid};
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use chrono::{DateTime, Duration, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc};
+use core::str::FromStr;
+
+fn find_value<'a>(s: &'a str, key: &str) -> Option<&'a str> {
+    tokenize(s)
+        .flatten()
+        .find(|token| token.key == key)
+        .map(|token| token.value)
+}
+
+/// Extracts the `KEY=VALUE` pairs with keys that are not in the known keys, in their original order.
+pub fn extract_unknown_params(s: &str, known_keys: &[&str]) -> Vec<(String, String)> {
+    tokenize(s)
+        .flatten()
+        .filter(|token| !known_keys.contains(&token.key))
+        .map(|token| (token.key.to_string(), token.value.to_string()))
+        .collect()
+}
+
+/// Extracts the `DTSTART` date, or returns the default start date when there is none.
+pub fn extract_start_date(
+    s: &str,
+    default_start: Option<DateTime<Utc>>,
+) -> Result<DateTime<Utc>, RecurrenceInvalid> {
+    match (find_value(s, "DTSTART"), default_start) {
+        (Some(date), _) => parse_date(date),
+        (None, Some(default_start)) => Ok(default_start),
+        (None, None) => Err(missing("DTSTART")),
+    }
 }
 
 pub fn extract_end_date(s: &str) -> Result<Option<DateTime<Utc>>, RecurrenceInvalid> {
-    use regex::Regex;
-    let re = Regex::new(r"DTEND=(?P<date>[0-9]{4}-[0-9]{2}-[0-9]{2}T[0-9]{2}:[0-9]{2}:[0-9]{2}Z)")
-        .unwrap();
-    let caps = match re.captures(s).ok_or(RecurrenceInvalid {
-        message: "No DTEND found".to_string(),
-    }) {
-        Ok(caps) => caps,
-        Err(_) => return Ok(None),
-    };
-    let date = caps.name("date").ok_or(RecurrenceInvalid {
-        message: "No date found".to_string(),
-    })?;
-    let date = DateTime::<Utc>::from_str(date.as_str()).map_err(|e| RecurrenceInvalid {
-        message: format!("Invalid date: {e}"),
-    })?;
-    Ok(Some(date))
+    match find_value(s, "DTEND") {
+        Some(date) => Ok(Some(parse_date(date)?)),
+        None => Ok(None),
+    }
+}
+
+/// Extracts the `COUNT` of the rule: the number of events, counting the excluded dates.
+pub fn extract_count(s: &str) -> Result<Option<u32>, RecurrenceInvalid> {
+    match find_value(s, "COUNT") {
+        Some(value) => Ok(Some(parse_count(value)?)),
+        None => Ok(None),
+    }
+}
+
+/// Extracts the comma-separated dates of the `EXDATE` part of the rule.
+pub fn extract_exdates(s: &str) -> Result<Vec<DateTime<Utc>>, RecurrenceInvalid> {
+    match find_value(s, "EXDATE") {
+        Some(value) => value.split(',').map(parse_date).collect(),
+        None => Ok(Vec::new()),
+    }
+}
+
+/// Extracts the `X-NOTBEFORE` date of the rule, before which the events are skipped.
+pub fn extract_not_before(s: &str) -> Result<Option<DateTime<Utc>>, RecurrenceInvalid> {
+    match find_value(s, "X-NOTBEFORE") {
+        Some(date) => Ok(Some(parse_date(date)?)),
+        None => Ok(None),
+    }
+}
+
+/// Parses the `COUNT` of a rule, which must be a number without a sign.
+pub(crate) fn parse_count(value: &str) -> Result<u32, RecurrenceInvalid> {
+    value
+        .parse()
+        .ok()
+        .filter(|_| value.bytes().all(|b| b.is_ascii_digit()))
+        .ok_or_else(|| {
+            RecurrenceInvalid::new(format!("Invalid count: {value}")).with_kind(invalid(value))
+        })
+}
+
+/// Verifies if the DTSTART value is a date without time, which defines an all-day recurrence.
+///
+/// The `VALUE=DATE:` prefix of previous versions is still accepted.
+pub fn is_all_day(s: &str) -> bool {
+    find_value(s, "DTSTART").is_some_and(|date| {
+        date.starts_with("VALUE=DATE:")
+            || (date.len() == 8 && parse_compact_date(date).is_some())
+            || parse_plain_date(date).is_some()
+    })
 }
 
+/// Parses dates in the RFC 3339 format (`2023-01-01T00:00:00Z`) or in the compact iCalendar
+/// format (`20230101T000000Z`), optionally preceded by a `VALUE=DATE:` or `VALUE=DATE-TIME:` parameter.
+///
+/// Date-only values (`2023-01-01` or `20230101`) are all-day dates starting at midnight.
+fn parse_date(date: &str) -> Result<DateTime<Utc>, RecurrenceInvalid> {
+    let value = date
+        .strip_prefix("VALUE=DATE:")
+        .or_else(|| date.strip_prefix("VALUE=DATE-TIME:"))
+        .unwrap_or(date);
+    if value.is_empty() {
+        return Err(
+            RecurrenceInvalid::new("No date found".to_string()).with_kind(
+                RecurrenceErrorKind::Missing {
+                    name: "date".to_string(),
+                },
+            ),
+        );
+    }
+    if let Some(date) = parse_compact_date(value).or_else(|| parse_plain_date(value)) {
+        return Ok(date);
+    }
+    DateTime::<Utc>::from_str(value)
+        .map_err(|e| RecurrenceInvalid::new(format!("Invalid date: {e}")).with_kind(invalid(value)))
+}
+
+/// Returns the error of a missing part of the rule, like `DTSTART`.
+pub(crate) fn missing(name: &str) -> RecurrenceInvalid {
+    RecurrenceInvalid::new(format!("No {name} found")).with_kind(RecurrenceErrorKind::Missing {
+        name: name.to_string(),
+    })
+}
+
+/// Returns the kind of error of a value that cannot be parsed.
+pub(crate) fn invalid(value: &str) -> RecurrenceErrorKind {
+    RecurrenceErrorKind::InvalidValue {
+        value: value.to_string(),
+    }
+}
+
+/// Parses dates in the compact iCalendar format, either `20230101T000000Z` or the
+/// date-only `20230101`, which is interpreted as midnight UTC.
+pub(crate) fn parse_compact_date(value: &str) -> Option<DateTime<Utc>> {
+    if let Ok(date) = NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%SZ") {
+        return Some(Utc.from_utc_datetime(&date));
+    }
+    if value.len() != 8 {
+        return None;
+    }
+    NaiveDate::parse_from_str(value, "%Y%m%d")
+        .ok()
+        .map(|date| Utc.from_utc_datetime(&date.and_time(NaiveTime::MIN)))
+}
+
+/// Parses the date-only values written for all-day recurrences, like `2023-01-01`,
+/// which are interpreted as midnight UTC.
+fn parse_plain_date(value: &str) -> Option<DateTime<Utc>> {
+    if value.len() != 10 {
+        return None;
+    }
+    NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .ok()
+        .map(|date| Utc.from_utc_datetime(&date.and_time(NaiveTime::MIN)))
+}
+
+/// Extracts the duration of the events, defaulting to 0 when it is missing.
+/// Returns an error when the duration is not a valid ISO 8601 duration.
 pub fn extract_duration(s: &str) -> Result<Duration, RecurrenceInvalid> {
-    let seconds = extract_seconds_duration(s).unwrap_or(Duration::seconds(0));
-    let minutes = extract_minutes_duration(s).unwrap_or(Duration::minutes(0));
-    let hours = extract_hours_duration(s).unwrap_or(Duration::hours(0));
-    let days = extract_days_duration(s).unwrap_or(Duration::days(0));
-    let weeks = extract_weeks_duration(s).unwrap_or(Duration::weeks(0));
-    let duration = seconds + minutes + hours + days + weeks;
-    Ok(duration)
-}
-
-pub fn extract_seconds_duration(s: &str) -> Result<Duration, RecurrenceInvalid> {
-    use regex::Regex;
-    let re = Regex::new(r"DURATION=PT(?P<seconds>[0-9]+)S").unwrap();
-    let caps = re.captures(s).ok_or(RecurrenceInvalid {
-        message: "No DURATION found".to_string(),
-    })?;
-    let seconds = parse_duration_pair(&caps, "seconds");
-    let seconds = seconds.parse::<i64>().map_err(|e| RecurrenceInvalid {
-        message: format!("Invalid seconds: {e}"),
-    })?;
-    let duration = Duration::seconds(seconds);
-    Ok(duration)
-}
-
-pub fn extract_minutes_duration(s: &str) -> Result<Duration, RecurrenceInvalid> {
-    use regex::Regex;
-    let re = Regex::new(r"DURATION=PT(?P<minutes>[0-9]+)M").unwrap();
-    let caps = re.captures(s).ok_or(RecurrenceInvalid {
-        message: "No DURATION found".to_string(),
-    })?;
-    let minutes = parse_duration_pair(&caps, "minutes");
-    let minutes = minutes.parse::<i64>().map_err(|e| RecurrenceInvalid {
-        message: format!("Invalid minutes: {e}"),
-    })?;
-    let duration = Duration::minutes(minutes);
-    Ok(duration)
-}
-
-pub fn extract_hours_duration(s: &str) -> Result<Duration, RecurrenceInvalid> {
-    use regex::Regex;
-    let re = Regex::new(r"DURATION=PT(?P<hours>[0-9]+)H").unwrap();
-    let caps = re.captures(s).ok_or(RecurrenceInvalid {
-        message: "No DURATION found".to_string(),
-    })?;
-    let hours = parse_duration_pair(&caps, "hours");
-    let hours = hours.parse::<i64>().map_err(|e| RecurrenceInvalid {
-        message: format!("Invalid hours: {e}"),
-    })?;
-    let duration = Duration::hours(hours);
-    Ok(duration)
-}
-
-pub fn extract_days_duration(s: &str) -> Result<Duration, RecurrenceInvalid> {
-    use regex::Regex;
-    let re = Regex::new(r"DURATION=P(?P<days>[0-9]+)D").unwrap();
-    let caps = re.captures(s).ok_or(RecurrenceInvalid {
-        message: "No DURATION found".to_string(),
-    })?;
-    let days = parse_duration_pair(&caps, "days");
-    let days = days.parse::<i64>().map_err(|e| RecurrenceInvalid {
-        message: format!("Invalid days: {e}"),
-    })?;
-    let duration = Duration::days(days);
-    Ok(duration)
-}
-
-pub fn extract_weeks_duration(s: &str) -> Result<Duration, RecurrenceInvalid> {
-    use regex::Regex;
-    let re = Regex::new(r"DURATION=P(?P<weeks>[0-9]+)W").unwrap();
-    let caps = re.captures(s).ok_or(RecurrenceInvalid {
-        message: "No DURATION found".to_string(),
-    })?;
-    let weeks = parse_duration_pair(&caps, "weeks");
-    let weeks = weeks.parse::<i64>().map_err(|e| RecurrenceInvalid {
-        message: format!("Invalid weeks: {e}"),
-    })?;
-    let duration = Duration::weeks(weeks);
-    Ok(duration)
-}
-
-fn parse_duration_pair<'a>(caps: &'a regex::Captures, key: &'a str) -> &'a str {
-    if let Some(d) = caps.name(key) {
-        d.as_str()
+    match find_value(s, "DURATION") {
+        Some(value) => parse_duration(value).ok_or_else(|| {
+            RecurrenceInvalid::new(format!("Invalid duration: {value}")).with_kind(invalid(value))
+        }),
+        None => Ok(Duration::seconds(0)),
+    }
+}
+
+/// Parses durations in the `P[nW][nD][T[nH][nM][nS]]` format.
+pub(crate) fn parse_duration(value: &str) -> Option<Duration> {
+    let value = value.strip_prefix('P')?;
+    let (date, time) = match value.split_once('T') {
+        Some((date, time)) if !time.is_empty() => (date, Some(time)),
+        Some(_) => return None,
+        None => (value, None),
+    };
+    let mut duration =
+        parse_duration_units(date, &[('W', Duration::weeks(1)), ('D', Duration::days(1))])?;
+    if let Some(time) = time {
+        let time = parse_duration_units(
+            time,
+            &[
+                ('H', Duration::hours(1)),
+                ('M', Duration::minutes(1)),
+                ('S', Duration::seconds(1)),
+            ],
+        )?;
+        duration = duration.checked_add(&time)?;
+    }
+    if date.is_empty() && time.is_none() {
+        return None;
+    }
+    Some(duration)
+}
+
+/// Formats durations in the ISO 8601 `P[nW][nD][T[nH][nM][nS]]` format understood by
+/// `parse_duration`, using the week form when the duration is a whole number of weeks.
+///
+/// Fractions of seconds are written as decimals of the seconds, like `PT1.5S`.
+pub(crate) fn format_duration(duration: &Duration) -> String {
+    let sign = if *duration < Duration::zero() {
+        "-"
     } else {
         ""
+    };
+    let seconds = duration.num_seconds().unsigned_abs();
+    let nanos = duration.subsec_nanos().unsigned_abs();
+    if seconds == 0 && nanos == 0 {
+        return "PT0S".to_string();
+    }
+    let week = 7 * 24 * 3600;
+    if nanos == 0 && seconds.is_multiple_of(week) {
+        return format!("{sign}P{}W", seconds / week);
+    }
+
+    let (days, hours) = (seconds / 86400, seconds % 86400 / 3600);
+    let (minutes, seconds) = (seconds % 3600 / 60, seconds % 60);
+    let mut res = format!("{sign}P");
+    if days > 0 {
+        res.push_str(&format!("{days}D"));
+    }
+    if hours > 0 || minutes > 0 || seconds > 0 || nanos > 0 {
+        res.push('T');
+    }
+    for (amount, unit) in [(hours, 'H'), (minutes, 'M')] {
+        if amount > 0 {
+            res.push_str(&format!("{amount}{unit}"));
+        }
     }
+    if nanos > 0 {
+        let fraction = format!("{nanos:09}");
+        res.push_str(&format!("{seconds}.{}S", fraction.trim_end_matches('0')));
+    } else if seconds > 0 {
+        res.push_str(&format!("{seconds}S"));
+    }
+    res
+}
+
+/// Sums the `<number><unit>` pairs of the value, with the units in the given order.
+///
+/// The seconds can have up to 9 decimals, like `1.5S`.
+fn parse_duration_units(value: &str, units: &[(char, Duration)]) -> Option<Duration> {
+    let mut duration = Duration::seconds(0);
+    let mut rest = value;
+    let mut units = units.iter();
+    while !rest.is_empty() {
+        let end = rest.find(|c: char| !c.is_ascii_digit())?;
+        let amount = rest[..end].parse::<i32>().ok()?;
+        rest = &rest[end..];
+        let mut nanos = None;
+        if let Some(fraction) = rest.strip_prefix('.') {
+            let digits = fraction.find(|c: char| !c.is_ascii_digit())?;
+            if digits == 0 || digits > 9 {
+                return None;
+            }
+            nanos = Some(fraction[..digits].parse::<i64>().ok()? * 10i64.pow(9 - digits as u32));
+            rest = &fraction[digits..];
+        }
+        let unit = rest.chars().next()?;
+        let (_, size) = units.find(|(u, _)| *u == unit)?;
+        duration = duration.checked_add(&(*size * amount))?;
+        if let Some(nanos) = nanos {
+            if unit != 'S' {
+                return None;
+            }
+            duration = duration.checked_add(&Duration::nanoseconds(nanos))?;
+        }
+        rest = &rest[1..];
+    }
+    Some(duration)
 }
 
 #[cfg(test)]
 mod test_helpers {
-    use crate::recurrences::serializers::helpers::{extract_duration, extract_start_date};
+    use crate::recurrences::serializers::helpers::{
+        extract_duration, extract_end_date, extract_start_date, format_duration, is_all_day,
+        parse_duration,
+    };
     use chrono::{DateTime, Duration, Utc};
     use std::str::FromStr;
 
@@ -136,53 +279,117 @@ mod test_helpers {
     fn test_extract_start() {
         let value = "FREQ=SECONDLY;INTERVAL=1;COUNT=10;DTSTART=2020-01-01T00:00:00Z";
         let expected = DateTime::<Utc>::from_str("2020-01-01T00:00:00Z").unwrap();
-        let actual = extract_start_date(&value).unwrap();
+        let actual = extract_start_date(value, None).unwrap();
         assert_eq!(actual, expected);
     }
 
     #[test]
     fn test_extract_start_no_dtstart() {
         let value = "FREQ=SECONDLY;INTERVAL=1;COUNT=10";
-        let result = extract_start_date(&value);
+        let result = extract_start_date(value, None);
         assert!(result.is_err());
     }
 
     #[test]
     fn test_extract_start_no_date() {
         let value = "FREQ=SECONDLY;INTERVAL=1;COUNT=10;DTSTART=";
-        let result = extract_start_date(&value);
+        let result = extract_start_date(value, None);
         assert!(result.is_err());
     }
 
     #[test]
     fn test_extract_start_invalid_date() {
         let value = "FREQ=SECONDLY;INTERVAL=1;COUNT=10;DTSTART=2020-01-01T00:00:00";
-        let result = extract_start_date(&value);
+        let result = extract_start_date(value, None);
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_extract_start_compact_date() {
+        let value = "FREQ=DAILY;INTERVAL=1;DTSTART=20230101T093000Z";
+        let expected = DateTime::<Utc>::from_str("2023-01-01T09:30:00Z").unwrap();
+        assert_eq!(extract_start_date(value, None).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_extract_start_date_only() {
+        let expected = DateTime::<Utc>::from_str("2023-01-01T00:00:00Z").unwrap();
+        let value = "FREQ=DAILY;INTERVAL=1;DTSTART=20230101";
+        assert_eq!(extract_start_date(value, None).unwrap(), expected);
+        let value = "FREQ=DAILY;INTERVAL=1;DTSTART=VALUE=DATE:20230101";
+        assert_eq!(extract_start_date(value, None).unwrap(), expected);
+        let value = "FREQ=DAILY;INTERVAL=1;DTSTART=2023-01-01";
+        assert_eq!(extract_start_date(value, None).unwrap(), expected);
+        let value = "FREQ=DAILY;INTERVAL=1;DTSTART=2023-1-01";
+        assert!(extract_start_date(value, None).is_err());
+    }
+
+    #[test]
+    fn test_extract_end_compact_date() {
+        let value = "FREQ=DAILY;INTERVAL=1;DTSTART=20230101;DTEND=20230201T120000Z";
+        let expected = DateTime::<Utc>::from_str("2023-02-01T12:00:00Z").unwrap();
+        assert_eq!(extract_end_date(value).unwrap(), Some(expected));
+        let value = "FREQ=DAILY;INTERVAL=1;DTSTART=20230101;DTEND=2023010";
+        assert!(extract_end_date(value).is_err());
+    }
+
+    #[test]
+    fn test_is_all_day() {
+        assert!(is_all_day("FREQ=DAILY;INTERVAL=1;DTSTART=20230101"));
+        assert!(is_all_day("FREQ=DAILY;INTERVAL=1;DTSTART=2023-01-01"));
+        assert!(is_all_day(
+            "FREQ=DAILY;INTERVAL=1;DTSTART=VALUE=DATE:20230101"
+        ));
+        assert!(!is_all_day(
+            "FREQ=DAILY;INTERVAL=1;DTSTART=20230101T000000Z"
+        ));
+        assert!(!is_all_day(
+            "FREQ=DAILY;INTERVAL=1;DTSTART=2023-01-01T00:00:00Z"
+        ));
+    }
+
     #[test]
     fn test_extract_duration_no_duration() {
         let value = "FREQ=SECONDLY;INTERVAL=1;COUNT=10;DTSTART=2020-01-01T00:00:00Z";
-        let result = extract_duration(&value);
+        let result = extract_duration(value);
         assert!(result.is_ok());
         let duration = result.unwrap();
         assert_eq!(duration, Duration::seconds(0));
     }
 
     #[test]
-    fn test_extract_duration_invalid_returns_0() {
+    fn test_extract_duration_invalid() {
         let value = "FREQ=SECONDLY;INTERVAL=1;COUNT=10;DTSTART=2020-01-01T00:00:00Z;DURATION=PT1";
-        let result = extract_duration(&value);
-        assert!(result.is_ok());
-        let duration = result.unwrap();
-        assert_eq!(duration, Duration::seconds(0));
+        assert!(extract_duration(value).is_err());
+        for duration in [
+            "",
+            "P",
+            "PT",
+            "P1DT",
+            "1H",
+            "PT1H30",
+            "P1H",
+            "PT1M1H",
+            "P1D1W",
+            "PT-1H",
+            "PT1.5H",
+            "PT1.S",
+            "PT.5S",
+            "PT0.1234567891S",
+        ] {
+            let value =
+                format!("FREQ=DAILY;INTERVAL=1;DTSTART=2020-01-01T00:00:00Z;DURATION={duration}");
+            assert!(
+                extract_duration(&value).is_err(),
+                "{duration} should not be parsed"
+            );
+        }
     }
 
     #[test]
     fn test_extract_seconds_duration() {
         let value = "FREQ=SECONDLY;INTERVAL=1;COUNT=10;DTSTART=2020-01-01T00:00:00Z;DURATION=PT1S";
-        let actual = extract_duration(&value).unwrap();
+        let actual = extract_duration(value).unwrap();
         let expected = Duration::seconds(1);
         assert_eq!(actual, expected);
     }
@@ -190,7 +397,7 @@ mod test_helpers {
     #[test]
     fn test_extract_minutes_duration() {
         let value = "FREQ=SECONDLY;INTERVAL=1;COUNT=10;DTSTART=2020-01-01T00:00:00Z;DURATION=PT1M";
-        let actual = extract_duration(&value).unwrap();
+        let actual = extract_duration(value).unwrap();
         let expected = Duration::minutes(1);
         assert_eq!(actual, expected);
     }
@@ -198,7 +405,7 @@ mod test_helpers {
     #[test]
     fn test_extract_hours_duration() {
         let value = "FREQ=SECONDLY;INTERVAL=1;COUNT=10;DTSTART=2020-01-01T00:00:00Z;DURATION=PT1H";
-        let actual = extract_duration(&value).unwrap();
+        let actual = extract_duration(value).unwrap();
         let expected = Duration::hours(1);
         assert_eq!(actual, expected);
     }
@@ -206,16 +413,56 @@ mod test_helpers {
     #[test]
     fn test_extract_days_duration() {
         let value = "FREQ=SECONDLY;INTERVAL=1;COUNT=10;DTSTART=2020-01-01T00:00:00Z;DURATION=P1D";
-        let actual = extract_duration(&value).unwrap();
+        let actual = extract_duration(value).unwrap();
         let expected = Duration::days(1);
         assert_eq!(actual, expected);
     }
 
+    #[test]
+    fn test_extract_combined_duration() {
+        let value = "FREQ=DAILY;INTERVAL=1;DTSTART=2020-01-01T00:00:00Z;DURATION=P1DT2H30M";
+        let actual = extract_duration(value).unwrap();
+        let expected = Duration::days(1) + Duration::hours(2) + Duration::minutes(30);
+        assert_eq!(actual, expected);
+    }
+
     #[test]
     fn test_extract_weeks_duration() {
         let value = "FREQ=SECONDLY;INTERVAL=1;COUNT=10;DTSTART=2020-01-01T00:00:00Z;DURATION=P1W";
-        let actual = extract_duration(&value).unwrap();
+        let actual = extract_duration(value).unwrap();
         let expected = Duration::weeks(1);
         assert_eq!(actual, expected);
     }
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(&Duration::seconds(0)), "PT0S");
+        assert_eq!(format_duration(&Duration::hours(1)), "PT1H");
+        assert_eq!(format_duration(&Duration::minutes(90)), "PT1H30M");
+        assert_eq!(format_duration(&Duration::hours(36)), "P1DT12H");
+        assert_eq!(format_duration(&Duration::days(1)), "P1D");
+        assert_eq!(format_duration(&Duration::weeks(2)), "P2W");
+        assert_eq!(format_duration(&Duration::seconds(93_784)), "P1DT2H3M4S");
+        assert_eq!(format_duration(&Duration::milliseconds(1500)), "PT1.5S");
+        assert_eq!(
+            format_duration(&Duration::milliseconds(60_001)),
+            "PT1M0.001S"
+        );
+        assert_eq!(format_duration(&Duration::nanoseconds(1)), "PT0.000000001S");
+    }
+
+    #[test]
+    fn test_format_duration_round_trip() {
+        for duration in [
+            Duration::seconds(45),
+            Duration::minutes(150),
+            Duration::days(8),
+            Duration::weeks(1),
+            Duration::seconds(1_000_000),
+            Duration::weeks(1) + Duration::milliseconds(250),
+            Duration::nanoseconds(123_456_789),
+        ] {
+            assert_eq!(parse_duration(&format_duration(&duration)), Some(duration));
+        }
+    }
 }
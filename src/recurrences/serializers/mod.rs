@@ -1,46 +1,227 @@
 mod helpers;
 
+pub(crate) use helpers::{
+    extract_unknown_params, format_duration, invalid, missing, parse_compact_date, parse_count,
+    parse_duration,
+};
+
+use crate::frequencies::serializer::{validate_strict, Normalized, FREQUENCY_KEYS};
+use crate::recurrences::ics::{is_content_lines, unfold_lines};
 use crate::recurrences::serializers::helpers::{
-    extract_duration, extract_end_date, extract_start_date,
+    extract_count, extract_duration, extract_end_date, extract_exdates, extract_not_before,
+    extract_start_date, is_all_day,
 };
-use crate::recurrences::MAX_DATE;
-use crate::{Frequency, Recurrence, RecurrenceInvalid};
+use crate::recurrences::validations::validate_duration_with;
+use crate::trace;
+use crate::{Frequency, ParseOptions, Recurrence, RecurrenceInvalid};
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
 use chrono::{DateTime, Duration, Utc};
-use std::fmt::{Display, Formatter};
-use std::str::FromStr;
+use core::fmt::{Display, Formatter};
+use core::str::FromStr;
+
+/// Keys accepted when parsing a recurrence, on top of the frequency ones.
+const RECURRENCE_KEYS: [&str; 6] = [
+    "DTSTART",
+    "DTEND",
+    "DURATION",
+    "COUNT",
+    "EXDATE",
+    "X-NOTBEFORE",
+];
+
+impl Recurrence {
+    /// Parses a recurrence from a string using the given parse options.
+    ///
+    /// With lenient options this is the same as `Recurrence::from_str`.
+    /// Examples:
+    /// ```
+    /// use rrules::{ParseOptions, Recurrence};
+    ///
+    /// let value = "FREQ=DAILY;INTERVAL=1;DTSTART=2023-01-01T12:00:00Z;UNKNOWN=1";
+    /// assert!(Recurrence::from_str_with(value, ParseOptions::lenient()).is_ok());
+    /// assert!(Recurrence::from_str_with(value, ParseOptions::strict()).is_err());
+    /// ```
+    pub fn from_str_with(s: &str, options: ParseOptions) -> Result<Self, RecurrenceInvalid> {
+        if options.strict {
+            let allowed_keys = [&FREQUENCY_KEYS[..], &RECURRENCE_KEYS[..]].concat();
+            validate_strict(s, &allowed_keys)
+                .map_err(|e| RecurrenceInvalid::with_source(format!("{e}"), e))?;
+        }
+        Recurrence::parse(s, options)
+    }
+
+    /// Parses a recurrence from a string, starting at the default start date when the
+    /// string has no `DTSTART`.
+    ///
+    /// `Recurrence::from_str` keeps requiring a `DTSTART`, so the rules stored without one
+    /// are only accepted when the caller chooses their start, E.g. the current date.
+    /// Examples:
+    /// ```
+    /// use std::str::FromStr;
+    /// use chrono::{DateTime, Utc};
+    /// use rrules::Recurrence;
+    ///
+    /// let now = DateTime::<Utc>::from_str("2023-01-01T12:00:00Z").unwrap();
+    /// let recurrence = Recurrence::from_str_with_default_start("FREQ=DAILY;INTERVAL=1", now).unwrap();
+    /// assert_eq!(recurrence.start(), now);
+    /// assert!(Recurrence::from_str("FREQ=DAILY;INTERVAL=1").is_err());
+    /// ```
+    pub fn from_str_with_default_start(
+        s: &str,
+        default_start: DateTime<Utc>,
+    ) -> Result<Self, RecurrenceInvalid> {
+        Recurrence::from_str_with(s, ParseOptions::default().with_default_start(default_start))
+    }
+
+    /// Returns the rule of the recurrence in its canonical form, with the keys in a fixed
+    /// order, the values of the frequency and the excluded dates sorted and the unknown
+    /// parameters sorted by key.
+    ///
+    /// The same rules written in a different order have the same canonical string, which
+    /// recurrences are compared and hashed by.
+    /// Examples:
+    /// ```
+    /// use std::str::FromStr;
+    /// use rrules::Recurrence;
+    ///
+    /// let first = Recurrence::from_str("FREQ=WEEKLY;INTERVAL=1;BYDAY=MO,TU;DTSTART=2023-01-02T09:00:00Z").unwrap();
+    /// let second = Recurrence::from_str("DTSTART=2023-01-02T09:00:00Z;BYDAY=TU,MO;INTERVAL=1;FREQ=WEEKLY").unwrap();
+    /// assert_eq!(first.canonical_string(), second.canonical_string());
+    /// assert_eq!(first, second);
+    /// ```
+    pub fn canonical_string(&self) -> String {
+        let mut canonical = self.clone();
+        canonical.frequency = canonical.frequency.normalized();
+        canonical.content_lines = false;
+        canonical.unknown_params.sort();
+        canonical.exdates.sort();
+        canonical.exdates.dedup();
+        canonical.to_string()
+    }
+}
 
 impl FromStr for Recurrence {
     type Err = RecurrenceInvalid;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Recurrence::parse(s, ParseOptions::default())
+    }
+}
+
+impl Recurrence {
+    /// Parses a rule string or content lines, using the default start date of the options
+    /// when there is no `DTSTART`. The strict mode of the options is not checked.
+    pub(crate) fn parse(s: &str, options: ParseOptions) -> Result<Self, RecurrenceInvalid> {
+        let _span = trace::debug_span!("parse_recurrence", rule = s);
+        trace::debug_error(Recurrence::parse_rule(s, options), "Invalid recurrence")
+    }
+
+    fn parse_rule(s: &str, options: ParseOptions) -> Result<Self, RecurrenceInvalid> {
+        if is_content_lines(s) {
+            let mut recurrence = Recurrence::from_content_lines(&unfold_lines(s), options)?;
+            recurrence.content_lines = true;
+            return Ok(recurrence);
+        }
         let frequency = match Frequency::from_str(s) {
             Ok(f) => f,
             Err(e) => {
-                return Err(RecurrenceInvalid {
-                    message: format!("Invalid frequency: {e}"),
-                })
+                return Err(RecurrenceInvalid::with_source(
+                    format!("Invalid frequency: {e}"),
+                    e,
+                ))
             }
         };
-        let start_date = extract_start_date(s)?;
-        let end_date = extract_end_date(s)?;
-        let duration = extract_duration(s)?;
-        Recurrence::new(frequency, start_date, end_date, Some(duration))
+        let known_keys = [&FREQUENCY_KEYS[..], &RECURRENCE_KEYS[..]].concat();
+        let normalized = Normalized::new(s, &known_keys);
+        let rule = normalized.value.as_str();
+        let start_date = extract_start_date(rule, options.default_start)?;
+        let end_date = extract_end_date(rule)?;
+        let duration = extract_duration(rule)?;
+        let count = extract_count(rule)?;
+        let exdates = extract_exdates(rule)?;
+        let not_before = extract_not_before(rule)?;
+        let unknown_params = extract_unknown_params(rule, &known_keys);
+        if !is_all_day(rule) {
+            let recurrence = Recurrence::new_with(
+                frequency,
+                start_date,
+                end_date,
+                Some(duration),
+                options.validation,
+            )?;
+            let mut recurrence = recurrence
+                .with_exdates(exdates)
+                .with_unknown_params(unknown_params);
+            recurrence.not_before = not_before;
+            return Ok(recurrence.with_rule_count(count));
+        }
+        let mut recurrence = Recurrence::all_day(
+            frequency,
+            start_date.date_naive(),
+            end_date.map(|date| date.date_naive()),
+        )?;
+        recurrence.validation = options.validation;
+        if duration > Duration::seconds(0) {
+            validate_duration_with(
+                &recurrence.frequency,
+                &recurrence.start,
+                &duration,
+                &recurrence.validation,
+            )?;
+            recurrence.duration = duration;
+        }
+        recurrence.not_before = not_before;
+        Ok(recurrence
+            .with_exdates(exdates)
+            .with_unknown_params(unknown_params)
+            .with_rule_count(count))
     }
 }
 
 impl Display for Recurrence {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        if self.content_lines {
+            return write!(f, "{}", self.to_content_lines());
+        }
+        let date_format = if self.all_day {
+            "%Y-%m-%d"
+        } else {
+            "%Y-%m-%dT%H:%M:%S%.fZ"
+        };
         let mut res = format!(
             "{};DTSTART={}",
             self.frequency,
-            self.start.format("%Y-%m-%dT%H:%M:%SZ"),
+            self.start.format(date_format),
         );
-        let max_date = DateTime::<Utc>::from_str(MAX_DATE).unwrap();
-        if self.end != max_date {
-            res = format!("{};DTEND={}", res, self.end.format("%Y-%m-%dT%H:%M:%SZ"));
+        if let Some(end) = self.end() {
+            res = format!("{};DTEND={}", res, end.format(date_format));
+        }
+        let default_duration = if self.all_day {
+            Duration::days(1)
+        } else {
+            Duration::seconds(0)
+        };
+        if self.duration != default_duration && self.duration > Duration::seconds(0) {
+            res = format!("{};DURATION={}", res, format_duration(&self.duration));
         }
-        if self.duration > Duration::seconds(0) {
-            res = format!("{};DURATION={}", res, self.duration);
+        if let Some(count) = self.count {
+            res = format!("{res};COUNT={count}");
+        }
+        if !self.exdates.is_empty() {
+            let exdates: Vec<String> = self
+                .exdates
+                .iter()
+                .map(|date| date.format(date_format).to_string())
+                .collect();
+            res = format!("{};EXDATE={}", res, exdates.join(","));
+        }
+        if let Some(not_before) = self.not_before {
+            res = format!("{};X-NOTBEFORE={}", res, not_before.format(date_format));
+        }
+        for (key, value) in &self.unknown_params {
+            res = format!("{res};{key}={value}");
         }
         write!(f, "{res}")
     }
@@ -48,7 +229,7 @@ impl Display for Recurrence {
 
 #[cfg(test)]
 mod deserialize_tests {
-    use crate::{Frequency, Recurrence};
+    use crate::{ParseOptions, Recurrence};
     use chrono::{DateTime, Utc};
     use std::str::FromStr;
 
@@ -56,7 +237,10 @@ mod deserialize_tests {
     fn secondly_recurrence_from_str() {
         let value = "FREQ=SECONDLY;INTERVAL=1;DTSTART=2020-01-01T00:00:00Z;DURATION=PT1S";
         let recurrence = Recurrence::from_str(value).unwrap();
-        let events = recurrence.take(2).collect::<Vec<DateTime<Utc>>>();
+        let events = recurrence
+            .into_iter()
+            .take(2)
+            .collect::<Vec<DateTime<Utc>>>();
         assert_eq!(
             events,
             vec![
@@ -70,7 +254,10 @@ mod deserialize_tests {
     fn minutely_recurrence_from_str() {
         let value = "FREQ=MINUTELY;INTERVAL=1;DTSTART=2020-01-01T00:00:00Z;DURATION=PT1M";
         let recurrence = Recurrence::from_str(value).unwrap();
-        let events = recurrence.take(2).collect::<Vec<DateTime<Utc>>>();
+        let events = recurrence
+            .into_iter()
+            .take(2)
+            .collect::<Vec<DateTime<Utc>>>();
         assert_eq!(
             events,
             vec![
@@ -84,7 +271,10 @@ mod deserialize_tests {
     fn hourly_recurrence_from_str() {
         let value = "FREQ=HOURLY;INTERVAL=1;DTSTART=2020-01-01T00:00:00Z;DURATION=PT1H";
         let recurrence = Recurrence::from_str(value).unwrap();
-        let events = recurrence.take(2).collect::<Vec<DateTime<Utc>>>();
+        let events = recurrence
+            .into_iter()
+            .take(2)
+            .collect::<Vec<DateTime<Utc>>>();
         assert_eq!(
             events,
             vec![
@@ -94,11 +284,32 @@ mod deserialize_tests {
         );
     }
 
+    #[test]
+    fn hourly_by_day_and_time_from_str() {
+        let value = "FREQ=HOURLY;INTERVAL=1;DTSTART=2023-01-01T00:00:00Z;BYDAY=MO,TU;BYHOUR=9,17";
+        let recurrence = Recurrence::from_str(value).unwrap();
+        let events = recurrence
+            .into_iter()
+            .take(3)
+            .collect::<Vec<DateTime<Utc>>>();
+        assert_eq!(
+            events,
+            vec![
+                DateTime::<Utc>::from_str("2023-01-02T09:00:00Z").unwrap(),
+                DateTime::<Utc>::from_str("2023-01-02T17:00:00Z").unwrap(),
+                DateTime::<Utc>::from_str("2023-01-03T09:00:00Z").unwrap(),
+            ]
+        );
+    }
+
     #[test]
     fn daily_recurrence_from_str() {
-        let value = "FREQ=DAILY;INTERVAL=1;DTSTART=2020-01-01T00:00:00Z;DURATION=PT1D";
+        let value = "FREQ=DAILY;INTERVAL=1;DTSTART=2020-01-01T00:00:00Z;DURATION=P1D";
         let recurrence = Recurrence::from_str(value).unwrap();
-        let events = recurrence.take(2).collect::<Vec<DateTime<Utc>>>();
+        let events = recurrence
+            .into_iter()
+            .take(2)
+            .collect::<Vec<DateTime<Utc>>>();
         assert_eq!(
             events,
             vec![
@@ -110,9 +321,12 @@ mod deserialize_tests {
 
     #[test]
     fn weekly_recurrence_from_str() {
-        let value = "FREQ=WEEKLY;INTERVAL=1;DTSTART=2020-01-01T00:00:00Z;DURATION=PT1W";
+        let value = "FREQ=WEEKLY;INTERVAL=1;DTSTART=2020-01-01T00:00:00Z;DURATION=P1W";
         let recurrence = Recurrence::from_str(value).unwrap();
-        let events = recurrence.take(2).collect::<Vec<DateTime<Utc>>>();
+        let events = recurrence
+            .into_iter()
+            .take(2)
+            .collect::<Vec<DateTime<Utc>>>();
         assert_eq!(
             events,
             vec![
@@ -126,7 +340,10 @@ mod deserialize_tests {
     fn monthly_recurrence_from_str() {
         let value = "FREQ=MONTHLY;INTERVAL=1;DTSTART=2020-01-01T00:00:00Z";
         let recurrence = Recurrence::from_str(value).unwrap();
-        let events = recurrence.take(2).collect::<Vec<DateTime<Utc>>>();
+        let events = recurrence
+            .into_iter()
+            .take(2)
+            .collect::<Vec<DateTime<Utc>>>();
         assert_eq!(
             events,
             vec![
@@ -140,7 +357,10 @@ mod deserialize_tests {
     fn yearly_recurrence_from_str() {
         let value = "FREQ=YEARLY;INTERVAL=1;DTSTART=2020-01-01T00:00:00Z";
         let recurrence = Recurrence::from_str(value).unwrap();
-        let events = recurrence.take(2).collect::<Vec<DateTime<Utc>>>();
+        let events = recurrence
+            .into_iter()
+            .take(2)
+            .collect::<Vec<DateTime<Utc>>>();
         assert_eq!(
             events,
             vec![
@@ -154,7 +374,10 @@ mod deserialize_tests {
     fn daily_by_time_from_str() {
         let value = "FREQ=DAILY;INTERVAL=1;DTSTART=2020-01-01T00:00:00Z;BYTIME=09:00,10:00";
         let recurrence = Recurrence::from_str(value).unwrap();
-        let events = recurrence.take(3).collect::<Vec<DateTime<Utc>>>();
+        let events = recurrence
+            .into_iter()
+            .take(3)
+            .collect::<Vec<DateTime<Utc>>>();
         assert_eq!(
             events,
             vec![
@@ -169,7 +392,10 @@ mod deserialize_tests {
     fn weekly_by_day() {
         let value = "FREQ=WEEKLY;INTERVAL=1;DTSTART=2023-01-01T00:00:00Z;BYDAY=MO,TU";
         let recurrence = Recurrence::from_str(value).unwrap();
-        let events = recurrence.take(3).collect::<Vec<DateTime<Utc>>>();
+        let events = recurrence
+            .into_iter()
+            .take(3)
+            .collect::<Vec<DateTime<Utc>>>();
         assert_eq!(
             events,
             vec![
@@ -184,7 +410,10 @@ mod deserialize_tests {
     fn monthly_by_month_day() {
         let value = "FREQ=MONTHLY;INTERVAL=1;DTSTART=2023-01-01T00:00:00Z;BYMONTHDAY=1,2";
         let recurrence = Recurrence::from_str(value).unwrap();
-        let events = recurrence.take(3).collect::<Vec<DateTime<Utc>>>();
+        let events = recurrence
+            .into_iter()
+            .take(3)
+            .collect::<Vec<DateTime<Utc>>>();
         assert_eq!(
             events,
             vec![
@@ -199,7 +428,10 @@ mod deserialize_tests {
     fn monthly_by_nth_weekday() {
         let value = "FREQ=MONTHLY;INTERVAL=1;DTSTART=2023-01-01T00:00:00Z;BYDAY=1MO";
         let recurrence = Recurrence::from_str(value).unwrap();
-        let events = recurrence.take(3).collect::<Vec<DateTime<Utc>>>();
+        let events = recurrence
+            .into_iter()
+            .take(3)
+            .collect::<Vec<DateTime<Utc>>>();
         assert_eq!(
             events,
             vec![
@@ -215,7 +447,10 @@ mod deserialize_tests {
         let value =
             "FREQ=YEARLY;INTERVAL=1;DTSTART=2023-01-01T00:00:00Z;BYMONTH=1;BY;BYMONTHDAY=15";
         let recurrence = Recurrence::from_str(value).unwrap();
-        let events = recurrence.take(2).collect::<Vec<DateTime<Utc>>>();
+        let events = recurrence
+            .into_iter()
+            .take(2)
+            .collect::<Vec<DateTime<Utc>>>();
         assert_eq!(
             events,
             vec![
@@ -225,11 +460,92 @@ mod deserialize_tests {
         );
     }
 
+    #[test]
+    fn content_lines_from_str() {
+        let value = "DTSTART:20230101T090000Z\nRRULE:FREQ=DAILY;INTERVAL=1;UNTIL=20230103T090000Z";
+        let recurrence = Recurrence::from_str(value).unwrap();
+        let events = recurrence.into_iter().collect::<Vec<DateTime<Utc>>>();
+        assert_eq!(
+            events,
+            vec![
+                DateTime::<Utc>::from_str("2023-01-01T09:00:00Z").unwrap(),
+                DateTime::<Utc>::from_str("2023-01-02T09:00:00Z").unwrap(),
+                DateTime::<Utc>::from_str("2023-01-03T09:00:00Z").unwrap(),
+            ]
+        );
+    }
+
+    #[test]
+    fn content_lines_round_trip() {
+        let value = "DTSTART;VALUE=DATE:20230102\nRRULE:FREQ=WEEKLY;INTERVAL=1;BYDAY=MO;COUNT=3";
+        let recurrence = Recurrence::from_str(value).unwrap();
+        assert_eq!(recurrence.to_string(), value);
+        assert_eq!(
+            Recurrence::from_str(&recurrence.to_string()).unwrap(),
+            recurrence
+        );
+
+        let value =
+            "RRULE:FREQ=DAILY;INTERVAL=1;UNTIL=20230103T090000Z\r\nDTSTART:20230101T090000Z\r\n";
+        assert_eq!(
+            Recurrence::from_str(value).unwrap().to_string(),
+            "DTSTART:20230101T090000Z\nRRULE:FREQ=DAILY;INTERVAL=1;UNTIL=20230103T090000Z"
+        );
+
+        // The canonical string keeps the rule string form
+        assert_eq!(
+            Recurrence::from_str(value).unwrap().canonical_string(),
+            "FREQ=DAILY;INTERVAL=1;DTSTART=2023-01-01T09:00:00Z;DTEND=2023-01-03T09:00:00Z"
+        );
+    }
+
+    #[test]
+    fn content_lines_with_crlf_and_date_only_start() {
+        let value = "RRULE:FREQ=WEEKLY;INTERVAL=1;BYDAY=MO\r\nDTSTART;VALUE=DATE:20230102\r\n";
+        let recurrence = Recurrence::from_str(value).unwrap();
+        let events = recurrence
+            .into_iter()
+            .take(2)
+            .collect::<Vec<DateTime<Utc>>>();
+        assert_eq!(
+            events,
+            vec![
+                DateTime::<Utc>::from_str("2023-01-02T00:00:00Z").unwrap(),
+                DateTime::<Utc>::from_str("2023-01-09T00:00:00Z").unwrap(),
+            ]
+        );
+    }
+
+    #[test]
+    fn rrule_prefix_from_str() {
+        let value = "RRULE:FREQ=DAILY;INTERVAL=2;DTSTART=2023-01-01T00:00:00Z";
+        let recurrence = Recurrence::from_str(value).unwrap();
+        let events = recurrence
+            .into_iter()
+            .take(2)
+            .collect::<Vec<DateTime<Utc>>>();
+        assert_eq!(
+            events,
+            vec![
+                DateTime::<Utc>::from_str("2023-01-01T00:00:00Z").unwrap(),
+                DateTime::<Utc>::from_str("2023-01-03T00:00:00Z").unwrap(),
+            ]
+        );
+    }
+
+    #[test]
+    fn content_lines_without_rule() {
+        assert!(Recurrence::from_str("DTSTART:20230101T090000Z").is_err());
+    }
+
     #[test]
     fn daily_with_dt_end() {
         let value = "FREQ=DAILY;INTERVAL=1;DTSTART=2023-01-01T00:00:00Z;DTEND=2023-01-03T00:00:00Z";
         let recurrence = Recurrence::from_str(value).unwrap();
-        let events = recurrence.take(5).collect::<Vec<DateTime<Utc>>>();
+        let events = recurrence
+            .into_iter()
+            .take(5)
+            .collect::<Vec<DateTime<Utc>>>();
         assert_eq!(
             events,
             vec![
@@ -246,6 +562,107 @@ mod deserialize_tests {
         let recurrence = Recurrence::from_str(value);
         assert!(recurrence.is_err());
     }
+
+    #[test]
+    fn lenient_parsing_accepts_lowercase_and_spaces() {
+        let value =
+            "freq=daily; interval=2; dtstart=2023-01-01t09:00:00z; duration=pt1h; x-name=Standup";
+        let recurrence = Recurrence::from_str(value).unwrap();
+        assert_eq!(
+            recurrence.to_string(),
+            "FREQ=DAILY;INTERVAL=2;DTSTART=2023-01-01T09:00:00Z;DURATION=PT1H;X-NAME=Standup"
+        );
+        let all_day = Recurrence::from_str("FREQ=YEARLY ;DTSTART=value=date:20230101").unwrap();
+        assert!(all_day.all_day);
+        assert!(Recurrence::from_str_with(value, ParseOptions::strict()).is_err());
+    }
+}
+
+#[cfg(test)]
+mod parse_options_tests {
+    use crate::{ParseOptions, Recurrence, ValidationOptions};
+    use chrono::{DateTime, Duration, Utc};
+    use std::str::FromStr;
+
+    #[test]
+    fn strict_accepts_valid_recurrence() {
+        let value = "FREQ=DAILY;INTERVAL=1;DTSTART=2023-01-01T00:00:00Z;DTEND=2023-01-03T00:00:00Z;DURATION=PT1H";
+        let recurrence = Recurrence::from_str_with(value, ParseOptions::strict()).unwrap();
+        assert_eq!(recurrence.into_iter().count(), 3);
+    }
+
+    #[test]
+    fn strict_rejects_malformed_recurrence() {
+        let value =
+            "FREQ=YEARLY;INTERVAL=1;DTSTART=2023-01-01T00:00:00Z;BYMONTH=1;BY;BYMONTHDAY=15";
+        assert!(Recurrence::from_str_with(value, ParseOptions::lenient()).is_ok());
+        let result = Recurrence::from_str_with(value, ParseOptions::strict());
+        assert_eq!(
+            result.unwrap_err().message,
+            "Invalid format: Expected KEY=VALUE, got \"BY\" at 62..64"
+        );
+    }
+
+    #[test]
+    fn strict_rejects_duplicated_start() {
+        let value =
+            "FREQ=DAILY;INTERVAL=1;DTSTART=2023-01-01T00:00:00Z;DTSTART=2023-02-01T00:00:00Z";
+        assert!(Recurrence::from_str_with(value, ParseOptions::strict()).is_err());
+    }
+
+    #[test]
+    fn default_start_is_used_without_dtstart() {
+        let now = DateTime::<Utc>::from_str("2023-01-01T12:00:00Z").unwrap();
+        let value = "FREQ=DAILY;INTERVAL=1;DURATION=PT1H";
+        let recurrence = Recurrence::from_str_with_default_start(value, now).unwrap();
+        assert_eq!(recurrence.start(), now);
+        assert_eq!(recurrence.duration(), Duration::hours(1));
+        assert_eq!(
+            recurrence.to_string(),
+            "FREQ=DAILY;INTERVAL=1;DTSTART=2023-01-01T12:00:00Z;DURATION=PT1H"
+        );
+
+        let options = ParseOptions::strict().with_default_start(now);
+        assert!(Recurrence::from_str_with(value, options).is_ok());
+        assert!(Recurrence::from_str_with(value, ParseOptions::strict()).is_err());
+        assert!(Recurrence::from_str(value).is_err());
+    }
+
+    #[test]
+    fn dtstart_takes_precedence_over_the_default_start() {
+        let now = DateTime::<Utc>::from_str("2023-01-01T12:00:00Z").unwrap();
+        let value = "FREQ=DAILY;INTERVAL=1;DTSTART=2023-02-01T09:00:00Z";
+        let recurrence = Recurrence::from_str_with_default_start(value, now).unwrap();
+        assert_eq!(
+            recurrence.start(),
+            DateTime::<Utc>::from_str("2023-02-01T09:00:00Z").unwrap()
+        );
+
+        let value = "RRULE:FREQ=WEEKLY;INTERVAL=1";
+        let recurrence = Recurrence::from_str_with_default_start(value, now).unwrap();
+        assert_eq!(recurrence.start(), now);
+        assert!(Recurrence::from_str_with_default_start("FREQ=DAILY;DTSTART=", now).is_err());
+    }
+
+    #[test]
+    fn validation_options_allow_overlapping_occurrences() {
+        let options = ParseOptions::strict()
+            .with_validation(ValidationOptions::default().allow_overlapping_occurrences());
+        let value = "FREQ=WEEKLY;INTERVAL=1;BYDAY=MO,WE;DTSTART=2023-01-02T09:00:00Z;DURATION=P3D";
+        assert!(Recurrence::from_str(value).is_err());
+        let recurrence = Recurrence::from_str_with(value, options).unwrap();
+        assert_eq!(recurrence.duration(), Duration::days(3));
+        assert!(recurrence.validation.allow_overlaps);
+
+        let value = "RRULE:FREQ=DAILY;INTERVAL=1;BYTIME=09:00,10:00\nDTSTART:20230101T000000Z\nDURATION:PT2H";
+        assert!(Recurrence::from_str(value).is_err());
+        let lenient = ParseOptions::lenient().with_validation(options.validation);
+        assert!(Recurrence::from_str_with(value, lenient).is_ok());
+
+        // The period is still validated
+        let value = "FREQ=DAILY;INTERVAL=1;DTSTART=2023-01-02T00:00:00Z;DTEND=2023-01-01T00:00:00Z";
+        assert!(Recurrence::from_str_with(value, options).is_err());
+    }
 }
 
 #[cfg(test)]
@@ -277,4 +694,217 @@ mod serialize_tests {
         let serialized = recurrence.to_string();
         assert_eq!(serialized, value);
     }
+
+    #[test]
+    fn duration_to_str() {
+        let value = "FREQ=DAILY;INTERVAL=2;DTSTART=2023-01-01T00:00:00Z;DURATION=P1DT12H";
+        let recurrence = Recurrence::from_str(value).unwrap();
+        assert_eq!(recurrence.to_string(), value);
+
+        let value = "FREQ=WEEKLY;INTERVAL=2;DTSTART=2023-01-01T00:00:00Z;DURATION=P1W";
+        let recurrence = Recurrence::from_str(value).unwrap();
+        assert_eq!(recurrence.to_string(), value);
+    }
+}
+
+#[cfg(test)]
+mod round_trip_tests {
+    use crate::{Frequency, Recurrence};
+    use chrono::{DateTime, Duration, Utc};
+    use std::str::FromStr;
+
+    /// Order of the keys written by the serializer, followed by the unknown parameters.
+    const KEY_ORDER: [&str; 22] = [
+        "FREQ",
+        "INTERVAL",
+        "BYMONTH",
+        "BYMONTHDAY",
+        "X-BYMONTHDATE",
+        "BYDAY",
+        "BYWEEKNO",
+        "BYHOUR",
+        "BYMINUTE",
+        "BYSECOND",
+        "BYTIME",
+        "X-BYDAYTIME",
+        "X-BUSINESS",
+        "RSCALE",
+        "SKIP",
+        "X-OVERFLOW",
+        "DTSTART",
+        "DTEND",
+        "DURATION",
+        "COUNT",
+        "EXDATE",
+        "X-NOTBEFORE",
+    ];
+
+    /// Frequencies with every kind of parameter, written in any order.
+    const FREQUENCIES: [&str; 14] = [
+        "FREQ=SECONDLY;INTERVAL=30;BYDAY=MO,FR;BYHOUR=9;BYMINUTE=0,30",
+        "FREQ=MINUTELY;INTERVAL=15;BYTIME=17:30,09:00",
+        "FREQ=HOURLY;INTERVAL=2;BYDAY=SA",
+        "FREQ=DAILY;BYDAY=SU,SA;INTERVAL=1;BYTIME=09:00,17:30",
+        "FREQ=DAILY;INTERVAL=3;BYHOUR=8;BYMINUTE=15;BYSECOND=30",
+        "FREQ=DAILY;INTERVAL=1;X-BUSINESS=TRUE",
+        "BYTIME=10:00;FREQ=WEEKLY;INTERVAL=2;BYDAY=WE,MO",
+        "FREQ=WEEKLY;INTERVAL=1;X-BYDAYTIME=MO09:00,FR17:00",
+        "FREQ=MONTHLY;INTERVAL=1;X-OVERFLOW=CLAMP;BYMONTHDAY=31",
+        "FREQ=MONTHLY;INTERVAL=1;BYDAY=1MO,3FR;BYTIME=12:00",
+        "FREQ=YEARLY;INTERVAL=1;BYMONTHDAY=29;BYMONTH=2;X-OVERFLOW=NEXT",
+        "FREQ=YEARLY;INTERVAL=1;X-BYMONTHDATE=JAN15,JUN1",
+        "FREQ=YEARLY;INTERVAL=1;BYMONTH=3;BYDAY=2SU;BYTIME=02:30",
+        "FREQ=YEARLY;INTERVAL=1;BYWEEKNO=1,-1;BYDAY=MO",
+    ];
+
+    const STARTS: [&str; 3] = [
+        "2023-01-01T00:00:00Z",
+        "2023-03-15T09:30:45Z",
+        "2023-03-15T09:30:45.250Z",
+    ];
+
+    /// Serializes the recurrence and parses it back, verifying that nothing is lost and that
+    /// the keys are written in the canonical order.
+    fn assert_round_trip(recurrence: &Recurrence) {
+        let serialized = recurrence.to_string();
+        let parsed = Recurrence::from_str(&serialized)
+            .unwrap_or_else(|e| panic!("{serialized} cannot be parsed: {e}"));
+        assert_eq!(parsed.to_string(), serialized);
+        assert_eq!(parsed.frequency, recurrence.frequency, "{serialized}");
+        assert_eq!(parsed.start, recurrence.start, "{serialized}");
+        assert_eq!(parsed.end(), recurrence.end(), "{serialized}");
+        assert_eq!(parsed.duration, recurrence.duration, "{serialized}");
+        assert_eq!(parsed.all_day, recurrence.all_day, "{serialized}");
+        assert_eq!(parsed.not_before(), recurrence.not_before(), "{serialized}");
+        assert_eq!(parsed.unknown_params, recurrence.unknown_params);
+
+        let positions: Vec<usize> = serialized
+            .split(';')
+            .map(|part| part.split_once('=').unwrap().0)
+            .map(|key| {
+                KEY_ORDER
+                    .iter()
+                    .position(|known| *known == key)
+                    .unwrap_or(KEY_ORDER.len())
+            })
+            .collect();
+        assert!(
+            positions.windows(2).all(|pair| pair[0] < pair[1]
+                || pair[0] == KEY_ORDER.len() && pair[1] == KEY_ORDER.len()),
+            "{serialized}"
+        );
+    }
+
+    #[test]
+    fn every_combination_round_trips() {
+        let durations = [
+            Duration::zero(),
+            Duration::seconds(1),
+            Duration::minutes(90),
+            Duration::milliseconds(1500),
+            Duration::hours(30),
+            Duration::weeks(1),
+        ];
+        let unknown_params = vec![
+            ("X-NAME".to_string(), "Standup".to_string()),
+            ("BYSETPOS".to_string(), "-1".to_string()),
+        ];
+        for rule in FREQUENCIES {
+            let frequency = Frequency::from_str(rule).unwrap();
+            let mut valid = 0;
+            for start in STARTS {
+                let start = DateTime::<Utc>::from_str(start).unwrap();
+                for end in [None, Some(start + Duration::days(400))] {
+                    let mut recurrences: Vec<Recurrence> = durations
+                        .iter()
+                        .filter_map(|duration| {
+                            Recurrence::new(frequency.clone(), start, end, Some(*duration)).ok()
+                        })
+                        .collect();
+                    recurrences.extend(
+                        Recurrence::all_day(
+                            frequency.clone(),
+                            start.date_naive(),
+                            end.map(|end| end.date_naive()),
+                        )
+                        .ok(),
+                    );
+                    for recurrence in recurrences {
+                        valid += 1;
+                        assert_round_trip(&recurrence);
+                        assert_round_trip(&recurrence.with_unknown_params(unknown_params.clone()));
+                    }
+                }
+            }
+            assert!(valid > 0, "{rule}");
+        }
+    }
+
+    #[test]
+    fn keys_are_written_in_the_canonical_order() {
+        let value = "DURATION=PT1H;DTEND=2023-02-01T00:00:00Z;X-NAME=Review;BYTIME=09:00;\
+            DTSTART=2023-01-02T00:00:00Z;BYDAY=FR,MO;INTERVAL=1;FREQ=WEEKLY";
+        let recurrence = Recurrence::from_str(value).unwrap();
+        assert_eq!(
+            recurrence.to_string(),
+            "FREQ=WEEKLY;INTERVAL=1;BYDAY=MO,FR;BYHOUR=9;BYMINUTE=0;\
+            DTSTART=2023-01-02T00:00:00Z;DTEND=2023-02-01T00:00:00Z;DURATION=PT1H;X-NAME=Review"
+        );
+    }
+}
+
+#[cfg(test)]
+mod unknown_params_tests {
+    use crate::Recurrence;
+    use std::str::FromStr;
+
+    #[test]
+    fn keeps_unknown_params() {
+        let value = "FREQ=MONTHLY;INTERVAL=1;BYSETPOS=-1;DTSTART=2023-01-01T00:00:00Z;X-NAME=Rent";
+        let recurrence = Recurrence::from_str(value).unwrap();
+        assert_eq!(
+            recurrence.unknown_params,
+            vec![
+                ("BYSETPOS".to_string(), "-1".to_string()),
+                ("X-NAME".to_string(), "Rent".to_string()),
+            ]
+        );
+        assert_eq!(
+            recurrence.to_string(),
+            "FREQ=MONTHLY;INTERVAL=1;DTSTART=2023-01-01T00:00:00Z;BYSETPOS=-1;X-NAME=Rent"
+        );
+        assert_eq!(
+            Recurrence::from_str(&recurrence.to_string()).unwrap(),
+            recurrence
+        );
+    }
+
+    #[test]
+    fn keeps_unknown_params_of_modified_recurrences() {
+        let value = "FREQ=DAILY;INTERVAL=1;DTSTART=2023-01-01T00:00:00Z;X-SOURCE=calendar";
+        let recurrence = Recurrence::from_str(value)
+            .unwrap()
+            .with_end(Some(
+                chrono::DateTime::from_str("2023-01-31T00:00:00Z").unwrap(),
+            ))
+            .unwrap();
+        assert_eq!(
+            recurrence.to_string(),
+            "FREQ=DAILY;INTERVAL=1;DTSTART=2023-01-01T00:00:00Z;DTEND=2023-01-31T00:00:00Z;X-SOURCE=calendar"
+        );
+    }
+
+    #[test]
+    fn keeps_unknown_rrule_parts() {
+        let value =
+            "DTSTART:20230101T090000Z\nRRULE:FREQ=DAILY;INTERVAL=1;WKST=SU;UNTIL=20230103T090000Z";
+        let recurrence = Recurrence::from_str(value).unwrap();
+        assert_eq!(
+            recurrence.unknown_params,
+            vec![("WKST".to_string(), "SU".to_string())]
+        );
+        assert!(recurrence
+            .to_ics_vevent()
+            .contains("RRULE:FREQ=DAILY;INTERVAL=1;WKST=SU;UNTIL=20230103T090000Z"));
+    }
 }
@@ -1,56 +1,139 @@
+use crate::frequencies::models::weekly_day_times;
 use crate::frequencies::{Frequency, NthWeekday, Time};
-use crate::recurrences::errors::RecurrenceInvalid;
-use crate::utils::{get_next_nth_weekday, DateUtils};
-use chrono::{DateTime, Datelike, Duration, Timelike, Utc, Weekday};
+use crate::recurrences::errors::{RecurrenceErrorKind, RecurrenceInvalid};
+use crate::recurrences::models::start_of_day;
+use crate::utils::get_next_nth_weekday;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use chrono::{DateTime, Datelike, Duration, NaiveTime, Timelike, Utc, Weekday};
+
+const SECONDS_PER_DAY: i64 = 24 * 60 * 60;
+
+/// Options that control how the recurrences are validated.
+///
+/// By default, the events of a recurrence cannot last past the start of the next event.
+/// Allowing overlapping occurrences skips the duration checks, E.g. to block a longer
+/// time than the event itself, while the frequency and the period are still validated.
+///
+/// Examples:
+/// ```
+/// use std::str::FromStr;
+/// use chrono::{DateTime, Duration, Utc};
+/// use rrules::{Frequency, Recurrence, ValidationOptions};
+///
+/// let weekly = Frequency::from_str("FREQ=WEEKLY;INTERVAL=1;BYDAY=MO,WE").unwrap();
+/// let start = DateTime::<Utc>::from_str("2023-01-02T09:00:00Z").unwrap();
+/// let duration = Some(Duration::days(3));
+/// assert!(Recurrence::new(weekly.clone(), start, None, duration).is_err());
+///
+/// let options = ValidationOptions::default().allow_overlapping_occurrences();
+/// assert!(Recurrence::new_with(weekly, start, None, duration, options).is_ok());
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ValidationOptions {
+    /// Whether the events can last past the start of the next event
+    pub allow_overlaps: bool,
+}
+
+impl ValidationOptions {
+    /// Skips the checks of the duration against the gaps between the events.
+    pub fn allow_overlapping_occurrences(mut self) -> Self {
+        self.allow_overlaps = true;
+        self
+    }
+}
 
 pub fn validate_recurrence_period(
     start: &DateTime<Utc>,
     end: &DateTime<Utc>,
 ) -> Result<(), RecurrenceInvalid> {
     if start >= end {
-        return Err(RecurrenceInvalid {
-            message: "Start date must be before end date".to_string(),
-        });
+        return Err(
+            RecurrenceInvalid::new("Start date must be before end date".to_string()).with_kind(
+                RecurrenceErrorKind::InvalidPeriod {
+                    start: *start,
+                    end: *end,
+                },
+            ),
+        );
     }
     Ok(())
 }
 
+pub fn validate_all_day_frequency(frequency: &Frequency) -> Result<(), RecurrenceInvalid> {
+    match frequency {
+        Frequency::Secondly { .. } | Frequency::Minutely { .. } | Frequency::Hourly { .. } => Err(
+            RecurrenceInvalid::new("All-day recurrences must be at least daily".to_string())
+                .with_kind(RecurrenceErrorKind::AllDayTooFrequent),
+        ),
+        _ if !frequency.by_time().is_empty() || !frequency.by_day_time().is_empty() => Err(
+            RecurrenceInvalid::new("All-day recurrences cannot define times".to_string())
+                .with_kind(RecurrenceErrorKind::AllDayWithTimes),
+        ),
+        _ => Ok(()),
+    }
+}
+
+/// Verifies that the events of the frequency don't last past the next event.
+///
+/// The gaps between the events are computed with calendar arithmetic, so the result only
+/// depends on the frequency, the start date and the duration. Gaps that depend on the
+/// month, like the ones between nth weekdays, are taken from the month of the start date.
+/// The values of the frequency can be in any order.
 pub fn validate_duration(
     frequency: &Frequency,
+    start: &DateTime<Utc>,
     duration: &Duration,
 ) -> Result<(), RecurrenceInvalid> {
+    // The gaps are computed between consecutive values, so they must be sorted
+    let frequency = &frequency.clone().normalized();
     match frequency {
-        Frequency::Secondly { interval } => {
+        Frequency::Secondly { interval, .. } => {
             let seconds = duration.num_seconds();
-            if seconds > *interval as i64 {
-                return Err(RecurrenceInvalid {
-                    message: "Duration must be smaller than interval".to_string(),
-                });
+            if seconds > i64::from(interval.get()) {
+                return Err(duration_too_long(
+                    Duration::seconds(i64::from(interval.get())),
+                    duration,
+                ));
             }
         }
-        Frequency::Minutely { interval } => {
+        Frequency::Minutely { interval, .. } => {
             let minutes = duration.num_minutes();
-            if minutes > *interval as i64 {
-                return Err(RecurrenceInvalid {
-                    message: "Duration must be smaller than interval".to_string(),
-                });
+            if minutes > i64::from(interval.get()) {
+                return Err(duration_too_long(
+                    Duration::minutes(i64::from(interval.get())),
+                    duration,
+                ));
             }
         }
-        Frequency::Hourly { interval } => {
+        Frequency::Hourly { interval, .. } => {
             let hours = duration.num_hours();
-            if hours > *interval as i64 {
-                return Err(RecurrenceInvalid {
-                    message: "Duration must be smaller than interval".to_string(),
-                });
+            if hours > i64::from(interval.get()) {
+                return Err(duration_too_long(
+                    Duration::hours(i64::from(interval.get())),
+                    duration,
+                ));
+            }
+        }
+        Frequency::BusinessDaily { interval } => {
+            if duration.num_days() > i64::from(interval.get()) {
+                return Err(duration_too_long(
+                    Duration::days(i64::from(interval.get())),
+                    duration,
+                ));
             }
         }
-        Frequency::Daily { interval, by_time } => {
+        Frequency::Daily {
+            interval, by_time, ..
+        } => {
             let days = duration.num_days();
 
-            if days > *interval as i64 {
-                return Err(RecurrenceInvalid {
-                    message: "Duration must be smaller than interval".to_string(),
-                });
+            if days > i64::from(interval.get()) {
+                return Err(duration_too_long(
+                    Duration::days(i64::from(interval.get())),
+                    duration,
+                ));
             }
 
             if by_time.len() > 1 {
@@ -64,15 +147,22 @@ pub fn validate_duration(
                 validate_time_duration(&by_time[by_time.len() - 1], &by_time[0], duration)?;
             }
         }
-        Frequency::Weekly { interval, by_day } => {
+        Frequency::Weekly {
+            interval,
+            by_day,
+            by_time,
+            by_day_time,
+        } => {
             let weeks = duration.num_weeks();
-            if weeks > *interval as i64 {
-                return Err(RecurrenceInvalid {
-                    message: "Duration must be smaller than interval".to_string(),
-                });
+            if weeks > i64::from(interval.get()) {
+                return Err(duration_too_long(
+                    Duration::weeks(i64::from(interval.get())),
+                    duration,
+                ));
             }
 
-            if by_day.len() > 1 {
+            // With times, the days are checked with their times below
+            if by_day.len() > 1 && by_time.is_empty() {
                 let mut w = &by_day[0];
                 for item in by_day.iter().skip(1) {
                     validate_weekday_duration(w, item, duration)?;
@@ -82,20 +172,48 @@ pub fn validate_duration(
                 // Compare last time of day with first time next day
                 validate_weekday_duration(&by_day[by_day.len() - 1], &by_day[0], duration)?;
             }
+
+            let day_times;
+            let by_day_time = if by_time.is_empty() {
+                by_day_time
+            } else {
+                day_times = weekly_day_times(start, by_day, by_time);
+                &day_times
+            };
+            if by_day_time.len() > 1 {
+                let mut d = &by_day_time[0];
+                for item in by_day_time.iter().skip(1) {
+                    validate_day_time_duration(d, item, duration)?;
+                    d = item;
+                }
+
+                // Compare last day and time of the week with the first one next week
+                validate_day_time_duration(
+                    &by_day_time[by_day_time.len() - 1],
+                    &by_day_time[0],
+                    duration,
+                )?;
+            }
         }
         Frequency::Monthly {
             interval,
             by_month_day,
+            by_day,
             nth_weekdays,
+            by_time,
+            ..
         } => {
             let months = duration.num_days() as f32 / 30.0;
-            if months > *interval as f32 {
-                return Err(RecurrenceInvalid {
-                    message: format!(
-                        "Total duration cannot be bigger than {} days",
-                        30 * interval
-                    ),
-                });
+            if months > interval.get() as f32 {
+                return Err(RecurrenceInvalid::new(format!(
+                    "Total duration cannot be bigger than {} days",
+                    30 * i64::from(interval.get())
+                ))
+                .with_kind(RecurrenceErrorKind::DurationTooLong {
+                    allowed: Duration::try_days(30 * i64::from(interval.get()))
+                        .unwrap_or(Duration::MAX),
+                    actual: *duration,
+                }));
             }
             if !by_month_day.is_empty() {
                 let mut m = &by_month_day[0];
@@ -112,10 +230,22 @@ pub fn validate_duration(
                 )?;
             }
 
-            if !nth_weekdays.is_empty() {
+            // The weekdays only restrict the month days when they are combined with them
+            if by_day.len() > 1 && by_month_day.is_empty() {
+                let mut w = &by_day[0];
+                for item in by_day.iter().skip(1) {
+                    validate_weekday_duration(w, item, duration)?;
+                    w = item;
+                }
+
+                // Compare last weekday with first weekday next week
+                validate_weekday_duration(&by_day[by_day.len() - 1], &by_day[0], duration)?;
+            }
+
+            if !nth_weekdays.is_empty() && by_month_day.is_empty() {
                 let mut m = &nth_weekdays[0];
                 for item in nth_weekdays.iter().skip(1) {
-                    validate_monthly_nth_weekday_duration(m, item, duration)?;
+                    validate_monthly_nth_weekday_duration(m, item, start, duration)?;
                     m = item;
                 }
 
@@ -123,39 +253,151 @@ pub fn validate_duration(
                 validate_monthly_nth_weekday_duration(
                     &nth_weekdays[nth_weekdays.len() - 1],
                     &nth_weekdays[0],
+                    start,
                     duration,
                 )?;
             }
+
+            validate_times_of_a_day_duration(by_time, duration)?;
         }
-        Frequency::Yearly { .. } => {}
+        Frequency::Yearly { by_time, .. } => validate_times_of_a_day_duration(by_time, duration)?,
+    }
+    Ok(())
+}
+
+/// Verifies that the events at the times of a day don't last past the next time of the day.
+///
+/// The days of the monthly and yearly events are checked on their own, so the last time of
+/// a day is not compared with the first time of the next day.
+fn validate_times_of_a_day_duration(
+    by_time: &[Time],
+    duration: &Duration,
+) -> Result<(), RecurrenceInvalid> {
+    for times in by_time.windows(2) {
+        validate_time_duration(&times[0], &times[1], duration)?;
     }
     Ok(())
 }
 
+/// Verifies that the events of the sub-daily frequencies, spaced by their interval from the
+/// start date, reach the hour and minute of at least one time of the frequency, so the
+/// recurrence yields events.
+///
+/// The seconds of the day of the events keep the remainder of the start date modulo the
+/// greatest common divisor of the interval and a day, so a time is reached when one of the
+/// seconds of its minute has that remainder.
+pub fn validate_sub_daily_times(
+    frequency: &Frequency,
+    start: &DateTime<Utc>,
+) -> Result<(), RecurrenceInvalid> {
+    let (step, by_time) = match frequency {
+        Frequency::Secondly {
+            interval, by_time, ..
+        } => (i64::from(interval.get()), by_time),
+        Frequency::Minutely {
+            interval, by_time, ..
+        } => (i64::from(interval.get()) * 60, by_time),
+        Frequency::Hourly {
+            interval, by_time, ..
+        } => (i64::from(interval.get()) * 60 * 60, by_time),
+        _ => return Ok(()),
+    };
+    let period = gcd(step, SECONDS_PER_DAY);
+    let phase = i64::from(start.num_seconds_from_midnight()) % period;
+    let reached = |time: &Time| {
+        let minute_start = i64::from(time.hour) * 60 * 60 + i64::from(time.minute) * 60;
+        (phase - minute_start).rem_euclid(period) < 60
+    };
+    if by_time.is_empty() || by_time.iter().any(reached) {
+        return Ok(());
+    }
+    let times = by_time
+        .iter()
+        .map(Time::to_string)
+        .collect::<Vec<_>>()
+        .join(",");
+    Err(invalid_value(
+        format!("The interval never reaches the times {times} from the start date {start}"),
+        times,
+    ))
+}
+
+fn gcd(a: i64, b: i64) -> i64 {
+    match b {
+        0 => a,
+        _ => gcd(b, a % b),
+    }
+}
+
+/// Verifies the duration of the events with `validate_duration`, unless the options
+/// allow overlapping occurrences.
+pub fn validate_duration_with(
+    frequency: &Frequency,
+    start: &DateTime<Utc>,
+    duration: &Duration,
+    options: &ValidationOptions,
+) -> Result<(), RecurrenceInvalid> {
+    if options.allow_overlaps {
+        return Ok(());
+    }
+    validate_duration(frequency, start, duration)
+}
+
+fn duration_too_long(allowed: Duration, actual: &Duration) -> RecurrenceInvalid {
+    RecurrenceInvalid::new("Duration must be smaller than interval".to_string()).with_kind(
+        RecurrenceErrorKind::DurationTooLong {
+            allowed,
+            actual: *actual,
+        },
+    )
+}
+
+fn out_of_range() -> RecurrenceInvalid {
+    RecurrenceInvalid::new("Date out of range").with_kind(RecurrenceErrorKind::OutOfRange)
+}
+
+fn invalid_value(message: String, value: impl ToString) -> RecurrenceInvalid {
+    RecurrenceInvalid::new(message).with_kind(RecurrenceErrorKind::InvalidValue {
+        value: value.to_string(),
+    })
+}
+
+fn overlap(kind: RecurrenceErrorKind) -> RecurrenceInvalid {
+    RecurrenceInvalid::new(
+        "There is an overlap of events with the current times and duration defined.".to_string(),
+    )
+    .with_kind(kind)
+}
+
+/// Returns the seconds from midnight to the time, or an error when the time doesn't exist.
+fn seconds_of_day(time: &Time) -> Result<i64, RecurrenceInvalid> {
+    NaiveTime::from_hms_opt(time.hour as u32, time.minute as u32, time.second as u32)
+        .map(|time| i64::from(time.num_seconds_from_midnight()))
+        .ok_or_else(|| invalid_value(format!("Invalid time: {time}"), time))
+}
+
+/// Verifies that an event doesn't last past the start of the next one, a gap of the
+/// given seconds later.
+fn fits_in(duration: &Duration, gap_seconds: i64) -> bool {
+    *duration <= Duration::seconds(gap_seconds)
+}
+
 pub fn validate_time_duration(
     time: &Time,
     next_time: &Time,
     duration: &Duration,
 ) -> Result<(), RecurrenceInvalid> {
-    let date = Utc::now()
-        .with_hour(time.hour as u32)
-        .unwrap()
-        .with_minute(time.minute as u32)
-        .unwrap();
-    let projected_date = date + *duration;
-    let mut next_date = Utc::now()
-        .with_hour(next_time.hour as u32)
-        .unwrap()
-        .with_minute(next_time.minute as u32)
-        .unwrap();
-    if next_date < date {
-        next_date = next_date.shift_days(1).unwrap();
-    }
-    if projected_date > next_date {
-        return Err(RecurrenceInvalid {
-            message: "There is an overlap of events with the current times and duration defined."
-                .to_string(),
-        });
+    let (seconds, next_seconds) = (seconds_of_day(time)?, seconds_of_day(next_time)?);
+    // The next time is on the following day when it is earlier
+    let gap = match next_seconds - seconds {
+        gap if gap < 0 => gap + SECONDS_PER_DAY,
+        gap => gap,
+    };
+    if !fits_in(duration, gap) {
+        return Err(overlap(RecurrenceErrorKind::OverlappingTimes {
+            first: time.clone(),
+            second: next_time.clone(),
+        }));
     }
     Ok(())
 }
@@ -165,68 +407,101 @@ pub fn validate_weekday_duration(
     next_weekday: &Weekday,
     duration: &Duration,
 ) -> Result<(), RecurrenceInvalid> {
-    let now = Utc::now();
-
-    let date = now.with_weekday(*weekday).unwrap();
-
-    let projected_date = date + *duration;
-    let mut next_date = now.with_weekday(*next_weekday).unwrap();
-    if next_date < date {
-        next_date = next_date.shift_weeks(1).unwrap();
-    }
-    if projected_date > next_date {
-        return Err(RecurrenceInvalid {
-            message: "There is an overlap of events with the current times and duration defined."
-                .to_string(),
-        });
+    // The next weekday is on the following week when it is earlier
+    let days =
+        i64::from(next_weekday.num_days_from_monday()) - i64::from(weekday.num_days_from_monday());
+    if !fits_in(duration, days.rem_euclid(7) * SECONDS_PER_DAY) {
+        return Err(overlap(RecurrenceErrorKind::OverlappingWeekdays {
+            first: *weekday,
+            second: *next_weekday,
+        }));
     }
     Ok(())
 }
 
+pub fn validate_day_time_duration(
+    day_time: &(Weekday, Time),
+    next_day_time: &(Weekday, Time),
+    duration: &Duration,
+) -> Result<(), RecurrenceInvalid> {
+    let seconds_of_week = |(weekday, time): &(Weekday, Time)| {
+        Ok::<_, RecurrenceInvalid>(
+            i64::from(weekday.num_days_from_monday()) * SECONDS_PER_DAY + seconds_of_day(time)?,
+        )
+    };
+    // The next day and time is on the following week when it is not later
+    let gap = match seconds_of_week(next_day_time)? - seconds_of_week(day_time)? {
+        gap if gap <= 0 => gap + 7 * SECONDS_PER_DAY,
+        gap => gap,
+    };
+    if fits_in(duration, gap) {
+        return Ok(());
+    }
+    let kind = if day_time.0 == next_day_time.0 {
+        RecurrenceErrorKind::OverlappingTimes {
+            first: day_time.1.clone(),
+            second: next_day_time.1.clone(),
+        }
+    } else {
+        RecurrenceErrorKind::OverlappingWeekdays {
+            first: day_time.0,
+            second: next_day_time.0,
+        }
+    };
+    Err(overlap(kind))
+}
+
+/// Verifies that the events on a month day don't last past the next month day.
+///
+/// The gap to an earlier day of the following month is counted in a month of 31 days, as
+/// the months without the day don't have the event.
 pub fn validate_monthly_by_month_day_duration(
     monthday: &i32,
     next_monthday: &i32,
     duration: &Duration,
 ) -> Result<(), RecurrenceInvalid> {
-    let now = Utc::now();
-
-    let date = now.with_day(*monthday as u32).unwrap();
-
-    let projected_date = date + *duration;
-    let mut next_date = now.with_day(*next_monthday as u32).unwrap();
-    if next_date < date {
-        next_date = next_date.shift_months(1).unwrap();
-    }
-    if projected_date > next_date {
-        return Err(RecurrenceInvalid {
-            message: "There is an overlap of events with the current times and duration defined."
-                .to_string(),
-        });
+    let day = |day: &i32| {
+        (1..=31)
+            .contains(day)
+            .then_some(i64::from(*day))
+            .ok_or_else(|| invalid_value(format!("Invalid day of the month: {day}"), day))
+    };
+    let days = day(next_monthday)? - day(monthday)?;
+    if !fits_in(duration, days.rem_euclid(31) * SECONDS_PER_DAY) {
+        return Err(overlap(RecurrenceErrorKind::OverlappingMonthDays {
+            first: *monthday,
+            second: *next_monthday,
+        }));
     }
     Ok(())
 }
 
+/// Verifies that the events on an nth weekday don't last past the next nth weekday,
+/// looking for them from the month of the start date.
 pub fn validate_monthly_nth_weekday_duration(
     nth_weekday: &NthWeekday,
     next_monthday: &NthWeekday,
+    start: &DateTime<Utc>,
     duration: &Duration,
 ) -> Result<(), RecurrenceInvalid> {
-    // Use last day of previous month as a reference to get the next nth weekday of current month
-    let last_day_prev_month = Utc::now().with_day(1).unwrap() - Duration::days(1);
-
-    let first_nth_datetime =
-        get_next_nth_weekday(&last_day_prev_month, 1, &vec![nth_weekday.clone()]).unwrap();
-
-    let projected_date = first_nth_datetime + *duration;
-
-    let next_date =
-        get_next_nth_weekday(&first_nth_datetime, 1, &vec![next_monthday.clone()]).unwrap();
-    if next_date < projected_date {
-        return Err(RecurrenceInvalid {
-            message:
-                "There is an overlap of events with the current weekdays and duration defined."
-                    .to_string(),
-        });
+    // Use last day of previous month as a reference to get the next nth weekday of the month
+    let month_start = start.date_naive().with_day(1).ok_or_else(out_of_range)?;
+    let last_day_prev_month = start_of_day(month_start.pred_opt().ok_or_else(out_of_range)?);
+
+    let next_nth_weekday = |date: &DateTime<Utc>, nth_weekday: &NthWeekday| {
+        get_next_nth_weekday(date, 1, core::slice::from_ref(nth_weekday))
+            .ok_or_else(|| invalid_value(format!("Invalid weekday: {nth_weekday}"), nth_weekday))
+    };
+    let first_nth_datetime = next_nth_weekday(&last_day_prev_month, nth_weekday)?;
+    let next_date = next_nth_weekday(&first_nth_datetime, next_monthday)?;
+    if !fits_in(duration, (next_date - first_nth_datetime).num_seconds()) {
+        return Err(RecurrenceInvalid::new(
+            "There is an overlap of events with the current weekdays and duration defined.",
+        )
+        .with_kind(RecurrenceErrorKind::OverlappingNthWeekdays {
+            first: nth_weekday.clone(),
+            second: next_monthday.clone(),
+        }));
     }
 
     Ok(())
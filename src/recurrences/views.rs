@@ -0,0 +1,131 @@
+use crate::codes::month_from_number;
+use crate::recurrences::Recurrence;
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+use chrono::{DateTime, Datelike, Days, Month, NaiveDate, NaiveTime, TimeZone, Utc, Weekday};
+
+impl Recurrence {
+    /// Returns the event dates of the year grouped by their month, to render calendar
+    /// views. Months without events are left out.
+    ///
+    /// The iteration jumps to the start of the year when the frequency allows it, instead
+    /// of going over every event from the start date.
+    ///
+    /// Examples:
+    /// ```
+    /// use std::str::FromStr;
+    /// use chrono::Month;
+    /// use rrules::Recurrence;
+    ///
+    /// let recurrence = Recurrence::from_str("FREQ=WEEKLY;INTERVAL=1;BYDAY=MO;DTSTART=2020-01-06T09:00:00Z").unwrap();
+    /// let by_month = recurrence.occurrences_by_month(2023);
+    /// assert_eq!(by_month.len(), 12);
+    /// assert_eq!(by_month[&Month::January].len(), 5);
+    /// assert_eq!(by_month[&Month::February].len(), 4);
+    /// ```
+    pub fn occurrences_by_month(&self, year: i32) -> BTreeMap<Month, Vec<DateTime<Utc>>> {
+        let mut months: BTreeMap<Month, Vec<DateTime<Utc>>> = BTreeMap::new();
+        let (Some(start), Some(end)) = (first_day_of_year(year), first_day_of_year(year + 1))
+        else {
+            return months;
+        };
+        for event in self.within(&(start..end)) {
+            if let Some(month) = month_from_number(event.month() as i32) {
+                months.entry(month).or_default().push(event);
+            }
+        }
+        months
+    }
+
+    /// Returns the event dates of the ISO week of the year, from Monday to Sunday, or an
+    /// empty list when the week doesn't exist.
+    ///
+    /// Examples:
+    /// ```
+    /// use std::str::FromStr;
+    /// use rrules::Recurrence;
+    ///
+    /// let recurrence = Recurrence::from_str("FREQ=DAILY;INTERVAL=2;DTSTART=2023-01-01T09:00:00Z").unwrap();
+    /// // The 1st ISO week of 2023 goes from Monday the 2nd to Sunday the 8th of January
+    /// assert_eq!(recurrence.occurrences_in_week(2023, 1).len(), 3);
+    /// assert!(recurrence.occurrences_in_week(2023, 54).is_empty());
+    /// ```
+    pub fn occurrences_in_week(&self, iso_year: i32, week: u32) -> Vec<DateTime<Utc>> {
+        let Some(monday) = NaiveDate::from_isoywd_opt(iso_year, week, Weekday::Mon) else {
+            return Vec::new();
+        };
+        let Some(next_monday) = monday.checked_add_days(Days::new(7)) else {
+            return Vec::new();
+        };
+        let start = Utc.from_utc_datetime(&monday.and_time(NaiveTime::MIN));
+        let end = Utc.from_utc_datetime(&next_monday.and_time(NaiveTime::MIN));
+        self.within(&(start..end)).collect()
+    }
+}
+
+/// Returns the first instant of the year, or None when it is out of range.
+fn first_day_of_year(year: i32) -> Option<DateTime<Utc>> {
+    NaiveDate::from_ymd_opt(year, 1, 1)
+        .map(|day| Utc.from_utc_datetime(&day.and_time(NaiveTime::MIN)))
+}
+
+#[cfg(test)]
+mod test_views {
+    use crate::test_utils::date;
+    use crate::Recurrence;
+    use chrono::Month;
+    use std::str::FromStr;
+
+    #[test]
+    fn groups_the_events_of_the_year_by_month() {
+        let recurrence = Recurrence::from_str(
+            "FREQ=MONTHLY;INTERVAL=2;BYMONTHDAY=1,31;DTSTART=2022-11-01T10:00:00Z;DTEND=2023-07-15T00:00:00Z",
+        )
+        .unwrap();
+        let by_month = recurrence.occurrences_by_month(2023);
+        assert_eq!(
+            by_month.keys().copied().collect::<Vec<_>>(),
+            vec![Month::January, Month::March, Month::May, Month::July]
+        );
+        assert_eq!(
+            by_month[&Month::January],
+            vec![date("2023-01-01T10:00:00Z"), date("2023-01-31T10:00:00Z")]
+        );
+        assert_eq!(by_month[&Month::May].len(), 2);
+        assert_eq!(by_month[&Month::July], vec![date("2023-07-01T10:00:00Z")]);
+        assert!(recurrence.occurrences_by_month(2021).is_empty());
+        assert!(recurrence.occurrences_by_month(2024).is_empty());
+    }
+
+    #[test]
+    fn includes_the_bounds_of_the_year() {
+        let recurrence =
+            Recurrence::from_str("FREQ=HOURLY;INTERVAL=12;DTSTART=2000-01-01T00:00:00Z").unwrap();
+        let by_month = recurrence.occurrences_by_month(2023);
+        assert_eq!(by_month.values().map(Vec::len).sum::<usize>(), 730);
+        assert_eq!(by_month[&Month::January][0], date("2023-01-01T00:00:00Z"));
+        assert_eq!(
+            by_month[&Month::December].last(),
+            Some(&date("2023-12-31T12:00:00Z"))
+        );
+    }
+
+    #[test]
+    fn returns_the_events_of_the_iso_week() {
+        let recurrence =
+            Recurrence::from_str("FREQ=WEEKLY;INTERVAL=1;BYDAY=MO,SU;DTSTART=2020-01-05T08:00:00Z")
+                .unwrap();
+        // The 1st ISO week of 2021 starts on Monday the 4th of January
+        assert_eq!(
+            recurrence.occurrences_in_week(2021, 1),
+            vec![date("2021-01-04T08:00:00Z"), date("2021-01-10T08:00:00Z")]
+        );
+        // The 53rd ISO week of 2020 ends on Sunday the 3rd of January 2021
+        assert_eq!(
+            recurrence.occurrences_in_week(2020, 53),
+            vec![date("2020-12-28T08:00:00Z"), date("2021-01-03T08:00:00Z")]
+        );
+        assert!(recurrence.occurrences_in_week(2021, 53).is_empty());
+        assert!(recurrence.occurrences_in_week(2021, 0).is_empty());
+    }
+}
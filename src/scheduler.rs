@@ -0,0 +1,121 @@
+//! Scheduling utilities to drive periodic tasks, available with the `tokio` feature.
+//!
+//! Examples:
+//! ```no_run
+//! use std::str::FromStr;
+//! use futures_util::StreamExt;
+//! use rrules::Recurrence;
+//!
+//! # async fn run() {
+//! let every_hour = Recurrence::from_str("FREQ=HOURLY;INTERVAL=1;DTSTART=2023-01-01T00:00:00Z").unwrap();
+//! let mut events = Box::pin(every_hour.into_stream());
+//! while let Some(event) = events.next().await {
+//!     println!("Running the task scheduled at {event}");
+//! }
+//! # }
+//! ```
+
+use crate::clock::{Clock, SystemClock};
+use crate::Recurrence;
+use chrono::{DateTime, Duration, Utc};
+use futures_core::Stream;
+use futures_util::stream;
+
+/// Maximum time slept at once, so that changes of the system clock are noticed.
+const MAX_SLEEP: Duration = Duration::minutes(1);
+
+impl Recurrence {
+    /// Returns a stream of the event dates after the current time, yielding every date
+    /// once it is reached.
+    ///
+    /// The next event is recomputed every time the stream wakes up, so long waits and
+    /// changes of the system clock are handled. Events reached while the stream was not
+    /// polled are yielded right away, in order. Dropping the stream cancels the wait.
+    pub fn into_stream(self) -> impl Stream<Item = DateTime<Utc>> {
+        self.into_stream_with(SystemClock)
+    }
+
+    /// Returns a stream of the event dates after the current time of the clock, yielding
+    /// every date once the clock reaches it.
+    ///
+    /// The stream sleeps until the next event, up to a minute at once, and then reads the
+    /// clock again, so a `MockClock` moved forward by a test is noticed within a minute,
+    /// and the events it already reached are yielded right away.
+    pub fn into_stream_with<C: Clock>(self, clock: C) -> impl Stream<Item = DateTime<Utc>> {
+        let from = clock.now();
+        stream::unfold(
+            (self, clock, from),
+            |(recurrence, clock, from)| async move {
+                loop {
+                    let next = recurrence.next_from(&from)?;
+                    let now = clock.now();
+                    if next <= now {
+                        return Some((next, (recurrence, clock, next)));
+                    }
+                    let wait = (next - now).min(MAX_SLEEP).to_std().unwrap_or_default();
+                    tokio::time::sleep(wait).await;
+                }
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod test_scheduler {
+    use crate::clock::MockClock;
+    use crate::{Frequency, Recurrence};
+    use chrono::{DateTime, Duration, Utc};
+    use futures_util::StreamExt;
+    use std::num::NonZeroU32;
+    use std::str::FromStr;
+
+    #[tokio::test]
+    async fn yields_the_events_once_reached() {
+        let recurrence =
+            Recurrence::from_str("FREQ=SECONDLY;INTERVAL=1;DTSTART=2023-01-01T00:00:00Z").unwrap();
+        let created_at = Utc::now();
+        let events: Vec<_> = recurrence.into_stream().take(2).collect().await;
+        assert!(events[0] > created_at);
+        assert_eq!(events[1] - events[0], Duration::seconds(1));
+        assert!(Utc::now() >= events[1]);
+    }
+
+    #[tokio::test]
+    async fn ends_after_the_last_event() {
+        let end = Utc::now() - Duration::days(1);
+        let recurrence = Recurrence::new(
+            Frequency::Daily {
+                interval: NonZeroU32::new(1).unwrap(),
+                by_day: vec![],
+                by_time: vec![],
+            },
+            end - Duration::days(10),
+            Some(end),
+            None,
+        )
+        .unwrap();
+        let events: Vec<_> = recurrence.into_stream().collect().await;
+        assert!(events.is_empty());
+    }
+
+    #[tokio::test]
+    async fn yields_the_events_reached_by_the_clock() {
+        let recurrence =
+            Recurrence::from_str("FREQ=HOURLY;INTERVAL=1;DTSTART=2023-01-01T00:00:00Z").unwrap();
+        let clock = MockClock::new(DateTime::<Utc>::from_str("2023-01-01T00:30:00Z").unwrap());
+        let mut events = Box::pin(recurrence.into_stream_with(clock.clone()));
+        clock.advance(Duration::hours(3));
+        let mut reached = vec![];
+        for _ in 0..3 {
+            reached.push(events.next().await.unwrap());
+        }
+        assert_eq!(
+            reached,
+            vec![
+                DateTime::<Utc>::from_str("2023-01-01T01:00:00Z").unwrap(),
+                DateTime::<Utc>::from_str("2023-01-01T02:00:00Z").unwrap(),
+                DateTime::<Utc>::from_str("2023-01-01T03:00:00Z").unwrap(),
+            ]
+        );
+    }
+}
@@ -0,0 +1,9 @@
+//! Helpers shared by the tests of the crate.
+
+use chrono::{DateTime, Utc};
+use core::str::FromStr;
+
+/// Parses an RFC 3339 date in UTC, panicking when it is not valid.
+pub(crate) fn date(value: &str) -> DateTime<Utc> {
+    DateTime::<Utc>::from_str(value).unwrap()
+}
@@ -0,0 +1,482 @@
+//! Property-based testing utilities, available with the `proptest` feature.
+//!
+//! The strategies generate valid frequencies and recurrences in their canonical form,
+//! so they can be used to verify that serializing and parsing them again gives the
+//! same value, and that every generated event matches the recurrence rules.
+//!
+//! Examples:
+//! ```
+//! use std::str::FromStr;
+//! use proptest::prelude::*;
+//! use rrules::testing::arb_recurrence;
+//! use rrules::Recurrence;
+//!
+//! proptest!(|(recurrence in arb_recurrence())| {
+//!     let parsed = Recurrence::from_str(&recurrence.to_string()).unwrap();
+//!     prop_assert_eq!(parsed, recurrence);
+//! });
+//! ```
+
+use crate::{Frequency, MonthlyDate, NthWeekday, OverflowPolicy, Recurrence, Time};
+use chrono::{DateTime, Duration, Month, Utc, Weekday};
+use core::num::NonZeroU32;
+use proptest::prelude::*;
+use proptest::sample::subsequence;
+
+const WEEKDAYS: [Weekday; 7] = [
+    Weekday::Mon,
+    Weekday::Tue,
+    Weekday::Wed,
+    Weekday::Thu,
+    Weekday::Fri,
+    Weekday::Sat,
+    Weekday::Sun,
+];
+
+/// Generates intervals between 1 and 10.
+pub fn arb_interval() -> impl Strategy<Value = NonZeroU32> {
+    (1..=10u32).prop_map(|interval| NonZeroU32::new(interval).unwrap())
+}
+
+/// Generates up to 3 different days of the week.
+pub fn arb_weekdays() -> impl Strategy<Value = Vec<Weekday>> {
+    subsequence(WEEKDAYS.to_vec(), 0..=3)
+}
+
+/// Generates up to 3 different sorted times of the day, with or without seconds.
+pub fn arb_times() -> impl Strategy<Value = Vec<Time>> {
+    let second = prop_oneof![Just(0), 0..60];
+    prop::collection::btree_set((0..24, 0..60, second), 0..=3).prop_map(|times| {
+        times
+            .into_iter()
+            .map(|(hour, minute, second)| Time {
+                hour,
+                minute,
+                second,
+            })
+            .collect()
+    })
+}
+
+/// Generates optional ranges of hours, which start before they end.
+pub fn arb_hour_range() -> impl Strategy<Value = Option<(u8, u8)>> {
+    proptest::option::of((0..24u8).prop_flat_map(|start| (Just(start), start + 1..=24)))
+}
+
+/// Generates between 1 and 3 different days of the week with a time, sorted from Monday.
+pub fn arb_day_times() -> impl Strategy<Value = Vec<(Weekday, Time)>> {
+    prop::collection::btree_set((0..7usize, 0..24, 0..60), 1..=3).prop_map(|day_times| {
+        day_times
+            .into_iter()
+            .map(|(weekday, hour, minute)| {
+                let time = Time {
+                    hour,
+                    minute,
+                    second: 0,
+                };
+                (WEEKDAYS[weekday], time)
+            })
+            .collect()
+    })
+}
+
+/// Generates up to 3 different days of the month.
+pub fn arb_month_days() -> impl Strategy<Value = Vec<i32>> {
+    subsequence((1..=31).collect::<Vec<i32>>(), 0..=3)
+}
+
+/// Generates up to 2 different sorted weekdays with ordinals up to the given week number.
+pub fn arb_nth_weekdays(max_week_number: i32) -> impl Strategy<Value = Vec<NthWeekday>> {
+    prop::collection::btree_set((0..7usize, 1..=max_week_number), 0..=2).prop_map(|days| {
+        let mut nth_weekdays: Vec<NthWeekday> = days
+            .into_iter()
+            .map(|(weekday, week_number)| NthWeekday::new(WEEKDAYS[weekday], week_number))
+            .collect();
+        nth_weekdays.sort();
+        nth_weekdays
+    })
+}
+
+/// Generates between 1 and 3 different sorted months.
+pub fn arb_months() -> impl Strategy<Value = Vec<Month>> {
+    subsequence((1..=12u8).collect::<Vec<u8>>(), 1..=3).prop_map(|months| {
+        months
+            .into_iter()
+            .map(|month| Month::try_from(month).unwrap())
+            .collect()
+    })
+}
+
+/// Generates up to 3 different sorted dates of the year, on days that exist every year.
+pub fn arb_monthly_dates() -> impl Strategy<Value = Vec<MonthlyDate>> {
+    prop::collection::btree_set((1..=12i32, 1..=28), 0..=3).prop_map(|dates| {
+        dates
+            .into_iter()
+            .map(|(month, day)| MonthlyDate {
+                month: Month::try_from(month as u8).unwrap(),
+                day,
+            })
+            .collect()
+    })
+}
+
+/// Generates every overflow policy.
+pub fn arb_overflow_policy() -> impl Strategy<Value = OverflowPolicy> {
+    prop_oneof![
+        Just(OverflowPolicy::Skip),
+        Just(OverflowPolicy::ClampToLastDay),
+        Just(OverflowPolicy::NextValid),
+    ]
+}
+
+/// Generates valid frequencies of every type.
+pub fn arb_frequency() -> impl Strategy<Value = Frequency> {
+    prop_oneof![
+        (arb_interval(), arb_weekdays(), arb_times()).prop_map(|(interval, by_day, by_time)| {
+            Frequency::Secondly {
+                interval,
+                by_day,
+                by_time,
+            }
+        }),
+        (
+            arb_interval(),
+            arb_weekdays(),
+            arb_times(),
+            arb_hour_range()
+        )
+            .prop_map(
+                |(interval, by_day, by_time, by_hour_range)| Frequency::Minutely {
+                    interval,
+                    by_day,
+                    by_time,
+                    by_hour_range,
+                }
+            ),
+        (
+            arb_interval(),
+            arb_weekdays(),
+            arb_times(),
+            arb_hour_range()
+        )
+            .prop_map(
+                |(interval, by_day, by_time, by_hour_range)| Frequency::Hourly {
+                    interval,
+                    by_day,
+                    by_time,
+                    by_hour_range,
+                }
+            ),
+        (arb_interval(), arb_weekdays(), arb_times()).prop_map(|(interval, by_day, by_time)| {
+            Frequency::Daily {
+                interval,
+                by_day,
+                by_time,
+            }
+        }),
+        arb_interval().prop_map(|interval| Frequency::BusinessDaily { interval }),
+        (arb_interval(), arb_weekdays(), arb_times()).prop_map(|(interval, by_day, by_time)| {
+            Frequency::Weekly {
+                interval,
+                by_day,
+                by_time,
+                by_day_time: vec![],
+            }
+        }),
+        (arb_interval(), arb_day_times()).prop_map(|(interval, by_day_time)| {
+            Frequency::Weekly {
+                interval,
+                by_day: vec![],
+                by_time: vec![],
+                by_day_time,
+            }
+        }),
+        (
+            arb_interval(),
+            arb_month_days(),
+            arb_weekdays(),
+            arb_nth_weekdays(4),
+            arb_times(),
+            arb_overflow_policy()
+        )
+            .prop_map(
+                |(interval, by_month_day, by_day, nth_weekdays, by_time, overflow)| {
+                    Frequency::Monthly {
+                        interval,
+                        by_month_day,
+                        by_day,
+                        nth_weekdays,
+                        by_time,
+                        overflow,
+                    }
+                }
+            ),
+        (
+            arb_interval(),
+            arb_monthly_dates(),
+            arb_times(),
+            arb_overflow_policy()
+        )
+            .prop_map(|(interval, by_monthly_date, by_time, overflow)| {
+                Frequency::Yearly {
+                    interval,
+                    by_month: vec![],
+                    by_monthly_date,
+                    nth_weekdays: vec![],
+                    by_week_number: vec![],
+                    by_time,
+                    overflow,
+                }
+            }),
+        (arb_interval(), arb_nth_weekdays(52), arb_overflow_policy()).prop_map(
+            |(interval, nth_weekdays, overflow)| Frequency::Yearly {
+                interval,
+                by_month: vec![],
+                by_monthly_date: vec![],
+                nth_weekdays,
+                by_week_number: vec![],
+                by_time: vec![],
+                overflow,
+            }
+        ),
+        (arb_interval(), arb_months(), arb_nth_weekdays(4)).prop_map(
+            |(interval, by_month, nth_weekdays)| Frequency::Yearly {
+                interval,
+                by_month,
+                by_monthly_date: vec![],
+                nth_weekdays,
+                by_week_number: vec![],
+                by_time: vec![],
+                overflow: OverflowPolicy::Skip,
+            }
+        ),
+    ]
+}
+
+/// Generates dates between 2000 and 2100, without fractions of seconds.
+pub fn arb_date() -> impl Strategy<Value = DateTime<Utc>> {
+    (946_684_800i64..4_102_444_800)
+        .prop_map(|timestamp| DateTime::<Utc>::from_timestamp(timestamp, 0).unwrap())
+}
+
+/// Generates valid recurrences, with or without an end date.
+///
+/// The start dates of sub-daily frequencies with times are moved to their first time, so the
+/// interval reaches it.
+pub fn arb_recurrence() -> impl Strategy<Value = Recurrence> {
+    (arb_frequency(), arb_date(), prop::option::of(1..=3650i64)).prop_map(
+        |(frequency, start, days)| {
+            let start = match (&frequency, frequency.by_time().first()) {
+                (
+                    Frequency::Secondly { .. }
+                    | Frequency::Minutely { .. }
+                    | Frequency::Hourly { .. },
+                    Some(time),
+                ) => start
+                    .date_naive()
+                    .and_hms_opt(time.hour.unsigned_abs(), time.minute.unsigned_abs(), 0)
+                    .unwrap()
+                    .and_utc(),
+                _ => start,
+            };
+            let end = days.map(|days| start + Duration::days(days));
+            Recurrence::new(frequency, start, end, None).unwrap()
+        },
+    )
+}
+
+#[cfg(test)]
+mod test_testing {
+    use crate::testing::{arb_frequency, arb_recurrence};
+    use crate::{Frequency, Recurrence};
+    use chrono::Duration;
+    use proptest::prelude::*;
+    use proptest::sample::select;
+    use std::str::FromStr;
+
+    const KEYS: [&str; 24] = [
+        "FREQ",
+        "INTERVAL",
+        "BYDAY",
+        "BYMONTH",
+        "BYMONTHDAY",
+        "X-BYMONTHDATE",
+        "BYWEEKNO",
+        "BYTIME",
+        "BYHOUR",
+        "BYMINUTE",
+        "BYSECOND",
+        "X-OVERFLOW",
+        "SKIP",
+        "X-BUSINESS",
+        "X-BYDAYTIME",
+        "DTSTART",
+        "DTEND",
+        "DURATION",
+        "COUNT",
+        "EXDATE",
+        "X-NOTBEFORE",
+        "UNTIL",
+        "X-NAME",
+        "",
+    ];
+
+    const FREQUENCIES: [&str; 7] = [
+        "SECONDLY", "MINUTELY", "HOURLY", "DAILY", "WEEKLY", "MONTHLY", "YEARLY",
+    ];
+
+    /// Numbers at the edges of the ranges of the keys, or just outside them.
+    const NUMBERS: [&str; 9] = [
+        "0",
+        "1",
+        "-1",
+        "12",
+        "31",
+        "32",
+        "53",
+        "2147483647",
+        "-2147483648",
+    ];
+
+    const DATES: [&str; 5] = [
+        "2023-01-31T09:00:00Z",
+        "9999-12-31T23:59:59Z",
+        "+262142-12-31T23:59:59Z",
+        "-262143-01-01T00:00:00Z",
+        "20230101T000000Z",
+    ];
+
+    const VALUES: [&str; 18] = [
+        "MO",
+        "1MO",
+        "5FR",
+        "-1SU",
+        "0TU",
+        "09:30",
+        "23:59:59",
+        "24:00",
+        "JAN31",
+        "FEB29",
+        "TH14:00",
+        "CLAMP",
+        "NEXT",
+        "FORWARD",
+        "TRUE",
+        "PT1H",
+        "P2147483647W",
+        "",
+    ];
+
+    /// Generates rule strings made of known keys with edge values, and random noise.
+    /// Most of them start with a frequency, an interval and a start date, so they can be parsed.
+    fn arb_rule_string() -> impl Strategy<Value = String> {
+        let head = prop::option::weighted(
+            0.9,
+            (
+                select(FREQUENCIES.to_vec()),
+                select(NUMBERS.to_vec()),
+                select(DATES.to_vec()),
+            ),
+        )
+        .prop_map(|head| match head {
+            Some((freq, interval, start)) => {
+                format!("FREQ={freq};INTERVAL={interval};DTSTART={start};")
+            }
+            None => String::new(),
+        });
+        let value = prop_oneof![
+            select([&FREQUENCIES[..], &NUMBERS, &DATES, &VALUES].concat()).prop_map(String::from),
+            "[A-Z0-9:,=+-]{0,8}"
+        ];
+        let part = (select(KEYS.to_vec()), prop::collection::vec(value, 1..=3))
+            .prop_map(|(key, values)| format!("{key}={}", values.join(",")));
+        (head, prop::collection::vec(part, 0..=4)).prop_map(|(head, parts)| head + &parts.join(";"))
+    }
+
+    proptest! {
+        #[test]
+        fn parsing_and_iterating_never_panics(
+            rule in arb_rule_string(),
+            seconds in -100_000_000..100_000_000i64,
+        ) {
+            let _ = Frequency::from_str(&rule).map(|frequency| frequency.to_string());
+            let _ = Recurrence::from_ics_vevent(&format!("BEGIN:VEVENT\nRRULE:{rule}\nEND:VEVENT"));
+            if let Ok(recurrence) = Recurrence::from_str(&rule) {
+                let date = recurrence
+                    .start
+                    .checked_add_signed(Duration::seconds(seconds))
+                    .unwrap_or(recurrence.start);
+                for event in recurrence.iter().take(10) {
+                    prop_assert!(recurrence.contains(&event));
+                }
+                let _ = recurrence.next_from(&date);
+                let _ = recurrence.iter().nth(1000);
+                let _ = recurrence.between(&recurrence.start, &date).take(10).count();
+                let _ = recurrence.split_at(&date);
+                let _ = Recurrence::from_str(&recurrence.to_string());
+                let _ = recurrence.to_ics_vevent();
+            }
+        }
+
+        #[test]
+        fn generated_frequencies_are_valid(frequency in arb_frequency()) {
+            prop_assert!(frequency.is_valid().is_ok());
+        }
+
+        #[test]
+        fn frequency_round_trip(frequency in arb_frequency()) {
+            let parsed = Frequency::from_str(&frequency.to_string()).unwrap();
+            prop_assert_eq!(parsed, frequency);
+        }
+
+        #[test]
+        fn recurrence_round_trip(recurrence in arb_recurrence()) {
+            let parsed = Recurrence::from_str(&recurrence.to_string()).unwrap();
+            prop_assert_eq!(parsed, recurrence);
+        }
+
+        #[test]
+        fn events_match_the_recurrence(recurrence in arb_recurrence()) {
+            for date in recurrence.iter().take(5) {
+                prop_assert!(recurrence.contains(&date));
+                prop_assert!(date >= recurrence.start && recurrence.end().is_none_or(|end| date <= end));
+            }
+        }
+
+        #[test]
+        fn events_keep_the_event_time(recurrence in arb_recurrence()) {
+            if let Some(time) = recurrence.event_time() {
+                for date in recurrence.iter().take(5) {
+                    prop_assert_eq!(date.time(), time);
+                }
+            }
+        }
+
+        #[test]
+        fn next_from_matches_the_iteration(recurrence in arb_recurrence()) {
+            let events: Vec<_> = recurrence.iter().take(5).collect();
+            for pair in events.windows(2) {
+                prop_assert_eq!(recurrence.next_from(&pair[0]), Some(pair[1]));
+            }
+        }
+
+        #[test]
+        fn position_of_matches_the_iteration(recurrence in arb_recurrence()) {
+            for (n, event) in recurrence.iter().take(5).enumerate() {
+                prop_assert_eq!(recurrence.position_of(&event), Some(n));
+            }
+        }
+
+        #[test]
+        fn next_from_any_date_matches_the_iteration(
+            recurrence in arb_recurrence(),
+            seconds in 0..100_000_000i64,
+        ) {
+            let date = recurrence.start + Duration::seconds(seconds);
+            let expected = recurrence.iter().take(1000).find(|event| *event > date);
+            if let Some(expected) = expected {
+                prop_assert_eq!(recurrence.next_from(&date), Some(expected));
+            }
+        }
+    }
+}
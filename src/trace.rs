@@ -0,0 +1,169 @@
+//! Diagnostics of the parsing, the validation and the generation of the events, logged with
+//! the [tracing](https://docs.rs/tracing) crate when the `tracing` feature is enabled.
+//!
+//! Without the feature the macros expand to nothing, so the values they log must also be
+//! used elsewhere.
+
+use core::fmt::Display;
+
+#[cfg(feature = "tracing")]
+macro_rules! debug {
+    ($($arg:tt)*) => {
+        ::tracing::debug!($($arg)*)
+    };
+}
+
+#[cfg(not(feature = "tracing"))]
+macro_rules! debug {
+    ($($arg:tt)*) => {};
+}
+
+#[cfg(feature = "tracing")]
+macro_rules! trace {
+    ($($arg:tt)*) => {
+        ::tracing::trace!($($arg)*)
+    };
+}
+
+#[cfg(not(feature = "tracing"))]
+macro_rules! trace {
+    ($($arg:tt)*) => {};
+}
+
+/// Enters a debug span until the returned guard is dropped.
+#[cfg(feature = "tracing")]
+macro_rules! debug_span {
+    ($($arg:tt)*) => {
+        ::tracing::debug_span!($($arg)*).entered()
+    };
+}
+
+#[cfg(not(feature = "tracing"))]
+macro_rules! debug_span {
+    ($($arg:tt)*) => {
+        $crate::trace::NoSpan
+    };
+}
+
+/// Enters a trace span until the returned guard is dropped.
+#[cfg(feature = "tracing")]
+macro_rules! trace_span {
+    ($($arg:tt)*) => {
+        ::tracing::trace_span!($($arg)*).entered()
+    };
+}
+
+#[cfg(not(feature = "tracing"))]
+macro_rules! trace_span {
+    ($($arg:tt)*) => {
+        $crate::trace::NoSpan
+    };
+}
+
+pub(crate) use {debug, debug_span, trace, trace_span};
+
+/// The guard of the spans when the `tracing` feature is disabled.
+#[cfg(not(feature = "tracing"))]
+pub(crate) struct NoSpan;
+
+/// Logs the error of the result with the message, at the debug level.
+pub(crate) fn debug_error<T, E: Display>(result: Result<T, E>, message: &str) -> Result<T, E> {
+    #[cfg(feature = "tracing")]
+    if let Err(error) = &result {
+        ::tracing::debug!(%error, "{message}");
+    }
+    #[cfg(not(feature = "tracing"))]
+    let _ = message;
+    result
+}
+
+#[cfg(all(test, feature = "tracing"))]
+mod test_trace {
+    use crate::Recurrence;
+    use core::str::FromStr;
+    use std::fmt::Debug;
+    use std::sync::{Arc, Mutex};
+    use tracing::field::{Field, Visit};
+    use tracing::span::{Attributes, Id, Record};
+    use tracing::{Event, Metadata, Subscriber};
+
+    /// Keeps the names of the spans and the messages of the events.
+    #[derive(Clone, Default)]
+    struct Collector {
+        logs: Arc<Mutex<Vec<String>>>,
+    }
+
+    struct Message<'a>(&'a mut String);
+
+    impl Visit for Message<'_> {
+        fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+            if field.name() == "message" {
+                *self.0 = format!("{value:?}");
+            }
+        }
+    }
+
+    impl Subscriber for Collector {
+        fn enabled(&self, _: &Metadata<'_>) -> bool {
+            true
+        }
+
+        fn new_span(&self, span: &Attributes<'_>) -> Id {
+            let mut logs = self.logs.lock().unwrap();
+            logs.push(format!("span {}", span.metadata().name()));
+            Id::from_u64(logs.len() as u64)
+        }
+
+        fn record(&self, _: &Id, _: &Record<'_>) {}
+
+        fn record_follows_from(&self, _: &Id, _: &Id) {}
+
+        fn event(&self, event: &Event<'_>) {
+            let mut message = String::new();
+            event.record(&mut Message(&mut message));
+            self.logs.lock().unwrap().push(message);
+        }
+
+        fn enter(&self, _: &Id) {}
+
+        fn exit(&self, _: &Id) {}
+    }
+
+    fn collect(run: impl FnOnce()) -> Vec<String> {
+        let collector = Collector::default();
+        tracing::subscriber::with_default(collector.clone(), run);
+        let logs = collector.logs.lock().unwrap();
+        logs.clone()
+    }
+
+    #[test]
+    fn logs_parsing_and_validation_errors() {
+        let logs = collect(|| {
+            assert!(
+                Recurrence::from_str("FREQ=DAILY;INTERVAL=0;DTSTART=2023-01-01T00:00:00Z").is_err()
+            );
+        });
+        assert!(logs.contains(&"span parse_recurrence".to_string()));
+        assert!(logs.iter().any(|log| log.starts_with("Invalid recurrence")));
+
+        let logs = collect(|| {
+            let rule = "FREQ=DAILY;INTERVAL=1;DTSTART=2023-01-01T00:00:00Z;DURATION=P2D";
+            assert!(Recurrence::from_str(rule).is_err());
+        });
+        assert!(logs.contains(&"Invalid duration".to_string()));
+    }
+
+    #[test]
+    fn logs_the_months_without_events() {
+        let recurrence = Recurrence::from_str(
+            "FREQ=MONTHLY;INTERVAL=1;BYMONTHDAY=31;DTSTART=2023-01-31T09:00:00Z",
+        )
+        .unwrap();
+        let logs = collect(|| {
+            assert_eq!(recurrence.iter().take(2).count(), 2);
+        });
+        assert!(logs.contains(&"span occurrences".to_string()));
+        assert!(logs.contains(&"No event in the month".to_string()));
+        assert!(logs.iter().filter(|log| *log == "Event").count() == 2);
+    }
+}
@@ -1,328 +1,239 @@
-use crate::frequencies::NthWeekday;
-use chrono::{DateTime, Datelike, Duration, Utc, Weekday};
-use std::ops::{Add, Sub};
+use crate::date_utils::DateUtils;
+use crate::frequencies::{NthWeekday, OverflowPolicy};
+use crate::trace;
+use chrono::{DateTime, Datelike, Duration, Month, Months, NaiveDate, Utc, Weekday};
 
-pub trait DateUtils {
-    fn with_weekday(self, weekday: Weekday) -> Option<Self>
-    where
-        Self: Sized;
-    fn shift_days(&self, days: i64) -> Option<DateTime<Utc>>;
-    fn shift_weeks(self, days: i64) -> Option<Self>
-    where
-        Self: Sized;
-    fn shift_months(self, months: i64) -> Option<Self>
-    where
-        Self: Sized;
-    fn shift_years(self, years: i64) -> Option<Self>
-    where
-        Self: Sized;
-}
-
-impl DateUtils for DateTime<Utc> {
-    fn with_weekday(self, weekday: Weekday) -> Option<Self> {
-        if self.weekday() == weekday {
-            Some(self)
-        } else {
-            let diff = self.weekday().num_days_from_monday() as i64
-                - weekday.num_days_from_monday() as i64;
-            if diff > 0 {
-                Some(self.sub(Duration::days(diff)))
-            } else {
-                Some(self.add(Duration::days(diff.abs())))
-            }
-        }
-    }
-
-    /// Shift the date by the given number of days.
-    fn shift_days(&self, days: i64) -> Option<DateTime<Utc>> {
-        Some(*self + Duration::days(days))
-    }
-
-    fn shift_weeks(self, days: i64) -> Option<Self> {
-        Some(self.add(Duration::days(days * 7)))
-    }
-    fn shift_months(self, months: i64) -> Option<Self> {
-        let mut diff = self.month() as i32 + months as i32;
-
-        // If the months shift is bigger than a year we need to shift the year
-        let years = if diff > 12 {
-            diff / 12
-        } else if diff < 1 {
-            (diff / 12) - 1
-        } else {
-            0
-        };
+/// Maximum number of months jumped looking for an nth weekday.
+/// The Gregorian calendar repeats itself every 400 years.
+pub(crate) const MAX_MONTHLY_JUMPS: usize = 4800;
 
-        diff = match diff {
-            0 => 12,
-            1.. => diff % 12,
-            _ => 12 + (diff % 12) - 1,
-        };
-
-        match self.with_month(diff as u32) {
-            None => None,
-            Some(d) => d.shift_years(years as i64),
-        }
-    }
-    fn shift_years(self, years: i64) -> Option<Self> {
-        self.with_year(self.year() + years as i32)
-    }
-}
-
-/// Check if the given date is the first week of the month.
-pub fn is_first_week(date: &DateTime<Utc>) -> bool {
-    let day = date.day();
-    let weekday = date.weekday();
-    let weekday_num = weekday.num_days_from_sunday() + 1;
-    if day <= 7 && day <= weekday_num {
-        return true;
-    }
-    false
+pub fn get_next_nth_weekday(
+    current_date: &DateTime<Utc>,
+    interval: i64,
+    nth_weekdays: &[NthWeekday],
+) -> Option<DateTime<Utc>> {
+    get_next_aligned_nth_weekday(current_date, current_date, interval, nth_weekdays)
 }
 
-/// Return the nth weekday
+/// Returns the first nth weekday after the current date, in the months that are a whole
+/// number of intervals away from the month of the anchor, keeping the time of the anchor.
 ///
-/// E.g. if the date is 2023-01-09 (Monday), it will return 2
-///   That is equivalent to the 2nd Monday of the month
-pub fn weekday_ordinal(date: &DateTime<Utc>) -> i32 {
-    let mut week_number = 1;
-    let mut tmp = *date;
-    while !is_first_week(&tmp) {
-        let _d = tmp.format("%Y-%m-%d").to_string();
-        tmp = tmp.shift_weeks(-1).unwrap();
-        if tmp.month() != date.month() {
-            break;
-        }
-        week_number += 1;
-    }
-    week_number
-}
-
-pub fn get_next_nth_weekday(
+/// E.g. every 3 months on the 1st Monday from January, the next date after 2023-02-10 is
+/// the 1st Monday of April, whatever the current date is.
+pub fn get_next_aligned_nth_weekday(
     current_date: &DateTime<Utc>,
+    anchor: &DateTime<Utc>,
     interval: i64,
-    nth_weekdays: &Vec<NthWeekday>,
+    nth_weekdays: &[NthWeekday],
 ) -> Option<DateTime<Utc>> {
-    if nth_weekdays.is_empty() {
+    if nth_weekdays.is_empty() || interval < 1 {
         return None;
     }
 
-    let ordered_weekdays = order_nth_weekdays(nth_weekdays);
-
-    let weekday = current_date.weekday();
-    let week_number = weekday_ordinal(current_date);
-
-    for nth_weekday in &ordered_weekdays {
-        if nth_weekday.week_number == week_number
-            && nth_weekday.weekday.num_days_from_sunday() > weekday.num_days_from_sunday()
-        {
-            return Some(current_date.add(Duration::days(
-                (nth_weekday.weekday.num_days_from_sunday() - weekday.num_days_from_sunday())
-                    as i64,
-            )));
-        } else if nth_weekday.week_number > week_number {
-            let days_diff = nth_weekday.weekday.num_days_from_sunday() as i64
-                - weekday.num_days_from_sunday() as i64;
-            let weeks_diff = nth_weekday.week_number - week_number;
-            return current_date
-                .shift_days(days_diff)?
-                .shift_weeks(weeks_diff as i64);
+    // Look for a date left in the month window of the current date before moving to the
+    // next ones, skipping the months without any of the weekdays
+    let mut month_start = aligned_month_start(anchor, current_date, interval)?;
+    for _ in 0..=MAX_MONTHLY_JUMPS {
+        let month = MonthWeekdays::new(&month_start)?;
+        let next_date = nth_weekdays
+            .iter()
+            .filter_map(|nth_weekday| month.nth(nth_weekday))
+            .filter(|date| date > current_date)
+            .min();
+        if next_date.is_some() {
+            return next_date;
         }
-    }
-    // Return first supported nth weekday of next month
-    let mut res = current_date.with_day(1)?.shift_months(interval)?;
-    let weekday_num_diff = ordered_weekdays[0].week_number as i64 - 1;
-    res = res.shift_weeks(weekday_num_diff)?;
-
-    if res.weekday() == ordered_weekdays[0].weekday {
-        return Some(res);
-    }
-
-    let d = res.with_weekday(ordered_weekdays[0].weekday)?;
-    if d < res {
-        return d.shift_weeks(1);
-    }
-    Some(d)
+        trace::trace!(month = %month_start.date_naive(), "No nth weekday in the month");
+        month_start = month_start.shift_months_with(interval, OverflowPolicy::ClampToLastDay)?;
+    }
+    trace::debug!(
+        months = MAX_MONTHLY_JUMPS,
+        "No nth weekday in the following months"
+    );
+    None
 }
 
-fn order_nth_weekdays(nth_weekdays: &[NthWeekday]) -> Vec<NthWeekday> {
-    let mut result = nth_weekdays.to_owned();
-    result.sort();
-    Vec::from(&result[..])
+/// Returns the first day of the latest month before or at the month of the date that is a
+/// whole number of intervals away from the month of the anchor, keeping the time of the anchor.
+pub fn aligned_month_start(
+    anchor: &DateTime<Utc>,
+    date: &DateTime<Utc>,
+    interval: i64,
+) -> Option<DateTime<Utc>> {
+    let month_index = |d: &DateTime<Utc>| d.year() as i64 * 12 + d.month0() as i64;
+    let months = month_index(date) - month_index(anchor);
+    anchor.with_day(1)?.shift_months_with(
+        months - months.rem_euclid(interval),
+        OverflowPolicy::ClampToLastDay,
+    )
 }
 
-#[cfg(test)]
-mod shift_days {
-    use super::*;
-    use std::str::FromStr;
+/// The layout of the weekdays of a month, computed once to find all its nth weekdays.
+struct MonthWeekdays {
+    first_day: DateTime<Utc>,
+    /// Days from the first day of the month until the first occurrence of each weekday,
+    /// indexed by the number of days from Monday
+    first_weekdays: [i64; 7],
+    days_in_month: i64,
+}
 
-    #[test]
-    fn test_shift_days() {
-        let date = DateTime::<Utc>::from_str("2019-01-01T00:00:00Z").unwrap();
-        let result = date.shift_days(1).unwrap();
-        assert_eq!(result.day(), 2);
+impl MonthWeekdays {
+    fn new(date: &DateTime<Utc>) -> Option<Self> {
+        let first_day = date.with_day(1)?;
+        let first_weekday = first_day.weekday().num_days_from_monday() as i64;
+        let mut first_weekdays = [0; 7];
+        for (weekday, days) in first_weekdays.iter_mut().enumerate() {
+            *days = (7 + weekday as i64 - first_weekday) % 7;
+        }
+        let next_month = NaiveDate::from_ymd_opt(first_day.year(), first_day.month(), 1)?
+            .checked_add_months(Months::new(1))?;
+        let days_in_month = (next_month - first_day.date_naive()).num_days();
+        Some(MonthWeekdays {
+            first_day,
+            first_weekdays,
+            days_in_month,
+        })
+    }
+
+    fn nth(&self, nth_weekday: &NthWeekday) -> Option<DateTime<Utc>> {
+        if nth_weekday.week_number < 1 {
+            return None;
+        }
+        let days = self.first_weekdays[nth_weekday.weekday.num_days_from_monday() as usize]
+            + (nth_weekday.week_number as i64 - 1) * 7;
+        if days >= self.days_in_month {
+            return None;
+        }
+        self.first_day.checked_add_signed(Duration::days(days))
     }
+}
 
-    #[test]
-    fn test_shift_days_backwards() {
-        let date = DateTime::<Utc>::from_str("2019-01-01T00:00:00Z").unwrap();
-        let result = date.shift_days(-1).unwrap();
-        assert_eq!(result.day(), 31);
+/// Return the date of the given weekday in the ISO week of the year.
+///
+/// Negative week numbers count from the last ISO week of the year, E.g. -1 is the last week.
+pub fn iso_week_date(year: i32, week: i32, weekday: Weekday) -> Option<NaiveDate> {
+    let weeks_in_year = NaiveDate::from_ymd_opt(year, 12, 28)?.iso_week().week() as i32;
+    let week = if week < 0 {
+        weeks_in_year + week + 1
+    } else {
+        week
+    };
+    if week < 1 || week > weeks_in_year {
+        return None;
     }
+    NaiveDate::from_isoywd_opt(year, week as u32, weekday)
 }
 
-#[cfg(test)]
-mod test_shift_weeks {
-    use super::*;
-    use std::str::FromStr;
-
-    #[test]
-    fn test_shift_weeks() {
-        let date = DateTime::<Utc>::from_str("2019-01-01T00:00:00Z").unwrap();
-        let result = date.shift_weeks(1).unwrap();
-        assert_eq!(result.day(), 8);
+/// Return the nth weekday of the year
+///
+/// E.g. the 20th Monday of 2023 is 2023-05-15
+pub fn nth_weekday_of_year(year: i32, nth_weekday: &NthWeekday) -> Option<NaiveDate> {
+    if nth_weekday.week_number < 1 {
+        return None;
     }
-
-    #[test]
-    fn test_shift_weeks_2() {
-        let date = DateTime::<Utc>::from_str("2019-01-01T00:00:00Z").unwrap();
-        let result = date.shift_weeks(2).unwrap();
-        assert_eq!(result.day(), 15);
+    let first_day = NaiveDate::from_ymd_opt(year, 1, 1)?;
+    let days_until_weekday = (7 + nth_weekday.weekday.num_days_from_monday() as i64
+        - first_day.weekday().num_days_from_monday() as i64)
+        % 7;
+    let date = first_day
+        .checked_add_signed(Duration::days(days_until_weekday))?
+        .checked_add_signed(Duration::weeks(nth_weekday.week_number as i64 - 1))?;
+    if date.year() != year {
+        return None;
     }
+    Some(date)
+}
 
-    #[test]
-    fn test_shift_back() {
-        let date = DateTime::<Utc>::from_str("2019-01-01T00:00:00Z").unwrap();
-        let result = date.shift_weeks(-1).unwrap();
-        assert_eq!(result.day(), 25);
-    }
+/// Return the nth weekday of the month
+///
+/// E.g. the 4th Thursday of November 2023 is 2023-11-23
+pub fn nth_weekday_of_month(
+    year: i32,
+    month: Month,
+    nth_weekday: &NthWeekday,
+) -> Option<NaiveDate> {
+    let n = u8::try_from(nth_weekday.week_number).ok()?;
+    NaiveDate::from_weekday_of_month_opt(year, month.number_from_month(), nth_weekday.weekday, n)
 }
 
 #[cfg(test)]
-mod tests {
+mod test_nth_weekdays {
     use super::*;
     use std::str::FromStr;
 
     #[test]
-    fn test_with_weekday() {
-        let date = DateTime::<Utc>::from_str("2019-01-01T00:00:00Z").unwrap();
-        assert_eq!(date.weekday(), Weekday::Tue);
-        let result = date.with_weekday(Weekday::Mon).unwrap();
-        assert_eq!(result.day(), 31);
-        assert_eq!(result.month(), 12);
-    }
-
-    #[test]
-    fn test_shift_month() {
-        let date = DateTime::<Utc>::from_str("2019-01-01T00:00:00Z").unwrap();
-        let result = date.shift_months(1).unwrap();
-        assert_eq!(result.month(), 2);
-    }
-
-    #[test]
-    fn test_shift_month_2() {
-        let date = DateTime::<Utc>::from_str("2019-01-01T00:00:00Z").unwrap();
-        let result = date.shift_months(2).unwrap();
-        assert_eq!(result.month(), 3);
-    }
-
-    #[test]
-    fn test_shift_month_to_next_year() {
-        let date = DateTime::<Utc>::from_str("2019-01-01T00:00:00Z").unwrap();
-        let result = date.shift_months(14).unwrap();
-        assert_eq!(result.month(), 3);
-    }
-
-    #[test]
-    fn test_shift_backwards() {
-        let date = DateTime::<Utc>::from_str("2019-05-01T00:00:00Z").unwrap();
-        let result = date.shift_months(-1).unwrap();
-        assert_eq!(result.month(), 4);
-    }
-
-    #[test]
-    fn test_shift_to_previous_year() {
-        let date = DateTime::<Utc>::from_str("2019-01-01T00:00:00Z").unwrap();
-        let result = date.shift_months(-1).unwrap();
-        assert_eq!(result.month(), 12);
-    }
-
-    #[test]
-    fn test_shift_to_previous_year_2() {
-        let date = DateTime::<Utc>::from_str("2019-01-01T00:00:00Z").unwrap();
-        let result = date.shift_months(-14).unwrap();
-        assert_eq!(result.month(), 10);
-    }
-
-    #[test]
-    fn test_get_ordinal_number_of_the_week() {
-        let date = DateTime::<Utc>::from_str("2023-02-06T00:00:00Z").unwrap();
-        let num = weekday_ordinal(&date);
-        assert_eq!(num, 1);
+    fn test_month_weekdays() {
+        let date = DateTime::<Utc>::from_str("2023-01-20T10:00:00Z").unwrap();
+        let month = MonthWeekdays::new(&date).unwrap();
+        let result = month.nth(&NthWeekday::new(Weekday::Mon, 2));
+        assert_eq!(
+            result.unwrap(),
+            DateTime::<Utc>::from_str("2023-01-09T10:00:00Z").unwrap()
+        );
+        assert!(month.nth(&NthWeekday::new(Weekday::Mon, 5)).is_some());
+        assert!(month.nth(&NthWeekday::new(Weekday::Thu, 5)).is_none());
     }
 
     #[test]
-    fn test_get_weekday_ordinal_last() {
-        let date = DateTime::<Utc>::from_str("2023-02-28T00:00:00Z").unwrap();
-        let num = weekday_ordinal(&date);
-        assert_eq!(num, 4);
+    fn test_get_next_nth_weekday_in_a_later_week() {
+        let date = DateTime::<Utc>::from_str("2048-04-12T00:00:00Z").unwrap();
+        let result = get_next_nth_weekday(&date, 2, &[NthWeekday::new(Weekday::Sat, 4)]);
+        assert_eq!(
+            result.unwrap(),
+            DateTime::<Utc>::from_str("2048-04-25T00:00:00Z").unwrap()
+        );
     }
-}
-
-#[cfg(test)]
-mod test_shift_years {
-    use super::*;
-    use std::str::FromStr;
 
     #[test]
-    fn test_shift_years() {
-        let date = DateTime::<Utc>::from_str("2019-01-01T00:00:00Z").unwrap();
-        let result = date.shift_years(1).unwrap();
-        assert_eq!(result.year(), 2020);
+    fn test_get_next_aligned_nth_weekday() {
+        // Every 3 months on the 1st Monday from January 2023
+        let anchor = DateTime::<Utc>::from_str("2023-01-02T09:00:00Z").unwrap();
+        let first_monday = [NthWeekday::new(Weekday::Mon, 1)];
+        for (date, expected) in [
+            ("2023-01-02T09:00:00Z", "2023-04-03T09:00:00Z"),
+            ("2023-02-10T00:00:00Z", "2023-04-03T09:00:00Z"),
+            ("2023-03-31T23:59:59Z", "2023-04-03T09:00:00Z"),
+            ("2023-04-03T09:00:00Z", "2023-07-03T09:00:00Z"),
+            ("2024-12-01T00:00:00Z", "2025-01-06T09:00:00Z"),
+        ] {
+            let date = DateTime::<Utc>::from_str(date).unwrap();
+            assert_eq!(
+                get_next_aligned_nth_weekday(&date, &anchor, 3, &first_monday),
+                Some(DateTime::<Utc>::from_str(expected).unwrap()),
+                "after {date}"
+            );
+        }
     }
 
     #[test]
-    fn test_shift_years_backwards() {
-        let date = DateTime::<Utc>::from_str("2019-01-01T00:00:00Z").unwrap();
-        let result = date.shift_years(-1).unwrap();
-        assert_eq!(result.year(), 2018);
-    }
-}
-
-#[cfg(test)]
-mod test_nth_weekdays {
-    use super::*;
-    use std::str::FromStr;
-
-    #[test]
-    fn test_order_nth_weekdays() {
-        let weekdays = vec![
-            NthWeekday::new(Weekday::Tue, 3),
-            NthWeekday::new(Weekday::Mon, 3),
-            NthWeekday::new(Weekday::Wed, 1),
-        ];
-        let result = order_nth_weekdays(&weekdays);
-        assert_eq!(
-            result,
-            vec![
-                NthWeekday::new(Weekday::Wed, 1),
-                NthWeekday::new(Weekday::Mon, 3),
-                NthWeekday::new(Weekday::Tue, 3),
-            ]
-        )
+    fn test_aligned_month_start() {
+        let anchor = DateTime::<Utc>::from_str("2023-05-20T10:30:00Z").unwrap();
+        for (date, expected) in [
+            ("2023-05-31T00:00:00Z", "2023-05-01T10:30:00Z"),
+            ("2023-06-15T00:00:00Z", "2023-05-01T10:30:00Z"),
+            ("2023-09-01T00:00:00Z", "2023-09-01T10:30:00Z"),
+            ("2023-03-01T00:00:00Z", "2023-01-01T10:30:00Z"),
+        ] {
+            let date = DateTime::<Utc>::from_str(date).unwrap();
+            assert_eq!(
+                aligned_month_start(&anchor, &date, 4),
+                Some(DateTime::<Utc>::from_str(expected).unwrap()),
+                "window of {date}"
+            );
+        }
     }
 
     #[test]
     fn test_get_next_nth_weekday_none() {
         let date = DateTime::<Utc>::from_str("2023-01-09T00:00:00Z").unwrap();
-        let result = get_next_nth_weekday(&date, 1, &vec![]);
+        let result = get_next_nth_weekday(&date, 1, &[]);
         assert!(result.is_none());
     }
 
     #[test]
     fn test_get_next_nth_weekday_base_case() {
         let date = DateTime::<Utc>::from_str("2023-01-01T00:00:00Z").unwrap();
-        let result = get_next_nth_weekday(&date, 1, &vec![NthWeekday::new(Weekday::Mon, 1)]);
+        let result = get_next_nth_weekday(&date, 1, &[NthWeekday::new(Weekday::Mon, 1)]);
         assert_eq!(
             result.unwrap(),
             DateTime::<Utc>::from_str("2023-01-02T00:00:00Z").unwrap()
@@ -335,7 +246,7 @@ mod test_nth_weekdays {
         let result = get_next_nth_weekday(
             &date,
             1,
-            &vec![
+            &[
                 NthWeekday::new(Weekday::Mon, 3),
                 NthWeekday::new(Weekday::Tue, 2),
             ],
@@ -348,7 +259,7 @@ mod test_nth_weekdays {
         let result = get_next_nth_weekday(
             &result.unwrap(),
             1,
-            &vec![
+            &[
                 NthWeekday::new(Weekday::Tue, 2),
                 NthWeekday::new(Weekday::Mon, 3),
             ],
@@ -362,7 +273,7 @@ mod test_nth_weekdays {
     #[test]
     fn test_get_next_nth_weekday_when_first_day_of_next_month() {
         let date = DateTime::<Utc>::from_str("2022-12-31T00:00:00Z").unwrap();
-        let result = get_next_nth_weekday(&date, 1, &vec![NthWeekday::new(Weekday::Mon, 1)]);
+        let result = get_next_nth_weekday(&date, 1, &[NthWeekday::new(Weekday::Mon, 1)]);
         assert_eq!(
             result.unwrap(),
             DateTime::<Utc>::from_str("2023-01-02T00:00:00Z").unwrap()
@@ -372,7 +283,7 @@ mod test_nth_weekdays {
     #[test]
     fn test_get_next_nth_weekday_when_first_day_of_the_year() {
         let date = DateTime::<Utc>::from_str("2023-01-01T00:00:00Z").unwrap();
-        let result = get_next_nth_weekday(&date, 1, &vec![NthWeekday::new(Weekday::Wed, 1)]);
+        let result = get_next_nth_weekday(&date, 1, &[NthWeekday::new(Weekday::Wed, 1)]);
         assert_eq!(
             result.unwrap(),
             DateTime::<Utc>::from_str("2023-01-04T00:00:00Z").unwrap()
@@ -382,10 +293,59 @@ mod test_nth_weekdays {
     #[test]
     fn test_get_next_nth_weekday_when_last_day_of_the_month() {
         let date = DateTime::<Utc>::from_str("2023-01-31T00:00:00Z").unwrap();
-        let result = get_next_nth_weekday(&date, 1, &vec![NthWeekday::new(Weekday::Wed, 1)]);
+        let result = get_next_nth_weekday(&date, 1, &[NthWeekday::new(Weekday::Wed, 1)]);
         assert_eq!(
             result.unwrap(),
             DateTime::<Utc>::from_str("2023-02-01T00:00:00Z").unwrap()
         );
     }
 }
+
+#[cfg(test)]
+mod test_yearly_weekdays {
+    use super::*;
+
+    #[test]
+    fn test_iso_week_date() {
+        let result = iso_week_date(2023, 20, Weekday::Mon).unwrap();
+        assert_eq!(result, NaiveDate::from_ymd_opt(2023, 5, 15).unwrap());
+    }
+
+    #[test]
+    fn test_iso_week_date_last_week() {
+        let result = iso_week_date(2023, -1, Weekday::Mon).unwrap();
+        assert_eq!(result, NaiveDate::from_ymd_opt(2023, 12, 25).unwrap());
+    }
+
+    #[test]
+    fn test_iso_week_date_out_of_range() {
+        assert!(iso_week_date(2023, 53, Weekday::Mon).is_none());
+        assert!(iso_week_date(2023, 0, Weekday::Mon).is_none());
+    }
+
+    #[test]
+    fn test_nth_weekday_of_year() {
+        let result = nth_weekday_of_year(2023, &NthWeekday::new(Weekday::Mon, 20)).unwrap();
+        assert_eq!(result, NaiveDate::from_ymd_opt(2023, 5, 15).unwrap());
+
+        let result = nth_weekday_of_year(2023, &NthWeekday::new(Weekday::Sun, 1)).unwrap();
+        assert_eq!(result, NaiveDate::from_ymd_opt(2023, 1, 1).unwrap());
+    }
+
+    #[test]
+    fn test_nth_weekday_of_month() {
+        let thanksgiving = NthWeekday::new(Weekday::Thu, 4);
+        let result = nth_weekday_of_month(2023, Month::November, &thanksgiving).unwrap();
+        assert_eq!(result, NaiveDate::from_ymd_opt(2023, 11, 23).unwrap());
+        let fifth_monday = NthWeekday::new(Weekday::Mon, 5);
+        assert!(nth_weekday_of_month(2023, Month::February, &fifth_monday).is_none());
+        assert!(
+            nth_weekday_of_month(2023, Month::May, &NthWeekday::new(Weekday::Mon, 0)).is_none()
+        );
+    }
+
+    #[test]
+    fn test_nth_weekday_of_year_out_of_range() {
+        assert!(nth_weekday_of_year(2023, &NthWeekday::new(Weekday::Mon, 53)).is_none());
+    }
+}
@@ -0,0 +1,72 @@
+//! JavaScript bindings for browsers and other WebAssembly hosts, available with the `wasm` feature.
+//!
+//! The dates are exchanged as RFC3339 strings, E.g. `2023-01-01T00:00:00Z`, with their fractions
+//! of seconds, E.g. `2023-01-01T00:00:00.250Z`.
+
+use crate::recurrences::serializers::format_duration;
+use crate::Recurrence;
+use chrono::{DateTime, SecondsFormat, Utc};
+use core::str::FromStr;
+use js_sys::{Array, Object, Reflect};
+use wasm_bindgen::prelude::*;
+
+/// Parses a recurrence rule into an object with its `frequency`, `start`, `end`,
+/// `duration`, `exdates` and `allDay` properties.
+/// The end date is `null` when the recurrence has no end.
+#[wasm_bindgen]
+pub fn parse_rrule(value: &str) -> Result<JsValue, JsError> {
+    let recurrence = Recurrence::from_str(value).map_err(|e| JsError::new(&e.to_string()))?;
+    let end = match recurrence.end() {
+        Some(end) => JsValue::from_str(&to_iso(&end)),
+        None => JsValue::NULL,
+    };
+    let exdates: Array = recurrence
+        .exdates
+        .iter()
+        .map(|date| JsValue::from_str(&to_iso(date)))
+        .collect();
+
+    let object = Object::new();
+    for (key, value) in [
+        (
+            "frequency",
+            JsValue::from_str(&recurrence.frequency.to_string()),
+        ),
+        ("start", JsValue::from_str(&to_iso(&recurrence.start))),
+        ("end", end),
+        (
+            "duration",
+            JsValue::from_str(&format_duration(&recurrence.duration)),
+        ),
+        ("exdates", exdates.into()),
+        ("allDay", JsValue::from_bool(recurrence.all_day)),
+    ] {
+        Reflect::set(&object, &JsValue::from_str(key), &value)
+            .map_err(|_| JsError::new(&format!("Could not set the {key} property")))?;
+    }
+    Ok(object.into())
+}
+
+/// Returns an array with the event dates of the recurrence rule between the given dates,
+/// both included.
+#[wasm_bindgen]
+pub fn occurrences_between(value: &str, from: &str, to: &str) -> Result<Array, JsError> {
+    let recurrence = Recurrence::from_str(value).map_err(|e| JsError::new(&e.to_string()))?;
+    let from = parse_iso(from)?;
+    let to = parse_iso(to)?;
+    Ok(recurrence
+        .between(&from, &to)
+        .map(|date| JsValue::from_str(&to_iso(&date)))
+        .collect())
+}
+
+/// Writes the date in RFC3339, keeping its fractions of seconds.
+fn to_iso(date: &DateTime<Utc>) -> String {
+    date.to_rfc3339_opts(SecondsFormat::AutoSi, true)
+}
+
+fn parse_iso(value: &str) -> Result<DateTime<Utc>, JsError> {
+    DateTime::parse_from_rfc3339(value)
+        .map(|date| date.with_timezone(&Utc))
+        .map_err(|e| JsError::new(&format!("Invalid date {value}: {e}")))
+}